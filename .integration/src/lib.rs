@@ -17,10 +17,10 @@
 #[cfg(test)]
 mod tests {
     use aleo_std::StorageMode;
-    use snarkos_node_cdn::sync_ledger_with_cdn;
+    use snarkos_node_cdn::{sync_block_store_with_cdn, sync_ledger_with_cdn, CdnConfig};
     use snarkvm::prelude::{
         block::Block,
-        store::helpers::memory::ConsensusMemory,
+        store::{helpers::memory::ConsensusMemory, ConsensusStore},
         FromBytes,
         Ledger,
         MainnetV0,
@@ -40,12 +40,30 @@ mod tests {
         let genesis = Block::<CurrentNetwork>::read_le(CurrentNetwork::genesis_bytes()).unwrap();
         // Initialize the ledger.
         let ledger = Ledger::<_, ConsensusMemory<_>>::load(genesis, StorageMode::Production).unwrap();
+        // Initialize the CDN configuration.
+        let cdn = CdnConfig::new(TEST_BASE_URL.to_string());
         // Perform the sync.
         let rt = tokio::runtime::Runtime::new().unwrap();
         rt.block_on(async {
             let completed_height =
-                sync_ledger_with_cdn(TEST_BASE_URL, ledger.clone(), Default::default()).await.unwrap();
+                sync_ledger_with_cdn(&cdn, ledger.clone(), Default::default(), None, None).await.unwrap();
             assert_eq!(completed_height, ledger.latest_height());
         });
     }
+
+    #[test]
+    #[traced_test]
+    fn test_sync_block_store_with_cdn_0_to_tip() {
+        // Initialize the consensus store.
+        let store = ConsensusStore::<CurrentNetwork, ConsensusMemory<_>>::open(None).unwrap();
+        // Perform the sync.
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        rt.block_on(async {
+            let completed_height =
+                sync_block_store_with_cdn(TEST_BASE_URL, store.block_store().clone(), Default::default(), None)
+                    .await
+                    .unwrap();
+            assert_eq!(Some(completed_height), store.block_store().max_height());
+        });
+    }
 }
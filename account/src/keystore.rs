@@ -0,0 +1,124 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::Account;
+use snarkvm::{console::network::prelude::*, prelude::PrivateKey};
+
+use argon2::{Algorithm, Argon2, Params, Version};
+use chacha20poly1305::{
+    aead::{Aead, KeyInit},
+    ChaCha20Poly1305,
+    Key,
+    Nonce,
+};
+use zeroize::Zeroizing;
+
+/// The magic bytes at the start of every keystore.
+const KEYSTORE_MAGIC: [u8; 8] = *b"SNARKKEY";
+/// The version of the keystore format.
+const KEYSTORE_VERSION: u8 = 1;
+/// The number of bytes of the salt of the key derivation.
+const SALT_SIZE: usize = 16;
+/// The number of bytes of the nonce of the encryption.
+const NONCE_SIZE: usize = 12;
+/// The memory cost of the key derivation, in KiB.
+const KDF_MEMORY_COST_IN_KIB: u32 = 64 * 1024;
+/// The number of passes of the key derivation.
+const KDF_TIME_COST: u32 = 3;
+
+impl<N: Network> Account<N> {
+    /// Returns the account private key encrypted with the given password, in the keystore format.
+    ///
+    /// The encryption key is derived from the password with Argon2id,
+    /// and the private key is encrypted with ChaCha20-Poly1305.
+    pub fn to_keystore<R: Rng + CryptoRng>(&self, password: &[u8], rng: &mut R) -> Result<Vec<u8>> {
+        // Sample the salt and the nonce.
+        let salt: [u8; SALT_SIZE] = rng.gen();
+        let nonce: [u8; NONCE_SIZE] = rng.gen();
+        // Encrypt the private key.
+        let key = derive_key(password, &salt)?;
+        let plaintext = Zeroizing::new(self.private_key.to_bytes_le()?);
+        let ciphertext = ChaCha20Poly1305::new(Key::from_slice(key.as_slice()))
+            .encrypt(Nonce::from_slice(&nonce), plaintext.as_slice())
+            .map_err(|_| anyhow!("Failed to encrypt the private key"))?;
+        // Encode the keystore.
+        Ok([&KEYSTORE_MAGIC[..], &[KEYSTORE_VERSION], &salt, &nonce, &ciphertext].concat())
+    }
+
+    /// Returns the account of the private key in the given keystore, decrypted with the given password.
+    pub fn from_keystore(keystore: &[u8], password: &[u8]) -> Result<Self> {
+        // Decode the keystore.
+        ensure!(is_keystore(keystore), "The file is not a keystore");
+        let (version, keystore) =
+            keystore[KEYSTORE_MAGIC.len()..].split_first().ok_or_else(|| anyhow!("The keystore is truncated"))?;
+        ensure!(*version == KEYSTORE_VERSION, "Unsupported keystore version {version} (expected {KEYSTORE_VERSION})");
+        ensure!(keystore.len() > SALT_SIZE + NONCE_SIZE, "The keystore is truncated");
+        let (salt, keystore) = keystore.split_at(SALT_SIZE);
+        let (nonce, ciphertext) = keystore.split_at(NONCE_SIZE);
+        // Decrypt the private key.
+        let key = derive_key(password, salt)?;
+        let plaintext = ChaCha20Poly1305::new(Key::from_slice(key.as_slice()))
+            .decrypt(Nonce::from_slice(nonce), ciphertext)
+            .map_err(|_| anyhow!("Failed to decrypt the keystore (is the password correct?)"))?;
+        Self::try_from(PrivateKey::read_le(Zeroizing::new(plaintext).as_slice())?)
+    }
+}
+
+/// Returns `true` if the given bytes are in the keystore format.
+pub fn is_keystore(bytes: &[u8]) -> bool {
+    bytes.starts_with(&KEYSTORE_MAGIC)
+}
+
+/// Derives the encryption key of a keystore from the given password and salt.
+fn derive_key(password: &[u8], salt: &[u8]) -> Result<Zeroizing<[u8; 32]>> {
+    let params = Params::new(KDF_MEMORY_COST_IN_KIB, KDF_TIME_COST, 1, Some(32))
+        .map_err(|error| anyhow!("Invalid key derivation parameters - {error}"))?;
+    let mut key = Zeroizing::new([0u8; 32]);
+    Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+        .hash_password_into(password, salt, key.as_mut_slice())
+        .map_err(|error| anyhow!("Failed to derive the keystore key - {error}"))?;
+    Ok(key)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkvm::prelude::MainnetV0;
+
+    type CurrentNetwork = MainnetV0;
+
+    #[test]
+    fn test_keystore() {
+        // Initialize the RNG.
+        let mut rng = TestRng::default();
+        // Encrypt the account.
+        let account = Account::<CurrentNetwork>::new(&mut rng).unwrap();
+        let keystore = account.to_keystore(b"password", &mut rng).unwrap();
+        assert!(is_keystore(&keystore));
+        // Ensure the private key is not stored in plaintext.
+        let private_key = account.private_key().to_bytes_le().unwrap();
+        assert!(!keystore.windows(private_key.len()).any(|window| window == private_key));
+        // Decrypt the account.
+        let decrypted = Account::<CurrentNetwork>::from_keystore(&keystore, b"password").unwrap();
+        assert_eq!(decrypted.private_key(), account.private_key());
+        // Ensure the keystore cannot be decrypted with another password, or once tampered with.
+        assert!(Account::<CurrentNetwork>::from_keystore(&keystore, b"passw0rd").is_err());
+        let mut tampered = keystore.clone();
+        *tampered.last_mut().unwrap() ^= 1;
+        assert!(Account::<CurrentNetwork>::from_keystore(&tampered, b"password").is_err());
+        // Ensure a private key in plaintext is not mistaken for a keystore.
+        assert!(!is_keystore(account.private_key().to_string().as_bytes()));
+        assert!(Account::<CurrentNetwork>::from_keystore(&keystore[..20], b"password").is_err());
+    }
+}
@@ -262,6 +262,7 @@ impl Scan {
                 cdn_request_start,
                 Some(cdn_request_end),
                 _shutdown,
+                None,
                 move |block| {
                     // Check if the block is within the requested range.
                     if block.height() < start_height || block.height() > end_height {
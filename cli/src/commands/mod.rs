@@ -21,12 +21,18 @@ pub use clean::*;
 mod developer;
 pub use developer::*;
 
+mod peers;
+pub use peers::*;
+
 mod start;
 pub use start::*;
 
 mod update;
 pub use update::*;
 
+mod validator;
+pub use validator::*;
+
 use anstyle::{AnsiColor, Color, Style};
 use anyhow::Result;
 use clap::{builder::Styles, Parser};
@@ -57,10 +63,14 @@ pub enum Command {
     Clean(Clean),
     #[clap(subcommand)]
     Developer(Developer),
+    #[clap(subcommand)]
+    Peers(Peers),
     #[clap(name = "start")]
     Start(Box<Start>),
     #[clap(name = "update")]
     Update(Update),
+    #[clap(subcommand)]
+    Validator(Validator),
 }
 
 impl Command {
@@ -70,8 +80,10 @@ impl Command {
             Self::Account(command) => command.parse(),
             Self::Clean(command) => command.parse(),
             Self::Developer(command) => command.parse(),
+            Self::Peers(command) => command.parse(),
             Self::Start(command) => command.parse(),
             Self::Update(command) => command.parse(),
+            Self::Validator(command) => command.parse(),
         }
     }
 }
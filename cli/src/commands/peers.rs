@@ -0,0 +1,152 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use anyhow::{bail, Result};
+use clap::Parser;
+use serde_json::{json, Value};
+use std::net::SocketAddr;
+
+/// Commands to manage the peers of a running node, over its admin API (see '--rest-admin').
+#[derive(Debug, Parser)]
+pub enum Peers {
+    /// List the connection states of the known peers.
+    List(AdminEndpoint),
+    /// Connect to the given peer.
+    Connect(PeerCommand),
+    /// Disconnect from the given peer.
+    Disconnect(PeerCommand),
+    /// Ban the given peer for a duration.
+    Ban(BanCommand),
+    /// Lift the ban of the given peer.
+    Unban(PeerCommand),
+    /// Export the view of the network of the node, including the known peers.
+    Topology(TopologyCommand),
+}
+
+impl Peers {
+    /// Parses the peers command.
+    pub fn parse(self) -> Result<String> {
+        match self {
+            Self::List(endpoint) => endpoint.get("peers/states"),
+            Self::Connect(command) => command.endpoint.post("peers/connect", json!({ "ip": command.ip })),
+            Self::Disconnect(command) => command.endpoint.post("peers/disconnect", json!({ "ip": command.ip })),
+            Self::Ban(command) => {
+                command.endpoint.post("peers/ban", json!({ "ip": command.ip, "ttl_secs": command.ttl }))
+            }
+            Self::Unban(command) => command.endpoint.post("peers/unban", json!({ "ip": command.ip })),
+            Self::Topology(command) => command.endpoint.get(&format!("peers/topology?format={}", command.format)),
+        }
+    }
+}
+
+/// The admin API of the node.
+#[derive(Debug, Parser)]
+pub struct AdminEndpoint {
+    /// Specify the REST endpoint of the node
+    #[clap(default_value = "http://127.0.0.1:3030", long = "endpoint")]
+    pub endpoint: String,
+    /// Specify the JWT token of the node, as printed when the node starts
+    #[clap(long = "jwt")]
+    pub jwt: String,
+}
+
+impl AdminEndpoint {
+    /// Returns the URL of the given admin route.
+    pub(crate) fn url(&self, route: &str) -> String {
+        format!("{}/mainnet/admin/{route}", self.endpoint.trim_end_matches('/'))
+    }
+
+    /// Sends a `GET` request to the given admin route, returning the response.
+    fn get(&self, route: &str) -> Result<String> {
+        let response = ureq::get(&self.url(route)).set("Authorization", &format!("Bearer {}", self.jwt)).call();
+        Self::handle_response(response)
+    }
+
+    /// Sends a `POST` request with the given body to the given admin route, returning the response.
+    pub(crate) fn post(&self, route: &str, body: Value) -> Result<String> {
+        let response =
+            ureq::post(&self.url(route)).set("Authorization", &format!("Bearer {}", self.jwt)).send_json(body);
+        Self::handle_response(response)
+    }
+
+    /// Returns the body of the given response, or the error returned by the node.
+    fn handle_response(response: Result<ureq::Response, ureq::Error>) -> Result<String> {
+        match response {
+            Ok(response) => Ok(response.into_string()?),
+            Err(ureq::Error::Status(code, response)) => {
+                bail!("The node returned an error (status code {code}): {}", response.into_string()?)
+            }
+            Err(error) => bail!("Failed to reach the node - {error}"),
+        }
+    }
+}
+
+/// A command targeting a single peer.
+#[derive(Debug, Parser)]
+pub struct PeerCommand {
+    /// The listener IP and port of the peer
+    pub ip: SocketAddr,
+    #[clap(flatten)]
+    pub endpoint: AdminEndpoint,
+}
+
+/// The command to ban a peer.
+#[derive(Debug, Parser)]
+pub struct BanCommand {
+    /// The listener IP and port of the peer
+    pub ip: SocketAddr,
+    /// Specify the duration of the ban in seconds (defaults to the duration of the bans for offenses)
+    #[clap(long = "ttl")]
+    pub ttl: Option<u64>,
+    #[clap(flatten)]
+    pub endpoint: AdminEndpoint,
+}
+
+/// The command to export the network topology.
+#[derive(Debug, Parser)]
+pub struct TopologyCommand {
+    /// Specify the format of the snapshot [options: json, dot]
+    #[clap(default_value = "json", long = "format", value_parser = ["json", "dot"])]
+    pub format: String,
+    #[clap(flatten)]
+    pub endpoint: AdminEndpoint,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_peers_commands() {
+        let peers = Peers::try_parse_from(["peers", "list", "--jwt", "token"].iter()).unwrap();
+        let Peers::List(endpoint) = peers else { panic!("Expected the 'list' command") };
+        assert_eq!(endpoint.url("peers/states"), "http://127.0.0.1:3030/mainnet/admin/peers/states");
+
+        let args = ["peers", "ban", "1.2.3.4:4130", "--ttl", "60", "--jwt", "token", "--endpoint", "http://node:3030/"];
+        let Peers::Ban(command) = Peers::try_parse_from(args.iter()).unwrap() else { panic!("Expected 'ban'") };
+        assert_eq!(command.ip, "1.2.3.4:4130".parse().unwrap());
+        assert_eq!(command.ttl, Some(60));
+        assert_eq!(command.endpoint.url("peers/ban"), "http://node:3030/mainnet/admin/peers/ban");
+
+        let args = ["peers", "topology", "--format", "dot", "--jwt", "token"];
+        let Peers::Topology(command) = Peers::try_parse_from(args.iter()).unwrap() else {
+            panic!("Expected 'topology'")
+        };
+        assert_eq!(command.format, "dot");
+        assert!(Peers::try_parse_from(["peers", "topology", "--format", "png", "--jwt", "token"].iter()).is_err());
+
+        // Ensure the JWT token is required.
+        assert!(Peers::try_parse_from(["peers", "unban", "1.2.3.4:4130"].iter()).is_err());
+    }
+}
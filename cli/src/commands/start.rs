@@ -14,7 +14,65 @@
 
 use snarkos_account::Account;
 use snarkos_display::Display;
-use snarkos_node::{bft::MEMORY_POOL_PORT, router::messages::NodeType, Node};
+use snarkos_node::{
+    bft::{
+        helpers::{
+            AdmissionLimits,
+            AuditLog,
+            BftConfig,
+            FileLease,
+            LaneLimits,
+            Lease,
+            LeasedSigner,
+            LocalSigner,
+            MempoolLimits,
+            ProposalLimits,
+            RemoteLease,
+            RemoteSigner,
+            Signer,
+            DEFAULT_DEPLOYMENT_LANE_CAPACITY,
+            DEFAULT_EXECUTION_LANE_CAPACITY,
+            DEFAULT_LEASE_DURATION_IN_SECS,
+            DEFAULT_MEMPOOL_MAX_BYTES,
+            DEFAULT_MEMPOOL_MAX_TRANSACTIONS,
+            DEFAULT_MEMPOOL_MAX_TRANSACTIONS_PER_PAYER,
+            DEFAULT_REPLACEMENT_FEE_BUMP,
+        },
+        ledger_service::{parse_cpu_cores, set_verification_pool},
+        DEFAULT_WORKERS,
+        MEMORY_POOL_PORT,
+    },
+    cdn::{CdnClientConfig, CdnConfig},
+    rest::{
+        AdminConfig,
+        ApiKeys,
+        CompressionConfig,
+        CorsConfig,
+        NodeInfo,
+        RateLimitConfig,
+        RequestLimits,
+        RestConfig,
+        TlsConfig,
+        UnixSocketConfig,
+    },
+    router::{
+        messages::{
+            set_default_maximum_message_size,
+            Capabilities,
+            NodeType,
+            NodeVersion,
+            MAXIMUM_HANDSHAKE_MESSAGE_SIZE,
+            MAXIMUM_MESSAGE_SIZE,
+        },
+        AccessListFiles,
+        CacheConfig,
+        HandshakePolicy,
+        PeerLimits,
+        DEFAULT_CACHE_SIZE,
+    },
+    sync::MAX_BLOCKS_BEHIND,
+    Node,
+};
 use snarkvm::{
     console::{
         account::{Address, PrivateKey},
@@ -24,7 +82,11 @@ use snarkvm::{
     ledger::{
         block::Block,
         committee::{Committee, MIN_VALIDATOR_STAKE},
-        store::{helpers::memory::ConsensusMemory, ConsensusStore},
+        store::{
+            helpers::{memory::ConsensusMemory, rocksdb::ConsensusDB},
+            ConsensusStore,
+        },
+        Ledger,
     },
     prelude::{FromBytes, ToBits, ToBytes},
     synthesizer::VM,
@@ -38,7 +100,13 @@ use colored::Colorize;
 use core::str::FromStr;
 use rand::SeedableRng;
 use rand_chacha::ChaChaRng;
-use std::{net::SocketAddr, path::PathBuf};
+use std::{
+    net::SocketAddr,
+    num::{NonZeroU32, NonZeroUsize},
+    path::PathBuf,
+    sync::Arc,
+    time::Duration,
+};
 use tokio::runtime::{self, Runtime};
 
 /// The recommended minimum number of 'open files' limit for a validator.
@@ -74,19 +142,166 @@ pub struct Start {
     /// Specify the path to a file containing the account private key of the node
     #[clap(long = "private-key-file")]
     pub private_key_file: Option<PathBuf>,
+    /// Specify the path to a keystore containing the encrypted account private key of the node
+    /// (the password is read from 'SNARKOS_KEYSTORE_PASSWORD', or prompted for)
+    #[clap(long = "keystore")]
+    pub keystore: Option<PathBuf>,
 
-    /// Specify the IP address and port for the node server
+    /// Specify the IP address and port for the node server (e.g. '[::]:4130' to listen on both IPv4 and IPv6)
     #[clap(default_value = "0.0.0.0:4130", long = "node")]
     pub node: SocketAddr,
-    /// Specify the IP address and port for the BFT
+    /// Specify the IP address and port to advertise to peers (detected from the peers' observations if not set)
+    #[clap(long = "node-external")]
+    pub node_external: Option<SocketAddr>,
+    /// Specify the IP address and port for the BFT (e.g. on a private interface, defaults to '0.0.0.0:5000')
     #[clap(long = "bft")]
     pub bft: Option<SocketAddr>,
-    /// Specify the IP address and port of the peer(s) to connect to
+    /// Specify the IP address and port to advertise to validators, if it differs from '--bft'
+    #[clap(long = "bft-external")]
+    pub bft_external: Option<SocketAddr>,
+    /// Specify the URL of a remote signing service to sign the batches of the validator, instead of '--private-key'
+    /// (which then only identifies the node to its peers)
+    #[clap(long = "bft-remote-signer", requires = "validator")]
+    pub bft_remote_signer: Option<String>,
+    /// Specify a lease shared with a standby node of the same validator, so that only the node holding it signs
+    /// (a path to a shared file, or the URL of a lock service)
+    #[clap(long = "bft-lease", requires = "validator")]
+    pub bft_lease: Option<String>,
+    /// Specify the duration of the validator lease, in seconds
+    #[clap(default_value_t = DEFAULT_LEASE_DURATION_IN_SECS, long = "bft-lease-duration")]
+    pub bft_lease_duration: u64,
+    /// Specify the number of rounds to retain in the BFT storage, before they are pruned
+    /// (defaults to the maximum number of GC rounds)
+    #[clap(long = "bft-pruning-rounds")]
+    pub bft_pruning_rounds: Option<u64>,
+    /// Specify the directory to archive the pruned BFT certificates to (e.g. for archive validators)
+    #[clap(long = "bft-archive")]
+    pub bft_archive: Option<PathBuf>,
+    /// Specify the maximum number of transmissions in a batch proposal (defaults to the network cap)
+    #[clap(long = "bft-max-batch-transmissions")]
+    pub bft_max_batch_transmissions: Option<usize>,
+    /// Specify the maximum total size of the transmissions in a batch proposal, in bytes (defaults to the network cap)
+    #[clap(long = "bft-max-batch-bytes")]
+    pub bft_max_batch_bytes: Option<usize>,
+    /// Specify the interval between batch proposals, in milliseconds (defaults to the network cap)
+    #[clap(long = "bft-batch-interval")]
+    pub bft_batch_interval: Option<u64>,
+    /// Specify the number of workers verifying and batching the transmissions of the validator
+    #[clap(default_value_t = DEFAULT_WORKERS, long = "bft-workers")]
+    pub bft_workers: u8,
+    /// Specify the number of threads in a dedicated thread pool verifying the transmissions
+    /// (defaults to the global thread pool, or to one thread per core in '--bft-verification-cores')
+    #[clap(long = "bft-verification-threads")]
+    pub bft_verification_threads: Option<usize>,
+    /// Specify the CPU cores to pin the verification threads to (e.g. '0-3,8')
+    #[clap(long = "bft-verification-cores")]
+    pub bft_verification_cores: Option<String>,
+    /// Specify a file to append every proposal received, signature issued, certificate formed,
+    /// and subdag committed by the validator to
+    #[clap(long = "bft-audit-log", requires = "validator")]
+    pub bft_audit_log: Option<PathBuf>,
+    /// Specify the maximum number of blocks the ledger may be behind the network tip,
+    /// before the validator stops signing and proposing batches
+    #[clap(default_value_t = MAX_BLOCKS_BEHIND, long = "bft-max-blocks-behind")]
+    pub bft_max_blocks_behind: u32,
+    /// Specify the fee increase required to replace a pending transaction consuming the same records, in percent
+    #[clap(default_value_t = DEFAULT_REPLACEMENT_FEE_BUMP, long = "mempool-replacement-fee-bump")]
+    pub mempool_replacement_fee_bump: u64,
+    /// Specify the maximum number of transactions in the memory pool
+    #[clap(default_value_t = DEFAULT_MEMPOOL_MAX_TRANSACTIONS, long = "mempool-max-transactions")]
+    pub mempool_max_transactions: usize,
+    /// Specify the maximum total size of the transactions in the memory pool, in bytes
+    #[clap(default_value_t = DEFAULT_MEMPOOL_MAX_BYTES, long = "mempool-max-bytes")]
+    pub mempool_max_bytes: usize,
+    /// Specify the maximum number of transactions from the same fee payer in the memory pool (public fees only)
+    #[clap(default_value_t = DEFAULT_MEMPOOL_MAX_TRANSACTIONS_PER_PAYER, long = "mempool-max-transactions-per-payer")]
+    pub mempool_max_transactions_per_payer: usize,
+    /// Specify the maximum number of deployments waiting for or undergoing verification at once
+    #[clap(default_value_t = DEFAULT_DEPLOYMENT_LANE_CAPACITY, long = "mempool-deployment-lane-capacity")]
+    pub mempool_deployment_lane_capacity: usize,
+    /// Specify the maximum number of executions waiting for or undergoing verification at once
+    #[clap(default_value_t = DEFAULT_EXECUTION_LANE_CAPACITY, long = "mempool-execution-lane-capacity")]
+    pub mempool_execution_lane_capacity: usize,
+    /// Specify the transaction to evict when the memory pool is full ('lowest-fee' or 'oldest')
+    #[clap(default_value = "lowest-fee", long = "mempool-eviction-policy")]
+    pub mempool_eviction_policy: String,
+    /// Specify the IP address and port of the peer(s) to connect to (or '<address>.onion:<port>', with '--proxy')
     #[clap(default_value = "", long = "peers")]
     pub peers: String,
     /// Specify the IP address and port of the validator(s) to connect to
     #[clap(default_value = "", long = "validators")]
     pub validators: String,
+    /// Specify the DNS seed hostname(s) to discover peers from, with an optional port (e.g. 'seed.example.com:4130')
+    #[clap(default_value = "", long = "dns-seeds")]
+    pub dns_seeds: String,
+    /// If the flag is set, the node asks the local router to forward its port (over UPnP or NAT-PMP)
+    #[clap(long = "upnp")]
+    pub upnp: bool,
+    /// Specify the IP address and port of a SOCKS5 proxy (e.g. Tor) to route outbound P2P connections through
+    #[clap(long = "proxy")]
+    pub proxy: Option<SocketAddr>,
+    /// If the flag is set, the node connects to unreachable peers with the help of relays (TCP hole punching)
+    #[clap(long = "p2p-hole-punching")]
+    pub p2p_hole_punching: bool,
+    /// Specify the maximum inbound bandwidth per peer connection, in KiB per second (0 for unlimited)
+    #[clap(default_value = "0", long = "peer-bandwidth-in")]
+    pub peer_bandwidth_in: u32,
+    /// Specify the maximum outbound bandwidth per peer connection, in KiB per second (0 for unlimited)
+    #[clap(default_value = "0", long = "peer-bandwidth-out")]
+    pub peer_bandwidth_out: u32,
+    /// Specify the maximum size of a P2P message in bytes, to lower the default of 128 MiB (at least 1 MiB)
+    #[clap(long = "p2p-max-message-size")]
+    pub p2p_max_message_size: Option<usize>,
+    /// Specify the maximum time to complete the P2P handshake with a peer, in seconds
+    #[clap(default_value = "3", long = "p2p-handshake-timeout")]
+    pub p2p_handshake_timeout: u64,
+    /// Specify the maximum time between two messages from a peer before it is dropped, in seconds (above 20s pings)
+    #[clap(long = "p2p-read-timeout")]
+    pub p2p_read_timeout: Option<u64>,
+    /// Specify the maximum number of inbound peer connections (defaults to a value based on the node type)
+    #[clap(long = "max-inbound-peers")]
+    pub max_inbound_peers: Option<usize>,
+    /// Specify the maximum number of outbound peer connections (defaults to a value based on the node type)
+    #[clap(long = "max-outbound-peers")]
+    pub max_outbound_peers: Option<usize>,
+    /// Specify the number of outbound peer connections to maintain (defaults to the maximum outbound peers)
+    #[clap(long = "target-outbound-peers")]
+    pub target_outbound_peers: Option<usize>,
+    /// Specify the path to a file of peer IPs or subnets to exclusively allow (one per line, reloaded on SIGHUP)
+    #[clap(long = "allowlist")]
+    pub allowlist: Option<PathBuf>,
+    /// Specify the path to a file of peer IPs or subnets to deny (one per line, reloaded on SIGHUP)
+    #[clap(long = "denylist")]
+    pub denylist: Option<PathBuf>,
+    /// Specify the path of a file to record the inbound P2P messages to, to reproduce them with '--replay-inbound'
+    #[cfg(feature = "record")]
+    #[clap(long = "record-inbound")]
+    pub record_inbound: Option<PathBuf>,
+    /// Specify the path of a recording of inbound P2P messages to replay once the node is initialized
+    #[cfg(feature = "record")]
+    #[clap(long = "replay-inbound")]
+    pub replay_inbound: Option<PathBuf>,
+    /// If the flag is set, the node compresses the P2P messages (with zstd) exchanged with peers that enable it too
+    #[clap(long = "p2p-compression")]
+    pub p2p_compression: bool,
+    /// Specify the maximum number of entries in each cache of recently seen solutions and transactions
+    #[clap(default_value_t = DEFAULT_CACHE_SIZE, long = "p2p-cache-size")]
+    pub p2p_cache_size: usize,
+    /// Specify the number of seconds after which a cached solution or transaction is no longer considered seen
+    #[clap(long = "p2p-cache-ttl")]
+    pub p2p_cache_ttl: Option<u64>,
+    /// Specify the optional features to advertise to peers during the handshake [options: archive, cdn, relay]
+    #[clap(default_value = "", long = "p2p-capabilities")]
+    pub p2p_capabilities: String,
+    /// Specify the minimum node version of peers (e.g. '2.2.0'); trusted peers are exempt
+    #[clap(long = "min-peer-version")]
+    pub min_peer_version: Option<String>,
+    /// Specify the capabilities peers must advertise to connect [options: compression, archive, cdn, noise]
+    #[clap(default_value = "", long = "require-peer-capabilities")]
+    pub require_peer_capabilities: String,
+    /// Specify the user agent prefixes of peers to reject, comma-separated (e.g. 'snarkOS/2.1')
+    #[clap(default_value = "", long = "deny-user-agents")]
+    pub deny_user_agents: String,
 
     /// Specify the IP address and port for the REST server
     #[clap(default_value = "0.0.0.0:3030", long = "rest")]
@@ -94,6 +309,72 @@ pub struct Start {
     /// Specify the requests per second (RPS) rate limit per IP for the REST server
     #[clap(default_value = "10", long = "rest-rps")]
     pub rest_rps: u32,
+    /// Specify the maximum number of requests in a burst to the REST server (default: the requests per second)
+    #[clap(long = "rest-burst")]
+    pub rest_burst: Option<u32>,
+    /// Specify whether the REST rate limit applies per IP or globally (options: 'ip', 'global')
+    #[clap(default_value = "ip", long = "rest-rate-limit-scope")]
+    pub rest_rate_limit_scope: String,
+    /// Specify the maximum size of a REST request body, in bytes (e.g. for large deployments)
+    #[clap(default_value = "10485760", long = "rest-max-body-size")]
+    pub rest_max_body_size: usize,
+    /// Specify the maximum time to handle a REST request, in seconds (if unset, requests never time out)
+    #[clap(long = "rest-request-timeout")]
+    pub rest_request_timeout: Option<u64>,
+    /// Specify the time to wait for in-flight REST requests to complete on shutdown, in seconds (or '0' to abort them)
+    #[clap(default_value = "10", long = "rest-shutdown-grace-period")]
+    pub rest_shutdown_grace_period: u64,
+    /// Specify the number of responses of immutable REST routes (e.g. historical blocks) to cache, or '0' to disable
+    #[clap(default_value = "1024", long = "rest-cache-size")]
+    pub rest_cache_size: usize,
+    /// Specify the origin(s) allowed to call the REST server from a browser, or '*' for any origin
+    #[clap(default_value = "*", long = "rest-cors-origins")]
+    pub rest_cors_origins: String,
+    /// Specify the HTTP method(s) allowed by the CORS policy of the REST server
+    #[clap(default_value = "GET,POST,OPTIONS", long = "rest-cors-methods")]
+    pub rest_cors_methods: String,
+    /// Specify the request header(s) allowed by the CORS policy of the REST server, or '*' for any header
+    #[clap(default_value = "content-type,x-api-key", long = "rest-cors-headers")]
+    pub rest_cors_headers: String,
+    /// Specify the number of seconds browsers may cache the CORS preflight responses of the REST server
+    #[clap(long = "rest-cors-max-age")]
+    pub rest_cors_max_age: Option<u64>,
+    /// Specify the response compression algorithm(s) for the REST server, or 'none' [options: gzip, br, zstd]
+    #[clap(default_value = "gzip,br", long = "rest-compression")]
+    pub rest_compression: String,
+    /// Specify the minimum response size (in bytes) for the REST server to compress
+    #[clap(default_value = "1024", long = "rest-compression-min-size")]
+    pub rest_compression_min_size: u16,
+    /// Specify the content type prefix(es) for the REST server to compress
+    #[clap(default_value = "application/json,application/octet-stream,text/", long = "rest-compression-content-types")]
+    pub rest_compression_content_types: String,
+    /// Specify the path to the PEM-encoded TLS certificate chain, to serve the REST API over HTTPS
+    #[clap(long = "rest-tls-cert", requires = "rest_tls_key")]
+    pub rest_tls_cert: Option<PathBuf>,
+    /// Specify the path to the PEM-encoded TLS private key, to serve the REST API over HTTPS
+    #[clap(long = "rest-tls-key", requires = "rest_tls_cert")]
+    pub rest_tls_key: Option<PathBuf>,
+    /// Specify the path to a JSON file of REST API keys, each with its own rate limit and allowed routes
+    #[clap(long = "rest-api-keys")]
+    pub rest_api_keys: Option<PathBuf>,
+    /// If the flag is set, the REST server rejects the requests without an API key
+    #[clap(long = "rest-require-api-key", requires = "rest_api_keys")]
+    pub rest_require_api_key: bool,
+    /// Specify the `program/mapping` pairs to index for historical queries (e.g. 'credits.aleo/account')
+    #[clap(long = "rest-state-history")]
+    pub rest_state_history: Option<String>,
+    /// If the flag is set, the REST server indexes the transactions of each address
+    #[clap(long = "rest-address-index")]
+    pub rest_address_index: bool,
+    /// Specify the path of a Unix socket for the REST server to listen on, in addition to TCP
+    #[clap(long = "rest-unix-socket")]
+    pub rest_unix_socket: Option<PathBuf>,
+    /// If the flag is set, the REST server only listens on the Unix socket, instead of also on TCP
+    #[clap(long = "rest-unix-socket-only", requires = "rest_unix_socket")]
+    pub rest_unix_socket_only: bool,
+    /// If the flag is set, the REST server enables the admin routes (JWT-protected) to control the node at runtime
+    #[clap(long = "rest-admin")]
+    pub rest_admin: bool,
     /// If the flag is set, the node will not initialize the REST server
     #[clap(long)]
     pub norest: bool,
@@ -117,6 +398,27 @@ pub struct Start {
     /// If the flag is set, the node will not prefetch from a CDN
     #[clap(long)]
     pub nocdn: bool,
+    /// Specify the base URL(s) of the mirror(s) of the CDN, which serve the same blocks
+    #[clap(default_value = "", long = "cdn-mirrors")]
+    pub cdn_mirrors: String,
+    /// Specify the bearer token to authenticate the requests to the CDN (and its mirrors)
+    #[clap(long = "cdn-bearer-token")]
+    pub cdn_bearer_token: Option<String>,
+    /// Specify the number of failed CDN requests tolerated before the node falls back to syncing from its peers
+    #[clap(long = "cdn-error-budget")]
+    pub cdn_error_budget: Option<u32>,
+    /// Specify the number of blocks below the CDN tip to leave to syncing from peers
+    #[clap(default_value = "10", long = "cdn-safety-margin")]
+    pub cdn_safety_margin: u32,
+    /// Specify the directory to cache the downloaded CDN blocks in, so a restarted sync does not download them again
+    #[clap(long = "cdn-cache-dir")]
+    pub cdn_cache_dir: Option<PathBuf>,
+    /// Specify the directory to spill the pending CDN blocks to, when their insertion falls behind the downloads
+    #[clap(long = "cdn-spill-dir")]
+    pub cdn_spill_dir: Option<PathBuf>,
+    /// Specify the block height to sync to from the CDN, after which the node stops (instead of starting)
+    #[clap(long = "sync-to-height")]
+    pub sync_to_height: Option<u32>,
 
     /// Enables development mode, specify a unique ID for this node
     #[clap(long)]
@@ -124,6 +426,10 @@ pub struct Start {
     /// If development mode is enabled, specify the number of genesis validators (default: 4)
     #[clap(long)]
     pub dev_num_validators: Option<u16>,
+    /// If development mode is enabled, specify the minimum interval between two blocks, in milliseconds,
+    /// to pace the block production (e.g. for integration tests and demos)
+    #[clap(long, requires = "dev")]
+    pub dev_min_block_interval: Option<u64>,
     /// Specify the path to a directory containing the ledger
     #[clap(long = "storage_path")]
     pub storage_path: Option<PathBuf>,
@@ -132,6 +438,17 @@ pub struct Start {
 impl Start {
     /// Starts the snarkOS node.
     pub fn parse(self) -> Result<String> {
+        // If a sync height is specified, sync the ledger from the CDN and stop.
+        if let Some(height) = self.sync_to_height {
+            // Initialize the logger, printing the logs to the terminal.
+            let _ = crate::helpers::initialize_logger(self.verbosity, true, self.logfile.clone());
+            // Parse the network.
+            return match self.network {
+                0 => self.sync_to_height::<MainnetV0>(height),
+                _ => bail!("Invalid network ID specified"),
+            };
+        }
+
         // Initialize the logger.
         let log_receiver = crate::helpers::initialize_logger(self.verbosity, self.nodisplay, self.logfile.clone());
         // Initialize the runtime.
@@ -161,6 +478,93 @@ impl Start {
 }
 
 impl Start {
+    /// Returns the DNS seed hostname(s) to discover peers from.
+    fn parse_dns_seeds(&self) -> Vec<String> {
+        self.dns_seeds.split(',').map(str::trim).filter(|seed| !seed.is_empty()).map(str::to_string).collect()
+    }
+
+    /// Returns the limits on the number of inbound and outbound peers for the given node type.
+    fn parse_peer_limits(&self, node_type: NodeType) -> Result<PeerLimits> {
+        let mut limits = PeerLimits::new(node_type);
+        if let Some(max_inbound) = self.max_inbound_peers {
+            limits.max_inbound = max_inbound;
+        }
+        if let Some(max_outbound) = self.max_outbound_peers {
+            limits.max_outbound = max_outbound;
+            limits.target_outbound = limits.target_outbound.min(max_outbound);
+        }
+        if let Some(target_outbound) = self.target_outbound_peers {
+            limits.target_outbound = target_outbound;
+        }
+        // Ensure the peer limits are valid.
+        limits.validate(u16::MAX as usize)?;
+        Ok(limits)
+    }
+
+    /// Returns the configuration of the caches of recently seen solutions and transactions.
+    fn parse_cache_config(&self) -> Result<CacheConfig> {
+        // Ensure the cache size is valid.
+        ensure!(self.p2p_cache_size > 0, "The P2P cache size must be greater than 0");
+        // Ensure the cache TTL is valid.
+        if let Some(ttl) = self.p2p_cache_ttl {
+            ensure!(ttl > 0, "The P2P cache TTL must be greater than 0 seconds");
+        }
+        Ok(CacheConfig { capacity: self.p2p_cache_size, ttl: self.p2p_cache_ttl.map(Duration::from_secs) })
+    }
+
+    /// Returns the optional features to advertise to peers during the handshake.
+    fn parse_capabilities(&self) -> Result<Capabilities> {
+        let mut capabilities = Capabilities::from_str(&self.p2p_capabilities)?;
+        if self.p2p_compression {
+            capabilities.insert(Capabilities::COMPRESSION);
+        }
+        if self.p2p_hole_punching {
+            capabilities.insert(Capabilities::HOLE_PUNCHING);
+        }
+        Ok(capabilities)
+    }
+
+    /// Returns the policy deciding which peers are accepted during the handshake.
+    fn parse_handshake_policy(&self) -> Result<HandshakePolicy> {
+        let min_node_version = self.min_peer_version.as_deref().map(NodeVersion::from_str).transpose()?;
+        let required_capabilities = Capabilities::from_str(&self.require_peer_capabilities)?;
+        let denied_user_agents = self
+            .deny_user_agents
+            .split(',')
+            .map(str::trim)
+            .filter(|user_agent| !user_agent.is_empty())
+            .map(str::to_string)
+            .collect();
+        Ok(HandshakePolicy { min_node_version, required_capabilities, denied_user_agents })
+    }
+
+    /// Returns the per-connection bandwidth limits (inbound, outbound) in bytes per second, if any.
+    fn parse_bandwidth_limits(&self) -> (Option<NonZeroU32>, Option<NonZeroU32>) {
+        let inbound = NonZeroU32::new(self.peer_bandwidth_in.saturating_mul(1024));
+        let outbound = NonZeroU32::new(self.peer_bandwidth_out.saturating_mul(1024));
+        (inbound, outbound)
+    }
+
+    /// Returns the handshake and read timeouts of the P2P connections in milliseconds (the latter is `0` if disabled).
+    fn parse_p2p_timeouts(&self) -> Result<(u64, u64)> {
+        ensure!(self.p2p_handshake_timeout > 0, "The P2P handshake timeout must be greater than 0 seconds");
+        if let Some(read_timeout) = self.p2p_read_timeout {
+            ensure!(read_timeout > 0, "The P2P read timeout must be greater than 0 seconds");
+        }
+        let handshake_timeout_ms = self.p2p_handshake_timeout.saturating_mul(1000);
+        let read_timeout_ms = self.p2p_read_timeout.unwrap_or_default().saturating_mul(1000);
+        Ok((handshake_timeout_ms, read_timeout_ms))
+    }
+
+    /// Returns the maximum size of a P2P message, if it was lowered.
+    fn parse_p2p_max_message_size(&self) -> Result<Option<usize>> {
+        if let Some(size) = self.p2p_max_message_size {
+            let (min, max) = (MAXIMUM_HANDSHAKE_MESSAGE_SIZE, MAXIMUM_MESSAGE_SIZE);
+            ensure!((min..=max).contains(&size), "The P2P maximum message size must be between {min} and {max} bytes");
+        }
+        Ok(self.p2p_max_message_size)
+    }
+
     /// Returns the initial peer(s) to connect to, from the given configurations.
     fn parse_trusted_peers(&self) -> Result<Vec<SocketAddr>> {
         match self.peers.is_empty() {
@@ -168,6 +572,7 @@ impl Start {
             false => Ok(self
                 .peers
                 .split(',')
+                .filter(|ip| !is_onion_peer(ip))
                 .flat_map(|ip| match ip.parse::<SocketAddr>() {
                     Ok(ip) => Some(ip),
                     Err(e) => {
@@ -179,6 +584,35 @@ impl Start {
         }
     }
 
+    /// Returns the `.onion` peer(s) to connect to through the proxy, as host names and ports.
+    fn parse_onion_peers(&self) -> Result<Vec<(String, u16)>> {
+        let onion_peers = self.peers.split(',').filter(|peer| is_onion_peer(peer)).collect::<Vec<_>>();
+        if !onion_peers.is_empty() && self.proxy.is_none() {
+            bail!("The '.onion' peers supplied to --peers require a SOCKS5 proxy (use '--proxy')")
+        }
+        onion_peers
+            .into_iter()
+            .map(|peer| match peer.rsplit_once(':').map(|(host, port)| (host, port.parse::<u16>())) {
+                Some((host, Ok(port))) => Ok((host.to_string(), port)),
+                _ => bail!("The '.onion' peer supplied to --peers ('{peer}') is malformed"),
+            })
+            .collect()
+    }
+
+    /// Resolves the `.onion` peer(s) to the virtual addresses mapped by the proxy.
+    async fn resolve_onion_peers(&self, onion_peers: Vec<(String, u16)>) -> Result<Vec<SocketAddr>> {
+        let mut peers = Vec::with_capacity(onion_peers.len());
+        if let Some(proxy) = self.proxy {
+            for (host, port) in onion_peers {
+                match snarkos_node::tcp::socks5_resolve(proxy, &host).await {
+                    Ok(ip) => peers.push(SocketAddr::new(ip, port)),
+                    Err(error) => bail!("Failed to resolve '{host}' through the proxy at '{proxy}' - {error}"),
+                }
+            }
+        }
+        Ok(peers)
+    }
+
     /// Returns the initial validator(s) to connect to, from the given configurations.
     fn parse_trusted_validators(&self) -> Result<Vec<SocketAddr>> {
         match self.validators.is_empty() {
@@ -198,7 +632,7 @@ impl Start {
     }
 
     /// Returns the CDN to prefetch initial blocks from, from the given configurations.
-    fn parse_cdn(&self) -> Option<String> {
+    fn parse_cdn(&self) -> Option<CdnConfig> {
         // Determine if the node type is not declared.
         let is_no_node_type = !(self.validator || self.prover || self.client);
 
@@ -212,7 +646,198 @@ impl Start {
         }
         // Enable the CDN otherwise.
         else {
-            Some(self.cdn.clone())
+            Some(CdnConfig {
+                mirrors: self
+                    .cdn_mirrors
+                    .split(',')
+                    .map(|mirror| mirror.trim().to_string())
+                    .filter(|mirror| !mirror.is_empty())
+                    .collect(),
+                error_budget: self.cdn_error_budget,
+                safety_margin: self.cdn_safety_margin,
+                sync_to_height: self.sync_to_height,
+                cache_dir: self.cdn_cache_dir.clone(),
+                spill_dir: self.cdn_spill_dir.clone(),
+                client: CdnClientConfig {
+                    authorization: self.cdn_bearer_token.as_ref().map(|token| format!("Bearer {token}")),
+                    ..Default::default()
+                },
+                ..CdnConfig::new(self.cdn.clone())
+            })
+        }
+    }
+
+    /// Returns the REST server configurations, from the given configurations.
+    fn parse_rest_config(&self) -> Result<RestConfig> {
+        // Parse the response compression settings.
+        let compression = match self.rest_compression.trim() {
+            "none" => None,
+            algorithms => Some(CompressionConfig {
+                algorithms: algorithms.split(',').map(str::parse).collect::<Result<Vec<_>>>()?,
+                min_size: self.rest_compression_min_size,
+                content_types: self
+                    .rest_compression_content_types
+                    .split(',')
+                    .map(|content_type| content_type.trim().to_string())
+                    .filter(|content_type| !content_type.is_empty())
+                    .collect(),
+            }),
+        };
+
+        // Parse the TLS settings.
+        let tls = match (&self.rest_tls_cert, &self.rest_tls_key) {
+            (Some(cert_path), Some(key_path)) => {
+                Some(TlsConfig { cert_path: cert_path.clone(), key_path: key_path.clone() })
+            }
+            _ => None,
+        };
+
+        // Parse the Unix socket settings.
+        let unix_socket =
+            self.rest_unix_socket.clone().map(|path| UnixSocketConfig { path, exclusive: self.rest_unix_socket_only });
+
+        // Load the API keys.
+        let api_keys = match &self.rest_api_keys {
+            Some(path) => Some(Arc::new(ApiKeys::load(path, self.rest_require_api_key)?)),
+            None => None,
+        };
+
+        // Parse the CORS policy.
+        let cors = CorsConfig::new(
+            &self.rest_cors_origins,
+            &self.rest_cors_methods,
+            &self.rest_cors_headers,
+            self.rest_cors_max_age,
+        )?;
+
+        // Parse the rate limiting settings.
+        let rate_limit = RateLimitConfig { burst: self.rest_burst, scope: self.rest_rate_limit_scope.parse()? };
+
+        // Parse the request limits.
+        let limits = RequestLimits {
+            max_body_size: self.rest_max_body_size,
+            timeout: self.rest_request_timeout.map(Duration::from_secs),
+        };
+
+        // Parse the mappings indexed by the state history.
+        let state_history = match &self.rest_state_history {
+            Some(mappings) => Some(
+                mappings
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|mapping| !mapping.is_empty())
+                    .map(|mapping| match mapping.contains('/') {
+                        true => Ok(mapping.to_string()),
+                        false => bail!("Invalid mapping '{mapping}' for the state history (expected program/mapping)"),
+                    })
+                    .collect::<Result<Vec<_>>>()?,
+            ),
+            None => None,
+        };
+
+        Ok(RestConfig {
+            compression,
+            tls,
+            unix_socket,
+            api_keys,
+            cors,
+            rate_limit,
+            limits,
+            response_cache: NonZeroUsize::new(self.rest_cache_size),
+            shutdown_grace_period: (self.rest_shutdown_grace_period > 0)
+                .then(|| Duration::from_secs(self.rest_shutdown_grace_period)),
+            state_history,
+            address_index: self.rest_address_index,
+            admin: self.rest_admin.then(|| AdminConfig { set_log_filter: Some(crate::helpers::set_log_filter) }),
+            ..Default::default()
+        })
+    }
+
+    /// Returns the node information served by the REST server, from the given configurations.
+    fn parse_node_info<N: Network>(
+        &self,
+        node_type: NodeType,
+        cdn: Option<&CdnConfig>,
+        rest_config: &RestConfig,
+    ) -> NodeInfo {
+        // Determine the enabled subsystems.
+        let is_rest_enabled = !self.norest && !node_type.is_prover();
+        let is_relay_enabled = self.parse_capabilities().is_ok_and(|caps| caps.contains(Capabilities::RELAY));
+        let subsystems = [
+            ("bft", node_type.is_validator()),
+            ("cdn", cdn.is_some()),
+            ("display", !self.nodisplay),
+            ("metrics", self.metrics),
+            ("rest", is_rest_enabled),
+            ("rest_compression", is_rest_enabled && rest_config.compression.is_some()),
+            ("rest_tls", is_rest_enabled && rest_config.tls.is_some()),
+            ("rest_unix_socket", is_rest_enabled && rest_config.unix_socket.is_some()),
+            ("rest_api_keys", is_rest_enabled && rest_config.api_keys.is_some()),
+            ("rest_state_history", is_rest_enabled && rest_config.state_history.is_some()),
+            ("rest_address_index", is_rest_enabled && rest_config.address_index),
+            ("rest_admin", is_rest_enabled && rest_config.admin.is_some()),
+            ("upnp", self.upnp),
+            ("proxy", self.proxy.is_some()),
+            ("peer_access_lists", self.allowlist.is_some() || self.denylist.is_some()),
+            ("p2p_compression", self.p2p_compression),
+            ("p2p_relay", is_relay_enabled),
+            ("p2p_hole_punching", self.p2p_hole_punching),
+            ("p2p_handshake_policy", self.parse_handshake_policy().is_ok_and(|policy| !policy.is_permissive())),
+        ]
+        .into_iter()
+        .filter_map(|(subsystem, enabled)| enabled.then(|| subsystem.to_string()))
+        .collect();
+
+        // Collect the configured limits.
+        let mut limits = indexmap::IndexMap::new();
+        if is_rest_enabled {
+            limits.insert("rest_rps".to_string(), self.rest_rps as u64);
+            limits.insert("rest_burst".to_string(), rest_config.rate_limit.burst.unwrap_or(self.rest_rps) as u64);
+            limits.insert("rest_max_body_size".to_string(), rest_config.limits.max_body_size as u64);
+            if let Some(timeout) = rest_config.limits.timeout {
+                limits.insert("rest_request_timeout_secs".to_string(), timeout.as_secs());
+            }
+            if let Some(capacity) = rest_config.response_cache {
+                limits.insert("rest_cache_size".to_string(), capacity.get() as u64);
+            }
+            if let Some(compression) = &rest_config.compression {
+                limits.insert("rest_compression_min_size".to_string(), compression.min_size as u64);
+            }
+        }
+        if self.p2p_cache_size != DEFAULT_CACHE_SIZE {
+            limits.insert("p2p_cache_size".to_string(), self.p2p_cache_size as u64);
+        }
+        if let Some(ttl) = self.p2p_cache_ttl {
+            limits.insert("p2p_cache_ttl_secs".to_string(), ttl);
+        }
+        if let Some(size) = self.p2p_max_message_size {
+            limits.insert("p2p_max_message_size".to_string(), size as u64);
+        }
+        if let Some(read_timeout) = self.p2p_read_timeout {
+            limits.insert("p2p_read_timeout_secs".to_string(), read_timeout);
+        }
+        if self.peer_bandwidth_in != 0 {
+            limits.insert("peer_bandwidth_in_kib".to_string(), self.peer_bandwidth_in as u64);
+        }
+        if self.peer_bandwidth_out != 0 {
+            limits.insert("peer_bandwidth_out_kib".to_string(), self.peer_bandwidth_out as u64);
+        }
+        if let Some(cdn) = cdn {
+            limits.insert("cdn_safety_margin".to_string(), cdn.safety_margin as u64);
+            if let Some(error_budget) = cdn.error_budget {
+                limits.insert("cdn_error_budget".to_string(), error_budget as u64);
+            }
+        }
+
+        NodeInfo {
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            git_commit: option_env!("SNARKOS_GIT_COMMIT").map(str::to_string),
+            features: snarkos_node::build_features().into_iter().map(str::to_string).collect(),
+            network: N::NAME.to_string(),
+            network_id: N::ID,
+            node_type: node_type.to_string(),
+            subsystems,
+            limits,
         }
     }
 
@@ -220,23 +845,32 @@ impl Start {
     /// returning the Aleo account.
     fn parse_private_key<N: Network>(&self) -> Result<Account<N>> {
         match self.dev {
-            None => match (&self.private_key, &self.private_key_file) {
+            None => match (&self.private_key, &self.private_key_file, &self.keystore) {
                 // Parse the private key directly.
-                (Some(private_key), None) => Account::from_str(private_key.trim()),
+                (Some(private_key), None, None) => Account::from_str(private_key.trim()),
                 // Parse the private key from a file.
-                (None, Some(path)) => {
+                (None, Some(path), None) => {
                     check_permissions(path)?;
                     Account::from_str(std::fs::read_to_string(path)?.trim())
                 }
+                // Decrypt the private key from a keystore.
+                (None, None, Some(path)) => {
+                    check_permissions(path)?;
+                    let password = match crate::helpers::keystore_password_from_env() {
+                        Some(password) => password,
+                        None => crate::helpers::read_password("🔐 Enter the keystore password")?,
+                    };
+                    Account::from_keystore(&std::fs::read(path)?, password.as_bytes())
+                }
                 // Ensure the private key is provided to the CLI, except for clients or nodes in development mode.
-                (None, None) => match self.client {
+                (None, None, None) => match self.client {
                     true => Account::new(&mut rand::thread_rng()),
-                    false => bail!("Missing the '--private-key' or '--private-key-file' argument"),
+                    false => bail!("Missing the '--private-key', '--private-key-file' or '--keystore' argument"),
                 },
                 // Ensure only one private key flag is provided to the CLI.
-                (Some(_), Some(_)) => {
-                    bail!("Cannot use '--private-key' and '--private-key-file' simultaneously, please use only one")
-                }
+                _ => bail!(
+                    "Cannot use more than one of '--private-key', '--private-key-file' and '--keystore', please use only one"
+                ),
             },
             Some(dev) => {
                 // Sample the private key of this node.
@@ -255,6 +889,84 @@ impl Start {
         }
     }
 
+    /// Returns the signer of the batches of the validator, if it differs from the account of the node
+    /// (i.e. if a remote signer or a validator lease is set).
+    async fn parse_signer<N: Network>(&self, account: &Account<N>) -> Result<Option<Arc<dyn Signer<N>>>> {
+        // Sign the batches of the validator with the remote signer, if one is set.
+        let signer: Arc<dyn Signer<N>> = match &self.bft_remote_signer {
+            Some(url) => {
+                let signer = RemoteSigner::<N>::connect(url).await?;
+                println!("🔏 Signing the batches with the remote signer for {}\n", signer.address().to_string().bold());
+                Arc::new(signer)
+            }
+            None => Arc::new(LocalSigner::new(account.clone())),
+        };
+        // Only sign while holding the validator lease, if one is set.
+        let Some(target) = &self.bft_lease else {
+            return Ok(self.bft_remote_signer.is_some().then_some(signer));
+        };
+        ensure!(self.bft_lease_duration >= 3, "The validator lease must last at least 3 seconds");
+        let lease: Arc<dyn Lease> = match target.starts_with("http://") || target.starts_with("https://") {
+            true => Arc::new(RemoteLease::new(target)?),
+            false => Arc::new(FileLease::new(target)),
+        };
+        let holder = format!("{}-{:016x}", account.address(), rand::random::<u64>());
+        let duration = Duration::from_secs(self.bft_lease_duration);
+        let signer = Arc::new(LeasedSigner::new(signer, lease, holder, duration));
+        // Attempt to acquire the lease, and keep renewing it in the background.
+        match signer.renew().await {
+            true => println!("🔐 Holding the validator lease at '{target}' (active validator)\n"),
+            false => println!("🔐 Waiting for the validator lease at '{target}' (standby validator)\n"),
+        }
+        signer.start_renewal();
+        Ok(Some(signer))
+    }
+
+    /// Returns the configuration of the BFT, with the given signer of the batches.
+    fn parse_bft_config<N: Network>(&self, signer: Option<Arc<dyn Signer<N>>>) -> Result<BftConfig<N>> {
+        // Parse the limits of the batch proposals.
+        let max = ProposalLimits::max::<N>();
+        let proposal_limits = ProposalLimits {
+            max_transmissions: self.bft_max_batch_transmissions.unwrap_or(max.max_transmissions),
+            max_bytes: self.bft_max_batch_bytes.unwrap_or(max.max_bytes),
+            interval_in_ms: self.bft_batch_interval.unwrap_or(max.interval_in_ms),
+            // Pace the block production of the development network, if a minimum block interval is set.
+            // Note: A block is committed every two rounds.
+            min_interval_in_ms: self.dev_min_block_interval.map_or(max.min_interval_in_ms, |interval| interval / 2),
+        };
+        // Parse the limits of the memory pool.
+        let mempool_limits = MempoolLimits {
+            max_transactions: self.mempool_max_transactions,
+            max_bytes: self.mempool_max_bytes,
+            max_transactions_per_payer: self.mempool_max_transactions_per_payer,
+            eviction_policy: self.mempool_eviction_policy.parse()?,
+            replacement_fee_bump: self.mempool_replacement_fee_bump,
+        };
+        // Parse the capacities of the admission lanes of the deployments and the executions.
+        let default = AdmissionLimits::default();
+        let admission_limits = AdmissionLimits {
+            deployments: LaneLimits { capacity: self.mempool_deployment_lane_capacity, ..default.deployments },
+            executions: LaneLimits { capacity: self.mempool_execution_lane_capacity, ..default.executions },
+        };
+        // Append the consensus events of the validator to the audit log, if one is set.
+        let audit_log = self.bft_audit_log.as_ref().map(AuditLog::open).transpose()?.map(Arc::new);
+
+        let config = BftConfig {
+            pruning_rounds: self.bft_pruning_rounds,
+            archive: self.bft_archive.clone(),
+            proposal_limits,
+            num_workers: self.bft_workers,
+            max_blocks_behind: self.bft_max_blocks_behind,
+            mempool_limits,
+            admission_limits,
+            audit_log,
+            signer,
+        };
+        // Ensure the configuration is within the limits enforced by the network.
+        config.check()?;
+        Ok(config)
+    }
+
     /// Updates the configurations if the node is in development mode.
     fn parse_development(
         &mut self,
@@ -379,6 +1091,51 @@ impl Start {
         }
     }
 
+    /// Returns the storage mode, from the given configurations.
+    fn parse_storage_mode(&self) -> StorageMode {
+        match &self.storage_path {
+            Some(path) => StorageMode::Custom(path.clone()),
+            None => StorageMode::from(self.dev),
+        }
+    }
+
+    /// Syncs the ledger from the CDN up to the given height (inclusive), without starting the node.
+    fn sync_to_height<N: Network>(&self, height: u32) -> Result<String> {
+        // Parse the CDN.
+        let Some(cdn) = self.parse_cdn() else {
+            bail!("The '--sync-to-height' flag requires a client or validator with the CDN enabled")
+        };
+        // Parse the genesis block.
+        let genesis = self.parse_genesis::<N>()?;
+        // Initialize the storage mode.
+        let storage_mode = self.parse_storage_mode();
+
+        Self::runtime().block_on(async move {
+            // Initialize the ledger.
+            let ledger = Ledger::<N, ConsensusDB<N>>::load(genesis, storage_mode)?;
+            // Ensure the ledger is not already past the given height.
+            let latest_height = ledger.latest_height();
+            ensure!(latest_height <= height, "The ledger is already at block {latest_height}, past block {height}");
+            // If the ledger is already at the given height, there is nothing to sync.
+            // Note: The CDN sync reports the (exclusive) end height if there are no blocks to load.
+            if latest_height == height {
+                return Ok(format!("✅ The ledger is already at block {height}"));
+            }
+
+            // Sync the ledger with the CDN.
+            println!("📦 Syncing the ledger from block {latest_height} to block {height} from the CDN...\n");
+            let completed_height = snarkos_node_cdn::sync_ledger_with_fallback(&cdn, ledger, Default::default(), None)
+                .await
+                .map_err(|(_, error)| error)?;
+            // Ensure the ledger reached the given height.
+            ensure!(
+                completed_height >= height,
+                "Synced the ledger to block {completed_height}, but block {height} is not available from the CDN"
+            );
+            Ok(format!("✅ Synced the ledger to block {completed_height}"))
+        })
+    }
+
     /// Returns the node type corresponding to the given configurations.
     #[rustfmt::skip]
     async fn parse_node<N: Network>(&mut self) -> Result<Node<N>> {
@@ -387,6 +1144,33 @@ impl Start {
 
         // Parse the trusted peers to connect to.
         let mut trusted_peers = self.parse_trusted_peers()?;
+        // Set the per-connection bandwidth limits.
+        let (inbound_bandwidth_limit, outbound_bandwidth_limit) = self.parse_bandwidth_limits();
+        snarkos_node::tcp::set_default_bandwidth_limits(inbound_bandwidth_limit, outbound_bandwidth_limit);
+        // Set the handshake and read timeouts, and the maximum size of the P2P messages.
+        let (handshake_timeout_ms, read_timeout_ms) = self.parse_p2p_timeouts()?;
+        snarkos_node::tcp::set_default_timeouts(handshake_timeout_ms, read_timeout_ms);
+        if let Some(size) = self.parse_p2p_max_message_size()? {
+            set_default_maximum_message_size(size);
+        }
+        // Route the outbound connections through the proxy, if one is set.
+        if let Some(proxy) = self.proxy {
+            snarkos_node::tcp::set_default_proxy(proxy);
+        }
+        // Dial the outbound connections from the listening port, if hole punching is enabled.
+        if self.p2p_hole_punching {
+            ensure!(self.proxy.is_none(), "TCP hole punching ('--p2p-hole-punching') cannot be used with '--proxy'");
+            snarkos_node::tcp::set_default_hole_punching(true);
+        }
+        // Verify the transmissions on a dedicated thread pool, if one is set.
+        let cpu_cores = self.bft_verification_cores.as_deref().map(parse_cpu_cores).transpose()?.unwrap_or_default();
+        let num_threads = self.bft_verification_threads.or((!cpu_cores.is_empty()).then_some(cpu_cores.len()));
+        if let Some(num_threads) = num_threads {
+            set_verification_pool(num_threads, cpu_cores)?;
+        }
+        // Resolve the '.onion' peers through the proxy.
+        let onion_peers = self.parse_onion_peers()?;
+        trusted_peers.extend(self.resolve_onion_peers(onion_peers).await?);
         // Parse the trusted validators to connect to.
         let mut trusted_validators = self.parse_trusted_validators()?;
         // Parse the development configurations.
@@ -399,14 +1183,30 @@ impl Start {
         let genesis = self.parse_genesis::<N>()?;
         // Parse the private key of the node.
         let account = self.parse_private_key::<N>()?;
+        // Parse the signer of the batches of the validator, if it differs from the account of the node.
+        let signer = self.parse_signer(&account).await?;
+        // Parse the configuration of the BFT.
+        let bft_config = self.parse_bft_config(signer)?;
         // Parse the node type.
         let node_type = self.parse_node_type();
+        // Parse the peer limits.
+        let peer_limits = self.parse_peer_limits(node_type)?;
+        // Parse the configuration of the P2P caches.
+        let cache_config = self.parse_cache_config()?;
+        // Parse the handshake capabilities and policy.
+        let capabilities = self.parse_capabilities()?;
+        let handshake_policy = self.parse_handshake_policy()?;
 
         // Parse the REST IP.
         let rest_ip = match self.norest {
             true => None,
             false => Some(self.rest),
         };
+        // Parse the REST configurations.
+        let mut rest_config = self.parse_rest_config()?;
+        // Parse the node information, and log it as a structured line for fleet tooling.
+        rest_config.node_info = self.parse_node_info::<N>(node_type, cdn.as_ref(), &rest_config);
+        tracing::info!("Node info: {}", serde_json::to_string(&rest_config.node_info)?);
 
         // If the display is not enabled, render the welcome message.
         if self.nodisplay {
@@ -446,18 +1246,58 @@ impl Start {
         }
 
         // Initialize the storage mode.
-        let storage_mode = match &self.storage_path {
-            Some(path) => StorageMode::Custom(path.clone()),
-            None => StorageMode::from(self.dev),
-        };
+        let storage_mode = self.parse_storage_mode();
+
+        // Ensure the BFT external address is only set for validators.
+        if !node_type.is_validator() && self.bft_external.is_some() {
+            bail!("The '--bft-external' flag is only available for validators")
+        }
 
         // Initialize the node.
-        let bft_ip = if self.dev.is_some() { self.bft } else { None };
-        match node_type {
-            NodeType::Validator => Node::new_validator(self.node, bft_ip, rest_ip, self.rest_rps, account, &trusted_peers, &trusted_validators, genesis, cdn, storage_mode).await,
-            NodeType::Prover => Node::new_prover(self.node, account, &trusted_peers, genesis, storage_mode).await,
-            NodeType::Client => Node::new_client(self.node, rest_ip, self.rest_rps, account, &trusted_peers, genesis, cdn, storage_mode).await,
+        let node = match node_type {
+            NodeType::Validator => Node::new_validator(self.node, self.bft, rest_ip, self.rest_rps, rest_config, account, &trusted_peers, peer_limits, &trusted_validators, genesis, cdn, storage_mode, bft_config).await,
+            NodeType::Prover => Node::new_prover(self.node, account, &trusted_peers, peer_limits, genesis, storage_mode).await,
+            NodeType::Client => Node::new_client(self.node, rest_ip, self.rest_rps, rest_config, account, &trusted_peers, peer_limits, genesis, cdn, storage_mode).await,
+        }?;
+        // Advertise the optional features, including the compression of the P2P messages, if enabled.
+        node.enable_capabilities(capabilities);
+        // Reject the peers that do not satisfy the handshake policy.
+        node.set_handshake_policy(handshake_policy);
+        // Advertise the external addresses, if they differ from the listener addresses.
+        // Otherwise, detect the external address from the addresses observed by the peers, unless the connections
+        // go through a proxy, or the node is in development mode (where the peers are local).
+        match self.node_external {
+            Some(node_external) => node.set_advertised_addr(node_external),
+            None if self.proxy.is_none() && self.dev.is_none() => node.enable_external_addr_detection(),
+            None => (),
+        }
+        if let Some(bft_external) = self.bft_external {
+            node.set_bft_advertised_addr(bft_external)?;
         }
+        // Configure the caches of recently seen solutions and transactions.
+        node.set_cache_config(cache_config);
+        // Discover peers from the DNS seeds, if any.
+        node.enable_dns_seeds(self.parse_dns_seeds());
+        // Load the allowlist and denylist of peers, if any.
+        let access_list_files = AccessListFiles { allowlist: self.allowlist.clone(), denylist: self.denylist.clone() };
+        node.enable_access_lists(access_list_files)?;
+        // Forward the node port on the local router, if enabled.
+        if self.upnp {
+            node.enable_port_mapping();
+        }
+        // Replay the recorded inbound messages, then record the new ones, if enabled.
+        #[cfg(feature = "record")]
+        {
+            if let Some(path) = &self.replay_inbound {
+                let num_messages = node.replay_inbound(path).await?;
+                println!("⏪ Replayed {num_messages} inbound messages from '{}'\n", path.display());
+            }
+            if let Some(path) = &self.record_inbound {
+                node.start_recording(path)?;
+                println!("📼 Recording the inbound messages to '{}'\n", path.display());
+            }
+        }
+        Ok(node)
     }
 
     /// Returns a runtime for the node.
@@ -489,6 +1329,11 @@ impl Start {
     }
 }
 
+/// Returns `true` if the given peer is a `.onion` address (with a port).
+fn is_onion_peer(peer: &str) -> bool {
+    peer.rsplit_once(':').is_some_and(|(host, _)| host.ends_with(".onion"))
+}
+
 fn check_permissions(path: &PathBuf) -> Result<(), snarkvm::prelude::Error> {
     #[cfg(target_family = "unix")]
     {
@@ -577,10 +1422,21 @@ fn load_or_compute_genesis<N: Network>(
 mod tests {
     use super::*;
     use crate::commands::{Command, CLI};
+    use snarkos_node::rest::{CompressionAlgorithm, RateLimitScope};
     use snarkvm::prelude::MainnetV0;
 
     type CurrentNetwork = MainnetV0;
 
+    #[test]
+    fn test_parse_dns_seeds() {
+        let config = Start::try_parse_from(["snarkos"].iter()).unwrap();
+        assert!(config.parse_dns_seeds().is_empty());
+
+        let config = Start::try_parse_from(["snarkos", "--dns-seeds", "seed.example.com, seed.example.org:4133,"].iter())
+            .unwrap();
+        assert_eq!(config.parse_dns_seeds(), vec!["seed.example.com", "seed.example.org:4133"]);
+    }
+
     #[test]
     fn test_parse_trusted_peers() {
         let config = Start::try_parse_from(["snarkos", "--peers", ""].iter()).unwrap();
@@ -599,6 +1455,221 @@ mod tests {
         ]);
     }
 
+    #[test]
+    fn test_parse_peer_limits() {
+        let config = Start::try_parse_from(["snarkos"].iter()).unwrap();
+        assert_eq!(config.parse_peer_limits(NodeType::Client).unwrap(), PeerLimits::new(NodeType::Client));
+
+        // Ensure the target is capped by the maximum number of outbound peers.
+        let config = Start::try_parse_from(["snarkos", "--max-outbound-peers", "4"].iter()).unwrap();
+        let limits = config.parse_peer_limits(NodeType::Validator).unwrap();
+        assert_eq!(limits.max_outbound, 4);
+        assert_eq!(limits.target_outbound, 4);
+
+        let args =
+            ["snarkos", "--max-inbound-peers", "50", "--max-outbound-peers", "20", "--target-outbound-peers", "8"];
+        let config = Start::try_parse_from(args.iter()).unwrap();
+        let limits = config.parse_peer_limits(NodeType::Client).unwrap();
+        assert_eq!(limits, PeerLimits { max_inbound: 50, max_outbound: 20, target_outbound: 8 });
+
+        // Ensure the target may not exceed the maximum number of outbound peers.
+        let args = ["snarkos", "--max-outbound-peers", "4", "--target-outbound-peers", "8"];
+        let config = Start::try_parse_from(args.iter()).unwrap();
+        assert!(config.parse_peer_limits(NodeType::Client).is_err());
+    }
+
+    #[test]
+    fn test_parse_cache_config() {
+        let config = Start::try_parse_from(["snarkos"].iter()).unwrap();
+        assert_eq!(config.parse_cache_config().unwrap(), CacheConfig::default());
+
+        let args = ["snarkos", "--p2p-cache-size", "1000", "--p2p-cache-ttl", "60"];
+        let config = Start::try_parse_from(args.iter()).unwrap();
+        let cache_config = config.parse_cache_config().unwrap();
+        assert_eq!(cache_config, CacheConfig { capacity: 1000, ttl: Some(Duration::from_secs(60)) });
+
+        // Ensure the cache size and TTL may not be zero.
+        let config = Start::try_parse_from(["snarkos", "--p2p-cache-size", "0"].iter()).unwrap();
+        assert!(config.parse_cache_config().is_err());
+        let config = Start::try_parse_from(["snarkos", "--p2p-cache-ttl", "0"].iter()).unwrap();
+        assert!(config.parse_cache_config().is_err());
+    }
+
+    #[test]
+    fn test_parse_handshake_policy() {
+        let config = Start::try_parse_from(["snarkos"].iter()).unwrap();
+        assert_eq!(config.parse_capabilities().unwrap(), Capabilities::NONE);
+        assert!(config.parse_handshake_policy().unwrap().is_permissive());
+
+        let args = ["snarkos", "--p2p-compression", "--p2p-capabilities", "archive"];
+        let config = Start::try_parse_from(args.iter()).unwrap();
+        assert_eq!(config.parse_capabilities().unwrap(), Capabilities::COMPRESSION | Capabilities::ARCHIVE);
+
+        let config = Start::try_parse_from(["snarkos", "--p2p-capabilities", "relay"].iter()).unwrap();
+        assert_eq!(config.parse_capabilities().unwrap(), Capabilities::RELAY);
+
+        let config = Start::try_parse_from(["snarkos", "--p2p-hole-punching"].iter()).unwrap();
+        assert_eq!(config.parse_capabilities().unwrap(), Capabilities::HOLE_PUNCHING);
+
+        let args = [
+            "snarkos",
+            "--min-peer-version",
+            "2.2.0",
+            "--require-peer-capabilities",
+            "cdn",
+            "--deny-user-agents",
+            "snarkOS/2.1, badbot",
+        ];
+        let config = Start::try_parse_from(args.iter()).unwrap();
+        let policy = config.parse_handshake_policy().unwrap();
+        assert_eq!(policy.min_node_version, Some(NodeVersion::new(2, 2, 0)));
+        assert_eq!(policy.required_capabilities, Capabilities::CDN);
+        assert_eq!(policy.denied_user_agents, vec!["snarkOS/2.1", "badbot"]);
+
+        // Ensure invalid versions and capabilities are rejected.
+        let config = Start::try_parse_from(["snarkos", "--min-peer-version", "2.2"].iter()).unwrap();
+        assert!(config.parse_handshake_policy().is_err());
+        let config = Start::try_parse_from(["snarkos", "--p2p-capabilities", "teleportation"].iter()).unwrap();
+        assert!(config.parse_capabilities().is_err());
+    }
+
+    #[test]
+    fn test_parse_p2p_transport_limits() {
+        let config = Start::try_parse_from(["snarkos"].iter()).unwrap();
+        assert_eq!(config.parse_p2p_timeouts().unwrap(), (3_000, 0));
+        assert_eq!(config.parse_p2p_max_message_size().unwrap(), None);
+
+        let args = ["snarkos", "--p2p-handshake-timeout", "5", "--p2p-read-timeout", "60"];
+        let config = Start::try_parse_from(args.iter()).unwrap();
+        assert_eq!(config.parse_p2p_timeouts().unwrap(), (5_000, 60_000));
+        let config = Start::try_parse_from(["snarkos", "--p2p-max-message-size", "16777216"].iter()).unwrap();
+        assert_eq!(config.parse_p2p_max_message_size().unwrap(), Some(16 * 1024 * 1024));
+
+        // Ensure the timeouts may not be zero, and the maximum message size must be within bounds.
+        let config = Start::try_parse_from(["snarkos", "--p2p-handshake-timeout", "0"].iter()).unwrap();
+        assert!(config.parse_p2p_timeouts().is_err());
+        let config = Start::try_parse_from(["snarkos", "--p2p-read-timeout", "0"].iter()).unwrap();
+        assert!(config.parse_p2p_timeouts().is_err());
+        let config = Start::try_parse_from(["snarkos", "--p2p-max-message-size", "1024"].iter()).unwrap();
+        assert!(config.parse_p2p_max_message_size().is_err());
+        let config = Start::try_parse_from(["snarkos", "--p2p-max-message-size", "268435456"].iter()).unwrap();
+        assert!(config.parse_p2p_max_message_size().is_err());
+    }
+
+    #[test]
+    fn test_parse_bandwidth_limits() {
+        let config = Start::try_parse_from(["snarkos"].iter()).unwrap();
+        assert_eq!(config.parse_bandwidth_limits(), (None, None));
+
+        let config = Start::try_parse_from(["snarkos", "--peer-bandwidth-in", "512"].iter()).unwrap();
+        assert_eq!(config.parse_bandwidth_limits(), (NonZeroU32::new(512 * 1024), None));
+
+        let args = ["snarkos", "--peer-bandwidth-in", "0", "--peer-bandwidth-out", "64"];
+        let config = Start::try_parse_from(args.iter()).unwrap();
+        assert_eq!(config.parse_bandwidth_limits(), (None, NonZeroU32::new(64 * 1024)));
+    }
+
+    #[test]
+    fn test_parse_onion_peers() {
+        let onion = "abcdefghijklmnopqrstuvwxyz234567abcdefghijklmnopqrstuvwx.onion";
+
+        // Ensure '.onion' peers require a proxy.
+        let peers = format!("1.2.3.4:5,{onion}:4130");
+        let config = Start::try_parse_from(["snarkos", "--peers", &peers].iter()).unwrap();
+        assert!(config.parse_onion_peers().is_err());
+        assert_eq!(config.parse_trusted_peers().unwrap(), vec![SocketAddr::from_str("1.2.3.4:5").unwrap()]);
+
+        let config = Start::try_parse_from(["snarkos", "--peers", &peers, "--proxy", "127.0.0.1:9050"].iter()).unwrap();
+        assert_eq!(config.parse_onion_peers().unwrap(), vec![(onion.to_string(), 4130)]);
+        assert_eq!(config.parse_trusted_peers().unwrap(), vec![SocketAddr::from_str("1.2.3.4:5").unwrap()]);
+
+        // Ensure malformed '.onion' peers are rejected.
+        let peers = format!("{onion}:port");
+        let config = Start::try_parse_from(["snarkos", "--peers", &peers, "--proxy", "127.0.0.1:9050"].iter()).unwrap();
+        assert!(config.parse_onion_peers().is_err());
+    }
+
+    #[test]
+    fn test_parse_signer() {
+        let account = Account::<CurrentNetwork>::new(&mut rand::thread_rng()).unwrap();
+        let runtime = runtime::Runtime::new().unwrap();
+
+        // Ensure no signer is set by default.
+        let config = Start::try_parse_from(["snarkos", "--validator"].iter()).unwrap();
+        assert!(runtime.block_on(config.parse_signer(&account)).unwrap().is_none());
+
+        // Ensure the lease must not be shorter than 3 seconds.
+        let path = std::env::temp_dir().join(format!("snarkos-cli-lease-{}", std::process::id()));
+        let path = path.to_str().unwrap();
+        let config =
+            Start::try_parse_from(["snarkos", "--validator", "--bft-lease", path, "--bft-lease-duration", "1"].iter())
+                .unwrap();
+        assert!(runtime.block_on(config.parse_signer(&account)).is_err());
+
+        // Ensure the signer holds the lease.
+        let config = Start::try_parse_from(["snarkos", "--validator", "--bft-lease", path].iter()).unwrap();
+        let signer = runtime.block_on(config.parse_signer(&account)).unwrap().unwrap();
+        assert_eq!(signer.address(), account.address());
+        assert!(signer.is_active());
+        std::fs::remove_file(path).unwrap();
+
+        // Ensure the lease requires a validator.
+        assert!(Start::try_parse_from(["snarkos", "--bft-lease", path].iter()).is_err());
+    }
+
+    #[test]
+    fn test_parse_bft_config() {
+        // Ensure the default configuration is within the limits of the network.
+        let config = Start::try_parse_from(["snarkos", "--validator"].iter()).unwrap();
+        let bft_config = config.parse_bft_config::<CurrentNetwork>(None).unwrap();
+        assert_eq!(bft_config.proposal_limits, ProposalLimits::max::<CurrentNetwork>());
+        assert_eq!(bft_config.num_workers, DEFAULT_WORKERS);
+        assert_eq!(bft_config.mempool_limits, MempoolLimits::default());
+        assert_eq!(bft_config.admission_limits, AdmissionLimits::default());
+        assert!(bft_config.audit_log.is_none());
+
+        // Ensure the flags are threaded into the configuration.
+        let config = Start::try_parse_from(
+            [
+                "snarkos",
+                "--validator",
+                "--bft-workers",
+                "2",
+                "--bft-max-blocks-behind",
+                "5",
+                "--mempool-replacement-fee-bump",
+                "25",
+                "--mempool-execution-lane-capacity",
+                "8",
+            ]
+            .iter(),
+        )
+        .unwrap();
+        let bft_config = config.parse_bft_config::<CurrentNetwork>(None).unwrap();
+        assert_eq!(bft_config.num_workers, 2);
+        assert_eq!(bft_config.max_blocks_behind, 5);
+        assert_eq!(bft_config.mempool_limits.replacement_fee_bump, 25);
+        assert_eq!(bft_config.admission_limits.executions.capacity, 8);
+
+        // Ensure the configuration is rejected if it exceeds the limits of the network.
+        let config = Start::try_parse_from(["snarkos", "--validator", "--bft-workers", "0"].iter()).unwrap();
+        assert!(config.parse_bft_config::<CurrentNetwork>(None).is_err());
+        let config =
+            Start::try_parse_from(["snarkos", "--validator", "--mempool-deployment-lane-capacity", "0"].iter())
+                .unwrap();
+        assert!(config.parse_bft_config::<CurrentNetwork>(None).is_err());
+    }
+
+    #[test]
+    fn test_parse_dev_min_block_interval() {
+        // Ensure the minimum block interval requires development mode.
+        assert!(Start::try_parse_from(["snarkos", "--dev-min-block-interval", "5000"].iter()).is_err());
+
+        let config =
+            Start::try_parse_from(["snarkos", "--dev", "0", "--dev-min-block-interval", "5000"].iter()).unwrap();
+        assert_eq!(config.dev_min_block_interval, Some(5000));
+    }
+
     #[test]
     fn test_parse_trusted_validators() {
         let config = Start::try_parse_from(["snarkos", "--validators", ""].iter()).unwrap();
@@ -695,6 +1766,58 @@ mod tests {
         .unwrap();
         assert!(config.parse_cdn().is_none());
 
+        // Error budget (Prod)
+        let config = Start::try_parse_from(["snarkos", "--client", "--cdn", "url"].iter()).unwrap();
+        assert_eq!(config.parse_cdn().unwrap().error_budget, None);
+        let config =
+            Start::try_parse_from(["snarkos", "--client", "--cdn", "url", "--cdn-error-budget", "20"].iter()).unwrap();
+        assert_eq!(config.parse_cdn().unwrap().error_budget, Some(20));
+
+        // Safety margin (Prod)
+        let config = Start::try_parse_from(["snarkos", "--client", "--cdn", "url"].iter()).unwrap();
+        assert_eq!(config.parse_cdn().unwrap().safety_margin, CdnConfig::DEFAULT_SAFETY_MARGIN);
+        let config =
+            Start::try_parse_from(["snarkos", "--client", "--cdn", "url", "--cdn-safety-margin", "0"].iter()).unwrap();
+        assert_eq!(config.parse_cdn().unwrap().safety_margin, 0);
+
+        // Mirrors (Prod)
+        let config = Start::try_parse_from(["snarkos", "--client", "--cdn", "url"].iter()).unwrap();
+        assert!(config.parse_cdn().unwrap().mirrors.is_empty());
+        let config =
+            Start::try_parse_from(["snarkos", "--client", "--cdn", "url", "--cdn-mirrors", "url2, url3"].iter())
+                .unwrap();
+        assert_eq!(config.parse_cdn().unwrap().mirrors, vec!["url2", "url3"]);
+
+        // Bearer token (Prod)
+        let config = Start::try_parse_from(["snarkos", "--client", "--cdn", "url"].iter()).unwrap();
+        assert_eq!(config.parse_cdn().unwrap().client.authorization, None);
+        let config =
+            Start::try_parse_from(["snarkos", "--client", "--cdn", "url", "--cdn-bearer-token", "abc"].iter()).unwrap();
+        assert_eq!(config.parse_cdn().unwrap().client.authorization, Some("Bearer abc".to_string()));
+
+        // Cache directory (Prod)
+        let config = Start::try_parse_from(["snarkos", "--client", "--cdn", "url"].iter()).unwrap();
+        assert_eq!(config.parse_cdn().unwrap().cache_dir, None);
+        let config =
+            Start::try_parse_from(["snarkos", "--client", "--cdn", "url", "--cdn-cache-dir", "/tmp/cache"].iter())
+                .unwrap();
+        assert_eq!(config.parse_cdn().unwrap().cache_dir, Some(PathBuf::from("/tmp/cache")));
+
+        // Spill directory (Prod)
+        let config = Start::try_parse_from(["snarkos", "--client", "--cdn", "url"].iter()).unwrap();
+        assert_eq!(config.parse_cdn().unwrap().spill_dir, None);
+        let config =
+            Start::try_parse_from(["snarkos", "--client", "--cdn", "url", "--cdn-spill-dir", "/tmp/spill"].iter())
+                .unwrap();
+        assert_eq!(config.parse_cdn().unwrap().spill_dir, Some(PathBuf::from("/tmp/spill")));
+
+        // Sync to height (Prod)
+        let config = Start::try_parse_from(["snarkos", "--client", "--cdn", "url"].iter()).unwrap();
+        assert_eq!(config.parse_cdn().unwrap().sync_to_height, None);
+        let config =
+            Start::try_parse_from(["snarkos", "--client", "--cdn", "url", "--sync-to-height", "1000"].iter()).unwrap();
+        assert_eq!(config.parse_cdn().unwrap().sync_to_height, Some(1000));
+
         // Default (Prod)
         let config = Start::try_parse_from(["snarkos"].iter()).unwrap();
         assert!(config.parse_cdn().is_none());
@@ -712,6 +1835,186 @@ mod tests {
         assert!(config.parse_cdn().is_none());
     }
 
+    #[test]
+    fn test_parse_rest_config() {
+        let config = Start::try_parse_from(["snarkos"].iter()).unwrap();
+        let compression = config.parse_rest_config().unwrap().compression.unwrap();
+        assert_eq!(compression.algorithms, vec![CompressionAlgorithm::Gzip, CompressionAlgorithm::Brotli]);
+        let config = Start::try_parse_from(["snarkos", "--rest-compression", "none"].iter()).unwrap();
+        assert!(config.parse_rest_config().unwrap().compression.is_none());
+
+        let config = Start::try_parse_from(["snarkos", "--rest-compression", "gzip,zstd"].iter()).unwrap();
+        let compression = config.parse_rest_config().unwrap().compression.unwrap();
+        assert!(compression.is_enabled(CompressionAlgorithm::Gzip));
+        assert!(!compression.is_enabled(CompressionAlgorithm::Brotli));
+        assert!(compression.is_enabled(CompressionAlgorithm::Zstd));
+        assert_eq!(compression.min_size, 1024);
+        assert_eq!(compression.content_types, vec!["application/json", "application/octet-stream", "text/"]);
+
+        let config = Start::try_parse_from(
+            [
+                "snarkos",
+                "--rest-compression",
+                "br",
+                "--rest-compression-min-size",
+                "0",
+                "--rest-compression-content-types",
+                "application/json",
+            ]
+            .iter(),
+        )
+        .unwrap();
+        let compression = config.parse_rest_config().unwrap().compression.unwrap();
+        assert_eq!(compression.algorithms, vec![CompressionAlgorithm::Brotli]);
+        assert_eq!(compression.min_size, 0);
+        assert_eq!(compression.content_types, vec!["application/json"]);
+
+        let config = Start::try_parse_from(["snarkos", "--rest-compression", "deflate"].iter()).unwrap();
+        assert!(config.parse_rest_config().is_err());
+
+        // TLS
+        let config = Start::try_parse_from(["snarkos"].iter()).unwrap();
+        assert!(config.parse_rest_config().unwrap().tls.is_none());
+        let config =
+            Start::try_parse_from(["snarkos", "--rest-tls-cert", "cert.pem", "--rest-tls-key", "key.pem"].iter())
+                .unwrap();
+        assert_eq!(
+            config.parse_rest_config().unwrap().tls,
+            Some(TlsConfig { cert_path: PathBuf::from("cert.pem"), key_path: PathBuf::from("key.pem") })
+        );
+        assert!(Start::try_parse_from(["snarkos", "--rest-tls-cert", "cert.pem"].iter()).is_err());
+        assert!(Start::try_parse_from(["snarkos", "--rest-tls-key", "key.pem"].iter()).is_err());
+
+        // API keys
+        let config = Start::try_parse_from(["snarkos"].iter()).unwrap();
+        assert!(config.parse_rest_config().unwrap().api_keys.is_none());
+        let path = std::env::temp_dir().join(format!("snarkos-cli-api-keys-{}.json", std::process::id()));
+        std::fs::write(&path, r#"[{"key": "abc", "rps": 5}]"#).unwrap();
+        let path_str = path.to_str().unwrap();
+        let config =
+            Start::try_parse_from(["snarkos", "--rest-api-keys", path_str, "--rest-require-api-key"].iter()).unwrap();
+        let api_keys = config.parse_rest_config().unwrap().api_keys.unwrap();
+        assert_eq!(api_keys.len(), 1);
+        assert!(api_keys.is_required());
+        std::fs::remove_file(&path).unwrap();
+        let config = Start::try_parse_from(["snarkos", "--rest-api-keys", path_str].iter()).unwrap();
+        assert!(config.parse_rest_config().is_err());
+        assert!(Start::try_parse_from(["snarkos", "--rest-require-api-key"].iter()).is_err());
+
+        // Rate limit
+        let config = Start::try_parse_from(["snarkos"].iter()).unwrap();
+        assert_eq!(config.parse_rest_config().unwrap().rate_limit, RateLimitConfig::default());
+        let config = Start::try_parse_from(
+            ["snarkos", "--rest-rps", "5", "--rest-burst", "20", "--rest-rate-limit-scope", "global"].iter(),
+        )
+        .unwrap();
+        assert_eq!(
+            config.parse_rest_config().unwrap().rate_limit,
+            RateLimitConfig { burst: Some(20), scope: RateLimitScope::Global }
+        );
+        let config = Start::try_parse_from(["snarkos", "--rest-rate-limit-scope", "peer"].iter()).unwrap();
+        assert!(config.parse_rest_config().is_err());
+
+        // CORS
+        let config = Start::try_parse_from(["snarkos"].iter()).unwrap();
+        assert_eq!(config.parse_rest_config().unwrap().cors, CorsConfig::default());
+        let config = Start::try_parse_from(
+            ["snarkos", "--rest-cors-origins", "https://app.example.com", "--rest-cors-methods", "GET"].iter(),
+        )
+        .unwrap();
+        let cors = config.parse_rest_config().unwrap().cors;
+        assert_eq!(cors.origins.unwrap(), vec!["https://app.example.com"]);
+        assert_eq!(cors.methods.len(), 1);
+        let config = Start::try_parse_from(["snarkos", "--rest-cors-headers", "content type"].iter()).unwrap();
+        assert!(config.parse_rest_config().is_err());
+
+        // State history
+        let config = Start::try_parse_from(["snarkos"].iter()).unwrap();
+        assert!(config.parse_rest_config().unwrap().state_history.is_none());
+        let config =
+            Start::try_parse_from(["snarkos", "--rest-state-history", "credits.aleo/account, foo.aleo/bar"].iter())
+                .unwrap();
+        assert_eq!(
+            config.parse_rest_config().unwrap().state_history,
+            Some(vec!["credits.aleo/account".to_string(), "foo.aleo/bar".to_string()])
+        );
+        let config = Start::try_parse_from(["snarkos", "--rest-state-history", "credits.aleo"].iter()).unwrap();
+        assert!(config.parse_rest_config().is_err());
+
+        // Address index
+        let config = Start::try_parse_from(["snarkos"].iter()).unwrap();
+        assert!(!config.parse_rest_config().unwrap().address_index);
+        let config = Start::try_parse_from(["snarkos", "--rest-address-index"].iter()).unwrap();
+        assert!(config.parse_rest_config().unwrap().address_index);
+
+        // Unix socket
+        let config = Start::try_parse_from(["snarkos"].iter()).unwrap();
+        assert!(config.parse_rest_config().unwrap().unix_socket.is_none());
+        let config = Start::try_parse_from(["snarkos", "--rest-unix-socket", "/tmp/snarkos.sock"].iter()).unwrap();
+        assert_eq!(
+            config.parse_rest_config().unwrap().unix_socket,
+            Some(UnixSocketConfig { path: PathBuf::from("/tmp/snarkos.sock"), exclusive: false })
+        );
+        let config = Start::try_parse_from(
+            ["snarkos", "--rest-unix-socket", "/tmp/snarkos.sock", "--rest-unix-socket-only"].iter(),
+        )
+        .unwrap();
+        assert!(config.parse_rest_config().unwrap().unix_socket.unwrap().exclusive);
+        assert!(Start::try_parse_from(["snarkos", "--rest-unix-socket-only"].iter()).is_err());
+
+        // Request limits
+        let config = Start::try_parse_from(["snarkos"].iter()).unwrap();
+        assert_eq!(config.parse_rest_config().unwrap().limits, RequestLimits::default());
+        let config =
+            Start::try_parse_from(["snarkos", "--rest-max-body-size", "1024", "--rest-request-timeout", "30"].iter())
+                .unwrap();
+        assert_eq!(
+            config.parse_rest_config().unwrap().limits,
+            RequestLimits { max_body_size: 1024, timeout: Some(Duration::from_secs(30)) }
+        );
+
+        // Response cache
+        let config = Start::try_parse_from(["snarkos"].iter()).unwrap();
+        assert_eq!(config.parse_rest_config().unwrap().response_cache, NonZeroUsize::new(1024));
+        let config = Start::try_parse_from(["snarkos", "--rest-cache-size", "0"].iter()).unwrap();
+        assert!(config.parse_rest_config().unwrap().response_cache.is_none());
+
+        // Shutdown grace period
+        let config = Start::try_parse_from(["snarkos"].iter()).unwrap();
+        assert_eq!(config.parse_rest_config().unwrap().shutdown_grace_period, Some(Duration::from_secs(10)));
+        let config = Start::try_parse_from(["snarkos", "--rest-shutdown-grace-period", "0"].iter()).unwrap();
+        assert!(config.parse_rest_config().unwrap().shutdown_grace_period.is_none());
+
+        // Admin
+        let config = Start::try_parse_from(["snarkos"].iter()).unwrap();
+        assert!(config.parse_rest_config().unwrap().admin.is_none());
+        let config = Start::try_parse_from(["snarkos", "--rest-admin"].iter()).unwrap();
+        assert!(config.parse_rest_config().unwrap().admin.unwrap().set_log_filter.is_some());
+    }
+
+    #[test]
+    fn test_parse_node_info() {
+        let config = Start::try_parse_from(["snarkos", "--validator", "--nodisplay"].iter()).unwrap();
+        let cdn = config.parse_cdn();
+        let rest_config = config.parse_rest_config().unwrap();
+        let node_info = config.parse_node_info::<CurrentNetwork>(NodeType::Validator, cdn.as_ref(), &rest_config);
+        assert_eq!(node_info.version, env!("CARGO_PKG_VERSION"));
+        assert_eq!(node_info.network, CurrentNetwork::NAME);
+        assert_eq!(node_info.node_type, "Validator");
+        assert_eq!(node_info.subsystems, vec!["bft", "cdn", "rest", "rest_compression"]);
+        assert_eq!(node_info.limits.get("rest_rps"), Some(&10));
+        assert_eq!(node_info.limits.get("cdn_safety_margin"), Some(&10));
+        assert!(!node_info.limits.contains_key("cdn_error_budget"));
+
+        let config = Start::try_parse_from(["snarkos", "--prover", "--metrics"].iter()).unwrap();
+        let cdn = config.parse_cdn();
+        let rest_config = config.parse_rest_config().unwrap();
+        let node_info = config.parse_node_info::<CurrentNetwork>(NodeType::Prover, cdn.as_ref(), &rest_config);
+        assert_eq!(node_info.node_type, "Prover");
+        assert_eq!(node_info.subsystems, vec!["display", "metrics"]);
+        assert!(node_info.limits.is_empty());
+    }
+
     #[test]
     fn test_parse_development_and_genesis() {
         let prod_genesis = Block::from_bytes_le(CurrentNetwork::genesis_bytes()).unwrap();
@@ -0,0 +1,606 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::AdminEndpoint;
+use crate::helpers::KEYSTORE_PASSWORD_ENV;
+use snarkos_account::Account;
+use snarkos_node::bft::{helpers::replay_dag, ledger_service::CoreLedgerService};
+use snarkvm::{
+    console::network::{MainnetV0, Network},
+    ledger::{
+        committee::MIN_VALIDATOR_STAKE,
+        store::{helpers::rocksdb::ConsensusDB, ConsensusStore},
+        Ledger,
+    },
+};
+
+use aleo_std::StorageMode;
+use anyhow::{bail, ensure, Result};
+use clap::Parser;
+use colored::Colorize;
+use core::str::FromStr;
+use std::{
+    io::Write,
+    net::{SocketAddr, TcpListener},
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+use zeroize::{Zeroize, Zeroizing};
+
+/// The minimum free disk space for a validator, in GiB.
+const MIN_DISK_SPACE_IN_GIB: u64 = 2048;
+/// The recommended free disk space for a validator, in GiB.
+const RECOMMENDED_DISK_SPACE_IN_GIB: u64 = 4096;
+
+/// Commands to operate a validator.
+#[derive(Debug, Parser)]
+pub enum Validator {
+    /// Sets up a new validator on this machine.
+    Init(Init),
+    /// Rotates the signer of a running validator at the next round, over its admin API (see '--rest-admin').
+    RotateKey(RotateKey),
+    /// Lists the equivocations of the validators observed by a running node.
+    Equivocations(Equivocations),
+    /// Replays the BFT DAG committed in the ledger offline, and checks it reproduces the committed subdags.
+    ReplayDag(ReplayDag),
+}
+
+impl Validator {
+    /// Parses the validator command.
+    pub fn parse(self) -> Result<String> {
+        match self {
+            Self::Init(init) => init.parse(),
+            Self::RotateKey(rotate_key) => rotate_key.parse(),
+            Self::Equivocations(equivocations) => equivocations.parse(),
+            Self::ReplayDag(replay_dag) => replay_dag.parse(),
+        }
+    }
+}
+
+/// Rotates the signer of a running validator, to a new private key or to a remote signing service.
+#[derive(Debug, Parser)]
+pub struct RotateKey {
+    /// Specify a file containing the new private key of the validator
+    #[clap(long = "private-key-file", conflicts_with = "remote_signer", required_unless_present = "remote_signer")]
+    pub private_key_file: Option<PathBuf>,
+    /// Specify the URL of the remote signing service to sign the batches of the validator
+    #[clap(long = "remote-signer")]
+    pub remote_signer: Option<String>,
+    #[clap(flatten)]
+    pub endpoint: AdminEndpoint,
+}
+
+impl RotateKey {
+    /// Schedules the rotation of the signer of the validator.
+    pub fn parse(self) -> Result<String> {
+        let body = match (&self.private_key_file, &self.remote_signer) {
+            (Some(path), _) => {
+                let mut private_key = std::fs::read_to_string(path)?;
+                let body = serde_json::json!({ "private_key": private_key.trim() });
+                private_key.zeroize();
+                body
+            }
+            (None, Some(url)) => serde_json::json!({ "remote_signer": url }),
+            (None, None) => bail!("Specify either '--private-key-file' or '--remote-signer'"),
+        };
+        self.endpoint.post("validator/signer", body)
+    }
+}
+
+/// Lists the equivocations observed by a node, i.e. the conflicting batches signed by a validator in the same round.
+#[derive(Debug, Parser)]
+pub struct Equivocations {
+    /// Only lists the equivocations of the given validator address
+    #[clap(long = "author")]
+    pub author: Option<String>,
+    /// Specify the REST endpoint of the node
+    #[clap(default_value = "http://127.0.0.1:3030", long = "endpoint")]
+    pub endpoint: String,
+}
+
+impl Equivocations {
+    /// Returns the URL of the equivocations of the node.
+    fn url(&self) -> String {
+        let url = format!("{}/mainnet/validators/equivocations", self.endpoint.trim_end_matches('/'));
+        match &self.author {
+            Some(author) => format!("{url}?author={author}"),
+            None => url,
+        }
+    }
+
+    /// Fetches the equivocations from the node.
+    pub fn parse(self) -> Result<String> {
+        match ureq::get(&self.url()).call() {
+            Ok(response) => Ok(response.into_string()?),
+            Err(ureq::Error::Status(code, response)) => {
+                bail!("The node returned an error (status code {code}): {}", response.into_string()?)
+            }
+            Err(error) => bail!("Failed to reach the node - {error}"),
+        }
+    }
+}
+
+/// Replays the BFT DAG committed in the blocks of the node storage through an offline BFT, and compares the subdags
+/// it commits with the subdags committed in the blocks, to debug divergences or validate an upgrade.
+/// Note: The node using the storage must be stopped, as the storage can only be opened by one process at a time.
+#[derive(Debug, Parser)]
+pub struct ReplayDag {
+    /// Specify the network of the node storage.
+    #[clap(default_value = "0", long = "network")]
+    pub network: u16,
+    /// Specify the height of the first block to replay
+    #[clap(default_value = "0", long = "start")]
+    pub start: u32,
+    /// Specify the height of the last block to replay (defaults to the latest block)
+    #[clap(long = "end")]
+    pub end: Option<u32>,
+    /// Enables development mode, specify the unique ID of the local node storage to replay.
+    #[clap(long)]
+    pub dev: Option<u16>,
+    /// Specify the path to a directory containing the ledger
+    #[clap(long = "path")]
+    pub path: Option<PathBuf>,
+}
+
+impl ReplayDag {
+    /// Replays the DAG, returning the replay report.
+    pub fn parse(self) -> Result<String> {
+        // Parse the network.
+        match self.network {
+            0 => self.replay::<MainnetV0>(),
+            _ => bail!("Invalid network ID specified"),
+        }
+    }
+
+    /// Replays the DAG of the given network.
+    fn replay<N: Network>(self) -> Result<String> {
+        // Initialize the storage mode.
+        let storage_mode = match self.path {
+            Some(path) => StorageMode::Custom(path),
+            None => StorageMode::from(self.dev),
+        };
+        // Retrieve the genesis block from the node storage, which may be a development genesis block.
+        let genesis = {
+            let store = ConsensusStore::<N, ConsensusDB<N>>::open(storage_mode.clone())?;
+            let Some(hash) = store.block_store().get_block_hash(0)? else { bail!("The node storage is empty") };
+            let Some(genesis) = store.block_store().get_block(&hash)? else { bail!("Missing the genesis block") };
+            genesis
+        };
+        // Load the ledger.
+        let ledger = Ledger::<N, ConsensusDB<N>>::load(genesis, storage_mode)?;
+        let end = self.end.unwrap_or_else(|| ledger.latest_height());
+        println!("🔁 Replaying the DAG of blocks {} to {end}...\n", self.start);
+
+        // Replay the DAG.
+        let ledger_service = Arc::new(CoreLedgerService::new(ledger, Default::default()));
+        let runtime = tokio::runtime::Builder::new_multi_thread().enable_all().build()?;
+        let report = runtime.block_on(replay_dag::<N>(ledger_service, self.start, end))?;
+
+        // Print the summary of the replay.
+        match report.is_consistent() {
+            true => println!("✅ Reproduced the subdags of {} blocks\n", report.num_matches),
+            false => {
+                let message = format!("⚠️  The subdags of {} blocks diverged from the replay", report.divergences.len());
+                println!("{}\n", message.yellow().bold());
+            }
+        }
+        Ok(serde_json::to_string_pretty(&report)?)
+    }
+}
+
+/// Sets up a new validator: stores the validator key in an encrypted keystore, checks the ports and disk space
+/// of this machine, writes the node configuration and a systemd unit, and prints the steps to bond the validator.
+#[derive(Debug, Parser)]
+pub struct Init {
+    /// Specify the network of the validator.
+    #[clap(default_value = "0", long = "network")]
+    pub network: u16,
+    /// Specify the directory to write the validator keystore and configuration files to
+    #[clap(default_value = "validator", long = "dir")]
+    pub dir: PathBuf,
+    /// Specify the IP address and port for the node server
+    #[clap(default_value = "0.0.0.0:4130", long = "node")]
+    pub node: SocketAddr,
+    /// Specify the IP address and port for the BFT
+    #[clap(default_value = "0.0.0.0:5000", long = "bft")]
+    pub bft: SocketAddr,
+    /// Specify the IP address and port for the REST server
+    #[clap(default_value = "0.0.0.0:3030", long = "rest")]
+    pub rest: SocketAddr,
+    /// Specify the IP address and port of the peer(s) to connect to
+    #[clap(default_value = "", long = "peers")]
+    pub peers: String,
+    /// Specify the IP address and port of the validator(s) to connect to
+    #[clap(default_value = "", long = "validators")]
+    pub validators: String,
+    /// Specify the user to run the systemd service as
+    #[clap(default_value = "root", long = "user")]
+    pub user: String,
+    /// Specify a file containing an existing private key to import, instead of generating a new one
+    #[clap(long = "import")]
+    pub import: Option<PathBuf>,
+    /// Enables the non-interactive mode, which uses the given flags without prompting
+    /// (the keystore password is then read from 'SNARKOS_KEYSTORE_PASSWORD')
+    #[clap(long)]
+    pub non_interactive: bool,
+}
+
+impl Init {
+    /// Runs the validator setup.
+    pub fn parse(self) -> Result<String> {
+        // Parse the network.
+        match self.network {
+            0 => self.init::<MainnetV0>(),
+            _ => bail!("Invalid network ID specified"),
+        }
+    }
+
+    /// Runs the validator setup for the given network.
+    fn init<N: Network>(mut self) -> Result<String> {
+        println!("\n{}\n", "🧙 Welcome to the snarkOS validator setup".bold());
+
+        // Step 1. Generate or import the validator key.
+        let account = self.load_account::<N>()?;
+        let password = self.keystore_password()?;
+        let key_path = self.write_keystore(&account, password.as_bytes())?;
+        println!("🔑 Your validator address is {}", account.address().to_string().bold());
+        println!("   The private key is encrypted in the keystore {}\n", key_path.display().to_string().dimmed());
+
+        // Step 2. Configure the node addresses.
+        if !self.non_interactive {
+            self.node = prompt("Node server address", &self.node.to_string())?.parse()?;
+            self.bft = prompt("BFT address", &self.bft.to_string())?.parse()?;
+            self.rest = prompt("REST server address", &self.rest.to_string())?.parse()?;
+            self.peers = prompt("Peers (comma-separated)", &self.peers)?;
+            self.validators = prompt("Validators (comma-separated)", &self.validators)?;
+            self.user = prompt("User to run the service as", &self.user)?;
+        }
+
+        // Step 3. Check the ports and disk space of this machine.
+        println!();
+        for (name, addr) in [("node", self.node), ("BFT", self.bft), ("REST", self.rest)] {
+            check_port(name, addr);
+        }
+        check_disk_space();
+        crate::helpers::check_validator_machine(snarkos_node::router::messages::NodeType::Validator);
+
+        // Step 4. Write the node configuration and the systemd unit, next to the (absolute) key path.
+        let env_path = key_path.with_file_name("snarkos-validator.env");
+        write_file(&env_path, &self.environment_file(&key_path))?;
+        let password_path = key_path.with_file_name("snarkos-validator.password");
+        let unit_path = key_path.with_file_name("snarkos-validator.service");
+        write_file(&unit_path, &self.systemd_unit(&std::env::current_exe()?, &env_path, &password_path))?;
+        println!("📝 Wrote the node configuration to {}", env_path.display().to_string().dimmed());
+        println!("📝 Wrote the systemd unit to {}\n", unit_path.display().to_string().dimmed());
+
+        // Step 5. Print the next steps.
+        Ok(self.next_steps(&account, &unit_path, &password_path))
+    }
+
+    /// Returns the account to use for the validator, importing or generating it as requested.
+    fn load_account<N: Network>(&mut self) -> Result<Account<N>> {
+        // Prompt for a key to import, if none is given.
+        if self.import.is_none() && !self.non_interactive {
+            let path = prompt("Path to an existing private key to import (leave empty to generate one)", "")?;
+            if !path.is_empty() {
+                self.import = Some(PathBuf::from(path));
+            }
+        }
+        match &self.import {
+            Some(path) => {
+                let mut private_key = std::fs::read_to_string(path)?;
+                let account = Account::from_str(private_key.trim());
+                private_key.zeroize();
+                account
+            }
+            None => Account::new(&mut rand::thread_rng()),
+        }
+    }
+
+    /// Returns the password to encrypt the keystore with, from 'SNARKOS_KEYSTORE_PASSWORD' or from the user.
+    fn keystore_password(&self) -> Result<Zeroizing<String>> {
+        if let Some(password) = crate::helpers::keystore_password_from_env() {
+            ensure!(!password.is_empty(), "The keystore password must not be empty");
+            return Ok(password);
+        }
+        if self.non_interactive {
+            bail!("Set the keystore password in the '{KEYSTORE_PASSWORD_ENV}' environment variable")
+        }
+        let password = crate::helpers::read_password("🔐 Choose the keystore password")?;
+        ensure!(!password.is_empty(), "The keystore password must not be empty");
+        let confirmation = crate::helpers::read_password("🔐 Confirm the keystore password")?;
+        ensure!(password == confirmation, "The keystore passwords do not match");
+        Ok(password)
+    }
+
+    /// Writes the private key of the given account, encrypted with the given password, into a keystore
+    /// in the validator directory, and returns its path.
+    ///
+    /// The directory must only be accessible by the owner (0700) and the keystore is only readable by the owner
+    /// (0600), as required by the `--keystore` flag of `snarkos start`. Only a directory created by this command
+    /// has its permissions set, and an existing directory with broader permissions is rejected.
+    fn write_keystore<N: Network>(&self, account: &Account<N>, password: &[u8]) -> Result<PathBuf> {
+        // Create the validator directory, or ensure the existing directory is only accessible by the owner.
+        match self.dir.exists() {
+            false => {
+                let mut builder = std::fs::DirBuilder::new();
+                builder.recursive(true);
+                #[cfg(target_family = "unix")]
+                {
+                    use std::os::unix::fs::DirBuilderExt;
+                    builder.mode(0o700);
+                }
+                builder.create(&self.dir)?;
+            }
+            #[cfg(target_family = "unix")]
+            true => {
+                use std::os::unix::fs::PermissionsExt;
+                let permissions = self.dir.metadata()?.permissions().mode();
+                ensure!(
+                    permissions & 0o777 == 0o700,
+                    "The directory {} must be accessible only by the owner (0700), please run 'chmod 700 {}' or use a different '--dir'",
+                    self.dir.display(),
+                    self.dir.display()
+                );
+            }
+            #[cfg(not(target_family = "unix"))]
+            true => (),
+        }
+        // Ensure an existing keystore is not overwritten.
+        let path = self.dir.canonicalize()?.join("validator.keystore");
+        if path.exists() {
+            bail!("The keystore {} already exists, please remove it or use a different '--dir'", path.display())
+        }
+        // Encrypt the private key.
+        let keystore = account.to_keystore(password, &mut rand::thread_rng())?;
+        // Write the keystore.
+        let mut options = std::fs::OpenOptions::new();
+        options.write(true).create_new(true);
+        #[cfg(target_family = "unix")]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            options.mode(0o600);
+        }
+        options.open(&path)?.write_all(&keystore)?;
+        Ok(path)
+    }
+
+    /// Returns the node configuration, in the format of a systemd environment file.
+    fn environment_file(&self, key_path: &Path) -> String {
+        let mut args = vec![
+            "--nodisplay".to_string(),
+            "--validator".to_string(),
+            format!("--keystore {}", key_path.display()),
+            format!("--node {}", self.node),
+            format!("--bft {}", self.bft),
+            format!("--rest {}", self.rest),
+        ];
+        if !self.peers.is_empty() {
+            args.push(format!("--peers {}", self.peers));
+        }
+        if !self.validators.is_empty() {
+            args.push(format!("--validators {}", self.validators));
+        }
+        format!(
+            "# The arguments to 'snarkos start' for the validator, generated by 'snarkos validator init'.\nSNARKOS_ARGS=\"{}\"\n",
+            args.join(" ")
+        )
+    }
+
+    /// Returns the systemd unit for the validator.
+    ///
+    /// The keystore password is read from the optional password file, which is left to the operator to write.
+    fn systemd_unit(&self, snarkos: &Path, env_path: &Path, password_path: &Path) -> String {
+        format!(
+            "[Unit]
+Description=snarkOS Validator
+After=network-online.target
+Wants=network-online.target
+
+[Service]
+User={user}
+EnvironmentFile={env}
+EnvironmentFile=-{password}
+ExecStart={snarkos} start $SNARKOS_ARGS
+Restart=always
+RestartSec=10
+LimitNOFILE=65535
+
+[Install]
+WantedBy=multi-user.target
+",
+            user = self.user,
+            env = env_path.display(),
+            password = password_path.display(),
+            snarkos = snarkos.display(),
+        )
+    }
+
+    /// Returns the steps to start and bond the validator.
+    fn next_steps<N: Network>(&self, account: &Account<N>, unit_path: &Path, password_path: &Path) -> String {
+        let address = account.address();
+        [
+            "🧭 Next steps:".bold().to_string(),
+            format!("  1. Ensure ports {} and {} are reachable from the other validators.", self.node.port(), self.bft.port()),
+            format!(
+                "  2. Write '{KEYSTORE_PASSWORD_ENV}=<password>' to {}, readable only by the owner (0600).",
+                password_path.display()
+            ),
+            format!("  3. Install the service with 'sudo cp {} /etc/systemd/system/'.", unit_path.display()),
+            "  4. Start the validator with 'sudo systemctl enable --now snarkos-validator'.".to_string(),
+            format!(
+                "  5. Fund {address} with at least {} credits for the stake, plus the transaction fees.",
+                MIN_VALIDATOR_STAKE / 1_000_000
+            ),
+            format!("  6. Bond the stake to {address} with 'credits.aleo/bond_public', e.g. using 'snarkos developer execute'."),
+            "  7. Once bonded, the validator joins the committee at the next committee update.".to_string(),
+        ]
+        .join("\n")
+    }
+}
+
+/// Prompts the user for a value, returning the default value if the input is empty.
+fn prompt(message: &str, default: &str) -> Result<String> {
+    print!("{} [{}]: ", message.cyan(), default);
+    std::io::stdout().flush()?;
+    let mut input = String::new();
+    std::io::stdin().read_line(&mut input)?;
+    match input.trim() {
+        "" => Ok(default.to_string()),
+        input => Ok(input.to_string()),
+    }
+}
+
+/// Checks that the given address is available on this machine.
+/// Note: This does not check that the port is reachable through the firewall or router of this machine.
+fn check_port(name: &str, addr: SocketAddr) {
+    match TcpListener::bind(addr) {
+        Ok(_) => println!("✅ The {name} port {} is available", addr.port()),
+        Err(error) => {
+            let message = format!("⚠️  The {name} address {addr} is not available - {error}");
+            println!("{}", message.yellow().bold());
+        }
+    }
+}
+
+/// Checks that the free disk space on this machine is sufficient for a validator.
+fn check_disk_space() {
+    match sys_info::disk_info() {
+        Ok(disk) => {
+            let free = disk.free / 1024 / 1024;
+            if free < MIN_DISK_SPACE_IN_GIB {
+                let message = format!(
+                    "⚠️  The free disk space ({free} GiB) on this machine is insufficient for a validator (minimum {MIN_DISK_SPACE_IN_GIB} GiB)"
+                );
+                println!("{}", message.yellow().bold());
+            } else if free < RECOMMENDED_DISK_SPACE_IN_GIB {
+                println!(
+                    "✅ The free disk space is {free} GiB ({RECOMMENDED_DISK_SPACE_IN_GIB} GiB or more is preferred)"
+                );
+            } else {
+                println!("✅ The free disk space is {free} GiB");
+            }
+        }
+        Err(error) => println!("{}", format!("⚠️  Unable to check the free disk space - {error}").yellow().bold()),
+    }
+    println!();
+}
+
+/// Writes the given contents to the given file.
+fn write_file(path: &Path, contents: &str) -> Result<()> {
+    Ok(std::fs::write(path, contents)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::{Command, CLI};
+
+    fn init(args: &[&str]) -> Init {
+        let cli = CLI::parse_from([&["snarkos", "validator", "init"][..], args].concat());
+        match cli.command {
+            Command::Validator(Validator::Init(init)) => init,
+            _ => panic!("Unexpected command"),
+        }
+    }
+
+    #[test]
+    fn test_environment_file() {
+        let init = init(&["--non-interactive", "--validators", "1.2.3.4:5000"]);
+        let env = init.environment_file(Path::new("/validator/validator.keystore"));
+        assert!(env.contains(
+            "SNARKOS_ARGS=\"--nodisplay --validator --keystore /validator/validator.keystore --node 0.0.0.0:4130 --bft 0.0.0.0:5000 --rest 0.0.0.0:3030 --validators 1.2.3.4:5000\""
+        ));
+        assert!(!env.contains("--peers"));
+    }
+
+    #[test]
+    fn test_systemd_unit() {
+        let init = init(&["--non-interactive", "--user", "aleo"]);
+        let unit = init.systemd_unit(
+            Path::new("/usr/bin/snarkos"),
+            Path::new("/validator/snarkos-validator.env"),
+            Path::new("/validator/snarkos-validator.password"),
+        );
+        assert!(unit.contains("User=aleo\n"));
+        assert!(unit.contains("EnvironmentFile=/validator/snarkos-validator.env\n"));
+        assert!(unit.contains("EnvironmentFile=-/validator/snarkos-validator.password\n"));
+        assert!(unit.contains("ExecStart=/usr/bin/snarkos start $SNARKOS_ARGS\n"));
+    }
+
+    #[test]
+    fn test_write_keystore() {
+        let dir = std::env::temp_dir().join(format!("snarkos-validator-init-{}", std::process::id()));
+        let init = init(&["--non-interactive", "--dir", dir.to_str().unwrap()]);
+        let account = Account::<MainnetV0>::new(&mut rand::thread_rng()).unwrap();
+
+        // Write the keystore into a new directory, and check it decrypts to the same account.
+        let path = init.write_keystore(&account, b"password").unwrap();
+        let keystore = std::fs::read(&path).unwrap();
+        assert_eq!(Account::<MainnetV0>::from_keystore(&keystore, b"password").unwrap().address(), account.address());
+        // Ensure an existing keystore is not overwritten.
+        assert!(init.write_keystore(&account, b"password").is_err());
+        std::fs::remove_file(&path).unwrap();
+
+        #[cfg(target_family = "unix")]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            // Check the permissions required by 'snarkos start'.
+            assert_eq!(std::fs::metadata(&dir).unwrap().permissions().mode() & 0o777, 0o700);
+            // Ensure the permissions of an existing directory are not changed, and broader ones are rejected.
+            std::fs::set_permissions(&dir, std::fs::Permissions::from_mode(0o755)).unwrap();
+            assert!(init.write_keystore(&account, b"password").is_err());
+            assert_eq!(std::fs::metadata(&dir).unwrap().permissions().mode() & 0o777, 0o755);
+        }
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_parse_rotate_key() {
+        let parse = |args: &[&str]| CLI::try_parse_from([&["snarkos", "validator", "rotate-key"][..], args].concat());
+
+        let cli = parse(&["--remote-signer", "https://signer:8080", "--jwt", "token"]).unwrap();
+        let Command::Validator(Validator::RotateKey(rotate_key)) = cli.command else { panic!("Unexpected command") };
+        assert_eq!(rotate_key.remote_signer.as_deref(), Some("https://signer:8080"));
+        assert_eq!(rotate_key.endpoint.url("validator/signer"), "http://127.0.0.1:3030/mainnet/admin/validator/signer");
+
+        // Ensure exactly one of the private key file or the remote signer is set.
+        assert!(parse(&["--jwt", "token"]).is_err());
+        assert!(parse(&["--private-key-file", "key", "--remote-signer", "https://signer", "--jwt", "token"]).is_err());
+    }
+
+    #[test]
+    fn test_parse_replay_dag() {
+        let cli = CLI::parse_from(["snarkos", "validator", "replay-dag", "--start", "10", "--end", "20", "--dev", "0"]);
+        let Command::Validator(Validator::ReplayDag(command)) = cli.command else { panic!("Unexpected command") };
+        assert_eq!((command.start, command.end, command.dev), (10, Some(20), Some(0)));
+    }
+
+    #[test]
+    fn test_parse_equivocations() {
+        let parse = |args: &[&str]| {
+            let cli = CLI::parse_from([&["snarkos", "validator", "equivocations"][..], args].concat());
+            let Command::Validator(Validator::Equivocations(command)) = cli.command else {
+                panic!("Unexpected command")
+            };
+            command
+        };
+        assert_eq!(parse(&[]).url(), "http://127.0.0.1:3030/mainnet/validators/equivocations");
+        assert_eq!(
+            parse(&["--author", "aleo1abc", "--endpoint", "http://node:3030/"]).url(),
+            "http://node:3030/mainnet/validators/equivocations?author=aleo1abc"
+        );
+    }
+}
@@ -14,15 +14,23 @@
 
 use crate::helpers::LogWriter;
 
+use anyhow::{bail, Result};
 use crossterm::tty::IsTty;
-use std::{fs::File, io, path::Path};
+use std::{fs::File, io, path::Path, sync::OnceLock};
 use tokio::sync::mpsc;
 use tracing_subscriber::{
     layer::{Layer, SubscriberExt},
+    reload,
     util::SubscriberInitExt,
     EnvFilter,
 };
 
+/// A function replacing one of the log filters of the node.
+type LogFilterReloader = Box<dyn Fn(EnvFilter) -> Result<()> + Send + Sync>;
+
+/// The functions replacing the log filters of the terminal and the logfile, once the logger is initialized.
+static LOG_FILTER_RELOADERS: OnceLock<[LogFilterReloader; 2]> = OnceLock::new();
+
 /// Initializes the logger.
 ///
 /// ```ignore
@@ -43,13 +51,7 @@ pub fn initialize_logger<P: AsRef<Path>>(verbosity: u8, nodisplay: bool, logfile
 
     // Filter out undesirable logs. (unfortunately EnvFilter cannot be cloned)
     let [filter, filter2] = std::array::from_fn(|_| {
-        let filter = EnvFilter::from_default_env()
-            .add_directive("mio=off".parse().unwrap())
-            .add_directive("tokio_util=off".parse().unwrap())
-            .add_directive("hyper=off".parse().unwrap())
-            .add_directive("reqwest=off".parse().unwrap())
-            .add_directive("want=off".parse().unwrap())
-            .add_directive("warp=off".parse().unwrap());
+        let filter = silence_dependencies(EnvFilter::from_default_env());
 
         let filter = if verbosity >= 2 {
             filter.add_directive("snarkos_node_sync=trace".parse().unwrap())
@@ -103,6 +105,15 @@ pub fn initialize_logger<P: AsRef<Path>>(verbosity: u8, nodisplay: bool, logfile
         false => Some(log_sender),
     };
 
+    // Make the filters reloadable at runtime.
+    let (filter, handle) = reload::Layer::new(filter);
+    let (filter2, handle2) = reload::Layer::new(filter2);
+    let reloaders: [LogFilterReloader; 2] = [
+        Box::new(move |filter: EnvFilter| Ok(handle.reload(filter)?)),
+        Box::new(move |filter: EnvFilter| Ok(handle2.reload(filter)?)),
+    ];
+    let _ = LOG_FILTER_RELOADERS.set(reloaders);
+
     // Initialize tracing.
     let _ = tracing_subscriber::registry()
         .with(
@@ -126,6 +137,26 @@ pub fn initialize_logger<P: AsRef<Path>>(verbosity: u8, nodisplay: bool, logfile
     log_receiver
 }
 
+/// Replaces the log filter of the node with the given directives (e.g. `info,snarkos_node_router=trace`).
+pub fn set_log_filter(directives: &str) -> Result<()> {
+    let Some(reloaders) = LOG_FILTER_RELOADERS.get() else { bail!("The logger is not initialized") };
+    for reloader in reloaders {
+        reloader(silence_dependencies(EnvFilter::try_new(directives)?))?;
+    }
+    Ok(())
+}
+
+/// Adds the directives filtering out the undesirable logs of the dependencies to the given filter.
+fn silence_dependencies(filter: EnvFilter) -> EnvFilter {
+    filter
+        .add_directive("mio=off".parse().unwrap())
+        .add_directive("tokio_util=off".parse().unwrap())
+        .add_directive("hyper=off".parse().unwrap())
+        .add_directive("reqwest=off".parse().unwrap())
+        .add_directive("want=off".parse().unwrap())
+        .add_directive("warp=off".parse().unwrap())
+}
+
 /// Returns the welcome message as a string.
 pub fn welcome_message() -> String {
     use colored::Colorize;
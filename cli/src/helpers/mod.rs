@@ -21,6 +21,9 @@ use log_writer::*;
 pub mod logger;
 pub use logger::*;
 
+mod password;
+pub use password::*;
+
 pub mod updater;
 pub use updater::*;
 
@@ -0,0 +1,67 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use anyhow::{bail, Result};
+use crossterm::{terminal, tty::IsTty};
+use std::io::{Read, Write};
+use zeroize::Zeroizing;
+
+/// The environment variable holding the password of the keystore, for nodes that run unattended.
+pub const KEYSTORE_PASSWORD_ENV: &str = "SNARKOS_KEYSTORE_PASSWORD";
+
+/// Returns the password of the keystore from the `SNARKOS_KEYSTORE_PASSWORD` environment variable, if it is set.
+pub fn keystore_password_from_env() -> Option<Zeroizing<String>> {
+    std::env::var(KEYSTORE_PASSWORD_ENV).ok().map(Zeroizing::new)
+}
+
+/// Prompts the user for a password on the terminal, without echoing it.
+pub fn read_password(prompt: &str) -> Result<Zeroizing<String>> {
+    if !std::io::stdin().is_tty() {
+        bail!("Unable to prompt for the password, set the '{KEYSTORE_PASSWORD_ENV}' environment variable instead")
+    }
+    eprint!("{prompt}: ");
+    std::io::stderr().flush()?;
+
+    // Read the password in raw mode, so that it is not echoed.
+    terminal::enable_raw_mode()?;
+    let password = read_line_raw();
+    terminal::disable_raw_mode()?;
+    eprintln!();
+    password
+}
+
+/// Reads a line from the terminal in raw mode, handling backspaces and interrupts.
+fn read_line_raw() -> Result<Zeroizing<String>> {
+    let mut bytes = Zeroizing::new(Vec::new());
+    for byte in std::io::stdin().bytes() {
+        match byte? {
+            b'\r' | b'\n' => break,
+            // Ctrl-C or Ctrl-D.
+            0x03 | 0x04 => bail!("The password prompt was interrupted"),
+            // Backspace, removing the last (possibly multi-byte) character.
+            0x08 | 0x7f => {
+                while let Some(byte) = bytes.pop() {
+                    if byte & 0xC0 != 0x80 {
+                        break;
+                    }
+                }
+            }
+            byte => bytes.push(byte),
+        }
+    }
+    match std::str::from_utf8(&bytes) {
+        Ok(password) => Ok(Zeroizing::new(password.to_string())),
+        Err(_) => bail!("The password is not valid UTF-8"),
+    }
+}
@@ -12,7 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::{fmt_id, spawn_blocking, LedgerService};
+use crate::{fmt_id, spawn_blocking, verify, LedgerService};
 use snarkvm::{
     ledger::{
         block::{Block, Transaction},
@@ -249,7 +249,7 @@ impl<N: Network, C: ConsensusStorage<N>> LedgerService<N> for CoreLedgerService<
         let proof_target = self.ledger.latest_proof_target();
 
         // Ensure that the prover solution is valid for the given epoch.
-        if !spawn_blocking!(solution.verify(&coinbase_verifying_key, &epoch_challenge, proof_target))? {
+        if !spawn_blocking!(verify(|| solution.verify(&coinbase_verifying_key, &epoch_challenge, proof_target)))? {
             bail!("Invalid prover solution '{puzzle_commitment}' for the current epoch.");
         }
         Ok(())
@@ -273,7 +273,7 @@ impl<N: Network, C: ConsensusStorage<N>> LedgerService<N> for CoreLedgerService<
         }
         // Check the transaction is well-formed.
         let ledger = self.ledger.clone();
-        spawn_blocking!(ledger.check_transaction_basic(&transaction, None, &mut rand::thread_rng()))
+        spawn_blocking!(verify(|| ledger.check_transaction_basic(&transaction, None, &mut rand::thread_rng())))
     }
 
     /// Checks the given block is valid next block.
@@ -37,6 +37,11 @@ pub mod translucent;
 #[cfg(feature = "translucent")]
 pub use translucent::*;
 
+#[cfg(feature = "ledger")]
+pub mod verification;
+#[cfg(feature = "ledger")]
+pub use verification::*;
+
 pub mod traits;
 pub use traits::*;
 
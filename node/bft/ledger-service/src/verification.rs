@@ -0,0 +1,94 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use snarkvm::prelude::{ensure, Result};
+
+use std::sync::OnceLock;
+
+/// The thread pool verifying the transmissions, if one was set.
+static VERIFICATION_POOL: OnceLock<rayon::ThreadPool> = OnceLock::new();
+
+/// Sets a dedicated thread pool with the given number of threads to verify the transmissions,
+/// instead of the global thread pool. If CPU cores are given, the threads are pinned to them (in round-robin order).
+/// Returns `false` if the thread pool was already set.
+pub fn set_verification_pool(num_threads: usize, cpu_cores: Vec<usize>) -> Result<bool> {
+    ensure!(num_threads > 0, "The verification thread pool must have at least one thread");
+    // Ensure the CPU cores exist on this machine.
+    if !cpu_cores.is_empty() {
+        let available = core_affinity::get_core_ids().unwrap_or_default();
+        for core in &cpu_cores {
+            ensure!(available.iter().any(|id| id.id == *core), "CPU core {core} is not available for pinning");
+        }
+    }
+    // Build the thread pool.
+    let pool = rayon::ThreadPoolBuilder::new()
+        .stack_size(8 * 1024 * 1024)
+        .num_threads(num_threads)
+        .thread_name(|index| format!("bft-verification-{index}"))
+        .start_handler(move |index| {
+            // Pin the thread to its CPU core, if any.
+            if !cpu_cores.is_empty() {
+                let core = cpu_cores[index % cpu_cores.len()];
+                if !core_affinity::set_for_current(core_affinity::CoreId { id: core }) {
+                    tracing::warn!("Failed to pin the verification thread {index} to CPU core {core}");
+                }
+            }
+        })
+        .build()?;
+    Ok(VERIFICATION_POOL.set(pool).is_ok())
+}
+
+/// Runs the given verification on the dedicated thread pool, if one was set, or on the current thread otherwise.
+/// Note: The parallel work spawned by the verification stays on the thread pool it runs on.
+pub(crate) fn verify<T: Send>(verification: impl FnOnce() -> T + Send) -> T {
+    match VERIFICATION_POOL.get() {
+        Some(pool) => pool.install(verification),
+        None => verification(),
+    }
+}
+
+/// Parses the given list of CPU cores (e.g. "0-3,8,10-11").
+pub fn parse_cpu_cores(cores: &str) -> Result<Vec<usize>> {
+    let mut parsed = Vec::new();
+    for range in cores.split(',').map(str::trim).filter(|range| !range.is_empty()) {
+        match range.split_once('-') {
+            Some((start, end)) => {
+                let (start, end) = (start.trim().parse::<usize>()?, end.trim().parse::<usize>()?);
+                ensure!(start <= end, "Invalid range of CPU cores '{range}'");
+                parsed.extend(start..=end);
+            }
+            None => parsed.push(range.parse()?),
+        }
+    }
+    Ok(parsed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_cpu_cores() {
+        assert_eq!(parse_cpu_cores("").unwrap(), Vec::<usize>::new());
+        assert_eq!(parse_cpu_cores("3").unwrap(), vec![3]);
+        assert_eq!(parse_cpu_cores("0-3, 8,10-11").unwrap(), vec![0, 1, 2, 3, 8, 10, 11]);
+        assert!(parse_cpu_cores("3-1").is_err());
+        assert!(parse_cpu_cores("a").is_err());
+    }
+
+    #[test]
+    fn test_verify_without_pool() {
+        assert_eq!(verify(|| 1 + 1), 2);
+    }
+}
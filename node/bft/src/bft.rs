@@ -16,8 +16,11 @@ use crate::{
     helpers::{
         fmt_id,
         init_bft_channels,
+        init_consensus_channels,
         now,
+        AuditEvent,
         BFTReceiver,
+        BftConfig,
         ConsensusSender,
         PrimaryReceiver,
         PrimarySender,
@@ -32,7 +35,8 @@ use snarkos_node_bft_ledger_service::LedgerService;
 use snarkvm::{
     console::account::Address,
     ledger::{
-        block::Transaction,
+        authority::Authority,
+        block::{Block, Transaction},
         coinbase::{ProverSolution, PuzzleCommitment},
         committee::Committee,
         narwhal::{BatchCertificate, Data, Subdag, Transmission, TransmissionID},
@@ -84,9 +88,10 @@ impl<N: Network> BFT<N> {
         ip: Option<SocketAddr>,
         trusted_validators: &[SocketAddr],
         dev: Option<u16>,
+        config: &BftConfig<N>,
     ) -> Result<Self> {
         Ok(Self {
-            primary: Primary::new(account, storage, ledger, ip, trusted_validators, dev)?,
+            primary: Primary::new(account, storage, ledger, ip, trusted_validators, dev, config)?,
             dag: Default::default(),
             leader_certificate: Default::default(),
             leader_certificate_timer: Default::default(),
@@ -246,6 +251,22 @@ impl<N: Network> BFT<N> {
             }
         }
 
+        // Record the outcome of the leader election, once the even round is complete.
+        #[cfg(feature = "metrics")]
+        if is_ready && current_round % 2 == 0 {
+            // Determine if the leader certificate was received for the current round.
+            let is_elected = self.leader_certificate.read().as_ref().map(|c| c.round()) == Some(current_round);
+            let outcome = match is_elected {
+                true => "elected",
+                false => "timeout",
+            };
+            metrics::increment_counter_with_labels(metrics::bft::LEADER_ELECTIONS, &[("outcome", outcome.to_string())]);
+            // Note: An even round only completes without a leader certificate once the leader timer expires.
+            if !is_elected {
+                metrics::increment_counter_with_labels(metrics::bft::TIMEOUTS, &[("kind", "leader".to_string())]);
+            }
+        }
+
         // If the BFT is ready, then update to the next round.
         if is_ready {
             // Update to the next round in storage.
@@ -537,6 +558,8 @@ impl<N: Network> BFT<N> {
         for leader_certificate in leader_certificates.into_iter().rev() {
             // Retrieve the leader certificate round.
             let leader_round = leader_certificate.round();
+            #[cfg(feature = "metrics")]
+            let leader_timestamp = leader_certificate.batch_header().timestamp();
             // Compute the commit subdag.
             let commit_subdag = match self.order_dag_with_dfs::<ALLOW_LEDGER_ACCESS>(leader_certificate) {
                 Ok(subdag) => subdag,
@@ -583,6 +606,8 @@ impl<N: Network> BFT<N> {
                 let num_transmissions = transmissions.len();
                 // Retrieve metadata about the subdag.
                 let subdag_metadata = subdag.iter().map(|(round, c)| (*round, c.len())).collect::<Vec<_>>();
+                // Prepare the audit event of the subdag.
+                let audit_event = AuditEvent::subdag_committed(&subdag, num_transmissions);
 
                 // Ensure the subdag anchor round matches the leader round.
                 ensure!(
@@ -613,6 +638,9 @@ impl<N: Network> BFT<N> {
                 info!(
                     "\n\nCommitting a subdag from round {anchor_round} with {num_transmissions} transmissions: {subdag_metadata:?}\n"
                 );
+                self.primary.audit(audit_event);
+                #[cfg(feature = "metrics")]
+                metrics::histogram(metrics::bft::COMMIT_LATENCY, now().saturating_sub(leader_timestamp) as f64);
                 // Update the DAG, as the subdag was successfully included into a block.
                 let mut dag_write = self.dag.write();
                 for certificate in commit_subdag.values().flatten() {
@@ -828,6 +856,58 @@ impl<N: Network> BFT<N> {
         }
     }
 
+    /// Replays the certificates of the given blocks through the DAG, in the same order as the block sync,
+    /// and returns the subdags committed by the BFT, in order.
+    ///
+    /// The DAG is first bootstrapped with the certificates of the given `preceding` blocks (i.e. the blocks
+    /// within the GC range before the replayed blocks), as at bootup.
+    /// Note: This is meant for an offline BFT (i.e. one that is not running), as it sets the consensus sender.
+    pub async fn replay(&self, preceding: &[Block<N>], blocks: &[Block<N>]) -> Result<Vec<Subdag<N>>> {
+        // Retrieve the certificates of the given blocks, in block order.
+        let certificates_of = |blocks: &[Block<N>]| -> Vec<BatchCertificate<N>> {
+            blocks
+                .iter()
+                .filter_map(|block| match block.authority() {
+                    Authority::Beacon(_) => None,
+                    Authority::Quorum(subdag) => Some(subdag.values().flatten().cloned().collect::<Vec<_>>()),
+                })
+                .flatten()
+                .collect()
+        };
+
+        // Bootstrap the DAG with the preceding blocks.
+        let leader_certificates = preceding
+            .iter()
+            .filter_map(|block| match block.authority() {
+                Authority::Beacon(_) => None,
+                Authority::Quorum(subdag) => Some(subdag.leader_certificate().clone()),
+            })
+            .collect();
+        self.sync_bft_dag_at_bootup(leader_certificates, certificates_of(preceding)).await;
+
+        // Collect the subdags sent to consensus.
+        let (consensus_sender, mut consensus_receiver) = init_consensus_channels();
+        if self.consensus_sender.set(consensus_sender).is_err() {
+            bail!("Cannot replay the DAG of a running BFT");
+        }
+        let subdags = Arc::new(Mutex::new(Vec::new()));
+        let subdags_ = subdags.clone();
+        self.spawn(async move {
+            while let Some((subdag, _, callback)) = consensus_receiver.rx_consensus_subdag.recv().await {
+                subdags_.lock().push(subdag);
+                callback.send(Ok(())).ok();
+            }
+        });
+
+        // Replay the certificates of the blocks.
+        for certificate in certificates_of(blocks) {
+            self.update_dag::<true>(certificate).await?;
+        }
+        // Return the committed subdags.
+        let subdags = std::mem::take(&mut *subdags.lock());
+        Ok(subdags)
+    }
+
     /// Spawns a task with the given future; it should only be used for long-running tasks.
     fn spawn<T: Future<Output = ()> + Send + 'static>(&self, future: T) {
         self.handles.lock().push(tokio::spawn(future));
@@ -901,7 +981,7 @@ mod tests {
         assert_eq!(storage.max_gc_rounds(), 10);
 
         // Initialize the BFT.
-        let bft = BFT::new(account, storage, ledger, None, &[], None)?;
+        let bft = BFT::new(account, storage, ledger, None, &[], None, &Default::default())?;
         assert!(bft.is_timer_expired()); // 0 + 5 < now()
 
         // Ensure this call succeeds on an odd round.
@@ -939,7 +1019,7 @@ mod tests {
         assert_eq!(storage.max_gc_rounds(), 10);
 
         // Initialize the BFT.
-        let bft = BFT::new(account, storage, ledger, None, &[], None)?;
+        let bft = BFT::new(account, storage, ledger, None, &[], None, &Default::default())?;
         assert!(bft.is_timer_expired()); // 0 + 5 < now()
 
         // Store is at round 1, and we are checking for round 2.
@@ -961,7 +1041,7 @@ mod tests {
         assert_eq!(storage.max_gc_rounds(), 10);
 
         // Initialize the BFT.
-        let bft = BFT::new(account, storage, ledger, None, &[], None)?;
+        let bft = BFT::new(account, storage, ledger, None, &[], None, &Default::default())?;
         assert!(bft.is_timer_expired()); // 0 + 5 < now()
 
         // Ensure this call fails on an even round.
@@ -982,7 +1062,7 @@ mod tests {
         assert_eq!(storage.max_gc_rounds(), 10);
 
         // Initialize the BFT.
-        let bft = BFT::new(account, storage, ledger, None, &[], None)?;
+        let bft = BFT::new(account, storage, ledger, None, &[], None, &Default::default())?;
 
         let result = bft.is_even_round_ready_for_next_round(IndexSet::new(), committee.clone(), 2);
         assert!(!result);
@@ -1007,7 +1087,7 @@ mod tests {
         assert_eq!(storage.max_gc_rounds(), 10);
 
         // Initialize the BFT.
-        let bft = BFT::new(account, storage, ledger, None, &[], None)?;
+        let bft = BFT::new(account, storage, ledger, None, &[], None, &Default::default())?;
 
         // Ensure this call fails on an odd round.
         let result = bft.update_leader_certificate_to_even_round(1);
@@ -1025,7 +1105,7 @@ mod tests {
         assert_eq!(storage.max_gc_rounds(), 10);
 
         // Initialize the BFT.
-        let bft = BFT::new(account, storage, ledger, None, &[], None)?;
+        let bft = BFT::new(account, storage, ledger, None, &[], None, &Default::default())?;
 
         // Ensure this call succeeds on an even round.
         let result = bft.update_leader_certificate_to_even_round(6);
@@ -1077,7 +1157,7 @@ mod tests {
 
         // Initialize the BFT.
         let account = Account::new(rng)?;
-        let bft = BFT::new(account, storage.clone(), ledger, None, &[], None)?;
+        let bft = BFT::new(account, storage.clone(), ledger, None, &[], None, &Default::default())?;
 
         // Set the leader certificate.
         *bft.leader_certificate.write() = Some(leader_certificate);
@@ -1115,7 +1195,7 @@ mod tests {
             // Initialize the storage.
             let storage = Storage::new(ledger.clone(), Arc::new(BFTMemoryService::new()), 1);
             // Initialize the BFT.
-            let bft = BFT::new(account.clone(), storage, ledger.clone(), None, &[], None)?;
+            let bft = BFT::new(account.clone(), storage, ledger.clone(), None, &[], None, &Default::default())?;
 
             // Insert a mock DAG in the BFT.
             *bft.dag.write() = crate::helpers::dag::test_helpers::mock_dag_with_modified_last_committed_round(3);
@@ -1145,7 +1225,7 @@ mod tests {
             // Initialize the storage.
             let storage = Storage::new(ledger.clone(), Arc::new(BFTMemoryService::new()), 1);
             // Initialize the BFT.
-            let bft = BFT::new(account, storage, ledger, None, &[], None)?;
+            let bft = BFT::new(account, storage, ledger, None, &[], None, &Default::default())?;
 
             // Insert a mock DAG in the BFT.
             *bft.dag.write() = crate::helpers::dag::test_helpers::mock_dag_with_modified_last_committed_round(2);
@@ -1203,7 +1283,7 @@ mod tests {
         /* Test missing previous certificate. */
 
         // Initialize the BFT.
-        let bft = BFT::new(account, storage, ledger, None, &[], None)?;
+        let bft = BFT::new(account, storage, ledger, None, &[], None, &Default::default())?;
 
         // The expected error message.
         let error_msg = format!(
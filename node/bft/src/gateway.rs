@@ -14,7 +14,16 @@
 
 use crate::{
     events::{EventCodec, PrimaryPing},
-    helpers::{assign_to_worker, Cache, PrimarySender, Resolver, SyncSender, WorkerSender},
+    helpers::{
+        assign_to_worker,
+        Cache,
+        LocalSigner,
+        PrimarySender,
+        Resolver,
+        Signer,
+        SyncSender,
+        WorkerSender,
+    },
     spawn_blocking,
     Worker,
     CONTEXT,
@@ -47,6 +56,7 @@ use snarkos_node_tcp::{
     Config,
     Connection,
     ConnectionSide,
+    MessagePriority,
     Tcp,
     P2P,
 };
@@ -100,6 +110,10 @@ pub trait Transport<N: Network>: Send + Sync {
 pub struct Gateway<N: Network> {
     /// The account of the node.
     account: Account<N>,
+    /// The signer of the handshakes (and of the batches, through the primary), on behalf of the validator.
+    signer: Arc<RwLock<Arc<dyn Signer<N>>>>,
+    /// The signer scheduled to replace the current signer, and the round from which it is active, if any.
+    pending_signer: Arc<Mutex<Option<(u64, Arc<dyn Signer<N>>)>>>,
     /// The ledger service.
     ledger: Arc<dyn LedgerService<N>>,
     /// The TCP stack.
@@ -110,6 +124,8 @@ pub struct Gateway<N: Network> {
     resolver: Arc<Resolver<N>>,
     /// The set of trusted validators.
     trusted_validators: IndexSet<SocketAddr>,
+    /// The external address advertised to the peers instead of the listener address, if set.
+    advertised_addr: Arc<RwLock<Option<SocketAddr>>>,
     /// The map of connected peer IPs to their peer handlers.
     connected_peers: Arc<RwLock<IndexSet<SocketAddr>>>,
     /// The set of handshaking peers. While `Tcp` already recognizes the connecting IP addresses
@@ -137,6 +153,7 @@ impl<N: Network> Gateway<N> {
         ip: Option<SocketAddr>,
         trusted_validators: &[SocketAddr],
         dev: Option<u16>,
+        signer: Option<Arc<dyn Signer<N>>>,
     ) -> Result<Self> {
         // Initialize the gateway IP.
         let ip = match (ip, dev) {
@@ -146,14 +163,19 @@ impl<N: Network> Gateway<N> {
         };
         // Initialize the TCP stack.
         let tcp = Tcp::new(Config::new(ip, Committee::<N>::MAX_COMMITTEE_SIZE));
+        // Initialize the signer, using the account of the node unless another signer is given.
+        let signer = signer.unwrap_or_else(|| Arc::new(LocalSigner::new(account.clone())));
         // Return the gateway.
         Ok(Self {
             account,
+            signer: Arc::new(RwLock::new(signer)),
+            pending_signer: Default::default(),
             ledger,
             tcp,
             cache: Default::default(),
             resolver: Default::default(),
             trusted_validators: trusted_validators.iter().copied().collect(),
+            advertised_addr: Default::default(),
             connected_peers: Default::default(),
             connecting_peers: Default::default(),
             primary_sender: Default::default(),
@@ -253,18 +275,87 @@ impl<N: Network> CommunicationService for Gateway<N> {
 
 impl<N: Network> Gateway<N> {
     /// Returns the account of the node.
+    /// Note: If a remote signer is set, the BFT signs on behalf of the address of the signer instead.
     pub const fn account(&self) -> &Account<N> {
         &self.account
     }
 
+    /// Returns the signer of the validator.
+    pub fn signer(&self) -> Arc<dyn Signer<N>> {
+        self.signer.read().clone()
+    }
+
+    /// Returns the address of the validator.
+    pub fn address(&self) -> Address<N> {
+        self.signer.read().address()
+    }
+
+    /// Schedules the rotation to the given signer, which becomes active from the given round.
+    /// Note: The address of the signer must be an authorized validator.
+    pub fn rotate_signer(&self, signer: Arc<dyn Signer<N>>, round: u64) -> Result<()> {
+        ensure!(
+            self.is_authorized_validator_address(signer.address()),
+            "The address '{}' of the new signer is not a member of the committee",
+            signer.address()
+        );
+        info!("Scheduled the rotation of the signer to '{}' at round {round}", signer.address());
+        *self.pending_signer.lock() = Some((round, signer));
+        Ok(())
+    }
+
+    /// Activates the pending signer, if the given round reached its rotation round.
+    /// Returns `true` if the signer was rotated.
+    pub fn update_signer(&self, round: u64) -> bool {
+        // Retrieve the pending signer, if it is due.
+        let signer = {
+            let mut pending_signer = self.pending_signer.lock();
+            match &*pending_signer {
+                Some((rotation_round, _)) if *rotation_round <= round => pending_signer.take().map(|(_, s)| s),
+                _ => None,
+            }
+        };
+        let Some(signer) = signer else {
+            return false;
+        };
+        // Replace the signer.
+        let previous_signer = std::mem::replace(&mut *self.signer.write(), signer.clone());
+        info!("Rotated the signer from '{}' to '{}' at round {round}", previous_signer.address(), signer.address());
+        // If the address changed, reconnect to the validators, so that they authenticate the new address.
+        if previous_signer.address() != signer.address() {
+            let connected_peers = self.connected_peers.read().clone();
+            for peer_ip in connected_peers {
+                self.disconnect(peer_ip);
+            }
+        }
+        true
+    }
+
     /// Returns the IP address of this node.
     pub fn local_ip(&self) -> SocketAddr {
         self.tcp.listening_addr().expect("The TCP listener is not enabled")
     }
 
+    /// Returns the external address advertised to the peers instead of the listener address, if set.
+    pub fn advertised_addr(&self) -> Option<SocketAddr> {
+        *self.advertised_addr.read()
+    }
+
+    /// Advertises the given external address (e.g. of a public interface, or of a port forwarded by a NAT)
+    /// to the peers that connect from now on, instead of the listener address.
+    /// Note: Only the port is advertised in the handshake, as the peers derive the IP from the connection.
+    pub fn set_advertised_addr(&self, addr: SocketAddr) {
+        *self.advertised_addr.write() = Some(addr);
+    }
+
+    /// Returns the listener port advertised to the peers.
+    fn advertised_port(&self) -> u16 {
+        self.advertised_addr().unwrap_or_else(|| self.local_ip()).port()
+    }
+
     /// Returns `true` if the given IP is this node.
     pub fn is_local_ip(&self, ip: SocketAddr) -> bool {
         ip == self.local_ip()
+            || Some(ip) == self.advertised_addr()
             || (ip.ip().is_unspecified() || ip.ip().is_loopback()) && ip.port() == self.local_ip().port()
     }
 
@@ -744,7 +835,7 @@ impl<N: Network> Gateway<N> {
                             }
 
                             // Ensure the validator address is not this node.
-                            if self_.account.address() == validator_address {
+                            if self_.address() == validator_address {
                                 continue;
                             }
                             // Ensure the validator IP is not already connected or connecting.
@@ -1048,6 +1139,21 @@ impl<N: Network> Writing for Gateway<N> {
     fn codec(&self, _peer_addr: SocketAddr, _side: ConnectionSide) -> Self::Codec {
         Default::default()
     }
+
+    /// Returns the priority of the given outbound message, so that the batches and certificates
+    /// preempt the transmissions and the sync responses whenever a connection is backed up.
+    fn message_priority(&self, event: &Self::Message) -> MessagePriority {
+        match event {
+            Event::BatchPropose(..)
+            | Event::BatchSignature(..)
+            | Event::BatchCertified(..)
+            | Event::CertificateRequest(..)
+            | Event::CertificateResponse(..)
+            | Event::Disconnect(..) => MessagePriority::High,
+            Event::BlockResponse(..) | Event::TransmissionResponse(..) => MessagePriority::Low,
+            _ => MessagePriority::Normal,
+        }
+    }
 }
 
 #[async_trait]
@@ -1170,7 +1276,7 @@ impl<N: Network> Gateway<N> {
         // Sample a random nonce.
         let our_nonce = rng.gen();
         // Send a challenge request to the peer.
-        let our_request = ChallengeRequest::new(self.local_ip().port(), self.account.address(), our_nonce);
+        let our_request = ChallengeRequest::new(self.advertised_port(), self.address(), our_nonce);
         send_event(&mut framed, peer_addr, Event::ChallengeRequest(our_request)).await?;
 
         /* Step 2: Receive the peer's challenge response followed by the challenge request. */
@@ -1198,7 +1304,7 @@ impl<N: Network> Gateway<N> {
         // Sign the counterparty nonce.
         let response_nonce: u64 = rng.gen();
         let data = [peer_request.nonce.to_le_bytes(), response_nonce.to_le_bytes()].concat();
-        let Ok(our_signature) = self.account.sign_bytes(&data, rng) else {
+        let Ok(our_signature) = self.signer().sign_bytes(&data).await else {
             return Err(error(format!("Failed to sign the challenge request nonce from '{peer_addr}'")));
         };
         // Send the challenge response.
@@ -1227,7 +1333,7 @@ impl<N: Network> Gateway<N> {
         let peer_request = expect_event!(Event::ChallengeRequest, framed, peer_addr);
 
         // Ensure the address is not the same as this node.
-        if self.account.address() == peer_request.address {
+        if self.address() == peer_request.address {
             return Err(error("Skipping request to connect to self".to_string()));
         }
 
@@ -1253,7 +1359,7 @@ impl<N: Network> Gateway<N> {
         // Sign the counterparty nonce.
         let response_nonce: u64 = rng.gen();
         let data = [peer_request.nonce.to_le_bytes(), response_nonce.to_le_bytes()].concat();
-        let Ok(our_signature) = self.account.sign_bytes(&data, rng) else {
+        let Ok(our_signature) = self.signer().sign_bytes(&data).await else {
             return Err(error(format!("Failed to sign the challenge request nonce from '{peer_addr}'")));
         };
         // Send the challenge response.
@@ -1263,7 +1369,7 @@ impl<N: Network> Gateway<N> {
         // Sample a random nonce.
         let our_nonce = rng.gen();
         // Send the challenge request.
-        let our_request = ChallengeRequest::new(self.local_ip().port(), self.account.address(), our_nonce);
+        let our_request = ChallengeRequest::new(self.advertised_port(), self.address(), our_nonce);
         send_event(&mut framed, peer_addr, Event::ChallengeRequest(our_request)).await?;
 
         /* Step 3: Receive the challenge response. */
@@ -1367,7 +1473,7 @@ mod prop_tests {
     impl Debug for Gateway<CurrentNetwork> {
         fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
             // TODO implement Debug properly and move it over to production code
-            f.debug_tuple("Gateway").field(&self.account.address()).field(&self.tcp.config()).finish()
+            f.debug_tuple("Gateway").field(&self.address()).field(&self.tcp.config()).finish()
         }
     }
 
@@ -1406,6 +1512,7 @@ mod prop_tests {
                         address.ip(),
                         &[],
                         address.port(),
+                        None,
                     )
                     .unwrap()
                 })
@@ -1450,7 +1557,7 @@ mod prop_tests {
         let (storage, _, private_key, dev) = input;
         let account = Account::try_from(private_key).unwrap();
 
-        let gateway = Gateway::new(account.clone(), storage.ledger().clone(), dev.ip(), &[], dev.port()).unwrap();
+        let gateway = Gateway::new(account.clone(), storage.ledger().clone(), dev.ip(), &[], dev.port(), None).unwrap();
         let tcp_config = gateway.tcp().config();
         assert_eq!(tcp_config.listener_ip, Some(IpAddr::V4(Ipv4Addr::LOCALHOST)));
         assert_eq!(tcp_config.desired_listening_port, Some(MEMORY_POOL_PORT + dev.port().unwrap()));
@@ -1465,7 +1572,7 @@ mod prop_tests {
         let (storage, _, private_key, dev) = input;
         let account = Account::try_from(private_key).unwrap();
 
-        let gateway = Gateway::new(account.clone(), storage.ledger().clone(), dev.ip(), &[], dev.port()).unwrap();
+        let gateway = Gateway::new(account.clone(), storage.ledger().clone(), dev.ip(), &[], dev.port(), None).unwrap();
         let tcp_config = gateway.tcp().config();
         if let Some(socket_addr) = dev.ip() {
             assert_eq!(tcp_config.listener_ip, Some(socket_addr.ip()));
@@ -1490,7 +1597,7 @@ mod prop_tests {
         let worker_storage = storage.clone();
         let account = Account::try_from(private_key).unwrap();
 
-        let gateway = Gateway::new(account, storage.ledger().clone(), dev.ip(), &[], dev.port()).unwrap();
+        let gateway = Gateway::new(account, storage.ledger().clone(), dev.ip(), &[], dev.port(), None).unwrap();
 
         let (primary_sender, _) = init_primary_channels();
 
@@ -1505,9 +1612,15 @@ mod prop_tests {
                 let (tx_worker, rx_worker) = init_worker_channels();
                 // Construct the worker instance.
                 let ledger = Arc::new(MockLedgerService::new(committee.clone()));
-                let worker =
-                    Worker::new(id, Arc::new(gateway.clone()), worker_storage.clone(), ledger, Default::default())
-                        .unwrap();
+                let worker = Worker::new(
+                    id,
+                    Arc::new(gateway.clone()),
+                    worker_storage.clone(),
+                    ledger,
+                    Default::default(),
+                    Default::default(),
+                )
+                .unwrap();
                 // Run the worker instance.
                 worker.run(rx_worker);
 
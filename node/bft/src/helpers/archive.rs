@@ -0,0 +1,101 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use snarkvm::{
+    ledger::narwhal::BatchCertificate,
+    prelude::{FromBytes, Network, Result, ToBytes},
+};
+
+use std::{fs, io::ErrorKind, path::PathBuf};
+
+/// An on-disk archive of the certificates that were garbage collected from the BFT storage.
+///
+/// Each certificate is written to `{round}/{certificate ID}` under the archive directory.
+/// Note: The transmissions are not archived, as the committed ones are already persisted in the ledger.
+#[derive(Clone, Debug)]
+pub struct CertificateArchive {
+    /// The directory of the archive.
+    path: PathBuf,
+}
+
+impl CertificateArchive {
+    /// Opens the archive at the given directory, creating it if it does not exist.
+    pub fn open(path: impl Into<PathBuf>) -> Result<Self> {
+        let path = path.into();
+        fs::create_dir_all(&path)?;
+        Ok(Self { path })
+    }
+
+    /// Returns the directory of the archive.
+    pub fn path(&self) -> &PathBuf {
+        &self.path
+    }
+
+    /// Persists the given certificate to the archive.
+    pub fn insert<N: Network>(&self, certificate: &BatchCertificate<N>) -> Result<()> {
+        let round_path = self.path.join(certificate.round().to_string());
+        fs::create_dir_all(&round_path)?;
+        // Write the certificate to a temporary file first, so that a partially-written certificate is never archived.
+        let certificate_path = round_path.join(certificate.id().to_string());
+        let mut temp_path = certificate_path.clone().into_os_string();
+        temp_path.push(".tmp");
+        fs::write(&temp_path, certificate.to_bytes_le()?)?;
+        fs::rename(temp_path, certificate_path)?;
+        Ok(())
+    }
+
+    /// Returns the archived certificates for the given round.
+    pub fn get_certificates_for_round<N: Network>(&self, round: u64) -> Result<Vec<BatchCertificate<N>>> {
+        let entries = match fs::read_dir(self.path.join(round.to_string())) {
+            Ok(entries) => entries,
+            Err(error) if error.kind() == ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(error) => return Err(error.into()),
+        };
+        let mut certificates = Vec::new();
+        for entry in entries {
+            let path = entry?.path();
+            // Skip the temporary files.
+            if path.extension().is_some() {
+                continue;
+            }
+            certificates.push(BatchCertificate::from_bytes_le(&fs::read(path)?)?);
+        }
+        Ok(certificates)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type CurrentNetwork = snarkvm::prelude::MainnetV0;
+
+    #[test]
+    fn test_certificate_archive() {
+        let rng = &mut snarkvm::prelude::TestRng::default();
+        let path = std::env::temp_dir().join(format!("snarkos-certificate-archive-{}", std::process::id()));
+
+        let archive = CertificateArchive::open(&path).unwrap();
+        assert!(archive.get_certificates_for_round::<CurrentNetwork>(1).unwrap().is_empty());
+
+        // Archive a certificate, and ensure it is read back for its round only.
+        let certificate =
+            snarkvm::ledger::narwhal::batch_certificate::test_helpers::sample_batch_certificate_for_round(1, rng);
+        archive.insert::<CurrentNetwork>(&certificate).unwrap();
+        assert_eq!(archive.get_certificates_for_round::<CurrentNetwork>(1).unwrap(), vec![certificate]);
+        assert!(archive.get_certificates_for_round::<CurrentNetwork>(2).unwrap().is_empty());
+
+        fs::remove_dir_all(path).unwrap();
+    }
+}
@@ -0,0 +1,151 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::helpers::now;
+use snarkvm::{
+    console::{account::Address, network::Network, types::Field},
+    ledger::narwhal::{BatchCertificate, BatchHeader, Subdag},
+    prelude::{Result, Signature},
+};
+
+use parking_lot::Mutex;
+use serde::Serialize;
+use std::{
+    fs::{self, File, OpenOptions},
+    io::Write,
+    net::SocketAddr,
+    path::Path,
+};
+
+/// An event recorded in the consensus audit log.
+#[derive(Clone, Debug, Serialize)]
+#[serde(bound = "", rename_all = "snake_case", tag = "event")]
+pub enum AuditEvent<N: Network> {
+    /// A batch proposal was received from a peer.
+    ProposalReceived { peer_ip: SocketAddr, author: Address<N>, round: u64, batch_id: Field<N> },
+    /// A batch (of this validator or of a peer) was signed by this validator.
+    SignatureIssued { author: Address<N>, round: u64, batch_id: Field<N>, signature: Signature<N> },
+    /// The batch of this validator was certified.
+    CertificateFormed { round: u64, batch_id: Field<N>, certificate_id: Field<N>, num_signatures: usize },
+    /// A subdag was committed.
+    SubdagCommitted { anchor_round: u64, certificate_ids: Vec<Field<N>>, num_transmissions: usize },
+}
+
+impl<N: Network> AuditEvent<N> {
+    /// Returns the event for the given batch proposal, received from the given peer.
+    pub fn proposal_received(peer_ip: SocketAddr, batch_header: &BatchHeader<N>) -> Self {
+        let (author, round, batch_id) = (batch_header.author(), batch_header.round(), batch_header.batch_id());
+        Self::ProposalReceived { peer_ip, author, round, batch_id }
+    }
+
+    /// Returns the event for the given signature of the given batch.
+    pub fn signature_issued(batch_header: &BatchHeader<N>, signature: Signature<N>) -> Self {
+        let (author, round, batch_id) = (batch_header.author(), batch_header.round(), batch_header.batch_id());
+        Self::SignatureIssued { author, round, batch_id, signature }
+    }
+
+    /// Returns the event for the given certificate of this validator.
+    pub fn certificate_formed(certificate: &BatchCertificate<N>) -> Self {
+        Self::CertificateFormed {
+            round: certificate.round(),
+            batch_id: certificate.batch_id(),
+            certificate_id: certificate.id(),
+            num_signatures: certificate.signatures().count(),
+        }
+    }
+
+    /// Returns the event for the given subdag, committed with the given number of transmissions.
+    pub fn subdag_committed(subdag: &Subdag<N>, num_transmissions: usize) -> Self {
+        Self::SubdagCommitted {
+            anchor_round: subdag.anchor_round(),
+            certificate_ids: subdag.values().flatten().map(|certificate| certificate.id()).collect(),
+            num_transmissions,
+        }
+    }
+}
+
+/// A record of the consensus audit log.
+#[derive(Serialize)]
+#[serde(bound = "")]
+struct AuditRecord<'a, N: Network> {
+    /// The UNIX timestamp (in seconds) of the event.
+    timestamp: i64,
+    /// The event.
+    #[serde(flatten)]
+    event: &'a AuditEvent<N>,
+}
+
+/// An append-only log of the consensus events of the validator, with one JSON record per line.
+/// Note: The records are written as they happen, but are not synced to disk individually.
+pub struct AuditLog {
+    /// The audit log file.
+    file: Mutex<File>,
+}
+
+impl AuditLog {
+    /// Opens the audit log at the given path, appending to it if it already exists.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        if let Some(dir) = path.as_ref().parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        Ok(Self { file: Mutex::new(file) })
+    }
+
+    /// Appends the given event to the audit log.
+    pub fn record<N: Network>(&self, event: &AuditEvent<N>) -> Result<()> {
+        let mut line = serde_json::to_vec(&AuditRecord { timestamp: now(), event })?;
+        line.push(b'\n');
+        // Note: The record is written at once, so the records are never interleaved.
+        self.file.lock().write_all(&line)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkvm::prelude::{PrivateKey, TestRng};
+
+    type CurrentNetwork = snarkvm::prelude::MainnetV0;
+
+    #[test]
+    fn test_audit_log() {
+        let rng = &mut TestRng::default();
+        let path = std::env::temp_dir().join(format!("snarkos-audit-{}.jsonl", std::process::id()));
+        let private_key = PrivateKey::<CurrentNetwork>::new(rng).unwrap();
+        let batch_header =
+            BatchHeader::new(&private_key, 2, now(), Default::default(), Default::default(), rng).unwrap();
+        let peer_ip = "127.0.0.1:5000".parse().unwrap();
+
+        // Record the events, reopening the audit log in between.
+        let audit_log = AuditLog::open(&path).unwrap();
+        audit_log.record(&AuditEvent::proposal_received(peer_ip, &batch_header)).unwrap();
+        drop(audit_log);
+        let audit_log = AuditLog::open(&path).unwrap();
+        audit_log.record(&AuditEvent::signature_issued(&batch_header, *batch_header.signature())).unwrap();
+
+        // Ensure the events are appended, in order.
+        let records = fs::read_to_string(&path).unwrap();
+        let records: Vec<serde_json::Value> = records.lines().map(|line| serde_json::from_str(line).unwrap()).collect();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0]["event"], "proposal_received");
+        assert_eq!(records[0]["peer_ip"], "127.0.0.1:5000");
+        assert_eq!(records[1]["event"], "signature_issued");
+        assert_eq!(records[1]["round"], 2);
+        assert!(records.iter().all(|record| record["timestamp"].is_i64()));
+
+        fs::remove_file(path).unwrap();
+    }
+}
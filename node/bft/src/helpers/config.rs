@@ -0,0 +1,75 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::{
+    helpers::{AdmissionLimits, AuditLog, MempoolLimits, ProposalLimits, Signer},
+    DEFAULT_WORKERS,
+    MAX_WORKERS,
+};
+use snarkos_node_sync::MAX_BLOCKS_BEHIND;
+use snarkvm::console::prelude::*;
+
+use std::{path::PathBuf, sync::Arc};
+
+/// The configuration of the BFT, as set by the operator of the validator.
+#[derive(Clone)]
+pub struct BftConfig<N: Network> {
+    /// The number of rounds to retain in the storage, if more than the maximum number of GC rounds.
+    pub pruning_rounds: Option<u64>,
+    /// The directory to archive the certificates that are garbage collected from the storage, if any.
+    pub archive: Option<PathBuf>,
+    /// The limits of the batches proposed by the primary.
+    pub proposal_limits: ProposalLimits,
+    /// The number of workers spawned by the primary.
+    pub num_workers: u8,
+    /// The maximum number of blocks the ledger may be behind the network tip, before the primary
+    /// stops signing and proposing batches.
+    pub max_blocks_behind: u32,
+    /// The limits of the memory pool of each worker.
+    pub mempool_limits: MempoolLimits,
+    /// The limits of the admission lanes of the unconfirmed transactions.
+    pub admission_limits: AdmissionLimits,
+    /// The log to append the consensus events to, if any.
+    pub audit_log: Option<Arc<AuditLog>>,
+    /// The signer of the batches and handshakes, if not the account of the validator.
+    pub signer: Option<Arc<dyn Signer<N>>>,
+}
+
+impl<N: Network> Default for BftConfig<N> {
+    fn default() -> Self {
+        Self {
+            pruning_rounds: None,
+            archive: None,
+            proposal_limits: ProposalLimits::max::<N>(),
+            num_workers: DEFAULT_WORKERS,
+            max_blocks_behind: MAX_BLOCKS_BEHIND,
+            mempool_limits: Default::default(),
+            admission_limits: Default::default(),
+            audit_log: None,
+            signer: None,
+        }
+    }
+}
+
+impl<N: Network> BftConfig<N> {
+    /// Ensures the configuration is within the limits enforced by the network.
+    pub fn check(&self) -> Result<()> {
+        self.proposal_limits.check::<N>()?;
+        ensure!(
+            (1..=MAX_WORKERS).contains(&self.num_workers),
+            "The number of workers must be between 1 and {MAX_WORKERS}"
+        );
+        self.admission_limits.check()
+    }
+}
@@ -0,0 +1,213 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::helpers::now;
+use snarkvm::{
+    console::{account::Address, network::Network},
+    ledger::narwhal::{BatchCertificate, BatchHeader},
+    prelude::{ensure, Result},
+};
+
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use std::{
+    fs::{self, OpenOptions},
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+/// The name of the equivocation log file.
+pub const EQUIVOCATIONS_FILE: &str = "equivocations.jsonl";
+
+/// The evidence that a validator equivocated, i.e. signed two conflicting batches for the same round.
+/// Note: Every batch header is signed by its author, so the evidence can be verified by anyone.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(bound = "", rename_all = "snake_case", tag = "type")]
+pub enum EquivocationEvidence<N: Network> {
+    /// Two batch proposals from the same author for the same round.
+    Proposals { first: BatchHeader<N>, second: BatchHeader<N> },
+    /// Two batch certificates from the same author for the same round.
+    Certificates { first: BatchCertificate<N>, second: BatchCertificate<N> },
+}
+
+impl<N: Network> EquivocationEvidence<N> {
+    /// Returns the conflicting batch headers.
+    fn batch_headers(&self) -> (&BatchHeader<N>, &BatchHeader<N>) {
+        match self {
+            Self::Proposals { first, second } => (first, second),
+            Self::Certificates { first, second } => (first.batch_header(), second.batch_header()),
+        }
+    }
+
+    /// Returns the author of the conflicting batches.
+    pub fn author(&self) -> Address<N> {
+        self.batch_headers().0.author()
+    }
+
+    /// Returns the round of the conflicting batches.
+    pub fn round(&self) -> u64 {
+        self.batch_headers().0.round()
+    }
+
+    /// Ensures the batches are from the same author, for the same round, and are different.
+    pub fn check(&self) -> Result<()> {
+        let (first, second) = self.batch_headers();
+        ensure!(first.author() == second.author(), "The batches are from different authors");
+        ensure!(first.round() == second.round(), "The batches are for different rounds");
+        ensure!(first.batch_id() != second.batch_id(), "The batches are identical");
+        Ok(())
+    }
+}
+
+/// An equivocation observed by the node.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct Equivocation<N: Network> {
+    /// The author of the conflicting batches.
+    pub author: Address<N>,
+    /// The round of the conflicting batches.
+    pub round: u64,
+    /// The UNIX timestamp (in seconds) at which the equivocation was observed.
+    pub observed_at: i64,
+    /// The evidence of the equivocation.
+    pub evidence: EquivocationEvidence<N>,
+}
+
+/// A log of the equivocations observed by the node, which is appended to the equivocation log file, if it is enabled.
+/// Note: At most one equivocation of each kind is recorded per author and round.
+#[derive(Debug)]
+pub struct EquivocationLog<N: Network> {
+    /// The path of the equivocation log file, if it is enabled.
+    path: RwLock<Option<PathBuf>>,
+    /// The recorded equivocations, from the oldest to the most recent.
+    equivocations: RwLock<Vec<Equivocation<N>>>,
+}
+
+impl<N: Network> Default for EquivocationLog<N> {
+    fn default() -> Self {
+        Self { path: Default::default(), equivocations: Default::default() }
+    }
+}
+
+impl<N: Network> EquivocationLog<N> {
+    /// Opens the equivocation log file at the given path, loading the recorded equivocations (if any),
+    /// and appending the equivocations recorded from now on to it.
+    pub fn open<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let path = path.as_ref().to_path_buf();
+        if path.exists() {
+            let mut equivocations = Vec::new();
+            for line in fs::read_to_string(&path)?.lines().filter(|line| !line.trim().is_empty()) {
+                equivocations.push(serde_json::from_str(line)?);
+            }
+            *self.equivocations.write() = equivocations;
+        }
+        *self.path.write() = Some(path);
+        Ok(())
+    }
+
+    /// Records the given evidence, returning `true` if it is a new equivocation.
+    pub fn record(&self, evidence: EquivocationEvidence<N>) -> Result<bool> {
+        evidence.check()?;
+        let equivocation =
+            Equivocation { author: evidence.author(), round: evidence.round(), observed_at: now(), evidence };
+        {
+            let mut equivocations = self.equivocations.write();
+            // Skip the equivocation if it was already recorded.
+            let is_recorded = equivocations.iter().any(|recorded| {
+                recorded.author == equivocation.author
+                    && recorded.round == equivocation.round
+                    && std::mem::discriminant(&recorded.evidence) == std::mem::discriminant(&equivocation.evidence)
+            });
+            if is_recorded {
+                return Ok(false);
+            }
+            equivocations.push(equivocation.clone());
+        }
+        // Append the equivocation to the log file, if it is enabled.
+        if let Some(path) = self.path.read().as_ref() {
+            if let Some(dir) = path.parent() {
+                fs::create_dir_all(dir)?;
+            }
+            let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+            writeln!(file, "{}", serde_json::to_string(&equivocation)?)?;
+        }
+        Ok(true)
+    }
+
+    /// Returns the recorded equivocations (of the given author, if any), from the oldest to the most recent.
+    pub fn equivocations(&self, author: Option<Address<N>>) -> Vec<Equivocation<N>> {
+        self.equivocations
+            .read()
+            .iter()
+            .filter(|equivocation| author.map_or(true, |author| equivocation.author == author))
+            .cloned()
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkvm::prelude::{PrivateKey, TestRng};
+
+    type CurrentNetwork = snarkvm::prelude::MainnetV0;
+
+    /// Samples a batch header from the given author, for the given round and timestamp.
+    fn sample_batch_header(
+        private_key: &PrivateKey<CurrentNetwork>,
+        round: u64,
+        timestamp: i64,
+        rng: &mut TestRng,
+    ) -> BatchHeader<CurrentNetwork> {
+        BatchHeader::new(private_key, round, timestamp, Default::default(), Default::default(), rng).unwrap()
+    }
+
+    #[test]
+    fn test_equivocation_log() {
+        let rng = &mut TestRng::default();
+        let path = std::env::temp_dir().join(format!("snarkos-equivocations-{}.jsonl", std::process::id()));
+        let (alice, bob) = (PrivateKey::new(rng).unwrap(), PrivateKey::new(rng).unwrap());
+
+        let log = EquivocationLog::<CurrentNetwork>::default();
+        log.open(&path).unwrap();
+
+        // Ensure the batches from different authors, for different rounds, or that are identical are rejected.
+        let first = sample_batch_header(&alice, 2, now(), rng);
+        let invalid =
+            [sample_batch_header(&bob, 2, now(), rng), sample_batch_header(&alice, 4, now(), rng), first.clone()];
+        for second in invalid {
+            assert!(log.record(EquivocationEvidence::Proposals { first: first.clone(), second }).is_err());
+        }
+        assert!(log.equivocations(None).is_empty());
+
+        // Ensure conflicting batches are recorded once.
+        let second = sample_batch_header(&alice, 2, now() + 1, rng);
+        let evidence = EquivocationEvidence::Proposals { first, second };
+        assert!(log.record(evidence.clone()).unwrap());
+        assert!(!log.record(evidence.clone()).unwrap());
+        let equivocations = log.equivocations(None);
+        assert_eq!(equivocations.len(), 1);
+        assert_eq!(equivocations[0].round, 2);
+        assert_eq!(equivocations[0].evidence, evidence);
+        assert_eq!(log.equivocations(Some(evidence.author())).len(), 1);
+        assert!(log.equivocations(Some(Address::try_from(&bob).unwrap())).is_empty());
+
+        // Ensure the equivocations are loaded back from the log file.
+        let reopened = EquivocationLog::<CurrentNetwork>::default();
+        reopened.open(&path).unwrap();
+        assert_eq!(reopened.equivocations(None), equivocations);
+
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,219 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use snarkvm::{console::prelude::*, ledger::block::Transaction};
+
+use std::{fmt, sync::Arc};
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+/// The default maximum number of deployments admitted for verification at once.
+pub const DEFAULT_DEPLOYMENT_LANE_CAPACITY: usize = 16;
+/// The default maximum number of executions admitted for verification at once.
+pub const DEFAULT_EXECUTION_LANE_CAPACITY: usize = 1024;
+/// The default maximum number of deployments verified concurrently.
+pub const DEFAULT_DEPLOYMENT_LANE_CONCURRENCY: usize = 2;
+/// The default maximum number of executions verified concurrently.
+pub const DEFAULT_EXECUTION_LANE_CONCURRENCY: usize = 64;
+
+/// The admission lane of an unconfirmed transaction, so that the (large and slow to verify) deployments
+/// cannot starve the executions during verification, and vice versa.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Lane {
+    /// The lane of the deployment transactions.
+    Deployment,
+    /// The lane of the execution (and fee) transactions.
+    Execution,
+}
+
+impl Lane {
+    /// Returns the admission lane of the given transaction.
+    pub fn of<N: Network>(transaction: &Transaction<N>) -> Self {
+        match transaction.is_deploy() {
+            true => Self::Deployment,
+            false => Self::Execution,
+        }
+    }
+}
+
+impl fmt::Display for Lane {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Deployment => write!(f, "deployment"),
+            Self::Execution => write!(f, "execution"),
+        }
+    }
+}
+
+/// The limits of an admission lane.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct LaneLimits {
+    /// The maximum number of transactions admitted at once (i.e. waiting for or undergoing verification).
+    pub capacity: usize,
+    /// The maximum number of transactions verified concurrently.
+    pub concurrency: usize,
+}
+
+/// The limits of the admission lanes of the unconfirmed transactions.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct AdmissionLimits {
+    /// The limits of the deployment lane.
+    pub deployments: LaneLimits,
+    /// The limits of the execution lane.
+    pub executions: LaneLimits,
+}
+
+impl Default for AdmissionLimits {
+    fn default() -> Self {
+        Self {
+            deployments: LaneLimits {
+                capacity: DEFAULT_DEPLOYMENT_LANE_CAPACITY,
+                concurrency: DEFAULT_DEPLOYMENT_LANE_CONCURRENCY,
+            },
+            executions: LaneLimits {
+                capacity: DEFAULT_EXECUTION_LANE_CAPACITY,
+                concurrency: DEFAULT_EXECUTION_LANE_CONCURRENCY,
+            },
+        }
+    }
+}
+
+impl AdmissionLimits {
+    /// Ensures every lane admits and verifies at least one transaction at once.
+    pub fn check(&self) -> Result<()> {
+        for (lane, limits) in [(Lane::Deployment, self.deployments), (Lane::Execution, self.executions)] {
+            ensure!(limits.capacity > 0, "The capacity of the {lane} lane must be at least 1");
+            ensure!(limits.concurrency > 0, "The concurrency of the {lane} lane must be at least 1");
+        }
+        Ok(())
+    }
+}
+
+/// A lane admitting unconfirmed transactions for verification.
+struct AdmissionLane {
+    /// The maximum number of transactions admitted at once.
+    capacity: usize,
+    /// The permits of the transactions admitted at once.
+    admitted: Arc<Semaphore>,
+    /// The permits of the transactions verified concurrently.
+    verifying: Arc<Semaphore>,
+}
+
+impl AdmissionLane {
+    /// Initializes a new admission lane with the given limits.
+    fn new(limits: LaneLimits) -> Self {
+        Self {
+            capacity: limits.capacity,
+            admitted: Arc::new(Semaphore::new(limits.capacity)),
+            verifying: Arc::new(Semaphore::new(limits.concurrency)),
+        }
+    }
+}
+
+/// The admission lanes of the unconfirmed transactions, each with its own capacity and verification concurrency.
+pub struct AdmissionLanes {
+    /// The lane of the deployment transactions.
+    deployments: AdmissionLane,
+    /// The lane of the execution transactions.
+    executions: AdmissionLane,
+}
+
+/// The admission of a transaction in a lane, which is released when it is dropped.
+pub struct Admission {
+    /// The admission permit.
+    _admitted: OwnedSemaphorePermit,
+    /// The permits of the transactions verified concurrently in the lane.
+    verifying: Arc<Semaphore>,
+}
+
+impl Admission {
+    /// Waits for a verification slot in the lane, which is released when the returned permit is dropped.
+    pub async fn verify(&self) -> Result<OwnedSemaphorePermit> {
+        Ok(self.verifying.clone().acquire_owned().await?)
+    }
+}
+
+impl AdmissionLanes {
+    /// Initializes new admission lanes with the given limits.
+    pub fn new(limits: AdmissionLimits) -> Self {
+        Self { deployments: AdmissionLane::new(limits.deployments), executions: AdmissionLane::new(limits.executions) }
+    }
+
+    /// Returns the given lane.
+    fn lane(&self, lane: Lane) -> &AdmissionLane {
+        match lane {
+            Lane::Deployment => &self.deployments,
+            Lane::Execution => &self.executions,
+        }
+    }
+
+    /// Admits a transaction in the given lane, or returns `None` if the lane is full.
+    pub fn try_admit(&self, lane: Lane) -> Option<Admission> {
+        let lane = self.lane(lane);
+        let admitted = lane.admitted.clone().try_acquire_owned().ok()?;
+        Some(Admission { _admitted: admitted, verifying: lane.verifying.clone() })
+    }
+
+    /// Returns the number of transactions admitted in the given lane.
+    pub fn num_admitted(&self, lane: Lane) -> usize {
+        let lane = self.lane(lane);
+        lane.capacity.saturating_sub(lane.admitted.available_permits())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_admission_lanes() {
+        let limits = AdmissionLimits {
+            deployments: LaneLimits { capacity: 2, concurrency: 1 },
+            executions: LaneLimits { capacity: 3, concurrency: 2 },
+        };
+        let lanes = AdmissionLanes::new(limits);
+
+        // Check that a full deployment lane does not affect the execution lane.
+        let first = lanes.try_admit(Lane::Deployment).unwrap();
+        let second = lanes.try_admit(Lane::Deployment).unwrap();
+        assert!(lanes.try_admit(Lane::Deployment).is_none());
+        assert_eq!(lanes.num_admitted(Lane::Deployment), 2);
+        let executions: Vec<_> = (0..3).map(|_| lanes.try_admit(Lane::Execution).unwrap()).collect();
+        assert!(lanes.try_admit(Lane::Execution).is_none());
+
+        // Check that the deployments are verified one at a time, without blocking the executions.
+        let verifying = first.verify().await.unwrap();
+        assert!(tokio::time::timeout(std::time::Duration::from_millis(10), second.verify()).await.is_err());
+        let _verifying = executions[0].verify().await.unwrap();
+        let _verifying = executions[1].verify().await.unwrap();
+        drop(verifying);
+        assert!(second.verify().await.is_ok());
+
+        // Check that the lane admits a transaction again once one is released.
+        drop(first);
+        assert!(lanes.try_admit(Lane::Deployment).is_some());
+        drop(executions);
+        assert_eq!(lanes.num_admitted(Lane::Execution), 0);
+    }
+
+    #[test]
+    fn test_admission_limits_check() {
+        assert!(AdmissionLimits::default().check().is_ok());
+        let mut limits = AdmissionLimits::default();
+        limits.deployments.capacity = 0;
+        assert!(limits.check().is_err());
+        let mut limits = AdmissionLimits::default();
+        limits.executions.concurrency = 0;
+        assert!(limits.check().is_err());
+    }
+}
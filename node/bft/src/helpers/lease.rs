@@ -0,0 +1,389 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::helpers::{now, Signer};
+use snarkvm::console::{
+    account::{Address, Signature},
+    prelude::*,
+    types::Field,
+};
+
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use std::{
+    fs,
+    io::ErrorKind,
+    path::PathBuf,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+use tokio::task::JoinHandle;
+
+/// The default duration of the validator lease, in seconds.
+pub const DEFAULT_LEASE_DURATION_IN_SECS: u64 = 15;
+/// The maximum time to wait for a response from a remote lock service.
+const REMOTE_LEASE_TIMEOUT_IN_SECS: u64 = 2;
+
+/// A lease shared by the nodes of a validator, so that only one of them (the active node) signs at a time.
+#[async_trait]
+pub trait Lease: Send + Sync {
+    /// Acquires or renews the lease on behalf of the given holder, for the given duration.
+    /// Returns `true` if the given holder holds the lease, and `false` if another holder holds it.
+    async fn try_acquire(&self, holder: &str, duration: Duration) -> Result<bool>;
+
+    /// Releases the lease, if it is held by the given holder.
+    async fn release(&self, holder: &str) -> Result<()>;
+}
+
+/// The state of a lease, as stored by a file lease.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+struct LeaseRecord {
+    /// The holder of the lease.
+    holder: String,
+    /// The UNIX timestamp (in seconds) at which the lease expires.
+    expires_at: i64,
+}
+
+/// A lease stored in a file shared by the nodes of a validator (e.g. on a network file system).
+///
+/// Every update of the file is guarded by an exclusive advisory lock (i.e. `flock`) on a lock file next to it
+/// (`<path>.lock`), and the file is replaced atomically, so that two nodes never acquire the lease at once.
+/// Note: The file system must support advisory locks across the nodes (e.g. NFS on Linux).
+pub struct FileLease {
+    /// The path of the lease file.
+    path: PathBuf,
+}
+
+impl FileLease {
+    /// Initializes a new file lease at the given path.
+    pub fn new<P: Into<PathBuf>>(path: P) -> Self {
+        Self { path: path.into() }
+    }
+
+    /// Returns the path of the lock file guarding the lease file.
+    fn lock_path(&self) -> PathBuf {
+        let mut path = self.path.clone().into_os_string();
+        path.push(".lock");
+        path.into()
+    }
+
+    /// Reads the lease record, if the lease file exists.
+    fn read(&self) -> Result<Option<LeaseRecord>> {
+        match fs::read(&self.path) {
+            Ok(bytes) => Ok(Some(serde_json::from_slice(&bytes)?)),
+            Err(error) if error.kind() == ErrorKind::NotFound => Ok(None),
+            Err(error) => Err(error.into()),
+        }
+    }
+
+    /// Replaces the lease record atomically.
+    fn write(&self, record: &LeaseRecord) -> Result<()> {
+        let mut temp_path = self.path.clone().into_os_string();
+        temp_path.push(".tmp");
+        fs::write(&temp_path, serde_json::to_vec(record)?)?;
+        fs::rename(temp_path, &self.path)?;
+        Ok(())
+    }
+
+    /// Runs the given update of the lease file while holding the lock on the lock file.
+    ///
+    /// Note: The lock is released by the operating system once the lock file is closed, including if the node
+    /// crashes while holding it, so that an abandoned lock never needs to be removed by another node.
+    #[cfg(unix)]
+    fn with_lock<T>(&self, update: impl FnOnce() -> Result<T>) -> Result<T> {
+        use nix::{
+            errno::Errno,
+            fcntl::{flock, FlockArg},
+        };
+        use std::{fs::OpenOptions, os::unix::io::AsRawFd};
+
+        // Acquire the lock.
+        let lock_file = OpenOptions::new().write(true).create(true).open(self.lock_path())?;
+        match flock(lock_file.as_raw_fd(), FlockArg::LockExclusiveNonblock) {
+            Ok(()) => (),
+            Err(Errno::EWOULDBLOCK) => bail!("The lease file '{}' is locked", self.path.display()),
+            Err(error) => return Err(error.into()),
+        }
+        // Update the lease file, and release the lock by closing the lock file.
+        let result = update();
+        drop(lock_file);
+        result
+    }
+
+    /// Runs the given update of the lease file while holding the lock on the lock file.
+    #[cfg(not(unix))]
+    fn with_lock<T>(&self, _update: impl FnOnce() -> Result<T>) -> Result<T> {
+        bail!("The file lease requires advisory file locks, which are only supported on Unix (use a lock service)")
+    }
+}
+
+#[async_trait]
+impl Lease for FileLease {
+    async fn try_acquire(&self, holder: &str, duration: Duration) -> Result<bool> {
+        self.with_lock(|| {
+            // If another holder holds an unexpired lease, then do not acquire it.
+            let timestamp = now();
+            if let Some(record) = self.read()? {
+                if record.holder != holder && record.expires_at > timestamp {
+                    return Ok(false);
+                }
+            }
+            // Acquire or renew the lease.
+            let expires_at = timestamp.saturating_add(duration.as_secs() as i64);
+            self.write(&LeaseRecord { holder: holder.to_string(), expires_at })?;
+            Ok(true)
+        })
+    }
+
+    async fn release(&self, holder: &str) -> Result<()> {
+        self.with_lock(|| {
+            if let Some(record) = self.read()? {
+                if record.holder == holder {
+                    self.write(&LeaseRecord { holder: record.holder, expires_at: 0 })?;
+                }
+            }
+            Ok(())
+        })
+    }
+}
+
+/// A lease held through an external lock service over HTTP. The service is expected to serve
+/// the following routes, with JSON bodies:
+///
+/// - `POST <url>/acquire`, taking `{ "holder": "..", "duration_secs": 15 }`,
+///   and returning `{ "acquired": true }` if the holder holds the lease.
+/// - `POST <url>/release`, taking `{ "holder": ".." }`.
+///
+/// The service is responsible for granting the lease to at most one holder until it expires.
+#[derive(Clone)]
+pub struct RemoteLease {
+    /// The base URL of the lock service.
+    url: String,
+    /// The HTTP client.
+    client: reqwest::Client,
+}
+
+/// An acquisition request to the lock service.
+#[derive(Serialize)]
+struct AcquireRequest<'a> {
+    holder: &'a str,
+    duration_secs: u64,
+}
+
+/// The response of the lock service to an acquisition request.
+#[derive(Deserialize)]
+struct AcquireResponse {
+    acquired: bool,
+}
+
+/// A release request to the lock service.
+#[derive(Serialize)]
+struct ReleaseRequest<'a> {
+    holder: &'a str,
+}
+
+impl RemoteLease {
+    /// Initializes a new lease held through the lock service at the given URL.
+    pub fn new(url: &str) -> Result<Self> {
+        let url = url.trim_end_matches('/').to_string();
+        let client = reqwest::Client::builder().timeout(Duration::from_secs(REMOTE_LEASE_TIMEOUT_IN_SECS)).build()?;
+        Ok(Self { url, client })
+    }
+}
+
+#[async_trait]
+impl Lease for RemoteLease {
+    async fn try_acquire(&self, holder: &str, duration: Duration) -> Result<bool> {
+        let request = AcquireRequest { holder, duration_secs: duration.as_secs() };
+        let response = self.client.post(format!("{}/acquire", self.url)).json(&request).send().await?;
+        let AcquireResponse { acquired } = response.error_for_status()?.json::<AcquireResponse>().await?;
+        Ok(acquired)
+    }
+
+    async fn release(&self, holder: &str) -> Result<()> {
+        let response =
+            self.client.post(format!("{}/release", self.url)).json(&ReleaseRequest { holder }).send().await?;
+        response.error_for_status()?;
+        Ok(())
+    }
+}
+
+/// A signer that only signs while its node holds the validator lease, so that two nodes can share
+/// the key of a validator (an active node and a standby node) without ever double-signing.
+///
+/// The lease is renewed every third of its duration. The node stops signing a third of the duration
+/// before the lease expires, unless it was renewed, so that it stops before a standby node may acquire it.
+pub struct LeasedSigner<N: Network> {
+    /// The signer of the validator.
+    signer: Arc<dyn Signer<N>>,
+    /// The validator lease.
+    lease: Arc<dyn Lease>,
+    /// The identifier of this node, as the holder of the lease.
+    holder: String,
+    /// The duration of the lease.
+    duration: Duration,
+    /// The time until which the node may sign, if it holds the lease.
+    active_until: RwLock<Option<Instant>>,
+}
+
+impl<N: Network> LeasedSigner<N> {
+    /// Initializes a new leased signer, identifying this node to the lease as the given holder.
+    pub fn new(signer: Arc<dyn Signer<N>>, lease: Arc<dyn Lease>, holder: String, duration: Duration) -> Self {
+        Self { signer, lease, holder, duration, active_until: Default::default() }
+    }
+
+    /// Returns the identifier of this node, as the holder of the lease.
+    pub fn holder(&self) -> &str {
+        &self.holder
+    }
+
+    /// Acquires or renews the lease, returning `true` if this node is the active node.
+    pub async fn renew(&self) -> bool {
+        // Note: The time is taken before the request, as the lease may be granted at any point during it.
+        let start = Instant::now();
+        let was_active = self.is_active();
+        match self.lease.try_acquire(&self.holder, self.duration).await {
+            Ok(true) => {
+                *self.active_until.write() = Some(start + self.duration * 2 / 3);
+                if !was_active {
+                    info!("Acquired the validator lease - this node is now the active validator");
+                }
+            }
+            Ok(false) => {
+                *self.active_until.write() = None;
+                if was_active {
+                    warn!("Lost the validator lease - this node is now a standby validator");
+                }
+            }
+            // Note: The node remains active until its current lease runs out.
+            Err(e) => warn!("Failed to renew the validator lease - {e}"),
+        }
+        self.is_active()
+    }
+
+    /// Releases the lease, so that a standby node can take over without waiting for it to expire.
+    pub async fn release(&self) -> Result<()> {
+        *self.active_until.write() = None;
+        self.lease.release(&self.holder).await
+    }
+
+    /// Starts renewing the lease in the background.
+    pub fn start_renewal(self: &Arc<Self>) -> JoinHandle<()> {
+        let self_ = self.clone();
+        tokio::spawn(async move {
+            loop {
+                self_.renew().await;
+                tokio::time::sleep(self_.duration / 3).await;
+            }
+        })
+    }
+}
+
+#[async_trait]
+impl<N: Network> Signer<N> for LeasedSigner<N> {
+    fn address(&self) -> Address<N> {
+        self.signer.address()
+    }
+
+    fn is_active(&self) -> bool {
+        self.active_until.read().is_some_and(|active_until| Instant::now() < active_until) && self.signer.is_active()
+    }
+
+    async fn sign(&self, message: &[Field<N>]) -> Result<Signature<N>> {
+        ensure!(self.is_active(), "Cannot sign without the validator lease (this node is a standby validator)");
+        self.signer.sign(message).await
+    }
+
+    async fn sign_bytes(&self, message: &[u8]) -> Result<Signature<N>> {
+        ensure!(self.is_active(), "Cannot sign without the validator lease (this node is a standby validator)");
+        self.signer.sign_bytes(message).await
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use crate::helpers::LocalSigner;
+    use snarkos_account::Account;
+    use snarkvm::prelude::MainnetV0;
+
+    use nix::fcntl::{flock, FlockArg};
+    use std::os::unix::io::AsRawFd;
+
+    type CurrentNetwork = MainnetV0;
+
+    #[tokio::test]
+    async fn test_file_lease() {
+        let path = std::env::temp_dir().join(format!("snarkos-validator-lease-{}", std::process::id()));
+        let lease = FileLease::new(&path);
+        let duration = Duration::from_secs(60);
+
+        // Check that the first holder acquires and renews the lease.
+        assert!(lease.try_acquire("active", duration).await.unwrap());
+        assert!(lease.try_acquire("active", duration).await.unwrap());
+        // Check that another holder cannot acquire the lease until it is released.
+        assert!(!lease.try_acquire("standby", duration).await.unwrap());
+        lease.release("standby").await.unwrap();
+        assert!(!lease.try_acquire("standby", duration).await.unwrap());
+        lease.release("active").await.unwrap();
+        assert!(lease.try_acquire("standby", duration).await.unwrap());
+        assert!(!lease.try_acquire("active", duration).await.unwrap());
+
+        // Check that an expired lease is acquired by another holder.
+        assert!(lease.try_acquire("standby", Duration::ZERO).await.unwrap());
+        assert!(lease.try_acquire("active", duration).await.unwrap());
+
+        // Check that a locked lease file is not updated.
+        let lock_file = fs::File::create(lease.lock_path()).unwrap();
+        flock(lock_file.as_raw_fd(), FlockArg::LockExclusiveNonblock).unwrap();
+        assert!(lease.try_acquire("active", duration).await.is_err());
+        // Check that the lock is released once the lock file is closed (e.g. if the node crashed), without removing it.
+        drop(lock_file);
+        assert!(lease.try_acquire("active", duration).await.unwrap());
+
+        fs::remove_file(lease.lock_path()).unwrap();
+        fs::remove_file(path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_leased_signer() {
+        let rng = &mut TestRng::default();
+        let path = std::env::temp_dir().join(format!("snarkos-validator-leased-signer-{}", std::process::id()));
+        let lease: Arc<dyn Lease> = Arc::new(FileLease::new(&path));
+        let duration = Duration::from_secs(60);
+
+        // Initialize two nodes sharing the key of the validator.
+        let signer: Arc<dyn Signer<CurrentNetwork>> = Arc::new(LocalSigner::new(Account::new(rng).unwrap()));
+        let active = LeasedSigner::new(signer.clone(), lease.clone(), "active".to_string(), duration);
+        let standby = LeasedSigner::new(signer, lease, "standby".to_string(), duration);
+        assert!(!active.is_active());
+
+        // Check that only the node holding the lease signs.
+        let message = [Field::rand(rng)];
+        assert!(active.renew().await);
+        assert!(!standby.renew().await);
+        assert!(active.sign(&message).await.is_ok());
+        assert!(standby.sign(&message).await.is_err());
+
+        // Check that the standby node takes over once the lease is released.
+        active.release().await.unwrap();
+        assert!(active.sign(&message).await.is_err());
+        assert!(standby.renew().await);
+        assert!(!active.renew().await);
+        assert!(standby.sign(&message).await.is_ok());
+
+        fs::remove_file(format!("{}.lock", path.display())).unwrap();
+        fs::remove_file(path).unwrap();
+    }
+}
@@ -12,15 +12,33 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+pub mod archive;
+pub use archive::*;
+
+pub mod audit;
+pub use audit::*;
+
 pub mod cache;
 pub use cache::*;
 
 pub mod channels;
 pub use channels::*;
 
+pub mod config;
+pub use config::*;
+
 pub mod dag;
 pub use dag::*;
 
+pub mod equivocation;
+pub use equivocation::*;
+
+pub mod lanes;
+pub use lanes::*;
+
+pub mod lease;
+pub use lease::*;
+
 pub mod partition;
 pub use partition::*;
 
@@ -33,9 +51,15 @@ pub use proposal::*;
 pub mod ready;
 pub use ready::*;
 
+pub mod replay;
+pub use replay::*;
+
 pub mod resolver;
 pub use resolver::*;
 
+pub mod signer;
+pub use signer::*;
+
 pub mod storage;
 pub use storage::*;
 
@@ -12,6 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use crate::{MAX_BATCH_DELAY_IN_MS, MAX_BATCH_SIZE_IN_BYTES, MIN_BATCH_DELAY_IN_MS};
 use snarkvm::{
     console::{
         account::{Address, Signature},
@@ -28,6 +29,53 @@ use snarkvm::{
 use indexmap::{IndexMap, IndexSet};
 use std::collections::HashSet;
 
+/// The limits of the batches proposed by the primary.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct ProposalLimits {
+    /// The maximum number of transmissions in a batch.
+    pub max_transmissions: usize,
+    /// The maximum total size of the transmissions in a batch, in bytes.
+    pub max_bytes: usize,
+    /// The number of milliseconds to wait between batch proposals.
+    pub interval_in_ms: u64,
+    /// The minimum number of milliseconds between two batch proposals, to pace the rounds (e.g. of a development
+    /// network). Note: A block is committed every two rounds, so this paces the blocks at twice this interval.
+    pub min_interval_in_ms: u64,
+}
+
+impl ProposalLimits {
+    /// Returns the largest limits enforced by the network.
+    pub const fn max<N: Network>() -> Self {
+        Self {
+            max_transmissions: BatchHeader::<N>::MAX_TRANSMISSIONS_PER_BATCH,
+            max_bytes: MAX_BATCH_SIZE_IN_BYTES,
+            interval_in_ms: MAX_BATCH_DELAY_IN_MS,
+            min_interval_in_ms: 0,
+        }
+    }
+
+    /// Ensures the limits are within the caps enforced by the network.
+    pub fn check<N: Network>(&self) -> Result<()> {
+        let max = Self::max::<N>();
+        ensure!(
+            (1..=max.max_transmissions).contains(&self.max_transmissions),
+            "The maximum number of transmissions per batch must be between 1 and {}",
+            max.max_transmissions
+        );
+        ensure!(
+            (1..=max.max_bytes).contains(&self.max_bytes),
+            "The maximum batch size must be between 1 and {} bytes",
+            max.max_bytes
+        );
+        ensure!(
+            (MIN_BATCH_DELAY_IN_MS..=max.interval_in_ms).contains(&self.interval_in_ms),
+            "The batch proposal interval must be between {MIN_BATCH_DELAY_IN_MS} and {} ms",
+            max.interval_in_ms
+        );
+        Ok(())
+    }
+}
+
 pub struct Proposal<N: Network> {
     /// The proposed batch header.
     batch_header: BatchHeader<N>,
@@ -169,6 +217,39 @@ impl<N: Network> Proposal<N> {
     }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type CurrentNetwork = snarkvm::prelude::MainnetV0;
+
+    #[test]
+    fn test_proposal_limits() {
+        let max = ProposalLimits::max::<CurrentNetwork>();
+        assert!(max.check::<CurrentNetwork>().is_ok());
+
+        // Ensure smaller limits are accepted.
+        let limits = ProposalLimits {
+            max_transmissions: 10,
+            max_bytes: 1024,
+            interval_in_ms: MIN_BATCH_DELAY_IN_MS,
+            min_interval_in_ms: 0,
+        };
+        assert!(limits.check::<CurrentNetwork>().is_ok());
+        // Ensure a minimum interval between the proposals is accepted, to pace a development network.
+        assert!(ProposalLimits { min_interval_in_ms: 60_000, ..max }.check::<CurrentNetwork>().is_ok());
+
+        // Ensure the limits beyond the caps enforced by the network are rejected.
+        let is_rejected = |limits: ProposalLimits| limits.check::<CurrentNetwork>().is_err();
+        assert!(is_rejected(ProposalLimits { max_transmissions: 0, ..max }));
+        assert!(is_rejected(ProposalLimits { max_transmissions: max.max_transmissions + 1, ..max }));
+        assert!(is_rejected(ProposalLimits { max_bytes: 0, ..max }));
+        assert!(is_rejected(ProposalLimits { max_bytes: MAX_BATCH_SIZE_IN_BYTES + 1, ..max }));
+        assert!(is_rejected(ProposalLimits { interval_in_ms: MIN_BATCH_DELAY_IN_MS - 1, ..max }));
+        assert!(is_rejected(ProposalLimits { interval_in_ms: MAX_BATCH_DELAY_IN_MS + 1, ..max }));
+    }
+}
+
 #[cfg(test)]
 mod prop_tests {
     use crate::helpers::{
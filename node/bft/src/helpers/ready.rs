@@ -12,10 +12,16 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use crate::helpers::fmt_id;
 use snarkvm::{
-    console::prelude::*,
+    console::{
+        account::Address,
+        prelude::*,
+        program::{Argument, Literal, Plaintext},
+        types::Field,
+    },
     ledger::{
-        block::Transaction,
+        block::{Output, Transaction},
         coinbase::{ProverSolution, PuzzleCommitment},
         narwhal::{Data, Transmission, TransmissionID},
     },
@@ -23,12 +29,304 @@ use snarkvm::{
 
 use indexmap::{IndexMap, IndexSet};
 use parking_lot::RwLock;
-use std::sync::Arc;
+use std::{
+    cmp::Reverse,
+    collections::BTreeMap,
+    fmt,
+    str::FromStr,
+    sync::Arc,
+};
+
+/// The default fee bump required to replace a pending transaction, in percent of its fee.
+pub const DEFAULT_REPLACEMENT_FEE_BUMP: u64 = 10;
+
+/// The default maximum number of transactions in the memory pool.
+pub const DEFAULT_MEMPOOL_MAX_TRANSACTIONS: usize = 10_000;
+/// The default maximum total size of the transactions in the memory pool, in bytes.
+pub const DEFAULT_MEMPOOL_MAX_BYTES: usize = 256 * 1024 * 1024;
+/// The default maximum number of transactions from the same fee payer in the memory pool.
+pub const DEFAULT_MEMPOOL_MAX_TRANSACTIONS_PER_PAYER: usize = 100;
+
+/// The policy selecting the transaction to evict when the memory pool is full.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum EvictionPolicy {
+    /// Evicts the transaction with the lowest priority (i.e. the lowest fee, then the lowest fee per byte).
+    /// Among the transactions with the same priority, the most recent one is evicted first.
+    #[default]
+    LowestFee,
+    /// Evicts the transaction that arrived first.
+    Oldest,
+}
+
+impl FromStr for EvictionPolicy {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "lowest-fee" => Ok(Self::LowestFee),
+            "oldest" => Ok(Self::Oldest),
+            _ => bail!("Invalid eviction policy '{s}' (expected 'lowest-fee' or 'oldest')"),
+        }
+    }
+}
+
+impl fmt::Display for EvictionPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::LowestFee => write!(f, "lowest-fee"),
+            Self::Oldest => write!(f, "oldest"),
+        }
+    }
+}
+
+/// The limits of the memory pool, applied to the transactions in each ready queue.
+/// Note: The solutions do not count towards the limits, and are never evicted.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct MempoolLimits {
+    /// The maximum number of transactions.
+    pub max_transactions: usize,
+    /// The maximum total size of the transactions, in bytes.
+    pub max_bytes: usize,
+    /// The maximum number of transactions from the same fee payer.
+    /// Note: Only the payers of public fees are known, so the transactions with a private fee are not limited.
+    pub max_transactions_per_payer: usize,
+    /// The policy selecting the transaction to evict when a limit is exceeded.
+    pub eviction_policy: EvictionPolicy,
+    /// The fee bump required to replace a pending transaction, in percent of its fee.
+    pub replacement_fee_bump: u64,
+}
+
+impl Default for MempoolLimits {
+    fn default() -> Self {
+        Self {
+            max_transactions: DEFAULT_MEMPOOL_MAX_TRANSACTIONS,
+            max_bytes: DEFAULT_MEMPOOL_MAX_BYTES,
+            max_transactions_per_payer: DEFAULT_MEMPOOL_MAX_TRANSACTIONS_PER_PAYER,
+            eviction_policy: EvictionPolicy::default(),
+            replacement_fee_bump: DEFAULT_REPLACEMENT_FEE_BUMP,
+        }
+    }
+}
+
+impl MempoolLimits {
+    /// Returns the minimum fee of a transaction replacing a pending transaction with the given fee.
+    fn minimum_replacement_fee(&self, fee: u64) -> u64 {
+        let percent = 100 + self.replacement_fee_bump as u128;
+        // Note: The minimum fee always exceeds the original fee, even if the fee bump is zero.
+        u64::try_from(fee as u128 * percent / 100).unwrap_or(u64::MAX).max(fee.saturating_add(1))
+    }
+}
+
+/// The priority of a transmission in the ready queue, where the transmissions with the highest priority
+/// are drained first. The solutions come first, followed by the transactions by decreasing fee, and then
+/// by decreasing fee per byte. Transmissions with the same priority are drained in arrival order.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+struct TransmissionPriority {
+    /// Whether the transmission is a solution.
+    is_solution: bool,
+    /// The fee of the transaction, in microcredits.
+    fee: u64,
+    /// The fee of the transaction per byte, in microcredits.
+    fee_per_byte: u64,
+}
+
+/// A transmission in the ready queue, with its priority and the serial numbers it consumes.
+#[derive(Clone, Debug)]
+struct Entry<N: Network> {
+    /// The transmission.
+    transmission: Transmission<N>,
+    /// The priority of the transmission.
+    priority: TransmissionPriority,
+    /// The serial numbers of the records consumed by the transaction.
+    serial_numbers: Vec<Field<N>>,
+    /// The size of the transaction, in bytes.
+    size: usize,
+    /// The payer of the fee of the transaction, if it is public.
+    fee_payer: Option<Address<N>>,
+}
+
+impl<N: Network> Entry<N> {
+    /// Initializes a new entry for the given transmission.
+    /// Note: A transaction received as bytes is deserialized to determine its priority; one that cannot be
+    /// deserialized has the lowest priority, and no serial numbers.
+    fn new(transmission: Transmission<N>) -> Self {
+        let ((priority, serial_numbers, size), fee_payer) = match &transmission {
+            Transmission::Solution(..) => {
+                ((TransmissionPriority { is_solution: true, ..Default::default() }, vec![], 0), None)
+            }
+            Transmission::Transaction(Data::Object(transaction)) => (
+                Self::inspect(transaction, transaction.to_bytes_le().map_or(0, |bytes| bytes.len())),
+                Self::fee_payer(transaction),
+            ),
+            Transmission::Transaction(Data::Buffer(bytes)) => match Transaction::read_le(&bytes[..]) {
+                Ok(transaction) => (Self::inspect(&transaction, bytes.len()), Self::fee_payer(&transaction)),
+                Err(_) => ((Default::default(), vec![], bytes.len()), None),
+            },
+            Transmission::Ratification => Default::default(),
+        };
+        Self { transmission, priority, serial_numbers, size, fee_payer }
+    }
+
+    /// Initializes a new entry for the given transaction, given its deserialized form, so that it is not
+    /// deserialized again.
+    fn with_transaction(transaction: Data<Transaction<N>>, deserialized: &Transaction<N>) -> Self {
+        let size = match &transaction {
+            Data::Object(transaction) => transaction.to_bytes_le().map_or(0, |bytes| bytes.len()),
+            Data::Buffer(bytes) => bytes.len(),
+        };
+        let (priority, serial_numbers, size) = Self::inspect(deserialized, size);
+        let fee_payer = Self::fee_payer(deserialized);
+        Self { transmission: Transmission::Transaction(transaction), priority, serial_numbers, size, fee_payer }
+    }
+
+    /// Returns the priority of the given transaction of the given size (in bytes), the serial numbers it consumes,
+    /// and its size. Note: A transaction without a valid fee has the lowest priority.
+    fn inspect(transaction: &Transaction<N>, size: usize) -> (TransmissionPriority, Vec<Field<N>>, usize) {
+        let fee = match (transaction.base_fee_amount(), transaction.priority_fee_amount()) {
+            (Ok(base_fee), Ok(priority_fee)) => (*base_fee).saturating_add(*priority_fee),
+            _ => 0,
+        };
+        let priority = TransmissionPriority { is_solution: false, fee, fee_per_byte: fee / size.max(1) as u64 };
+        (priority, transaction.serial_numbers().copied().collect(), size)
+    }
+
+    /// Returns the payer of the fee of the given transaction, if the fee is public.
+    /// Note: The payer of a public fee is the first argument of the future of the fee transition.
+    fn fee_payer(transaction: &Transaction<N>) -> Option<Address<N>> {
+        let fee = transaction.fee_transition()?;
+        if !fee.is_fee_public() {
+            return None;
+        }
+        match fee.outputs().last()? {
+            Output::Future(_, Some(future)) => match future.arguments().first()? {
+                Argument::Plaintext(Plaintext::Literal(Literal::Address(address), _)) => Some(*address),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    /// Returns `true` if the entry is a transaction.
+    fn is_transaction(&self) -> bool {
+        matches!(self.transmission, Transmission::Transaction(..))
+    }
+}
+
+/// The transmissions in the ready queue, indexed by arrival order, priority, serial number and fee payer,
+/// so that inserting, evicting and draining a transmission does not scan the whole queue.
+#[derive(Debug)]
+struct Queue<N: Network> {
+    /// The entries, with the sequence number of their arrival.
+    entries: IndexMap<TransmissionID<N>, (u64, Entry<N>)>,
+    /// The transmission IDs, by sequence number (i.e. in arrival order).
+    arrivals: BTreeMap<u64, TransmissionID<N>>,
+    /// The transmission IDs, by increasing priority, and then by decreasing sequence number.
+    priorities: BTreeMap<(TransmissionPriority, Reverse<u64>), TransmissionID<N>>,
+    /// The pending transaction consuming each serial number.
+    serial_numbers: IndexMap<Field<N>, TransmissionID<N>>,
+    /// The number of pending transactions of each public fee payer.
+    fee_payers: IndexMap<Address<N>, usize>,
+    /// The number of transactions.
+    num_transactions: usize,
+    /// The total size of the transactions, in bytes.
+    num_bytes: usize,
+    /// The sequence number of the next transmission.
+    next_sequence: u64,
+}
+
+impl<N: Network> Default for Queue<N> {
+    fn default() -> Self {
+        Self {
+            entries: Default::default(),
+            arrivals: Default::default(),
+            priorities: Default::default(),
+            serial_numbers: Default::default(),
+            fee_payers: Default::default(),
+            num_transactions: 0,
+            num_bytes: 0,
+            next_sequence: 0,
+        }
+    }
+}
+
+impl<N: Network> Queue<N> {
+    /// Inserts the given entry, which must not be in the queue.
+    fn insert(&mut self, transmission_id: TransmissionID<N>, entry: Entry<N>) {
+        let sequence = self.next_sequence;
+        self.next_sequence += 1;
+        self.arrivals.insert(sequence, transmission_id);
+        self.priorities.insert((entry.priority, Reverse(sequence)), transmission_id);
+        for serial_number in &entry.serial_numbers {
+            self.serial_numbers.insert(*serial_number, transmission_id);
+        }
+        if let Some(fee_payer) = entry.fee_payer {
+            *self.fee_payers.entry(fee_payer).or_default() += 1;
+        }
+        if entry.is_transaction() {
+            self.num_transactions += 1;
+            self.num_bytes += entry.size;
+        }
+        self.entries.insert(transmission_id, (sequence, entry));
+    }
+
+    /// Removes the given entry, and returns it, if it is in the queue.
+    fn remove(&mut self, transmission_id: &TransmissionID<N>) -> Option<Entry<N>> {
+        let (sequence, entry) = self.entries.swap_remove(transmission_id)?;
+        self.arrivals.remove(&sequence);
+        self.priorities.remove(&(entry.priority, Reverse(sequence)));
+        for serial_number in &entry.serial_numbers {
+            if self.serial_numbers.get(serial_number) == Some(transmission_id) {
+                self.serial_numbers.swap_remove(serial_number);
+            }
+        }
+        if let Some(fee_payer) = entry.fee_payer {
+            if let Some(num_pending) = self.fee_payers.get_mut(&fee_payer) {
+                *num_pending -= 1;
+                if *num_pending == 0 {
+                    self.fee_payers.swap_remove(&fee_payer);
+                }
+            }
+        }
+        if entry.is_transaction() {
+            self.num_transactions -= 1;
+            self.num_bytes -= entry.size;
+        }
+        Some(entry)
+    }
+
+    /// Returns the IDs and fees of the pending transactions that consume any of the given serial numbers.
+    fn conflicts(&self, serial_numbers: &[Field<N>]) -> Vec<(TransmissionID<N>, u64)> {
+        serial_numbers
+            .iter()
+            .filter_map(|serial_number| self.serial_numbers.get(serial_number))
+            .copied()
+            .collect::<IndexSet<_>>()
+            .into_iter()
+            .filter_map(|id| self.entries.get(&id).map(|(_, entry)| (id, entry.priority.fee)))
+            .collect()
+    }
+
+    /// Returns the transaction to evict according to the eviction policy, if the memory pool limits are exceeded.
+    fn select_eviction(&self, limits: &MempoolLimits) -> Option<TransmissionID<N>> {
+        // Ensure the limits are exceeded.
+        if self.num_transactions <= limits.max_transactions && self.num_bytes <= limits.max_bytes {
+            return None;
+        }
+        // Select the transaction to evict.
+        let is_transaction = |id: &&TransmissionID<N>| matches!(id, TransmissionID::Transaction(..));
+        match limits.eviction_policy {
+            EvictionPolicy::LowestFee => self.priorities.values().find(is_transaction).copied(),
+            EvictionPolicy::Oldest => self.arrivals.values().find(is_transaction).copied(),
+        }
+    }
+}
 
 #[derive(Clone, Debug)]
 pub struct Ready<N: Network> {
-    /// The current map of `(transmission ID, transmission)` entries.
-    transmissions: Arc<RwLock<IndexMap<TransmissionID<N>, Transmission<N>>>>,
+    /// The transmissions in the ready queue.
+    queue: Arc<RwLock<Queue<N>>>,
+    /// The limits of the memory pool.
+    limits: MempoolLimits,
 }
 
 impl<N: Network> Default for Ready<N> {
@@ -39,49 +337,55 @@ impl<N: Network> Default for Ready<N> {
 }
 
 impl<N: Network> Ready<N> {
-    /// Initializes a new instance of the ready queue.
+    /// Initializes a new instance of the ready queue, with the default memory pool limits.
     pub fn new() -> Self {
-        Self { transmissions: Default::default() }
+        Self::with_limits(Default::default())
+    }
+
+    /// Initializes a new instance of the ready queue, with the given memory pool limits.
+    pub fn with_limits(limits: MempoolLimits) -> Self {
+        Self { queue: Default::default(), limits }
     }
 
     /// Returns `true` if the ready queue is empty.
     pub fn is_empty(&self) -> bool {
-        self.transmissions.read().is_empty()
+        self.queue.read().entries.is_empty()
     }
 
     /// Returns the number of transmissions in the ready queue.
     pub fn num_transmissions(&self) -> usize {
-        self.transmissions.read().len()
+        self.queue.read().entries.len()
     }
 
     /// Returns the number of ratifications in the ready queue.
     pub fn num_ratifications(&self) -> usize {
-        self.transmissions.read().keys().filter(|id| matches!(id, TransmissionID::Ratification)).count()
+        self.queue.read().entries.keys().filter(|id| matches!(id, TransmissionID::Ratification)).count()
     }
 
     /// Returns the number of solutions in the ready queue.
     pub fn num_solutions(&self) -> usize {
-        self.transmissions.read().keys().filter(|id| matches!(id, TransmissionID::Solution(..))).count()
+        self.queue.read().entries.keys().filter(|id| matches!(id, TransmissionID::Solution(..))).count()
     }
 
     /// Returns the number of transactions in the ready queue.
     pub fn num_transactions(&self) -> usize {
-        self.transmissions.read().keys().filter(|id| matches!(id, TransmissionID::Transaction(..))).count()
+        self.queue.read().num_transactions
     }
 
-    /// Returns the transmission IDs in the ready queue.
+    /// Returns the transmission IDs in the ready queue, in arrival order.
     pub fn transmission_ids(&self) -> IndexSet<TransmissionID<N>> {
-        self.transmissions.read().keys().copied().collect()
+        self.queue.read().arrivals.values().copied().collect()
     }
 
-    /// Returns the transmissions in the ready queue.
+    /// Returns the transmissions in the ready queue, in arrival order.
     pub fn transmissions(&self) -> IndexMap<TransmissionID<N>, Transmission<N>> {
-        self.transmissions.read().clone()
+        let queue = self.queue.read();
+        queue.arrivals.values().map(|id| (*id, queue.entries[id].1.transmission.clone())).collect()
     }
 
     /// Returns the solutions in the ready queue.
     pub fn solutions(&self) -> impl '_ + Iterator<Item = (PuzzleCommitment<N>, Data<ProverSolution<N>>)> {
-        self.transmissions.read().clone().into_iter().filter_map(|(id, transmission)| match (id, transmission) {
+        self.transmissions().into_iter().filter_map(|(id, transmission)| match (id, transmission) {
             (TransmissionID::Solution(id), Transmission::Solution(solution)) => Some((id, solution)),
             _ => None,
         })
@@ -89,7 +393,7 @@ impl<N: Network> Ready<N> {
 
     /// Returns the transactions in the ready queue.
     pub fn transactions(&self) -> impl '_ + Iterator<Item = (N::TransactionID, Data<Transaction<N>>)> {
-        self.transmissions.read().clone().into_iter().filter_map(|(id, transmission)| match (id, transmission) {
+        self.transmissions().into_iter().filter_map(|(id, transmission)| match (id, transmission) {
             (TransmissionID::Transaction(id), Transmission::Transaction(tx)) => Some((id, tx)),
             _ => None,
         })
@@ -99,32 +403,99 @@ impl<N: Network> Ready<N> {
 impl<N: Network> Ready<N> {
     /// Returns `true` if the ready queue contains the specified `transmission ID`.
     pub fn contains(&self, transmission_id: impl Into<TransmissionID<N>>) -> bool {
-        self.transmissions.read().contains_key(&transmission_id.into())
+        self.queue.read().entries.contains_key(&transmission_id.into())
     }
 
     /// Returns the transmission, given the specified `transmission ID`.
     pub fn get(&self, transmission_id: impl Into<TransmissionID<N>>) -> Option<Transmission<N>> {
-        self.transmissions.read().get(&transmission_id.into()).cloned()
+        self.queue.read().entries.get(&transmission_id.into()).map(|(_, entry)| entry.transmission.clone())
     }
 
     /// Inserts the specified (`transmission ID`, `transmission`) to the ready queue.
     /// Returns `true` if the transmission is new, and was added to the ready queue.
+    ///
+    /// A transaction that consumes the same records as pending transactions replaces them if its fee exceeds
+    /// each of their fees by the replacement fee bump, and is rejected otherwise. If the memory pool limits
+    /// are exceeded, transactions are evicted according to the eviction policy (possibly the new one).
     pub fn insert(&self, transmission_id: impl Into<TransmissionID<N>>, transmission: Transmission<N>) -> bool {
-        let transmission_id = transmission_id.into();
-        // Insert the transmission ID.
-        let is_new = self.transmissions.write().insert(transmission_id, transmission).is_none();
-        // Return whether the transmission is new.
-        is_new
+        self.insert_entry(transmission_id.into(), Entry::new(transmission))
     }
 
-    /// Removes up to the specified number of transmissions and returns them.
+    /// Inserts the specified transaction to the ready queue, given its deserialized form, so that it is not
+    /// deserialized again to determine its priority. See [`Ready::insert`] for the replacement and eviction rules.
+    pub fn insert_transaction(
+        &self,
+        transaction_id: N::TransactionID,
+        transaction: Data<Transaction<N>>,
+        deserialized: &Transaction<N>,
+    ) -> bool {
+        self.insert_entry(
+            TransmissionID::Transaction(transaction_id),
+            Entry::with_transaction(transaction, deserialized),
+        )
+    }
+
+    /// Inserts the specified (`transmission ID`, `entry`) to the ready queue, replacing the conflicting transactions.
+    /// Returns `true` if the transmission is new, and was added to the ready queue.
+    fn insert_entry(&self, transmission_id: TransmissionID<N>, entry: Entry<N>) -> bool {
+        // Acquire the write lock.
+        let mut queue = self.queue.write();
+        // Ensure the transmission is new.
+        if queue.entries.contains_key(&transmission_id) {
+            return false;
+        }
+        // Find the pending transactions that consume the same records.
+        let conflicts = queue.conflicts(&entry.serial_numbers);
+        // Ensure the fee is high enough to replace every conflicting transaction.
+        if conflicts.iter().any(|(_, fee)| entry.priority.fee < self.limits.minimum_replacement_fee(*fee)) {
+            return false;
+        }
+        // Ensure the fee payer does not exceed its number of pending transactions (excluding the replaced ones).
+        if let Some(fee_payer) = entry.fee_payer {
+            let num_replaced = conflicts
+                .iter()
+                .filter(|(id, _)| queue.entries.get(id).is_some_and(|(_, other)| other.fee_payer == Some(fee_payer)))
+                .count();
+            let num_pending = queue.fee_payers.get(&fee_payer).copied().unwrap_or_default() - num_replaced;
+            if num_pending >= self.limits.max_transactions_per_payer {
+                debug!(
+                    "Rejected the transaction '{}' (too many pending transactions from {fee_payer})",
+                    fmt_id(transmission_id)
+                );
+                return false;
+            }
+        }
+        // Remove the replaced transactions.
+        for (id, _) in conflicts {
+            queue.remove(&id);
+            debug!("Replaced the pending transaction '{}' with '{}' (higher fee)", fmt_id(id), fmt_id(transmission_id));
+        }
+        // Insert the transmission.
+        queue.insert(transmission_id, entry);
+        // Evict transactions until the memory pool limits are satisfied.
+        let mut is_evicted = false;
+        while let Some(id) = queue.select_eviction(&self.limits) {
+            queue.remove(&id);
+            is_evicted |= id == transmission_id;
+            debug!("Evicted the pending transaction '{}' (memory pool is full)", fmt_id(id));
+            #[cfg(feature = "metrics")]
+            metrics::increment_counter_with_labels(metrics::bft::MEMPOOL_EVICTIONS, &[(
+                "policy",
+                self.limits.eviction_policy.to_string(),
+            )]);
+        }
+        !is_evicted
+    }
+
+    /// Removes up to the specified number of transmissions with the highest priority and returns them,
+    /// in order of decreasing priority. The transmissions with the same priority are drained in arrival order.
     pub fn drain(&self, num_transmissions: usize) -> IndexMap<TransmissionID<N>, Transmission<N>> {
         // Acquire the write lock.
-        let mut transmissions = self.transmissions.write();
-        // Determine the number of transmissions to drain.
-        let range = 0..transmissions.len().min(num_transmissions);
-        // Drain the transmission IDs.
-        transmissions.drain(range).collect::<IndexMap<_, _>>()
+        let mut queue = self.queue.write();
+        // Select the transmissions with the highest priority.
+        let transmission_ids: Vec<_> = queue.priorities.values().rev().take(num_transmissions).copied().collect();
+        // Remove the drained transmissions.
+        transmission_ids.into_iter().filter_map(|id| queue.remove(&id).map(|entry| (id, entry.transmission))).collect()
     }
 }
 
@@ -223,4 +594,252 @@ mod tests {
         // Check the number of transmissions.
         assert_eq!(ready.num_transmissions(), 1);
     }
+
+    #[test]
+    fn test_ready_drain_by_priority() {
+        let rng = &mut TestRng::default();
+
+        // Sample random fake bytes.
+        let data = |rng: &mut TestRng| Data::Buffer(Bytes::from((0..512).map(|_| rng.gen::<u8>()).collect::<Vec<_>>()));
+        // Sample a random transaction ID.
+        let transaction_id = |rng: &mut TestRng| {
+            TransmissionID::<CurrentNetwork>::Transaction(Field::<CurrentNetwork>::rand(rng).into())
+        };
+        // Initialize a transaction entry.
+        let entry = |rng: &mut TestRng, fee, fee_per_byte| Entry {
+            transmission: Transmission::Transaction(data(rng)),
+            priority: TransmissionPriority { is_solution: false, fee, fee_per_byte },
+            serial_numbers: vec![],
+            size: 512,
+            fee_payer: None,
+        };
+
+        // Initialize the ready queue.
+        let ready = Ready::<CurrentNetwork>::new();
+
+        // Insert the transactions, and a solution last.
+        let (transaction_1, transaction_2, transaction_3, transaction_4) =
+            (transaction_id(rng), transaction_id(rng), transaction_id(rng), transaction_id(rng));
+        assert!(ready.insert_entry(transaction_1, entry(rng, 10, 1)));
+        assert!(ready.insert_entry(transaction_2, entry(rng, 30, 1)));
+        assert!(ready.insert_entry(transaction_3, entry(rng, 30, 2)));
+        assert!(ready.insert_entry(transaction_4, entry(rng, 10, 1)));
+        let commitment = TransmissionID::Solution(PuzzleCommitment::from_g1_affine(rng.gen()));
+        assert!(ready.insert(commitment, Transmission::Solution(data(rng))));
+
+        // Check that the solution is drained first, followed by the transactions with the highest fees.
+        let transmission_ids = ready.drain(3).into_keys().collect::<Vec<_>>();
+        assert_eq!(transmission_ids, vec![commitment, transaction_3, transaction_2]);
+
+        // Check that the transactions with the same priority are drained in arrival order.
+        let transmission_ids = ready.drain(3).into_keys().collect::<Vec<_>>();
+        assert_eq!(transmission_ids, vec![transaction_1, transaction_4]);
+        assert!(ready.is_empty());
+    }
+
+    #[test]
+    fn test_ready_replace_by_fee() {
+        let rng = &mut TestRng::default();
+
+        // Sample random fake bytes.
+        let data = |rng: &mut TestRng| Data::Buffer(Bytes::from((0..512).map(|_| rng.gen::<u8>()).collect::<Vec<_>>()));
+        // Sample a random transaction ID.
+        let transaction_id = |rng: &mut TestRng| {
+            TransmissionID::<CurrentNetwork>::Transaction(Field::<CurrentNetwork>::rand(rng).into())
+        };
+        // Initialize a transaction entry consuming the given serial numbers.
+        let entry = |rng: &mut TestRng, fee, serial_numbers: &[Field<CurrentNetwork>]| Entry {
+            transmission: Transmission::Transaction(data(rng)),
+            priority: TransmissionPriority { is_solution: false, fee, fee_per_byte: fee / 512 },
+            serial_numbers: serial_numbers.to_vec(),
+            size: 512,
+            fee_payer: None,
+        };
+
+        // Sample the serial numbers.
+        let (serial_number_1, serial_number_2) = (Field::rand(rng), Field::rand(rng));
+
+        // Initialize the ready queue.
+        let ready = Ready::<CurrentNetwork>::new();
+
+        // Insert the original transactions.
+        let (original_1, original_2) = (transaction_id(rng), transaction_id(rng));
+        assert!(ready.insert_entry(original_1, entry(rng, 1000, &[serial_number_1])));
+        assert!(ready.insert_entry(original_2, entry(rng, 1000, &[serial_number_2])));
+
+        // Check that a conflicting transaction with a fee below the fee bump is rejected.
+        let underpriced = transaction_id(rng);
+        assert_eq!(MempoolLimits::default().minimum_replacement_fee(1000), 1100);
+        assert!(!ready.insert_entry(underpriced, entry(rng, 1099, &[serial_number_1])));
+        assert!(ready.contains(original_1));
+        assert!(!ready.contains(underpriced));
+
+        // Check that a transaction conflicting with both originals must exceed both of their fees.
+        let replacement = transaction_id(rng);
+        assert!(ready.insert_entry(replacement, entry(rng, 1100, &[serial_number_1, serial_number_2])));
+        assert_eq!(ready.transmission_ids(), [replacement].into_iter().collect::<IndexSet<_>>());
+
+        // Check that the original transaction cannot replace its replacement.
+        assert!(!ready.insert_entry(original_1, entry(rng, 1000, &[serial_number_1])));
+        assert_eq!(ready.num_transactions(), 1);
+    }
+
+    #[test]
+    fn test_ready_eviction() {
+        let rng = &mut TestRng::default();
+
+        // Sample random fake bytes.
+        let data = |rng: &mut TestRng| Data::Buffer(Bytes::from((0..512).map(|_| rng.gen::<u8>()).collect::<Vec<_>>()));
+        // Sample a random transaction ID.
+        let transaction_id = |rng: &mut TestRng| {
+            TransmissionID::<CurrentNetwork>::Transaction(Field::<CurrentNetwork>::rand(rng).into())
+        };
+        // Initialize a transaction entry.
+        let entry = |rng: &mut TestRng, fee| Entry {
+            transmission: Transmission::Transaction(data(rng)),
+            priority: TransmissionPriority { is_solution: false, fee, fee_per_byte: fee / 512 },
+            serial_numbers: vec![],
+            size: 512,
+            fee_payer: None,
+        };
+
+        for eviction_policy in [EvictionPolicy::LowestFee, EvictionPolicy::Oldest] {
+            // Initialize the ready queue, with room for 3 transactions.
+            let limits =
+                MempoolLimits { max_transactions: 3, max_bytes: 3 * 512, eviction_policy, ..Default::default() };
+            let ready = Ready::<CurrentNetwork>::with_limits(limits);
+
+            // Fill the ready queue, with a solution that does not count towards the limits.
+            let (transaction_1, transaction_2, transaction_3) =
+                (transaction_id(rng), transaction_id(rng), transaction_id(rng));
+            assert!(ready.insert_entry(transaction_1, entry(rng, 2000)));
+            assert!(ready.insert_entry(transaction_2, entry(rng, 1000)));
+            assert!(ready.insert_entry(transaction_3, entry(rng, 3000)));
+            let commitment = TransmissionID::Solution(PuzzleCommitment::from_g1_affine(rng.gen()));
+            assert!(ready.insert(commitment, Transmission::Solution(data(rng))));
+
+            // Check that a new transaction evicts a transaction according to the policy.
+            let transaction_4 = transaction_id(rng);
+            assert!(ready.insert_entry(transaction_4, entry(rng, 1500)));
+            let evicted = match eviction_policy {
+                EvictionPolicy::LowestFee => transaction_2,
+                EvictionPolicy::Oldest => transaction_1,
+            };
+            assert!(!ready.contains(evicted));
+            assert!(ready.contains(commitment));
+            assert_eq!(ready.num_transactions(), 3);
+
+            // Check that a new transaction with the lowest fee is rejected by the lowest-fee policy.
+            let transaction_5 = transaction_id(rng);
+            let is_inserted = ready.insert_entry(transaction_5, entry(rng, 500));
+            assert_eq!(is_inserted, eviction_policy == EvictionPolicy::Oldest);
+            assert_eq!(ready.num_transactions(), 3);
+        }
+    }
+
+    #[test]
+    fn test_ready_payer_limit() {
+        let rng = &mut TestRng::default();
+
+        // Sample random fake bytes.
+        let data = |rng: &mut TestRng| Data::Buffer(Bytes::from((0..512).map(|_| rng.gen::<u8>()).collect::<Vec<_>>()));
+        // Sample a random transaction ID.
+        let transaction_id = |rng: &mut TestRng| {
+            TransmissionID::<CurrentNetwork>::Transaction(Field::<CurrentNetwork>::rand(rng).into())
+        };
+        // Initialize a transaction entry from the given fee payer, consuming the given serial numbers.
+        let entry = |rng: &mut TestRng, fee_payer, serial_numbers: &[Field<CurrentNetwork>]| Entry {
+            transmission: Transmission::Transaction(data(rng)),
+            priority: TransmissionPriority { is_solution: false, fee: 1000, fee_per_byte: 1000 / 512 },
+            serial_numbers: serial_numbers.to_vec(),
+            size: 512,
+            fee_payer,
+        };
+
+        // Initialize the ready queue, with room for 2 transactions per fee payer.
+        let ready =
+            Ready::<CurrentNetwork>::with_limits(MempoolLimits { max_transactions_per_payer: 2, ..Default::default() });
+        let (alice, bob): (Address<CurrentNetwork>, Address<CurrentNetwork>) = (rng.gen(), rng.gen());
+
+        // Fill the pending transactions of Alice.
+        let serial_number = Field::rand(rng);
+        let (transaction_1, transaction_2) = (transaction_id(rng), transaction_id(rng));
+        assert!(ready.insert_entry(transaction_1, entry(rng, Some(alice), &[serial_number])));
+        assert!(ready.insert_entry(transaction_2, entry(rng, Some(alice), &[])));
+
+        // Check that another transaction from Alice is rejected, unlike the ones from Bob or with a private fee.
+        assert!(!ready.insert_entry(transaction_id(rng), entry(rng, Some(alice), &[])));
+        assert!(ready.insert_entry(transaction_id(rng), entry(rng, Some(bob), &[])));
+        assert!(ready.insert_entry(transaction_id(rng), entry(rng, None, &[])));
+        assert_eq!(ready.num_transactions(), 4);
+
+        // Check that Alice can still replace one of her pending transactions.
+        let mut replacement = entry(rng, Some(alice), &[serial_number]);
+        replacement.priority.fee = 2000;
+        let transaction_3 = transaction_id(rng);
+        assert!(ready.insert_entry(transaction_3, replacement));
+        assert!(!ready.contains(transaction_1));
+        assert!(ready.contains(transaction_3));
+        assert_eq!(ready.num_transactions(), 4);
+    }
+
+    #[test]
+    fn test_ready_indexes() {
+        let rng = &mut TestRng::default();
+
+        // Sample random fake bytes.
+        let data = |rng: &mut TestRng| Data::Buffer(Bytes::from((0..512).map(|_| rng.gen::<u8>()).collect::<Vec<_>>()));
+        // Sample a random transaction ID.
+        let transaction_id = |rng: &mut TestRng| {
+            TransmissionID::<CurrentNetwork>::Transaction(Field::<CurrentNetwork>::rand(rng).into())
+        };
+        // Initialize a transaction entry from the given fee payer, consuming the given serial numbers.
+        let entry = |rng: &mut TestRng, fee, fee_payer, serial_numbers: &[Field<CurrentNetwork>]| Entry {
+            transmission: Transmission::Transaction(data(rng)),
+            priority: TransmissionPriority { is_solution: false, fee, fee_per_byte: fee / 512 },
+            serial_numbers: serial_numbers.to_vec(),
+            size: 512,
+            fee_payer,
+        };
+
+        // Initialize the ready queue, with room for 2 transactions.
+        let limits = MempoolLimits { max_transactions: 2, ..Default::default() };
+        let ready = Ready::<CurrentNetwork>::with_limits(limits);
+        let alice: Address<CurrentNetwork> = rng.gen();
+        let serial_number = Field::rand(rng);
+
+        // Insert a transaction, replace it, and evict the replacement with higher fee transactions.
+        let (transaction_1, transaction_2) = (transaction_id(rng), transaction_id(rng));
+        assert!(ready.insert_entry(transaction_1, entry(rng, 1000, Some(alice), &[serial_number])));
+        assert!(ready.insert_entry(transaction_2, entry(rng, 2000, Some(alice), &[serial_number])));
+        assert!(ready.insert_entry(transaction_id(rng), entry(rng, 3000, None, &[])));
+        assert!(ready.insert_entry(transaction_id(rng), entry(rng, 4000, None, &[])));
+        assert!(!ready.contains(transaction_1));
+        assert!(!ready.contains(transaction_2));
+
+        // Check that the indexes only refer to the remaining transactions.
+        {
+            let queue = ready.queue.read();
+            assert_eq!(queue.num_transactions, 2);
+            assert_eq!(queue.num_bytes, 2 * 512);
+            assert_eq!(queue.arrivals.len(), 2);
+            assert_eq!(queue.priorities.len(), 2);
+            assert!(queue.serial_numbers.is_empty());
+            assert!(queue.fee_payers.is_empty());
+        }
+
+        // Check that draining the ready queue empties the indexes.
+        assert_eq!(ready.drain(5).len(), 2);
+        let queue = ready.queue.read();
+        assert!(queue.entries.is_empty() && queue.arrivals.is_empty() && queue.priorities.is_empty());
+        assert_eq!((queue.num_transactions, queue.num_bytes), (0, 0));
+    }
+
+    #[test]
+    fn test_eviction_policy() {
+        assert_eq!("lowest-fee".parse::<EvictionPolicy>().unwrap(), EvictionPolicy::LowestFee);
+        assert_eq!("oldest".parse::<EvictionPolicy>().unwrap(), EvictionPolicy::Oldest);
+        assert!("newest".parse::<EvictionPolicy>().is_err());
+        assert_eq!(EvictionPolicy::LowestFee.to_string(), "lowest-fee");
+    }
 }
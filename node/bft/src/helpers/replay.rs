@@ -0,0 +1,177 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::{helpers::Storage, BFT};
+use snarkos_account::Account;
+use snarkos_node_bft_ledger_service::LedgerService;
+use snarkos_node_bft_storage_service::BFTMemoryService;
+use snarkvm::{
+    ledger::{
+        authority::Authority,
+        narwhal::{BatchHeader, Subdag},
+    },
+    prelude::{ensure, Field, Network, Result},
+};
+
+use serde::Serialize;
+use std::sync::Arc;
+
+/// The anchor round and the ordered certificate IDs of a subdag.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(bound = "")]
+pub struct SubdagSummary<N: Network> {
+    /// The anchor round of the subdag.
+    pub anchor_round: u64,
+    /// The IDs of the certificates in the subdag, in commit order.
+    pub certificate_ids: Vec<Field<N>>,
+}
+
+impl<N: Network> From<&Subdag<N>> for SubdagSummary<N> {
+    fn from(subdag: &Subdag<N>) -> Self {
+        Self {
+            anchor_round: subdag.anchor_round(),
+            certificate_ids: subdag.values().flatten().map(|certificate| certificate.id()).collect(),
+        }
+    }
+}
+
+/// A block whose committed subdag differs from the subdag reproduced by the replay.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(bound = "")]
+pub struct SubdagDivergence<N: Network> {
+    /// The height of the block.
+    pub height: u32,
+    /// The subdag committed in the block.
+    pub expected: SubdagSummary<N>,
+    /// The subdag reproduced by the replay.
+    pub replayed: SubdagSummary<N>,
+}
+
+/// The outcome of a DAG replay.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(bound = "")]
+pub struct ReplayReport<N: Network> {
+    /// The height of the first replayed block.
+    pub start_height: u32,
+    /// The height of the last replayed block.
+    pub end_height: u32,
+    /// The number of blocks whose subdag was reproduced exactly.
+    pub num_matches: usize,
+    /// The blocks whose subdag differs from the reproduced subdag.
+    pub divergences: Vec<SubdagDivergence<N>>,
+    /// The heights of the blocks whose subdag was not reproduced, as the replay ended before it could be committed.
+    pub unreproduced: Vec<u32>,
+}
+
+impl<N: Network> ReplayReport<N> {
+    /// Compares the subdags committed in the blocks `start_height..=end_height` with the replayed subdags, in order.
+    /// Note: The replayed subdags past the committed subdags (i.e. of the blocks after the end height) are ignored.
+    fn new(
+        start_height: u32,
+        end_height: u32,
+        committed: Vec<(u32, SubdagSummary<N>)>,
+        mut replayed: Vec<SubdagSummary<N>>,
+    ) -> Self {
+        let (mut num_matches, mut divergences, mut unreproduced) = (0, Vec::new(), Vec::new());
+        replayed.truncate(committed.len());
+        let mut replayed = replayed.into_iter();
+        for (height, expected) in committed {
+            match replayed.next() {
+                Some(replayed) if replayed == expected => num_matches += 1,
+                Some(replayed) => divergences.push(SubdagDivergence { height, expected, replayed }),
+                None => unreproduced.push(height),
+            }
+        }
+        Self { start_height, end_height, num_matches, divergences, unreproduced }
+    }
+
+    /// Returns `true` if every reproduced subdag matches the subdag committed in its block.
+    pub fn is_consistent(&self) -> bool {
+        self.divergences.is_empty()
+    }
+}
+
+/// Replays the DAG committed in the blocks `start_height..=end_height` of the given ledger through an offline BFT,
+/// and compares the subdags it commits with the subdags committed in the blocks.
+///
+/// The DAG is bootstrapped with the blocks in the GC range before the start height, and the block after
+/// the end height (if any) is also replayed, as the subdag of a block is only committed once the certificates
+/// of the following round are in the DAG.
+pub async fn replay_dag<N: Network>(
+    ledger: Arc<dyn LedgerService<N>>,
+    start_height: u32,
+    end_height: u32,
+) -> Result<ReplayReport<N>> {
+    let latest_height = ledger.latest_block_height();
+    ensure!(start_height <= end_height, "The start height {start_height} exceeds the end height {end_height}");
+    ensure!(end_height <= latest_height, "The end height {end_height} exceeds the latest block height {latest_height}");
+
+    // Retrieve the blocks in the GC range before the start height, and the blocks to replay.
+    let max_gc_rounds = BatchHeader::<N>::MAX_GC_ROUNDS as u64;
+    let max_gc_blocks = u32::try_from(max_gc_rounds)?.saturating_div(2);
+    let preceding = ledger.get_blocks(start_height.saturating_sub(max_gc_blocks)..start_height)?;
+    let blocks = ledger.get_blocks(start_height..end_height.saturating_add(2).min(latest_height + 1))?;
+
+    // Replay the blocks through an offline BFT.
+    let storage = Storage::new(ledger.clone(), Arc::new(BFTMemoryService::new()), max_gc_rounds);
+    let bft = BFT::new(Account::new(&mut rand::thread_rng())?, storage, ledger, None, &[], None, &Default::default())?;
+    let replayed = bft.replay(&preceding, &blocks).await;
+    bft.shut_down().await;
+
+    // Compare the replayed subdags with the committed subdags.
+    let committed = blocks
+        .iter()
+        .filter(|block| block.height() <= end_height)
+        .filter_map(|block| match block.authority() {
+            Authority::Beacon(_) => None,
+            Authority::Quorum(subdag) => Some((block.height(), SubdagSummary::from(subdag))),
+        })
+        .collect();
+    let replayed = replayed?.iter().map(SubdagSummary::from).collect();
+    Ok(ReplayReport::new(start_height, end_height, committed, replayed))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkvm::prelude::{TestRng, Uniform};
+
+    type CurrentNetwork = snarkvm::prelude::MainnetV0;
+
+    /// Samples a subdag summary for the given anchor round.
+    fn sample_summary(anchor_round: u64, rng: &mut TestRng) -> SubdagSummary<CurrentNetwork> {
+        SubdagSummary { anchor_round, certificate_ids: (0..3).map(|_| Field::rand(rng)).collect() }
+    }
+
+    #[test]
+    fn test_replay_report() {
+        let rng = &mut TestRng::default();
+        let (first, second, third) = (sample_summary(2, rng), sample_summary(4, rng), sample_summary(6, rng));
+        let committed = vec![(1, first.clone()), (2, second.clone()), (3, third)];
+
+        // Ensure the matching subdags are counted, and the last block is not reproduced.
+        let report = ReplayReport::new(1, 3, committed.clone(), vec![first.clone(), second.clone()]);
+        assert_eq!(report.num_matches, 2);
+        assert!(report.is_consistent());
+        assert_eq!(report.unreproduced, vec![3]);
+
+        // Ensure a different ordering of the certificates is reported as a divergence.
+        let mut reordered = second.clone();
+        reordered.certificate_ids.reverse();
+        let report = ReplayReport::new(1, 3, committed, vec![first, reordered.clone()]);
+        assert_eq!(report.num_matches, 1);
+        assert!(!report.is_consistent());
+        assert_eq!(report.divergences, vec![SubdagDivergence { height: 2, expected: second, replayed: reordered }]);
+    }
+}
@@ -0,0 +1,185 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::spawn_blocking;
+use snarkos_account::Account;
+use snarkvm::console::{
+    account::{Address, Signature},
+    prelude::*,
+    types::Field,
+};
+
+use serde::{Deserialize, Serialize};
+use std::{sync::Arc, time::Duration};
+
+/// The maximum time to wait for a response from a remote signer.
+const REMOTE_SIGNER_TIMEOUT_IN_SECS: u64 = 5;
+
+/// A signer of the batch headers, batch signatures and handshakes of the BFT, on behalf of a validator.
+#[async_trait]
+pub trait Signer<N: Network>: Send + Sync {
+    /// Returns the address of the validator.
+    fn address(&self) -> Address<N>;
+
+    /// Returns `true` if the signer may currently sign (e.g. if its node holds the validator lease).
+    fn is_active(&self) -> bool {
+        true
+    }
+
+    /// Signs the given field elements (e.g. a batch ID).
+    async fn sign(&self, message: &[Field<N>]) -> Result<Signature<N>>;
+
+    /// Signs the given bytes (e.g. a handshake nonce).
+    async fn sign_bytes(&self, message: &[u8]) -> Result<Signature<N>>;
+}
+
+/// A signer holding the private key of the validator in memory.
+#[derive(Clone)]
+pub struct LocalSigner<N: Network> {
+    /// The account of the validator.
+    account: Account<N>,
+}
+
+impl<N: Network> LocalSigner<N> {
+    /// Initializes a new local signer for the given account.
+    pub const fn new(account: Account<N>) -> Self {
+        Self { account }
+    }
+}
+
+#[async_trait]
+impl<N: Network> Signer<N> for LocalSigner<N> {
+    fn address(&self) -> Address<N> {
+        self.account.address()
+    }
+
+    async fn sign(&self, message: &[Field<N>]) -> Result<Signature<N>> {
+        let (account, message) = (self.account.clone(), message.to_vec());
+        spawn_blocking!(account.sign(&message, &mut rand::thread_rng()))
+    }
+
+    async fn sign_bytes(&self, message: &[u8]) -> Result<Signature<N>> {
+        self.account.sign_bytes(message, &mut rand::thread_rng())
+    }
+}
+
+/// A signer delegating to an external signing service over HTTP, so that the private key of the validator
+/// is never held by the node. The service is expected to serve the following routes, with JSON bodies:
+///
+/// - `GET <url>/address`, returning `{ "address": "aleo1..." }`.
+/// - `POST <url>/sign`, taking `{ "address": "aleo1...", "fields": ["..field"] }`,
+///   and returning `{ "signature": "sign1..." }`.
+/// - `POST <url>/sign_bytes`, taking `{ "address": "aleo1...", "bytes": [..] }`,
+///   and returning `{ "signature": "sign1..." }`.
+///
+/// The service is responsible for the storage of the private key (e.g. in an HSM or a KMS),
+/// and may enforce its own policies (e.g. never signing two batch headers for the same round).
+/// Every returned signature is verified against the address of the validator.
+#[derive(Clone)]
+pub struct RemoteSigner<N: Network> {
+    /// The base URL of the signing service.
+    url: String,
+    /// The address of the validator.
+    address: Address<N>,
+    /// The HTTP client.
+    client: reqwest::Client,
+}
+
+/// The response of the signing service to an address request.
+#[derive(Deserialize)]
+#[serde(bound = "")]
+struct AddressResponse<N: Network> {
+    address: Address<N>,
+}
+
+/// A signing request to the signing service.
+#[derive(Serialize)]
+#[serde(bound = "")]
+struct SignRequest<'a, N: Network> {
+    address: Address<N>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    fields: Option<&'a [Field<N>]>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    bytes: Option<&'a [u8]>,
+}
+
+/// The response of the signing service to a signing request.
+#[derive(Deserialize)]
+#[serde(bound = "")]
+struct SignResponse<N: Network> {
+    signature: Signature<N>,
+}
+
+impl<N: Network> RemoteSigner<N> {
+    /// Connects to the signing service at the given URL, and retrieves the address of the validator.
+    pub async fn connect(url: &str) -> Result<Self> {
+        let url = url.trim_end_matches('/').to_string();
+        let client = reqwest::Client::builder().timeout(Duration::from_secs(REMOTE_SIGNER_TIMEOUT_IN_SECS)).build()?;
+        // Retrieve the address of the validator.
+        let response = client.get(format!("{url}/address")).send().await?.error_for_status()?;
+        let AddressResponse { address } = response.json::<AddressResponse<N>>().await?;
+        Ok(Self { url, address, client })
+    }
+
+    /// Sends the given signing request to the signing service, and verifies the returned signature.
+    async fn request(&self, route: &str, request: SignRequest<'_, N>) -> Result<Signature<N>> {
+        let response = self.client.post(format!("{}/{route}", self.url)).json(&request).send().await?;
+        let SignResponse { signature } = response.error_for_status()?.json::<SignResponse<N>>().await?;
+        // Ensure the signature is valid for the address of the validator.
+        let is_valid = match (request.fields, request.bytes) {
+            (Some(fields), _) => signature.verify(&self.address, fields),
+            (_, Some(bytes)) => signature.verify_bytes(&self.address, bytes),
+            (None, None) => false,
+        };
+        ensure!(is_valid, "The remote signer returned an invalid signature");
+        Ok(signature)
+    }
+}
+
+#[async_trait]
+impl<N: Network> Signer<N> for RemoteSigner<N> {
+    fn address(&self) -> Address<N> {
+        self.address
+    }
+
+    async fn sign(&self, message: &[Field<N>]) -> Result<Signature<N>> {
+        self.request("sign", SignRequest { address: self.address, fields: Some(message), bytes: None }).await
+    }
+
+    async fn sign_bytes(&self, message: &[u8]) -> Result<Signature<N>> {
+        self.request("sign_bytes", SignRequest { address: self.address, fields: None, bytes: Some(message) }).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkvm::prelude::MainnetV0;
+
+    type CurrentNetwork = MainnetV0;
+
+    #[tokio::test]
+    async fn test_local_signer() {
+        let rng = &mut TestRng::default();
+        let account = Account::<CurrentNetwork>::new(rng).unwrap();
+        let signer = LocalSigner::new(account.clone());
+        assert_eq!(signer.address(), account.address());
+
+        // Check the signatures verify against the address of the account.
+        let message = [Field::rand(rng), Field::rand(rng)];
+        assert!(signer.sign(&message).await.unwrap().verify(&account.address(), &message));
+        let bytes = b"nonce";
+        assert!(signer.sign_bytes(bytes).await.unwrap().verify_bytes(&account.address(), bytes));
+    }
+}
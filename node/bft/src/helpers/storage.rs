@@ -12,7 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::helpers::{check_timestamp_for_liveness, fmt_id};
+use crate::helpers::{check_timestamp_for_liveness, fmt_id, CertificateArchive, EquivocationEvidence, EquivocationLog};
 use snarkos_node_bft_ledger_service::LedgerService;
 use snarkos_node_bft_storage_service::StorageService;
 use snarkvm::{
@@ -63,6 +63,8 @@ impl<N: Network> std::ops::Deref for Storage<N> {
 ///   - The certificate ID is inserted into the `transmissions` map.
 /// 3. After a `round` reaches quorum threshold:
 ///  - The next round is inserted into the `current_round`.
+/// 4. After a `round` falls behind both the GC round and the pruning depth:
+///  - The certificates of the `round` are pruned from storage (and persisted to the archive, if one is set).
 #[derive(Debug)]
 pub struct StorageInner<N: Network> {
     /// The ledger service.
@@ -77,6 +79,15 @@ pub struct StorageInner<N: Network> {
     gc_round: AtomicU64,
     /// The maximum number of rounds to keep in storage.
     max_gc_rounds: u64,
+    /// The `round` for which pruning has occurred **up to** (inclusive).
+    pruned_round: AtomicU64,
+    /// The number of rounds to retain in storage (at least the maximum number of GC rounds).
+    pruning_rounds: u64,
+    /// The archive to persist the pruned certificates to, if one is set.
+    archive: Option<CertificateArchive>,
+    /// The time at which the current round started.
+    #[cfg(feature = "metrics")]
+    round_started_at: parking_lot::Mutex<std::time::Instant>,
     /* Once per batch */
     /// The map of `round` to a list of `(certificate ID, batch ID, author)` entries.
     rounds: RwLock<IndexMap<u64, IndexSet<(Field<N>, Field<N>, Address<N>)>>>,
@@ -86,6 +97,8 @@ pub struct StorageInner<N: Network> {
     batch_ids: RwLock<IndexMap<Field<N>, u64>>,
     /// The map of `transmission ID` to `(transmission, certificate IDs)` entries.
     transmissions: Arc<dyn StorageService<N>>,
+    /// The log of the equivocations observed in the batches.
+    equivocations: EquivocationLog<N>,
 }
 
 impl<N: Network> Storage<N> {
@@ -94,6 +107,18 @@ impl<N: Network> Storage<N> {
         ledger: Arc<dyn LedgerService<N>>,
         transmissions: Arc<dyn StorageService<N>>,
         max_gc_rounds: u64,
+    ) -> Self {
+        Self::new_with_pruning(ledger, transmissions, max_gc_rounds, max_gc_rounds, None)
+    }
+
+    /// Initializes a new instance of storage, which retains the given number of rounds (if more than
+    /// the maximum number of GC rounds), and persists the pruned certificates to the given archive.
+    pub fn new_with_pruning(
+        ledger: Arc<dyn LedgerService<N>>,
+        transmissions: Arc<dyn StorageService<N>>,
+        max_gc_rounds: u64,
+        pruning_rounds: u64,
+        archive: Option<CertificateArchive>,
     ) -> Self {
         // Retrieve the current committee.
         let committee = ledger.current_committee().expect("Ledger is missing a committee.");
@@ -107,10 +132,16 @@ impl<N: Network> Storage<N> {
             current_round: Default::default(),
             gc_round: Default::default(),
             max_gc_rounds,
+            pruned_round: Default::default(),
+            pruning_rounds: pruning_rounds.max(max_gc_rounds),
+            archive,
+            #[cfg(feature = "metrics")]
+            round_started_at: parking_lot::Mutex::new(std::time::Instant::now()),
             rounds: Default::default(),
             certificates: Default::default(),
             batch_ids: Default::default(),
             transmissions,
+            equivocations: Default::default(),
         }));
         // Update the storage to the current round.
         storage.update_current_round(current_round);
@@ -145,6 +176,16 @@ impl<N: Network> Storage<N> {
         self.max_gc_rounds
     }
 
+    /// Returns the `round` that pruning has occurred **up to** (inclusive).
+    pub fn pruned_round(&self) -> u64 {
+        self.pruned_round.load(Ordering::SeqCst)
+    }
+
+    /// Returns the number of rounds to retain in storage.
+    pub fn pruning_rounds(&self) -> u64 {
+        self.pruning_rounds
+    }
+
     /// Increments storage to the next round, updating the current round.
     /// Note: This method is only called once per round, upon certification of the primary's batch.
     pub fn increment_to_next_round(&self, current_round: u64) -> Result<u64> {
@@ -171,6 +212,13 @@ impl<N: Network> Storage<N> {
             );
         }
 
+        #[cfg(feature = "metrics")]
+        {
+            let num_certificates = self.get_certificates_for_round(current_round).len();
+            metrics::histogram(metrics::bft::CERTIFICATES_PER_ROUND, num_certificates as f64);
+            metrics::histogram(metrics::bft::ROUND_DURATION, self.round_started_at.lock().elapsed().as_secs_f64());
+        }
+
         // Update the storage to the next round.
         self.update_current_round(next_round);
 
@@ -195,6 +243,11 @@ impl<N: Network> Storage<N> {
     fn update_current_round(&self, next_round: u64) {
         // Update the current round.
         self.current_round.store(next_round, Ordering::SeqCst);
+        // Restart the timer for the round.
+        #[cfg(feature = "metrics")]
+        {
+            *self.round_started_at.lock() = std::time::Instant::now();
+        }
 
         // Fetch the current GC round.
         let current_gc_round = self.gc_round();
@@ -202,18 +255,40 @@ impl<N: Network> Storage<N> {
         let next_gc_round = next_round.saturating_sub(self.max_gc_rounds);
         // Check if storage needs to be garbage collected.
         if next_gc_round > current_gc_round {
-            // Remove the GC round(s) from storage.
-            for gc_round in current_gc_round..=next_gc_round {
-                // Iterate over the certificates for the GC round.
-                for certificate in self.get_certificates_for_round(gc_round).iter() {
-                    // Remove the certificate from storage.
-                    self.remove_certificate(certificate.id());
-                }
-            }
+            // Remove the round(s) that fell behind the pruning depth from storage.
+            // Note: The pruned round never exceeds the GC round, as the pruning depth is at least the GC depth.
+            self.prune(next_round.saturating_sub(self.pruning_rounds));
             // Update the GC round.
             self.gc_round.store(next_gc_round, Ordering::SeqCst);
         }
     }
+
+    /// Removes the certificates (and their transmissions) up to the given round (inclusive) from storage,
+    /// persisting the certificates to the archive, if one is set.
+    fn prune(&self, next_pruned_round: u64) {
+        // Fetch the current pruned round.
+        let current_pruned_round = self.pruned_round();
+        // Check if storage needs to be pruned.
+        if next_pruned_round <= current_pruned_round {
+            return;
+        }
+        // Remove the pruned round(s) from storage.
+        for round in current_pruned_round..=next_pruned_round {
+            // Iterate over the certificates for the round.
+            for certificate in self.get_certificates_for_round(round).iter() {
+                // Persist the certificate to the archive, if one is set.
+                if let Some(archive) = &self.archive {
+                    if let Err(error) = archive.insert(certificate) {
+                        warn!("Failed to archive the certificate '{}' - {error}", fmt_id(certificate.id()));
+                    }
+                }
+                // Remove the certificate from storage.
+                self.remove_certificate(certificate.id());
+            }
+        }
+        // Update the pruned round.
+        self.pruned_round.store(next_pruned_round, Ordering::SeqCst);
+    }
 }
 
 impl<N: Network> Storage<N> {
@@ -436,7 +511,12 @@ impl<N: Network> Storage<N> {
         }
 
         // Ensure the storage does not already contain a certificate for this author in this round.
-        if self.contains_certificate_in_round_from(round, certificate.author()) {
+        if let Some(existing) = self.get_certificate_for_round_with_author(round, certificate.author()) {
+            // Record the conflicting certificates as evidence of an equivocation.
+            self.record_equivocation(EquivocationEvidence::Certificates {
+                first: existing,
+                second: certificate.clone(),
+            });
             bail!("Certificate with this author for round {round} already exists in storage {gc_log}")
         }
 
@@ -678,6 +758,25 @@ impl<N: Network> Storage<N> {
     }
 }
 
+impl<N: Network> Storage<N> {
+    /// Returns the log of the equivocations observed in the batches.
+    pub fn equivocations(&self) -> &EquivocationLog<N> {
+        &self.equivocations
+    }
+
+    /// Records the given evidence of an equivocation.
+    /// Note: The batch headers and certificates are only constructed with valid signatures from their authors,
+    /// so the evidence cannot be fabricated by a third party.
+    pub(crate) fn record_equivocation(&self, evidence: EquivocationEvidence<N>) {
+        let (author, round) = (evidence.author(), evidence.round());
+        match self.equivocations.record(evidence) {
+            Ok(true) => warn!("Recorded an equivocation from validator {author} in round {round}"),
+            Ok(false) => (),
+            Err(e) => warn!("Failed to record an equivocation from validator {author} in round {round} - {e}"),
+        }
+    }
+}
+
 #[cfg(test)]
 impl<N: Network> Storage<N> {
     /// Returns the ledger service.
@@ -937,6 +1036,62 @@ mod tests {
         // Check that the underlying storage representation remains unchanged.
         assert_storage(&storage, &rounds, &certificates, &batch_ids, &transmissions);
     }
+
+    #[test]
+    fn test_prune() {
+        let rng = &mut TestRng::default();
+
+        // Sample a committee.
+        let committee = snarkvm::ledger::committee::test_helpers::sample_committee(rng);
+        // Initialize the ledger.
+        let ledger = Arc::new(MockLedgerService::new(committee));
+        // Initialize the archive.
+        let path = std::env::temp_dir().join(format!("snarkos-storage-archive-{}", std::process::id()));
+        let archive = CertificateArchive::open(&path).unwrap();
+        // Initialize the storage, retaining 2 rounds behind a GC depth of 1 round.
+        let storage = Storage::<CurrentNetwork>::new_with_pruning(
+            ledger,
+            Arc::new(BFTMemoryService::new()),
+            1,
+            2,
+            Some(archive.clone()),
+        );
+        assert_eq!(storage.pruning_rounds(), 2);
+
+        // Insert a certificate for each round.
+        let certificates: Vec<_> = (1..=4)
+            .map(|round| {
+                let certificate =
+                    snarkvm::ledger::narwhal::batch_certificate::test_helpers::sample_batch_certificate_for_round(
+                        round, rng,
+                    );
+                let (missing_transmissions, _) = sample_transmissions(&certificate, rng);
+                storage.insert_certificate_atomic(certificate.clone(), missing_transmissions);
+                certificate
+            })
+            .collect();
+
+        // Advance the round, and ensure the rounds behind the pruning depth are removed synchronously.
+        storage.update_current_round(5);
+        assert_eq!(storage.gc_round(), 4);
+        assert_eq!(storage.pruned_round(), 3);
+        assert!(certificates[..3].iter().all(|certificate| !storage.contains_certificate(certificate.id())));
+        assert!(storage.contains_certificate(certificates[3].id()));
+
+        // Ensure the pruned certificates were persisted to the archive.
+        for certificate in &certificates[..3] {
+            let archived = archive.get_certificates_for_round::<CurrentNetwork>(certificate.round()).unwrap();
+            assert_eq!(archived, vec![certificate.clone()]);
+        }
+        assert!(archive.get_certificates_for_round::<CurrentNetwork>(4).unwrap().is_empty());
+
+        // Advance the round again, and ensure the last round is removed.
+        storage.update_current_round(6);
+        assert_eq!(storage.pruned_round(), 4);
+        assert_storage(&storage, &[], &[], &[], &Default::default());
+
+        std::fs::remove_dir_all(path).unwrap();
+    }
 }
 
 #[cfg(test)]
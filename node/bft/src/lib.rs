@@ -48,6 +48,10 @@ pub const MEMORY_POOL_PORT: u16 = 5000; // port
 
 /// The maximum number of milliseconds to wait before proposing a batch.
 pub const MAX_BATCH_DELAY_IN_MS: u64 = 2500; // ms
+/// The minimum number of milliseconds to wait before proposing a batch.
+pub const MIN_BATCH_DELAY_IN_MS: u64 = 100; // ms
+/// The maximum total size of the transmissions in a batch.
+pub const MAX_BATCH_SIZE_IN_BYTES: usize = 32 * 1024 * 1024; // bytes
 /// The maximum number of milliseconds to wait before timing out on a fetch.
 pub const MAX_FETCH_TIMEOUT_IN_MS: u64 = 3 * MAX_BATCH_DELAY_IN_MS; // ms
 /// The maximum number of seconds allowed for the leader to send their certificate.
@@ -55,7 +59,11 @@ pub const MAX_LEADER_CERTIFICATE_DELAY_IN_SECS: i64 = 2 * MAX_BATCH_DELAY_IN_MS
 /// The maximum number of seconds before the timestamp is considered expired.
 pub const MAX_TIMESTAMP_DELTA_IN_SECS: i64 = 10; // seconds
 /// The maximum number of workers that can be spawned.
-pub const MAX_WORKERS: u8 = 1; // worker(s)
+pub const MAX_WORKERS: u8 = 16; // worker(s)
+/// The default number of workers spawned by each primary.
+pub const DEFAULT_WORKERS: u8 = 1; // worker(s)
+/// The maximum number of milliseconds a primary waits to finish its current round before shutting down.
+pub const MAX_SHUTDOWN_DELAY_IN_MS: u64 = 3 * MAX_BATCH_DELAY_IN_MS; // ms
 
 /// The frequency at which each primary broadcasts a ping to every other node.
 /// Note: If this is updated, be sure to update `MAX_BLOCKS_BEHIND` to correspond properly.
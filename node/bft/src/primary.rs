@@ -21,10 +21,18 @@ use crate::{
         init_sync_channels,
         init_worker_channels,
         now,
+        AdmissionLanes,
+        AuditEvent,
+        AuditLog,
         BFTSender,
+        BftConfig,
+        EquivocationEvidence,
+        Lane,
+        MempoolLimits,
         PrimaryReceiver,
         PrimarySender,
         Proposal,
+        ProposalLimits,
         Storage,
     },
     spawn_blocking,
@@ -32,8 +40,7 @@ use crate::{
     Sync,
     Transport,
     Worker,
-    MAX_BATCH_DELAY_IN_MS,
-    MAX_WORKERS,
+    MIN_BATCH_DELAY_IN_MS,
     PRIMARY_PING_IN_MS,
     WORKER_PING_IN_MS,
 };
@@ -63,7 +70,7 @@ use std::{
     future::Future,
     net::SocketAddr,
     sync::Arc,
-    time::Duration,
+    time::{Duration, Instant},
 };
 use tokio::{
     sync::{Mutex as TMutex, OnceCell},
@@ -87,14 +94,30 @@ pub struct Primary<N: Network> {
     workers: Arc<[Worker<N>]>,
     /// The BFT sender.
     bft_sender: Arc<OnceCell<BFTSender<N>>>,
+    /// The limits of the batches proposed by the primary.
+    limits: ProposalLimits,
+    /// The number of workers to spawn.
+    workers_to_spawn: u8,
+    /// The limits of the memory pool of each worker.
+    mempool_limits: MempoolLimits,
+    /// The admission lanes of the unconfirmed transactions.
+    lanes: Arc<AdmissionLanes>,
+    /// The log to append the consensus events to, if any.
+    audit_log: Option<Arc<AuditLog>>,
     /// The batch proposal, if the primary is currently proposing a batch.
     proposed_batch: Arc<ProposedBatch<N>>,
-    /// The recently-signed batch proposals (a map from the address to the round, batch ID, and signature).
-    signed_proposals: Arc<RwLock<HashMap<Address<N>, (u64, Field<N>, Signature<N>)>>>,
+    /// The recently-signed batch proposals (a map from the address to the batch header and signature).
+    signed_proposals: Arc<RwLock<HashMap<Address<N>, (BatchHeader<N>, Signature<N>)>>>,
     /// The spawned handles.
     handles: Arc<Mutex<Vec<JoinHandle<()>>>>,
     /// The lock for propose_batch.
     propose_lock: Arc<TMutex<u64>>,
+    /// The last round to propose a batch for, if the primary is shutting down.
+    final_round: Arc<RwLock<Option<u64>>>,
+    /// The round in which the node took over the validator lease, if it was a standby node before.
+    takeover_round: Arc<RwLock<Option<u64>>>,
+    /// The time of the latest batch proposal of the primary, if any.
+    latest_proposal_at: Arc<Mutex<Option<Instant>>>,
 }
 
 impl<N: Network> Primary<N> {
@@ -106,11 +129,14 @@ impl<N: Network> Primary<N> {
         ip: Option<SocketAddr>,
         trusted_validators: &[SocketAddr],
         dev: Option<u16>,
+        config: &BftConfig<N>,
     ) -> Result<Self> {
+        // Ensure the configuration is valid.
+        config.check()?;
         // Initialize the gateway.
-        let gateway = Gateway::new(account, ledger.clone(), ip, trusted_validators, dev)?;
+        let gateway = Gateway::new(account, ledger.clone(), ip, trusted_validators, dev, config.signer.clone())?;
         // Initialize the sync module.
-        let sync = Sync::new(gateway.clone(), storage.clone(), ledger.clone());
+        let sync = Sync::new(gateway.clone(), storage.clone(), ledger.clone(), config.max_blocks_behind);
         // Initialize the primary instance.
         Ok(Self {
             sync,
@@ -119,10 +145,18 @@ impl<N: Network> Primary<N> {
             ledger,
             workers: Arc::from(vec![]),
             bft_sender: Default::default(),
+            limits: config.proposal_limits,
+            workers_to_spawn: config.num_workers,
+            mempool_limits: config.mempool_limits,
+            lanes: Arc::new(AdmissionLanes::new(config.admission_limits)),
+            audit_log: config.audit_log.clone(),
             proposed_batch: Default::default(),
             signed_proposals: Default::default(),
             handles: Default::default(),
             propose_lock: Default::default(),
+            final_round: Default::default(),
+            takeover_round: Default::default(),
+            latest_proposal_at: Default::default(),
         })
     }
 
@@ -146,7 +180,7 @@ impl<N: Network> Primary<N> {
         // Construct a map for the workers.
         let mut workers = Vec::new();
         // Initialize the workers.
-        for id in 0..MAX_WORKERS {
+        for id in 0..self.workers_to_spawn {
             // Construct the worker channels.
             let (tx_worker, rx_worker) = init_worker_channels();
             // Construct the worker instance.
@@ -156,6 +190,7 @@ impl<N: Network> Primary<N> {
                 self.storage.clone(),
                 self.ledger.clone(),
                 self.proposed_batch.clone(),
+                self.mempool_limits,
             )?;
             // Run the worker instance.
             worker.run(rx_worker);
@@ -190,6 +225,11 @@ impl<N: Network> Primary<N> {
         self.sync.is_synced()
     }
 
+    /// Pauses or resumes the block sync of the primary.
+    pub fn set_block_sync_paused(&self, paused: bool) {
+        self.sync.set_block_sync_paused(paused)
+    }
+
     /// Returns the gateway.
     pub const fn gateway(&self) -> &Gateway<N> {
         &self.gateway
@@ -215,10 +255,25 @@ impl<N: Network> Primary<N> {
         &self.workers
     }
 
+    /// Appends the given event to the consensus audit log, if it is enabled.
+    pub(crate) fn audit(&self, event: AuditEvent<N>) {
+        if let Some(audit_log) = &self.audit_log {
+            if let Err(e) = audit_log.record(&event) {
+                warn!("Failed to write to the consensus audit log - {e}");
+            }
+        }
+    }
+
     /// Returns the batch proposal of our primary, if one currently exists.
     pub fn proposed_batch(&self) -> &Arc<ProposedBatch<N>> {
         &self.proposed_batch
     }
+
+    /// Returns `true` if the primary may sign in the given round, i.e. unless the node took over the validator lease
+    /// (from another node sharing the key of the validator) in that round or later.
+    fn may_sign_in_round(&self, round: u64) -> bool {
+        self.takeover_round.read().map_or(true, |takeover_round| round > takeover_round)
+    }
 }
 
 impl<N: Network> Primary<N> {
@@ -313,12 +368,37 @@ impl<N: Network> Primary<N> {
 
         // Retrieve the current round.
         let round = self.current_round();
+        // If the primary is shutting down, do not propose a batch past its final round.
+        if let Some(final_round) = *self.final_round.read() {
+            if round > final_round {
+                debug!("Primary is shutting down, skipping the batch proposal for round {round}");
+                return Ok(());
+            }
+        }
+        // If the node took over the validator lease in this round, do not propose a batch until the next round.
+        if !self.may_sign_in_round(round) {
+            debug!(
+                "Primary is safely skipping a batch proposal {}",
+                format!("(took over the validator lease in round {round})").dimmed()
+            );
+            return Ok(());
+        }
+        // If a minimum interval between the batch proposals is set, do not propose a batch until it elapsed.
+        if let Some(latest_proposal_at) = *self.latest_proposal_at.lock() {
+            if latest_proposal_at.elapsed() < Duration::from_millis(self.limits.min_interval_in_ms) {
+                debug!("Primary is safely skipping a batch proposal {}", "(pacing the batch proposals)".dimmed());
+                return Ok(());
+            }
+        }
+        // Rotate the signer, if a rotation is due at this round.
+        // Note: No batch is being proposed at this point, so the signer never changes in the middle of a proposal.
+        self.gateway.update_signer(round);
 
         #[cfg(feature = "metrics")]
         metrics::gauge(metrics::bft::PROPOSAL_ROUND, round as f64);
 
         // Ensure the primary has not proposed a batch for this round before.
-        if self.storage.contains_certificate_in_round_from(round, self.gateway.account().address()) {
+        if self.storage.contains_certificate_in_round_from(round, self.gateway.address()) {
             // If a BFT sender was provided, attempt to advance the current round.
             if let Some(bft_sender) = self.bft_sender.get() {
                 match bft_sender.send_primary_round_to_bft(self.current_round()).await {
@@ -343,7 +423,7 @@ impl<N: Network> Primary<N> {
             // Retrieve the connected validator addresses.
             let mut connected_validators = self.gateway.connected_addresses();
             // Append the primary to the set.
-            connected_validators.insert(self.gateway.account().address());
+            connected_validators.insert(self.gateway.address());
             // If quorum threshold is not reached, return early.
             if !committee_lookback.is_quorum_threshold_reached(&connected_validators) {
                 debug!(
@@ -386,11 +466,13 @@ impl<N: Network> Primary<N> {
         }
 
         // Determined the required number of transmissions per worker.
-        let num_transmissions_per_worker = BatchHeader::<N>::MAX_TRANSMISSIONS_PER_BATCH / self.num_workers() as usize;
+        let num_transmissions_per_worker = self.limits.max_transmissions / self.num_workers() as usize;
         // Initialize the map of transmissions.
         let mut transmissions: IndexMap<_, _> = Default::default();
         // Initialize a tracker for the number of transactions.
         let mut num_transactions = 0;
+        // Initialize a tracker for the total size of the transmissions, in bytes.
+        let mut num_bytes = 0;
         // Take the transmissions from the workers.
         for worker in self.workers.iter() {
             for (id, transmission) in worker.drain(num_transmissions_per_worker) {
@@ -399,6 +481,13 @@ impl<N: Network> Primary<N> {
                     trace!("Proposing - Skipping transmission '{}' - Already in ledger", fmt_id(id));
                     continue;
                 }
+                // Check the transmission fits in the batch, and otherwise return it to the worker.
+                let size = transmission.to_bytes_le().map_or(0, |bytes| bytes.len());
+                if num_bytes + size > self.limits.max_bytes {
+                    trace!("Proposing - Deferring transmission '{}' - The batch is full", fmt_id(id));
+                    worker.reinsert(id, transmission);
+                    continue;
+                }
                 // Check the transmission is still valid.
                 match (id, transmission.clone()) {
                     (TransmissionID::Solution(solution_id), Transmission::Solution(solution)) => {
@@ -425,6 +514,7 @@ impl<N: Network> Primary<N> {
                 }
                 // Insert the transmission into the map.
                 transmissions.insert(id, transmission);
+                num_bytes += size;
             }
         }
         // If there are no unconfirmed transmissions to propose, return early.
@@ -448,22 +538,31 @@ impl<N: Network> Primary<N> {
 
         /* Proceeding to sign & propose the batch. */
         info!("Proposing a batch with {} transmissions for round {round}...", transmissions.len());
+        #[cfg(feature = "metrics")]
+        metrics::histogram(metrics::bft::BATCH_SIZE, transmissions.len() as f64);
 
-        // Retrieve the private key.
-        let private_key = *self.gateway.account().private_key();
+        // Retrieve the signer.
+        let signer = self.gateway.signer();
         // Prepare the transmission IDs.
-        let transmission_ids = transmissions.keys().copied().collect();
+        let transmission_ids = transmissions.keys().copied().collect::<IndexSet<_>>();
         // Prepare the previous batch certificate IDs.
-        let previous_certificate_ids = previous_certificates.into_iter().map(|c| c.id()).collect();
-        // Sign the batch header.
-        let batch_header = spawn_blocking!(BatchHeader::new(
-            &private_key,
+        let previous_certificate_ids = previous_certificates.into_iter().map(|c| c.id()).collect::<IndexSet<_>>();
+        // Compute the batch ID.
+        let (author, timestamp) = (signer.address(), now());
+        let batch_id =
+            BatchHeader::compute_batch_id(author, round, timestamp, &transmission_ids, &previous_certificate_ids)?;
+        // Sign the batch ID.
+        let signature = signer.sign(&[batch_id]).await?;
+        // Construct the batch header.
+        let batch_header = spawn_blocking!(BatchHeader::from(
+            author,
             round,
-            now(),
+            timestamp,
             transmission_ids,
             previous_certificate_ids,
-            &mut rand::thread_rng()
+            signature
         ))?;
+        self.audit(AuditEvent::signature_issued(&batch_header, signature));
         // Construct the proposal.
         let proposal =
             Proposal::new(self.ledger.get_committee_lookback_for_round(round)?, batch_header.clone(), transmissions)?;
@@ -471,6 +570,7 @@ impl<N: Network> Primary<N> {
         self.gateway.broadcast(Event::BatchPropose(batch_header.into()));
         // Set the proposed batch.
         *self.proposed_batch.write() = Some(proposal);
+        *self.latest_proposal_at.lock() = Some(Instant::now());
         Ok(())
     }
 
@@ -494,6 +594,11 @@ impl<N: Network> Primary<N> {
             self.gateway.disconnect(peer_ip);
             bail!("Malicious peer - proposed round {batch_round}, but sent batch for round {}", batch_header.round());
         }
+        self.audit(AuditEvent::proposal_received(peer_ip, &batch_header));
+        // If the node took over the validator lease in this round, do not sign a batch until the next round.
+        if !self.may_sign_in_round(batch_round) {
+            bail!("Took over the validator lease in round {batch_round}, skipping the batch until the next round");
+        }
 
         // Retrieve the batch author.
         let batch_author = batch_header.author();
@@ -517,16 +622,18 @@ impl<N: Network> Primary<N> {
             bail!("Malicious peer - proposed batch from a non-committee member ({batch_author})");
         }
         // Ensure the batch proposal is not from the current primary.
-        if self.gateway.account().address() == batch_author {
+        if self.gateway.address() == batch_author {
             bail!("Invalid peer - proposed batch from myself ({batch_author})");
         }
 
-        // Retrieve the cached round and batch ID for this validator.
-        if let Some((signed_round, signed_batch_id, signature)) =
-            self.signed_proposals.read().get(&batch_author).copied()
-        {
+        // Retrieve the cached batch header for this validator.
+        if let Some((signed_header, signature)) = self.signed_proposals.read().get(&batch_author).cloned() {
+            let (signed_round, signed_batch_id) = (signed_header.round(), signed_header.batch_id());
             // If the round matches and the batch ID differs, then the validator is malicious.
             if signed_round == batch_header.round() && signed_batch_id != batch_header.batch_id() {
+                // Record the conflicting proposals as evidence of an equivocation.
+                let evidence = EquivocationEvidence::Proposals { first: signed_header, second: batch_header };
+                self.storage.record_equivocation(evidence);
                 // Proceed to disconnect the validator.
                 self.gateway.disconnect(peer_ip);
                 bail!("Malicious peer - proposed another batch for the same round ({signed_round})");
@@ -570,9 +677,9 @@ impl<N: Network> Primary<N> {
 
         // Retrieve the batch ID.
         let batch_id = batch_header.batch_id();
-        // Sign the batch ID.
-        let account = self.gateway.account().clone();
-        let signature = spawn_blocking!(account.sign(&[batch_id], &mut rand::thread_rng()))?;
+        // Sign the batch ID, rotating the signer first if a rotation is due at this round.
+        self.gateway.update_signer(self.current_round());
+        let signature = self.gateway.signer().sign(&[batch_id]).await?;
 
         // Ensure the proposal has not already been signed.
         //
@@ -585,16 +692,18 @@ impl<N: Network> Primary<N> {
                 // since, if the peer still has not received the signature, they will request it again,
                 // and the logic at the start of this function will resend the (now cached) signature
                 // to the peer if asked to sign this batch proposal again.
-                if entry.get().0 == batch_round {
+                if entry.get().0.round() == batch_round {
                     return Ok(());
                 }
-                // Otherwise, cache the round, batch ID, and signature for this validator.
-                entry.insert((batch_round, batch_id, signature));
+                // Otherwise, cache the batch header and signature for this validator.
+                self.audit(AuditEvent::signature_issued(&batch_header, signature));
+                entry.insert((batch_header, signature));
             }
             // If the validator has not signed a batch before, then continue.
             std::collections::hash_map::Entry::Vacant(entry) => {
-                // Cache the round, batch ID, and signature for this validator.
-                entry.insert((batch_round, batch_id, signature));
+                // Cache the batch header and signature for this validator.
+                self.audit(AuditEvent::signature_issued(&batch_header, signature));
+                entry.insert((batch_header, signature));
             }
         };
 
@@ -639,7 +748,7 @@ impl<N: Network> Primary<N> {
             bail!("Malicious peer - batch signature is from a different validator ({signer})");
         }
         // Ensure the batch signature is not from the current primary.
-        if self.gateway.account().address() == signer {
+        if self.gateway.address() == signer {
             bail!("Invalid peer - received a batch signature from myself ({signer})");
         }
 
@@ -727,7 +836,7 @@ impl<N: Network> Primary<N> {
             bail!("Malicious peer - Received a batch certificate from an unauthorized validator IP ({peer_ip})");
         }
         // Ensure the batch certificate is not from the current primary.
-        if self.gateway.account().address() == author {
+        if self.gateway.address() == author {
             bail!("Received a batch certificate for myself ({author})");
         }
 
@@ -794,7 +903,7 @@ impl<N: Network> Primary<N> {
                     // Retrieve the latest certificate of the primary.
                     let primary_certificate = {
                         // Retrieve the primary address.
-                        let primary_address = self_.gateway.account().address();
+                        let primary_address = self_.gateway.address();
 
                         // Iterate backwards from the latest round to find the primary certificate.
                         let mut certificate = None;
@@ -882,14 +991,29 @@ impl<N: Network> Primary<N> {
         // Start the batch proposer.
         let self_ = self.clone();
         self.spawn(async move {
+            // Whether the node was a standby validator (i.e. did not hold the validator lease).
+            let mut is_standby = false;
             loop {
                 // Sleep briefly, but longer than if there were no batch.
-                tokio::time::sleep(Duration::from_millis(MAX_BATCH_DELAY_IN_MS)).await;
+                tokio::time::sleep(Duration::from_millis(self_.limits.interval_in_ms)).await;
                 // If the primary is not synced, then do not propose a batch.
                 if !self_.sync.is_synced() {
                     debug!("Skipping batch proposal {}", "(node is syncing)".dimmed());
                     continue;
                 }
+                // If the signer is not active, then do not propose a batch.
+                if !self_.gateway.signer().is_active() {
+                    debug!("Skipping batch proposal {}", "(node is a standby validator)".dimmed());
+                    is_standby = true;
+                    continue;
+                }
+                // If the node just took over the validator lease, record the round, as the node that previously
+                // held the lease may have signed in this round.
+                if std::mem::take(&mut is_standby) {
+                    let round = self_.current_round();
+                    info!("Took over the validator lease in round {round}, resuming signing in the next round");
+                    *self_.takeover_round.write() = Some(round);
+                }
                 // If there is no proposed batch, attempt to propose a batch.
                 // Note: Do NOT spawn a task around this function call. Proposing a batch is a critical path,
                 // and only one batch needs be proposed at a time.
@@ -908,6 +1032,11 @@ impl<N: Network> Primary<N> {
                     trace!("Skipping a batch proposal from '{peer_ip}' {}", "(node is syncing)".dimmed());
                     continue;
                 }
+                // If the signer is not active, then do not sign the batch.
+                if !self_.gateway.signer().is_active() {
+                    trace!("Skipping a batch proposal from '{peer_ip}' {}", "(node is a standby validator)".dimmed());
+                    continue;
+                }
                 // Spawn a task to process the proposed batch.
                 let self_ = self_.clone();
                 tokio::spawn(async move {
@@ -997,10 +1126,8 @@ impl<N: Network> Primary<N> {
                 };
                 let self_ = self_.clone();
                 tokio::spawn(async move {
-                    // Retrieve the worker.
-                    let worker = &self_.workers[worker_id as usize];
-                    // Process the unconfirmed transaction.
-                    let result = worker.process_unconfirmed_transaction(transaction_id, transaction).await;
+                    // Process the unconfirmed transaction in its admission lane.
+                    let result = self_.process_unconfirmed_transaction(worker_id, transaction_id, transaction).await;
                     // Send the result to the callback.
                     callback.send(result).ok();
                 });
@@ -1017,6 +1144,8 @@ impl<N: Network> Primary<N> {
         };
         // If the batch is expired, clear the proposed batch.
         if is_expired {
+            #[cfg(feature = "metrics")]
+            metrics::increment_counter_with_labels(metrics::bft::TIMEOUTS, &[("kind", "proposal".to_string())]);
             // Reset the proposed batch.
             let proposal = self.proposed_batch.write().take();
             if let Some(proposal) = proposal {
@@ -1105,6 +1234,7 @@ impl<N: Network> Primary<N> {
     async fn store_and_broadcast_certificate(&self, proposal: &Proposal<N>, committee: &Committee<N>) -> Result<()> {
         // Create the batch certificate and transmissions.
         let (certificate, transmissions) = proposal.to_certificate(committee)?;
+        self.audit(AuditEvent::certificate_formed(&certificate));
         // Convert the transmissions into a HashMap.
         // Note: Do not change the `Proposal` to use a HashMap. The ordering there is necessary for safety.
         let transmissions = transmissions.into_iter().collect::<HashMap<_, _>>();
@@ -1142,6 +1272,32 @@ impl<N: Network> Primary<N> {
         })
     }
 
+    /// Processes the given unconfirmed transaction in its admission lane, so that the deployments
+    /// and the executions are admitted and verified with separate capacities.
+    async fn process_unconfirmed_transaction(
+        &self,
+        worker_id: u8,
+        transaction_id: N::TransactionID,
+        transaction: Data<Transaction<N>>,
+    ) -> Result<()> {
+        // Deserialize the transaction once, for its admission lane, its basic checks and its priority in the ready queue.
+        let deserialized = {
+            let transaction = transaction.clone();
+            spawn_blocking!(transaction.deserialize_blocking())?
+        };
+        // Admit the transaction in its lane.
+        let lane = Lane::of(&deserialized);
+        let Some(admission) = self.lanes.try_admit(lane) else {
+            bail!("Transaction '{}' was rejected, as the {lane} lane is full", fmt_id(transaction_id));
+        };
+        // Wait for a verification slot in the lane.
+        let _verifying = admission.verify().await?;
+        // Process the unconfirmed transaction.
+        self.workers[worker_id as usize]
+            .process_deserialized_transaction(transaction_id, transaction, Some(deserialized))
+            .await
+    }
+
     /// Re-inserts the transmissions from the proposal into the workers.
     fn reinsert_transmissions_into_workers(&self, proposal: Proposal<N>) -> Result<()> {
         // Re-insert the transmissions into the workers.
@@ -1389,6 +1545,34 @@ impl<N: Network> Primary<N> {
         self.handles.lock().push(tokio::spawn(future));
     }
 
+    /// Finishes the current round before shutting down, waiting (up to the given timeout) for the round to advance
+    /// and for the batch proposal of the primary (if any) to be certified or expire.
+    /// In the meantime, the primary keeps signing and propagating the certificates of the round,
+    /// but does not propose a batch for the next round.
+    pub async fn finish_round(&self, timeout: Duration) {
+        // If the primary is not synced, there is no round to finish.
+        if !self.is_synced() {
+            return;
+        }
+        // Retrieve the current round, and set it as the final round.
+        let round = self.current_round();
+        *self.final_round.write() = Some(round);
+        info!("Finishing round {round} before shutting down the primary...");
+
+        // Wait for the round to advance, and for the batch proposal to be certified or expire.
+        let is_finished = || self.current_round() > round && self.proposed_batch.read().is_none();
+        let result = tokio::time::timeout(timeout, async {
+            while !is_finished() {
+                tokio::time::sleep(Duration::from_millis(MIN_BATCH_DELAY_IN_MS)).await;
+            }
+        })
+        .await;
+        match result {
+            Ok(()) => info!("Finished round {round}"),
+            Err(_) => warn!("Timed out while finishing round {round} - shutting down the primary"),
+        }
+    }
+
     /// Shuts down the primary.
     pub async fn shut_down(&self) {
         info!("Shutting down the primary...");
@@ -1442,7 +1626,7 @@ mod tests {
         let storage = Storage::new(ledger.clone(), Arc::new(BFTMemoryService::new()), 10);
 
         // Initialize the primary.
-        let mut primary = Primary::new(account, storage, ledger, None, &[], None).unwrap();
+        let mut primary = Primary::new(account, storage, ledger, None, &[], None, &Default::default()).unwrap();
 
         // Construct a worker instance.
         primary.workers = Arc::from([Worker::new(
@@ -1451,6 +1635,7 @@ mod tests {
             primary.storage.clone(),
             primary.ledger.clone(),
             primary.proposed_batch.clone(),
+            Default::default(),
         )
         .unwrap()]);
         for a in accounts.iter() {
@@ -1655,6 +1840,97 @@ mod tests {
         assert!(primary.proposed_batch.read().is_some());
     }
 
+    #[tokio::test]
+    async fn test_propose_batch_past_final_round() {
+        let round = 3;
+        let mut rng = TestRng::default();
+        let (primary, accounts) = primary_without_handlers(&mut rng).await;
+
+        // Fill primary storage.
+        store_certificate_chain(&primary, &accounts, round, &mut rng);
+
+        // Generate a solution and a transaction.
+        let (solution_commitment, solution) = sample_unconfirmed_solution(&mut rng);
+        let (transaction_id, transaction) = sample_unconfirmed_transaction(&mut rng);
+
+        // Store it on one of the workers.
+        primary.workers[0].process_unconfirmed_solution(solution_commitment, solution).await.unwrap();
+        primary.workers[0].process_unconfirmed_transaction(transaction_id, transaction).await.unwrap();
+
+        // Set the final round to the previous round, as if the primary was shutting down.
+        *primary.final_round.write() = Some(round - 1);
+
+        // Ensure the primary does not propose a batch past its final round.
+        assert!(primary.propose_batch().await.is_ok());
+        assert!(primary.proposed_batch.read().is_none());
+
+        // Ensure the primary proposes a batch for its final round.
+        *primary.final_round.write() = Some(round);
+        assert!(primary.propose_batch().await.is_ok());
+        assert!(primary.proposed_batch.read().is_some());
+    }
+
+    #[tokio::test]
+    async fn test_propose_batch_after_takeover() {
+        let round = 3;
+        let mut rng = TestRng::default();
+        let (primary, accounts) = primary_without_handlers(&mut rng).await;
+
+        // Fill primary storage.
+        store_certificate_chain(&primary, &accounts, round, &mut rng);
+
+        // Generate a solution and a transaction.
+        let (solution_commitment, solution) = sample_unconfirmed_solution(&mut rng);
+        let (transaction_id, transaction) = sample_unconfirmed_transaction(&mut rng);
+
+        // Store it on one of the workers.
+        primary.workers[0].process_unconfirmed_solution(solution_commitment, solution).await.unwrap();
+        primary.workers[0].process_unconfirmed_transaction(transaction_id, transaction).await.unwrap();
+
+        // Take over the validator lease in the current round.
+        *primary.takeover_round.write() = Some(round);
+
+        // Ensure the primary does not propose a batch in the round it took over the validator lease in.
+        assert!(primary.propose_batch().await.is_ok());
+        assert!(primary.proposed_batch.read().is_none());
+
+        // Ensure the primary proposes a batch once the takeover round has passed.
+        *primary.takeover_round.write() = Some(round - 1);
+        assert!(primary.propose_batch().await.is_ok());
+        assert!(primary.proposed_batch.read().is_some());
+    }
+
+    #[tokio::test]
+    async fn test_propose_batch_min_interval() {
+        let round = 3;
+        let mut rng = TestRng::default();
+        let (mut primary, accounts) = primary_without_handlers(&mut rng).await;
+
+        // Fill primary storage.
+        store_certificate_chain(&primary, &accounts, round, &mut rng);
+
+        // Generate a solution and a transaction.
+        let (solution_commitment, solution) = sample_unconfirmed_solution(&mut rng);
+        let (transaction_id, transaction) = sample_unconfirmed_transaction(&mut rng);
+
+        // Store it on one of the workers.
+        primary.workers[0].process_unconfirmed_solution(solution_commitment, solution).await.unwrap();
+        primary.workers[0].process_unconfirmed_transaction(transaction_id, transaction).await.unwrap();
+
+        // Set a minimum interval between the batch proposals, as if a proposal was just made.
+        primary.limits.min_interval_in_ms = 60_000;
+        *primary.latest_proposal_at.lock() = Some(Instant::now());
+
+        // Ensure the primary does not propose a batch until the minimum interval elapsed.
+        assert!(primary.propose_batch().await.is_ok());
+        assert!(primary.proposed_batch.read().is_none());
+
+        // Ensure the primary proposes a batch once the minimum interval elapsed.
+        *primary.latest_proposal_at.lock() = Some(Instant::now() - Duration::from_secs(60));
+        assert!(primary.propose_batch().await.is_ok());
+        assert!(primary.proposed_batch.read().is_some());
+    }
+
     #[tokio::test]
     async fn test_propose_batch_in_round() {
         let round = 3;
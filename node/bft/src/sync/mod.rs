@@ -57,11 +57,18 @@ pub struct Sync<N: Network> {
     response_lock: Arc<TMutex<()>>,
     /// The sync lock.
     sync_lock: Arc<TMutex<()>>,
+    /// The maximum number of blocks the ledger may be behind the network tip for the primary to participate.
+    max_blocks_behind: u32,
 }
 
 impl<N: Network> Sync<N> {
     /// Initializes a new sync instance.
-    pub fn new(gateway: Gateway<N>, storage: Storage<N>, ledger: Arc<dyn LedgerService<N>>) -> Self {
+    pub fn new(
+        gateway: Gateway<N>,
+        storage: Storage<N>,
+        ledger: Arc<dyn LedgerService<N>>,
+        max_blocks_behind: u32,
+    ) -> Self {
         // Initialize the block sync module.
         let block_sync = BlockSync::new(BlockSyncMode::Gateway, ledger.clone());
         // Return the sync instance.
@@ -75,6 +82,7 @@ impl<N: Network> Sync<N> {
             handles: Default::default(),
             response_lock: Default::default(),
             sync_lock: Default::default(),
+            max_blocks_behind,
         }
     }
 
@@ -382,11 +390,19 @@ impl<N: Network> Sync<N> {
 // Methods to assist with the block sync module.
 impl<N: Network> Sync<N> {
     /// Returns `true` if the node is synced and has connected peers.
+    ///
+    /// The node is synced once the block locators of its peers are known, and its ledger
+    /// is within the configured number of blocks of the network tip.
     pub fn is_synced(&self) -> bool {
         if self.gateway.number_of_connected_peers() == 0 {
             return false;
         }
-        self.block_sync.is_block_synced()
+        self.block_sync.num_blocks_behind().is_some_and(|num_blocks_behind| num_blocks_behind <= self.max_blocks_behind)
+    }
+
+    /// Pauses or resumes the block sync, e.g. at the request of an operator.
+    pub fn set_block_sync_paused(&self, paused: bool) {
+        self.block_sync.set_paused(paused)
     }
 
     /// Returns `true` if the node is in gateway mode.
@@ -430,7 +446,11 @@ impl<N: Network> Sync<N> {
             // If the certificate was fetched, return it.
             Ok(result) => Ok(result?),
             // If the certificate was not fetched, return an error.
-            Err(e) => bail!("Unable to fetch certificate {} - (timeout) {e}", fmt_id(certificate_id)),
+            Err(e) => {
+                #[cfg(feature = "metrics")]
+                metrics::increment_counter_with_labels(metrics::bft::TIMEOUTS, &[("kind", "certificate".to_string())]);
+                bail!("Unable to fetch certificate {} - (timeout) {e}", fmt_id(certificate_id))
+            }
         }
     }
 
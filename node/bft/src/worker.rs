@@ -14,9 +14,10 @@
 
 use crate::{
     events::{Event, TransmissionRequest, TransmissionResponse},
-    helpers::{fmt_id, Pending, Ready, Storage, WorkerReceiver, NUM_REDUNDANT_REQUESTS},
+    helpers::{fmt_id, MempoolLimits, Pending, Ready, Storage, WorkerReceiver, NUM_REDUNDANT_REQUESTS},
     ProposedBatch,
     Transport,
+    DEFAULT_WORKERS,
     MAX_FETCH_TIMEOUT_IN_MS,
     MAX_WORKERS,
 };
@@ -63,6 +64,7 @@ impl<N: Network> Worker<N> {
         storage: Storage<N>,
         ledger: Arc<dyn LedgerService<N>>,
         proposed_batch: Arc<ProposedBatch<N>>,
+        mempool_limits: MempoolLimits,
     ) -> Result<Self> {
         // Ensure the worker ID is valid.
         ensure!(id < MAX_WORKERS, "Invalid worker ID '{id}'");
@@ -73,7 +75,7 @@ impl<N: Network> Worker<N> {
             storage,
             ledger,
             proposed_batch,
-            ready: Default::default(),
+            ready: Ready::with_limits(mempool_limits),
             pending: Default::default(),
             handles: Default::default(),
         })
@@ -93,9 +95,9 @@ impl<N: Network> Worker<N> {
 }
 
 impl<N: Network> Worker<N> {
-    /// The maximum number of transmissions allowed in a worker.
-    pub const MAX_TRANSMISSIONS_PER_WORKER: usize =
-        BatchHeader::<N>::MAX_TRANSMISSIONS_PER_BATCH / MAX_WORKERS as usize;
+    /// The maximum number of transmissions allowed in a worker, before it stops fetching the transmissions
+    /// advertised in the pings of its peers.
+    pub const MAX_TRANSMISSIONS_PER_WORKER: usize = BatchHeader::<N>::MAX_TRANSMISSIONS_PER_BATCH;
     /// The maximum number of transmissions allowed in a worker ping.
     pub const MAX_TRANSMISSIONS_PER_WORKER_PING: usize = BatchHeader::<N>::MAX_TRANSMISSIONS_PER_BATCH / 10;
 
@@ -195,7 +197,7 @@ impl<N: Network> Worker<N> {
         Ok((transmission_id, transmission))
     }
 
-    /// Removes up to the specified number of transmissions from the ready queue, and returns them.
+    /// Removes up to the specified number of transmissions from the ready queue, by priority, and returns them.
     pub(crate) fn drain(&self, num_transmissions: usize) -> impl Iterator<Item = (TransmissionID<N>, Transmission<N>)> {
         self.ready.drain(num_transmissions).into_iter()
     }
@@ -324,6 +326,17 @@ impl<N: Network> Worker<N> {
         &self,
         transaction_id: N::TransactionID,
         transaction: Data<Transaction<N>>,
+    ) -> Result<()> {
+        self.process_deserialized_transaction(transaction_id, transaction, None).await
+    }
+
+    /// Handles the incoming unconfirmed transaction, given its deserialized form (if known), so that it is
+    /// not deserialized again for the basic checks and for its priority in the ready queue.
+    pub(crate) async fn process_deserialized_transaction(
+        &self,
+        transaction_id: N::TransactionID,
+        transaction: Data<Transaction<N>>,
+        deserialized: Option<Transaction<N>>,
     ) -> Result<()> {
         // Construct the transmission.
         let transmission = Transmission::Transaction(transaction.clone());
@@ -334,13 +347,27 @@ impl<N: Network> Worker<N> {
             bail!("Transaction '{}' already exists.", fmt_id(transaction_id));
         }
         // Check that the transaction is well-formed and unique.
-        if let Err(e) = self.ledger.check_transaction_basic(transaction_id, transaction).await {
+        let checked = match &deserialized {
+            Some(deserialized) => Data::Object(deserialized.clone()),
+            None => transaction.clone(),
+        };
+        if let Err(e) = self.ledger.check_transaction_basic(transaction_id, checked).await {
             bail!("Invalid unconfirmed transaction '{}': {e}", fmt_id(transaction_id));
         }
         // Adds the transaction to the ready queue.
-        if self.ready.insert(&transaction_id, transmission) {
-            trace!("Worker {} - Added unconfirmed transaction '{}'", self.id, fmt_id(transaction_id));
+        // Note: This fails if the transaction conflicts with a pending transaction, or if the memory pool is full,
+        // and its fee is too low, or if its fee payer has too many pending transactions.
+        let is_inserted = match &deserialized {
+            Some(deserialized) => self.ready.insert_transaction(transaction_id, transaction, deserialized),
+            None => self.ready.insert(&transaction_id, transmission),
+        };
+        if !is_inserted {
+            bail!(
+                "Transaction '{}' was rejected, as its fee is too low or its payer has too many pending transactions",
+                fmt_id(transaction_id)
+            );
         }
+        trace!("Worker {} - Added unconfirmed transaction '{}'", self.id, fmt_id(transaction_id));
         Ok(())
     }
 }
@@ -402,7 +429,11 @@ impl<N: Network> Worker<N> {
             // If the transmission was fetched, return it.
             Ok(result) => Ok((transmission_id, result?)),
             // If the transmission was not fetched, return an error.
-            Err(e) => bail!("Unable to fetch transmission - (timeout) {e}"),
+            Err(e) => {
+                #[cfg(feature = "metrics")]
+                metrics::increment_counter_with_labels(metrics::bft::TIMEOUTS, &[("kind", "transmission".to_string())]);
+                bail!("Unable to fetch transmission - (timeout) {e}")
+            }
         }
     }
 
@@ -543,7 +574,8 @@ mod tests {
         let storage = Storage::<CurrentNetwork>::new(ledger.clone(), Arc::new(BFTMemoryService::new()), 1);
 
         // Create the Worker.
-        let worker = Worker::new(0, Arc::new(gateway), storage, ledger, Default::default()).unwrap();
+        let worker =
+            Worker::new(0, Arc::new(gateway), storage, ledger, Default::default(), Default::default()).unwrap();
         let data = |rng: &mut TestRng| Data::Buffer(Bytes::from((0..512).map(|_| rng.gen::<u8>()).collect::<Vec<_>>()));
         let transmission_id = TransmissionID::Solution(PuzzleCommitment::from_g1_affine(rng.gen()));
         let peer_ip = SocketAddr::from(([127, 0, 0, 1], 1234));
@@ -579,7 +611,8 @@ mod tests {
         let storage = Storage::<CurrentNetwork>::new(ledger.clone(), Arc::new(BFTMemoryService::new()), 1);
 
         // Create the Worker.
-        let worker = Worker::new(0, Arc::new(gateway), storage, ledger, Default::default()).unwrap();
+        let worker =
+            Worker::new(0, Arc::new(gateway), storage, ledger, Default::default(), Default::default()).unwrap();
         let transmission_id = TransmissionID::Solution(PuzzleCommitment::from_g1_affine(rng.gen()));
         let worker_ = worker.clone();
         let peer_ip = SocketAddr::from(([127, 0, 0, 1], 1234));
@@ -615,7 +648,8 @@ mod tests {
         let storage = Storage::<CurrentNetwork>::new(ledger.clone(), Arc::new(BFTMemoryService::new()), 1);
 
         // Create the Worker.
-        let worker = Worker::new(0, Arc::new(gateway), storage, ledger, Default::default()).unwrap();
+        let worker =
+            Worker::new(0, Arc::new(gateway), storage, ledger, Default::default(), Default::default()).unwrap();
         let puzzle = PuzzleCommitment::from_g1_affine(rng.gen());
         let transmission_id = TransmissionID::Solution(puzzle);
         let worker_ = worker.clone();
@@ -653,7 +687,8 @@ mod tests {
         let storage = Storage::<CurrentNetwork>::new(ledger.clone(), Arc::new(BFTMemoryService::new()), 1);
 
         // Create the Worker.
-        let worker = Worker::new(0, Arc::new(gateway), storage, ledger, Default::default()).unwrap();
+        let worker =
+            Worker::new(0, Arc::new(gateway), storage, ledger, Default::default(), Default::default()).unwrap();
         let puzzle = PuzzleCommitment::from_g1_affine(rng.gen());
         let transmission_id = TransmissionID::Solution(puzzle);
         let worker_ = worker.clone();
@@ -691,7 +726,8 @@ mod tests {
         let storage = Storage::<CurrentNetwork>::new(ledger.clone(), Arc::new(BFTMemoryService::new()), 1);
 
         // Create the Worker.
-        let worker = Worker::new(0, Arc::new(gateway), storage, ledger, Default::default()).unwrap();
+        let worker =
+            Worker::new(0, Arc::new(gateway), storage, ledger, Default::default(), Default::default()).unwrap();
         let transaction_id: <CurrentNetwork as Network>::TransactionID = Field::<CurrentNetwork>::rand(&mut rng).into();
         let transmission_id = TransmissionID::Transaction(transaction_id);
         let worker_ = worker.clone();
@@ -729,7 +765,8 @@ mod tests {
         let storage = Storage::<CurrentNetwork>::new(ledger.clone(), Arc::new(BFTMemoryService::new()), 1);
 
         // Create the Worker.
-        let worker = Worker::new(0, Arc::new(gateway), storage, ledger, Default::default()).unwrap();
+        let worker =
+            Worker::new(0, Arc::new(gateway), storage, ledger, Default::default(), Default::default()).unwrap();
         let transaction_id: <CurrentNetwork as Network>::TransactionID = Field::<CurrentNetwork>::rand(&mut rng).into();
         let transmission_id = TransmissionID::Transaction(transaction_id);
         let worker_ = worker.clone();
@@ -784,7 +821,8 @@ mod prop_tests {
     ) {
         let committee = new_test_committee(4);
         let ledger: Arc<dyn LedgerService<CurrentNetwork>> = Arc::new(MockLedgerService::new(committee));
-        let worker = Worker::new(id, Arc::new(gateway), storage, ledger, Default::default()).unwrap();
+        let worker =
+            Worker::new(id, Arc::new(gateway), storage, ledger, Default::default(), Default::default()).unwrap();
         assert_eq!(worker.id(), id);
     }
 
@@ -796,7 +834,7 @@ mod prop_tests {
     ) {
         let committee = new_test_committee(4);
         let ledger: Arc<dyn LedgerService<CurrentNetwork>> = Arc::new(MockLedgerService::new(committee));
-        let worker = Worker::new(id, Arc::new(gateway), storage, ledger, Default::default());
+        let worker = Worker::new(id, Arc::new(gateway), storage, ledger, Default::default(), Default::default());
         // TODO once Worker implements Debug, simplify this with `unwrap_err`
         if let Err(error) = worker {
             assert_eq!(error.to_string(), format!("Invalid worker ID '{}'", id));
@@ -16,9 +16,20 @@
 // https://github.com/rust-lang/rust-clippy/issues/6446
 #![allow(clippy::await_holding_lock)]
 
+use crate::{
+    cache::BundleCache,
+    mirrors::Mirrors,
+    progress::ProgressTracker,
+    spill::SpillQueue,
+    CdnClient,
+    CdnConfig,
+    CdnSyncObserver,
+    ErrorBudget,
+    ErrorBudgetExhausted,
+};
 use snarkvm::prelude::{
     block::Block,
-    store::{cow_to_copied, ConsensusStorage},
+    store::{cow_to_copied, BlockStorage, BlockStore, ConsensusStorage},
     Deserialize,
     DeserializeOwned,
     Ledger,
@@ -26,51 +37,220 @@ use snarkvm::prelude::{
     Serialize,
 };
 
-use anyhow::{anyhow, bail, Result};
+use anyhow::{anyhow, bail, ensure, Result};
 use colored::Colorize;
 use parking_lot::Mutex;
-use reqwest::Client;
+use reqwest::{header, StatusCode};
 use std::{
     cmp,
+    fmt,
+    ops::Range,
     sync::{
         atomic::{AtomicBool, AtomicU32, Ordering},
         Arc,
     },
     time::{Duration, Instant},
 };
+use tokio_util::sync::CancellationToken;
 
 /// The number of blocks per file.
-const BLOCKS_PER_FILE: u32 = 50;
+pub(crate) const BLOCKS_PER_FILE: u32 = 50;
 /// The desired number of concurrent requests to the CDN.
-const CONCURRENT_REQUESTS: u32 = 16;
+pub(crate) const CONCURRENT_REQUESTS: u32 = 16;
 /// Maximum number of pending sync blocks.
 const MAXIMUM_PENDING_BLOCKS: u32 = BLOCKS_PER_FILE * CONCURRENT_REQUESTS * 2;
+/// Maximum number of pending sync blocks spilled to disk.
+const MAXIMUM_SPILLED_BLOCKS: u32 = MAXIMUM_PENDING_BLOCKS * 50;
 /// Maximum number of attempts for a request to the CDN.
 const MAXIMUM_REQUEST_ATTEMPTS: u8 = 10;
+/// Maximum delay honored from a `Retry-After` header.
+const MAXIMUM_RETRY_AFTER: Duration = Duration::from_secs(300);
+/// The interval at which the shutdown flag is checked, to cancel the in-flight requests.
+const SHUTDOWN_POLL_INTERVAL: Duration = Duration::from_millis(100);
 /// The supported network.
 const NETWORK_ID: u16 = 3;
 
+/// A representation of the 'latest.json' file object.
+#[derive(Deserialize, Serialize, Debug)]
+pub(crate) struct LatestState {
+    pub(crate) exclusive_height: u32,
+    pub(crate) inclusive_height: u32,
+    pub(crate) hash: String,
+}
+
+impl LatestState {
+    /// Returns the `(inclusive, exclusive)` heights up to which blocks are loaded from the CDN,
+    /// leaving the given safety margin of blocks below the tip reported by the CDN.
+    fn cdn_height(&self, safety_margin: u32) -> Result<(u32, u32)> {
+        // Decrement the tip by the safety margin to ensure the CDN is caught up.
+        let exclusive = self.exclusive_height.saturating_sub(safety_margin);
+        // Ensure there is at least one block to load.
+        match exclusive.checked_sub(1) {
+            Some(inclusive) => Ok((inclusive, exclusive)),
+            None => bail!(
+                "The CDN has no blocks available (exclusive height {}, safety margin {safety_margin})",
+                self.exclusive_height
+            ),
+        }
+    }
+
+    /// Ensures the given block matches the hash in 'latest.json', if it is the block at the inclusive height.
+    fn check_block<N: Network>(&self, block: &Block<N>) -> Result<()> {
+        if block.height() == self.inclusive_height && block.hash().to_string() != self.hash {
+            return Err(LatestHashMismatch {
+                height: block.height(),
+                expected: self.hash.clone(),
+                found: block.hash().to_string(),
+            }
+            .into());
+        }
+        Ok(())
+    }
+}
+
+/// The error returned when a synced block does not match the hash reported in the CDN's 'latest.json'.
+/// This indicates that the CDN is corrupted or malicious, and the sync must not be resumed from it.
+#[derive(Debug)]
+pub struct LatestHashMismatch {
+    /// The height of the mismatching block.
+    pub height: u32,
+    /// The hash reported in 'latest.json'.
+    pub expected: String,
+    /// The hash of the synced block.
+    pub found: String,
+}
+
+impl fmt::Display for LatestHashMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Block {} from the CDN has hash '{}', but 'latest.json' reports '{}'",
+            self.height, self.found, self.expected
+        )
+    }
+}
+
+impl std::error::Error for LatestHashMismatch {}
+
+/// The error returned when the CDN (or a proxy in front of it) rate-limits a request.
+#[derive(Debug)]
+pub struct RateLimited {
+    /// The HTTP status code of the response (i.e. 429 or 503).
+    pub status: u16,
+    /// The delay requested by the `Retry-After` header, if any.
+    pub retry_after: Option<Duration>,
+}
+
+impl RateLimited {
+    /// Returns the rate limit signalled by the given response, if any.
+    fn from_response(response: &reqwest::Response) -> Option<Self> {
+        match response.status() {
+            StatusCode::TOO_MANY_REQUESTS | StatusCode::SERVICE_UNAVAILABLE => Some(Self {
+                status: response.status().as_u16(),
+                retry_after: response
+                    .headers()
+                    .get(header::RETRY_AFTER)
+                    .and_then(|value| value.to_str().ok())
+                    .and_then(parse_retry_after),
+            }),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for RateLimited {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.retry_after {
+            Some(retry_after) => {
+                write!(f, "The CDN rate-limited the request (status {}, retry after {retry_after:?})", self.status)
+            }
+            None => write!(f, "The CDN rate-limited the request (status {})", self.status),
+        }
+    }
+}
+
+impl std::error::Error for RateLimited {}
+
+/// Parses the value of a `Retry-After` header, capped at `MAXIMUM_RETRY_AFTER`.
+///
+/// Note: Only the delay-seconds form is supported; an HTTP-date falls back to the default backoff.
+fn parse_retry_after(value: &str) -> Option<Duration> {
+    value.trim().parse::<u64>().ok().map(|secs| cmp::min(Duration::from_secs(secs), MAXIMUM_RETRY_AFTER))
+}
+
 /// Loads blocks from a CDN into the ledger.
+/// If an error budget is given, the sync is cancelled once it is exhausted.
 ///
 /// On success, this function returns the completed block height.
 /// On failure, this function returns the last successful block height (if any), along with the error.
 pub async fn sync_ledger_with_cdn<N: Network, C: ConsensusStorage<N>>(
-    base_url: &str,
+    cdn: &CdnConfig,
     ledger: Ledger<N, C>,
     shutdown: Arc<AtomicBool>,
+    observer: Option<Arc<dyn CdnSyncObserver>>,
+    error_budget: Option<Arc<ErrorBudget>>,
 ) -> Result<u32, (u32, anyhow::Error)> {
     // Fetch the node height.
     let start_height = ledger.latest_height() + 1;
+    // Create a client to maintain a connection pool throughout the sync.
+    let client = cdn.client.build().map_err(|error| (start_height - 1, error))?;
     // Load the blocks from the CDN into the ledger.
     let ledger_clone = ledger.clone();
-    let result = load_blocks(base_url, start_height, None, shutdown, move |block: Block<N>| {
-        ledger_clone.advance_to_next_block(&block)
+    let process = move |block: Block<N>| ledger_clone.advance_to_next_block(&block);
+    let end_height = cdn.end_height();
+    let result =
+        load_blocks_with_config(cdn, &client, start_height, end_height, shutdown, observer, error_budget, process)
+            .await;
+
+    check_sync_result(&ledger, start_height, result)
+}
+
+/// Loads blocks from a CDN directly into the given block store, without instantiating a VM or ledger.
+/// This is intended for tooling (e.g. indexers) that only needs a populated block database;
+/// for a consensus store, pass in `ConsensusStore::block_store`.
+///
+/// Note: The blocks are only checked to extend the chain in the block store; they are not verified or executed.
+///
+/// On success, this function returns the completed block height.
+/// On failure, this function returns the last successful block height (if any), along with the error.
+pub async fn sync_block_store_with_cdn<N: Network, B: BlockStorage<N>>(
+    base_url: &str,
+    block_store: BlockStore<N, B>,
+    shutdown: Arc<AtomicBool>,
+    observer: Option<Arc<dyn CdnSyncObserver>>,
+) -> Result<u32, (u32, anyhow::Error)> {
+    // Fetch the block store height.
+    let start_height = block_store.max_height().map_or(0, |height| height + 1);
+    // Load the blocks from the CDN into the block store.
+    load_blocks(base_url, start_height, None, shutdown, observer, move |block: Block<N>| {
+        // Ensure the block extends the chain in the block store.
+        if let Some(previous_height) = block.height().checked_sub(1) {
+            ensure!(
+                block_store.get_block_hash(previous_height)? == Some(block.previous_hash()),
+                "Block {} does not extend the block store",
+                block.height()
+            );
+        }
+        // Insert the block into the block store.
+        block_store.insert(&block)
     })
-    .await;
+    .await
+}
 
+/// Checks the integrity of the ledger after a CDN sync, returning the completed block height.
+fn check_sync_result<N: Network, C: ConsensusStorage<N>>(
+    ledger: &Ledger<N, C>,
+    start_height: u32,
+    result: Result<u32, (u32, anyhow::Error)>,
+) -> Result<u32, (u32, anyhow::Error)> {
     // TODO (howardwu): Find a way to resolve integrity failures.
     // If the sync failed, check the integrity of the ledger.
     if let Err((completed_height, error)) = &result {
+        // If the CDN served a block that does not match 'latest.json', fail regardless of the progress made.
+        if error.is::<LatestHashMismatch>() {
+            error!("{error}");
+            return result;
+        }
         warn!("{error}");
 
         // If the sync made any progress, then check the integrity of the ledger.
@@ -90,6 +270,11 @@ pub async fn sync_ledger_with_cdn<N: Network, C: ConsensusStorage<N>>(
             }
         }
 
+        // If the error budget was exhausted, report it, so the caller can fall back to P2P sync.
+        if error.is::<ErrorBudgetExhausted>() {
+            return result;
+        }
+
         Ok(*completed_height)
     } else {
         result
@@ -97,6 +282,11 @@ pub async fn sync_ledger_with_cdn<N: Network, C: ConsensusStorage<N>>(
 }
 
 /// Loads blocks from a CDN and process them with the given function.
+/// If an observer is given, it is notified of the sync lifecycle events.
+///
+/// If the bundle with the block at the inclusive height of the CDN's 'latest.json' is loaded, the hash of that block
+/// is cross-checked against the reported hash before the bundle is processed, even if the block itself is above the
+/// end height, and the sync fails with [`LatestHashMismatch`] on mismatch.
 ///
 /// On success, this function returns the completed block height.
 /// On failure, this function returns the last successful block height (if any), along with the error.
@@ -105,6 +295,49 @@ pub async fn load_blocks<N: Network>(
     start_height: u32,
     end_height: Option<u32>,
     shutdown: Arc<AtomicBool>,
+    observer: Option<Arc<dyn CdnSyncObserver>>,
+    process: impl FnMut(Block<N>) -> Result<()> + Clone + Send + Sync + 'static,
+) -> Result<u32, (u32, anyhow::Error)> {
+    let cdn = CdnConfig::new(base_url.to_string());
+    // Create a client to maintain a connection pool throughout the sync.
+    let client = cdn.client.build().map_err(|error| (start_height.saturating_sub(1), error))?;
+    load_blocks_with_config(&cdn, &client, start_height, end_height, shutdown, observer, None, process).await
+}
+
+/// Loads blocks from the given CDN and process them with the given function.
+/// If an error budget is given, the sync is cancelled with [`ErrorBudgetExhausted`] once it is exhausted.
+#[allow(clippy::too_many_arguments)]
+async fn load_blocks_with_config<N: Network>(
+    cdn: &CdnConfig,
+    client: &CdnClient,
+    start_height: u32,
+    end_height: Option<u32>,
+    shutdown: Arc<AtomicBool>,
+    observer: Option<Arc<dyn CdnSyncObserver>>,
+    error_budget: Option<Arc<ErrorBudget>>,
+    process: impl FnMut(Block<N>) -> Result<()> + Clone + Send + Sync + 'static,
+) -> Result<u32, (u32, anyhow::Error)> {
+    // Load the blocks from the CDN.
+    let result =
+        load_blocks_inner(cdn, client, start_height, end_height, shutdown, observer.clone(), error_budget, process)
+            .await;
+    // If the sync failed, notify the observer.
+    if let (Err((height, error)), Some(observer)) = (&result, &observer) {
+        observer.sync_failed(*height, error);
+    }
+    result
+}
+
+/// Loads blocks from a CDN and process them with the given function.
+#[allow(clippy::too_many_arguments)]
+async fn load_blocks_inner<N: Network>(
+    cdn: &CdnConfig,
+    client: &CdnClient,
+    start_height: u32,
+    end_height: Option<u32>,
+    shutdown: Arc<AtomicBool>,
+    observer: Option<Arc<dyn CdnSyncObserver>>,
+    error_budget: Option<Arc<ErrorBudget>>,
     process: impl FnMut(Block<N>) -> Result<()> + Clone + Send + Sync + 'static,
 ) -> Result<u32, (u32, anyhow::Error)> {
     // If the network is not supported, return.
@@ -112,17 +345,14 @@ pub async fn load_blocks<N: Network>(
         return Err((start_height, anyhow!("The network ({}) is not supported", N::ID)));
     }
 
-    // Create a Client to maintain a connection pool throughout the sync.
-    let client = match Client::builder().build() {
-        Ok(client) => client,
-        Err(error) => {
-            return Err((start_height.saturating_sub(1), anyhow!("Failed to create a CDN request client - {error}")));
-        }
+    // Fetch the latest state of the CDN.
+    let latest = match cdn_latest_state(client, &cdn.base_url).await {
+        Ok(latest) => Arc::new(latest),
+        Err(error) => return Err((start_height, error)),
     };
-
-    // Fetch the CDN height.
-    let cdn_height = match cdn_height::<BLOCKS_PER_FILE>(&client, base_url).await {
-        Ok(cdn_height) => cdn_height,
+    // Compute the CDN height.
+    let cdn_height = match latest.cdn_height(cdn.safety_margin) {
+        Ok((_, cdn_height)) => cdn_height,
         Err(error) => return Err((start_height, error)),
     };
     // If the CDN height is less than the start height, return.
@@ -155,16 +385,59 @@ pub async fn load_blocks<N: Network>(
 
     // A collection of downloaded blocks pending insertion into the ledger.
     let pending_blocks: Arc<Mutex<Vec<Block<N>>>> = Default::default();
+    // The on-disk cache of the downloaded bundles, if configured.
+    let cache = match &cdn.cache_dir {
+        Some(dir) => match BundleCache::new(dir) {
+            Ok(cache) => Some(Arc::new(cache)),
+            Err(error) => return Err((start_height.saturating_sub(1), error)),
+        },
+        None => None,
+    };
+    // Prune the cached bundles that were inserted before a restart.
+    if let (Some(cache), Some(height)) = (&cache, start_height.checked_sub(1)) {
+        cache.prune(height);
+    }
+    // The on-disk overflow of the pending blocks, if configured.
+    let spill = match &cdn.spill_dir {
+        Some(dir) => match SpillQueue::new(dir) {
+            Ok(spill) => Some(Arc::new(spill)),
+            Err(error) => return Err((start_height.saturating_sub(1), error)),
+        },
+        None => None,
+    };
+
+    // Track the progress of the sync.
+    let tracker = Arc::new(Mutex::new(ProgressTracker::new(start_height, end_height - 1)));
 
-    // Start a timer.
-    let timer = Instant::now();
+    // Cancel the in-flight downloads on shutdown, or once the sync returns.
+    let cancel = CancellationToken::new();
+    let _cancel_guard = cancel.clone().drop_guard();
+    cancel_on_shutdown(shutdown.clone(), cancel.clone());
 
     // Spawn a background task responsible for concurrent downloads.
+    let client_clone = client.clone();
     let pending_blocks_clone = pending_blocks.clone();
-    let base_url = base_url.to_owned();
+    let mirrors = Arc::new(Mirrors::new(cdn.mirror_urls(), cdn.slow_mirror_threshold));
     let shutdown_clone = shutdown.clone();
+    let observer_clone = observer.clone();
+    let error_budget_clone = error_budget.clone();
+    let spill_clone = spill.clone();
+    let cache_clone = cache.clone();
     tokio::spawn(async move {
-        download_block_bundles(client, base_url, cdn_start, cdn_end, pending_blocks_clone, shutdown_clone).await;
+        download_block_bundles(
+            client_clone,
+            mirrors,
+            cdn_start,
+            cdn_end,
+            pending_blocks_clone,
+            shutdown_clone,
+            observer_clone,
+            error_budget_clone,
+            spill_clone,
+            cache_clone,
+            cancel,
+        )
+        .await;
     });
 
     // A loop for inserting the pending blocks into the ledger.
@@ -177,6 +450,18 @@ pub async fn load_blocks<N: Network>(
             std::process::exit(0);
         }
 
+        // If the error budget is exhausted, cancel the sync.
+        if let Some(error_budget) = error_budget.as_ref().filter(|budget| budget.is_exhausted()) {
+            return Err((current_height, ErrorBudgetExhausted { failures: error_budget.failures() }.into()));
+        }
+
+        // Restore the spilled blocks that are needed next, or that fit in memory again.
+        if let Some(spill) = &spill {
+            if let Err(error) = restore_spilled_blocks(spill, &pending_blocks, current_height) {
+                return Err((current_height, error));
+            }
+        }
+
         let mut candidate_blocks = pending_blocks.lock();
 
         // Obtain the height of the nearest pending block.
@@ -202,63 +487,140 @@ pub async fn load_blocks<N: Network>(
         drop(candidate_blocks);
 
         // Attempt to advance the ledger using the CDN block bundle.
-        let mut process_clone = process.clone();
+        let process_clone = process.clone();
         let shutdown_clone = shutdown.clone();
+        let observer_clone = observer.clone();
+        let latest_clone = latest.clone();
+        let tracker_clone = tracker.clone();
         current_height = tokio::task::spawn_blocking(move || {
-            for block in next_blocks.into_iter().filter(|b| (start_height..end_height).contains(&b.height())) {
-                // If we are instructed to shut down, abort.
-                if shutdown_clone.load(Ordering::Relaxed) {
-                    info!("Stopping block sync at {} - the node is shutting down", current_height);
-                    // We can shut down cleanly from here, as the node hasn't been started yet.
-                    std::process::exit(0);
-                }
-
-                // Register the next block's height, as the block gets consumed next.
-                let block_height = block.height();
-
-                // Insert the block into the ledger.
-                process_clone(block)?;
-
+            let inserted = |block_height| {
                 // Update the current height.
                 current_height = block_height;
 
-                // Log the progress.
-                log_progress::<BLOCKS_PER_FILE>(timer, current_height, cdn_start, cdn_end, "block");
-            }
+                // Update the progress.
+                let mut tracker = tracker_clone.lock();
+                let progress = tracker.update(current_height);
 
-            Ok(current_height)
+                // Notify the observer.
+                if let Some(observer) = &observer_clone {
+                    observer.block_inserted(current_height);
+                    observer.progress(progress);
+                }
+
+                // Log the progress.
+                info!("{progress}");
+            };
+            process_bundle(
+                next_blocks,
+                start_height..end_height,
+                &latest_clone,
+                &shutdown_clone,
+                process_clone,
+                inserted,
+            )
+            .map(|height| height.unwrap_or(current_height))
         })
         .await
         .map_err(|e| (current_height, e.into()))?
         .map_err(|e| (current_height, e))?;
+
+        // Prune the cached bundles that are now inserted.
+        if let Some(cache) = &cache {
+            cache.prune(current_height);
+        }
     }
 
     Ok(current_height)
 }
 
+/// Processes the given bundle of blocks with the given function, skipping the blocks outside of the given heights,
+/// and returns the height of the last processed block (if any).
+///
+/// The whole bundle is cross-checked against the latest state of the CDN before any of its blocks is processed,
+/// including the blocks above the end height. As the blocks of a bundle are hash-linked (see [`check_bundle`]),
+/// a match at the inclusive height of 'latest.json' authenticates the last synced blocks below the safety margin.
+fn process_bundle<N: Network>(
+    blocks: Vec<Block<N>>,
+    heights: Range<u32>,
+    latest: &LatestState,
+    shutdown: &AtomicBool,
+    mut process: impl FnMut(Block<N>) -> Result<()>,
+    mut inserted: impl FnMut(u32),
+) -> Result<Option<u32>> {
+    // Cross-check the bundle against the latest state of the CDN.
+    for block in &blocks {
+        latest.check_block(block)?;
+    }
+
+    let mut last_height = None;
+    for block in blocks.into_iter().filter(|b| heights.contains(&b.height())) {
+        // If we are instructed to shut down, abort.
+        if shutdown.load(Ordering::Relaxed) {
+            info!("Stopping block sync at {} - the node is shutting down", block.height().saturating_sub(1));
+            // We can shut down cleanly from here, as the node hasn't been started yet.
+            std::process::exit(0);
+        }
+
+        // Register the next block's height, as the block gets consumed next.
+        let block_height = block.height();
+
+        // Insert the block into the ledger.
+        process(block)?;
+
+        // Record the inserted block.
+        last_height = Some(block_height);
+        inserted(block_height);
+    }
+    Ok(last_height)
+}
+
+#[allow(clippy::too_many_arguments)]
 async fn download_block_bundles<N: Network>(
-    client: Client,
-    base_url: String,
+    client: CdnClient,
+    mirrors: Arc<Mirrors>,
     cdn_start: u32,
     cdn_end: u32,
     pending_blocks: Arc<Mutex<Vec<Block<N>>>>,
     shutdown: Arc<AtomicBool>,
+    observer: Option<Arc<dyn CdnSyncObserver>>,
+    error_budget: Option<Arc<ErrorBudget>>,
+    spill: Option<Arc<SpillQueue>>,
+    cache: Option<Arc<BundleCache>>,
+    cancel: CancellationToken,
 ) {
     // Keep track of the number of concurrent requests.
     let active_requests: Arc<AtomicU32> = Default::default();
+    // Keep track of the time until which the CDN asked to pause the requests.
+    let paused_until: Arc<Mutex<Option<Instant>>> = Default::default();
 
     let mut start = cdn_start;
     while start < cdn_end - 1 {
         // If we are instructed to shut down, stop downloading.
-        if shutdown.load(Ordering::Relaxed) {
+        if shutdown.load(Ordering::Relaxed) || cancel.is_cancelled() {
+            break;
+        }
+        // If the error budget is exhausted, stop downloading.
+        if error_budget.as_ref().map_or(false, |budget| budget.is_exhausted()) {
             break;
         }
 
-        // Avoid collecting too many blocks in order to restrict memory use.
-        let num_pending_blocks = pending_blocks.lock().len();
-        if num_pending_blocks >= MAXIMUM_PENDING_BLOCKS as usize {
+        // If the CDN is rate-limiting the requests, wait before sending new ones.
+        let pause = (*paused_until.lock()).and_then(|until| until.checked_duration_since(Instant::now()));
+        if let Some(pause) = pause {
+            debug!("The CDN is rate-limiting the requests, pausing for {pause:?}...");
+            sleep_unless_cancelled(pause, &cancel).await;
+            continue;
+        }
+
+        // Avoid collecting too many blocks in order to restrict memory use (and disk use, if spilling).
+        let num_pending_blocks = pending_blocks.lock().len() + spill.as_ref().map_or(0, |spill| spill.len());
+        let max_pending_blocks = match spill {
+            Some(_) => MAXIMUM_PENDING_BLOCKS + MAXIMUM_SPILLED_BLOCKS,
+            None => MAXIMUM_PENDING_BLOCKS,
+        };
+        if num_pending_blocks >= max_pending_blocks as usize {
             debug!("Maximum number of pending blocks reached ({num_pending_blocks}), waiting...");
-            tokio::time::sleep(Duration::from_secs(5)).await;
+            sleep_unless_cancelled(Duration::from_secs(5), &cancel).await;
             continue;
         }
 
@@ -266,7 +628,7 @@ async fn download_block_bundles<N: Network>(
         // number of pending blocks may be breached.
         let active_request_count = active_requests.load(Ordering::Relaxed);
         let num_requests =
-            cmp::min(CONCURRENT_REQUESTS, (MAXIMUM_PENDING_BLOCKS - num_pending_blocks as u32) / BLOCKS_PER_FILE)
+            cmp::min(CONCURRENT_REQUESTS, (max_pending_blocks - num_pending_blocks as u32) / BLOCKS_PER_FILE)
                 .saturating_sub(active_request_count);
 
         // Spawn concurrent requests for bundles of blocks.
@@ -281,10 +643,16 @@ async fn download_block_bundles<N: Network>(
             }
 
             let client_clone = client.clone();
-            let base_url_clone = base_url.clone();
+            let mirrors_clone = mirrors.clone();
             let pending_blocks_clone = pending_blocks.clone();
             let active_requests_clone = active_requests.clone();
             let shutdown_clone = shutdown.clone();
+            let observer_clone = observer.clone();
+            let error_budget_clone = error_budget.clone();
+            let paused_until_clone = paused_until.clone();
+            let spill_clone = spill.clone();
+            let cache_clone = cache.clone();
+            let cancel_clone = cancel.clone();
             tokio::spawn(async move {
                 // Increment the number of active requests.
                 active_requests_clone.fetch_add(1, Ordering::Relaxed);
@@ -292,39 +660,120 @@ async fn download_block_bundles<N: Network>(
                 let ctx = format!("blocks {start} to {end}");
                 debug!("Requesting {ctx} (of {cdn_end})");
 
-                // Prepare the URL.
-                let blocks_url = format!("{base_url_clone}/{start}.{end}.blocks");
+                // Notify the observer.
+                if let Some(observer) = &observer_clone {
+                    observer.bundle_requested(start, end);
+                }
+
                 let ctx = format!("blocks {start} to {end}");
+                let request_time = Instant::now();
+
+                // Load the blocks from the cache, if they were downloaded before a restart.
+                if let Some(blocks) = cache_clone.as_ref().and_then(|cache| cache.load::<N>(start, end)) {
+                    insert_pending_blocks(&pending_blocks_clone, spill_clone.as_deref(), blocks);
+                    debug!("Loaded {ctx} from the bundle cache");
+                    // Notify the observer.
+                    if let Some(observer) = &observer_clone {
+                        observer.bundle_downloaded(start, end, request_time.elapsed());
+                    }
+                    // Decrement the number of active requests.
+                    active_requests_clone.fetch_sub(1, Ordering::Relaxed);
+                    return;
+                }
+
                 // Download blocks, retrying on failure.
                 let mut attempts = 0;
-                let request_time = Instant::now();
 
                 loop {
-                    // Fetch the blocks.
-                    match cdn_get(client_clone.clone(), &blocks_url, &ctx).await {
+                    // Select the mirror, and prepare the URL.
+                    let mirror = mirrors_clone.select();
+                    let blocks_url = format!("{}/{start}.{end}.blocks", mirrors_clone.base_url(mirror));
+                    let attempt_time = Instant::now();
+
+                    // Fetch the blocks, aborting the request if the sync is cancelled.
+                    let result = tokio::select! {
+                        result = cdn_get(client_clone.clone(), &blocks_url, &ctx) => {
+                            // Reject a corrupted bundle before it reaches the ledger, so it is refetched.
+                            result.and_then(|blocks| check_bundle::<N>(&blocks, start, end).map(|_| blocks))
+                        }
+                        _ = cancel_clone.cancelled() => {
+                            debug!("Cancelled the request for {ctx}");
+                            break;
+                        }
+                    };
+                    match result {
                         Ok::<Vec<Block<N>>, _>(blocks) => {
-                            // Keep the collection of pending blocks sorted by the height.
-                            let mut pending_blocks = pending_blocks_clone.lock();
-                            for block in blocks {
-                                match pending_blocks.binary_search_by_key(&block.height(), |b| b.height()) {
-                                    Ok(_idx) => warn!("Found a duplicate pending block at height {}", block.height()),
-                                    Err(idx) => pending_blocks.insert(idx, block),
+                            // Record the download time of the mirror.
+                            mirrors_clone.record_success(mirror, attempt_time.elapsed());
+                            // Cache the blocks, so they survive a restart until they are inserted.
+                            if let Some(cache) = &cache_clone {
+                                if let Err(error) = cache.store(start, end, &blocks) {
+                                    warn!("Failed to cache {ctx} - {error}");
                                 }
                             }
+                            insert_pending_blocks(&pending_blocks_clone, spill_clone.as_deref(), blocks);
                             debug!("Received {ctx} {}", format!("(in {:.2?})", request_time.elapsed()).dimmed());
+                            // Notify the observer.
+                            if let Some(observer) = &observer_clone {
+                                observer.bundle_downloaded(start, end, request_time.elapsed());
+                            }
                             break;
                         }
                         Err(error) => {
-                            // Increment the attempt counter, and wait with a linear backoff, or abort in
-                            // case the maximum number of attempts has been breached.
+                            // Record the failure of the mirror.
+                            mirrors_clone.record_failure(mirror);
+                            // Notify the observer.
+                            if let Some(observer) = &observer_clone {
+                                observer.bundle_failed(start, end, &error);
+                            }
+                            // A rate-limited request is not a failure of the CDN, so it is not charged to the
+                            // error budget; otherwise, charge the failure, and stop retrying if it is exhausted.
+                            let rate_limited = error.downcast_ref::<RateLimited>();
+                            if let Some(rate_limited) = rate_limited {
+                                #[cfg(feature = "metrics")]
+                                metrics::increment_counter(metrics::cdn::RATE_LIMITED);
+                                // Notify the observer.
+                                if let Some(observer) = &observer_clone {
+                                    observer.rate_limited(start, end, rate_limited.retry_after);
+                                }
+                            } else if let Some(error_budget) = &error_budget_clone {
+                                if error_budget.record_failure() {
+                                    warn!("{error} - the CDN error budget is exhausted, stopping the downloads");
+                                    break;
+                                }
+                            }
+                            // Increment the attempt counter, or abort in case the maximum number of attempts
+                            // has been breached.
                             attempts += 1;
                             if attempts > MAXIMUM_REQUEST_ATTEMPTS {
-                                warn!("Maximum number of requests to {blocks_url} reached - shutting down...");
-                                shutdown_clone.store(true, Ordering::Relaxed);
+                                // If an error budget is configured, spend it to fall back to P2P sync,
+                                // rather than shutting down the node.
+                                match &error_budget_clone {
+                                    Some(error_budget) => {
+                                        warn!("Maximum number of requests to {blocks_url} reached - falling back");
+                                        error_budget.exhaust();
+                                    }
+                                    None => {
+                                        warn!("Maximum number of requests to {blocks_url} reached - shutting down...");
+                                        shutdown_clone.store(true, Ordering::Relaxed);
+                                    }
+                                }
+                                break;
+                            }
+                            // Wait for the delay requested by the CDN, or with a linear backoff.
+                            let delay = rate_limited
+                                .and_then(|rate_limited| rate_limited.retry_after)
+                                .unwrap_or(Duration::from_secs(attempts as u64 * 10));
+                            // If the CDN is rate-limiting the requests, pause the other downloads as well.
+                            if rate_limited.is_some() {
+                                let resume_at = Instant::now() + delay;
+                                let mut paused_until = paused_until_clone.lock();
+                                *paused_until = Some(paused_until.map_or(resume_at, |until| until.max(resume_at)));
+                            }
+                            warn!("{error} - retrying {ctx} in {delay:?} ({attempts} attempt(s) so far)");
+                            if !sleep_unless_cancelled(delay, &cancel_clone).await {
                                 break;
                             }
-                            tokio::time::sleep(Duration::from_secs(attempts as u64 * 10)).await;
-                            warn!("{error} - retrying ({attempts} attempt(s) so far)");
                         }
                     }
                 }
@@ -338,24 +787,127 @@ async fn download_block_bundles<N: Network>(
         start += BLOCKS_PER_FILE * num_requests;
 
         // A short sleep in order to allow some block processing to happen in the meantime.
-        tokio::time::sleep(Duration::from_secs(1)).await;
+        sleep_unless_cancelled(Duration::from_secs(1), &cancel).await;
     }
 
     debug!("Finished network requests to the CDN");
 }
 
-/// Retrieves the CDN height with the given base URL.
+/// Ensures the given bundle of blocks `start..end` is structurally sound, i.e. its heights are contiguous
+/// from `start` and every block links to the hash of its predecessor.
+/// Note: This is a cheap pre-validation, the blocks are still fully verified upon insertion.
+pub(crate) fn check_bundle<N: Network>(blocks: &[Block<N>], start: u32, end: u32) -> Result<()> {
+    ensure!(blocks.len() as u32 <= end.saturating_sub(start), "The bundle of blocks {start} to {end} is too large");
+    for (i, block) in blocks.iter().enumerate() {
+        let expected_height = start + i as u32;
+        ensure!(
+            block.height() == expected_height,
+            "Expected block {expected_height} in the bundle, found block {}",
+            block.height()
+        );
+        if let Some(previous) = i.checked_sub(1).map(|i| &blocks[i]) {
+            ensure!(
+                block.previous_hash() == previous.hash(),
+                "Block {expected_height} does not link to the hash of block {}",
+                previous.height()
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Cancels the given token once the shutdown flag is set, so the in-flight requests are aborted promptly.
+fn cancel_on_shutdown(shutdown: Arc<AtomicBool>, cancel: CancellationToken) {
+    tokio::spawn(async move {
+        loop {
+            tokio::select! {
+                // Stop watching once the sync is cancelled otherwise.
+                _ = cancel.cancelled() => break,
+                _ = tokio::time::sleep(SHUTDOWN_POLL_INTERVAL) => {
+                    if shutdown.load(Ordering::Relaxed) {
+                        cancel.cancel();
+                        break;
+                    }
+                }
+            }
+        }
+    });
+}
+
+/// Sleeps for the given duration, returning `false` if the sync is cancelled in the meantime.
+async fn sleep_unless_cancelled(duration: Duration, cancel: &CancellationToken) -> bool {
+    tokio::select! {
+        _ = tokio::time::sleep(duration) => true,
+        _ = cancel.cancelled() => false,
+    }
+}
+
+/// Adds the given downloaded blocks to the pending blocks, spilling them to disk if the pending blocks are full.
+fn insert_pending_blocks<N: Network>(
+    pending_blocks: &Mutex<Vec<Block<N>>>,
+    spill: Option<&SpillQueue>,
+    blocks: Vec<Block<N>>,
+) {
+    // If the pending blocks are full, spill the blocks to disk.
+    let is_full = pending_blocks.lock().len() >= MAXIMUM_PENDING_BLOCKS as usize;
+    let blocks = match spill {
+        Some(spill) if is_full => spill_blocks(spill, blocks),
+        _ => blocks,
+    };
+    // Keep the collection of pending blocks sorted by the height.
+    let mut pending_blocks = pending_blocks.lock();
+    for block in blocks {
+        match pending_blocks.binary_search_by_key(&block.height(), |b| b.height()) {
+            Ok(_idx) => warn!("Found a duplicate pending block at height {}", block.height()),
+            Err(idx) => pending_blocks.insert(idx, block),
+        }
+    }
+}
+
+/// Spills the given blocks to disk, returning the blocks that could not be spilled (to be kept in memory).
+fn spill_blocks<N: Network>(spill: &SpillQueue, blocks: Vec<Block<N>>) -> Vec<Block<N>> {
+    let mut unspilled = Vec::new();
+    for block in blocks {
+        if let Err(error) = spill.push(&block) {
+            warn!("Failed to spill block {} to disk - {error}", block.height());
+            unspilled.push(block);
+        }
+    }
+    unspilled
+}
+
+/// Moves the spilled blocks back into the pending blocks, starting with the lowest height, as long as
+/// the pending blocks have room, or the spilled block is among the next blocks to insert after `current_height`.
+fn restore_spilled_blocks<N: Network>(
+    spill: &SpillQueue,
+    pending_blocks: &Mutex<Vec<Block<N>>>,
+    current_height: u32,
+) -> Result<()> {
+    while let Some(height) = spill.first_height() {
+        let has_room = pending_blocks.lock().len() < MAXIMUM_PENDING_BLOCKS as usize;
+        if !has_room && height > current_height + BLOCKS_PER_FILE {
+            break;
+        }
+        if let Some(block) = spill.take::<N>(height)? {
+            let mut pending_blocks = pending_blocks.lock();
+            if let Err(idx) = pending_blocks.binary_search_by_key(&height, |b| b.height()) {
+                pending_blocks.insert(idx, block);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Retrieves the `(inclusive, exclusive)` CDN heights with the given base URL.
 ///
-/// Note: This function decrements the tip by a few blocks, to ensure the
+/// Note: This function decrements the tip by the given safety margin, to ensure the
 /// tip is not on a block that is not yet available on the CDN.
-async fn cdn_height<const BLOCKS_PER_FILE: u32>(client: &Client, base_url: &str) -> Result<u32> {
-    // A representation of the 'latest.json' file object.
-    #[derive(Deserialize, Serialize, Debug)]
-    struct LatestState {
-        exclusive_height: u32,
-        inclusive_height: u32,
-        hash: String,
-    }
+async fn cdn_height(client: &CdnClient, base_url: &str, safety_margin: u32) -> Result<(u32, u32)> {
+    cdn_latest_state(client, base_url).await?.cdn_height(safety_margin)
+}
+
+/// Retrieves the latest state (i.e. 'latest.json') of the CDN with the given base URL.
+async fn cdn_latest_state(client: &CdnClient, base_url: &str) -> Result<LatestState> {
     // Prepare the URL.
     let latest_json_url = format!("{base_url}/latest.json");
     // Send the request.
@@ -373,24 +925,24 @@ async fn cdn_height<const BLOCKS_PER_FILE: u32>(client: &Client, base_url: &str)
         Ok(string) => string,
         Err(error) => bail!("Failed to deserialize the CDN height response - {error}"),
     };
-    // Parse the string for the tip.
-    let tip = match serde_json::from_str::<LatestState>(&latest_state_string) {
-        Ok(latest) => latest.exclusive_height,
+    // Parse the string for the latest state.
+    match serde_json::from_str::<LatestState>(&latest_state_string) {
+        Ok(latest) => Ok(latest),
         Err(error) => bail!("Failed to extract the CDN height response - {error}"),
-    };
-    // Decrement the tip by a few blocks to ensure the CDN is caught up.
-    let tip = tip.saturating_sub(10);
-    // Adjust the tip to the closest subsequent multiple of BLOCKS_PER_FILE.
-    Ok(tip - (tip % BLOCKS_PER_FILE) + BLOCKS_PER_FILE)
+    }
 }
 
 /// Retrieves the objects from the CDN with the given URL.
-async fn cdn_get<T: 'static + DeserializeOwned + Send>(client: Client, url: &str, ctx: &str) -> Result<T> {
+async fn cdn_get<T: 'static + DeserializeOwned + Send>(client: CdnClient, url: &str, ctx: &str) -> Result<T> {
     // Fetch the bytes from the given URL.
     let response = match client.get(url).send().await {
         Ok(response) => response,
         Err(error) => bail!("Failed to fetch {ctx} - {error}"),
     };
+    // Check whether the request was rate-limited.
+    if let Some(rate_limited) = RateLimited::from_response(&response) {
+        return Err(rate_limited.into());
+    }
     // Parse the response.
     let bytes = match response.bytes().await {
         Ok(bytes) => bytes,
@@ -404,44 +956,37 @@ async fn cdn_get<T: 'static + DeserializeOwned + Send>(client: Client, url: &str
     }
 }
 
-/// Logs the progress of the sync.
-fn log_progress<const OBJECTS_PER_FILE: u32>(
-    timer: Instant,
-    current_index: u32,
-    cdn_start: u32,
-    mut cdn_end: u32,
-    object_name: &str,
-) {
-    // Subtract 1, as the end of the range is exclusive.
-    cdn_end -= 1;
-    // Compute the percentage completed.
-    let percentage = current_index * 100 / cdn_end;
-    // Compute the number of files processed so far.
-    let num_files_done = 1 + (current_index - cdn_start) / OBJECTS_PER_FILE;
-    // Compute the number of files remaining.
-    let num_files_remaining = 1 + (cdn_end.saturating_sub(current_index)) / OBJECTS_PER_FILE;
-    // Compute the milliseconds per file.
-    let millis_per_file = timer.elapsed().as_millis() / num_files_done as u128;
-    // Compute the heuristic slowdown factor (in millis).
-    let slowdown = 100 * num_files_remaining as u128;
-    // Compute the time remaining (in millis).
-    let time_remaining = num_files_remaining as u128 * millis_per_file + slowdown;
-    // Prepare the estimate message (in secs).
-    let estimate = format!("(est. {} minutes remaining)", time_remaining / (60 * 1000));
-    // Log the progress.
-    info!("Synced up to {object_name} {current_index} of {cdn_end} - {percentage}% complete {}", estimate.dimmed());
-}
-
 #[cfg(test)]
 mod tests {
     use crate::{
-        blocks::{cdn_get, cdn_height, log_progress, BLOCKS_PER_FILE},
+        blocks::{
+            cancel_on_shutdown,
+            cdn_get,
+            cdn_height,
+            check_bundle,
+            parse_retry_after,
+            process_bundle,
+            sleep_unless_cancelled,
+            LatestState,
+            MAXIMUM_RETRY_AFTER,
+        },
         load_blocks,
+        CdnClientConfig,
+        CdnConfig,
+        CdnSyncObserver,
+        LatestHashMismatch,
     };
-    use snarkvm::prelude::{block::Block, MainnetV0};
+    use snarkvm::prelude::{block::Block, FromBytes, MainnetV0, Network};
 
     use parking_lot::RwLock;
-    use std::{sync::Arc, time::Instant};
+    use std::{
+        sync::{
+            atomic::{AtomicBool, AtomicU32, Ordering},
+            Arc,
+        },
+        time::Duration,
+    };
+    use tokio_util::sync::CancellationToken;
 
     type CurrentNetwork = MainnetV0;
 
@@ -457,7 +1002,8 @@ mod tests {
 
         let rt = tokio::runtime::Runtime::new().unwrap();
         rt.block_on(async {
-            let completed_height = load_blocks(TEST_BASE_URL, start, end, Default::default(), process).await.unwrap();
+            let completed_height =
+                load_blocks(TEST_BASE_URL, start, end, Default::default(), None, process).await.unwrap();
             assert_eq!(blocks.read().len(), expected);
             if expected > 0 {
                 assert_eq!(blocks.read().last().unwrap().height(), completed_height);
@@ -497,13 +1043,158 @@ mod tests {
         check_load_blocks(start_height, end_height, 188);
     }
 
+    #[test]
+    fn test_load_blocks_with_observer() {
+        #[derive(Default)]
+        struct Counter {
+            requested: AtomicU32,
+            downloaded: AtomicU32,
+            inserted: AtomicU32,
+        }
+
+        impl CdnSyncObserver for Counter {
+            fn bundle_requested(&self, _start: u32, _end: u32) {
+                self.requested.fetch_add(1, Ordering::Relaxed);
+            }
+
+            fn bundle_downloaded(&self, _start: u32, _end: u32, _elapsed: Duration) {
+                self.downloaded.fetch_add(1, Ordering::Relaxed);
+            }
+
+            fn block_inserted(&self, _height: u32) {
+                self.inserted.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+
+        let counter = Arc::new(Counter::default());
+        let observer: Arc<dyn CdnSyncObserver> = counter.clone();
+
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        rt.block_on(async {
+            let process = move |_block: Block<CurrentNetwork>| Ok(());
+            load_blocks(TEST_BASE_URL, 0, Some(100), Default::default(), Some(observer), process).await.unwrap();
+        });
+        assert!(counter.requested.load(Ordering::Relaxed) >= 2);
+        assert!(counter.downloaded.load(Ordering::Relaxed) >= 2);
+        assert_eq!(counter.inserted.load(Ordering::Relaxed), 100);
+    }
+
     #[test]
     fn test_cdn_height() {
         let rt = tokio::runtime::Runtime::new().unwrap();
-        let client = reqwest::Client::builder().build().unwrap();
+        let client = CdnClientConfig::default().build().unwrap();
         rt.block_on(async {
-            let height = cdn_height::<BLOCKS_PER_FILE>(&client, TEST_BASE_URL).await.unwrap();
-            assert!(height > 0);
+            let safety_margin = CdnConfig::new(TEST_BASE_URL.to_string()).safety_margin;
+            let (inclusive, exclusive) = cdn_height(&client, TEST_BASE_URL, safety_margin).await.unwrap();
+            assert!(exclusive > 0);
+            assert_eq!(inclusive + 1, exclusive);
+        });
+    }
+
+    #[test]
+    fn test_latest_state_cdn_height() {
+        let latest = |exclusive_height: u32| LatestState {
+            exclusive_height,
+            inclusive_height: exclusive_height.saturating_sub(1),
+            hash: String::new(),
+        };
+
+        // Check the heights with and without a safety margin.
+        assert_eq!(latest(1000).cdn_height(0).unwrap(), (999, 1000));
+        assert_eq!(latest(1000).cdn_height(10).unwrap(), (989, 990));
+        // Check that the heights are not rounded to the bundle size.
+        assert_eq!(latest(1234).cdn_height(10).unwrap(), (1223, 1224));
+        // Check a short chain.
+        assert_eq!(latest(5).cdn_height(4).unwrap(), (0, 1));
+        assert!(latest(5).cdn_height(5).is_err());
+        assert!(latest(5).cdn_height(10).is_err());
+        assert!(latest(0).cdn_height(0).is_err());
+    }
+
+    #[test]
+    fn test_latest_state_check_block() {
+        let genesis = Block::<CurrentNetwork>::from_bytes_le(CurrentNetwork::genesis_bytes()).unwrap();
+
+        // Check that a matching block passes.
+        let latest = LatestState { exclusive_height: 1, inclusive_height: 0, hash: genesis.hash().to_string() };
+        assert!(latest.check_block(&genesis).is_ok());
+
+        // Check that a block below the inclusive height is not cross-checked.
+        let latest = LatestState { exclusive_height: 2, inclusive_height: 1, hash: "ab1invalid".to_string() };
+        assert!(latest.check_block(&genesis).is_ok());
+
+        // Check that a mismatching block fails.
+        let latest = LatestState { exclusive_height: 1, inclusive_height: 0, hash: "ab1invalid".to_string() };
+        let error = latest.check_block(&genesis).unwrap_err();
+        assert!(error.is::<LatestHashMismatch>());
+    }
+
+    #[test]
+    fn test_process_bundle_cross_checks_latest_state() {
+        let genesis = Block::<CurrentNetwork>::from_bytes_le(CurrentNetwork::genesis_bytes()).unwrap();
+        let shutdown = AtomicBool::new(false);
+
+        // Check that the blocks in the given heights are processed.
+        let latest = LatestState { exclusive_height: 1, inclusive_height: 0, hash: genesis.hash().to_string() };
+        let mut processed = Vec::new();
+        let height = process_bundle(vec![genesis.clone()], 0..1, &latest, &shutdown, |_| Ok(()), |h| processed.push(h));
+        assert_eq!(height.unwrap(), Some(0));
+        assert_eq!(processed, vec![0]);
+
+        // Check that a block above the end height (i.e. in the safety margin) is skipped, but still cross-checked.
+        let height = process_bundle(vec![genesis.clone()], 0..0, &latest, &shutdown, |_| Ok(()), |_| ());
+        assert_eq!(height.unwrap(), None);
+        let latest = LatestState { exclusive_height: 1, inclusive_height: 0, hash: "ab1invalid".to_string() };
+        let error =
+            process_bundle(vec![genesis], 0..0, &latest, &shutdown, |_| panic!("processed"), |_| ()).unwrap_err();
+        assert!(error.is::<LatestHashMismatch>());
+    }
+
+    #[test]
+    fn test_check_bundle() {
+        let genesis = Block::<CurrentNetwork>::from_bytes_le(CurrentNetwork::genesis_bytes()).unwrap();
+
+        // Check that a well-formed bundle is accepted.
+        assert!(check_bundle(&[genesis.clone()], 0, 50).is_ok());
+        assert!(check_bundle::<CurrentNetwork>(&[], 0, 50).is_ok());
+
+        // Check that a bundle with unexpected heights is rejected.
+        assert!(check_bundle(&[genesis.clone()], 50, 100).is_err());
+        assert!(check_bundle(&[genesis.clone(), genesis.clone()], 0, 50).is_err());
+        // Check that a bundle with too many blocks is rejected.
+        assert!(check_bundle(&[genesis], 0, 0).is_err());
+    }
+
+    #[test]
+    fn test_parse_retry_after() {
+        assert_eq!(parse_retry_after("0"), Some(Duration::ZERO));
+        assert_eq!(parse_retry_after("30"), Some(Duration::from_secs(30)));
+        assert_eq!(parse_retry_after(" 120 "), Some(Duration::from_secs(120)));
+        // Check that an excessive delay is capped.
+        assert_eq!(parse_retry_after("86400"), Some(MAXIMUM_RETRY_AFTER));
+        // Check that an HTTP-date and malformed values are not supported.
+        assert_eq!(parse_retry_after("Wed, 21 Oct 2015 07:28:00 GMT"), None);
+        assert_eq!(parse_retry_after("-1"), None);
+        assert_eq!(parse_retry_after(""), None);
+    }
+
+    #[test]
+    fn test_cancel_on_shutdown() {
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        rt.block_on(async {
+            let shutdown: Arc<AtomicBool> = Default::default();
+            let cancel = CancellationToken::new();
+            cancel_on_shutdown(shutdown.clone(), cancel.clone());
+
+            // Check that the sleep completes while the node is running.
+            assert!(sleep_unless_cancelled(Duration::from_millis(10), &cancel).await);
+            assert!(!cancel.is_cancelled());
+
+            // Check that a long sleep is interrupted promptly on shutdown.
+            shutdown.store(true, Ordering::Relaxed);
+            let sleep = sleep_unless_cancelled(Duration::from_secs(60), &cancel);
+            assert!(!tokio::time::timeout(Duration::from_secs(5), sleep).await.unwrap());
+            assert!(cancel.is_cancelled());
         });
     }
 
@@ -511,30 +1202,10 @@ mod tests {
     fn test_cdn_get() {
         let rt = tokio::runtime::Runtime::new().unwrap();
         rt.block_on(async {
-            let client = reqwest::Client::new();
+            let client = CdnClientConfig::default().build().unwrap();
             let height =
                 cdn_get::<u32>(client, &format!("{TEST_BASE_URL}/mainnet/latest/height"), "height").await.unwrap();
             assert!(height > 0);
         });
     }
-
-    #[test]
-    fn test_log_progress() {
-        // This test sanity checks that basic arithmetic is correct (i.e. no divide by zero, etc.).
-        let timer = Instant::now();
-        let cdn_start = 0;
-        let cdn_end = 100;
-        let object_name = "blocks";
-        log_progress::<10>(timer, 0, cdn_start, cdn_end, object_name);
-        log_progress::<10>(timer, 10, cdn_start, cdn_end, object_name);
-        log_progress::<10>(timer, 20, cdn_start, cdn_end, object_name);
-        log_progress::<10>(timer, 30, cdn_start, cdn_end, object_name);
-        log_progress::<10>(timer, 40, cdn_start, cdn_end, object_name);
-        log_progress::<10>(timer, 50, cdn_start, cdn_end, object_name);
-        log_progress::<10>(timer, 60, cdn_start, cdn_end, object_name);
-        log_progress::<10>(timer, 70, cdn_start, cdn_end, object_name);
-        log_progress::<10>(timer, 80, cdn_start, cdn_end, object_name);
-        log_progress::<10>(timer, 90, cdn_start, cdn_end, object_name);
-        log_progress::<10>(timer, 100, cdn_start, cdn_end, object_name);
-    }
 }
@@ -0,0 +1,136 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::blocks::check_bundle;
+use snarkvm::prelude::{block::Block, Network};
+
+use anyhow::{ensure, Result};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// An on-disk cache of the block bundles downloaded from the CDN, so that a node restarted
+/// in the middle of a sync does not download the bundles it already fetched again.
+///
+/// Every bundle is stored in its own file, named after its block range and the hash of its last block,
+/// which is checked when the bundle is loaded. The bundles are pruned once all their blocks are inserted.
+pub(crate) struct BundleCache {
+    /// The directory of the cached bundles.
+    dir: PathBuf,
+}
+
+impl BundleCache {
+    /// Initializes the bundle cache in the given directory, creating it if necessary.
+    pub(crate) fn new(dir: &Path) -> Result<Self> {
+        fs::create_dir_all(dir)?;
+        Ok(Self { dir: dir.to_path_buf() })
+    }
+
+    /// Returns the cached bundle of blocks `start..end`, if any.
+    /// Note: A cached bundle that fails to load is removed, so it is downloaded again.
+    pub(crate) fn load<N: Network>(&self, start: u32, end: u32) -> Option<Vec<Block<N>>> {
+        let (_, _, hash, path) = self.entries().find(|(s, e, _, _)| (*s, *e) == (start, end))?;
+        match Self::read::<N>(&path, start, end, &hash) {
+            Ok(blocks) => Some(blocks),
+            Err(error) => {
+                warn!("Discarding the cached bundle of blocks {start} to {end} - {error}");
+                let _ = fs::remove_file(path);
+                None
+            }
+        }
+    }
+
+    /// Stores the given bundle of blocks `start..end`.
+    pub(crate) fn store<N: Network>(&self, start: u32, end: u32, blocks: &[Block<N>]) -> Result<()> {
+        let Some(last) = blocks.last() else { return Ok(()) };
+        let path = self.dir.join(format!("{start}.{end}.{}.blocks", last.hash()));
+        // Write to a temporary file first, so that an interrupted write is never mistaken for a bundle.
+        let temp_path = path.with_extension("tmp");
+        fs::write(&temp_path, bincode::serialize(blocks)?)?;
+        fs::rename(temp_path, path)?;
+        Ok(())
+    }
+
+    /// Removes the cached bundles whose blocks are all at or below the given height.
+    pub(crate) fn prune(&self, height: u32) {
+        for (_, end, _, path) in self.entries().filter(|(_, end, _, _)| *end <= height + 1) {
+            if let Err(error) = fs::remove_file(&path) {
+                warn!("Failed to prune the cached bundle '{}' - {error}", path.display());
+            } else {
+                trace!("Pruned the cached bundle of blocks up to {end}");
+            }
+        }
+    }
+
+    /// Reads the bundle at the given path, ensuring it holds the blocks `start..end`, ending with the given hash.
+    fn read<N: Network>(path: &Path, start: u32, end: u32, hash: &str) -> Result<Vec<Block<N>>> {
+        let blocks: Vec<Block<N>> = bincode::deserialize(&fs::read(path)?)?;
+        ensure!(blocks.last().map_or(false, |block| block.hash().to_string() == hash), "The bundle hash mismatches");
+        check_bundle(&blocks, start, end)?;
+        Ok(blocks)
+    }
+
+    /// Returns the `(start, end, hash, path)` of the cached bundles.
+    fn entries(&self) -> impl Iterator<Item = (u32, u32, String, PathBuf)> {
+        fs::read_dir(&self.dir).into_iter().flatten().flatten().filter_map(|entry| {
+            let path = entry.path();
+            let name = path.file_name()?.to_str()?;
+            let mut parts = name.strip_suffix(".blocks")?.split('.');
+            let start = parts.next()?.parse().ok()?;
+            let end = parts.next()?.parse().ok()?;
+            let hash = parts.next()?.to_string();
+            Some((start, end, hash, path))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkvm::prelude::{FromBytes, MainnetV0};
+
+    type CurrentNetwork = MainnetV0;
+
+    #[test]
+    fn test_bundle_cache() {
+        let genesis = Block::<CurrentNetwork>::from_bytes_le(CurrentNetwork::genesis_bytes()).unwrap();
+        let dir = std::env::temp_dir().join(format!("snarkos-cdn-cache-{}", std::process::id()));
+        let cache = BundleCache::new(&dir).unwrap();
+
+        // Check that a missing bundle is not found.
+        assert!(cache.load::<CurrentNetwork>(0, 50).is_none());
+
+        // Store a bundle, and check that it is loaded (e.g. after a restart).
+        cache.store(0, 50, &[genesis.clone()]).unwrap();
+        let cache = BundleCache::new(&dir).unwrap();
+        assert_eq!(cache.load::<CurrentNetwork>(0, 50), Some(vec![genesis.clone()]));
+        assert!(cache.load::<CurrentNetwork>(50, 100).is_none());
+
+        // Check that the bundle is kept until all its blocks are inserted.
+        cache.prune(48);
+        assert!(cache.load::<CurrentNetwork>(0, 50).is_some());
+        cache.prune(49);
+        assert!(cache.load::<CurrentNetwork>(0, 50).is_none());
+
+        // Check that a corrupted bundle is discarded.
+        cache.store(0, 50, &[genesis.clone()]).unwrap();
+        let (_, _, _, path) = cache.entries().next().unwrap();
+        fs::write(&path, b"corrupted").unwrap();
+        assert!(cache.load::<CurrentNetwork>(0, 50).is_none());
+        assert!(!path.exists());
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
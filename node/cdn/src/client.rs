@@ -0,0 +1,183 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::blocks::CONCURRENT_REQUESTS;
+
+use anyhow::{anyhow, Result};
+use reqwest::{
+    header::{HeaderValue, AUTHORIZATION},
+    Client,
+    IntoUrl,
+    RequestBuilder,
+};
+use std::{fmt, sync::Arc, time::Duration};
+
+/// A callback that signs the URL of a CDN request, e.g. by appending the signature query string
+/// of an authenticated object storage.
+#[derive(Clone)]
+pub struct UrlSigner(Arc<dyn Fn(&str) -> String + Send + Sync>);
+
+impl UrlSigner {
+    /// Initializes a new URL signer with the given callback.
+    pub fn new(sign: impl Fn(&str) -> String + Send + Sync + 'static) -> Self {
+        Self(Arc::new(sign))
+    }
+
+    /// Returns the signed URL for the given URL.
+    pub fn sign(&self, url: &str) -> String {
+        (self.0)(url)
+    }
+}
+
+impl fmt::Debug for UrlSigner {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("UrlSigner")
+    }
+}
+
+impl PartialEq for UrlSigner {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl Eq for UrlSigner {}
+
+/// An HTTP client for the requests to the CDN, which authenticates every request as configured.
+#[derive(Clone, Debug)]
+pub struct CdnClient {
+    /// The HTTP client.
+    client: Client,
+    /// The value of the `Authorization` header, if any.
+    authorization: Option<HeaderValue>,
+    /// The URL signer, if any.
+    url_signer: Option<UrlSigner>,
+}
+
+impl CdnClient {
+    /// Returns a GET request for the given URL, authenticated as configured.
+    pub fn get(&self, url: impl IntoUrl + AsRef<str>) -> RequestBuilder {
+        // Sign the URL, if a signer is configured.
+        let request = match &self.url_signer {
+            Some(signer) => self.client.get(signer.sign(url.as_ref())),
+            None => self.client.get(url),
+        };
+        // Attach the authorization header, if configured.
+        match &self.authorization {
+            Some(authorization) => request.header(AUTHORIZATION, authorization.clone()),
+            None => request,
+        }
+    }
+}
+
+/// The HTTP client settings for the requests to the CDN.
+///
+/// A single client is built per sync and shared by all of its requests, so that the connections are pooled.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CdnClientConfig {
+    /// The maximum number of idle connections kept in the pool (per host).
+    pub pool_max_idle_per_host: usize,
+    /// The duration after which an idle connection is closed.
+    pub pool_idle_timeout: Duration,
+    /// The interval of the TCP keepalive probes.
+    /// Note: If `None`, TCP keepalive is disabled.
+    pub tcp_keepalive: Option<Duration>,
+    /// The timeout to establish a connection.
+    pub connect_timeout: Duration,
+    /// If `true`, the requests are sent over HTTP/2 without negotiation.
+    /// Note: This requires the CDN to support HTTP/2.
+    pub http2_prior_knowledge: bool,
+    /// The value of the `Authorization` header sent with every request (e.g. `Bearer <token>`), if any.
+    pub authorization: Option<String>,
+    /// The callback that signs the URL of every request, if any.
+    pub url_signer: Option<UrlSigner>,
+}
+
+impl Default for CdnClientConfig {
+    /// Returns the default client settings, which keep a connection per concurrent request alive.
+    fn default() -> Self {
+        Self {
+            pool_max_idle_per_host: CONCURRENT_REQUESTS as usize,
+            pool_idle_timeout: Duration::from_secs(90),
+            tcp_keepalive: Some(Duration::from_secs(60)),
+            connect_timeout: Duration::from_secs(30),
+            http2_prior_knowledge: false,
+            authorization: None,
+            url_signer: None,
+        }
+    }
+}
+
+impl CdnClientConfig {
+    /// Builds an HTTP client with these settings.
+    pub fn build(&self) -> Result<CdnClient> {
+        let mut builder = Client::builder()
+            .pool_max_idle_per_host(self.pool_max_idle_per_host)
+            .pool_idle_timeout(self.pool_idle_timeout)
+            .tcp_keepalive(self.tcp_keepalive)
+            .connect_timeout(self.connect_timeout);
+        if self.http2_prior_knowledge {
+            builder = builder.http2_prior_knowledge().http2_adaptive_window(true);
+        }
+        let client = builder.build().map_err(|error| anyhow!("Failed to create a CDN request client - {error}"))?;
+
+        // Prepare the authorization header.
+        let authorization = match &self.authorization {
+            Some(authorization) => {
+                let mut value = HeaderValue::from_str(authorization)
+                    .map_err(|_| anyhow!("The CDN authorization header contains invalid characters"))?;
+                // Keep the credentials out of the logs.
+                value.set_sensitive(true);
+                Some(value)
+            }
+            None => None,
+        };
+
+        Ok(CdnClient { client, authorization, url_signer: self.url_signer.clone() })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_client() {
+        assert!(CdnClientConfig::default().build().is_ok());
+
+        let config = CdnClientConfig { tcp_keepalive: None, http2_prior_knowledge: true, ..Default::default() };
+        assert!(config.build().is_ok());
+    }
+
+    #[test]
+    fn test_authorization() {
+        let config = CdnClientConfig { authorization: Some("Bearer token".to_string()), ..Default::default() };
+        let request = config.build().unwrap().get("https://cdn.example.com/latest.json").build().unwrap();
+        assert_eq!(request.headers()[AUTHORIZATION], "Bearer token");
+        assert!(request.headers()[AUTHORIZATION].is_sensitive());
+
+        // Check that an invalid header value is rejected.
+        let config = CdnClientConfig { authorization: Some("Bearer\ntoken".to_string()), ..Default::default() };
+        assert!(config.build().is_err());
+    }
+
+    #[test]
+    fn test_url_signer() {
+        let signer = UrlSigner::new(|url| format!("{url}?signature=abc"));
+        let config = CdnClientConfig { url_signer: Some(signer), ..Default::default() };
+        let request = config.build().unwrap().get("https://cdn.example.com/latest.json").build().unwrap();
+        assert_eq!(request.url().as_str(), "https://cdn.example.com/latest.json?signature=abc");
+        assert!(!request.headers().contains_key(AUTHORIZATION));
+    }
+}
@@ -0,0 +1,79 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::CdnClientConfig;
+
+use std::{path::PathBuf, time::Duration};
+
+/// The CDN sync configuration of a node.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CdnConfig {
+    /// The base URL of the CDN.
+    pub base_url: String,
+    /// The base URLs of the mirrors of the CDN, which serve the same block bundles.
+    /// Note: The bundle requests are biased toward the fastest of the CDN and its mirrors.
+    pub mirrors: Vec<String>,
+    /// The median bundle download time above which a mirror is avoided.
+    pub slow_mirror_threshold: Duration,
+    /// The number of failed CDN requests tolerated before falling back to P2P sync.
+    /// Note: If `None`, the CDN sync does not fall back to P2P sync.
+    pub error_budget: Option<u32>,
+    /// The number of blocks below the tip reported by the CDN that are left to P2P sync,
+    /// in case the latest blocks are not yet available on the CDN.
+    pub safety_margin: u32,
+    /// The block height to sync up to (inclusive).
+    /// Note: If `None`, the ledger is synced up to the tip of the CDN (minus the safety margin).
+    pub sync_to_height: Option<u32>,
+    /// The HTTP client settings for the requests to the CDN.
+    pub client: CdnClientConfig,
+    /// The directory to cache the downloaded block bundles in, until their blocks are inserted,
+    /// so that a restarted sync does not download them again.
+    /// Note: If `None`, the downloaded bundles are not cached.
+    pub cache_dir: Option<PathBuf>,
+    /// The directory to spill the pending blocks to, when their insertion falls behind the downloads.
+    /// Note: If `None`, the downloads are paused instead.
+    pub spill_dir: Option<PathBuf>,
+}
+
+impl CdnConfig {
+    /// The default number of blocks below the tip reported by the CDN that are left to P2P sync.
+    pub const DEFAULT_SAFETY_MARGIN: u32 = 10;
+    /// The default median bundle download time above which a mirror is avoided.
+    pub const DEFAULT_SLOW_MIRROR_THRESHOLD: Duration = Duration::from_secs(30);
+
+    /// Initializes a new CDN configuration with the given base URL, and the default values.
+    pub fn new(base_url: String) -> Self {
+        Self {
+            base_url,
+            mirrors: Vec::new(),
+            slow_mirror_threshold: Self::DEFAULT_SLOW_MIRROR_THRESHOLD,
+            error_budget: None,
+            safety_margin: Self::DEFAULT_SAFETY_MARGIN,
+            sync_to_height: None,
+            client: Default::default(),
+            cache_dir: None,
+            spill_dir: None,
+        }
+    }
+
+    /// Returns the base URLs of the CDN and its mirrors.
+    pub(crate) fn mirror_urls(&self) -> Vec<String> {
+        std::iter::once(&self.base_url).chain(&self.mirrors).cloned().collect()
+    }
+
+    /// Returns the (exclusive) end height of the sync, if a sync height is configured.
+    pub(crate) fn end_height(&self) -> Option<u32> {
+        self.sync_to_height.map(|height| height.saturating_add(1))
+    }
+}
@@ -0,0 +1,185 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::{sync_ledger_with_cdn, CdnConfig, CdnSyncObserver};
+use snarkvm::prelude::{store::ConsensusStorage, Ledger, Network};
+
+use std::{
+    fmt,
+    sync::{
+        atomic::{AtomicBool, AtomicU32, Ordering},
+        Arc,
+    },
+};
+
+/// The number of failed CDN requests tolerated during a sync, before the sync is cancelled.
+pub struct ErrorBudget {
+    /// The maximum number of failed requests.
+    max_failures: u32,
+    /// The number of failed requests so far.
+    failures: AtomicU32,
+}
+
+impl ErrorBudget {
+    /// Initializes a new error budget, tolerating the given number of failed requests.
+    pub fn new(max_failures: u32) -> Self {
+        Self { max_failures, failures: Default::default() }
+    }
+
+    /// Records a failed request, returning `true` if the error budget is now exhausted.
+    pub fn record_failure(&self) -> bool {
+        self.failures.fetch_add(1, Ordering::Relaxed) + 1 > self.max_failures
+    }
+
+    /// Returns the number of failed requests so far.
+    pub fn failures(&self) -> u32 {
+        self.failures.load(Ordering::Relaxed)
+    }
+
+    /// Exhausts the error budget, e.g. once a request has failed too many times to be retried.
+    pub fn exhaust(&self) {
+        self.failures.fetch_max(self.max_failures.saturating_add(1), Ordering::Relaxed);
+    }
+
+    /// Returns `true` if the error budget is exhausted.
+    pub fn is_exhausted(&self) -> bool {
+        self.failures() > self.max_failures
+    }
+}
+
+/// The error returned when a CDN sync is cancelled because its error budget is exhausted.
+#[derive(Debug)]
+pub struct ErrorBudgetExhausted {
+    /// The number of failed requests.
+    pub failures: u32,
+}
+
+impl fmt::Display for ErrorBudgetExhausted {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Cancelled the CDN sync after {} failed requests (error budget exhausted)", self.failures)
+    }
+}
+
+impl std::error::Error for ErrorBudgetExhausted {}
+
+/// Syncs the ledger with the CDN according to the given configuration, falling back to P2P sync
+/// when the CDN is unreliable.
+///
+/// If the configuration has an error budget and it is exhausted, the CDN sync is cancelled cleanly,
+/// keeping the blocks synced so far, and this function returns the completed block height.
+/// The remaining blocks are then synced from the peers by the block sync of the node,
+/// which resumes from the latest block in the ledger.
+///
+/// On success, this function returns the completed block height.
+/// On failure, this function returns the last successful block height (if any), along with the error.
+pub async fn sync_ledger_with_fallback<N: Network, C: ConsensusStorage<N>>(
+    cdn: &CdnConfig,
+    ledger: Ledger<N, C>,
+    shutdown: Arc<AtomicBool>,
+    observer: Option<Arc<dyn CdnSyncObserver>>,
+) -> Result<u32, (u32, anyhow::Error)> {
+    // Initialize the error budget.
+    let error_budget = cdn.error_budget.map(|max_failures| Arc::new(ErrorBudget::new(max_failures)));
+
+    // Sync the ledger with the CDN.
+    let result = sync_ledger_with_cdn(cdn, ledger.clone(), shutdown, observer, error_budget.clone()).await;
+
+    // If the error budget was exhausted, hand the remaining blocks over to the P2P sync.
+    match (result, error_budget) {
+        (Err((_, error)), Some(_)) if error.is::<ErrorBudgetExhausted>() => {
+            let height = ledger.latest_height();
+            warn!("{error} - falling back to P2P sync from block {height}");
+            Ok(height)
+        }
+        (Ok(height), Some(error_budget)) if error_budget.is_exhausted() => {
+            warn!("The CDN error budget is exhausted - falling back to P2P sync from block {height}");
+            Ok(height)
+        }
+        (result, _) => result,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::blocks::LatestState;
+    use snarkvm::prelude::{block::Block, store::helpers::memory::ConsensusMemory, FromBytes, MainnetV0};
+
+    use aleo_std::StorageMode;
+    use axum::{http::StatusCode, routing::get};
+    use tokio::net::TcpListener;
+
+    type CurrentNetwork = MainnetV0;
+
+    #[test]
+    fn test_error_budget() {
+        let budget = ErrorBudget::new(2);
+        assert!(!budget.is_exhausted());
+
+        // Check that the failures within the budget are tolerated.
+        assert!(!budget.record_failure());
+        assert!(!budget.record_failure());
+        assert!(!budget.is_exhausted());
+
+        // Check that the budget is exhausted by the next failure.
+        assert!(budget.record_failure());
+        assert!(budget.is_exhausted());
+        assert_eq!(budget.failures(), 3);
+    }
+
+    #[test]
+    fn test_error_budget_zero() {
+        let budget = ErrorBudget::new(0);
+        assert!(!budget.is_exhausted());
+        assert!(budget.record_failure());
+        assert!(budget.is_exhausted());
+    }
+
+    #[test]
+    fn test_error_budget_exhaust() {
+        let budget = ErrorBudget::new(5);
+        assert!(!budget.record_failure());
+        budget.exhaust();
+        assert!(budget.is_exhausted());
+        assert_eq!(budget.failures(), 6);
+    }
+
+    #[test]
+    fn test_sync_ledger_with_fallback() {
+        // Initialize a ledger at the genesis block.
+        let genesis = Block::<CurrentNetwork>::read_le(CurrentNetwork::genesis_bytes()).unwrap();
+        let ledger = Ledger::<_, ConsensusMemory<_>>::load(genesis, StorageMode::Production).unwrap();
+
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        rt.block_on(async {
+            // Serve a CDN that advertises blocks, but fails to serve them.
+            let latest = LatestState { exclusive_height: 100, inclusive_height: 99, hash: String::new() };
+            let latest = bincode::serialize(&serde_json::to_string(&latest).unwrap()).unwrap();
+            let router = axum::Router::new()
+                .route("/latest.json", get(move || async move { latest }))
+                .route("/:file", get(|| async { StatusCode::INTERNAL_SERVER_ERROR }));
+            let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+            let base_url = format!("http://{}", listener.local_addr().unwrap());
+            tokio::spawn(async move { axum::serve(listener, router).await });
+
+            // Check that the sync falls back to P2P sync once the error budget is exhausted.
+            let shutdown: Arc<AtomicBool> = Default::default();
+            let cdn = CdnConfig { error_budget: Some(0), ..CdnConfig::new(base_url) };
+            let result = sync_ledger_with_fallback(&cdn, ledger, shutdown.clone(), None).await;
+            assert_eq!(result.unwrap(), 0);
+            // Check that the node is not shut down.
+            assert!(!shutdown.load(Ordering::Relaxed));
+        });
+    }
+}
@@ -18,4 +18,28 @@
 extern crate tracing;
 
 mod blocks;
-pub use blocks::{load_blocks, sync_ledger_with_cdn};
+pub use blocks::{load_blocks, sync_block_store_with_cdn, sync_ledger_with_cdn, LatestHashMismatch, RateLimited};
+
+mod client;
+pub use client::{CdnClient, CdnClientConfig, UrlSigner};
+
+mod config;
+pub use config::CdnConfig;
+
+mod fallback;
+pub use fallback::{sync_ledger_with_fallback, ErrorBudget, ErrorBudgetExhausted};
+
+mod cache;
+
+mod mirrors;
+
+mod observer;
+pub use observer::CdnSyncObserver;
+
+mod progress;
+pub use progress::{ProgressWatcher, SyncProgress};
+
+mod server;
+pub use server::get_cdn_file;
+
+mod spill;
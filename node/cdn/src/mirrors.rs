@@ -0,0 +1,160 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use parking_lot::Mutex;
+use std::{collections::VecDeque, time::Duration};
+
+/// The number of recent bundle download times tracked per mirror.
+const NUM_SAMPLES: usize = 16;
+/// The minimum number of samples before a mirror can be demoted.
+const MIN_SAMPLES_TO_DEMOTE: usize = 3;
+
+/// The download statistics of a CDN mirror.
+#[derive(Default)]
+struct MirrorStats {
+    /// The recent bundle download times, with the most recent last.
+    samples: VecDeque<Duration>,
+    /// The number of bundles currently requested from the mirror.
+    in_flight: u32,
+}
+
+impl MirrorStats {
+    /// Returns the median of the recent bundle download times, if any.
+    fn median(&self) -> Option<Duration> {
+        let mut samples: Vec<_> = self.samples.iter().copied().collect();
+        samples.sort_unstable();
+        samples.get(samples.len() / 2).copied()
+    }
+}
+
+/// The set of CDN mirrors serving the block bundles, which tracks the rolling download time of
+/// every mirror, in order to bias the bundle requests toward the fastest mirrors.
+///
+/// A mirror whose median bundle time exceeds the slow threshold is demoted, i.e. it is only used
+/// when all the mirrors are demoted. Note: Failed requests count as a sample of twice the threshold.
+pub(crate) struct Mirrors {
+    /// The base URLs of the mirrors.
+    base_urls: Vec<String>,
+    /// The download statistics of the mirrors.
+    stats: Mutex<Vec<MirrorStats>>,
+    /// The median bundle time above which a mirror is demoted.
+    slow_threshold: Duration,
+}
+
+impl Mirrors {
+    /// Initializes the mirrors with the given base URLs (which must not be empty).
+    pub(crate) fn new(base_urls: Vec<String>, slow_threshold: Duration) -> Self {
+        debug_assert!(!base_urls.is_empty(), "There must be at least one CDN mirror");
+        let stats = Mutex::new(base_urls.iter().map(|_| MirrorStats::default()).collect());
+        Self { base_urls, stats, slow_threshold }
+    }
+
+    /// Returns the base URL of the given mirror.
+    pub(crate) fn base_url(&self, mirror: usize) -> &str {
+        &self.base_urls[mirror]
+    }
+
+    /// Selects the mirror for the next bundle request, and marks the request as in flight.
+    ///
+    /// The selected mirror is the one with the lowest expected wait (i.e. its median bundle time,
+    /// times the number of its requests in flight), preferring the mirrors that are not demoted.
+    /// Mirrors without samples yet are tried first, so every mirror gets measured.
+    pub(crate) fn select(&self) -> usize {
+        let mut stats = self.stats.lock();
+        let mirror = (0..stats.len())
+            .min_by_key(|&i| {
+                let median = stats[i].median();
+                let is_demoted = stats[i].samples.len() >= MIN_SAMPLES_TO_DEMOTE
+                    && median.map_or(false, |median| median > self.slow_threshold);
+                let expected_wait = median.unwrap_or_default() * (stats[i].in_flight + 1);
+                (is_demoted, expected_wait, stats[i].in_flight)
+            })
+            .unwrap_or_default();
+        stats[mirror].in_flight += 1;
+        mirror
+    }
+
+    /// Records a successful bundle request to the given mirror.
+    pub(crate) fn record_success(&self, mirror: usize, elapsed: Duration) {
+        self.record(mirror, elapsed);
+    }
+
+    /// Records a failed bundle request to the given mirror.
+    pub(crate) fn record_failure(&self, mirror: usize) {
+        self.record(mirror, self.slow_threshold * 2);
+    }
+
+    /// Records the outcome of a bundle request to the given mirror.
+    fn record(&self, mirror: usize, sample: Duration) {
+        let mut stats = self.stats.lock();
+        let stats = &mut stats[mirror];
+        stats.in_flight = stats.in_flight.saturating_sub(1);
+        if stats.samples.len() == NUM_SAMPLES {
+            stats.samples.pop_front();
+        }
+        stats.samples.push_back(sample);
+        // Log when the mirror is demoted.
+        if stats.samples.len() >= MIN_SAMPLES_TO_DEMOTE {
+            if let Some(median) = stats.median().filter(|median| *median > self.slow_threshold) {
+                debug!("The CDN mirror '{}' is slow (median {median:.2?} per bundle)", self.base_urls[mirror]);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mirrors() -> Mirrors {
+        Mirrors::new(vec!["a".to_string(), "b".to_string()], Duration::from_secs(10))
+    }
+
+    #[test]
+    fn test_select_untested_mirrors_first() {
+        let mirrors = mirrors();
+        // Check that the requests are spread over the mirrors without samples.
+        assert_eq!(mirrors.select(), 0);
+        assert_eq!(mirrors.select(), 1);
+        assert_eq!(mirrors.select(), 0);
+    }
+
+    #[test]
+    fn test_select_fastest_mirror() {
+        let mirrors = mirrors();
+        for _ in 0..3 {
+            mirrors.record_success(0, Duration::from_secs(4));
+            mirrors.record_success(1, Duration::from_secs(1));
+        }
+        // Check that the requests are biased toward the fastest mirror.
+        assert_eq!(mirrors.select(), 1);
+        assert_eq!(mirrors.select(), 1);
+        assert_eq!(mirrors.select(), 1);
+        // Check that the slower mirror is used once the fastest one is busy.
+        assert_eq!(mirrors.select(), 0);
+    }
+
+    #[test]
+    fn test_demote_slow_mirror() {
+        let mirrors = mirrors();
+        for _ in 0..3 {
+            mirrors.record_success(0, Duration::from_secs(1));
+            mirrors.record_failure(1);
+        }
+        // Check that the demoted mirror is not used, regardless of the load of the other mirror.
+        for _ in 0..10 {
+            assert_eq!(mirrors.select(), 0);
+        }
+    }
+}
@@ -0,0 +1,45 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::SyncProgress;
+
+use std::time::Duration;
+
+/// An observer of the CDN sync lifecycle.
+///
+/// Every event has an empty default implementation, so implementors only override the events they need.
+/// Note: Events are invoked from the download and insertion tasks, so implementations must not block.
+pub trait CdnSyncObserver: Send + Sync {
+    /// Invoked when the bundle of blocks `start..end` is requested from the CDN.
+    fn bundle_requested(&self, _start: u32, _end: u32) {}
+
+    /// Invoked when the bundle of blocks `start..end` is downloaded from the CDN.
+    fn bundle_downloaded(&self, _start: u32, _end: u32, _elapsed: Duration) {}
+
+    /// Invoked when a request for the bundle of blocks `start..end` fails (it may be retried).
+    fn bundle_failed(&self, _start: u32, _end: u32, _error: &anyhow::Error) {}
+
+    /// Invoked when a request for the bundle of blocks `start..end` is rate-limited by the CDN,
+    /// with the delay requested by its `Retry-After` header (if any).
+    fn rate_limited(&self, _start: u32, _end: u32, _retry_after: Option<Duration>) {}
+
+    /// Invoked when the block at the given height is processed (i.e. inserted into the ledger).
+    fn block_inserted(&self, _height: u32) {}
+
+    /// Invoked when the sync progresses, after a block is inserted.
+    fn progress(&self, _progress: &SyncProgress) {}
+
+    /// Invoked when the sync fails, with the last successful block height.
+    fn sync_failed(&self, _height: u32, _error: &anyhow::Error) {}
+}
@@ -0,0 +1,194 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::CdnSyncObserver;
+use snarkvm::prelude::Serialize;
+
+use colored::Colorize;
+use std::{
+    fmt,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+use tokio::sync::watch;
+
+/// The minimum interval between two samples of the sync rate.
+const SAMPLE_INTERVAL: Duration = Duration::from_secs(1);
+/// The weight of the latest sample in the moving average of the sync rate.
+const SMOOTHING_FACTOR: f64 = 0.2;
+
+/// The progress of a CDN sync.
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct SyncProgress {
+    /// The first block height of the sync.
+    pub start_height: u32,
+    /// The last synced block height.
+    pub current_height: u32,
+    /// The last block height of the sync.
+    pub end_height: u32,
+    /// The percentage of the sync completed.
+    pub percentage: f64,
+    /// The sync rate, in blocks per second (as an exponential moving average).
+    pub blocks_per_second: f64,
+    /// The estimated time remaining, if the sync rate is known.
+    pub eta: Option<Duration>,
+}
+
+impl SyncProgress {
+    /// Returns `true` if the sync is complete.
+    pub fn is_complete(&self) -> bool {
+        self.current_height >= self.end_height
+    }
+}
+
+impl fmt::Display for SyncProgress {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Synced up to block {} of {} - {:.0}% complete",
+            self.current_height, self.end_height, self.percentage
+        )?;
+        match self.eta {
+            Some(eta) => write!(f, " {}", format!("(est. {} minutes remaining)", eta.as_secs() / 60).dimmed()),
+            None => Ok(()),
+        }
+    }
+}
+
+/// An observer that publishes the progress of a CDN sync to a watch channel,
+/// so the progress can be consumed programmatically (e.g. by the display, REST, or metrics).
+pub struct ProgressWatcher {
+    /// The sender of the progress.
+    sender: watch::Sender<SyncProgress>,
+}
+
+impl ProgressWatcher {
+    /// Initializes a new progress watcher, returning it along with the receiver of the progress.
+    pub fn channel() -> (Arc<Self>, watch::Receiver<SyncProgress>) {
+        let (sender, receiver) = watch::channel(SyncProgress::default());
+        (Arc::new(Self { sender }), receiver)
+    }
+}
+
+impl CdnSyncObserver for ProgressWatcher {
+    fn progress(&self, progress: &SyncProgress) {
+        self.sender.send_replace(progress.clone());
+    }
+}
+
+/// Tracks the progress of a CDN sync, estimating the sync rate with a moving average.
+pub(crate) struct ProgressTracker {
+    /// The current progress.
+    progress: SyncProgress,
+    /// The time and block height of the last sample of the sync rate.
+    last_sample: (Instant, u32),
+}
+
+impl ProgressTracker {
+    /// Initializes a new tracker for a sync of the blocks `start_height..=end_height`.
+    pub(crate) fn new(start_height: u32, end_height: u32) -> Self {
+        let progress = SyncProgress {
+            start_height,
+            current_height: start_height.saturating_sub(1),
+            end_height,
+            ..Default::default()
+        };
+        Self { progress, last_sample: (Instant::now(), start_height.saturating_sub(1)) }
+    }
+
+    /// Updates the progress with the given synced block height, returning the new progress.
+    pub(crate) fn update(&mut self, current_height: u32) -> &SyncProgress {
+        self.update_at(current_height, Instant::now())
+    }
+
+    /// Updates the progress with the given synced block height at the given time, returning the new progress.
+    fn update_at(&mut self, current_height: u32, now: Instant) -> &SyncProgress {
+        let progress = &mut self.progress;
+        progress.current_height = current_height;
+
+        // Compute the percentage completed.
+        let num_blocks = (progress.end_height + 1).saturating_sub(progress.start_height).max(1);
+        let num_synced = (current_height + 1).saturating_sub(progress.start_height);
+        progress.percentage = (num_synced as f64 * 100.0 / num_blocks as f64).min(100.0);
+
+        // Sample the sync rate, and fold it into the moving average.
+        let (last_time, last_height) = self.last_sample;
+        let elapsed = now.saturating_duration_since(last_time);
+        if elapsed >= SAMPLE_INTERVAL {
+            let rate = current_height.saturating_sub(last_height) as f64 / elapsed.as_secs_f64();
+            progress.blocks_per_second = match progress.blocks_per_second {
+                average if average > 0.0 => SMOOTHING_FACTOR * rate + (1.0 - SMOOTHING_FACTOR) * average,
+                _ => rate,
+            };
+            self.last_sample = (now, current_height);
+        }
+
+        // Estimate the time remaining.
+        let num_remaining = progress.end_height.saturating_sub(current_height);
+        progress.eta = match progress.blocks_per_second > 0.0 {
+            true => Some(Duration::from_secs_f64(num_remaining as f64 / progress.blocks_per_second)),
+            false => None,
+        };
+
+        progress
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_progress_tracker() {
+        let mut tracker = ProgressTracker::new(1, 100);
+        let start = tracker.last_sample.0;
+
+        // Check that the rate is unknown before the first sample.
+        let progress = tracker.update_at(10, start).clone();
+        assert_eq!(progress.current_height, 10);
+        assert_eq!(progress.percentage, 10.0);
+        assert_eq!(progress.blocks_per_second, 0.0);
+        assert_eq!(progress.eta, None);
+
+        // Check the first sample: 20 blocks in 2 seconds.
+        let progress = tracker.update_at(20, start + Duration::from_secs(2)).clone();
+        assert_eq!(progress.blocks_per_second, 10.0);
+        assert_eq!(progress.eta, Some(Duration::from_secs(8)));
+
+        // Check that the samples are smoothed: 30 blocks in 1 second.
+        let progress = tracker.update_at(50, start + Duration::from_secs(3)).clone();
+        assert!((progress.blocks_per_second - 14.0).abs() < 1e-9);
+        assert_eq!(progress.percentage, 50.0);
+
+        // Check that updates within the sample interval keep the rate.
+        let progress = tracker.update_at(60, start + Duration::from_millis(3500)).clone();
+        assert!((progress.blocks_per_second - 14.0).abs() < 1e-9);
+
+        // Check the completion.
+        let progress = tracker.update_at(100, start + Duration::from_secs(5)).clone();
+        assert!(progress.is_complete());
+        assert_eq!(progress.percentage, 100.0);
+        assert_eq!(progress.eta, Some(Duration::ZERO));
+    }
+
+    #[test]
+    fn test_progress_watcher() {
+        let (watcher, receiver) = ProgressWatcher::channel();
+        assert_eq!(*receiver.borrow(), SyncProgress::default());
+
+        let progress = SyncProgress { start_height: 1, current_height: 5, end_height: 10, ..Default::default() };
+        watcher.progress(&progress);
+        assert_eq!(*receiver.borrow(), progress);
+    }
+}
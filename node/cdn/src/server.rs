@@ -0,0 +1,141 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::blocks::{LatestState, BLOCKS_PER_FILE};
+use snarkvm::prelude::{
+    block::Block,
+    store::{BlockStorage, BlockStore},
+    Network,
+};
+
+use anyhow::{anyhow, Result};
+use axum::http::StatusCode;
+
+/// Returns the CDN file with the given name from the block store, i.e. 'latest.json' or a complete bundle
+/// named `{start}.{end}.blocks`, so that a running node can serve the CDN format (e.g. from its REST server)
+/// and other nodes can sync from it with `--cdn`.
+pub async fn get_cdn_file<N: Network, B: BlockStorage<N>>(
+    block_store: BlockStore<N, B>,
+    file: String,
+) -> Result<Vec<u8>, (StatusCode, String)> {
+    match file.as_str() {
+        "latest.json" => get_latest(block_store),
+        _ => get_bundle(block_store, file).await,
+    }
+}
+
+/// Returns the exclusive height of the complete bundles in the block store.
+fn exclusive_height<N: Network, B: BlockStorage<N>>(block_store: &BlockStore<N, B>) -> u32 {
+    let num_blocks = block_store.max_height().map_or(0, |height| height + 1);
+    num_blocks - (num_blocks % BLOCKS_PER_FILE)
+}
+
+/// Returns the block at the given height from the block store.
+fn get_block<N: Network, B: BlockStorage<N>>(block_store: &BlockStore<N, B>, height: u32) -> Result<Block<N>> {
+    let hash = block_store.get_block_hash(height)?.ok_or_else(|| anyhow!("Missing the hash for block {height}"))?;
+    block_store.get_block(&hash)?.ok_or_else(|| anyhow!("Missing block {height}"))
+}
+
+/// Parses the given CDN file name into its start and end heights.
+fn parse_file_name(file: &str) -> Option<(u32, u32)> {
+    let (start, end) = file.strip_suffix(".blocks")?.split_once('.')?;
+    Some((start.parse().ok()?, end.parse().ok()?))
+}
+
+/// Returns the 'latest.json' file, which advertises the complete bundles in the block store.
+fn get_latest<N: Network, B: BlockStorage<N>>(block_store: BlockStore<N, B>) -> Result<Vec<u8>, (StatusCode, String)> {
+    let internal_error = |e: anyhow::Error| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string());
+
+    // Only advertise the complete bundles.
+    let exclusive_height = exclusive_height(&block_store);
+    let Some(inclusive_height) = exclusive_height.checked_sub(1) else {
+        return Err((StatusCode::NOT_FOUND, "No complete bundles are available yet".to_string()));
+    };
+    let hash = get_block(&block_store, inclusive_height).map_err(internal_error)?.hash().to_string();
+
+    // Encode the state in the CDN format, i.e. a bincode-encoded JSON string.
+    let latest = LatestState { exclusive_height, inclusive_height, hash };
+    let json = serde_json::to_string(&latest).map_err(|e| internal_error(e.into()))?;
+    bincode::serialize(&json).map_err(|e| internal_error(e.into()))
+}
+
+/// Returns the `{start}.{end}.blocks` file, if it is a complete bundle in the block store.
+async fn get_bundle<N: Network, B: BlockStorage<N>>(
+    block_store: BlockStore<N, B>,
+    file: String,
+) -> Result<Vec<u8>, (StatusCode, String)> {
+    // Ensure the file is a complete bundle.
+    let Some((start, end)) = parse_file_name(&file) else {
+        return Err((StatusCode::NOT_FOUND, format!("Unknown file '{file}'")));
+    };
+    if start % BLOCKS_PER_FILE != 0 || end.checked_sub(start) != Some(BLOCKS_PER_FILE) {
+        return Err((StatusCode::NOT_FOUND, format!("Invalid bundle '{file}'")));
+    }
+    if end > exclusive_height(&block_store) {
+        return Err((StatusCode::NOT_FOUND, format!("The bundle '{file}' is not available yet")));
+    }
+
+    // Load and encode the bundle.
+    let bundle = tokio::task::spawn_blocking(move || -> Result<Vec<u8>> {
+        let blocks = (start..end).map(|height| get_block(&block_store, height)).collect::<Result<Vec<_>>>()?;
+        Ok(bincode::serialize(&blocks)?)
+    })
+    .await;
+
+    match bundle {
+        Ok(Ok(bundle)) => Ok(bundle),
+        Ok(Err(error)) => Err((StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to load '{file}' - {error}"))),
+        Err(error) => Err((StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to join task for '{file}' - {error}"))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkvm::prelude::{
+        store::{helpers::memory::ConsensusMemory, ConsensusStore},
+        FromBytes,
+        MainnetV0,
+    };
+
+    type CurrentNetwork = MainnetV0;
+
+    #[test]
+    fn test_parse_file_name() {
+        assert_eq!(parse_file_name("0.50.blocks"), Some((0, 50)));
+        assert_eq!(parse_file_name("100.150.headers"), None);
+        assert_eq!(parse_file_name("0.50.txt"), None);
+        assert_eq!(parse_file_name("latest.json"), None);
+        assert_eq!(parse_file_name("a.50.blocks"), None);
+    }
+
+    #[test]
+    fn test_get_cdn_file_incomplete_bundle() {
+        let genesis = Block::<CurrentNetwork>::from_bytes_le(CurrentNetwork::genesis_bytes()).unwrap();
+        let store = ConsensusStore::<CurrentNetwork, ConsensusMemory<_>>::open(None).unwrap();
+        store.block_store().insert(&genesis).unwrap();
+        assert_eq!(exclusive_height(store.block_store()), 0);
+
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        rt.block_on(async {
+            let get = |file: &str| get_cdn_file(store.block_store().clone(), file.to_string());
+            // Check that an incomplete bundle is neither advertised nor served.
+            assert_eq!(get("latest.json").await.unwrap_err().0, StatusCode::NOT_FOUND);
+            assert_eq!(get("0.50.blocks").await.unwrap_err().0, StatusCode::NOT_FOUND);
+            // Check that the bundles must be aligned.
+            assert_eq!(get("0.1.blocks").await.unwrap_err().0, StatusCode::NOT_FOUND);
+            assert_eq!(get("unknown").await.unwrap_err().0, StatusCode::NOT_FOUND);
+        });
+    }
+}
@@ -0,0 +1,121 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use snarkvm::prelude::{block::Block, FromBytes, Network, ToBytes};
+
+use anyhow::Result;
+use parking_lot::Mutex;
+use std::{
+    collections::BTreeSet,
+    fs::{self, File},
+    io::{BufReader, BufWriter, Write},
+    path::{Path, PathBuf},
+};
+
+/// A temporary on-disk queue for the pending blocks that do not fit in memory,
+/// so the downloads can continue while the insertion of the blocks falls behind.
+///
+/// Every block is stored in its own file, and the directory is removed when the queue is dropped.
+pub(crate) struct SpillQueue {
+    /// The directory of the spilled blocks.
+    dir: PathBuf,
+    /// The heights of the spilled blocks.
+    heights: Mutex<BTreeSet<u32>>,
+}
+
+impl SpillQueue {
+    /// Initializes a new spill queue in a fresh subdirectory of the given directory.
+    pub(crate) fn new(dir: &Path) -> Result<Self> {
+        let dir = dir.join(format!("snarkos-cdn-spill-{}", std::process::id()));
+        // Remove any leftovers from a previous run with the same process ID.
+        if dir.exists() {
+            fs::remove_dir_all(&dir)?;
+        }
+        fs::create_dir_all(&dir)?;
+        Ok(Self { dir, heights: Default::default() })
+    }
+
+    /// Returns the number of spilled blocks.
+    pub(crate) fn len(&self) -> usize {
+        self.heights.lock().len()
+    }
+
+    /// Returns the height of the lowest spilled block, if any.
+    pub(crate) fn first_height(&self) -> Option<u32> {
+        self.heights.lock().first().copied()
+    }
+
+    /// Writes the given block to disk.
+    pub(crate) fn push<N: Network>(&self, block: &Block<N>) -> Result<()> {
+        let mut writer = BufWriter::new(File::create(self.path(block.height()))?);
+        block.write_le(&mut writer)?;
+        writer.flush()?;
+        self.heights.lock().insert(block.height());
+        Ok(())
+    }
+
+    /// Reads back the spilled block at the given height, removing it from disk.
+    pub(crate) fn take<N: Network>(&self, height: u32) -> Result<Option<Block<N>>> {
+        if !self.heights.lock().remove(&height) {
+            return Ok(None);
+        }
+        let path = self.path(height);
+        let block = Block::read_le(BufReader::new(File::open(&path)?))?;
+        fs::remove_file(path)?;
+        Ok(Some(block))
+    }
+
+    /// Returns the path of the file of the block at the given height.
+    fn path(&self, height: u32) -> PathBuf {
+        self.dir.join(format!("{height}.block"))
+    }
+}
+
+impl Drop for SpillQueue {
+    fn drop(&mut self) {
+        if let Err(error) = fs::remove_dir_all(&self.dir) {
+            warn!("Failed to remove the CDN spill directory '{}' - {error}", self.dir.display());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkvm::prelude::MainnetV0;
+
+    type CurrentNetwork = MainnetV0;
+
+    #[test]
+    fn test_spill_queue() {
+        let genesis = Block::<CurrentNetwork>::from_bytes_le(CurrentNetwork::genesis_bytes()).unwrap();
+        let queue = SpillQueue::new(&std::env::temp_dir()).unwrap();
+        let dir = queue.dir.clone();
+        assert_eq!(queue.len(), 0);
+        assert_eq!(queue.first_height(), None);
+
+        // Spill a block, and read it back.
+        queue.push(&genesis).unwrap();
+        assert_eq!(queue.len(), 1);
+        assert_eq!(queue.first_height(), Some(0));
+        assert_eq!(queue.take::<CurrentNetwork>(1).unwrap(), None);
+        assert_eq!(queue.take::<CurrentNetwork>(0).unwrap(), Some(genesis));
+        assert_eq!(queue.len(), 0);
+        assert!(!queue.path(0).exists());
+
+        // Check that the directory is removed on drop.
+        drop(queue);
+        assert!(!dir.exists());
+    }
+}
@@ -17,14 +17,23 @@
 #[macro_use]
 extern crate tracing;
 
+mod mempool;
+pub use mempool::*;
+
 use snarkos_account::Account;
 use snarkos_node_bft::{
     helpers::{
         fmt_id,
         init_consensus_channels,
+        BftConfig,
+        CertificateArchive,
         ConsensusReceiver,
+        Lane,
+        LocalSigner,
         PrimaryReceiver,
         PrimarySender,
+        RemoteSigner,
+        Signer,
         Storage as NarwhalStorage,
     },
     spawn_blocking,
@@ -47,7 +56,14 @@ use colored::Colorize;
 use indexmap::IndexMap;
 use lru::LruCache;
 use parking_lot::Mutex;
-use std::{future::Future, net::SocketAddr, num::NonZeroUsize, sync::Arc};
+use std::{
+    future::Future,
+    net::SocketAddr,
+    num::NonZeroUsize,
+    path::PathBuf,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 use tokio::{
     sync::{oneshot, OnceCell},
     task::JoinHandle,
@@ -63,12 +79,16 @@ pub struct Consensus<N: Network> {
     primary_sender: Arc<OnceCell<PrimarySender<N>>>,
     /// The unconfirmed solutions queue.
     solutions_queue: Arc<Mutex<LruCache<PuzzleCommitment<N>, ProverSolution<N>>>>,
-    /// The unconfirmed transactions queue.
-    transactions_queue: Arc<Mutex<LruCache<N::TransactionID, Transaction<N>>>>,
-    /// The recently-seen unconfirmed solutions.
-    seen_solutions: Arc<Mutex<LruCache<PuzzleCommitment<N>, ()>>>,
-    /// The recently-seen unconfirmed transactions.
-    seen_transactions: Arc<Mutex<LruCache<N::TransactionID, ()>>>,
+    /// The unconfirmed deployments queue.
+    deployments_queue: Arc<Mutex<LruCache<N::TransactionID, Transaction<N>>>>,
+    /// The unconfirmed executions queue.
+    executions_queue: Arc<Mutex<LruCache<N::TransactionID, Transaction<N>>>>,
+    /// The recently-seen unconfirmed solutions, with the time they were first seen.
+    seen_solutions: Arc<Mutex<LruCache<PuzzleCommitment<N>, Instant>>>,
+    /// The recently-seen unconfirmed transactions, with the time they were first seen.
+    seen_transactions: Arc<Mutex<LruCache<N::TransactionID, Instant>>>,
+    /// The path the memory pool is persisted to on shutdown, if it is enabled.
+    mempool_path: Arc<Mutex<Option<PathBuf>>>,
     /// The spawned handles.
     handles: Arc<Mutex<Vec<JoinHandle<()>>>>,
 }
@@ -81,6 +101,7 @@ impl<N: Network> Consensus<N> {
         ip: Option<SocketAddr>,
         trusted_validators: &[SocketAddr],
         storage_mode: StorageMode,
+        config: BftConfig<N>,
     ) -> Result<Self> {
         // Recover the development ID, if it is present.
         let dev = match storage_mode {
@@ -90,9 +111,14 @@ impl<N: Network> Consensus<N> {
         // Initialize the Narwhal transmissions.
         let transmissions = Arc::new(BFTPersistentStorage::open(storage_mode)?);
         // Initialize the Narwhal storage.
-        let storage = NarwhalStorage::new(ledger.clone(), transmissions, BatchHeader::<N>::MAX_GC_ROUNDS as u64);
+        let max_gc_rounds = BatchHeader::<N>::MAX_GC_ROUNDS as u64;
+        // Open the archive of the garbage collected certificates, if one is set.
+        let archive = config.archive.clone().map(CertificateArchive::open).transpose()?;
+        let pruning_rounds = config.pruning_rounds.unwrap_or(max_gc_rounds);
+        let storage =
+            NarwhalStorage::new_with_pruning(ledger.clone(), transmissions, max_gc_rounds, pruning_rounds, archive);
         // Initialize the BFT.
-        let bft = BFT::new(account, storage, ledger.clone(), ip, trusted_validators, dev)?;
+        let bft = BFT::new(account, storage, ledger.clone(), ip, trusted_validators, dev, &config)?;
         // Return the consensus.
         Ok(Self {
             ledger,
@@ -101,11 +127,15 @@ impl<N: Network> Consensus<N> {
             solutions_queue: Arc::new(Mutex::new(LruCache::new(
                 NonZeroUsize::new(BatchHeader::<N>::MAX_TRANSMISSIONS_PER_BATCH).unwrap(),
             ))),
-            transactions_queue: Arc::new(Mutex::new(LruCache::new(
+            deployments_queue: Arc::new(Mutex::new(LruCache::new(
+                NonZeroUsize::new(BatchHeader::<N>::MAX_TRANSMISSIONS_PER_BATCH).unwrap(),
+            ))),
+            executions_queue: Arc::new(Mutex::new(LruCache::new(
                 NonZeroUsize::new(BatchHeader::<N>::MAX_TRANSMISSIONS_PER_BATCH).unwrap(),
             ))),
             seen_solutions: Arc::new(Mutex::new(LruCache::new(NonZeroUsize::new(1 << 16).unwrap()))),
             seen_transactions: Arc::new(Mutex::new(LruCache::new(NonZeroUsize::new(1 << 16).unwrap()))),
+            mempool_path: Default::default(),
             handles: Default::default(),
         })
     }
@@ -141,6 +171,28 @@ impl<N: Network> Consensus<N> {
     }
 }
 
+impl<N: Network> Consensus<N> {
+    /// Schedules the rotation of the signer of the validator to the given private key, at the next round.
+    /// Returns the address of the new signer, and the round from which it signs the batches.
+    pub fn rotate_to_private_key(&self, private_key: PrivateKey<N>) -> Result<(Address<N>, u64)> {
+        self.rotate_signer(Arc::new(LocalSigner::new(Account::try_from(private_key)?)))
+    }
+
+    /// Schedules the rotation of the signer of the validator to the remote signer at the given URL, at the next round.
+    /// Returns the address of the new signer, and the round from which it signs the batches.
+    pub async fn rotate_to_remote_signer(&self, url: &str) -> Result<(Address<N>, u64)> {
+        self.rotate_signer(Arc::new(RemoteSigner::connect(url).await?))
+    }
+
+    /// Schedules the rotation of the signer of the validator to the given signer, at the next round.
+    /// Note: The rotation takes effect between two batch proposals, so that no proposal is signed by both signers.
+    fn rotate_signer(&self, signer: Arc<dyn Signer<N>>) -> Result<(Address<N>, u64)> {
+        let (address, round) = (signer.address(), self.bft.primary().current_round() + 1);
+        self.bft.primary().gateway().rotate_signer(signer, round)?;
+        Ok((address, round))
+    }
+}
+
 impl<N: Network> Consensus<N> {
     /// Returns the number of unconfirmed transmissions.
     pub fn num_unconfirmed_transmissions(&self) -> usize {
@@ -183,6 +235,18 @@ impl<N: Network> Consensus<N> {
     pub fn unconfirmed_transactions(&self) -> impl '_ + Iterator<Item = (N::TransactionID, Data<Transaction<N>>)> {
         self.bft.unconfirmed_transactions()
     }
+
+    /// Returns the time elapsed since the given solution was first seen by this node, if it was recently seen.
+    /// Note: Solutions received from other validators through the BFT are not tracked.
+    pub fn unconfirmed_solution_age(&self, solution_id: &PuzzleCommitment<N>) -> Option<Duration> {
+        self.seen_solutions.lock().peek(solution_id).map(Instant::elapsed)
+    }
+
+    /// Returns the time elapsed since the given transaction was first seen by this node, if it was recently seen.
+    /// Note: Transactions received from other validators through the BFT are not tracked.
+    pub fn unconfirmed_transaction_age(&self, transaction_id: &N::TransactionID) -> Option<Duration> {
+        self.seen_transactions.lock().peek(transaction_id).map(Instant::elapsed)
+    }
 }
 
 impl<N: Network> Consensus<N> {
@@ -192,10 +256,15 @@ impl<N: Network> Consensus<N> {
         {
             let solution_id = solution.commitment();
 
-            // Check if the transaction was recently seen.
-            if self.seen_solutions.lock().put(solution_id, ()).is_some() {
-                // If the transaction was recently seen, return early.
-                return Ok(());
+            // Check if the solution was recently seen.
+            {
+                let mut seen_solutions = self.seen_solutions.lock();
+                if seen_solutions.get(&solution_id).is_some() {
+                    // If the solution was recently seen, return early.
+                    return Ok(());
+                }
+                // Record the time the solution was first seen.
+                seen_solutions.put(solution_id, Instant::now());
             }
             // Check if the solution already exists in the ledger.
             if self.ledger.contains_transmission(&TransmissionID::from(solution_id))? {
@@ -250,9 +319,14 @@ impl<N: Network> Consensus<N> {
                 bail!("Transaction '{}' is a fee transaction {}", fmt_id(transaction_id), "(skipping)".dimmed());
             }
             // Check if the transaction was recently seen.
-            if self.seen_transactions.lock().put(transaction_id, ()).is_some() {
-                // If the transaction was recently seen, return early.
-                return Ok(());
+            {
+                let mut seen_transactions = self.seen_transactions.lock();
+                if seen_transactions.get(&transaction_id).is_some() {
+                    // If the transaction was recently seen, return early.
+                    return Ok(());
+                }
+                // Record the time the transaction was first seen.
+                seen_transactions.put(transaction_id, Instant::now());
             }
             // Check if the transaction already exists in the ledger.
             if self.ledger.contains_transmission(&TransmissionID::from(&transaction_id))? {
@@ -260,7 +334,12 @@ impl<N: Network> Consensus<N> {
             }
             // Add the transaction to the memory pool.
             trace!("Received unconfirmed transaction '{}' in the queue", fmt_id(transaction_id));
-            if self.transactions_queue.lock().put(transaction_id, transaction).is_some() {
+            // Note: The deployments and the executions are queued separately, so that one cannot evict the other.
+            let queue = match Lane::of(&transaction) {
+                Lane::Deployment => &self.deployments_queue,
+                Lane::Execution => &self.executions_queue,
+            };
+            if queue.lock().put(transaction_id, transaction).is_some() {
                 bail!("Transaction '{}' exists in the memory pool", fmt_id(transaction_id));
             }
         }
@@ -274,14 +353,19 @@ impl<N: Network> Consensus<N> {
         let transactions = {
             // Determine the available capacity.
             let capacity = BatchHeader::<N>::MAX_TRANSMISSIONS_PER_BATCH.saturating_sub(num_unconfirmed);
-            // Acquire the lock on the queue.
-            let mut queue = self.transactions_queue.lock();
-            // Determine the number of transactions to send.
-            let num_transactions = queue.len().min(capacity);
-            // Drain the solutions from the queue.
-            (0..num_transactions)
-                .filter_map(|_| queue.pop_lru().map(|(_, transaction)| transaction))
-                .collect::<Vec<_>>()
+            // Acquire the locks on the queues.
+            let mut deployments = self.deployments_queue.lock();
+            let mut executions = self.executions_queue.lock();
+            // Drain the transactions from the queues in turn, so that one lane cannot starve the other.
+            let mut transactions = Vec::with_capacity(capacity.min(deployments.len() + executions.len()));
+            while transactions.len() < capacity && !(deployments.is_empty() && executions.is_empty()) {
+                for queue in [&mut *executions, &mut *deployments] {
+                    if transactions.len() < capacity {
+                        transactions.extend(queue.pop_lru().map(|(_, transaction)| transaction));
+                    }
+                }
+            }
+            transactions
         };
         // Iterate over the transactions.
         for transaction in transactions.into_iter() {
@@ -304,6 +388,77 @@ impl<N: Network> Consensus<N> {
     }
 }
 
+impl<N: Network> Consensus<N> {
+    /// Reloads the memory pool persisted at the given path by the previous run (if any), and persists
+    /// the memory pool to the same path on shutdown. Returns the number of reloaded transmissions.
+    /// Note: The reloaded solutions and transactions are verified again before entering the memory pool,
+    /// so the ones that were confirmed or invalidated in the meantime are dropped.
+    pub async fn restore_mempool(&self, path: PathBuf) -> Result<usize> {
+        // Persist the memory pool to the given path on shutdown.
+        *self.mempool_path.lock() = Some(path.clone());
+        // Load the memory pool persisted by the previous run, if any.
+        let Some(snapshot) = spawn_blocking!(MempoolSnapshot::<N>::take(path))? else {
+            return Ok(0);
+        };
+        let num_transmissions = snapshot.len();
+        // Reinsert the solutions and transactions into the memory pool.
+        for solution in snapshot.solutions {
+            if let Err(e) = self.add_unconfirmed_solution(solution).await {
+                debug!("Dropped a persisted solution - {e}");
+            }
+        }
+        for transaction in snapshot.transactions {
+            if let Err(e) = self.add_unconfirmed_transaction(transaction).await {
+                debug!("Dropped a persisted transaction - {e}");
+            }
+        }
+        Ok(num_transmissions)
+    }
+
+    /// Persists the unconfirmed solutions and transactions of the memory pool, if it is enabled.
+    fn persist_mempool(&self) {
+        let Some(path) = self.mempool_path.lock().clone() else {
+            return;
+        };
+        // Collect the transmissions queued by consensus, which were not yet sent to the BFT.
+        let mut solutions: IndexMap<_, _> =
+            self.solutions_queue.lock().iter().map(|(id, solution)| (*id, solution.clone())).collect();
+        let mut transactions = IndexMap::new();
+        for queue in [&self.deployments_queue, &self.executions_queue] {
+            transactions.extend(queue.lock().iter().map(|(id, transaction)| (*id, transaction.clone())));
+        }
+        // Collect the transmissions in the memory pool of the BFT.
+        for (solution_id, solution) in self.unconfirmed_solutions() {
+            match solution.deserialize_blocking() {
+                Ok(solution) => {
+                    solutions.insert(solution_id, solution);
+                }
+                Err(e) => warn!("Failed to persist the unconfirmed solution '{}' - {e}", fmt_id(solution_id)),
+            }
+        }
+        for (transaction_id, transaction) in self.unconfirmed_transactions() {
+            match transaction.deserialize_blocking() {
+                Ok(transaction) => {
+                    transactions.insert(transaction_id, transaction);
+                }
+                Err(e) => warn!("Failed to persist the unconfirmed transaction '{}' - {e}", fmt_id(transaction_id)),
+            }
+        }
+        // Write the memory pool to disk.
+        let snapshot = MempoolSnapshot {
+            solutions: solutions.into_values().collect(),
+            transactions: transactions.into_values().collect(),
+        };
+        if snapshot.is_empty() {
+            return;
+        }
+        match snapshot.save(&path) {
+            Ok(()) => info!("Persisted {} unconfirmed transmissions to '{}'", snapshot.len(), path.display()),
+            Err(e) => warn!("Failed to persist the memory pool to '{}' - {e}", path.display()),
+        }
+    }
+}
+
 impl<N: Network> Consensus<N> {
     /// Starts the consensus handlers.
     fn start_handlers(&self, consensus_receiver: ConsensusReceiver<N>) {
@@ -421,6 +576,8 @@ impl<N: Network> Consensus<N> {
     /// Shuts down the BFT.
     pub async fn shut_down(&self) {
         info!("Shutting down consensus...");
+        // Persist the memory pool, so that it is reloaded on the next run.
+        self.persist_mempool();
         // Shut down the BFT.
         self.bft.shut_down().await;
         // Abort the tasks.
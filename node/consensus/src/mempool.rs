@@ -0,0 +1,103 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use snarkvm::prelude::{block::Transaction, coinbase::ProverSolution, error, FromBytes, Network, ToBytes};
+
+use anyhow::Result;
+use std::{
+    fs,
+    io::{self, ErrorKind},
+    path::Path,
+};
+
+/// The file name of the memory pool persisted across restarts, in the ledger directory.
+pub const MEMPOOL_FILE: &str = "mempool.bin";
+
+/// The version of the persisted memory pool format.
+const MEMPOOL_VERSION: u8 = 1;
+
+/// The unconfirmed solutions and transactions of the memory pool, as persisted across restarts.
+#[derive(Clone, Debug)]
+pub struct MempoolSnapshot<N: Network> {
+    /// The unconfirmed solutions.
+    pub solutions: Vec<ProverSolution<N>>,
+    /// The unconfirmed transactions.
+    pub transactions: Vec<Transaction<N>>,
+}
+
+impl<N: Network> MempoolSnapshot<N> {
+    /// Returns `true` if the snapshot has no solutions and no transactions.
+    pub fn is_empty(&self) -> bool {
+        self.solutions.is_empty() && self.transactions.is_empty()
+    }
+
+    /// Returns the number of solutions and transactions in the snapshot.
+    pub fn len(&self) -> usize {
+        self.solutions.len() + self.transactions.len()
+    }
+
+    /// Writes the snapshot to the given path, replacing any existing file atomically.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let path = path.as_ref();
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let temp_path = path.with_extension("tmp");
+        fs::write(&temp_path, self.to_bytes_le()?)?;
+        fs::rename(temp_path, path)?;
+        Ok(())
+    }
+
+    /// Reads the snapshot at the given path, and removes the file, so that it is only restored once.
+    /// Returns `None` if there is no snapshot at the given path.
+    pub fn take<P: AsRef<Path>>(path: P) -> Result<Option<Self>> {
+        let bytes = match fs::read(&path) {
+            Ok(bytes) => bytes,
+            Err(error) if error.kind() == ErrorKind::NotFound => return Ok(None),
+            Err(error) => return Err(error.into()),
+        };
+        fs::remove_file(&path)?;
+        Ok(Some(Self::from_bytes_le(&bytes)?))
+    }
+}
+
+impl<N: Network> ToBytes for MempoolSnapshot<N> {
+    fn write_le<W: io::Write>(&self, mut writer: W) -> io::Result<()> {
+        MEMPOOL_VERSION.write_le(&mut writer)?;
+        u32::try_from(self.solutions.len()).map_err(error)?.write_le(&mut writer)?;
+        for solution in &self.solutions {
+            solution.write_le(&mut writer)?;
+        }
+        u32::try_from(self.transactions.len()).map_err(error)?.write_le(&mut writer)?;
+        for transaction in &self.transactions {
+            transaction.write_le(&mut writer)?;
+        }
+        Ok(())
+    }
+}
+
+impl<N: Network> FromBytes for MempoolSnapshot<N> {
+    fn read_le<R: io::Read>(mut reader: R) -> io::Result<Self> {
+        let version = u8::read_le(&mut reader)?;
+        if version != MEMPOOL_VERSION {
+            return Err(error(format!("Unsupported memory pool version {version} (expected {MEMPOOL_VERSION})")));
+        }
+        let num_solutions = u32::read_le(&mut reader)?;
+        let solutions = (0..num_solutions).map(|_| ProverSolution::read_le(&mut reader)).collect::<io::Result<_>>()?;
+        let num_transactions = u32::read_le(&mut reader)?;
+        let transactions =
+            (0..num_transactions).map(|_| Transaction::read_le(&mut reader)).collect::<io::Result<_>>()?;
+        Ok(Self { solutions, transactions })
+    }
+}
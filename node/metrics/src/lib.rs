@@ -38,3 +38,23 @@ pub fn initialize_metrics() {
         register_histogram(name);
     }
 }
+
+/// Increments the counter with the given name and labels (e.g. `[("route", "/mainnet/block/:height")]`).
+pub fn increment_counter_with_labels(name: &'static str, labels: &[(&'static str, String)]) {
+    ::metrics::counter!(name, to_labels(labels)).increment(1);
+}
+
+/// Increases the counter with the given name and labels by the given value.
+pub fn counter_with_labels(name: &'static str, labels: &[(&'static str, String)], value: u64) {
+    ::metrics::counter!(name, to_labels(labels)).increment(value);
+}
+
+/// Records the given value in the histogram with the given name and labels.
+pub fn histogram_with_labels(name: &'static str, labels: &[(&'static str, String)], value: f64) {
+    ::metrics::histogram!(name, to_labels(labels)).record(value);
+}
+
+/// Returns the given key-value pairs as metric labels.
+fn to_labels(labels: &[(&'static str, String)]) -> Vec<::metrics::Label> {
+    labels.iter().map(|(key, value)| ::metrics::Label::new(*key, value.clone())).collect()
+}
@@ -12,7 +12,19 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-pub(super) const COUNTER_NAMES: [&str; 1] = [bft::LEADERS_ELECTED];
+pub(super) const COUNTER_NAMES: [&str; 11] = [
+    bft::LEADER_ELECTIONS,
+    bft::LEADERS_ELECTED,
+    bft::MEMPOOL_EVICTIONS,
+    bft::TIMEOUTS,
+    cdn::RATE_LIMITED,
+    rest::REQUESTS,
+    router::CACHE_HITS,
+    router::CACHE_MISSES,
+    router::CACHE_EVICTIONS,
+    tcp::INBOUND_THROTTLE_TIME,
+    tcp::OUTBOUND_THROTTLE_TIME,
+];
 
 pub(super) const GAUGE_NAMES: [&str; 12] = [
     bft::CONNECTED,
@@ -29,10 +41,17 @@ pub(super) const GAUGE_NAMES: [&str; 12] = [
     tcp::TCP_TASKS,
 ];
 
-pub(super) const HISTOGRAM_NAMES: [&str; 7] = [
+pub(super) const HISTOGRAM_NAMES: [&str; 14] = [
+    bft::BATCH_SIZE,
+    bft::CERTIFICATES_PER_ROUND,
+    bft::COMMIT_LATENCY,
     bft::COMMIT_ROUNDS_LATENCY,
+    bft::ROUND_DURATION,
     consensus::CERTIFICATE_COMMIT_LATENCY,
     consensus::BLOCK_LATENCY,
+    rest::REQUEST_LATENCY,
+    rest::REQUEST_SIZE,
+    rest::RESPONSE_SIZE,
     tcp::NOISE_CODEC_ENCRYPTION_TIME,
     tcp::NOISE_CODEC_DECRYPTION_TIME,
     tcp::NOISE_CODEC_ENCRYPTION_SIZE,
@@ -40,12 +59,19 @@ pub(super) const HISTOGRAM_NAMES: [&str; 7] = [
 ];
 
 pub mod bft {
+    pub const BATCH_SIZE: &str = "snarkos_bft_batch_transmissions";
+    pub const CERTIFICATES_PER_ROUND: &str = "snarkos_bft_certificates_per_round";
+    pub const COMMIT_LATENCY: &str = "snarkos_bft_commit_latency_secs";
     pub const COMMIT_ROUNDS_LATENCY: &str = "snarkos_bft_commit_rounds_latency_secs"; // <-- This one doesn't even make sense.
     pub const CONNECTED: &str = "snarkos_bft_connected_total";
     pub const CONNECTING: &str = "snarkos_bft_connecting_total";
     pub const LAST_STORED_ROUND: &str = "snarkos_bft_last_stored_round";
+    pub const LEADER_ELECTIONS: &str = "snarkos_bft_leader_elections_total";
     pub const LEADERS_ELECTED: &str = "snarkos_bft_leaders_elected_total";
+    pub const MEMPOOL_EVICTIONS: &str = "snarkos_bft_mempool_evictions_total";
     pub const PROPOSAL_ROUND: &str = "snarkos_bft_primary_proposal_round";
+    pub const ROUND_DURATION: &str = "snarkos_bft_round_duration_secs";
+    pub const TIMEOUTS: &str = "snarkos_bft_timeouts_total";
 }
 
 pub mod blocks {
@@ -53,6 +79,10 @@ pub mod blocks {
     pub const TRANSACTIONS: &str = "snarkos_blocks_transactions_total";
 }
 
+pub mod cdn {
+    pub const RATE_LIMITED: &str = "snarkos_cdn_rate_limited_total";
+}
+
 pub mod consensus {
     pub const CERTIFICATE_COMMIT_LATENCY: &str = "snarkos_consensus_certificate_commit_latency_secs";
     pub const COMMITTED_CERTIFICATES: &str = "snarkos_consensus_committed_certificates_total";
@@ -60,10 +90,20 @@ pub mod consensus {
     pub const BLOCK_LATENCY: &str = "snarkos_consensus_block_latency_secs";
 }
 
+pub mod rest {
+    pub const REQUESTS: &str = "snarkos_rest_requests_total";
+    pub const REQUEST_LATENCY: &str = "snarkos_rest_request_latency_secs";
+    pub const REQUEST_SIZE: &str = "snarkos_rest_request_size_bytes";
+    pub const RESPONSE_SIZE: &str = "snarkos_rest_response_size_bytes";
+}
+
 pub mod router {
     pub const CONNECTED: &str = "snarkos_router_connected_total";
     pub const CANDIDATE: &str = "snarkos_router_candidate_total";
     pub const RESTRICTED: &str = "snarkos_router_restricted_total";
+    pub const CACHE_HITS: &str = "snarkos_router_cache_hits_total";
+    pub const CACHE_MISSES: &str = "snarkos_router_cache_misses_total";
+    pub const CACHE_EVICTIONS: &str = "snarkos_router_cache_evictions_total";
 }
 
 pub mod tcp {
@@ -72,4 +112,6 @@ pub mod tcp {
     pub const NOISE_CODEC_ENCRYPTION_SIZE: &str = "snarkos_tcp_noise_codec_encryption_size";
     pub const NOISE_CODEC_DECRYPTION_SIZE: &str = "snarkos_tcp_noise_codec_decryption_size";
     pub const TCP_TASKS: &str = "snarkos_tcp_tasks_total";
+    pub const INBOUND_THROTTLE_TIME: &str = "snarkos_tcp_inbound_throttle_millis_total";
+    pub const OUTBOUND_THROTTLE_TIME: &str = "snarkos_tcp_outbound_throttle_millis_total";
 }
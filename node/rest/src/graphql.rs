@@ -0,0 +1,203 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::transaction_status;
+use snarkvm::prelude::{
+    block::{Block, ConfirmedTransaction, Transition},
+    store::ConsensusStorage,
+    Ledger,
+    Network,
+    ProgramID,
+};
+
+use async_graphql::{EmptyMutation, EmptySubscription, Object, Result, Schema};
+use async_graphql_axum::GraphQL;
+use std::str::FromStr;
+
+/// The maximum number of blocks per `blocks` query.
+const MAX_BLOCK_RANGE: u32 = 50;
+/// The maximum depth of a query.
+const MAX_QUERY_DEPTH: usize = 8;
+/// The maximum complexity of a query.
+const MAX_QUERY_COMPLEXITY: usize = 1000;
+
+/// The GraphQL schema over the ledger.
+pub(crate) type LedgerSchema<N, C> = Schema<Query<N, C>, EmptyMutation, EmptySubscription>;
+
+/// Returns the GraphQL service over the given ledger.
+pub(crate) fn service<N: Network, C: ConsensusStorage<N>>(ledger: Ledger<N, C>) -> GraphQL<LedgerSchema<N, C>> {
+    let schema = Schema::build(Query { ledger }, EmptyMutation, EmptySubscription)
+        .limit_depth(MAX_QUERY_DEPTH)
+        .limit_complexity(MAX_QUERY_COMPLEXITY)
+        .finish();
+    GraphQL::new(schema)
+}
+
+/// The root of the GraphQL queries.
+pub(crate) struct Query<N: Network, C: ConsensusStorage<N>> {
+    /// The ledger.
+    ledger: Ledger<N, C>,
+}
+
+#[Object]
+impl<N: Network, C: ConsensusStorage<N>> Query<N, C> {
+    /// The height of the latest block.
+    async fn latest_height(&self) -> u32 {
+        self.ledger.latest_height()
+    }
+
+    /// The latest block.
+    async fn latest_block(&self) -> BlockObject<N> {
+        BlockObject(self.ledger.latest_block())
+    }
+
+    /// The block with the given height or hash.
+    async fn block(&self, height: Option<u32>, hash: Option<String>) -> Result<BlockObject<N>> {
+        let block = match (height, hash) {
+            (Some(height), None) => self.ledger.get_block(height)?,
+            (None, Some(hash)) => self.ledger.get_block_by_hash(&N::BlockHash::from_str(&hash)?)?,
+            _ => return Err("Exactly one of 'height' or 'hash' must be given".into()),
+        };
+        Ok(BlockObject(block))
+    }
+
+    /// The blocks from `start` (inclusive) to `end` (exclusive).
+    async fn blocks(&self, start: u32, end: u32) -> Result<Vec<BlockObject<N>>> {
+        if start > end || end - start > MAX_BLOCK_RANGE {
+            return Err(format!("Invalid block range (at most {MAX_BLOCK_RANGE} blocks per query)").into());
+        }
+        Ok((start..end).map(|height| self.ledger.get_block(height).map(BlockObject)).collect::<anyhow::Result<_>>()?)
+    }
+
+    /// The confirmed transaction with the given ID.
+    async fn transaction(&self, id: String) -> Result<TransactionObject<N>> {
+        let id = N::TransactionID::from_str(&id)?;
+        let height = self.ledger.get_height(&self.ledger.find_block_hash(&id)?.ok_or("Transaction not found")?)?;
+        Ok(TransactionObject { height, confirmed: self.ledger.get_confirmed_transaction(id)? })
+    }
+
+    /// The source code of the program with the given ID.
+    async fn program(&self, id: String) -> Result<String> {
+        Ok(self.ledger.get_program(ProgramID::from_str(&id)?)?.to_string())
+    }
+}
+
+/// A block.
+pub(crate) struct BlockObject<N: Network>(Block<N>);
+
+#[Object(name = "Block")]
+impl<N: Network> BlockObject<N> {
+    /// The height of the block.
+    async fn height(&self) -> u32 {
+        self.0.height()
+    }
+
+    /// The hash of the block.
+    async fn hash(&self) -> String {
+        self.0.hash().to_string()
+    }
+
+    /// The hash of the previous block.
+    async fn previous_hash(&self) -> String {
+        self.0.previous_hash().to_string()
+    }
+
+    /// The timestamp of the block.
+    async fn timestamp(&self) -> i64 {
+        self.0.timestamp()
+    }
+
+    /// The confirmed transactions of the block, optionally only those invoking the given program.
+    async fn transactions(&self, program: Option<String>) -> Result<Vec<TransactionObject<N>>> {
+        let program = program.map(|program| ProgramID::<N>::from_str(&program)).transpose()?;
+        Ok(self
+            .0
+            .transactions()
+            .iter()
+            .filter(|confirmed| {
+                program.map_or(true, |program| {
+                    confirmed.transaction().transitions().any(|transition| transition.program_id() == &program)
+                })
+            })
+            .map(|confirmed| TransactionObject { height: self.0.height(), confirmed: confirmed.clone() })
+            .collect())
+    }
+}
+
+/// A confirmed transaction.
+pub(crate) struct TransactionObject<N: Network> {
+    /// The height of the block of the transaction.
+    height: u32,
+    /// The confirmed transaction.
+    confirmed: ConfirmedTransaction<N>,
+}
+
+#[Object(name = "Transaction")]
+impl<N: Network> TransactionObject<N> {
+    /// The transaction ID.
+    async fn id(&self) -> String {
+        self.confirmed.transaction().id().to_string()
+    }
+
+    /// The height of the block of the transaction.
+    async fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// The index of the transaction in the block.
+    async fn index(&self) -> u32 {
+        self.confirmed.index()
+    }
+
+    /// The status of the transaction (e.g. "accepted_execute").
+    async fn status(&self) -> &'static str {
+        transaction_status(&self.confirmed)
+    }
+
+    /// The transitions of the transaction, including the fee transition.
+    async fn transitions(&self) -> Vec<TransitionObject<N>> {
+        self.confirmed.transaction().transitions().cloned().map(TransitionObject).collect()
+    }
+}
+
+/// A transition.
+pub(crate) struct TransitionObject<N: Network>(Transition<N>);
+
+#[Object(name = "Transition")]
+impl<N: Network> TransitionObject<N> {
+    /// The transition ID.
+    async fn id(&self) -> String {
+        self.0.id().to_string()
+    }
+
+    /// The program ID.
+    async fn program(&self) -> String {
+        self.0.program_id().to_string()
+    }
+
+    /// The function name.
+    async fn function(&self) -> String {
+        self.0.function_name().to_string()
+    }
+
+    /// The inputs of the transition, as JSON.
+    async fn inputs(&self) -> Vec<String> {
+        self.0.inputs().iter().map(|input| input.to_string()).collect()
+    }
+
+    /// The outputs of the transition, as JSON.
+    async fn outputs(&self) -> Vec<String> {
+        self.0.outputs().iter().map(|output| output.to_string()).collect()
+    }
+}
@@ -0,0 +1,299 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::{public_addresses, Cursor, LedgerIndex, Page};
+use snarkvm::prelude::{block::Block, store::ConsensusStorage, Address, Ledger, Network};
+
+use anyhow::Result;
+use indexmap::IndexMap;
+use parking_lot::{Mutex, RwLock};
+use serde::{Deserialize, Serialize};
+use std::{
+    fs::{self, File, OpenOptions},
+    io::{BufWriter, Write},
+    path::{Path, PathBuf},
+};
+
+/// The name of the address index file.
+pub const ADDRESS_INDEX_FILE: &str = "address_index.jsonl";
+
+/// An index of the confirmed transactions of each address, in ledger order.
+///
+/// A transaction is indexed under every address in its public inputs, outputs and finalize arguments
+/// (see [`public_addresses`]); the addresses of private records are not visible to the node.
+///
+/// If the index is opened at a path, each indexed block is appended to the address index file, so the index
+/// is only backfilled from genesis the first time. If the ledger no longer contains the last indexed block
+/// (e.g. the ledger was reset), the index is cleared and rebuilt.
+pub struct AddressIndex<N: Network> {
+    /// The index entries.
+    inner: RwLock<AddressIndexInner<N>>,
+    /// The path of the address index file, if the index is persisted.
+    path: Option<PathBuf>,
+    /// The writer of the address index file, if the index is persisted.
+    file: Mutex<Option<BufWriter<File>>>,
+}
+
+/// The entries of the address index.
+struct AddressIndexInner<N: Network> {
+    /// The height of the next block to index.
+    next_height: u32,
+    /// The hash of the last indexed block, if any.
+    last_hash: Option<N::BlockHash>,
+    /// The positions and IDs of the transactions of each address, in ledger order.
+    transactions: IndexMap<Address<N>, Vec<(Cursor, N::TransactionID)>>,
+}
+
+/// An indexed block, as stored in the address index file.
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(bound = "")]
+struct IndexedBlock<N: Network> {
+    /// The block height.
+    height: u32,
+    /// The block hash.
+    hash: N::BlockHash,
+    /// The addresses, positions and IDs of the transactions in the block.
+    transactions: Vec<(Address<N>, u32, N::TransactionID)>,
+}
+
+impl<N: Network> Default for AddressIndexInner<N> {
+    fn default() -> Self {
+        Self { next_height: 0, last_hash: None, transactions: Default::default() }
+    }
+}
+
+impl<N: Network> AddressIndexInner<N> {
+    /// Adds the given block to the entries.
+    fn insert(&mut self, block: &IndexedBlock<N>) {
+        for (address, index, transaction_id) in &block.transactions {
+            let cursor = Cursor { height: block.height, index: *index };
+            self.transactions.entry(*address).or_default().push((cursor, *transaction_id));
+        }
+        self.next_height = block.height + 1;
+        self.last_hash = Some(block.hash);
+    }
+}
+
+impl<N: Network> Default for AddressIndex<N> {
+    /// Initializes an empty address index, which is kept in memory.
+    fn default() -> Self {
+        Self { inner: Default::default(), path: None, file: Default::default() }
+    }
+}
+
+impl<N: Network> AddressIndex<N> {
+    /// Opens the address index file at the given path, loading the indexed blocks (if any), and appending
+    /// the blocks indexed from now on to it.
+    ///
+    /// Note: If the file ends with an invalid entry (e.g. the node stopped while writing it),
+    /// the file is truncated to the last valid entry.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let mut inner = AddressIndexInner::default();
+        if path.exists() {
+            let mut lines = Vec::new();
+            for line in fs::read_to_string(&path)?.lines().filter(|line| !line.trim().is_empty()) {
+                match serde_json::from_str::<IndexedBlock<N>>(line) {
+                    Ok(block) if block.height == inner.next_height => inner.insert(&block),
+                    _ => {
+                        warn!("Truncating the address index file at block {}", inner.next_height);
+                        fs::write(&path, lines.concat())?;
+                        break;
+                    }
+                }
+                lines.push(format!("{line}\n"));
+            }
+            debug!("Loaded {} block(s) from the address index file", inner.next_height);
+        } else if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let file = BufWriter::new(OpenOptions::new().create(true).append(true).open(&path)?);
+        Ok(Self { inner: RwLock::new(inner), path: Some(path), file: Mutex::new(Some(file)) })
+    }
+
+    /// Returns the height of the next block to index.
+    pub fn next_height(&self) -> u32 {
+        self.inner.read().next_height
+    }
+
+    /// Indexes the transactions of the given block, which must be the next block to index.
+    pub fn index_block(&self, block: &Block<N>) -> Result<()> {
+        if block.height() != self.next_height() {
+            return Ok(());
+        }
+        let mut transactions = Vec::new();
+        for confirmed in block.transactions().iter() {
+            let transaction = confirmed.transaction();
+            for address in public_addresses(transaction) {
+                transactions.push((address, confirmed.index(), transaction.id()));
+            }
+        }
+        let block = IndexedBlock { height: block.height(), hash: block.hash(), transactions };
+        // Append the block to the address index file before adding it to the entries, if the index is persisted.
+        if let Some(file) = self.file.lock().as_mut() {
+            writeln!(file, "{}", serde_json::to_string(&block)?)?;
+        }
+        self.inner.write().insert(&block);
+        Ok(())
+    }
+
+    /// Clears the index, including the address index file, if the index is persisted.
+    fn clear(&self) -> Result<()> {
+        if let Some(path) = &self.path {
+            *self.file.lock() = Some(BufWriter::new(File::create(path)?));
+        }
+        *self.inner.write() = Default::default();
+        Ok(())
+    }
+
+    /// Returns a page of the transactions of the given address, starting at the given cursor (inclusive).
+    pub fn page(&self, address: &Address<N>, start: Cursor, limit: u32) -> Page<(Cursor, N::TransactionID)> {
+        let inner = self.inner.read();
+        let entries = inner.transactions.get(address).map(Vec::as_slice).unwrap_or_default();
+        let first = entries.partition_point(|(cursor, _)| *cursor < start);
+        let last = entries.len().min(first + limit as usize);
+        Page { items: entries[first..last].to_vec(), next: entries.get(last).map(|(cursor, _)| cursor.to_string()) }
+    }
+}
+
+impl<N: Network, C: ConsensusStorage<N>> LedgerIndex<N, C> for AddressIndex<N> {
+    /// Returns the name of the index, for the logs.
+    fn name(&self) -> &'static str {
+        "address index"
+    }
+
+    /// Indexes the blocks added to the ledger since the last update.
+    fn update(&self, ledger: &Ledger<N, C>) -> Result<()> {
+        // Rebuild the index if the ledger no longer contains the last indexed block.
+        let last_indexed = {
+            let inner = self.inner.read();
+            inner.last_hash.map(|hash| (inner.next_height - 1, hash))
+        };
+        if let Some((height, hash)) = last_indexed {
+            if ledger.get_hash(height).ok() != Some(hash) {
+                warn!("Rebuilding the address index, as the ledger does not contain block {height}");
+                self.clear()?;
+            }
+        }
+
+        let latest_height = ledger.latest_height();
+        let result = (|| -> Result<()> {
+            while self.next_height() <= latest_height {
+                self.index_block(&ledger.get_block(self.next_height())?)?;
+            }
+            Ok(())
+        })();
+        // Flush the indexed blocks to the address index file.
+        if let Some(file) = self.file.lock().as_mut() {
+            file.flush()?;
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkvm::prelude::{FromBytes, Group, MainnetV0};
+
+    type CurrentNetwork = MainnetV0;
+
+    #[test]
+    fn test_address_index() {
+        let genesis = Block::<CurrentNetwork>::from_bytes_le(CurrentNetwork::genesis_bytes()).unwrap();
+        let index = AddressIndex::<CurrentNetwork>::default();
+
+        // Check that blocks are only indexed in order.
+        index.index_block(&genesis).unwrap();
+        index.index_block(&genesis).unwrap();
+        assert_eq!(index.next_height(), 1);
+
+        // Check that each transaction is returned for its addresses.
+        for confirmed in genesis.transactions().iter() {
+            let transaction = confirmed.transaction();
+            for address in public_addresses(transaction) {
+                let page = index.page(&address, Cursor::default(), u32::MAX);
+                assert!(page.items.iter().any(|(_, id)| *id == transaction.id()));
+                assert!(page.next.is_none());
+            }
+        }
+    }
+
+    #[test]
+    fn test_address_index_file() {
+        let genesis = Block::<CurrentNetwork>::from_bytes_le(CurrentNetwork::genesis_bytes()).unwrap();
+        let path = std::env::temp_dir().join(format!("snarkos-address-index-{}.jsonl", std::process::id()));
+        let _ = fs::remove_file(&path);
+
+        // Index the genesis block to the address index file.
+        let index = AddressIndex::<CurrentNetwork>::open(&path).unwrap();
+        index.index_block(&genesis).unwrap();
+        index.file.lock().as_mut().unwrap().flush().unwrap();
+        let address = genesis
+            .transactions()
+            .iter()
+            .flat_map(|confirmed| public_addresses(confirmed.transaction()))
+            .next()
+            .unwrap();
+        let expected = index.page(&address, Cursor::default(), u32::MAX).items;
+
+        // Check that the indexed blocks are loaded when the file is reopened.
+        let index = AddressIndex::<CurrentNetwork>::open(&path).unwrap();
+        assert_eq!(index.next_height(), 1);
+        assert_eq!(index.inner.read().last_hash, Some(genesis.hash()));
+        assert_eq!(index.page(&address, Cursor::default(), u32::MAX).items, expected);
+        drop(index);
+
+        // Check that an invalid trailing entry is truncated.
+        let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+        writeln!(file, "{{\"height\": 1,").unwrap();
+        let index = AddressIndex::<CurrentNetwork>::open(&path).unwrap();
+        assert_eq!(index.next_height(), 1);
+        assert_eq!(fs::read_to_string(&path).unwrap().lines().count(), 1);
+
+        // Check that clearing the index also clears the file.
+        index.clear().unwrap();
+        assert_eq!(index.next_height(), 0);
+        assert!(fs::read_to_string(&path).unwrap().is_empty());
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_address_index_page() {
+        let index = AddressIndex::<CurrentNetwork>::default();
+        let genesis = Block::<CurrentNetwork>::from_bytes_le(CurrentNetwork::genesis_bytes()).unwrap();
+        let address = Address::<CurrentNetwork>::new(Group::generator());
+        let id = genesis.transactions().iter().next().unwrap().transaction().id();
+        {
+            let mut inner = index.inner.write();
+            let entries = (0..5).map(|height| (Cursor { height, index: 0 }, id)).collect();
+            inner.transactions.insert(address, entries);
+        }
+
+        // Check the pages follow each other.
+        let page = index.page(&address, Cursor::default(), 2);
+        assert_eq!(page.items.len(), 2);
+        assert_eq!(page.next.as_deref(), Some("2"));
+        let page = index.page(&address, Cursor { height: 2, index: 0 }, 2);
+        assert_eq!(page.items[0].0, Cursor { height: 2, index: 0 });
+        assert_eq!(page.next.as_deref(), Some("4"));
+        let page = index.page(&address, Cursor { height: 4, index: 0 }, 2);
+        assert_eq!(page.items.len(), 1);
+        assert!(page.next.is_none());
+
+        // Check an unknown address has no transactions.
+        let other = Address::<CurrentNetwork>::new(Group::generator() + Group::generator());
+        assert!(index.page(&other, Cursor::default(), 2).items.is_empty());
+    }
+}
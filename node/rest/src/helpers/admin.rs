@@ -0,0 +1,82 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::net::SocketAddr;
+
+/// The settings of the admin API, to control the node at runtime.
+///
+/// The admin routes are protected with JWT auth, like the other node management routes.
+#[derive(Clone, Debug, Default)]
+pub struct AdminConfig {
+    /// The function replacing the log filter of the node, if the node supports it.
+    pub set_log_filter: Option<fn(&str) -> Result<()>>,
+}
+
+/// The request object of the admin routes to connect to or disconnect from a peer.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct AdminPeerRequest {
+    /// The listener IP of the peer.
+    pub ip: SocketAddr,
+}
+
+/// The request object of the admin route to ban a peer.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct AdminBanRequest {
+    /// The listener IP of the peer.
+    pub ip: SocketAddr,
+    /// The duration of the ban in seconds (defaults to the duration of the bans for offenses).
+    #[serde(default)]
+    pub ttl_secs: Option<u64>,
+}
+
+/// The format of the network topology snapshot returned by the admin API.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TopologyFormat {
+    /// The snapshot in JSON.
+    #[default]
+    Json,
+    /// The snapshot in the Graphviz DOT format.
+    Dot,
+}
+
+/// The query object of the admin route to export the network topology.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct AdminTopologyQuery {
+    /// The format of the snapshot (defaults to JSON).
+    #[serde(default)]
+    pub format: TopologyFormat,
+}
+
+/// The request object of the admin route to change the log filter.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct AdminLogRequest {
+    /// The log filter directives (e.g. `info,snarkos_node_router=trace`).
+    pub filter: String,
+}
+
+/// The request object of the admin route to rotate the signer of the validator,
+/// with either a new private key or the URL of a remote signing service.
+/// Note: The request does not implement `Debug`, so that the private key is never logged.
+#[derive(Clone, Deserialize)]
+pub struct AdminSignerRequest {
+    /// The new private key of the validator.
+    #[serde(default)]
+    pub private_key: Option<String>,
+    /// The URL of the remote signing service.
+    #[serde(default)]
+    pub remote_signer: Option<String>,
+}
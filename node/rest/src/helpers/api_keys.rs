@@ -0,0 +1,232 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use anyhow::{ensure, Context, Result};
+use axum::{
+    body::Body,
+    extract::State,
+    http::{header::RETRY_AFTER, Request, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, fmt, path::Path, sync::Arc, time::Instant};
+
+/// The header carrying the API key of a request.
+pub const API_KEY_HEADER: &str = "x-api-key";
+
+/// An API key, as specified in the API keys file.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct ApiKeyEntry {
+    /// The API key.
+    pub key: String,
+    /// The number of requests per second allowed for the key.
+    pub rps: u32,
+    /// The routes allowed for the key, either exact paths or prefixes ending in `*` (e.g. `/mainnet/block/*`);
+    /// if empty, all routes are allowed.
+    #[serde(default)]
+    pub routes: Vec<String>,
+}
+
+/// An API key, with its own rate limit.
+struct ApiKey {
+    /// The number of requests per second allowed for the key, which is also its burst size.
+    rps: u32,
+    /// The routes allowed for the key.
+    routes: Vec<String>,
+    /// The available requests, and the time they were last refilled.
+    bucket: Mutex<(f64, Instant)>,
+}
+
+impl ApiKey {
+    /// Returns `true` if the given path is allowed for the key.
+    fn allows(&self, path: &str) -> bool {
+        self.routes.is_empty()
+            || self.routes.iter().any(|route| match route.strip_suffix('*') {
+                Some(prefix) => path.starts_with(prefix),
+                None => path == route,
+            })
+    }
+
+    /// Consumes a request from the rate limit of the key, returning `false` if the limit is reached.
+    fn try_acquire(&self) -> bool {
+        let mut bucket = self.bucket.lock();
+        let (available, refilled_at) = &mut *bucket;
+        // Refill the available requests, up to the burst size.
+        let now = Instant::now();
+        *available =
+            (*available + now.duration_since(*refilled_at).as_secs_f64() * self.rps as f64).min(self.rps as f64);
+        *refilled_at = now;
+        // Consume a request, if available.
+        if *available < 1.0 {
+            return false;
+        }
+        *available -= 1.0;
+        true
+    }
+}
+
+/// The API keys of the REST server, each with its own rate limit and allowed routes.
+///
+/// Requests with an API key are rate limited per key, instead of per IP. Requests without an API key
+/// are rejected if a key is required, and are otherwise subject to the default per-IP rate limit.
+pub struct ApiKeys {
+    /// The API keys.
+    keys: HashMap<String, ApiKey>,
+    /// If `true`, requests without an API key are rejected.
+    required: bool,
+}
+
+impl ApiKeys {
+    /// Initializes the API keys from the given entries.
+    pub fn new(entries: Vec<ApiKeyEntry>, required: bool) -> Result<Self> {
+        let mut keys = HashMap::with_capacity(entries.len());
+        for ApiKeyEntry { key, rps, routes } in entries {
+            ensure!(!key.is_empty(), "An API key must not be empty");
+            ensure!(rps > 0, "The rate limit of an API key must be greater than 0");
+            let api_key = ApiKey { rps, routes, bucket: Mutex::new((rps as f64, Instant::now())) };
+            ensure!(keys.insert(key, api_key).is_none(), "Found a duplicate API key");
+        }
+        Ok(Self { keys, required })
+    }
+
+    /// Loads the API keys from the given JSON file, containing a list of [`ApiKeyEntry`].
+    pub fn load<P: AsRef<Path>>(path: P, required: bool) -> Result<Self> {
+        let path = path.as_ref();
+        let contents = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read the API keys file '{}'", path.display()))?;
+        let entries = serde_json::from_str(&contents)
+            .with_context(|| format!("Failed to parse the API keys file '{}'", path.display()))?;
+        Self::new(entries, required)
+    }
+
+    /// Returns the number of API keys.
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    /// Returns `true` if there are no API keys.
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    /// Returns `true` if requests without an API key are rejected.
+    pub fn is_required(&self) -> bool {
+        self.required
+    }
+
+    /// Checks whether a request with the given API key (if any) to the given path is allowed.
+    fn check(&self, key: Option<&str>, path: &str) -> Result<(), StatusCode> {
+        match key {
+            Some(key) => {
+                let api_key = self.keys.get(key).ok_or(StatusCode::UNAUTHORIZED)?;
+                if !api_key.allows(path) {
+                    return Err(StatusCode::FORBIDDEN);
+                }
+                match api_key.try_acquire() {
+                    true => Ok(()),
+                    false => Err(StatusCode::TOO_MANY_REQUESTS),
+                }
+            }
+            None if self.required => Err(StatusCode::UNAUTHORIZED),
+            None => Ok(()),
+        }
+    }
+}
+
+impl fmt::Debug for ApiKeys {
+    /// Formats the API keys, without revealing them.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("ApiKeys").field("len", &self.keys.len()).field("required", &self.required).finish()
+    }
+}
+
+/// Enforces the API key of the request (if any), i.e. its allowed routes and rate limit.
+pub async fn api_key_middleware(
+    State(api_keys): State<Arc<ApiKeys>>,
+    request: Request<Body>,
+    next: Next,
+) -> Result<Response, Response> {
+    let key = request.headers().get(API_KEY_HEADER).map(|key| key.to_str().unwrap_or_default());
+    match api_keys.check(key, request.uri().path()) {
+        // The rate limit of a key allows at least 1 request per second, so it is replenished within a second.
+        Err(StatusCode::TOO_MANY_REQUESTS) => {
+            return Err((StatusCode::TOO_MANY_REQUESTS, [(RETRY_AFTER, "1")], "Too many requests").into_response());
+        }
+        Err(status) => return Err(status.into_response()),
+        Ok(()) => (),
+    }
+    Ok(next.run(request).await)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(key: &str, rps: u32, routes: &[&str]) -> ApiKeyEntry {
+        ApiKeyEntry { key: key.to_string(), rps, routes: routes.iter().map(|route| route.to_string()).collect() }
+    }
+
+    #[test]
+    fn test_api_keys_check() {
+        let api_keys =
+            ApiKeys::new(vec![entry("all", 100, &[]), entry("blocks", 100, &["/mainnet/block/*"])], false).unwrap();
+
+        // Check that requests without a key are allowed, unless a key is required.
+        assert_eq!(api_keys.check(None, "/mainnet/block/1"), Ok(()));
+        // Check that unknown keys are rejected.
+        assert_eq!(api_keys.check(Some("unknown"), "/mainnet/block/1"), Err(StatusCode::UNAUTHORIZED));
+        // Check the allowed routes.
+        assert_eq!(api_keys.check(Some("all"), "/mainnet/transaction/broadcast"), Ok(()));
+        assert_eq!(api_keys.check(Some("blocks"), "/mainnet/block/1"), Ok(()));
+        assert_eq!(api_keys.check(Some("blocks"), "/mainnet/transaction/broadcast"), Err(StatusCode::FORBIDDEN));
+
+        let api_keys = ApiKeys::new(vec![entry("all", 100, &[])], true).unwrap();
+        assert_eq!(api_keys.check(None, "/mainnet/block/1"), Err(StatusCode::UNAUTHORIZED));
+    }
+
+    #[test]
+    fn test_api_keys_rate_limit() {
+        let api_keys = ApiKeys::new(vec![entry("slow", 2, &[]), entry("fast", 100, &[])], false).unwrap();
+
+        // Check that the burst of the key is allowed, after which the key is rate limited.
+        assert_eq!(api_keys.check(Some("slow"), "/"), Ok(()));
+        assert_eq!(api_keys.check(Some("slow"), "/"), Ok(()));
+        assert_eq!(api_keys.check(Some("slow"), "/"), Err(StatusCode::TOO_MANY_REQUESTS));
+        // Check that the rate limits are per key.
+        assert_eq!(api_keys.check(Some("fast"), "/"), Ok(()));
+    }
+
+    #[test]
+    fn test_api_keys_invalid() {
+        assert!(ApiKeys::new(vec![entry("", 1, &[])], false).is_err());
+        assert!(ApiKeys::new(vec![entry("key", 0, &[])], false).is_err());
+        assert!(ApiKeys::new(vec![entry("key", 1, &[]), entry("key", 2, &[])], false).is_err());
+    }
+
+    #[test]
+    fn test_api_keys_load() {
+        let path = std::env::temp_dir().join(format!("snarkos-rest-api-keys-{}.json", std::process::id()));
+        std::fs::write(
+            &path,
+            r#"[{"key": "abc", "rps": 5, "routes": ["/mainnet/block/*"]}, {"key": "def", "rps": 1}]"#,
+        )
+        .unwrap();
+        let api_keys = ApiKeys::load(&path, true).unwrap();
+        assert_eq!(api_keys.len(), 2);
+        assert!(api_keys.is_required());
+        std::fs::remove_file(path).unwrap();
+    }
+}
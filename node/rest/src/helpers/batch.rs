@@ -0,0 +1,126 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use snarkvm::prelude::{store::ConsensusStorage, Identifier, Ledger, Network, Plaintext, ProgramID};
+
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// The maximum number of sub-requests in a batch.
+pub const MAX_BATCH_REQUESTS: usize = 50;
+
+/// A sub-request of a batch query.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(tag = "method", rename_all = "snake_case", bound = "")]
+pub enum BatchRequest<N: Network> {
+    /// Returns the block with the given height or hash.
+    GetBlock { block: String },
+    /// Returns the transaction with the given ID.
+    GetTransaction { id: N::TransactionID },
+    /// Returns the value of the given key in the given mapping.
+    GetMappingValue { program: ProgramID<N>, mapping: Identifier<N>, key: Plaintext<N> },
+}
+
+/// The response to a sub-request of a batch query, in the same position as the sub-request.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BatchResponse {
+    /// The result of the sub-request.
+    Result(Value),
+    /// The error of the sub-request.
+    Error(String),
+}
+
+impl<N: Network> BatchRequest<N> {
+    /// Executes the sub-request against the given ledger.
+    pub fn execute<C: ConsensusStorage<N>>(&self, ledger: &Ledger<N, C>) -> BatchResponse {
+        let result = match self {
+            Self::GetBlock { block } => match block.parse::<u32>() {
+                Ok(height) => ledger.get_block(height).and_then(to_json),
+                Err(_) => block
+                    .parse::<N::BlockHash>()
+                    .map_err(|_| anyhow!("invalid input, it is neither a block height nor a block hash"))
+                    .and_then(|hash| ledger.get_block_by_hash(&hash))
+                    .and_then(to_json),
+            },
+            Self::GetTransaction { id } => ledger.get_transaction(*id).and_then(to_json),
+            Self::GetMappingValue { program, mapping, key } => {
+                ledger.vm().finalize_store().get_value_confirmed(*program, *mapping, key).and_then(to_json)
+            }
+        };
+        match result {
+            Ok(value) => BatchResponse::Result(value),
+            Err(error) => BatchResponse::Error(error.to_string()),
+        }
+    }
+}
+
+/// Serializes the given value into JSON.
+fn to_json<T: Serialize>(value: T) -> Result<Value> {
+    Ok(serde_json::to_value(value)?)
+}
+
+/// Ensures the number of sub-requests in a batch is bounded.
+pub fn check_batch_size(num_requests: usize) -> Result<()> {
+    match num_requests <= MAX_BATCH_REQUESTS {
+        true => Ok(()),
+        false => Err(anyhow!(
+            "Cannot request more than {MAX_BATCH_REQUESTS} sub-requests per batch (requested {num_requests})"
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkvm::prelude::MainnetV0;
+
+    type CurrentNetwork = MainnetV0;
+
+    #[test]
+    fn test_batch_request_deserialize() {
+        let requests: Vec<BatchRequest<CurrentNetwork>> = serde_json::from_str(
+            r#"[
+                { "method": "get_block", "block": "10" },
+                { "method": "get_mapping_value", "program": "credits.aleo", "mapping": "account", "key": "1u32" }
+            ]"#,
+        )
+        .unwrap();
+        assert!(matches!(&requests[0], BatchRequest::GetBlock { block } if block == "10"));
+        match &requests[1] {
+            BatchRequest::GetMappingValue { program, mapping, .. } => {
+                assert_eq!(program.to_string(), "credits.aleo");
+                assert_eq!(mapping.to_string(), "account");
+            }
+            request => panic!("Unexpected request {request:?}"),
+        }
+
+        // Check an unknown method is rejected.
+        assert!(serde_json::from_str::<BatchRequest<CurrentNetwork>>(r#"{ "method": "get_peers" }"#).is_err());
+    }
+
+    #[test]
+    fn test_batch_response_serialize() {
+        let responses = vec![BatchResponse::Result(Value::from(1)), BatchResponse::Error("missing".to_string())];
+        assert_eq!(serde_json::to_string(&responses).unwrap(), r#"[{"result":1},{"error":"missing"}]"#);
+    }
+
+    #[test]
+    fn test_check_batch_size() {
+        assert!(check_batch_size(0).is_ok());
+        assert!(check_batch_size(MAX_BATCH_REQUESTS).is_ok());
+        assert!(check_batch_size(MAX_BATCH_REQUESTS + 1).is_err());
+    }
+}
@@ -0,0 +1,132 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use snarkvm::{
+    ledger::committee::Committee,
+    prelude::{store::ConsensusStorage, Address, Identifier, Ledger, Literal, Network, Plaintext, ProgramID, Value},
+};
+
+use anyhow::Result;
+use serde::Serialize;
+use std::str::FromStr;
+
+/// A committee, with the stake and bonded state of its members.
+#[derive(Clone, Debug, Serialize)]
+#[serde(bound = "")]
+pub struct CommitteeView<N: Network> {
+    /// The round the committee starts at.
+    pub starting_round: u64,
+    /// The total stake of the committee, in microcredits.
+    pub total_stake: u64,
+    /// The members of the committee, by decreasing stake.
+    pub members: Vec<CommitteeMember<N>>,
+}
+
+/// A member of a committee.
+#[derive(Clone, Debug, Serialize)]
+#[serde(bound = "")]
+pub struct CommitteeMember<N: Network> {
+    /// The address of the validator.
+    pub address: Address<N>,
+    /// The stake of the validator (including delegations), in microcredits.
+    pub stake: u64,
+    /// The share of the total stake of the committee.
+    pub stake_share: f64,
+    /// Whether the validator is open to new delegators.
+    pub is_open: bool,
+    /// The bonded state of the validator, only available for the latest committee.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bonded: Option<BondedState>,
+}
+
+/// The bonded state of a validator, from the `bonded` and `unbonding` mappings of `credits.aleo`.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct BondedState {
+    /// The microcredits bonded by the validator itself.
+    pub self_bonded: u64,
+    /// The microcredits being unbonded by the validator.
+    pub unbonding: u64,
+}
+
+impl<N: Network> CommitteeView<N> {
+    /// Returns the view of the given committee, without the bonded state of its members.
+    pub fn new(committee: &Committee<N>) -> Self {
+        let total_stake = committee.total_stake();
+        let mut members: Vec<_> = committee
+            .members()
+            .iter()
+            .map(|(address, (stake, is_open))| CommitteeMember {
+                address: *address,
+                stake: *stake,
+                stake_share: if total_stake == 0 { 0.0 } else { *stake as f64 / total_stake as f64 },
+                is_open: *is_open,
+                bonded: None,
+            })
+            .collect();
+        members.sort_by(|a, b| b.stake.cmp(&a.stake));
+        Self { starting_round: committee.starting_round(), total_stake, members }
+    }
+
+    /// Adds the current bonded state of the members, from the given ledger.
+    pub fn with_bonded_state<C: ConsensusStorage<N>>(mut self, ledger: &Ledger<N, C>) -> Result<Self> {
+        let credits = ProgramID::from_str("credits.aleo")?;
+        let (bonded, unbonding) = (Identifier::from_str("bonded")?, Identifier::from_str("unbonding")?);
+        let microcredits = |mapping, address: Address<N>| -> Result<u64> {
+            let key = Plaintext::from(Literal::Address(address));
+            let value = ledger.vm().finalize_store().get_value_confirmed(credits, mapping, &key)?;
+            Ok(value.as_ref().and_then(struct_microcredits).unwrap_or_default())
+        };
+        for member in &mut self.members {
+            member.bonded = Some(BondedState {
+                self_bonded: microcredits(bonded, member.address)?,
+                unbonding: microcredits(unbonding, member.address)?,
+            });
+        }
+        Ok(self)
+    }
+}
+
+/// Returns the `microcredits` member of the given struct value (e.g. a `bond_state` or an `unbond_state`).
+fn struct_microcredits<N: Network>(value: &Value<N>) -> Option<u64> {
+    match value {
+        Value::Plaintext(Plaintext::Struct(members, _)) => {
+            match members.get(&Identifier::from_str("microcredits").ok()?)? {
+                Plaintext::Literal(Literal::U64(microcredits), _) => Some(**microcredits),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkvm::prelude::{Group, MainnetV0};
+
+    type CurrentNetwork = MainnetV0;
+
+    #[test]
+    fn test_struct_microcredits() {
+        let address = Address::<CurrentNetwork>::new(Group::generator());
+        let bond_state = Value::from_str(&format!("{{ validator: {address}, microcredits: 100u64 }}")).unwrap();
+        assert_eq!(struct_microcredits::<CurrentNetwork>(&bond_state), Some(100));
+        let unbond_state = Value::from_str("{ microcredits: 7u64, height: 10u32 }").unwrap();
+        assert_eq!(struct_microcredits::<CurrentNetwork>(&unbond_state), Some(7));
+
+        // Check the values without microcredits are ignored.
+        assert_eq!(struct_microcredits::<CurrentNetwork>(&Value::from_str("100u64").unwrap()), None);
+        assert_eq!(struct_microcredits::<CurrentNetwork>(&Value::from_str("{ amount: 1u64 }").unwrap()), None);
+    }
+}
@@ -0,0 +1,131 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use anyhow::{bail, Result};
+use http::{header::CONTENT_TYPE, Extensions, HeaderMap, StatusCode, Version};
+use std::{str::FromStr, sync::Arc};
+use tower_http::compression::{
+    predicate::{Predicate, SizeAbove},
+    CompressionLayer,
+};
+
+/// The default minimum response size (in bytes) to compress.
+const DEFAULT_MIN_SIZE: u16 = 1024;
+/// The default content types to compress.
+const DEFAULT_CONTENT_TYPES: [&str; 3] = ["application/json", "application/octet-stream", "text/"];
+
+/// A response compression algorithm.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum CompressionAlgorithm {
+    Gzip,
+    Brotli,
+    Zstd,
+}
+
+impl FromStr for CompressionAlgorithm {
+    type Err = anyhow::Error;
+
+    fn from_str(algorithm: &str) -> Result<Self> {
+        match algorithm.trim().to_lowercase().as_str() {
+            "gzip" => Ok(Self::Gzip),
+            "br" | "brotli" => Ok(Self::Brotli),
+            "zstd" => Ok(Self::Zstd),
+            _ => bail!("Unsupported compression algorithm '{algorithm}' (options: gzip, br, zstd)"),
+        }
+    }
+}
+
+/// The response compression settings of the REST server.
+#[derive(Clone, Debug)]
+pub struct CompressionConfig {
+    /// The enabled compression algorithms; the one used is negotiated via `Accept-Encoding`.
+    pub algorithms: Vec<CompressionAlgorithm>,
+    /// The minimum response size (in bytes) to compress.
+    pub min_size: u16,
+    /// The content type prefixes to compress (e.g. `application/json`, `text/`).
+    pub content_types: Vec<String>,
+}
+
+impl Default for CompressionConfig {
+    /// Initializes a new compression configuration with all algorithms enabled, and the default thresholds.
+    fn default() -> Self {
+        Self {
+            algorithms: vec![CompressionAlgorithm::Gzip, CompressionAlgorithm::Brotli, CompressionAlgorithm::Zstd],
+            min_size: DEFAULT_MIN_SIZE,
+            content_types: DEFAULT_CONTENT_TYPES.iter().map(|content_type| content_type.to_string()).collect(),
+        }
+    }
+}
+
+impl CompressionConfig {
+    /// Returns `true` if the given algorithm is enabled.
+    pub fn is_enabled(&self, algorithm: CompressionAlgorithm) -> bool {
+        self.algorithms.contains(&algorithm)
+    }
+
+    /// Returns `true` if a response with the given headers has a compressible content type.
+    fn is_compressible(content_types: &[String], headers: &HeaderMap) -> bool {
+        match headers.get(CONTENT_TYPE).and_then(|content_type| content_type.to_str().ok()) {
+            Some(content_type) => content_types.iter().any(|prefix| content_type.starts_with(prefix.as_str())),
+            None => false,
+        }
+    }
+
+    /// Returns the compression layer for the REST server.
+    pub(crate) fn layer(&self) -> CompressionLayer<impl Predicate> {
+        let content_types: Arc<[String]> = self.content_types.clone().into();
+        let predicate =
+            SizeAbove::new(self.min_size).and(move |_: StatusCode, _: Version, headers: &HeaderMap, _: &Extensions| {
+                Self::is_compressible(&content_types, headers)
+            });
+
+        CompressionLayer::new()
+            .gzip(self.is_enabled(CompressionAlgorithm::Gzip))
+            .br(self.is_enabled(CompressionAlgorithm::Brotli))
+            .zstd(self.is_enabled(CompressionAlgorithm::Zstd))
+            .deflate(false)
+            .compress_when(predicate)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use http::HeaderValue;
+
+    #[test]
+    fn test_compression_algorithm_from_str() {
+        assert_eq!(CompressionAlgorithm::from_str("gzip").unwrap(), CompressionAlgorithm::Gzip);
+        assert_eq!(CompressionAlgorithm::from_str("br").unwrap(), CompressionAlgorithm::Brotli);
+        assert_eq!(CompressionAlgorithm::from_str(" ZSTD ").unwrap(), CompressionAlgorithm::Zstd);
+        assert!(CompressionAlgorithm::from_str("deflate").is_err());
+    }
+
+    #[test]
+    fn test_is_compressible() {
+        let config = CompressionConfig::default();
+
+        let mut headers = HeaderMap::new();
+        assert!(!CompressionConfig::is_compressible(&config.content_types, &headers));
+
+        headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+        assert!(CompressionConfig::is_compressible(&config.content_types, &headers));
+
+        headers.insert(CONTENT_TYPE, HeaderValue::from_static("text/plain; charset=utf-8"));
+        assert!(CompressionConfig::is_compressible(&config.content_types, &headers));
+
+        headers.insert(CONTENT_TYPE, HeaderValue::from_static("image/png"));
+        assert!(!CompressionConfig::is_compressible(&config.content_types, &headers));
+    }
+}
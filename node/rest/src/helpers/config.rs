@@ -0,0 +1,79 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::{
+    AdminConfig,
+    ApiKeys,
+    CompressionConfig,
+    CorsConfig,
+    NodeInfo,
+    RateLimitConfig,
+    RequestLimits,
+    TlsConfig,
+    UnixSocketConfig,
+};
+
+use std::{num::NonZeroUsize, path::PathBuf, sync::Arc, time::Duration};
+
+/// The REST server's configuration. See the source of [`RestConfig::default`] for the defaults.
+#[derive(Clone, Debug, Default)]
+pub struct RestConfig {
+    /// The response compression settings.
+    ///
+    /// note: If set to `None`, responses are never compressed.
+    pub compression: Option<CompressionConfig>,
+    /// The TLS settings.
+    ///
+    /// note: If set to `None`, the server serves plain HTTP.
+    pub tls: Option<TlsConfig>,
+    /// The Unix socket settings.
+    ///
+    /// note: If set to `None`, the server only listens on TCP.
+    pub unix_socket: Option<UnixSocketConfig>,
+    /// The API keys, each with its own rate limit and allowed routes.
+    ///
+    /// note: If set to `None`, all requests are rate limited per IP.
+    pub api_keys: Option<Arc<ApiKeys>>,
+    /// The CORS policy.
+    pub cors: CorsConfig,
+    /// The rate limiting settings, besides the number of requests per second.
+    pub rate_limit: RateLimitConfig,
+    /// The size and time limits of the requests.
+    pub limits: RequestLimits,
+    /// The number of responses of the immutable routes (e.g. historical blocks) to cache in memory.
+    ///
+    /// note: If set to `None`, responses are never cached.
+    pub response_cache: Option<NonZeroUsize>,
+    /// The `program/mapping` pairs indexed by the state history, to query their values as of a past block.
+    ///
+    /// note: If set to `None`, mapping values can only be queried at the latest block.
+    pub state_history: Option<Vec<String>>,
+    /// Whether to index the transactions of each address, served by `GET /mainnet/address/{address}/transactions`.
+    pub address_index: bool,
+    /// The directory in which the indexes are persisted (e.g. the address index).
+    ///
+    /// note: If set to `None`, the indexes are kept in memory, and rebuilt on every start.
+    pub storage_dir: Option<PathBuf>,
+    /// The admin API settings.
+    ///
+    /// note: If set to `None`, the admin routes are disabled.
+    pub admin: Option<AdminConfig>,
+    /// The time to wait for the in-flight requests to complete on shutdown, after the server stops accepting
+    /// new connections.
+    ///
+    /// note: If set to `None`, the in-flight requests are aborted on shutdown.
+    pub shutdown_grace_period: Option<Duration>,
+    /// The node information served by `GET /mainnet/node/info`.
+    pub node_info: NodeInfo,
+}
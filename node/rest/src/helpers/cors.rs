@@ -0,0 +1,127 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::API_KEY_HEADER;
+
+use anyhow::{anyhow, Result};
+use axum::http::{header::CONTENT_TYPE, HeaderName, HeaderValue, Method};
+use std::time::Duration;
+use tower_http::cors::{AllowHeaders, AllowOrigin, Any, CorsLayer};
+
+/// The CORS policy of the REST server, for browser-based applications.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CorsConfig {
+    /// The allowed origins (e.g. `https://app.example.com`), or `None` to allow any origin.
+    pub origins: Option<Vec<HeaderValue>>,
+    /// The allowed methods.
+    pub methods: Vec<Method>,
+    /// The allowed request headers, or `None` to allow any header.
+    pub headers: Option<Vec<HeaderName>>,
+    /// The duration browsers may cache the preflight responses for, if set.
+    pub max_age: Option<Duration>,
+}
+
+impl Default for CorsConfig {
+    /// Initializes a new CORS policy allowing any origin to send `GET` and `POST` requests.
+    fn default() -> Self {
+        Self {
+            origins: None,
+            methods: vec![Method::GET, Method::POST, Method::OPTIONS],
+            headers: Some(vec![CONTENT_TYPE, HeaderName::from_static(API_KEY_HEADER)]),
+            max_age: None,
+        }
+    }
+}
+
+impl CorsConfig {
+    /// Initializes a new CORS policy from the given comma-separated lists, where `*` allows any origin or header.
+    pub fn new(origins: &str, methods: &str, headers: &str, max_age: Option<u64>) -> Result<Self> {
+        let origins = match origins.trim() {
+            "*" => None,
+            origins => Some(
+                split(origins)
+                    .map(|origin| HeaderValue::from_str(origin).map_err(|_| anyhow!("Invalid CORS origin '{origin}'")))
+                    .collect::<Result<_>>()?,
+            ),
+        };
+        let methods = split(methods)
+            .map(|method| {
+                Method::from_bytes(method.to_uppercase().as_bytes())
+                    .map_err(|_| anyhow!("Invalid CORS method '{method}'"))
+            })
+            .collect::<Result<_>>()?;
+        let headers = match headers.trim() {
+            "*" => None,
+            headers => Some(
+                split(headers)
+                    .map(|header| {
+                        HeaderName::from_bytes(header.as_bytes()).map_err(|_| anyhow!("Invalid CORS header '{header}'"))
+                    })
+                    .collect::<Result<_>>()?,
+            ),
+        };
+        Ok(Self { origins, methods, headers, max_age: max_age.map(Duration::from_secs) })
+    }
+
+    /// Returns the CORS layer for the REST server.
+    pub(crate) fn layer(&self) -> CorsLayer {
+        let origins = match &self.origins {
+            Some(origins) => AllowOrigin::list(origins.iter().cloned()),
+            None => AllowOrigin::from(Any),
+        };
+        let headers = match &self.headers {
+            Some(headers) => AllowHeaders::list(headers.iter().cloned()),
+            None => AllowHeaders::from(Any),
+        };
+        let layer = CorsLayer::new().allow_origin(origins).allow_methods(self.methods.clone()).allow_headers(headers);
+        match self.max_age {
+            Some(max_age) => layer.max_age(max_age),
+            None => layer,
+        }
+    }
+}
+
+/// Returns the non-empty, trimmed items of the given comma-separated list.
+fn split(list: &str) -> impl '_ + Iterator<Item = &str> {
+    list.split(',').map(str::trim).filter(|item| !item.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cors_config() {
+        // Check the default policy can be expressed with the flags.
+        let config = CorsConfig::new("*", "GET,POST,OPTIONS", "content-type,x-api-key", None).unwrap();
+        assert_eq!(config, CorsConfig::default());
+
+        let config = CorsConfig::new("https://a.example.com, https://b.example.com", "get", "*", Some(600)).unwrap();
+        assert_eq!(
+            config.origins,
+            Some(vec![
+                HeaderValue::from_static("https://a.example.com"),
+                HeaderValue::from_static("https://b.example.com")
+            ])
+        );
+        assert_eq!(config.methods, vec![Method::GET]);
+        assert_eq!(config.headers, None);
+        assert_eq!(config.max_age, Some(Duration::from_secs(600)));
+
+        // Check the invalid values are rejected.
+        assert!(CorsConfig::new("*", "GET POST", "*", None).is_err());
+        assert!(CorsConfig::new("*", "GET", "content type", None).is_err());
+        assert!(CorsConfig::new("https://a.example.com\u{1}", "GET", "*", None).is_err());
+    }
+}
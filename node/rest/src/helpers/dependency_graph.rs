@@ -0,0 +1,190 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use snarkvm::{
+    console::{program::ProgramID, types::Field},
+    prelude::{
+        block::{Block, ConfirmedTransaction},
+        Network,
+    },
+    synthesizer::program::FinalizeOperation,
+};
+
+use indexmap::IndexSet;
+use serde::Serialize;
+
+/// The transaction dependency graph of a block.
+///
+/// Two transactions depend on each other if they write to the same mapping, in which case
+/// the later transaction (in block order) depends on the earlier one.
+///
+/// Note: The edges only cover mapping writes, i.e. the finalize operations recorded in the ledger.
+/// Mapping reads are not recorded in the ledger, so read-write dependencies are not captured, and
+/// the records consumed and produced by the transactions (listed in the nodes) do not form edges.
+#[derive(Clone, Debug, Serialize)]
+#[serde(bound = "")]
+pub struct DependencyGraph<N: Network> {
+    /// The height of the block.
+    pub height: u32,
+    /// The transactions of the block, in block order.
+    pub transactions: Vec<TransactionNode<N>>,
+    /// The dependencies between the transactions that write to the same mappings.
+    pub edges: Vec<DependencyEdge<N>>,
+}
+
+/// A transaction in the dependency graph.
+#[derive(Clone, Debug, Serialize)]
+#[serde(bound = "")]
+pub struct TransactionNode<N: Network> {
+    /// The index of the transaction in the block.
+    pub index: u32,
+    /// The transaction ID.
+    pub id: N::TransactionID,
+    /// The status of the transaction (e.g. "accepted_execute").
+    pub status: String,
+    /// The programs invoked by the transaction.
+    pub programs: IndexSet<ProgramID<N>>,
+    /// The mapping IDs written by the finalize operations of the transaction.
+    pub mappings: IndexSet<Field<N>>,
+    /// The serial numbers of the records consumed by the transaction.
+    pub serial_numbers: Vec<Field<N>>,
+    /// The commitments of the records produced by the transaction.
+    pub commitments: Vec<Field<N>>,
+}
+
+/// A dependency of a transaction on an earlier transaction in the block.
+#[derive(Clone, Debug, Serialize)]
+#[serde(bound = "")]
+pub struct DependencyEdge<N: Network> {
+    /// The index of the earlier transaction.
+    pub from: u32,
+    /// The index of the later transaction.
+    pub to: u32,
+    /// The mapping IDs written by both transactions.
+    pub mappings: Vec<Field<N>>,
+}
+
+impl<N: Network> DependencyGraph<N> {
+    /// Computes the transaction dependency graph of the given block.
+    pub fn new(block: &Block<N>) -> Self {
+        // Construct the transaction nodes.
+        let transactions: Vec<_> = block.transactions().iter().map(TransactionNode::new).collect();
+        // Construct the edges between the transactions.
+        let edges = dependency_edges(&transactions);
+
+        Self { height: block.height(), transactions, edges }
+    }
+}
+
+/// Returns the edges between the given transactions (in block order) that write to the same mappings.
+fn dependency_edges<N: Network>(transactions: &[TransactionNode<N>]) -> Vec<DependencyEdge<N>> {
+    let mut edges = Vec::new();
+    for (i, later) in transactions.iter().enumerate() {
+        for earlier in &transactions[..i] {
+            let mappings: Vec<_> = later.mappings.intersection(&earlier.mappings).copied().collect();
+            if !mappings.is_empty() {
+                edges.push(DependencyEdge { from: earlier.index, to: later.index, mappings });
+            }
+        }
+    }
+    edges
+}
+
+impl<N: Network> TransactionNode<N> {
+    /// Returns the dependency graph node for the given confirmed transaction.
+    fn new(confirmed: &ConfirmedTransaction<N>) -> Self {
+        let transaction = confirmed.transaction();
+        // Collect the mapping IDs written by the finalize operations.
+        let mappings = confirmed
+            .finalize_operations()
+            .iter()
+            .map(|operation| match operation {
+                FinalizeOperation::InitializeMapping(mapping_id, ..)
+                | FinalizeOperation::InsertKeyValue(mapping_id, ..)
+                | FinalizeOperation::UpdateKeyValue(mapping_id, ..)
+                | FinalizeOperation::RemoveKeyValue(mapping_id, ..)
+                | FinalizeOperation::ReplaceMapping(mapping_id, ..)
+                | FinalizeOperation::RemoveMapping(mapping_id, ..) => *mapping_id,
+            })
+            .collect();
+
+        Self {
+            index: confirmed.index(),
+            id: transaction.id(),
+            status: transaction_status(confirmed).to_string(),
+            programs: transaction.transitions().map(|transition| *transition.program_id()).collect(),
+            mappings,
+            serial_numbers: transaction.serial_numbers().copied().collect(),
+            commitments: transaction.commitments().copied().collect(),
+        }
+    }
+}
+
+/// Returns the status of the given confirmed transaction (e.g. "accepted_execute").
+pub(crate) fn transaction_status<N: Network>(confirmed: &ConfirmedTransaction<N>) -> &'static str {
+    match confirmed {
+        ConfirmedTransaction::AcceptedDeploy(..) => "accepted_deploy",
+        ConfirmedTransaction::AcceptedExecute(..) => "accepted_execute",
+        ConfirmedTransaction::RejectedDeploy(..) => "rejected_deploy",
+        ConfirmedTransaction::RejectedExecute(..) => "rejected_execute",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkvm::prelude::{MainnetV0, TestRng, Uniform};
+
+    type CurrentNetwork = MainnetV0;
+
+    /// Samples a transaction node at the given index, writing to the given mappings.
+    fn sample_node(
+        rng: &mut TestRng,
+        index: u32,
+        mappings: &[Field<CurrentNetwork>],
+    ) -> TransactionNode<CurrentNetwork> {
+        TransactionNode {
+            index,
+            id: Field::rand(rng).into(),
+            status: "accepted_execute".to_string(),
+            programs: Default::default(),
+            mappings: mappings.iter().copied().collect(),
+            serial_numbers: vec![Field::rand(rng)],
+            commitments: vec![Field::rand(rng)],
+        }
+    }
+
+    #[test]
+    fn test_dependency_edges() {
+        let rng = &mut TestRng::default();
+        let (account, supply, counter) = (Field::rand(rng), Field::rand(rng), Field::rand(rng));
+
+        let transactions = [
+            sample_node(rng, 0, &[account, supply]),
+            sample_node(rng, 1, &[counter]),
+            sample_node(rng, 2, &[supply, account]),
+            sample_node(rng, 3, &[]),
+            sample_node(rng, 4, &[account]),
+        ];
+        let edges = dependency_edges(&transactions);
+
+        // Check the later transactions depend on every earlier transaction writing to the same mappings.
+        let edges: Vec<_> = edges.iter().map(|edge| (edge.from, edge.to, edge.mappings.clone())).collect();
+        assert_eq!(edges, vec![(0, 2, vec![supply, account]), (0, 4, vec![account]), (2, 4, vec![account])]);
+
+        // Check the transactions without common mapping writes do not depend on each other.
+        assert!(dependency_edges(&transactions[1..4]).is_empty());
+        assert!(dependency_edges::<CurrentNetwork>(&[]).is_empty());
+    }
+}
@@ -15,19 +15,122 @@
 use axum::{
     http::StatusCode,
     response::{IntoResponse, Response},
+    Json,
 };
+use serde::Serialize;
+use serde_json::Value;
 
-/// An enum of error handlers for the REST API server.
-pub struct RestError(pub String);
+/// The machine-readable code of a REST error, so clients can branch on the type of error.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCode {
+    /// The request is invalid (e.g. a malformed parameter, or a range that is too large).
+    InvalidRequest,
+    /// The requested object was not found.
+    NotFound,
+    /// The submitted transaction or solution was rejected by the node.
+    Rejected,
+    /// The route is not available on this node (e.g. for its node type, or as it is disabled).
+    Unavailable,
+    /// An internal error occurred.
+    Internal,
+}
+
+impl ErrorCode {
+    /// Returns the HTTP status code of the error.
+    pub const fn status_code(&self) -> StatusCode {
+        match self {
+            Self::InvalidRequest => StatusCode::BAD_REQUEST,
+            Self::NotFound => StatusCode::NOT_FOUND,
+            Self::Rejected => StatusCode::UNPROCESSABLE_ENTITY,
+            Self::Unavailable => StatusCode::NOT_IMPLEMENTED,
+            Self::Internal => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+}
+
+/// An error of the REST API server, returned to the client as a JSON envelope.
+#[derive(Clone, Debug, Serialize)]
+pub struct RestError {
+    /// The machine-readable code of the error.
+    pub code: ErrorCode,
+    /// The human-readable message of the error.
+    pub message: String,
+    /// The details of the error, if any (e.g. the maximum of a range).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub details: Option<Value>,
+}
+
+impl RestError {
+    /// Initializes a new error with the given code and message.
+    pub fn new(code: ErrorCode, message: impl Into<String>) -> Self {
+        Self { code, message: message.into(), details: None }
+    }
+
+    /// Initializes a new error for an invalid request.
+    pub fn invalid_request(message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::InvalidRequest, message)
+    }
+
+    /// Initializes a new error for an object that was not found.
+    pub fn not_found(message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::NotFound, message)
+    }
+
+    /// Initializes a new error for a transaction or solution that was rejected.
+    pub fn rejected(message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::Rejected, message)
+    }
+
+    /// Initializes a new error for a route that is not available on this node.
+    pub fn unavailable(message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::Unavailable, message)
+    }
+
+    /// Initializes a new internal error.
+    pub fn internal(message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::Internal, message)
+    }
+
+    /// Returns the error with the given details.
+    pub fn with_details(mut self, details: Value) -> Self {
+        self.details = Some(details);
+        self
+    }
+}
 
 impl IntoResponse for RestError {
     fn into_response(self) -> Response {
-        (StatusCode::INTERNAL_SERVER_ERROR, format!("Something went wrong: {}", self.0)).into_response()
+        (self.code.status_code(), Json(self)).into_response()
     }
 }
 
 impl From<anyhow::Error> for RestError {
     fn from(err: anyhow::Error) -> Self {
-        Self(err.to_string())
+        Self::internal(err.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::to_bytes;
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn test_error_response() {
+        let error = RestError::invalid_request("Invalid block range").with_details(json!({ "max": 50 }));
+        let response = error.into_response();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let expected = json!({ "code": "invalid_request", "message": "Invalid block range", "details": { "max": 50 } });
+        assert_eq!(serde_json::from_slice::<Value>(&body).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_error_without_details() {
+        let error = serde_json::to_value(RestError::not_found("Missing block")).unwrap();
+        assert_eq!(error, json!({ "code": "not_found", "message": "Missing block" }));
     }
 }
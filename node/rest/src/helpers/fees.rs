@@ -0,0 +1,205 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use snarkvm::prelude::{
+    block::{Block, ConfirmedTransaction, Transaction},
+    Network,
+    ToBytes,
+};
+
+use anyhow::{bail, Result};
+use serde::{Deserialize, Serialize};
+
+/// The default number of recent blocks analyzed by the fee estimate.
+pub const DEFAULT_FEE_ESTIMATE_BLOCKS: u32 = 100;
+/// The maximum number of recent blocks analyzed by the fee estimate.
+pub const MAX_FEE_ESTIMATE_BLOCKS: u32 = 1000;
+
+/// The query object for the fee estimate.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct FeeEstimateQuery {
+    /// The number of recent blocks to analyze.
+    pub blocks: Option<u32>,
+}
+
+impl FeeEstimateQuery {
+    /// Returns the number of recent blocks to analyze, ensuring it is within bounds.
+    pub fn blocks(&self) -> Result<u32> {
+        match self.blocks.unwrap_or(DEFAULT_FEE_ESTIMATE_BLOCKS) {
+            0 => bail!("The number of blocks must be greater than 0"),
+            blocks if blocks > MAX_FEE_ESTIMATE_BLOCKS => {
+                bail!("The number of blocks must not exceed {MAX_FEE_ESTIMATE_BLOCKS}")
+            }
+            blocks => Ok(blocks),
+        }
+    }
+}
+
+/// The suggested fees, based on the recent blocks and the current memory pool pressure.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct FeeEstimate {
+    /// The number of blocks analyzed.
+    pub blocks: u32,
+    /// The current memory pool pressure.
+    pub mempool: MempoolPressure,
+    /// The suggested fees for deployments.
+    pub deploy: FeeSuggestion,
+    /// The suggested fees for executions.
+    pub execute: FeeSuggestion,
+}
+
+/// The pressure on the memory pool of the node.
+#[derive(Copy, Clone, Debug, PartialEq, Serialize)]
+pub struct MempoolPressure {
+    /// The number of unconfirmed transmissions.
+    pub unconfirmed: usize,
+    /// The maximum number of transmissions in a batch.
+    pub capacity: usize,
+    /// The ratio of unconfirmed transmissions to the capacity.
+    pub utilization: f64,
+}
+
+impl MempoolPressure {
+    /// Initializes the memory pool pressure.
+    pub fn new(unconfirmed: usize, capacity: usize) -> Self {
+        let utilization = if capacity == 0 { 0.0 } else { unconfirmed as f64 / capacity as f64 };
+        Self { unconfirmed, capacity, utilization }
+    }
+}
+
+/// The suggested fees for one type of transaction, in microcredits.
+///
+/// The base fee of a transaction is determined by its size and its finalize cost, so it is
+/// suggested per byte; the priority fee is suggested as an absolute amount.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct FeeSuggestion {
+    /// The number of confirmed transactions analyzed.
+    pub samples: usize,
+    /// The median base fee per byte.
+    pub base_fee_per_byte: u64,
+    /// The 25th, 50th and 75th percentiles of the priority fees.
+    pub priority_fees: [u64; 3],
+    /// The suggested priority fee, given the memory pool pressure.
+    pub priority_fee: u64,
+}
+
+/// The fees paid by the confirmed transactions of one type.
+#[derive(Clone, Debug, Default)]
+struct FeeSamples {
+    /// The base fees per byte.
+    base_fees_per_byte: Vec<u64>,
+    /// The priority fees.
+    priority_fees: Vec<u64>,
+}
+
+impl FeeSamples {
+    /// Records the fees paid by the given transaction.
+    fn insert<N: Network>(&mut self, transaction: &Transaction<N>) -> Result<()> {
+        let size = transaction.to_bytes_le()?.len().max(1) as u64;
+        self.base_fees_per_byte.push(*transaction.base_fee_amount()? / size);
+        self.priority_fees.push(*transaction.priority_fee_amount()?);
+        Ok(())
+    }
+
+    /// Returns the fee suggestion, given the memory pool pressure.
+    fn suggest(mut self, pressure: &MempoolPressure) -> FeeSuggestion {
+        let priority_fees = [
+            percentile(&mut self.priority_fees, 25),
+            percentile(&mut self.priority_fees, 50),
+            percentile(&mut self.priority_fees, 75),
+        ];
+        // The priority fee only matters when the memory pool is contended.
+        let priority_fee = match pressure.utilization {
+            utilization if utilization < 0.5 => priority_fees[0],
+            utilization if utilization < 1.0 => priority_fees[1],
+            _ => priority_fees[2],
+        };
+        FeeSuggestion {
+            samples: self.priority_fees.len(),
+            base_fee_per_byte: percentile(&mut self.base_fees_per_byte, 50),
+            priority_fees,
+            priority_fee,
+        }
+    }
+}
+
+/// Estimates the fees from the given recent blocks and memory pool pressure.
+pub fn estimate_fees<N: Network>(blocks: &[Block<N>], pressure: MempoolPressure) -> Result<FeeEstimate> {
+    let mut deploy = FeeSamples::default();
+    let mut execute = FeeSamples::default();
+    for confirmed in blocks.iter().flat_map(|block| block.transactions().iter()) {
+        match confirmed {
+            ConfirmedTransaction::AcceptedDeploy(_, transaction, _) => deploy.insert(transaction)?,
+            ConfirmedTransaction::AcceptedExecute(_, transaction, _) => execute.insert(transaction)?,
+            // Rejected transactions only pay their fee, so they are not representative.
+            ConfirmedTransaction::RejectedDeploy(..) | ConfirmedTransaction::RejectedExecute(..) => (),
+        }
+    }
+    Ok(FeeEstimate {
+        blocks: blocks.len() as u32,
+        mempool: pressure,
+        deploy: deploy.suggest(&pressure),
+        execute: execute.suggest(&pressure),
+    })
+}
+
+/// Returns the given percentile of the values (using the nearest-rank method), or `0` if there are none.
+fn percentile(values: &mut [u64], percentile: usize) -> u64 {
+    if values.is_empty() {
+        return 0;
+    }
+    values.sort_unstable();
+    let rank = (percentile * values.len()).div_ceil(100).max(1);
+    values[rank.min(values.len()) - 1]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_percentile() {
+        assert_eq!(percentile(&mut [], 50), 0);
+        assert_eq!(percentile(&mut [7], 25), 7);
+        let mut values = [40, 10, 30, 20];
+        assert_eq!(percentile(&mut values, 25), 10);
+        assert_eq!(percentile(&mut values, 50), 20);
+        assert_eq!(percentile(&mut values, 75), 30);
+        assert_eq!(percentile(&mut values, 100), 40);
+    }
+
+    #[test]
+    fn test_suggest() {
+        let samples = FeeSamples { base_fees_per_byte: vec![3, 1, 2], priority_fees: vec![0, 100, 200, 300] };
+
+        // Check the suggested priority fee rises with the memory pool pressure.
+        let low = samples.clone().suggest(&MempoolPressure::new(10, 100));
+        assert_eq!(low.samples, 4);
+        assert_eq!(low.base_fee_per_byte, 2);
+        assert_eq!(low.priority_fees, [0, 100, 200]);
+        assert_eq!(low.priority_fee, 0);
+        assert_eq!(samples.clone().suggest(&MempoolPressure::new(75, 100)).priority_fee, 100);
+        assert_eq!(samples.suggest(&MempoolPressure::new(150, 100)).priority_fee, 200);
+
+        // Check there is no suggestion without samples.
+        assert_eq!(FeeSamples::default().suggest(&MempoolPressure::new(150, 100)), FeeSuggestion::default());
+    }
+
+    #[test]
+    fn test_fee_estimate_query() {
+        assert_eq!(FeeEstimateQuery::default().blocks().unwrap(), DEFAULT_FEE_ESTIMATE_BLOCKS);
+        assert!(FeeEstimateQuery { blocks: Some(0) }.blocks().is_err());
+        assert!(FeeEstimateQuery { blocks: Some(MAX_FEE_ESTIMATE_BLOCKS + 1) }.blocks().is_err());
+    }
+}
@@ -0,0 +1,203 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use snarkvm::prelude::{store::ConsensusStorage, Address, Ledger, Network};
+
+use axum::http::StatusCode;
+use serde::Serialize;
+
+/// The maximum number of blocks a node may be behind the best-known tip, and still be ready.
+pub const MAX_READY_BLOCKS_BEHIND: u32 = 10;
+
+/// The readiness report of the node.
+#[derive(Clone, Debug, Serialize)]
+#[serde(bound = "")]
+pub struct HealthReport<N: Network> {
+    /// Whether the node is ready to serve traffic.
+    pub ready: bool,
+    /// The reasons the node is not ready, if any.
+    pub issues: Vec<String>,
+    /// The sync status of the node.
+    pub sync: SyncHealth,
+    /// The peer connectivity of the node.
+    pub peers: PeerHealth,
+    /// The storage health of the node.
+    pub storage: StorageHealth,
+    /// The BFT participation of the node, if it is a validator.
+    pub bft: Option<BftHealth<N>>,
+}
+
+/// The sync status of the node.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct SyncHealth {
+    /// The latest block height of the node.
+    pub height: u32,
+    /// The greatest block height advertised by the peers, if known.
+    pub best_known_height: Option<u32>,
+    /// The number of blocks the node is behind the best-known tip, if known.
+    pub blocks_behind: Option<u32>,
+}
+
+/// The peer connectivity of the node.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct PeerHealth {
+    /// The number of connected peers.
+    pub connected: usize,
+}
+
+/// The storage health of the node.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct StorageHealth {
+    /// Whether the latest block can be read from storage.
+    pub is_healthy: bool,
+    /// The storage error, if any.
+    pub error: Option<String>,
+}
+
+/// The BFT participation of a validator.
+#[derive(Clone, Debug, Serialize)]
+#[serde(bound = "")]
+pub struct BftHealth<N: Network> {
+    /// Whether the BFT is synced with the other validators.
+    pub is_synced: bool,
+    /// Whether the validator is a member of the latest committee.
+    pub is_committee_member: bool,
+    /// The current round of the BFT.
+    pub current_round: u64,
+    /// The leader of the current round, if known.
+    pub leader: Option<Address<N>>,
+    /// The number of connected validators.
+    pub connected_validators: usize,
+}
+
+impl<N: Network> HealthReport<N> {
+    /// Initializes a new readiness report, determining whether the node is ready.
+    pub fn new(sync: SyncHealth, peers: PeerHealth, storage: StorageHealth, bft: Option<BftHealth<N>>) -> Self {
+        let mut issues = Vec::new();
+        // Ensure the storage is healthy.
+        if let Some(error) = &storage.error {
+            issues.push(format!("Storage is unhealthy - {error}"));
+        }
+        // Ensure the node is connected to the network.
+        if peers.connected == 0 {
+            issues.push("There are no connected peers".to_string());
+        }
+        // Ensure the node is synced to the best-known tip.
+        if let (Some(blocks_behind), Some(tip)) = (sync.blocks_behind, sync.best_known_height) {
+            if blocks_behind > MAX_READY_BLOCKS_BEHIND {
+                issues.push(format!("The node is {blocks_behind} blocks behind the best-known tip ({tip})"));
+            }
+        }
+        // Ensure the BFT is synced, if the node is a validator.
+        if let Some(bft) = &bft {
+            if !bft.is_synced {
+                issues.push("The BFT is not synced".to_string());
+            }
+        }
+        Self { ready: issues.is_empty(), issues, sync, peers, storage, bft }
+    }
+
+    /// Returns the HTTP status code of the report, for load balancers.
+    pub fn status_code(&self) -> StatusCode {
+        match self.ready {
+            true => StatusCode::OK,
+            false => StatusCode::SERVICE_UNAVAILABLE,
+        }
+    }
+}
+
+impl SyncHealth {
+    /// Initializes the sync status, given the latest block height and the best-known tip.
+    pub fn new(height: u32, best_known_height: Option<u32>) -> Self {
+        let blocks_behind = best_known_height.map(|tip| tip.saturating_sub(height));
+        Self { height, best_known_height, blocks_behind }
+    }
+}
+
+impl StorageHealth {
+    /// Checks that the latest block header can be read from the ledger.
+    pub fn check<N: Network, C: ConsensusStorage<N>>(ledger: &Ledger<N, C>) -> Self {
+        let height = ledger.latest_height();
+        match ledger.get_header(height) {
+            Ok(header) if header.height() == height => Self { is_healthy: true, error: None },
+            Ok(header) => Self {
+                is_healthy: false,
+                error: Some(format!("Expected the header of block {height}, found block {}", header.height())),
+            },
+            Err(error) => Self { is_healthy: false, error: Some(format!("Failed to read block {height} - {error}")) },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkvm::prelude::MainnetV0;
+
+    type CurrentNetwork = MainnetV0;
+
+    fn healthy_storage() -> StorageHealth {
+        StorageHealth { is_healthy: true, error: None }
+    }
+
+    #[test]
+    fn test_sync_health() {
+        assert_eq!(SyncHealth::new(10, None).blocks_behind, None);
+        assert_eq!(SyncHealth::new(10, Some(25)).blocks_behind, Some(15));
+        // Check that a node ahead of its peers is not behind.
+        assert_eq!(SyncHealth::new(30, Some(25)).blocks_behind, Some(0));
+    }
+
+    #[test]
+    fn test_health_report_ready() {
+        let sync = SyncHealth::new(100, Some(100 + MAX_READY_BLOCKS_BEHIND));
+        let report = HealthReport::<CurrentNetwork>::new(sync, PeerHealth { connected: 3 }, healthy_storage(), None);
+        assert!(report.ready);
+        assert!(report.issues.is_empty());
+        assert_eq!(report.status_code(), StatusCode::OK);
+    }
+
+    #[test]
+    fn test_health_report_not_ready() {
+        // Check that a node behind the tip is not ready.
+        let sync = SyncHealth::new(100, Some(101 + MAX_READY_BLOCKS_BEHIND));
+        let report = HealthReport::<CurrentNetwork>::new(sync, PeerHealth { connected: 3 }, healthy_storage(), None);
+        assert!(!report.ready);
+        assert_eq!(report.issues.len(), 1);
+        assert_eq!(report.status_code(), StatusCode::SERVICE_UNAVAILABLE);
+
+        // Check that a node without peers, and with failing storage, is not ready.
+        let storage = StorageHealth { is_healthy: false, error: Some("failure".to_string()) };
+        let report =
+            HealthReport::<CurrentNetwork>::new(SyncHealth::new(0, None), PeerHealth { connected: 0 }, storage, None);
+        assert!(!report.ready);
+        assert_eq!(report.issues.len(), 2);
+        assert_eq!(report.status_code(), StatusCode::SERVICE_UNAVAILABLE);
+    }
+
+    #[test]
+    fn test_health_report_bft_not_synced() {
+        let bft = BftHealth::<CurrentNetwork> {
+            is_synced: false,
+            is_committee_member: true,
+            current_round: 5,
+            leader: None,
+            connected_validators: 3,
+        };
+        let report =
+            HealthReport::new(SyncHealth::new(10, Some(10)), PeerHealth { connected: 3 }, healthy_storage(), Some(bft));
+        assert!(!report.ready);
+        assert_eq!(report.issues, vec!["The BFT is not synced".to_string()]);
+    }
+}
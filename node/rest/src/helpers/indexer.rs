@@ -0,0 +1,67 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use snarkvm::prelude::{store::ConsensusStorage, Ledger, Network};
+
+use anyhow::Result;
+use std::{sync::Arc, time::Duration};
+use tokio::task::JoinHandle;
+
+/// The interval at which the indexer checks the ledger for new blocks.
+const INDEXER_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// An index of the ledger, which is updated as blocks are added to the ledger.
+pub trait LedgerIndex<N: Network, C: ConsensusStorage<N>>: Send + Sync {
+    /// Returns the name of the index, for the logs.
+    fn name(&self) -> &'static str;
+
+    /// Indexes the blocks added to the ledger since the last update.
+    fn update(&self, ledger: &Ledger<N, C>) -> Result<()>;
+}
+
+/// Spawns a single task that updates the given indexes, in order, as blocks are added to the ledger.
+///
+/// Note: The indexes are only updated when the latest height changes, and a failed index is retried
+/// on the next poll without holding back the other indexes.
+pub fn spawn_indexer<N: Network, C: 'static + ConsensusStorage<N>>(
+    indexes: Vec<Arc<dyn LedgerIndex<N, C>>>,
+    ledger: Ledger<N, C>,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let indexes = Arc::new(indexes);
+        let mut indexed_height = None;
+        loop {
+            let latest_height = ledger.latest_height();
+            if indexed_height != Some(latest_height) {
+                let (indexes, ledger) = (indexes.clone(), ledger.clone());
+                let update = move || {
+                    let mut is_updated = true;
+                    for index in indexes.iter() {
+                        if let Err(error) = index.update(&ledger) {
+                            warn!("Failed to update the {} - {error}", index.name());
+                            is_updated = false;
+                        }
+                    }
+                    is_updated
+                };
+                match tokio::task::spawn_blocking(update).await {
+                    Ok(true) => indexed_height = Some(latest_height),
+                    Ok(false) => (),
+                    Err(error) => warn!("Failed to update the ledger indexes - {error}"),
+                }
+            }
+            tokio::time::sleep(INDEXER_POLL_INTERVAL).await;
+        }
+    })
+}
@@ -0,0 +1,114 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use axum::{
+    body::Body,
+    extract::State,
+    http::{Request, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use std::time::Duration;
+
+/// The default maximum size of a request body, in bytes.
+pub const DEFAULT_MAX_BODY_SIZE: usize = 10 * 1024 * 1024;
+
+/// The size and time limits of the REST requests.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct RequestLimits {
+    /// The maximum size of a request body, in bytes (e.g. for large deployments sent to `/transaction/broadcast`).
+    pub max_body_size: usize,
+    /// The maximum time to handle a request, if any.
+    pub timeout: Option<Duration>,
+}
+
+impl Default for RequestLimits {
+    /// Returns the default limits, with a body size limit of 10MB and no timeout.
+    fn default() -> Self {
+        Self { max_body_size: DEFAULT_MAX_BODY_SIZE, timeout: None }
+    }
+}
+
+/// Enforces the request timeout, and replaces the errors of the requests exceeding the limits with clear messages.
+pub(crate) async fn limits_middleware(
+    State(limits): State<RequestLimits>,
+    request: Request<Body>,
+    next: Next,
+) -> Response {
+    let response = match limits.timeout {
+        Some(timeout) => match tokio::time::timeout(timeout, next.run(request)).await {
+            Ok(response) => response,
+            Err(_) => {
+                let message = format!("The request timed out after {} seconds", timeout.as_secs_f64());
+                return (StatusCode::REQUEST_TIMEOUT, message).into_response();
+            }
+        },
+        None => next.run(request).await,
+    };
+    match response.status() {
+        StatusCode::PAYLOAD_TOO_LARGE => {
+            let message = format!("The request body exceeds the limit of {} bytes", limits.max_body_size);
+            (StatusCode::PAYLOAD_TOO_LARGE, message).into_response()
+        }
+        _ => response,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{body::to_bytes, extract::DefaultBodyLimit, middleware, routing::post, Router};
+    use tower::ServiceExt;
+
+    /// Returns a router with the given limits, and a route echoing the request body after the given delay.
+    fn router(limits: RequestLimits, delay: Duration) -> Router {
+        Router::new()
+            .route(
+                "/",
+                post(move |body: String| async move {
+                    tokio::time::sleep(delay).await;
+                    body
+                }),
+            )
+            .layer(middleware::from_fn_with_state(limits, limits_middleware))
+            .layer(DefaultBodyLimit::max(limits.max_body_size))
+    }
+
+    /// Sends a request with the given body to the router, returning the status code and the response body.
+    async fn send(router: Router, body: &'static str) -> (StatusCode, String) {
+        let response = router.oneshot(Request::post("/").body(Body::from(body)).unwrap()).await.unwrap();
+        let status = response.status();
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (status, String::from_utf8(body.to_vec()).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_body_limit() {
+        let limits = RequestLimits { max_body_size: 4, timeout: None };
+        assert_eq!(send(router(limits, Duration::ZERO), "1234").await, (StatusCode::OK, "1234".to_string()));
+
+        let (status, message) = send(router(limits, Duration::ZERO), "12345").await;
+        assert_eq!(status, StatusCode::PAYLOAD_TOO_LARGE);
+        assert_eq!(message, "The request body exceeds the limit of 4 bytes");
+    }
+
+    #[tokio::test]
+    async fn test_timeout() {
+        let limits = RequestLimits { max_body_size: DEFAULT_MAX_BODY_SIZE, timeout: Some(Duration::from_millis(50)) };
+        assert_eq!(send(router(limits, Duration::ZERO), "fast").await, (StatusCode::OK, "fast".to_string()));
+
+        let (status, _) = send(router(limits, Duration::from_secs(1)), "slow").await;
+        assert_eq!(status, StatusCode::REQUEST_TIMEOUT);
+    }
+}
@@ -0,0 +1,86 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use snarkvm::{
+    ledger::{
+        block::Transaction,
+        coinbase::{ProverSolution, PuzzleCommitment},
+    },
+    prelude::{Network, ToBytes},
+};
+
+use anyhow::Result;
+use serde::Serialize;
+use std::time::Duration;
+
+/// An unconfirmed transaction in the memory pool.
+#[derive(Clone, Debug, Serialize)]
+#[serde(bound = "")]
+pub struct MempoolTransaction<N: Network> {
+    /// The transaction ID.
+    pub id: N::TransactionID,
+    /// The type of the transaction (e.g. "execute").
+    #[serde(rename = "type")]
+    pub type_: &'static str,
+    /// The fee of the transaction, in microcredits.
+    pub fee: u64,
+    /// The size of the transaction, in bytes.
+    pub size: usize,
+    /// The number of seconds since the transaction was first seen by this node, if it is known.
+    pub age: Option<u64>,
+}
+
+impl<N: Network> MempoolTransaction<N> {
+    /// Returns the summary of the given unconfirmed transaction.
+    pub fn new(transaction: &Transaction<N>, age: Option<Duration>) -> Result<Self> {
+        let type_ = match transaction {
+            Transaction::Deploy(..) => "deploy",
+            Transaction::Execute(..) => "execute",
+            Transaction::Fee(..) => "fee",
+        };
+        Ok(Self {
+            id: transaction.id(),
+            type_,
+            fee: *transaction.fee_amount()?,
+            size: transaction.to_bytes_le()?.len(),
+            age: age.map(|age| age.as_secs()),
+        })
+    }
+}
+
+/// An unconfirmed solution in the memory pool.
+#[derive(Clone, Debug, Serialize)]
+#[serde(bound = "")]
+pub struct MempoolSolution<N: Network> {
+    /// The puzzle commitment of the solution.
+    pub commitment: PuzzleCommitment<N>,
+    /// The address of the prover.
+    pub address: String,
+    /// The size of the solution, in bytes.
+    pub size: usize,
+    /// The number of seconds since the solution was first seen by this node, if it is known.
+    pub age: Option<u64>,
+}
+
+impl<N: Network> MempoolSolution<N> {
+    /// Returns the summary of the given unconfirmed solution.
+    pub fn new(solution: &ProverSolution<N>, age: Option<Duration>) -> Result<Self> {
+        Ok(Self {
+            commitment: solution.commitment(),
+            address: solution.address().to_string(),
+            size: solution.to_bytes_le()?.len(),
+            age: age.map(|age| age.as_secs()),
+        })
+    }
+}
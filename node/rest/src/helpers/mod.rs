@@ -12,8 +12,109 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+mod address_index;
+pub use address_index::*;
+
+mod admin;
+pub use admin::*;
+
+mod api_keys;
+pub use api_keys::*;
+
+mod batch;
+pub use batch::*;
+
 mod auth;
 pub use auth::*;
 
+mod committee;
+pub use committee::*;
+
+mod compression;
+pub use compression::*;
+
+mod config;
+pub use config::*;
+
+mod cors;
+pub use cors::*;
+
+mod dependency_graph;
+pub use dependency_graph::*;
+
 mod error;
 pub use error::*;
+
+mod fees;
+pub use fees::*;
+
+mod health;
+pub use health::*;
+
+mod indexer;
+pub use indexer::*;
+
+mod limits;
+pub use limits::*;
+
+mod mempool;
+pub use mempool::*;
+
+mod node_info;
+pub use node_info::*;
+
+mod openapi;
+pub use openapi::*;
+
+mod program;
+pub use program::*;
+
+mod puzzle;
+pub use puzzle::*;
+
+mod rate_limit;
+pub use rate_limit::*;
+
+mod record_scan;
+pub use record_scan::*;
+
+mod pagination;
+pub use pagination::*;
+
+#[cfg(feature = "metrics")]
+mod request_metrics;
+#[cfg(feature = "metrics")]
+pub(crate) use request_metrics::*;
+
+mod simulation;
+pub use simulation::*;
+
+mod response_cache;
+pub use response_cache::*;
+
+mod shutdown;
+pub(crate) use shutdown::*;
+
+mod sse;
+pub use sse::*;
+
+mod state_history;
+pub use state_history::*;
+
+mod stream;
+pub use stream::*;
+
+mod timestamps;
+pub use timestamps::*;
+
+mod tls;
+pub use tls::*;
+
+mod transaction_status;
+pub use transaction_status::*;
+
+mod unix_socket;
+pub use unix_socket::*;
+
+mod websocket;
+pub use websocket::*;
@@ -0,0 +1,37 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+
+/// The build and configuration information of a node, as returned by `GET /mainnet/node/info`.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NodeInfo {
+    /// The version of snarkOS.
+    pub version: String,
+    /// The git commit snarkOS was built from, if known.
+    pub git_commit: Option<String>,
+    /// The enabled build features.
+    pub features: Vec<String>,
+    /// The name of the network.
+    pub network: String,
+    /// The ID of the network.
+    pub network_id: u16,
+    /// The node type.
+    pub node_type: String,
+    /// The enabled subsystems (e.g. "rest", "cdn", "metrics").
+    pub subsystems: Vec<String>,
+    /// The configured limits, by name.
+    pub limits: IndexMap<String, u64>,
+}
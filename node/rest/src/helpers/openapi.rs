@@ -0,0 +1,222 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::auth_middleware;
+
+use axum::{
+    handler::Handler,
+    http::Method,
+    middleware,
+    response::Html,
+    routing::{get, post},
+    Router,
+};
+use axum_extra::response::ErasedJson;
+use serde_json::{json, Map, Value};
+use std::sync::Arc;
+
+/// The path of the OpenAPI specification.
+pub const OPENAPI_PATH: &str = "/openapi.json";
+/// The path of the Swagger UI.
+pub const SWAGGER_UI_PATH: &str = "/docs";
+
+/// A documented route.
+#[derive(Clone, Debug)]
+struct RouteDoc {
+    /// The method of the route.
+    method: Method,
+    /// The path of the route, in axum syntax (e.g. `/mainnet/block/:height_or_hash`).
+    path: &'static str,
+    /// The summary of the route.
+    summary: &'static str,
+    /// If `true`, the route requires a JWT.
+    authenticated: bool,
+}
+
+/// A router that documents its routes as they are added, so the OpenAPI specification
+/// is generated from the actual route definitions.
+pub struct ApiRouter<S> {
+    /// The router.
+    router: Router<S>,
+    /// The documented routes.
+    routes: Vec<RouteDoc>,
+}
+
+impl<S: Clone + Send + Sync + 'static> Default for ApiRouter<S> {
+    fn default() -> Self {
+        Self { router: Router::new(), routes: Vec::new() }
+    }
+}
+
+impl<S: Clone + Send + Sync + 'static> ApiRouter<S> {
+    /// Adds a `GET` route with the given summary.
+    pub fn get<H: Handler<T, S>, T: 'static>(mut self, path: &'static str, handler: H, summary: &'static str) -> Self {
+        self.router = self.router.route(path, get(handler));
+        self.routes.push(RouteDoc { method: Method::GET, path, summary, authenticated: false });
+        self
+    }
+
+    /// Adds a `POST` route with the given summary.
+    pub fn post<H: Handler<T, S>, T: 'static>(mut self, path: &'static str, handler: H, summary: &'static str) -> Self {
+        self.router = self.router.route(path, post(handler));
+        self.routes.push(RouteDoc { method: Method::POST, path, summary, authenticated: false });
+        self
+    }
+
+    /// Protects all the routes added so far with JWT auth.
+    pub fn authenticated(mut self) -> Self {
+        self.router = self.router.route_layer(middleware::from_fn(auth_middleware));
+        self.routes.iter_mut().for_each(|route| route.authenticated = true);
+        self
+    }
+
+    /// Merges the given (undocumented) router.
+    pub fn merge(mut self, router: Router<S>) -> Self {
+        self.router = self.router.merge(router);
+        self
+    }
+
+    /// Returns the router, serving the OpenAPI specification and the Swagger UI of its routes.
+    pub fn finish(self) -> Router<S> {
+        let spec = Arc::new(openapi_spec(&self.routes));
+        self.router
+            .route(OPENAPI_PATH, get(move || async move { ErasedJson::pretty(&*spec) }))
+            .route(SWAGGER_UI_PATH, get(|| async { Html(swagger_ui()) }))
+    }
+}
+
+/// Returns the OpenAPI specification of the given routes.
+fn openapi_spec(routes: &[RouteDoc]) -> Value {
+    let mut paths = Map::new();
+    for route in routes {
+        // Convert the path parameters from axum syntax (`:id`) to OpenAPI syntax (`{id}`).
+        let segments = route.path.split('/');
+        let path = segments
+            .map(|segment| segment.strip_prefix(':').map_or(segment.to_string(), |name| format!("{{{name}}}")))
+            .collect::<Vec<_>>()
+            .join("/");
+        let parameters: Vec<_> = route
+            .path
+            .split('/')
+            .filter_map(|segment| segment.strip_prefix(':'))
+            .map(|name| json!({ "name": name, "in": "path", "required": true, "schema": { "type": "string" } }))
+            .collect();
+        // Group the routes by the first segment after the network (e.g. `block`).
+        let tag = route.path.split('/').nth(2).unwrap_or_default();
+
+        let mut operation = json!({
+            "summary": route.summary,
+            "tags": [tag],
+            "parameters": parameters,
+            "responses": {
+                "200": { "description": "Success", "content": { "application/json": { "schema": {} } } },
+                "default": {
+                    "description": "Error",
+                    "content": { "application/json": { "schema": { "$ref": "#/components/schemas/Error" } } },
+                },
+            },
+        });
+        if route.authenticated {
+            operation["security"] = json!([{ "jwt": [] }]);
+            operation["responses"]["401"] = json!({ "description": "Unauthorized" });
+        }
+        if route.method == Method::POST {
+            operation["requestBody"] =
+                json!({ "required": true, "content": { "application/json": { "schema": {} } } });
+        }
+
+        let item = paths.entry(path).or_insert_with(|| json!({}));
+        item[route.method.as_str().to_lowercase()] = operation;
+    }
+
+    json!({
+        "openapi": "3.0.3",
+        "info": { "title": "snarkOS REST API", "version": env!("CARGO_PKG_VERSION") },
+        "paths": paths,
+        "components": {
+            "securitySchemes": { "jwt": { "type": "http", "scheme": "bearer", "bearerFormat": "JWT" } },
+            "schemas": { "Error": error_schema() },
+        },
+    })
+}
+
+/// Returns the schema of the error envelope (see `RestError`).
+fn error_schema() -> Value {
+    json!({
+        "type": "object",
+        "required": ["code", "message"],
+        "properties": {
+            "code": {
+                "type": "string",
+                "enum": ["invalid_request", "not_found", "rejected", "unavailable", "internal"],
+            },
+            "message": { "type": "string" },
+            "details": {},
+        },
+    })
+}
+
+/// Returns the Swagger UI page, rendering the OpenAPI specification.
+fn swagger_ui() -> String {
+    format!(
+        r##"<!DOCTYPE html>
+<html>
+<head>
+  <title>snarkOS REST API</title>
+  <link rel="stylesheet" href="https://unpkg.com/swagger-ui-dist@5/swagger-ui.css" />
+</head>
+<body>
+  <div id="swagger-ui"></div>
+  <script src="https://unpkg.com/swagger-ui-dist@5/swagger-ui-bundle.js"></script>
+  <script>window.ui = SwaggerUIBundle({{ url: "{OPENAPI_PATH}", dom_id: "#swagger-ui" }});</script>
+</body>
+</html>"##
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_openapi_spec() {
+        let doc = |method, path, summary, authenticated| RouteDoc { method, path, summary, authenticated };
+        let routes = vec![
+            doc(Method::GET, "/mainnet/node/address", "Address", true),
+            doc(Method::GET, "/mainnet/block/:height_or_hash", "Block", false),
+            doc(Method::POST, "/mainnet/transaction/broadcast", "Transaction", false),
+        ];
+        let spec = openapi_spec(&routes);
+
+        // Check the authenticated route.
+        let operation = &spec["paths"]["/mainnet/node/address"]["get"];
+        assert_eq!(operation["summary"], "Address");
+        assert_eq!(operation["tags"], json!(["node"]));
+        assert_eq!(operation["security"], json!([{ "jwt": [] }]));
+
+        // Check that the path parameters are converted.
+        let operation = &spec["paths"]["/mainnet/block/{height_or_hash}"]["get"];
+        assert_eq!(operation["parameters"][0]["name"], "height_or_hash");
+        assert!(operation.get("security").is_none());
+
+        // Check the error responses refer to the error envelope.
+        let schema = &operation["responses"]["default"]["content"]["application/json"]["schema"];
+        assert_eq!(schema["$ref"], "#/components/schemas/Error");
+        assert_eq!(spec["components"]["schemas"]["Error"]["required"], json!(["code", "message"]));
+
+        // Check the request body of the POST route.
+        let operation = &spec["paths"]["/mainnet/transaction/broadcast"]["post"];
+        assert_eq!(operation["requestBody"]["required"], true);
+    }
+}
@@ -0,0 +1,284 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::transaction_status;
+use snarkvm::prelude::{
+    block::{Block, ConfirmedTransaction, Input, Output, Transaction},
+    Address,
+    Argument,
+    Future,
+    Identifier,
+    Literal,
+    Network,
+    Plaintext,
+    ProgramID,
+};
+
+use anyhow::{anyhow, bail, Result};
+use indexmap::IndexSet;
+use serde::{Deserialize, Serialize};
+use std::{fmt, str::FromStr};
+
+/// The default number of items per page.
+pub const DEFAULT_PAGE_LIMIT: u32 = 20;
+/// The maximum number of items per page.
+pub const MAX_PAGE_LIMIT: u32 = 100;
+/// The maximum number of blocks scanned per page, so a rare filter does not scan the entire ledger at once.
+pub const MAX_PAGE_SCANNED_BLOCKS: u32 = 1000;
+
+/// The query object for paginated endpoints.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(bound = "")]
+pub struct PageQuery<N: Network> {
+    /// The cursor to start from (inclusive), as returned in the `next` field of the previous page.
+    pub start: Option<String>,
+    /// The maximum number of items in the page.
+    pub limit: Option<u32>,
+    /// Only includes the transactions invoking the given program.
+    pub program: Option<ProgramID<N>>,
+    /// Only includes the transactions invoking a function with the given name.
+    pub function: Option<Identifier<N>>,
+    /// Only includes the transactions with the given address as a public input or output.
+    pub address: Option<Address<N>>,
+}
+
+impl<N: Network> PageQuery<N> {
+    /// Returns the number of items per page, ensuring it is within bounds.
+    pub fn limit(&self) -> Result<u32> {
+        match self.limit.unwrap_or(DEFAULT_PAGE_LIMIT) {
+            0 => bail!("The page limit must be greater than 0"),
+            limit if limit > MAX_PAGE_LIMIT => bail!("The page limit must not exceed {MAX_PAGE_LIMIT}"),
+            limit => Ok(limit),
+        }
+    }
+
+    /// Returns the cursor to start from, defaulting to the genesis block.
+    pub fn cursor(&self) -> Result<Cursor> {
+        self.start.as_deref().map_or(Ok(Cursor::default()), Cursor::from_str)
+    }
+
+    /// Returns `true` if the query has any filters.
+    pub fn has_filters(&self) -> bool {
+        self.program.is_some() || self.function.is_some() || self.address.is_some()
+    }
+
+    /// Returns `true` if the given transaction passes the filters of the query.
+    pub fn matches(&self, transaction: &Transaction<N>) -> bool {
+        // Check the program and function filters, which must hold for the same transition.
+        let matches_call = transaction.transitions().any(|transition| {
+            self.program.map_or(true, |program| transition.program_id() == &program)
+                && self.function.map_or(true, |function| transition.function_name() == &function)
+        });
+        // Check the address filter.
+        let matches_address = self.address.map_or(true, |address| public_addresses(transaction).contains(&address));
+        matches_call && matches_address
+    }
+}
+
+/// Returns the addresses in the public inputs, outputs and finalize arguments of the given transaction.
+pub fn public_addresses<N: Network>(transaction: &Transaction<N>) -> IndexSet<Address<N>> {
+    let mut addresses = IndexSet::new();
+    for transition in transaction.transitions() {
+        for input in transition.inputs() {
+            if let Input::Constant(_, Some(plaintext)) | Input::Public(_, Some(plaintext)) = input {
+                collect_addresses(plaintext, &mut addresses);
+            }
+        }
+        for output in transition.outputs() {
+            match output {
+                Output::Constant(_, Some(plaintext)) | Output::Public(_, Some(plaintext)) => {
+                    collect_addresses(plaintext, &mut addresses)
+                }
+                Output::Future(_, Some(future)) => collect_future_addresses(future, &mut addresses),
+                _ => (),
+            }
+        }
+    }
+    addresses
+}
+
+/// Collects the addresses in the given plaintext.
+fn collect_addresses<N: Network>(plaintext: &Plaintext<N>, addresses: &mut IndexSet<Address<N>>) {
+    match plaintext {
+        Plaintext::Literal(Literal::Address(address), _) => {
+            addresses.insert(*address);
+        }
+        Plaintext::Struct(members, _) => members.values().for_each(|member| collect_addresses(member, addresses)),
+        _ => (),
+    }
+}
+
+/// Collects the addresses in the arguments of the given future (e.g. the caller passed to finalize).
+fn collect_future_addresses<N: Network>(future: &Future<N>, addresses: &mut IndexSet<Address<N>>) {
+    for argument in future.arguments() {
+        match argument {
+            Argument::Plaintext(plaintext) => collect_addresses(plaintext, addresses),
+            Argument::Future(future) => collect_future_addresses(future, addresses),
+        }
+    }
+}
+
+/// A stable position in the ledger, formatted as `{height}` or `{height}.{index}`,
+/// where `index` is the index of a transaction in the block.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Cursor {
+    /// The block height.
+    pub height: u32,
+    /// The index of the transaction in the block.
+    pub index: u32,
+}
+
+impl FromStr for Cursor {
+    type Err = anyhow::Error;
+
+    fn from_str(cursor: &str) -> Result<Self> {
+        let invalid = || anyhow!("Invalid cursor '{cursor}' (expected '{{height}}' or '{{height}}.{{index}}')");
+        let (height, index) = cursor.split_once('.').unwrap_or((cursor, "0"));
+        Ok(Self { height: height.parse().map_err(|_| invalid())?, index: index.parse().map_err(|_| invalid())? })
+    }
+}
+
+impl fmt::Display for Cursor {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.index {
+            0 => write!(f, "{}", self.height),
+            index => write!(f, "{}.{index}", self.height),
+        }
+    }
+}
+
+/// A page of items, with the cursor of the next page.
+#[derive(Clone, Debug, Serialize)]
+pub struct Page<T> {
+    /// The items in the page.
+    pub items: Vec<T>,
+    /// The cursor of the next page, or `None` if the latest block was reached.
+    pub next: Option<String>,
+}
+
+/// A summary of a block, without its transactions.
+#[derive(Clone, Debug, Serialize)]
+#[serde(bound = "")]
+pub struct BlockSummary<N: Network> {
+    /// The height of the block.
+    pub height: u32,
+    /// The hash of the block.
+    pub hash: N::BlockHash,
+    /// The hash of the previous block.
+    pub previous_hash: N::BlockHash,
+    /// The timestamp of the block.
+    pub timestamp: i64,
+    /// The number of transactions in the block.
+    pub transactions: usize,
+}
+
+impl<N: Network> From<&Block<N>> for BlockSummary<N> {
+    fn from(block: &Block<N>) -> Self {
+        Self {
+            height: block.height(),
+            hash: block.hash(),
+            previous_hash: block.previous_hash(),
+            timestamp: block.timestamp(),
+            transactions: block.transactions().len(),
+        }
+    }
+}
+
+/// A summary of a confirmed transaction, without its proofs.
+#[derive(Clone, Debug, Serialize)]
+#[serde(bound = "")]
+pub struct TransactionSummary<N: Network> {
+    /// The transaction ID.
+    pub id: N::TransactionID,
+    /// The height of the block of the transaction.
+    pub height: u32,
+    /// The index of the transaction in the block.
+    pub index: u32,
+    /// The status of the transaction (e.g. "accepted_execute").
+    pub status: String,
+    /// The `{program}/{function}` calls of the transitions of the transaction.
+    pub calls: Vec<String>,
+}
+
+impl<N: Network> TransactionSummary<N> {
+    /// Returns the summary of the given confirmed transaction, in the block at the given height.
+    pub fn new(height: u32, confirmed: &ConfirmedTransaction<N>) -> Self {
+        let transaction = confirmed.transaction();
+        Self {
+            id: transaction.id(),
+            height,
+            index: confirmed.index(),
+            status: transaction_status(confirmed).to_string(),
+            calls: transaction
+                .transitions()
+                .map(|transition| format!("{}/{}", transition.program_id(), transition.function_name()))
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkvm::prelude::{FromBytes, MainnetV0};
+
+    type CurrentNetwork = MainnetV0;
+
+    #[test]
+    fn test_cursor() {
+        assert_eq!(Cursor::from_str("12").unwrap(), Cursor { height: 12, index: 0 });
+        assert_eq!(Cursor::from_str("12.3").unwrap(), Cursor { height: 12, index: 3 });
+        assert!(Cursor::from_str("").is_err());
+        assert!(Cursor::from_str("12.").is_err());
+        assert!(Cursor::from_str("a.3").is_err());
+
+        // Check that the cursors round-trip.
+        for cursor in [Cursor { height: 12, index: 0 }, Cursor { height: 12, index: 3 }] {
+            assert_eq!(Cursor::from_str(&cursor.to_string()).unwrap(), cursor);
+        }
+    }
+
+    #[test]
+    fn test_page_query_limit() {
+        let mut query =
+            PageQuery::<CurrentNetwork> { start: None, limit: None, program: None, function: None, address: None };
+        assert_eq!(query.limit().unwrap(), DEFAULT_PAGE_LIMIT);
+        query.limit = Some(MAX_PAGE_LIMIT);
+        assert_eq!(query.limit().unwrap(), MAX_PAGE_LIMIT);
+        query.limit = Some(MAX_PAGE_LIMIT + 1);
+        assert!(query.limit().is_err());
+        query.limit = Some(0);
+        assert!(query.limit().is_err());
+    }
+
+    #[test]
+    fn test_page_query_matches() {
+        let genesis = Block::<CurrentNetwork>::from_bytes_le(CurrentNetwork::genesis_bytes()).unwrap();
+        let transaction = genesis.transactions().iter().next().unwrap().transaction();
+
+        let mut query =
+            PageQuery::<CurrentNetwork> { start: None, limit: None, program: None, function: None, address: None };
+        assert!(!query.has_filters());
+        assert!(query.matches(transaction));
+
+        query.program = Some(ProgramID::from_str("credits.aleo").unwrap());
+        assert!(query.matches(transaction));
+        query.function = Some(Identifier::from_str("nonexistent").unwrap());
+        assert!(!query.matches(transaction));
+
+        query.function = None;
+        query.program = Some(ProgramID::from_str("other.aleo").unwrap());
+        assert!(!query.matches(transaction));
+    }
+}
@@ -0,0 +1,265 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use snarkvm::prelude::{store::ConsensusStorage, Identifier, Ledger, Network, Program, ProgramID};
+
+use anyhow::Result;
+use indexmap::IndexMap;
+use parking_lot::RwLock;
+use serde::Serialize;
+use std::{str::FromStr, sync::Arc};
+
+/// The maximum number of program descriptions kept in the cache.
+const MAX_CACHED_PROGRAMS: usize = 256;
+
+/// A structured description of a deployed program.
+#[derive(Clone, Debug, Serialize)]
+#[serde(bound = "")]
+pub struct ProgramDescription<N: Network> {
+    /// The program ID.
+    pub id: ProgramID<N>,
+    /// The IDs of the imported programs.
+    pub imports: Vec<ProgramID<N>>,
+    /// The mappings of the program.
+    pub mappings: Vec<MappingDescription<N>>,
+    /// The structs of the program.
+    pub structs: Vec<TypeDescription<N>>,
+    /// The records of the program.
+    pub records: Vec<TypeDescription<N>>,
+    /// The functions of the program.
+    pub functions: Vec<FunctionDescription<N>>,
+}
+
+/// A mapping of a program.
+#[derive(Clone, Debug, Serialize)]
+#[serde(bound = "")]
+pub struct MappingDescription<N: Network> {
+    /// The mapping name.
+    pub name: Identifier<N>,
+    /// The type of the mapping keys (e.g. "address").
+    pub key: String,
+    /// The type of the mapping values (e.g. "u64").
+    pub value: String,
+}
+
+/// A struct or record of a program.
+#[derive(Clone, Debug, Serialize)]
+#[serde(bound = "")]
+pub struct TypeDescription<N: Network> {
+    /// The type name.
+    pub name: Identifier<N>,
+    /// The members of the type, in declaration order.
+    pub members: Vec<MemberDescription<N>>,
+}
+
+/// A member of a struct or record.
+#[derive(Clone, Debug, Serialize)]
+#[serde(bound = "")]
+pub struct MemberDescription<N: Network> {
+    /// The member name.
+    pub name: Identifier<N>,
+    /// The member type (e.g. "u64" or "u64.private").
+    #[serde(rename = "type")]
+    pub type_: String,
+}
+
+/// A function of a program.
+#[derive(Clone, Debug, Serialize)]
+#[serde(bound = "")]
+pub struct FunctionDescription<N: Network> {
+    /// The function name.
+    pub name: Identifier<N>,
+    /// The types of the inputs (e.g. "u64.public").
+    pub inputs: Vec<String>,
+    /// The types of the outputs (e.g. "u64.private").
+    pub outputs: Vec<String>,
+    /// The types of the finalize inputs, if the function has a finalize block.
+    pub finalize: Option<Vec<String>>,
+}
+
+impl<N: Network> ProgramDescription<N> {
+    /// Returns the description of the given program.
+    pub fn new(program: &Program<N>) -> Result<Self> {
+        let mappings = program
+            .mappings()
+            .iter()
+            .map(|(name, mapping)| MappingDescription {
+                name: *name,
+                key: mapping.key().plaintext_type().to_string(),
+                value: mapping.value().plaintext_type().to_string(),
+            })
+            .collect();
+
+        let structs = program
+            .structs()
+            .iter()
+            .map(|(name, struct_)| TypeDescription {
+                name: *name,
+                members: struct_
+                    .members()
+                    .iter()
+                    .map(|(name, type_)| MemberDescription { name: *name, type_: type_.to_string() })
+                    .collect(),
+            })
+            .collect();
+
+        // The owner is implicit in every record, so it is listed as the first member.
+        let owner_name = Identifier::from_str("owner")?;
+        let records = program
+            .records()
+            .iter()
+            .map(|(name, record)| {
+                let owner = match record.owner().is_public() {
+                    true => "address.public",
+                    false => "address.private",
+                };
+                let owner = MemberDescription { name: owner_name, type_: owner.to_string() };
+                let entries = record
+                    .entries()
+                    .iter()
+                    .map(|(name, type_)| MemberDescription { name: *name, type_: type_.to_string() });
+                TypeDescription { name: *name, members: std::iter::once(owner).chain(entries).collect() }
+            })
+            .collect();
+
+        let functions = program
+            .functions()
+            .iter()
+            .map(|(name, function)| FunctionDescription {
+                name: *name,
+                inputs: function.inputs().iter().map(|input| input.value_type().to_string()).collect(),
+                outputs: function.outputs().iter().map(|output| output.value_type().to_string()).collect(),
+                finalize: function
+                    .finalize_logic()
+                    .map(|finalize| finalize.inputs().iter().map(|input| input.finalize_type().to_string()).collect()),
+            })
+            .collect();
+
+        Ok(Self {
+            id: *program.id(),
+            imports: program.imports().keys().copied().collect(),
+            mappings,
+            structs,
+            records,
+            functions,
+        })
+    }
+}
+
+/// A cache of the descriptions of deployed programs.
+///
+/// Deployed programs are immutable, so each program is only parsed once, until it is evicted from the cache.
+pub struct ProgramCache<N: Network> {
+    /// The program descriptions, in insertion order.
+    descriptions: RwLock<IndexMap<ProgramID<N>, Arc<ProgramDescription<N>>>>,
+}
+
+impl<N: Network> Default for ProgramCache<N> {
+    /// Initializes an empty cache.
+    fn default() -> Self {
+        Self { descriptions: Default::default() }
+    }
+}
+
+impl<N: Network> ProgramCache<N> {
+    /// Returns the description of the given program, loading it from the ledger if it is not cached.
+    pub fn get<C: ConsensusStorage<N>>(
+        &self,
+        ledger: &Ledger<N, C>,
+        id: &ProgramID<N>,
+    ) -> Result<Arc<ProgramDescription<N>>> {
+        if let Some(description) = self.descriptions.read().get(id) {
+            return Ok(description.clone());
+        }
+        let description = Arc::new(ProgramDescription::new(&ledger.get_program(*id)?)?);
+        self.insert(description.clone());
+        Ok(description)
+    }
+
+    /// Inserts the given description, evicting the oldest one if the cache is full.
+    fn insert(&self, description: Arc<ProgramDescription<N>>) {
+        let mut descriptions = self.descriptions.write();
+        if descriptions.len() >= MAX_CACHED_PROGRAMS {
+            descriptions.shift_remove_index(0);
+        }
+        descriptions.insert(description.id, description);
+    }
+
+    /// Returns the number of cached descriptions.
+    pub fn len(&self) -> usize {
+        self.descriptions.read().len()
+    }
+
+    /// Returns `true` if there are no cached descriptions.
+    pub fn is_empty(&self) -> bool {
+        self.descriptions.read().is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkvm::prelude::MainnetV0;
+
+    type CurrentNetwork = MainnetV0;
+
+    const PROGRAM: &str = r"
+program token.aleo;
+
+struct point:
+    x as u32;
+    y as u32;
+
+record token:
+    owner as address.private;
+    amount as u64.private;
+
+mapping balances:
+    key as address.public;
+    value as u64.public;
+
+function add:
+    input r0 as u32.public;
+    input r1 as u32.private;
+    add r0 r1 into r2;
+    output r2 as u32.private;
+";
+
+    #[test]
+    fn test_program_description() {
+        let program = Program::<CurrentNetwork>::from_str(PROGRAM).unwrap();
+        let description = ProgramDescription::new(&program).unwrap();
+        assert_eq!(description.id.to_string(), "token.aleo");
+        assert!(description.imports.is_empty());
+
+        assert_eq!(description.mappings.len(), 1);
+        assert_eq!(description.mappings[0].name.to_string(), "balances");
+        assert_eq!(description.mappings[0].key, "address");
+        assert_eq!(description.mappings[0].value, "u64");
+
+        assert_eq!(description.structs.len(), 1);
+        let members: Vec<_> = description.structs[0].members.iter().map(|m| (m.name.to_string(), &*m.type_)).collect();
+        assert_eq!(members, [("x".to_string(), "u32"), ("y".to_string(), "u32")]);
+
+        assert_eq!(description.records.len(), 1);
+        let members: Vec<_> = description.records[0].members.iter().map(|m| (m.name.to_string(), &*m.type_)).collect();
+        assert_eq!(members, [("owner".to_string(), "address.private"), ("amount".to_string(), "u64.private")]);
+
+        assert_eq!(description.functions.len(), 1);
+        assert_eq!(description.functions[0].name.to_string(), "add");
+        assert_eq!(description.functions[0].inputs, ["u32.public", "u32.private"]);
+        assert_eq!(description.functions[0].outputs, ["u32.private"]);
+        assert!(description.functions[0].finalize.is_none());
+    }
+}
@@ -0,0 +1,80 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::MempoolSolution;
+use snarkvm::{
+    ledger::coinbase::ProverSolution,
+    prelude::{store::ConsensusStorage, Ledger, Network},
+};
+
+use anyhow::Result;
+use serde::Serialize;
+use std::time::Duration;
+
+/// The state of the coinbase puzzle, for provers and pool operators.
+#[derive(Clone, Debug, Serialize)]
+#[serde(bound = "")]
+pub struct PuzzleStatus<N: Network> {
+    /// The latest block height.
+    pub height: u32,
+    /// The number of the current puzzle epoch.
+    pub epoch_number: u32,
+    /// The block hash the current epoch challenge is derived from.
+    pub epoch_block_hash: N::BlockHash,
+    /// The current coinbase target.
+    pub coinbase_target: u64,
+    /// The current proof target, which a solution must meet to be accepted.
+    pub proof_target: u64,
+    /// The solutions pending in the memory pool, if the node has one.
+    pub pending: Option<Vec<PendingSolution<N>>>,
+}
+
+/// A solution pending in the memory pool.
+#[derive(Clone, Debug, Serialize)]
+#[serde(bound = "")]
+pub struct PendingSolution<N: Network> {
+    /// The summary of the solution.
+    #[serde(flatten)]
+    pub solution: MempoolSolution<N>,
+    /// The target of the solution.
+    pub target: u64,
+    /// Whether the solution meets the current proof target.
+    pub meets_proof_target: bool,
+}
+
+impl<N: Network> PuzzleStatus<N> {
+    /// Returns the current puzzle state of the given ledger, with the given pending solutions (if any).
+    pub fn new<C: ConsensusStorage<N>>(
+        ledger: &Ledger<N, C>,
+        pending: Option<Vec<PendingSolution<N>>>,
+    ) -> Result<Self> {
+        let epoch_challenge = ledger.latest_epoch_challenge()?;
+        Ok(Self {
+            height: ledger.latest_height(),
+            epoch_number: epoch_challenge.epoch_number(),
+            epoch_block_hash: epoch_challenge.epoch_block_hash(),
+            coinbase_target: ledger.latest_coinbase_target(),
+            proof_target: ledger.latest_proof_target(),
+            pending,
+        })
+    }
+}
+
+impl<N: Network> PendingSolution<N> {
+    /// Returns the pending solution, checking its target against the given proof target.
+    pub fn new(solution: &ProverSolution<N>, age: Option<Duration>, proof_target: u64) -> Result<Self> {
+        let target = solution.to_target()?;
+        Ok(Self { solution: MempoolSolution::new(solution, age)?, target, meets_proof_target: target >= proof_target })
+    }
+}
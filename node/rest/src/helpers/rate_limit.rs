@@ -0,0 +1,139 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use anyhow::{anyhow, bail, ensure, Result};
+use axum::{
+    http::{header::RETRY_AFTER, StatusCode},
+    response::{IntoResponse, Response},
+    Router,
+};
+use std::{fmt, str::FromStr};
+use tower_governor::{
+    governor::GovernorConfigBuilder,
+    key_extractor::GlobalKeyExtractor,
+    GovernorError,
+    GovernorLayer,
+};
+
+/// The scope of the REST rate limit.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum RateLimitScope {
+    /// The requests are rate limited per IP.
+    #[default]
+    PerIp,
+    /// The requests of all the clients share a single rate limit.
+    Global,
+}
+
+impl FromStr for RateLimitScope {
+    type Err = anyhow::Error;
+
+    fn from_str(scope: &str) -> Result<Self> {
+        match scope.trim().to_lowercase().as_str() {
+            "ip" | "per-ip" => Ok(Self::PerIp),
+            "global" => Ok(Self::Global),
+            _ => bail!("Unsupported rate limit scope '{scope}' (options: ip, global)"),
+        }
+    }
+}
+
+impl fmt::Display for RateLimitScope {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::PerIp => write!(f, "per IP"),
+            Self::Global => write!(f, "global"),
+        }
+    }
+}
+
+/// The rate limiting settings of the REST server.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct RateLimitConfig {
+    /// The maximum number of requests allowed in a burst; if `None`, it is the number of requests per second.
+    pub burst: Option<u32>,
+    /// The scope of the rate limit.
+    pub scope: RateLimitScope,
+}
+
+impl RateLimitConfig {
+    /// Applies the rate limit to the given router, allowing the given number of requests per second on average.
+    pub(crate) fn apply(&self, router: Router, rps: u32) -> Result<Router> {
+        let burst = self.burst.unwrap_or(rps);
+        ensure!(rps > 0, "The REST rate limit must allow at least 1 request per second");
+        ensure!(burst > 0, "The REST rate limit burst must allow at least 1 request");
+        debug!("REST rate limit ({}) - {rps} RPS, with bursts of up to {burst} requests", self.scope);
+
+        // Replenish one request per interval.
+        let interval_ms = (1000 / rps as u64).max(1);
+        let error = || anyhow!("Couldn't set up rate limiting for the REST server");
+        // We can leak the configurations because they are created only once and they persist.
+        match self.scope {
+            RateLimitScope::PerIp => {
+                let config = GovernorConfigBuilder::default()
+                    .per_millisecond(interval_ms)
+                    .burst_size(burst)
+                    .error_handler(too_many_requests)
+                    .finish()
+                    .ok_or_else(error)?;
+                Ok(router.layer(GovernorLayer { config: Box::leak(Box::new(config)) }))
+            }
+            RateLimitScope::Global => {
+                let config = GovernorConfigBuilder::default()
+                    .key_extractor(GlobalKeyExtractor)
+                    .per_millisecond(interval_ms)
+                    .burst_size(burst)
+                    .error_handler(too_many_requests)
+                    .finish()
+                    .ok_or_else(error)?;
+                Ok(router.layer(GovernorLayer { config: Box::leak(Box::new(config)) }))
+            }
+        }
+    }
+}
+
+/// Returns the response to a rate-limited request, i.e. `429 Too Many Requests` with a `Retry-After` header.
+fn too_many_requests(error: GovernorError) -> Response {
+    match error {
+        GovernorError::TooManyRequests { wait_time, .. } => {
+            // Round the delay up, so the client does not retry too early.
+            let retry_after = wait_time.max(1).to_string();
+            (StatusCode::TOO_MANY_REQUESTS, [(RETRY_AFTER, retry_after)], "Too many requests").into_response()
+        }
+        error => (StatusCode::INTERNAL_SERVER_ERROR, error.to_string()).into_response(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rate_limit_scope_from_str() {
+        assert_eq!(RateLimitScope::from_str("ip").unwrap(), RateLimitScope::PerIp);
+        assert_eq!(RateLimitScope::from_str(" Per-IP ").unwrap(), RateLimitScope::PerIp);
+        assert_eq!(RateLimitScope::from_str("global").unwrap(), RateLimitScope::Global);
+        assert!(RateLimitScope::from_str("peer").is_err());
+    }
+
+    #[test]
+    fn test_too_many_requests() {
+        let response = too_many_requests(GovernorError::TooManyRequests { wait_time: 3, headers: None });
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(response.headers().get(RETRY_AFTER).unwrap(), "3");
+
+        // Check that the delay is never 0.
+        let response = too_many_requests(GovernorError::TooManyRequests { wait_time: 0, headers: None });
+        assert_eq!(response.headers().get(RETRY_AFTER).unwrap(), "1");
+    }
+}
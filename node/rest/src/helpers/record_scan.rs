@@ -0,0 +1,243 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::{Cursor, Page, DEFAULT_PAGE_LIMIT, MAX_PAGE_LIMIT, MAX_PAGE_SCANNED_BLOCKS};
+use snarkvm::prelude::{
+    block::{Input, Transition},
+    store::ConsensusStorage,
+    Field,
+    GraphKey,
+    Ledger,
+    Network,
+    Plaintext,
+    Record,
+    ViewKey,
+};
+
+use anyhow::{bail, Result};
+use indexmap::IndexSet;
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+/// The maximum number of tags per scan.
+pub const MAX_SCAN_TAGS: usize = 1000;
+
+/// The request object for scanning the ledger for records.
+///
+/// Either the view key is given, to find and decrypt the records owned by it, or the tags are given,
+/// to find where the records with these tags were spent, without sharing the view key with the node.
+#[derive(Clone, Deserialize)]
+#[serde(bound = "")]
+pub struct RecordScanRequest<N: Network> {
+    /// The view key of the owner of the records.
+    pub view_key: Option<ViewKey<N>>,
+    /// The tags of the records, as derived by the client from its graph key.
+    #[serde(default)]
+    pub tags: Vec<Field<N>>,
+    /// The cursor to start from (inclusive), as returned in the `next` field of the previous page.
+    pub start: Option<String>,
+    /// The block height to stop at (inclusive), defaulting to the latest block.
+    pub end: Option<u32>,
+    /// The maximum number of records in the page.
+    pub limit: Option<u32>,
+}
+
+/// A record found by a scan.
+#[derive(Clone, Debug, Serialize)]
+#[serde(bound = "")]
+pub struct ScannedRecord<N: Network> {
+    /// The height of the block.
+    pub height: u32,
+    /// The ID of the transaction.
+    pub transaction_id: N::TransactionID,
+    /// The ID of the transition that produced (or, for a tag scan, consumed) the record.
+    pub transition_id: N::TransitionID,
+    /// The tag of the record.
+    pub tag: Field<N>,
+    /// `true` if the record is spent.
+    pub spent: bool,
+    /// The commitment of the record, for a view key scan.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub commitment: Option<Field<N>>,
+    /// The decrypted record, for a view key scan.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub record: Option<Record<N, Plaintext<N>>>,
+    /// The serial number of the record, for a tag scan.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub serial_number: Option<Field<N>>,
+}
+
+impl<N: Network> RecordScanRequest<N> {
+    /// Ensures the request has either a view key or tags, and a valid page limit and cursor.
+    pub fn check(&self) -> Result<()> {
+        match (&self.view_key, self.tags.len()) {
+            (Some(_), 0) | (None, 1..=MAX_SCAN_TAGS) => (),
+            (None, 0) | (Some(_), _) => bail!("Exactly one of 'view_key' or 'tags' must be given"),
+            (None, _) => bail!("Cannot scan for more than {MAX_SCAN_TAGS} tags at once"),
+        }
+        self.limit()?;
+        self.cursor()?;
+        Ok(())
+    }
+
+    /// Returns the number of records per page, ensuring it is within bounds.
+    fn limit(&self) -> Result<usize> {
+        match self.limit.unwrap_or(DEFAULT_PAGE_LIMIT) {
+            0 => bail!("The page limit must be greater than 0"),
+            limit if limit > MAX_PAGE_LIMIT => bail!("The page limit must not exceed {MAX_PAGE_LIMIT}"),
+            limit => Ok(limit as usize),
+        }
+    }
+
+    /// Returns the cursor to start from, defaulting to the genesis block.
+    fn cursor(&self) -> Result<Cursor> {
+        self.start.as_deref().map_or(Ok(Cursor::default()), Cursor::from_str)
+    }
+
+    /// Scans the blocks until the page is full, or the scan limit or the end height is reached.
+    ///
+    /// Note: The page is only cut between transactions, so it may exceed the limit by the records of one transaction.
+    pub fn scan<C: ConsensusStorage<N>>(&self, ledger: &Ledger<N, C>) -> Result<Page<ScannedRecord<N>>> {
+        let limit = self.limit()?;
+        let mut cursor = self.cursor()?;
+        let last_height = self.end.unwrap_or(u32::MAX).min(ledger.latest_height());
+        let end_height = last_height.min(cursor.height.saturating_add(MAX_PAGE_SCANNED_BLOCKS - 1));
+
+        // Derive the graph key, to compute the tags of the owned records.
+        let keys = match self.view_key {
+            Some(view_key) => Some((view_key, GraphKey::try_from(view_key)?)),
+            None => None,
+        };
+        let tags: IndexSet<_> = self.tags.iter().copied().collect();
+
+        let mut items = Vec::new();
+        'scan: while cursor.height <= end_height {
+            let block = ledger.get_block(cursor.height)?;
+            for confirmed in block.transactions().iter().filter(|tx| tx.index() >= cursor.index) {
+                // If the page is full, resume from this transaction on the next page.
+                if items.len() >= limit {
+                    cursor.index = confirmed.index();
+                    break 'scan;
+                }
+                let location = (cursor.height, confirmed.transaction().id());
+                for transition in confirmed.transaction().transitions() {
+                    match &keys {
+                        Some((view_key, graph_key)) => {
+                            items.extend(owned_records(ledger, location, transition, view_key, graph_key)?)
+                        }
+                        None => items.extend(spent_records(location, transition, &tags)),
+                    }
+                }
+            }
+            cursor = Cursor { height: cursor.height + 1, index: 0 };
+        }
+
+        let next = (cursor.height <= last_height).then(|| cursor.to_string());
+        Ok(Page { items, next })
+    }
+}
+
+/// Returns the records produced by the given transition and owned by the given view key.
+fn owned_records<N: Network, C: ConsensusStorage<N>>(
+    ledger: &Ledger<N, C>,
+    (height, transaction_id): (u32, N::TransactionID),
+    transition: &Transition<N>,
+    view_key: &ViewKey<N>,
+    graph_key: &GraphKey<N>,
+) -> Result<Vec<ScannedRecord<N>>> {
+    let mut records = Vec::new();
+    for (commitment, record) in transition.records() {
+        if !record.is_owner(view_key) {
+            continue;
+        }
+        let tag = Record::<N, Plaintext<N>>::tag(*graph_key, *commitment)?;
+        records.push(ScannedRecord {
+            height,
+            transaction_id,
+            transition_id: *transition.id(),
+            tag,
+            spent: ledger.contains_tag(&tag)?,
+            commitment: Some(*commitment),
+            record: Some(record.decrypt(view_key)?),
+            serial_number: None,
+        });
+    }
+    Ok(records)
+}
+
+/// Returns the records with the given tags consumed by the given transition.
+fn spent_records<N: Network>(
+    (height, transaction_id): (u32, N::TransactionID),
+    transition: &Transition<N>,
+    tags: &IndexSet<Field<N>>,
+) -> Vec<ScannedRecord<N>> {
+    transition
+        .inputs()
+        .iter()
+        .filter_map(|input| match input {
+            Input::Record(serial_number, tag) if tags.contains(tag) => Some(ScannedRecord {
+                height,
+                transaction_id,
+                transition_id: *transition.id(),
+                tag: *tag,
+                spent: true,
+                commitment: None,
+                record: None,
+                serial_number: Some(*serial_number),
+            }),
+            _ => None,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkvm::prelude::{MainnetV0, PrivateKey, TestRng};
+
+    type CurrentNetwork = MainnetV0;
+
+    /// Returns a scan request with the given view key and tags.
+    fn request(
+        view_key: Option<ViewKey<CurrentNetwork>>,
+        tags: Vec<Field<CurrentNetwork>>,
+    ) -> RecordScanRequest<CurrentNetwork> {
+        RecordScanRequest { view_key, tags, start: None, end: None, limit: None }
+    }
+
+    #[test]
+    fn test_check_request() {
+        let rng = &mut TestRng::default();
+        let view_key = ViewKey::try_from(PrivateKey::<CurrentNetwork>::new(rng).unwrap()).unwrap();
+        let tag = Field::from_u64(1);
+
+        // Check that exactly one of the view key or the tags is given.
+        assert!(request(Some(view_key), vec![]).check().is_ok());
+        assert!(request(None, vec![tag]).check().is_ok());
+        assert!(request(None, vec![]).check().is_err());
+        assert!(request(Some(view_key), vec![tag]).check().is_err());
+        assert!(request(None, vec![tag; MAX_SCAN_TAGS + 1]).check().is_err());
+
+        // Check the page limit and the cursor.
+        let mut scan = request(Some(view_key), vec![]);
+        scan.limit = Some(MAX_PAGE_LIMIT + 1);
+        assert!(scan.check().is_err());
+        scan.limit = Some(MAX_PAGE_LIMIT);
+        scan.start = Some("10.2".to_string());
+        assert!(scan.check().is_ok());
+        assert_eq!(scan.cursor().unwrap(), Cursor { height: 10, index: 2 });
+        scan.start = Some("invalid".to_string());
+        assert!(scan.check().is_err());
+    }
+}
@@ -0,0 +1,58 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use axum::{
+    body::{Body, HttpBody},
+    extract::MatchedPath,
+    http::{header::CONTENT_LENGTH, HeaderMap, Request},
+    middleware::Next,
+    response::Response,
+};
+use std::time::Instant;
+
+/// The route label of the requests that do not match any route.
+const UNMATCHED_ROUTE: &str = "unmatched";
+
+/// Records the latency, status code, and payload sizes of each request, per route.
+pub(crate) async fn metrics_middleware(request: Request<Body>, next: Next) -> Response {
+    let start = Instant::now();
+    let method = request.method().to_string();
+    // Label the request by its route (e.g. `/mainnet/block/:height_or_hash`) instead of its path,
+    // so the number of label values is bounded.
+    let route = request.extensions().get::<MatchedPath>().map_or(UNMATCHED_ROUTE, |path| path.as_str()).to_string();
+    let request_size = content_length(request.headers());
+
+    let response = next.run(request).await;
+
+    let status = response.status().as_u16().to_string();
+    let response_size = content_length(response.headers()).or_else(|| response.body().size_hint().exact());
+
+    let route_labels = [("method", method), ("route", route)];
+    metrics::histogram_with_labels(metrics::rest::REQUEST_LATENCY, &route_labels, start.elapsed().as_secs_f64());
+    if let Some(size) = request_size {
+        metrics::histogram_with_labels(metrics::rest::REQUEST_SIZE, &route_labels, size as f64);
+    }
+    if let Some(size) = response_size {
+        metrics::histogram_with_labels(metrics::rest::RESPONSE_SIZE, &route_labels, size as f64);
+    }
+    let [method, route] = route_labels;
+    metrics::increment_counter_with_labels(metrics::rest::REQUESTS, &[method, route, ("status", status)]);
+
+    response
+}
+
+/// Returns the value of the `Content-Length` header, if it is set.
+fn content_length(headers: &HeaderMap) -> Option<u64> {
+    headers.get(CONTENT_LENGTH)?.to_str().ok()?.parse().ok()
+}
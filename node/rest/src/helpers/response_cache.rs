@@ -0,0 +1,212 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use axum::{
+    body::{to_bytes, Body, Bytes},
+    extract::{MatchedPath, State},
+    http::{
+        header::{CACHE_CONTROL, CONTENT_TYPE, ETAG, IF_NONE_MATCH},
+        HeaderValue,
+        Method,
+        Request,
+        StatusCode,
+    },
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use lru::LruCache;
+use parking_lot::Mutex;
+use std::{
+    collections::hash_map::DefaultHasher,
+    hash::{Hash, Hasher},
+    num::NonZeroUsize,
+    sync::Arc,
+};
+
+/// The maximum size of a cached response body, in bytes.
+const MAX_CACHED_RESPONSE_SIZE: usize = 4 * 1024 * 1024;
+/// The routes serving immutable objects, whose successful responses never change.
+const IMMUTABLE_ROUTES: [&str; 7] = [
+    "/mainnet/block/:height_or_hash",
+    "/mainnet/block/:height_or_hash/transactions",
+    "/mainnet/block/:height_or_hash/dependency-graph",
+    "/mainnet/transaction/:id",
+    "/mainnet/transaction/confirmed/:id",
+    "/mainnet/program/:id",
+    "/mainnet/program/:id/description",
+];
+
+/// A cached response.
+#[derive(Clone, Debug)]
+struct CachedResponse {
+    /// The response body.
+    body: Bytes,
+    /// The content type of the response, if any.
+    content_type: Option<HeaderValue>,
+    /// The entity tag of the response, derived from its body.
+    etag: HeaderValue,
+}
+
+impl CachedResponse {
+    /// Initializes a new cached response, computing its entity tag.
+    fn new(body: Bytes, content_type: Option<HeaderValue>) -> Self {
+        let mut hasher = DefaultHasher::new();
+        body.hash(&mut hasher);
+        let etag = HeaderValue::from_str(&format!("\"{:016x}\"", hasher.finish())).expect("The entity tag is valid");
+        Self { body, content_type, etag }
+    }
+
+    /// Returns `true` if the given `If-None-Match` header matches the entity tag of the response.
+    fn matches(&self, if_none_match: Option<&HeaderValue>) -> bool {
+        let Some(Ok(if_none_match)) = if_none_match.map(|value| value.to_str()) else { return false };
+        let etag = self.etag.to_str().unwrap_or_default();
+        if_none_match.split(',').map(str::trim).any(|tag| tag == "*" || tag.trim_start_matches("W/") == etag)
+    }
+
+    /// Returns the response, or an empty `304 Not Modified` response if the client has it already.
+    fn into_response(self, if_none_match: Option<&HeaderValue>) -> Response {
+        let immutable = HeaderValue::from_static("public, max-age=31536000, immutable");
+        if self.matches(if_none_match) {
+            return (StatusCode::NOT_MODIFIED, [(ETAG, self.etag), (CACHE_CONTROL, immutable)]).into_response();
+        }
+        let mut response = (StatusCode::OK, [(ETAG, self.etag), (CACHE_CONTROL, immutable)], self.body).into_response();
+        if let Some(content_type) = self.content_type {
+            response.headers_mut().insert(CONTENT_TYPE, content_type);
+        }
+        response
+    }
+}
+
+/// An in-process LRU cache of the responses of the routes serving immutable objects (e.g. historical blocks),
+/// so repeated requests neither read the ledger nor serialize the objects again.
+#[derive(Debug)]
+pub struct ResponseCache {
+    /// The cached responses, by request URI.
+    responses: Mutex<LruCache<String, CachedResponse>>,
+}
+
+impl ResponseCache {
+    /// Initializes a new response cache with the given capacity.
+    pub fn new(capacity: NonZeroUsize) -> Self {
+        Self { responses: Mutex::new(LruCache::new(capacity)) }
+    }
+
+    /// Returns the number of cached responses.
+    pub fn len(&self) -> usize {
+        self.responses.lock().len()
+    }
+
+    /// Returns `true` if there are no cached responses.
+    pub fn is_empty(&self) -> bool {
+        self.responses.lock().is_empty()
+    }
+}
+
+/// Serves the responses of the immutable routes from the cache, and sets their `ETag` header.
+pub(crate) async fn response_cache_middleware(
+    State(cache): State<Option<Arc<ResponseCache>>>,
+    request: Request<Body>,
+    next: Next,
+) -> Response {
+    // Only cache the GET requests to the immutable routes.
+    let is_immutable =
+        request.extensions().get::<MatchedPath>().map_or(false, |path| IMMUTABLE_ROUTES.contains(&path.as_str()));
+    let cache = match cache {
+        Some(cache) if is_immutable && request.method() == Method::GET => cache,
+        _ => return next.run(request).await,
+    };
+    let key = request.uri().to_string();
+    let if_none_match = request.headers().get(IF_NONE_MATCH).cloned();
+
+    // Return the cached response, if any.
+    if let Some(cached) = cache.responses.lock().get(&key).cloned() {
+        return cached.into_response(if_none_match.as_ref());
+    }
+
+    // Otherwise, cache the successful response, unless it is too large.
+    let response = next.run(request).await;
+    if response.status() != StatusCode::OK {
+        return response;
+    }
+    let (parts, body) = response.into_parts();
+    let body = match to_bytes(body, usize::MAX).await {
+        Ok(body) => body,
+        Err(error) => return (StatusCode::INTERNAL_SERVER_ERROR, error.to_string()).into_response(),
+    };
+    if body.len() > MAX_CACHED_RESPONSE_SIZE {
+        return Response::from_parts(parts, Body::from(body));
+    }
+    let cached = CachedResponse::new(body, parts.headers.get(CONTENT_TYPE).cloned());
+    cache.responses.lock().put(key, cached.clone());
+    cached.into_response(if_none_match.as_ref())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{middleware, routing::get, Router};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use tower::ServiceExt;
+
+    /// Returns a router with the given cache, and a block route counting the number of times it is called.
+    fn router(cache: Arc<ResponseCache>, calls: Arc<AtomicUsize>) -> Router {
+        let handler = move || async move { format!("block {}", calls.fetch_add(1, Ordering::SeqCst)) };
+        Router::new()
+            .route("/mainnet/block/:height_or_hash", get(handler.clone()))
+            .route("/mainnet/block/latest", get(handler))
+            .layer(middleware::from_fn_with_state(Some(cache), response_cache_middleware))
+    }
+
+    /// Sends a request to the given URI, returning the status code, the `ETag` header and the body.
+    async fn send(router: Router, uri: &str, if_none_match: Option<&str>) -> (StatusCode, Option<String>, String) {
+        let mut request = Request::get(uri);
+        if let Some(etag) = if_none_match {
+            request = request.header(IF_NONE_MATCH, etag);
+        }
+        let response = router.oneshot(request.body(Body::empty()).unwrap()).await.unwrap();
+        let status = response.status();
+        let etag = response.headers().get(ETAG).map(|etag| etag.to_str().unwrap().to_string());
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (status, etag, String::from_utf8(body.to_vec()).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_response_cache() {
+        let cache = Arc::new(ResponseCache::new(NonZeroUsize::new(2).unwrap()));
+        let calls = Arc::new(AtomicUsize::new(0));
+        let router = router(cache.clone(), calls.clone());
+
+        // Check the response of an immutable route is cached.
+        let (status, etag, body) = send(router.clone(), "/mainnet/block/1", None).await;
+        assert_eq!((status, body.as_str()), (StatusCode::OK, "block 0"));
+        assert_eq!(send(router.clone(), "/mainnet/block/1", None).await, (StatusCode::OK, etag.clone(), body));
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+        assert_eq!(cache.len(), 1);
+
+        // Check a matching entity tag returns an empty response.
+        let (status, _, body) = send(router.clone(), "/mainnet/block/1", etag.as_deref()).await;
+        assert_eq!((status, body.as_str()), (StatusCode::NOT_MODIFIED, ""));
+
+        // Check the responses of the other routes are not cached.
+        send(router.clone(), "/mainnet/block/latest", None).await;
+        send(router.clone(), "/mainnet/block/latest", None).await;
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+
+        // Check the least recently used response is evicted.
+        send(router.clone(), "/mainnet/block/2", None).await;
+        send(router.clone(), "/mainnet/block/3", None).await;
+        assert_eq!(cache.len(), 2);
+        assert_eq!(send(router, "/mainnet/block/1", None).await.2, "block 5");
+    }
+}
@@ -0,0 +1,69 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+use tokio::sync::watch;
+
+/// A signal for the REST servers to stop accepting new connections and drain the in-flight requests.
+#[derive(Clone, Debug)]
+pub(crate) struct ShutdownSignal {
+    /// The sender of the signal, set to `true` once the shutdown is triggered.
+    sender: Arc<watch::Sender<bool>>,
+}
+
+impl Default for ShutdownSignal {
+    /// Initializes a new, untriggered shutdown signal.
+    fn default() -> Self {
+        Self { sender: Arc::new(watch::channel(false).0) }
+    }
+}
+
+impl ShutdownSignal {
+    /// Triggers the shutdown.
+    pub(crate) fn trigger(&self) {
+        self.sender.send_replace(true);
+    }
+
+    /// Waits until the shutdown is triggered.
+    pub(crate) async fn wait(&self) {
+        let mut receiver = self.sender.subscribe();
+        // Note: The sender is held by `self`, so the channel cannot be closed while waiting.
+        let _ = receiver.wait_for(|triggered| *triggered).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_shutdown_signal() {
+        let signal = ShutdownSignal::default();
+        // Check that waiting blocks until the shutdown is triggered.
+        let waiter = tokio::spawn({
+            let signal = signal.clone();
+            async move { signal.wait().await }
+        });
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        assert!(!waiter.is_finished());
+
+        signal.trigger();
+        tokio::time::timeout(Duration::from_secs(1), waiter).await.unwrap().unwrap();
+
+        // Check that waiting returns immediately once the shutdown is triggered.
+        tokio::time::timeout(Duration::from_secs(1), signal.wait()).await.unwrap();
+    }
+}
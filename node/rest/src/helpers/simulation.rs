@@ -0,0 +1,147 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::transaction_status;
+use snarkvm::{
+    prelude::{
+        block::Transaction,
+        deployment_cost,
+        execution_cost,
+        store::ConsensusStorage,
+        Field,
+        Ledger,
+        Network,
+    },
+    synthesizer::program::{FinalizeGlobalState, FinalizeOperation},
+};
+
+use anyhow::{bail, Result};
+use serde::Serialize;
+
+/// The would-be outcome of a transaction, speculatively executed against the latest ledger state.
+#[derive(Clone, Debug, Serialize)]
+#[serde(bound = "")]
+pub struct Simulation<N: Network> {
+    /// The transaction ID.
+    pub transaction_id: N::TransactionID,
+    /// The would-be status of the transaction (e.g. "accepted_execute", "aborted" or "invalid").
+    pub status: String,
+    /// The reason the transaction is invalid or aborted, if any.
+    pub error: Option<String>,
+    /// The would-be finalize operations of the transaction.
+    pub finalize: Vec<FinalizeOperation<N>>,
+    /// The records consumed by the transaction.
+    pub consumed: Vec<ConsumedRecord<N>>,
+    /// The commitments of the records produced by the transaction.
+    pub produced: Vec<Field<N>>,
+    /// The estimated cost of the transaction.
+    pub cost: SimulationCost,
+}
+
+/// A record consumed by a simulated transaction.
+#[derive(Clone, Debug, Serialize)]
+#[serde(bound = "")]
+pub struct ConsumedRecord<N: Network> {
+    /// The serial number of the record.
+    pub serial_number: Field<N>,
+    /// Whether the record is already spent in the ledger.
+    pub spent: bool,
+}
+
+/// The estimated cost of a simulated transaction, in microcredits.
+#[derive(Copy, Clone, Debug, Serialize)]
+pub struct SimulationCost {
+    /// The storage cost.
+    pub storage: u64,
+    /// The finalize cost (or, for deployments, the namespace and synthesis cost).
+    pub finalize: u64,
+    /// The minimum base fee, i.e. the storage and finalize costs.
+    pub minimum_base_fee: u64,
+    /// The base fee paid by the transaction.
+    pub base_fee: u64,
+    /// The priority fee paid by the transaction.
+    pub priority_fee: u64,
+}
+
+/// Speculatively executes the given transaction against the latest ledger state, without committing
+/// or broadcasting it, and returns its would-be outcome.
+pub fn simulate<N: Network, C: ConsensusStorage<N>>(
+    ledger: &Ledger<N, C>,
+    transaction: &Transaction<N>,
+) -> Result<Simulation<N>> {
+    // Estimate the cost of the transaction.
+    let (storage, finalize) = match transaction {
+        Transaction::Deploy(_, _, deployment, _) => {
+            let (_, (storage, namespace, synthesis)) = deployment_cost(deployment)?;
+            (storage, namespace.saturating_add(synthesis))
+        }
+        Transaction::Execute(_, execution, _) => execution_cost(ledger.vm(), execution)?.1,
+        Transaction::Fee(..) => bail!("Fee transactions cannot be simulated"),
+    };
+    let cost = SimulationCost {
+        storage,
+        finalize,
+        minimum_base_fee: storage.saturating_add(finalize),
+        base_fee: *transaction.base_fee_amount()?,
+        priority_fee: *transaction.priority_fee_amount()?,
+    };
+
+    // Determine the records consumed and produced by the transaction.
+    let consumed = transaction
+        .serial_numbers()
+        .map(|serial_number| {
+            Ok(ConsumedRecord { serial_number: *serial_number, spent: ledger.contains_serial_number(serial_number)? })
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let produced = transaction.commitments().copied().collect();
+
+    let mut simulation = Simulation {
+        transaction_id: transaction.id(),
+        status: "invalid".to_string(),
+        error: None,
+        finalize: vec![],
+        consumed,
+        produced,
+        cost,
+    };
+
+    // Verify the transaction.
+    if let Err(error) = ledger.check_transaction_basic(transaction, None, &mut rand::thread_rng()) {
+        simulation.error = Some(error.to_string());
+        return Ok(simulation);
+    }
+
+    // Speculatively execute the transaction in the next block, without committing the finalize operations.
+    let block = ledger.latest_block();
+    let state = FinalizeGlobalState::new::<N>(
+        block.round().saturating_add(1),
+        block.height().saturating_add(1),
+        block.cumulative_weight(),
+        block.cumulative_proof_target(),
+        block.hash(),
+    )?;
+    let (_, transactions, _, _) = ledger.vm().speculate(state, None, vec![], None, std::iter::once(transaction))?;
+
+    match transactions.iter().next() {
+        Some(confirmed) => {
+            simulation.status = transaction_status(confirmed).to_string();
+            simulation.finalize = confirmed.finalize_operations().to_vec();
+        }
+        None => {
+            simulation.status = "aborted".to_string();
+            simulation.error = Some("The transaction would be aborted from the block".to_string());
+        }
+    }
+    Ok(simulation)
+}
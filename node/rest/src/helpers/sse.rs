@@ -0,0 +1,201 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::{block_stream, transaction_status};
+use snarkvm::prelude::{block::Block, store::ConsensusStorage, Ledger, Network};
+
+use anyhow::{anyhow, bail, Result};
+use axum::response::{
+    sse::{Event, KeepAlive, Sse},
+    IntoResponse,
+    Response,
+};
+use futures_util::stream;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::{collections::VecDeque, convert::Infallible, fmt, str::FromStr, time::Duration};
+
+/// The header sent by a reconnecting SSE client, with the ID of the last event it received.
+pub const LAST_EVENT_ID_HEADER: &str = "last-event-id";
+/// The delay the SSE client is asked to wait for, before reconnecting.
+const SSE_RETRY_DELAY: Duration = Duration::from_secs(3);
+
+/// The query object for the SSE endpoint.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct SseQuery {
+    /// The block height to start streaming from (inclusive); if `None`, only new blocks are streamed.
+    /// Note: The `Last-Event-ID` header of a reconnecting client takes precedence.
+    pub from_height: Option<u32>,
+    /// The comma-separated topics to stream (`blocks` and/or `transactions`); if `None`, all topics are streamed.
+    pub topics: Option<String>,
+}
+
+/// The topics streamed to an SSE client.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct SseTopics {
+    /// Whether the block headers are streamed.
+    pub blocks: bool,
+    /// Whether the transaction confirmations are streamed.
+    pub transactions: bool,
+}
+
+/// The ID of an SSE event, used by reconnecting clients to resume the stream after the last event they received.
+///
+/// A block event has the ID `{height}`, and a transaction event has the ID `{height}-{index}`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct SseEventId {
+    /// The block height.
+    pub height: u32,
+    /// The index of the transaction in the block, for transaction events.
+    pub index: Option<u32>,
+}
+
+impl SseTopics {
+    /// Parses the given comma-separated topics, defaulting to all topics.
+    pub fn new(topics: Option<&str>) -> Result<Self> {
+        let Some(topics) = topics else { return Ok(Self { blocks: true, transactions: true }) };
+        let mut selected = Self { blocks: false, transactions: false };
+        for topic in topics.split(',').map(str::trim).filter(|topic| !topic.is_empty()) {
+            match topic {
+                "blocks" => selected.blocks = true,
+                "transactions" => selected.transactions = true,
+                _ => bail!("Invalid SSE topic '{topic}' (expected 'blocks' or 'transactions')"),
+            }
+        }
+        match selected.blocks || selected.transactions {
+            true => Ok(selected),
+            false => bail!("At least one SSE topic must be given"),
+        }
+    }
+}
+
+impl FromStr for SseEventId {
+    type Err = anyhow::Error;
+
+    fn from_str(id: &str) -> Result<Self> {
+        let parse = |value: &str| value.parse::<u32>().map_err(|_| anyhow!("Invalid SSE event ID '{id}'"));
+        match id.split_once('-') {
+            Some((height, index)) => Ok(Self { height: parse(height)?, index: Some(parse(index)?) }),
+            None => Ok(Self { height: parse(id)?, index: None }),
+        }
+    }
+}
+
+impl fmt::Display for SseEventId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.index {
+            Some(index) => write!(f, "{}-{index}", self.height),
+            None => write!(f, "{}", self.height),
+        }
+    }
+}
+
+/// Returns the SSE response streaming the block headers and transaction confirmations of the ledger.
+///
+/// If `last_event_id` is given, the stream resumes right after that event; otherwise, it starts at `from_height`.
+pub fn sse_response<N: Network, C: ConsensusStorage<N>>(
+    ledger: Ledger<N, C>,
+    from_height: Option<u32>,
+    topics: SseTopics,
+    last_event_id: Option<SseEventId>,
+) -> Response {
+    let blocks = block_stream(ledger, last_event_id.map(|id| id.height).or(from_height));
+    // Ask the client to reconnect after a short delay, if the connection drops.
+    let pending = VecDeque::from([Event::default().retry(SSE_RETRY_DELAY)]);
+
+    let events =
+        stream::unfold((blocks, last_event_id, pending), move |(mut blocks, mut resume, mut pending)| async move {
+            loop {
+                // Send the pending events first.
+                if let Some(event) = pending.pop_front() {
+                    return Some((Ok::<_, Infallible>(event), (blocks, resume, pending)));
+                }
+                match blocks.recv().await {
+                    Some(Ok(block)) => pending.extend(block_events(&block, topics, resume.take())),
+                    // Note: The block stream ends after an error, which ends the SSE stream once the error is sent.
+                    Some(Err(error)) => pending.push_back(Event::default().event("error").data(error.to_string())),
+                    None => return None,
+                }
+            }
+        });
+
+    Sse::new(events).keep_alive(KeepAlive::default()).into_response()
+}
+
+/// Returns the events of the given block, skipping the events up to (and including) `resume_after`, if given.
+fn block_events<N: Network>(block: &Block<N>, topics: SseTopics, resume_after: Option<SseEventId>) -> Vec<Event> {
+    let height = block.height();
+    let mut events = Vec::new();
+
+    // Add the block header event, unless the client already received it.
+    if topics.blocks && resume_after.is_none() {
+        let data = json!({ "height": height, "hash": block.hash(), "header": block.header() });
+        events.extend(sse_event("block", SseEventId { height, index: None }, data));
+    }
+    // Add the transaction confirmation events, after the last one the client received.
+    if topics.transactions {
+        let after = resume_after.and_then(|id| id.index);
+        for confirmed in block.transactions().iter().filter(|confirmed| after.map_or(true, |i| confirmed.index() > i)) {
+            let data = json!({
+                "height": height,
+                "block_hash": block.hash(),
+                "index": confirmed.index(),
+                "id": confirmed.transaction().id(),
+                "status": transaction_status(confirmed),
+            });
+            events.extend(sse_event("transaction", SseEventId { height, index: Some(confirmed.index()) }, data));
+        }
+    }
+    events
+}
+
+/// Returns the SSE event with the given type, ID, and JSON data.
+fn sse_event(event: &str, id: SseEventId, data: serde_json::Value) -> Option<Event> {
+    match Event::default().event(event).id(id.to_string()).json_data(data) {
+        Ok(event) => Some(event),
+        Err(error) => {
+            warn!("Failed to serialize an SSE event - {error}");
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sse_topics() {
+        assert_eq!(SseTopics::new(None).unwrap(), SseTopics { blocks: true, transactions: true });
+        assert_eq!(SseTopics::new(Some("blocks")).unwrap(), SseTopics { blocks: true, transactions: false });
+        assert_eq!(
+            SseTopics::new(Some(" transactions, blocks ")).unwrap(),
+            SseTopics { blocks: true, transactions: true }
+        );
+        assert!(SseTopics::new(Some("mempool")).is_err());
+        assert!(SseTopics::new(Some("")).is_err());
+    }
+
+    #[test]
+    fn test_sse_event_id() {
+        for id in [SseEventId { height: 42, index: None }, SseEventId { height: 42, index: Some(7) }] {
+            assert_eq!(id.to_string().parse::<SseEventId>().unwrap(), id);
+        }
+        assert_eq!("42".parse::<SseEventId>().unwrap(), SseEventId { height: 42, index: None });
+        assert_eq!("42-7".parse::<SseEventId>().unwrap(), SseEventId { height: 42, index: Some(7) });
+        assert!("".parse::<SseEventId>().is_err());
+        assert!("42-".parse::<SseEventId>().is_err());
+        assert!("block-7".parse::<SseEventId>().is_err());
+    }
+}
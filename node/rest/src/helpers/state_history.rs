@@ -0,0 +1,236 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::LedgerIndex;
+use snarkvm::prelude::{store::ConsensusStorage, Identifier, Ledger, Network, Plaintext, ProgramID, Value};
+
+use anyhow::{anyhow, bail, ensure, Result};
+use indexmap::IndexMap;
+use parking_lot::RwLock;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    str::FromStr,
+};
+
+/// A program mapping, i.e. a `(program ID, mapping name)` pair.
+type Mapping<N> = (ProgramID<N>, Identifier<N>);
+
+/// An index of the past values of the tracked program mappings, to query them as of a past block.
+///
+/// The index snapshots the tracked mappings whenever a new block is added to the ledger, and records the keys
+/// that changed since the previous snapshot. It starts at the height the node was started at, and only answers
+/// for the heights that were snapshotted exactly: if more than one block was added between two snapshots, the
+/// values as of the blocks in between are unknown, and querying them fails.
+pub struct StateHistory<N: Network> {
+    /// The tracked mappings.
+    mappings: Vec<Mapping<N>>,
+    /// The snapshots of the tracked mappings.
+    snapshots: RwLock<Snapshots<N>>,
+}
+
+/// The snapshots of the tracked mappings.
+struct Snapshots<N: Network> {
+    /// The height of the first snapshot, if any.
+    start_height: Option<u32>,
+    /// The height of the latest snapshot.
+    indexed_height: u32,
+    /// The heights that were snapshotted.
+    heights: BTreeSet<u32>,
+    /// The latest values of the tracked mappings.
+    latest: IndexMap<Mapping<N>, IndexMap<Plaintext<N>, Value<N>>>,
+    /// The values of every key of the tracked mappings, by the height they were set at (`None` if removed).
+    history: IndexMap<(Mapping<N>, Plaintext<N>), BTreeMap<u32, Option<Value<N>>>>,
+}
+
+impl<N: Network> Default for Snapshots<N> {
+    fn default() -> Self {
+        Self {
+            start_height: None,
+            indexed_height: 0,
+            heights: Default::default(),
+            latest: Default::default(),
+            history: Default::default(),
+        }
+    }
+}
+
+impl<N: Network> StateHistory<N> {
+    /// Initializes a new state history, tracking the given `program/mapping` pairs (e.g. `credits.aleo/account`).
+    pub fn new(mappings: &[String]) -> Result<Self> {
+        let mappings = mappings
+            .iter()
+            .map(|mapping| {
+                let (program, name) = mapping
+                    .split_once('/')
+                    .ok_or_else(|| anyhow!("Invalid mapping '{mapping}' (expected program/mapping)"))?;
+                Ok((ProgramID::from_str(program)?, Identifier::from_str(name)?))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Self { mappings, snapshots: Default::default() })
+    }
+
+    /// Returns `true` if the given mapping is tracked.
+    pub fn is_tracked(&self, program_id: &ProgramID<N>, mapping_name: &Identifier<N>) -> bool {
+        self.mappings.iter().any(|(program, name)| program == program_id && name == mapping_name)
+    }
+
+    /// Returns the value of the given key in the given mapping, as of the given block height.
+    pub fn get(
+        &self,
+        program_id: ProgramID<N>,
+        mapping_name: Identifier<N>,
+        key: Plaintext<N>,
+        height: u32,
+    ) -> Result<Option<Value<N>>> {
+        ensure!(
+            self.is_tracked(&program_id, &mapping_name),
+            "The mapping '{program_id}/{mapping_name}' is not tracked"
+        );
+        let snapshots = self.snapshots.read();
+        let Some(start_height) = snapshots.start_height else {
+            bail!("The state history is not indexed yet");
+        };
+        ensure!(height >= start_height, "The state history starts at block {start_height}");
+        ensure!(
+            height <= snapshots.indexed_height,
+            "The state history is indexed up to block {}",
+            snapshots.indexed_height
+        );
+        ensure!(snapshots.heights.contains(&height), "The state history did not snapshot block {height}");
+
+        Ok(snapshots
+            .history
+            .get(&((program_id, mapping_name), key))
+            .and_then(|values| values.range(..=height).next_back())
+            .and_then(|(_, value)| value.clone()))
+    }
+
+    /// Snapshots the tracked mappings at the latest block in the ledger, if it was not indexed yet.
+    ///
+    /// Note: If a block is added to the ledger while the mappings are read, the snapshot is discarded,
+    /// as its values may belong to either block; the next snapshot is taken at the new block instead.
+    pub fn snapshot<C: ConsensusStorage<N>>(&self, ledger: &Ledger<N, C>) -> Result<()> {
+        // Note: The latest height waits for a block that is being added to the ledger.
+        let height = ledger.latest_height();
+        {
+            let snapshots = self.snapshots.read();
+            if snapshots.start_height.is_some() && height <= snapshots.indexed_height {
+                return Ok(());
+            }
+        }
+        // Read the tracked mappings before acquiring the lock, as this may be slow.
+        let mut entries = Vec::with_capacity(self.mappings.len());
+        for (program_id, mapping_name) in &self.mappings {
+            entries.push(ledger.vm().finalize_store().get_mapping_confirmed(*program_id, *mapping_name)?);
+        }
+        // Ensure no block was added while reading the mappings.
+        if ledger.latest_height() != height {
+            debug!("Discarded the state history snapshot at block {height}, as a new block was added");
+            return Ok(());
+        }
+
+        let mut snapshots = self.snapshots.write();
+        for (mapping, entries) in self.mappings.iter().zip(entries) {
+            snapshots.record(height, *mapping, entries.into_iter().collect());
+        }
+        snapshots.start_height.get_or_insert(height);
+        snapshots.indexed_height = height;
+        snapshots.heights.insert(height);
+        Ok(())
+    }
+}
+
+impl<N: Network, C: ConsensusStorage<N>> LedgerIndex<N, C> for StateHistory<N> {
+    /// Returns the name of the index, for the logs.
+    fn name(&self) -> &'static str {
+        "state history"
+    }
+
+    /// Snapshots the tracked mappings at the latest block in the ledger.
+    fn update(&self, ledger: &Ledger<N, C>) -> Result<()> {
+        self.snapshot(ledger)
+    }
+}
+
+impl<N: Network> Snapshots<N> {
+    /// Records the changes of the given mapping since the previous snapshot, at the given height.
+    fn record(&mut self, height: u32, mapping: Mapping<N>, entries: IndexMap<Plaintext<N>, Value<N>>) {
+        let previous = self.latest.insert(mapping, entries).unwrap_or_default();
+        let entries = &self.latest[&mapping];
+        // Record the inserted and updated keys.
+        for (key, value) in entries {
+            if previous.get(key) != Some(value) {
+                self.history.entry((mapping, key.clone())).or_default().insert(height, Some(value.clone()));
+            }
+        }
+        // Record the removed keys.
+        for key in previous.keys().filter(|key| !entries.contains_key(*key)) {
+            self.history.entry((mapping, key.clone())).or_default().insert(height, None);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkvm::prelude::MainnetV0;
+
+    type CurrentNetwork = MainnetV0;
+
+    #[test]
+    fn test_state_history() {
+        let history = StateHistory::<CurrentNetwork>::new(&["credits.aleo/account".to_string()]).unwrap();
+        let (program_id, mapping_name) = history.mappings[0];
+        let key = |s: &str| Plaintext::<CurrentNetwork>::from_str(s).unwrap();
+        let value = |s: &str| Value::<CurrentNetwork>::from_str(s).unwrap();
+        let entries =
+            |pairs: &[(&str, &str)]| pairs.iter().map(|&(k, v)| (key(k), value(v))).collect::<IndexMap<_, _>>();
+
+        // Record three snapshots.
+        {
+            let mut snapshots = history.snapshots.write();
+            snapshots.record(10, (program_id, mapping_name), entries(&[("1u32", "5u64"), ("2u32", "7u64")]));
+            snapshots.record(12, (program_id, mapping_name), entries(&[("1u32", "6u64"), ("2u32", "7u64")]));
+            snapshots.record(15, (program_id, mapping_name), entries(&[("2u32", "7u64"), ("3u32", "1u64")]));
+            snapshots.start_height = Some(10);
+            snapshots.indexed_height = 15;
+            snapshots.heights.extend([10, 12, 15]);
+        }
+        let get = |k: &str, height: u32| history.get(program_id, mapping_name, key(k), height).unwrap();
+
+        // Check the values as of each snapshotted height.
+        assert_eq!(get("1u32", 10), Some(value("5u64")));
+        assert_eq!(get("1u32", 12), Some(value("6u64")));
+        assert_eq!(get("1u32", 15), None);
+        assert_eq!(get("2u32", 15), Some(value("7u64")));
+        assert_eq!(get("3u32", 12), None);
+        assert_eq!(get("3u32", 15), Some(value("1u64")));
+
+        // Check the heights between the snapshots are rejected, as their values are unknown.
+        assert!(history.get(program_id, mapping_name, key("1u32"), 11).is_err());
+        assert!(history.get(program_id, mapping_name, key("3u32"), 14).is_err());
+
+        // Check the heights outside of the index, and the untracked mappings, are rejected.
+        assert!(history.get(program_id, mapping_name, key("1u32"), 9).is_err());
+        assert!(history.get(program_id, mapping_name, key("1u32"), 16).is_err());
+        let other = Identifier::from_str("committee").unwrap();
+        assert!(history.get(program_id, other, key("1u32"), 12).is_err());
+    }
+
+    #[test]
+    fn test_state_history_invalid_mapping() {
+        assert!(StateHistory::<CurrentNetwork>::new(&["credits.aleo".to_string()]).is_err());
+        assert!(StateHistory::<CurrentNetwork>::new(&["credits/account".to_string()]).is_err());
+    }
+}
@@ -0,0 +1,92 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use snarkvm::prelude::{block::Block, store::ConsensusStorage, Ledger, Network};
+
+use anyhow::Result;
+use std::time::Duration;
+use tokio::sync::mpsc;
+
+/// The maximum number of blocks buffered for a streaming client, before the stream waits for the client.
+const STREAM_BUFFER_SIZE: usize = 16;
+/// The interval at which the stream checks the ledger for new blocks, once the client has caught up.
+const STREAM_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Returns a stream of the blocks in the ledger starting at the given height.
+///
+/// The blocks already in the ledger are backfilled first, after which new blocks are streamed as they are
+/// added to the ledger. The stream is bounded, so blocks are only read from storage as fast as the client
+/// consumes them. The stream ends when the receiver is dropped, or after a block fails to load.
+pub fn block_stream<N: Network, C: ConsensusStorage<N>>(
+    ledger: Ledger<N, C>,
+    from_height: Option<u32>,
+) -> mpsc::Receiver<Result<Block<N>>> {
+    let (sender, receiver) = mpsc::channel(STREAM_BUFFER_SIZE);
+
+    tokio::spawn(async move {
+        // If no start height is given, start from the next block.
+        let mut next_height = from_height.unwrap_or_else(|| ledger.latest_height() + 1);
+
+        loop {
+            // If the client has caught up, wait for the next block.
+            if next_height > ledger.latest_height() {
+                tokio::time::sleep(STREAM_POLL_INTERVAL).await;
+                // If the client disconnected, stop streaming.
+                if sender.is_closed() {
+                    break;
+                }
+                continue;
+            }
+
+            // Load the next block.
+            let block = ledger.get_block(next_height);
+            let is_error = block.is_err();
+            // Send the block, waiting if the client is behind; stop streaming if the client disconnected.
+            if sender.send(block).await.is_err() || is_error {
+                break;
+            }
+            next_height += 1;
+        }
+        trace!("Stopped streaming blocks at height {next_height}");
+    });
+
+    receiver
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkvm::prelude::{store::helpers::memory::ConsensusMemory, FromBytes, MainnetV0};
+
+    use aleo_std::StorageMode;
+
+    type CurrentNetwork = MainnetV0;
+
+    #[tokio::test]
+    async fn test_block_stream() {
+        // Initialize a ledger at the genesis block.
+        let genesis = Block::<CurrentNetwork>::read_le(CurrentNetwork::genesis_bytes()).unwrap();
+        let ledger = Ledger::<_, ConsensusMemory<_>>::load(genesis.clone(), StorageMode::Production).unwrap();
+
+        // Check that the blocks in the ledger are backfilled from the given height.
+        let mut blocks = block_stream(ledger.clone(), Some(0));
+        assert_eq!(blocks.recv().await.unwrap().unwrap(), genesis);
+        // Check that the stream then waits for new blocks.
+        assert!(tokio::time::timeout(2 * STREAM_POLL_INTERVAL, blocks.recv()).await.is_err());
+
+        // Check that only new blocks are streamed if no height is given.
+        let mut blocks = block_stream(ledger, None);
+        assert!(tokio::time::timeout(2 * STREAM_POLL_INTERVAL, blocks.recv()).await.is_err());
+    }
+}
@@ -0,0 +1,140 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::LedgerIndex;
+use snarkvm::prelude::{store::ConsensusStorage, Ledger, Network};
+
+use anyhow::Result;
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use std::ops::Range;
+
+/// The maximum number of blocks returned for a time range.
+pub const MAX_TIME_RANGE_BLOCKS: usize = 50;
+/// The number of block headers read at once while backfilling the timestamp index.
+const TIMESTAMP_INDEX_BATCH_SIZE: u32 = 1000;
+
+/// The query object for the blocks within a time range.
+#[derive(Copy, Clone, Debug, Deserialize, Serialize)]
+pub struct TimeRange {
+    /// The starting UNIX timestamp (inclusive).
+    pub start: i64,
+    /// The ending UNIX timestamp (inclusive).
+    pub end: i64,
+}
+
+/// An index of the block timestamps by height, to find the blocks nearest to a timestamp or within a time range.
+///
+/// Note: Block timestamps are non-decreasing, so the index is searched with a binary search.
+#[derive(Debug, Default)]
+pub struct TimestampIndex {
+    /// The timestamp of each block, by height.
+    timestamps: RwLock<Vec<i64>>,
+}
+
+impl TimestampIndex {
+    /// Returns the number of indexed blocks.
+    pub fn len(&self) -> usize {
+        self.timestamps.read().len()
+    }
+
+    /// Returns `true` if no blocks are indexed.
+    pub fn is_empty(&self) -> bool {
+        self.timestamps.read().is_empty()
+    }
+
+    /// Returns the height of the block nearest to the given timestamp, if any blocks are indexed.
+    /// Note: If two blocks are equally near, the earlier one is returned.
+    pub fn nearest(&self, timestamp: i64) -> Option<u32> {
+        let timestamps = self.timestamps.read();
+        // Find the first block at or after the timestamp.
+        let after = timestamps.partition_point(|t| *t < timestamp);
+        let height = match after {
+            _ if timestamps.is_empty() => return None,
+            0 => 0,
+            after if after == timestamps.len() => after - 1,
+            after => match timestamp - timestamps[after - 1] <= timestamps[after] - timestamp {
+                true => after - 1,
+                false => after,
+            },
+        };
+        Some(height as u32)
+    }
+
+    /// Returns the heights of the blocks with a timestamp within the given (inclusive) range.
+    pub fn range(&self, start: i64, end: i64) -> Range<u32> {
+        let timestamps = self.timestamps.read();
+        let start_height = timestamps.partition_point(|t| *t < start);
+        let end_height = timestamps.partition_point(|t| *t <= end).max(start_height);
+        start_height as u32..end_height as u32
+    }
+}
+
+impl<N: Network, C: ConsensusStorage<N>> LedgerIndex<N, C> for TimestampIndex {
+    /// Returns the name of the index, for the logs.
+    fn name(&self) -> &'static str {
+        "timestamp index"
+    }
+
+    /// Indexes the blocks added to the ledger since the last update.
+    fn update(&self, ledger: &Ledger<N, C>) -> Result<()> {
+        let latest_height = ledger.latest_height();
+        loop {
+            let next_height = self.len() as u32;
+            if next_height > latest_height {
+                return Ok(());
+            }
+            // Read the timestamps of the next batch before acquiring the lock, as this may be slow.
+            let end_height = latest_height.min(next_height.saturating_add(TIMESTAMP_INDEX_BATCH_SIZE - 1));
+            let timestamps = (next_height..=end_height)
+                .map(|height| Ok(ledger.get_header(height)?.timestamp()))
+                .collect::<Result<Vec<_>>>()?;
+            self.timestamps.write().extend(timestamps);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_index() -> TimestampIndex {
+        TimestampIndex { timestamps: RwLock::new(vec![0, 10, 20, 20, 35]) }
+    }
+
+    #[test]
+    fn test_nearest() {
+        assert_eq!(TimestampIndex::default().nearest(10), None);
+
+        let index = sample_index();
+        assert_eq!(index.nearest(-5), Some(0));
+        assert_eq!(index.nearest(4), Some(0));
+        assert_eq!(index.nearest(5), Some(0));
+        assert_eq!(index.nearest(6), Some(1));
+        assert_eq!(index.nearest(20), Some(2));
+        assert_eq!(index.nearest(28), Some(4));
+        assert_eq!(index.nearest(100), Some(4));
+    }
+
+    #[test]
+    fn test_range() {
+        let index = sample_index();
+        assert_eq!(index.range(0, 35), 0..5);
+        assert_eq!(index.range(10, 20), 1..4);
+        assert_eq!(index.range(11, 19), 2..2);
+        assert_eq!(index.range(21, 30), 4..4);
+        assert_eq!(index.range(36, 40), 5..5);
+        assert_eq!(index.range(20, 10), 2..2);
+    }
+}
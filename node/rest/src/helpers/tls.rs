@@ -0,0 +1,39 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use anyhow::{Context, Result};
+use axum_server::tls_rustls::RustlsConfig;
+use std::path::PathBuf;
+
+/// The TLS settings of the REST server, to serve HTTPS without a reverse proxy.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TlsConfig {
+    /// The path to the PEM-encoded certificate chain.
+    pub cert_path: PathBuf,
+    /// The path to the PEM-encoded private key.
+    pub key_path: PathBuf,
+}
+
+impl TlsConfig {
+    /// Loads the certificate chain and the private key.
+    pub(crate) async fn load(&self) -> Result<RustlsConfig> {
+        RustlsConfig::from_pem_file(&self.cert_path, &self.key_path).await.with_context(|| {
+            format!(
+                "Failed to load the REST TLS certificate '{}' and key '{}'",
+                self.cert_path.display(),
+                self.key_path.display()
+            )
+        })
+    }
+}
@@ -0,0 +1,227 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::LedgerIndex;
+use snarkvm::prelude::{
+    block::{Block, ConfirmedTransaction},
+    store::ConsensusStorage,
+    Ledger,
+    Network,
+};
+
+use anyhow::Result;
+use indexmap::IndexMap;
+use parking_lot::RwLock;
+use serde::Serialize;
+use std::sync::atomic::{AtomicU32, Ordering};
+
+/// The maximum number of submitted transactions tracked at once.
+pub const MAX_TRACKED_TRANSACTIONS: usize = 10_000;
+
+/// The status of a transaction.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum TransactionStatus {
+    /// The transaction is waiting to be included in a block.
+    Pending,
+    /// The transaction was accepted in the block at the given height.
+    Confirmed { height: u32 },
+    /// The transaction was rejected, either when it was submitted or when it was finalized in a block.
+    Rejected { reason: String, height: Option<u32> },
+    /// The transaction was aborted from the block at the given height.
+    Aborted { height: u32 },
+}
+
+impl TransactionStatus {
+    /// Returns the status of the given transaction, confirmed in the block at the given height.
+    pub fn confirmed<N: Network>(height: u32, confirmed: &ConfirmedTransaction<N>) -> Self {
+        let reason = match confirmed {
+            ConfirmedTransaction::AcceptedDeploy(..) | ConfirmedTransaction::AcceptedExecute(..) => {
+                return Self::Confirmed { height };
+            }
+            ConfirmedTransaction::RejectedDeploy(..) => "The deployment failed to finalize, and only its fee was paid",
+            ConfirmedTransaction::RejectedExecute(..) => "The execution failed to finalize, and only its fee was paid",
+        };
+        Self::Rejected { reason: reason.to_string(), height: Some(height) }
+    }
+
+    /// Returns `true` if the transaction is still pending.
+    pub fn is_pending(&self) -> bool {
+        matches!(self, Self::Pending)
+    }
+}
+
+/// A tracker of the transactions submitted to this node, following them through the memory pool and consensus.
+///
+/// Note: Only the blocks added after the node started are checked, and the oldest transactions are evicted
+/// once `MAX_TRACKED_TRANSACTIONS` is reached.
+#[derive(Debug)]
+pub struct TransactionTracker<N: Network> {
+    /// The status of each tracked transaction, in submission order.
+    transactions: RwLock<IndexMap<N::TransactionID, TransactionStatus>>,
+    /// The height of the next block to check.
+    next_height: AtomicU32,
+}
+
+impl<N: Network> Default for TransactionTracker<N> {
+    /// Initializes an empty transaction tracker.
+    fn default() -> Self {
+        Self::new(0)
+    }
+}
+
+impl<N: Network> TransactionTracker<N> {
+    /// Initializes an empty transaction tracker, which checks the blocks from the given height onwards.
+    pub fn new(next_height: u32) -> Self {
+        Self { transactions: Default::default(), next_height: AtomicU32::new(next_height) }
+    }
+
+    /// Returns the number of tracked transactions.
+    pub fn len(&self) -> usize {
+        self.transactions.read().len()
+    }
+
+    /// Returns `true` if no transactions are tracked.
+    pub fn is_empty(&self) -> bool {
+        self.transactions.read().is_empty()
+    }
+
+    /// Returns the status of the given transaction, if it is tracked.
+    pub fn get(&self, transaction_id: &N::TransactionID) -> Option<TransactionStatus> {
+        self.transactions.read().get(transaction_id).cloned()
+    }
+
+    /// Starts tracking the given transaction, as pending.
+    pub fn track(&self, transaction_id: N::TransactionID) {
+        let mut transactions = self.transactions.write();
+        if !transactions.contains_key(&transaction_id) {
+            Self::insert(&mut transactions, transaction_id, TransactionStatus::Pending);
+        }
+    }
+
+    /// Records that the given transaction was rejected when it was submitted, for the given reason.
+    pub fn reject(&self, transaction_id: N::TransactionID, reason: String) {
+        let status = TransactionStatus::Rejected { reason, height: None };
+        Self::insert(&mut self.transactions.write(), transaction_id, status);
+    }
+
+    /// Inserts the given status, evicting the oldest transaction if the tracker is full.
+    fn insert(
+        transactions: &mut IndexMap<N::TransactionID, TransactionStatus>,
+        transaction_id: N::TransactionID,
+        status: TransactionStatus,
+    ) {
+        if !transactions.contains_key(&transaction_id) && transactions.len() >= MAX_TRACKED_TRANSACTIONS {
+            transactions.shift_remove_index(0);
+        }
+        transactions.insert(transaction_id, status);
+    }
+
+    /// Updates the status of the pending transactions included in the given block.
+    pub fn process_block(&self, block: &Block<N>) -> Result<()> {
+        let mut transactions = self.transactions.write();
+        // Resolve the confirmed transactions, which are tracked by their unconfirmed ID.
+        for confirmed in block.transactions().iter() {
+            let transaction_id = confirmed.to_unconfirmed_transaction_id()?;
+            if let Some(status) = transactions.get_mut(&transaction_id) {
+                *status = TransactionStatus::confirmed(block.height(), confirmed);
+            }
+        }
+        // Resolve the aborted transactions.
+        for transaction_id in block.aborted_transaction_ids() {
+            if let Some(status) = transactions.get_mut(transaction_id) {
+                *status = TransactionStatus::Aborted { height: block.height() };
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<N: Network, C: ConsensusStorage<N>> LedgerIndex<N, C> for TransactionTracker<N> {
+    /// Returns the name of the index, for the logs.
+    fn name(&self) -> &'static str {
+        "transaction tracker"
+    }
+
+    /// Processes the blocks added to the ledger since the last update.
+    fn update(&self, ledger: &Ledger<N, C>) -> Result<()> {
+        let latest_height = ledger.latest_height();
+        while self.next_height.load(Ordering::Relaxed) <= latest_height {
+            let height = self.next_height.load(Ordering::Relaxed);
+            // Skip reading the block if no transactions are pending.
+            if self.transactions.read().values().any(TransactionStatus::is_pending) {
+                self.process_block(&ledger.get_block(height)?)?;
+            }
+            self.next_height.store(height + 1, Ordering::Relaxed);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkvm::prelude::{Field, MainnetV0, TestRng, Uniform};
+
+    type CurrentNetwork = MainnetV0;
+
+    /// Samples a random transaction ID.
+    fn sample_transaction_id(rng: &mut TestRng) -> <CurrentNetwork as Network>::TransactionID {
+        Field::rand(rng).into()
+    }
+
+    #[test]
+    fn test_track_and_reject() {
+        let rng = &mut TestRng::default();
+        let tracker = TransactionTracker::<CurrentNetwork>::default();
+        let (first, second) = (sample_transaction_id(rng), sample_transaction_id(rng));
+
+        // Check the submitted transactions are pending.
+        tracker.track(first);
+        tracker.track(second);
+        assert_eq!(tracker.get(&first), Some(TransactionStatus::Pending));
+        assert_eq!(tracker.len(), 2);
+
+        // Check a rejection is recorded, and is not overwritten by tracking the transaction again.
+        tracker.reject(second, "invalid".to_string());
+        tracker.track(second);
+        assert_eq!(
+            tracker.get(&second),
+            Some(TransactionStatus::Rejected { reason: "invalid".to_string(), height: None })
+        );
+        assert_eq!(tracker.get(&sample_transaction_id(rng)), None);
+    }
+
+    #[test]
+    fn test_eviction() {
+        let rng = &mut TestRng::default();
+        let tracker = TransactionTracker::<CurrentNetwork>::default();
+        let first = sample_transaction_id(rng);
+        tracker.track(first);
+        for _ in 0..MAX_TRACKED_TRANSACTIONS {
+            tracker.track(sample_transaction_id(rng));
+        }
+        // Check the oldest transaction is evicted once the tracker is full.
+        assert_eq!(tracker.len(), MAX_TRACKED_TRANSACTIONS);
+        assert_eq!(tracker.get(&first), None);
+    }
+
+    #[test]
+    fn test_serialize_status() {
+        let status = serde_json::to_value(TransactionStatus::Confirmed { height: 5 }).unwrap();
+        assert_eq!(status, serde_json::json!({ "status": "confirmed", "height": 5 }));
+        let status = serde_json::to_value(TransactionStatus::Pending).unwrap();
+        assert_eq!(status, serde_json::json!({ "status": "pending" }));
+    }
+}
@@ -0,0 +1,156 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::ShutdownSignal;
+
+use anyhow::{bail, Result};
+use axum::Router;
+use std::path::PathBuf;
+use tokio::task::JoinHandle;
+
+#[cfg(target_family = "unix")]
+use anyhow::Context;
+#[cfg(target_family = "unix")]
+use axum::{extract::ConnectInfo, Extension};
+#[cfg(target_family = "unix")]
+use hyper_util::{
+    rt::{TokioExecutor, TokioIo},
+    server::conn::auto::Builder,
+    service::TowerToHyperService,
+};
+#[cfg(target_family = "unix")]
+use std::{
+    net::{IpAddr, Ipv4Addr, SocketAddr},
+    os::unix::fs::FileTypeExt,
+    path::Path,
+};
+#[cfg(target_family = "unix")]
+use tokio::{net::UnixListener, task::JoinSet};
+
+/// The peer address attributed to the requests received over the Unix socket (e.g. for rate limiting).
+#[cfg(target_family = "unix")]
+const UNIX_SOCKET_PEER: SocketAddr = SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), 0);
+
+/// The Unix socket settings of the REST server, for co-located services and the CLI.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UnixSocketConfig {
+    /// The path of the Unix socket.
+    pub path: PathBuf,
+    /// Whether the server only listens on the Unix socket, instead of also listening on TCP.
+    pub exclusive: bool,
+}
+
+impl UnixSocketConfig {
+    /// Binds the Unix socket, replacing the socket file left behind by a previous run (if any),
+    /// and serves the given router on it until the shutdown is triggered.
+    #[cfg(target_family = "unix")]
+    pub(crate) fn spawn(&self, router: Router, shutdown: ShutdownSignal) -> Result<JoinHandle<()>> {
+        remove_stale_socket(&self.path)?;
+        let listener = UnixListener::bind(&self.path)
+            .with_context(|| format!("Failed to bind the REST server to the Unix socket '{}'", self.path.display()))?;
+        Ok(tokio::spawn(serve_unix_socket(listener, router, shutdown)))
+    }
+
+    /// Fails, as Unix sockets are not supported on this platform.
+    #[cfg(not(target_family = "unix"))]
+    pub(crate) fn spawn(&self, _router: Router, _shutdown: ShutdownSignal) -> Result<JoinHandle<()>> {
+        bail!("Cannot bind the REST server to '{}' - Unix sockets are not supported", self.path.display())
+    }
+}
+
+/// Removes the socket file at the given path, if it exists; fails if the path is not a socket.
+#[cfg(target_family = "unix")]
+fn remove_stale_socket(path: &Path) -> Result<()> {
+    match std::fs::symlink_metadata(path) {
+        Ok(metadata) if metadata.file_type().is_socket() => Ok(std::fs::remove_file(path)?),
+        Ok(_) => bail!("Cannot bind the REST server to '{}' - the file exists and is not a socket", path.display()),
+        Err(_) => Ok(()),
+    }
+}
+
+/// Serves the given router on the given Unix socket listener.
+///
+/// Once the shutdown is triggered, the listener stops accepting new connections, and this function
+/// returns after the in-flight requests are complete.
+///
+/// Note: All the requests received over the Unix socket are attributed to `127.0.0.1:0`,
+/// so they share the rate limit of a single IP.
+#[cfg(target_family = "unix")]
+async fn serve_unix_socket(listener: UnixListener, router: Router, shutdown: ShutdownSignal) {
+    let router = router.layer(Extension(ConnectInfo(UNIX_SOCKET_PEER)));
+    // Note: The connections are aborted if this task is aborted.
+    let mut connections = JoinSet::new();
+    loop {
+        let stream = tokio::select! {
+            result = listener.accept() => match result {
+                Ok((stream, _)) => stream,
+                Err(error) => {
+                    warn!("Failed to accept a REST connection on the Unix socket - {error}");
+                    continue;
+                }
+            },
+            // Reap the closed connections.
+            Some(_) = connections.join_next() => continue,
+            _ = shutdown.wait() => break,
+        };
+        let service = TowerToHyperService::new(router.clone());
+        let shutdown = shutdown.clone();
+        connections.spawn(async move {
+            let builder = Builder::new(TokioExecutor::new());
+            let connection = builder.serve_connection_with_upgrades(TokioIo::new(stream), service);
+            tokio::pin!(connection);
+            // Serve the connection, and close it gracefully once the shutdown is triggered.
+            let result = tokio::select! {
+                result = connection.as_mut() => result,
+                _ = shutdown.wait() => {
+                    connection.as_mut().graceful_shutdown();
+                    connection.await
+                }
+            };
+            if let Err(error) = result {
+                trace!("Failed to serve a REST connection on the Unix socket - {error}");
+            }
+        });
+    }
+    // Wait for the in-flight requests to complete.
+    while connections.join_next().await.is_some() {}
+}
+
+#[cfg(all(test, target_family = "unix"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_remove_stale_socket() {
+        let path = std::env::temp_dir().join(format!("snarkos-rest-{}.sock", std::process::id()));
+        // Check that a missing socket file is not an error.
+        remove_stale_socket(&path).unwrap();
+
+        // Check that the socket file left behind by a previous listener is removed.
+        drop(std::os::unix::net::UnixListener::bind(&path).unwrap());
+        assert!(path.exists());
+        remove_stale_socket(&path).unwrap();
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn test_remove_stale_socket_rejects_regular_file() {
+        let path = std::env::temp_dir().join(format!("snarkos-rest-not-a-socket-{}", std::process::id()));
+        std::fs::write(&path, b"not a socket").unwrap();
+        assert!(remove_stale_socket(&path).is_err());
+        // Check that the file was left untouched.
+        assert_eq!(std::fs::read(&path).unwrap(), b"not a socket");
+        std::fs::remove_file(path).unwrap();
+    }
+}
@@ -0,0 +1,316 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::block_stream;
+use snarkos_node_consensus::Consensus;
+use snarkvm::prelude::{
+    block::{Block, ConfirmedTransaction, Transaction},
+    store::ConsensusStorage,
+    Ledger,
+    Network,
+    ProgramID,
+};
+
+use anyhow::Result;
+use axum::extract::ws::{Message, WebSocket};
+use indexmap::IndexSet;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use tokio::sync::mpsc;
+
+/// The interval at which the memory pool is checked for new transactions, for the `mempool` topic.
+const MEMPOOL_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// The topics a WebSocket client can subscribe to.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WsTopic {
+    /// The blocks added to the ledger.
+    Blocks,
+    /// The confirmed transactions in the blocks added to the ledger.
+    Transactions,
+    /// The transactions admitted into the memory pool.
+    Mempool,
+}
+
+/// A request sent by a WebSocket client, e.g. `{"action": "subscribe", "topic": "blocks"}`.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(tag = "action", rename_all = "lowercase", bound = "")]
+pub enum WsRequest<N: Network> {
+    /// Subscribes to a topic, replacing any existing subscription to it.
+    Subscribe(WsSubscription<N>),
+    /// Unsubscribes from a topic.
+    Unsubscribe { topic: WsTopic },
+}
+
+/// A subscription of a WebSocket client to a topic.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(bound = "")]
+pub struct WsSubscription<N: Network> {
+    /// The topic.
+    pub topic: WsTopic,
+    /// The block height to start streaming from (inclusive), for the `blocks` and `transactions` topics;
+    /// if `None`, only new blocks are streamed.
+    #[serde(default)]
+    pub from_height: Option<u32>,
+    /// The programs to filter the transactions by, for the `transactions` and `mempool` topics;
+    /// if empty, all transactions are streamed.
+    #[serde(default)]
+    pub programs: IndexSet<ProgramID<N>>,
+}
+
+impl<N: Network> WsSubscription<N> {
+    /// Returns `true` if the given transaction passes the filters of the subscription.
+    pub fn matches(&self, transaction: &Transaction<N>) -> bool {
+        self.programs.is_empty()
+            || transaction.transitions().any(|transition| self.programs.contains(transition.program_id()))
+    }
+}
+
+/// An event sent to a WebSocket client, e.g. `{"type": "block", "block": {...}}`.
+#[derive(Clone, Debug, Serialize)]
+#[serde(tag = "type", rename_all = "lowercase", bound = "")]
+pub enum WsEvent<'a, N: Network> {
+    /// The client subscribed to the topic.
+    Subscribed { topic: WsTopic },
+    /// The client unsubscribed from the topic.
+    Unsubscribed { topic: WsTopic },
+    /// A block was added to the ledger.
+    Block { block: &'a Block<N> },
+    /// A confirmed transaction was added to the ledger, in the block at the given height.
+    Transaction { height: u32, transaction: &'a ConfirmedTransaction<N> },
+    /// A transaction was admitted into the memory pool.
+    Mempool { transaction: &'a Transaction<N> },
+    /// The request of the client failed, or a subscription was interrupted.
+    Error { message: String },
+}
+
+impl<N: Network> WsEvent<'_, N> {
+    /// Returns the event as a JSON WebSocket message.
+    fn to_message(&self) -> Option<Message> {
+        match serde_json::to_string(self) {
+            Ok(json) => Some(Message::Text(json)),
+            Err(error) => {
+                warn!("Failed to serialize a WebSocket event - {error}");
+                None
+            }
+        }
+    }
+}
+
+/// A WebSocket session, streaming the events of the topics the client subscribed to.
+pub(crate) struct WsSession<N: Network, C: ConsensusStorage<N>> {
+    /// The ledger.
+    ledger: Ledger<N, C>,
+    /// The consensus module, if any.
+    consensus: Option<Consensus<N>>,
+    /// The stream of blocks, if subscribed to the `blocks` topic.
+    blocks: Option<mpsc::Receiver<Result<Block<N>>>>,
+    /// The stream of blocks and the subscription, if subscribed to the `transactions` topic.
+    transactions: Option<(mpsc::Receiver<Result<Block<N>>>, WsSubscription<N>)>,
+    /// The transactions seen in the memory pool and the subscription, if subscribed to the `mempool` topic.
+    mempool: Option<(IndexSet<N::TransactionID>, WsSubscription<N>)>,
+}
+
+impl<N: Network, C: ConsensusStorage<N>> WsSession<N, C> {
+    /// Initializes a new WebSocket session, without any subscriptions.
+    pub(crate) fn new(ledger: Ledger<N, C>, consensus: Option<Consensus<N>>) -> Self {
+        Self { ledger, consensus, blocks: None, transactions: None, mempool: None }
+    }
+
+    /// Runs the session until the client disconnects.
+    pub(crate) async fn run(mut self, mut socket: WebSocket) {
+        let mut mempool_interval = tokio::time::interval(MEMPOOL_POLL_INTERVAL);
+
+        loop {
+            let messages = tokio::select! {
+                message = socket.recv() => match message {
+                    Some(Ok(Message::Text(text))) => self.process_request(&text),
+                    Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                    // Pings are answered automatically, and other messages are ignored.
+                    Some(Ok(_)) => continue,
+                },
+                Some(block) = next_block(self.blocks.as_mut()) => match block {
+                    Ok(block) => WsEvent::Block { block: &block }.to_message().into_iter().collect(),
+                    Err(error) => self.interrupt(WsTopic::Blocks, error),
+                },
+                Some(block) = next_block(self.transactions.as_mut().map(|(stream, _)| stream)) => match block {
+                    Ok(block) => self.transaction_messages(&block),
+                    Err(error) => self.interrupt(WsTopic::Transactions, error),
+                },
+                _ = mempool_interval.tick(), if self.mempool.is_some() => self.poll_mempool(),
+            };
+
+            // Send the messages, stopping if the client disconnected.
+            for message in messages {
+                if socket.send(message).await.is_err() {
+                    return;
+                }
+            }
+        }
+    }
+
+    /// Processes the given request of the client, returning the response.
+    fn process_request(&mut self, text: &str) -> Vec<Message> {
+        let event = match serde_json::from_str::<WsRequest<N>>(text) {
+            Ok(WsRequest::Subscribe(subscription)) => {
+                let topic = subscription.topic;
+                match topic {
+                    WsTopic::Blocks => {
+                        self.blocks = Some(block_stream(self.ledger.clone(), subscription.from_height));
+                        WsEvent::Subscribed { topic }
+                    }
+                    WsTopic::Transactions => {
+                        let stream = block_stream(self.ledger.clone(), subscription.from_height);
+                        self.transactions = Some((stream, subscription));
+                        WsEvent::Subscribed { topic }
+                    }
+                    WsTopic::Mempool => match &self.consensus {
+                        Some(consensus) => {
+                            // Only the transactions admitted from now on are streamed.
+                            let seen = consensus.unconfirmed_transactions().map(|(id, _)| id).collect();
+                            self.mempool = Some((seen, subscription));
+                            WsEvent::Subscribed { topic }
+                        }
+                        None => {
+                            WsEvent::Error { message: "The mempool topic isn't available for this node type".into() }
+                        }
+                    },
+                }
+            }
+            Ok(WsRequest::Unsubscribe { topic }) => {
+                match topic {
+                    WsTopic::Blocks => self.blocks = None,
+                    WsTopic::Transactions => self.transactions = None,
+                    WsTopic::Mempool => self.mempool = None,
+                }
+                WsEvent::Unsubscribed { topic }
+            }
+            Err(error) => WsEvent::Error { message: format!("Invalid request - {error}") },
+        };
+        event.to_message().into_iter().collect()
+    }
+
+    /// Returns the messages for the confirmed transactions in the given block that pass the filters.
+    fn transaction_messages(&self, block: &Block<N>) -> Vec<Message> {
+        let Some((_, subscription)) = &self.transactions else { return vec![] };
+        block
+            .transactions()
+            .iter()
+            .filter(|transaction| subscription.matches(transaction.transaction()))
+            .filter_map(|transaction| WsEvent::Transaction { height: block.height(), transaction }.to_message())
+            .collect()
+    }
+
+    /// Returns the messages for the transactions admitted into the memory pool since the last poll.
+    fn poll_mempool(&mut self) -> Vec<Message> {
+        let (Some(consensus), Some((seen, subscription))) = (&self.consensus, &mut self.mempool) else {
+            return vec![];
+        };
+        let mut messages = Vec::new();
+        let mut current = IndexSet::new();
+        for (id, transaction) in consensus.unconfirmed_transactions() {
+            if !seen.contains(&id) {
+                match transaction.deserialize_blocking() {
+                    Ok(transaction) if subscription.matches(&transaction) => {
+                        messages.extend(WsEvent::Mempool { transaction: &transaction }.to_message())
+                    }
+                    Ok(_) => (),
+                    Err(error) => warn!("Failed to deserialize the unconfirmed transaction '{id}' - {error}"),
+                }
+            }
+            current.insert(id);
+        }
+        // Only retain the transactions still in the memory pool, so the set does not grow unbounded.
+        *seen = current;
+        messages
+    }
+
+    /// Ends the subscription to the given topic after its stream failed, returning the error message.
+    fn interrupt(&mut self, topic: WsTopic, error: anyhow::Error) -> Vec<Message> {
+        match topic {
+            WsTopic::Blocks => self.blocks = None,
+            WsTopic::Transactions => self.transactions = None,
+            WsTopic::Mempool => self.mempool = None,
+        }
+        let message = format!("The subscription to '{}' was interrupted - {error}", topic_name(topic));
+        WsEvent::<N>::Error { message }.to_message().into_iter().collect()
+    }
+}
+
+/// Returns the next block of the given stream, or waits forever if there is no stream.
+async fn next_block<N: Network>(stream: Option<&mut mpsc::Receiver<Result<Block<N>>>>) -> Option<Result<Block<N>>> {
+    match stream {
+        Some(stream) => stream.recv().await,
+        None => std::future::pending().await,
+    }
+}
+
+/// Returns the name of the given topic, as used in the requests.
+fn topic_name(topic: WsTopic) -> &'static str {
+    match topic {
+        WsTopic::Blocks => "blocks",
+        WsTopic::Transactions => "transactions",
+        WsTopic::Mempool => "mempool",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkvm::prelude::{FromBytes, MainnetV0};
+
+    use std::str::FromStr;
+
+    type CurrentNetwork = MainnetV0;
+
+    #[test]
+    fn test_ws_request_deserialization() {
+        let request: WsRequest<CurrentNetwork> =
+            serde_json::from_str(r#"{"action": "subscribe", "topic": "blocks", "from_height": 10}"#).unwrap();
+        let WsRequest::Subscribe(subscription) = request else { panic!("Expected a subscription") };
+        assert_eq!(subscription.topic, WsTopic::Blocks);
+        assert_eq!(subscription.from_height, Some(10));
+        assert!(subscription.programs.is_empty());
+
+        let request: WsRequest<CurrentNetwork> =
+            serde_json::from_str(r#"{"action": "subscribe", "topic": "mempool", "programs": ["credits.aleo"]}"#)
+                .unwrap();
+        let WsRequest::Subscribe(subscription) = request else { panic!("Expected a subscription") };
+        assert_eq!(subscription.topic, WsTopic::Mempool);
+        assert_eq!(subscription.programs.len(), 1);
+
+        let request: WsRequest<CurrentNetwork> =
+            serde_json::from_str(r#"{"action": "unsubscribe", "topic": "transactions"}"#).unwrap();
+        assert!(matches!(request, WsRequest::Unsubscribe { topic: WsTopic::Transactions }));
+
+        assert!(serde_json::from_str::<WsRequest<CurrentNetwork>>(r#"{"action": "subscribe", "topic": "x"}"#).is_err());
+    }
+
+    #[test]
+    fn test_ws_subscription_matches() {
+        let genesis = Block::<CurrentNetwork>::from_bytes_le(CurrentNetwork::genesis_bytes()).unwrap();
+        let transaction = genesis.transactions().iter().next().unwrap().transaction();
+
+        let mut subscription =
+            WsSubscription::<CurrentNetwork> { topic: WsTopic::Transactions, from_height: None, programs: [].into() };
+        assert!(subscription.matches(transaction));
+
+        subscription.programs = [ProgramID::from_str("credits.aleo").unwrap()].into();
+        assert!(subscription.matches(transaction));
+
+        subscription.programs = [ProgramID::from_str("other.aleo").unwrap()].into();
+        assert!(!subscription.matches(transaction));
+    }
+}
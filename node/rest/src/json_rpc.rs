@@ -0,0 +1,310 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::{Rest, RestError};
+use snarkos_node_router::Routing;
+use snarkvm::prelude::{
+    block::{Block, Transaction},
+    store::ConsensusStorage,
+    FromBytes,
+    Network,
+};
+
+use anyhow::{anyhow, bail, Result};
+use axum::{body::Bytes, extract::State, routing::post, Json};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::str::FromStr;
+
+/// The path of the JSON-RPC endpoint.
+pub(crate) const JSON_RPC_PATH: &str = "/mainnet/rpc";
+/// The maximum number of calls in a batch request.
+const MAX_BATCH_SIZE: usize = 50;
+
+/// The error code for an invalid JSON payload.
+const PARSE_ERROR: i64 = -32700;
+/// The error code for a payload that is not a valid request.
+const INVALID_REQUEST: i64 = -32600;
+/// The error code for an unsupported method.
+const METHOD_NOT_FOUND: i64 = -32601;
+/// The error code for invalid method parameters.
+const INVALID_PARAMS: i64 = -32602;
+/// The error code for a failed call.
+const SERVER_ERROR: i64 = -32000;
+
+/// A JSON-RPC 2.0 request.
+#[derive(Debug, Deserialize)]
+struct RpcRequest {
+    /// The version of the protocol, which must be "2.0".
+    jsonrpc: String,
+    /// The name of the method.
+    method: String,
+    /// The parameters of the method, by position.
+    #[serde(default)]
+    params: Vec<Value>,
+    /// The ID of the request, echoed in the response.
+    #[serde(default)]
+    id: Value,
+}
+
+/// A JSON-RPC 2.0 response.
+#[derive(Debug, Serialize)]
+struct RpcResponse {
+    /// The version of the protocol.
+    jsonrpc: &'static str,
+    /// The result of the call, if it succeeded.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    result: Option<Value>,
+    /// The error of the call, if it failed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<RpcError>,
+    /// The ID of the request.
+    id: Value,
+}
+
+impl RpcResponse {
+    /// Returns the response for the given request ID and call result.
+    fn new(id: Value, result: Result<Value, RpcError>) -> Self {
+        match result {
+            Ok(result) => Self { jsonrpc: "2.0", result: Some(result), error: None, id },
+            Err(error) => Self { jsonrpc: "2.0", result: None, error: Some(error), id },
+        }
+    }
+}
+
+/// A JSON-RPC 2.0 error.
+#[derive(Debug, Serialize)]
+struct RpcError {
+    /// The error code.
+    code: i64,
+    /// The error message.
+    message: String,
+}
+
+impl RpcError {
+    /// Initializes a new error with the given code and message.
+    fn new(code: i64, message: impl ToString) -> Self {
+        Self { code, message: message.to_string() }
+    }
+}
+
+impl From<RestError> for RpcError {
+    fn from(error: RestError) -> Self {
+        Self::new(SERVER_ERROR, error.message)
+    }
+}
+
+impl From<anyhow::Error> for RpcError {
+    fn from(error: anyhow::Error) -> Self {
+        Self::new(SERVER_ERROR, error)
+    }
+}
+
+/// Returns the router of the JSON-RPC endpoint.
+pub(crate) fn router<N: Network, C: ConsensusStorage<N>, R: Routing<N>>() -> axum::Router<Rest<N, C, R>> {
+    axum::Router::new().route(JSON_RPC_PATH, post(json_rpc::<N, C, R>))
+}
+
+// POST /mainnet/rpc
+async fn json_rpc<N: Network, C: ConsensusStorage<N>, R: Routing<N>>(
+    State(rest): State<Rest<N, C, R>>,
+    body: Bytes,
+) -> Json<Value> {
+    // Parse the payload, which is either a single request or a batch of requests.
+    let payload = match serde_json::from_slice::<Value>(&body) {
+        Ok(payload) => payload,
+        Err(error) => return Json(json!(RpcResponse::new(Value::Null, Err(RpcError::new(PARSE_ERROR, error))))),
+    };
+    match payload {
+        Value::Array(requests) if requests.is_empty() || requests.len() > MAX_BATCH_SIZE => {
+            let error = RpcError::new(INVALID_REQUEST, format!("A batch must have 1 to {MAX_BATCH_SIZE} requests"));
+            Json(json!(RpcResponse::new(Value::Null, Err(error))))
+        }
+        Value::Array(requests) => {
+            let mut responses = Vec::with_capacity(requests.len());
+            for request in requests {
+                responses.push(rest.handle_rpc_request(request).await);
+            }
+            Json(json!(responses))
+        }
+        request => Json(json!(rest.handle_rpc_request(request).await)),
+    }
+}
+
+impl<N: Network, C: ConsensusStorage<N>, R: Routing<N>> Rest<N, C, R> {
+    /// Handles a single JSON-RPC request.
+    async fn handle_rpc_request(&self, request: Value) -> RpcResponse {
+        let request = match serde_json::from_value::<RpcRequest>(request) {
+            Ok(request) if request.jsonrpc == "2.0" => request,
+            Ok(request) => {
+                return RpcResponse::new(request.id, Err(RpcError::new(INVALID_REQUEST, "Expected 'jsonrpc: 2.0'")))
+            }
+            Err(error) => return RpcResponse::new(Value::Null, Err(RpcError::new(INVALID_REQUEST, error))),
+        };
+        let result = self.call_rpc_method(&request.method, &request.params).await;
+        RpcResponse::new(request.id, result)
+    }
+
+    /// Calls the given JSON-RPC method, translating it onto the ledger.
+    async fn call_rpc_method(&self, method: &str, params: &[Value]) -> Result<Value, RpcError> {
+        match method {
+            "eth_chainId" | "net_version" => Ok(json!(to_quantity(N::ID as u64))),
+            "eth_blockNumber" => Ok(json!(to_quantity(self.ledger.latest_height() as u64))),
+            "eth_getBlockByNumber" => {
+                let height = match param::<String>(params, 0)?.as_str() {
+                    "latest" | "pending" | "safe" | "finalized" => self.ledger.latest_height(),
+                    "earliest" => 0,
+                    number => parse_quantity(number).map_err(|error| RpcError::new(INVALID_PARAMS, error))?,
+                };
+                let full = param::<Option<bool>>(params, 1)?.unwrap_or(false);
+                match height <= self.ledger.latest_height() {
+                    true => Ok(block_object(&self.ledger.get_block(height)?, full)?),
+                    false => Ok(Value::Null),
+                }
+            }
+            "eth_getBlockByHash" => {
+                let hash = parse_param::<N::BlockHash>(params, 0)?;
+                let full = param::<Option<bool>>(params, 1)?.unwrap_or(false);
+                match self.ledger.contains_block_hash(&hash)? {
+                    true => Ok(block_object(&self.ledger.get_block_by_hash(&hash)?, full)?),
+                    false => Ok(Value::Null),
+                }
+            }
+            "eth_getTransactionByHash" => {
+                let tx_id = parse_param::<N::TransactionID>(params, 0)?;
+                // Check the ledger, and then the memory pool.
+                if let Some(block_hash) = self.ledger.find_block_hash(&tx_id)? {
+                    let confirmed = self.ledger.get_confirmed_transaction(tx_id)?;
+                    return Ok(json!({
+                        "hash": tx_id.to_string(),
+                        "blockHash": block_hash.to_string(),
+                        "blockNumber": to_quantity(self.ledger.get_height(&block_hash)? as u64),
+                        "transactionIndex": to_quantity(confirmed.index() as u64),
+                        "transaction": confirmed.transaction(),
+                    }));
+                }
+                let unconfirmed = self.consensus.as_ref().and_then(|consensus| {
+                    consensus
+                        .unconfirmed_transactions()
+                        .find(|(id, _)| *id == tx_id)
+                        .map(|(_, transaction)| transaction)
+                });
+                match unconfirmed {
+                    Some(transaction) => Ok(json!({
+                        "hash": tx_id.to_string(),
+                        "blockHash": Value::Null,
+                        "blockNumber": Value::Null,
+                        "transactionIndex": Value::Null,
+                        "transaction": transaction.deserialize_blocking()?,
+                    })),
+                    None => Ok(Value::Null),
+                }
+            }
+            "eth_sendRawTransaction" => {
+                let bytes =
+                    parse_hex(&param::<String>(params, 0)?).map_err(|error| RpcError::new(INVALID_PARAMS, error))?;
+                let transaction =
+                    Transaction::<N>::from_bytes_le(&bytes).map_err(|error| RpcError::new(INVALID_PARAMS, error))?;
+                Ok(json!(self.broadcast_transaction(transaction).await?.to_string()))
+            }
+            method => Err(RpcError::new(METHOD_NOT_FOUND, format!("Method '{method}' is not supported"))),
+        }
+    }
+}
+
+/// Returns the block in the shape of a JSON-RPC block object, with the full transactions or only their IDs.
+fn block_object<N: Network>(block: &Block<N>, full: bool) -> Result<Value> {
+    let transactions = match full {
+        true => block.transactions().iter().map(|confirmed| json!(confirmed.transaction())).collect::<Vec<_>>(),
+        false => block.transaction_ids().map(|id| json!(id.to_string())).collect(),
+    };
+    Ok(json!({
+        "number": to_quantity(block.height() as u64),
+        "hash": block.hash().to_string(),
+        "parentHash": block.previous_hash().to_string(),
+        "timestamp": to_quantity(u64::try_from(block.timestamp())?),
+        "transactions": transactions,
+    }))
+}
+
+/// Returns the parameter at the given position.
+fn param<T: for<'de> Deserialize<'de>>(params: &[Value], index: usize) -> Result<T, RpcError> {
+    let value = params.get(index).cloned().unwrap_or(Value::Null);
+    serde_json::from_value(value)
+        .map_err(|error| RpcError::new(INVALID_PARAMS, format!("Invalid parameter {index} - {error}")))
+}
+
+/// Returns the parameter at the given position, parsed from a string.
+fn parse_param<T: FromStr<Err = anyhow::Error>>(params: &[Value], index: usize) -> Result<T, RpcError> {
+    T::from_str(&param::<String>(params, index)?)
+        .map_err(|error| RpcError::new(INVALID_PARAMS, format!("Invalid parameter {index} - {error}")))
+}
+
+/// Returns the given number as a JSON-RPC quantity, i.e. a '0x'-prefixed hexadecimal string.
+fn to_quantity(number: u64) -> String {
+    format!("{number:#x}")
+}
+
+/// Parses a JSON-RPC quantity, i.e. a '0x'-prefixed hexadecimal string.
+fn parse_quantity(quantity: &str) -> Result<u32> {
+    let Some(digits) = quantity.strip_prefix("0x") else {
+        bail!("Invalid quantity '{quantity}' (expected a '0x' prefix)")
+    };
+    u32::from_str_radix(digits, 16).map_err(|_| anyhow!("Invalid quantity '{quantity}'"))
+}
+
+/// Parses a '0x'-prefixed hexadecimal string of bytes.
+fn parse_hex(data: &str) -> Result<Vec<u8>> {
+    let digits = data.strip_prefix("0x").unwrap_or(data);
+    if digits.len() % 2 != 0 {
+        bail!("Invalid hexadecimal data (odd length)")
+    }
+    (0..digits.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&digits[i..i + 2], 16).map_err(|_| anyhow!("Invalid hexadecimal data")))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_quantity() {
+        assert_eq!(to_quantity(0), "0x0");
+        assert_eq!(to_quantity(436), "0x1b4");
+        assert_eq!(parse_quantity("0x1b4").unwrap(), 436);
+        assert!(parse_quantity("436").is_err());
+        assert!(parse_quantity("0xzz").is_err());
+    }
+
+    #[test]
+    fn test_parse_hex() {
+        assert_eq!(parse_hex("0x00ff10").unwrap(), vec![0, 255, 16]);
+        assert_eq!(parse_hex("00ff10").unwrap(), vec![0, 255, 16]);
+        assert!(parse_hex("0x0").is_err());
+        assert!(parse_hex("0xgg").is_err());
+    }
+
+    #[test]
+    fn test_response() {
+        let response = json!(RpcResponse::new(json!(1), Ok(json!("0x1"))));
+        assert_eq!(response, json!({ "jsonrpc": "2.0", "result": "0x1", "id": 1 }));
+        let response = json!(RpcResponse::new(json!(2), Err(RpcError::new(METHOD_NOT_FOUND, "Unsupported"))));
+        assert_eq!(
+            response,
+            json!({ "jsonrpc": "2.0", "error": { "code": -32601, "message": "Unsupported" }, "id": 2 })
+        );
+    }
+}
@@ -20,6 +20,10 @@ extern crate tracing;
 mod helpers;
 pub use helpers::*;
 
+#[cfg(feature = "graphql")]
+mod graphql;
+#[cfg(feature = "json-rpc")]
+mod json_rpc;
 mod routes;
 
 use snarkos_node_consensus::Consensus;
@@ -30,29 +34,25 @@ use snarkos_node_router::{
 use snarkvm::{
     console::{program::ProgramID, types::Field},
     ledger::narwhal::Data,
-    prelude::{cfg_into_iter, store::ConsensusStorage, Ledger, Network},
+    prelude::{block::Transaction, cfg_into_iter, cfg_iter, store::ConsensusStorage, Ledger, Network},
 };
 
 use anyhow::Result;
 use axum::{
     body::Body,
     extract::{ConnectInfo, DefaultBodyLimit, Path, Query, State},
-    http::{header::CONTENT_TYPE, Method, Request, StatusCode},
+    http::{HeaderMap, Request, StatusCode},
     middleware,
     middleware::Next,
     response::Response,
-    routing::{get, post},
     Json,
 };
 use axum_extra::response::ErasedJson;
 use parking_lot::Mutex;
-use std::{net::SocketAddr, sync::Arc};
+use std::{net::SocketAddr, sync::Arc, time::Duration};
 use tokio::{net::TcpListener, task::JoinHandle};
-use tower_governor::{governor::GovernorConfigBuilder, GovernorLayer};
-use tower_http::{
-    cors::{Any, CorsLayer},
-    trace::TraceLayer,
-};
+use tower::steer::Steer;
+use tower_http::trace::TraceLayer;
 
 /// A REST API server for the ledger.
 #[derive(Clone)]
@@ -63,8 +63,28 @@ pub struct Rest<N: Network, C: ConsensusStorage<N>, R: Routing<N>> {
     ledger: Ledger<N, C>,
     /// The node (routing).
     routing: Arc<R>,
+    /// The node information.
+    node_info: Arc<NodeInfo>,
+    /// The state history, if enabled.
+    state_history: Option<Arc<StateHistory<N>>>,
+    /// The block timestamp index.
+    timestamps: Arc<TimestampIndex>,
+    /// The address index, if enabled.
+    address_index: Option<Arc<AddressIndex<N>>>,
+    /// The cache of program descriptions.
+    programs: Arc<ProgramCache<N>>,
+    /// The tracker of the submitted transactions.
+    transactions: Arc<TransactionTracker<N>>,
+    /// The admin API settings, if enabled.
+    admin: Option<AdminConfig>,
     /// The server handles.
     handles: Arc<Mutex<Vec<JoinHandle<()>>>>,
+    /// The handles of the tasks serving the connections.
+    servers: Arc<Mutex<Vec<JoinHandle<()>>>>,
+    /// The signal for the servers to stop accepting new connections.
+    shutdown: ShutdownSignal,
+    /// The time to wait for the in-flight requests to complete on shutdown, if any.
+    shutdown_grace_period: Option<Duration>,
 }
 
 impl<N: Network, C: 'static + ConsensusStorage<N>, R: Routing<N>> Rest<N, C, R> {
@@ -72,14 +92,57 @@ impl<N: Network, C: 'static + ConsensusStorage<N>, R: Routing<N>> Rest<N, C, R>
     pub async fn start(
         rest_ip: SocketAddr,
         rest_rps: u32,
+        rest_config: RestConfig,
         consensus: Option<Consensus<N>>,
         ledger: Ledger<N, C>,
         routing: Arc<R>,
     ) -> Result<Self> {
         // Initialize the server.
-        let mut server = Self { consensus, ledger, routing, handles: Default::default() };
+        let node_info = Arc::new(rest_config.node_info.clone());
+        let state_history = match &rest_config.state_history {
+            Some(mappings) => Some(Arc::new(StateHistory::new(mappings)?)),
+            None => None,
+        };
+        let address_index = match (rest_config.address_index, &rest_config.storage_dir) {
+            (false, _) => None,
+            (true, None) => Some(Default::default()),
+            (true, Some(dir)) => match AddressIndex::open(dir.join(ADDRESS_INDEX_FILE)) {
+                Ok(address_index) => Some(Arc::new(address_index)),
+                Err(error) => {
+                    warn!("Failed to open the address index file, rebuilding it in memory - {error}");
+                    Some(Default::default())
+                }
+            },
+        };
+        let transactions = Arc::new(TransactionTracker::new(ledger.latest_height().saturating_add(1)));
+        let mut server = Self {
+            consensus,
+            ledger,
+            routing,
+            node_info,
+            state_history,
+            timestamps: Default::default(),
+            address_index,
+            programs: Default::default(),
+            transactions,
+            admin: rest_config.admin.clone(),
+            handles: Default::default(),
+            servers: Default::default(),
+            shutdown: Default::default(),
+            shutdown_grace_period: rest_config.shutdown_grace_period,
+        };
         // Spawn the server.
-        server.spawn_server(rest_ip, rest_rps).await;
+        server.spawn_server(rest_ip, rest_rps, rest_config).await?;
+        // Spawn the indexer, which updates the timestamp index, the transaction tracker, the address index
+        // (if enabled) and the state history (if enabled), in a single task.
+        let mut indexes: Vec<Arc<dyn LedgerIndex<N, C>>> = vec![server.timestamps.clone(), server.transactions.clone()];
+        if let Some(address_index) = &server.address_index {
+            indexes.push(address_index.clone());
+        }
+        if let Some(state_history) = &server.state_history {
+            indexes.push(state_history.clone());
+        }
+        server.handles.lock().push(spawn_indexer(indexes, server.ledger.clone()));
         // Return the server.
         Ok(server)
     }
@@ -95,116 +158,418 @@ impl<N: Network, C: ConsensusStorage<N>, R: Routing<N>> Rest<N, C, R> {
     pub const fn handles(&self) -> &Arc<Mutex<Vec<JoinHandle<()>>>> {
         &self.handles
     }
+
+    /// Shuts down the server, which stops accepting new connections and waits for the in-flight requests
+    /// to complete, up to the grace period, before aborting them.
+    pub async fn shut_down(&self) {
+        trace!("Shutting down the REST server...");
+        // Stop accepting new connections.
+        self.shutdown.trigger();
+        // Abort the background tasks.
+        self.handles.lock().iter().for_each(|handle| handle.abort());
+
+        // Wait for the in-flight requests to complete, up to the grace period.
+        let servers = std::mem::take(&mut *self.servers.lock());
+        let abort_handles: Vec<_> = servers.iter().map(|handle| handle.abort_handle()).collect();
+        let grace_period = self.shutdown_grace_period.unwrap_or_default();
+        if tokio::time::timeout(grace_period, futures_util::future::join_all(servers)).await.is_err() {
+            if !grace_period.is_zero() {
+                warn!("Aborting the in-flight REST requests after a grace period of {}s", grace_period.as_secs());
+            }
+            abort_handles.iter().for_each(|handle| handle.abort());
+        }
+    }
+
+    /// Returns the admin API settings, or an error if the admin API is disabled.
+    fn admin(&self) -> Result<&AdminConfig, RestError> {
+        self.admin.as_ref().ok_or_else(|| RestError::unavailable("The admin API is disabled (see '--rest-admin')"))
+    }
+
+    /// Adds the given transaction to the memory pool (if the node has one), and broadcasts it to the peers.
+    async fn broadcast_transaction(&self, tx: Transaction<N>) -> Result<N::TransactionID, RestError> {
+        let tx_id = tx.id();
+        // If the consensus module is enabled, add the unconfirmed transaction to the memory pool.
+        if let Some(consensus) = &self.consensus {
+            // Add the unconfirmed transaction to the memory pool, recording the reason if it is rejected.
+            if let Err(error) = consensus.add_unconfirmed_transaction(tx.clone()).await {
+                self.transactions.reject(tx_id, error.to_string());
+                return Err(RestError::rejected(error.to_string()));
+            }
+        }
+        // Track the status of the transaction.
+        self.transactions.track(tx_id);
+
+        // Prepare the unconfirmed transaction message.
+        let message = Message::UnconfirmedTransaction(UnconfirmedTransaction {
+            transaction_id: tx_id,
+            transaction: Data::Object(tx),
+        });
+
+        // Broadcast the transaction.
+        self.routing.propagate(message, &[]);
+
+        Ok(tx_id)
+    }
 }
 
 impl<N: Network, C: ConsensusStorage<N>, R: Routing<N>> Rest<N, C, R> {
-    async fn spawn_server(&mut self, rest_ip: SocketAddr, rest_rps: u32) {
-        let cors = CorsLayer::new()
-            .allow_origin(Any)
-            .allow_methods([Method::GET, Method::POST, Method::OPTIONS])
-            .allow_headers([CONTENT_TYPE]);
-
-        // Log the REST rate limit per IP.
-        debug!("REST rate limit per IP - {rest_rps} RPS");
-
-        // Prepare the rate limiting setup.
-        let governor_config = Box::new(
-            GovernorConfigBuilder::default()
-                .per_second(1)
-                .burst_size(rest_rps)
-                .error_handler(|error| Response::new(error.to_string().into()))
-                .finish()
-                .expect("Couldn't set up rate limiting for the REST server!"),
-        );
-
-        let router = {
-            axum::Router::new()
-
-            // All the endpoints before the call to `route_layer` are protected with JWT auth.
-            .route("/mainnet/node/address", get(Self::get_node_address))
-            .route_layer(middleware::from_fn(auth_middleware))
+    async fn spawn_server(&mut self, rest_ip: SocketAddr, rest_rps: u32, rest_config: RestConfig) -> Result<()> {
+        let cors = rest_config.cors.layer();
+
+        let mut router = {
+            ApiRouter::default()
+
+            // All the endpoints before the call to `authenticated` are protected with JWT auth.
+            .get("/mainnet/node/address", Self::get_node_address, "Returns the address of the node")
+            .post("/mainnet/admin/peers/connect", Self::admin_connect_peer, "Connects to a peer")
+            .post("/mainnet/admin/peers/disconnect", Self::admin_disconnect_peer, "Disconnects from a peer")
+            .get("/mainnet/admin/peers/scores", Self::admin_get_peer_scores, "Returns the reputation of the peers")
+            .get("/mainnet/admin/peers/states", Self::admin_get_peer_states, "Returns the peer connection states")
+            .get("/mainnet/admin/peers/topology", Self::admin_get_topology, "Exports the network topology")
+            .post("/mainnet/admin/peers/ban", Self::admin_ban_peer, "Bans a peer for a duration")
+            .post("/mainnet/admin/peers/unban", Self::admin_unban_peer, "Lifts the ban of a peer")
+            .get("/mainnet/admin/peers/limits", Self::admin_get_peer_limits, "Returns the peer limits")
+            .post("/mainnet/admin/peers/limits", Self::admin_set_peer_limits, "Changes the peer limits")
+            .post("/mainnet/admin/peers/access/reload", Self::admin_reload_access_lists, "Reloads the peer access list")
+            .post("/mainnet/admin/log", Self::admin_set_log_filter, "Changes the log filter of the node")
+            .post("/mainnet/admin/sync/pause", Self::admin_pause_sync, "Pauses the block sync")
+            .post("/mainnet/admin/sync/resume", Self::admin_resume_sync, "Resumes the block sync")
+            .post("/mainnet/admin/validator/signer", Self::admin_rotate_signer, "Rotates the signer of the validator")
+            .authenticated()
 
             // ----------------- DEPRECATED ROUTES -----------------
             // The following `GET ../latest/..` routes will be removed before mainnet.
             // Please refer to the recommended routes for each endpoint:
 
             // Deprecated: use `/mainnet/block/height/latest` instead.
-            .route("/mainnet/latest/height", get(Self::latest_height))
+            .get("/mainnet/latest/height", Self::latest_height, "Deprecated: returns the latest block height")
             // Deprecated: use `/mainnet/block/hash/latest` instead.
-            .route("/mainnet/latest/hash", get(Self::latest_hash))
+            .get("/mainnet/latest/hash", Self::latest_hash, "Deprecated: returns the latest block hash")
             // Deprecated: use `/mainnet/latest/block/height` instead.
-            .route("/mainnet/latest/block", get(Self::latest_block))
+            .get("/mainnet/latest/block", Self::latest_block, "Deprecated: returns the latest block")
             // Deprecated: use `/mainnet/stateRoot/latest` instead.
-            .route("/mainnet/latest/stateRoot", get(Self::latest_state_root))
+            .get("/mainnet/latest/stateRoot", Self::latest_state_root, "Deprecated: returns the latest state root")
             // Deprecated: use `/mainnet/committee/latest` instead.
-            .route("/mainnet/latest/committee", get(Self::latest_committee))
+            .get("/mainnet/latest/committee", Self::latest_committee, "Deprecated: returns the latest committee")
             // ------------------------------------------------------
 
             // GET ../block/..
-            .route("/mainnet/block/height/latest", get(Self::get_block_height_latest))
-            .route("/mainnet/block/hash/latest", get(Self::get_block_hash_latest))
-            .route("/mainnet/block/latest", get(Self::get_block_latest))
-            .route("/mainnet/block/:height_or_hash", get(Self::get_block))
+            .get("/mainnet/block/height/latest", Self::get_block_height_latest, "Returns the latest block height")
+            .get("/mainnet/block/hash/latest", Self::get_block_hash_latest, "Returns the latest block hash")
+            .get("/mainnet/block/latest", Self::get_block_latest, "Returns the latest block")
+            .get("/mainnet/block/:height_or_hash", Self::get_block, "Returns a block by height or hash")
+            .get(
+                "/mainnet/block/timestamp/:timestamp",
+                Self::get_block_by_timestamp,
+                "Returns the block nearest to a timestamp",
+            )
             // The path param here is actually only the height, but the name must match the route
             // above, otherwise there'll be a conflict at runtime.
-            .route("/mainnet/block/:height_or_hash/transactions", get(Self::get_block_transactions))
+            .get(
+                "/mainnet/block/:height_or_hash/transactions",
+                Self::get_block_transactions,
+                "Returns the transactions of a block",
+            )
+            .get(
+                "/mainnet/block/:height_or_hash/dependency-graph",
+                Self::get_block_dependency_graph,
+                "Returns the transaction dependency graph of a block",
+            )
 
             // GET and POST ../transaction/..
-            .route("/mainnet/transaction/:id", get(Self::get_transaction))
-            .route("/mainnet/transaction/confirmed/:id", get(Self::get_confirmed_transaction))
-            .route("/mainnet/transaction/broadcast", post(Self::transaction_broadcast))
+            .get("/mainnet/transaction/:id", Self::get_transaction, "Returns a transaction")
+            .get(
+                "/mainnet/transaction/:id/status",
+                Self::get_transaction_status,
+                "Returns the status of a transaction (pending, confirmed, rejected or aborted)",
+            )
+            .get(
+                "/mainnet/transaction/confirmed/:id",
+                Self::get_confirmed_transaction,
+                "Returns a confirmed transaction",
+            )
+            .post("/mainnet/transaction/broadcast", Self::transaction_broadcast, "Broadcasts the given transaction")
+            .post(
+                "/mainnet/transaction/simulate",
+                Self::transaction_simulate,
+                "Simulates the given transaction without broadcasting it",
+            )
 
             // POST ../solution/broadcast
-            .route("/mainnet/solution/broadcast", post(Self::solution_broadcast))
+            .post("/mainnet/solution/broadcast", Self::solution_broadcast, "Broadcasts the given prover solution")
 
             // GET ../find/..
-            .route("/mainnet/find/blockHash/:tx_id", get(Self::find_block_hash))
-            .route("/mainnet/find/transactionID/deployment/:program_id", get(Self::find_transaction_id_from_program_id))
-            .route("/mainnet/find/transactionID/:transition_id", get(Self::find_transaction_id_from_transition_id))
-            .route("/mainnet/find/transitionID/:input_or_output_id", get(Self::find_transition_id))
+            .get("/mainnet/find/blockHash/:tx_id", Self::find_block_hash, "Finds the block hash of a transaction")
+            .get(
+                "/mainnet/find/transactionID/deployment/:program_id",
+                Self::find_transaction_id_from_program_id,
+                "Finds the deployment of a program",
+            )
+            .get(
+                "/mainnet/find/transactionID/:transition_id",
+                Self::find_transaction_id_from_transition_id,
+                "Finds the transaction of a transition",
+            )
+            .get(
+                "/mainnet/find/transitionID/:input_or_output_id",
+                Self::find_transition_id,
+                "Finds the transition of an input or output",
+            )
 
             // GET ../peers/..
-            .route("/mainnet/peers/count", get(Self::get_peers_count))
-            .route("/mainnet/peers/all", get(Self::get_peers_all))
-            .route("/mainnet/peers/all/metrics", get(Self::get_peers_all_metrics))
+            .get("/mainnet/peers/count", Self::get_peers_count, "Returns the number of connected peers")
+            .get("/mainnet/peers/all", Self::get_peers_all, "Returns the connected peers")
+            .get(
+                "/mainnet/peers/all/metrics",
+                Self::get_peers_all_metrics,
+                "Returns the connected peers and their types",
+            )
+            .get(
+                "/mainnet/peers/sync",
+                Self::get_peers_sync,
+                "Returns the advertised heights, locators and block requests of the sync peers",
+            )
 
             // GET ../program/..
-            .route("/mainnet/program/:id", get(Self::get_program))
-            .route("/mainnet/program/:id/mappings", get(Self::get_mapping_names))
-            .route("/mainnet/program/:id/mapping/:name/:key", get(Self::get_mapping_value))
+            .get("/mainnet/program/:id", Self::get_program, "Returns a program")
+            .get("/mainnet/program/:id/mappings", Self::get_mapping_names, "Returns the mapping names of a program")
+            .get(
+                "/mainnet/program/:id/description",
+                Self::get_program_description,
+                "Returns the functions, types and mappings of a program",
+            )
+            .get("/mainnet/program/:id/mapping/:name/:key", Self::get_mapping_value, "Returns a mapping value")
+
+            // POST ../batch
+            .post("/mainnet/batch", Self::batch, "Executes a batch of block, transaction and mapping queries")
+
+            // POST ../records/status
+            .post("/mainnet/records/status", Self::get_records_status, "Returns the status of records")
+            .post(
+                "/mainnet/records/scan",
+                Self::scan_records,
+                "Returns a page of the records owned by a view key, or spent with the given tags",
+            )
+
+            // GET ../node/info
+            .get("/mainnet/node/info", Self::get_node_info, "Returns the node information")
+
+            // GET health checks, for load balancers.
+            .get("/health/live", Self::get_health_live, "Returns 200 while the node is running")
+            .get("/health/ready", Self::get_health_ready, "Returns 200 if the node is ready to serve traffic, or 503")
+
+            // GET ../events
+            .get("/mainnet/events", Self::get_events, "Opens a server-sent events stream of blocks and transactions")
+
+            // GET ../ws
+            .get("/mainnet/ws", Self::ws_subscribe, "Opens a WebSocket subscription")
+
+            // GET and POST ../graphql
+            .merge(self.graphql_router())
+
+            // POST ../rpc
+            .merge(self.json_rpc_router())
 
             // GET misc endpoints.
-            .route("/mainnet/blocks", get(Self::get_blocks))
-            .route("/mainnet/height/:hash", get(Self::get_height))
-            .route("/mainnet/memoryPool/transmissions", get(Self::get_memory_pool_transmissions))
-            .route("/mainnet/memoryPool/solutions", get(Self::get_memory_pool_solutions))
-            .route("/mainnet/memoryPool/transactions", get(Self::get_memory_pool_transactions))
-            .route("/mainnet/statePath/:commitment", get(Self::get_state_path_for_commitment))
-            .route("/mainnet/stateRoot/latest", get(Self::get_state_root_latest))
-            .route("/mainnet/committee/latest", get(Self::get_committee_latest))
+            .get("/mainnet/blocks", Self::get_blocks, "Returns a range of blocks")
+            .get("/mainnet/blocks/page", Self::get_blocks_page, "Returns a page of block summaries")
+            .get(
+                "/mainnet/cdn/:file",
+                Self::get_cdn_file,
+                "Returns a CDN file ('latest.json' or '{start}.{end}.blocks'), to bootstrap other nodes",
+            )
+            .get("/mainnet/blocks/time", Self::get_blocks_by_time, "Returns the blocks within a time range")
+            .get("/mainnet/transactions/page", Self::get_transactions_page, "Returns a page of transaction summaries")
+            .get(
+                "/mainnet/address/:address/transactions",
+                Self::get_address_transactions,
+                "Returns a page of the transactions of an address",
+            )
+            .get(
+                "/mainnet/solutions/pending",
+                Self::get_pending_solutions,
+                "Returns the puzzle epoch, targets and pending solutions",
+            )
+            .get("/mainnet/fees/estimate", Self::get_fee_estimate, "Returns the suggested fees")
+            .get("/mainnet/height/:hash", Self::get_height, "Returns the height of a block hash")
+            .get(
+                "/mainnet/memoryPool/transmissions",
+                Self::get_memory_pool_transmissions,
+                "Returns the unconfirmed transmissions",
+            )
+            .get("/mainnet/memoryPool/solutions", Self::get_memory_pool_solutions, "Returns the unconfirmed solutions")
+            .get(
+                "/mainnet/memoryPool/transactions",
+                Self::get_memory_pool_transactions,
+                "Returns the unconfirmed transactions",
+            )
+            .get(
+                "/mainnet/memoryPool/solutions/summary",
+                Self::get_memory_pool_solution_summaries,
+                "Returns the fee, size and age of the unconfirmed solutions",
+            )
+            .get(
+                "/mainnet/memoryPool/transactions/summary",
+                Self::get_memory_pool_transaction_summaries,
+                "Returns the fee, size and age of the unconfirmed transactions",
+            )
+            .get(
+                "/mainnet/memoryPool/solution/:commitment",
+                Self::get_memory_pool_solution,
+                "Returns an unconfirmed solution",
+            )
+            .get(
+                "/mainnet/memoryPool/transaction/:id",
+                Self::get_memory_pool_transaction,
+                "Returns an unconfirmed transaction",
+            )
+            .get(
+                "/mainnet/statePath/:commitment",
+                Self::get_state_path_for_commitment,
+                "Returns the state path of a commitment",
+            )
+            .get("/mainnet/stateRoot/latest", Self::get_state_root_latest, "Returns the latest state root")
+            .get("/mainnet/committee/latest", Self::get_committee_latest, "Returns the latest committee")
+            .get("/mainnet/committee/stake", Self::get_committee_stake, "Returns the stake of the latest committee")
+            .get("/mainnet/committee/round/:round", Self::get_committee_for_round, "Returns the committee of a round")
+            .get(
+                "/mainnet/committee/height/:height",
+                Self::get_committee_for_height,
+                "Returns the committee of a block height",
+            )
+            .get(
+                "/mainnet/validators/equivocations",
+                Self::get_equivocations,
+                "Returns the equivocations of the validators observed by the node",
+            )
+
+            // Serve the OpenAPI specification and the Swagger UI of the routes above.
+            .finish()
 
             // Pass in `Rest` to make things convenient.
             .with_state(self.clone())
+            // Serve the responses of the immutable routes from the cache.
+            .layer(middleware::from_fn_with_state(
+                rest_config.response_cache.map(|capacity| Arc::new(ResponseCache::new(capacity))),
+                response_cache_middleware,
+            ))
             // Enable tower-http tracing.
             .layer(TraceLayer::new_for_http())
             // Custom logging.
             .layer(middleware::from_fn(log_middleware))
             // Enable CORS.
             .layer(cors)
-            // Cap body size at 10MB.
-            .layer(DefaultBodyLimit::max(10 * 1024 * 1024))
-            .layer(GovernorLayer {
-                // We can leak this because it is created only once and it persists.
-                config: Box::leak(governor_config),
-            })
+            // Enforce the request timeout, and return clear errors for the requests exceeding the limits.
+            .layer(middleware::from_fn_with_state(rest_config.limits, limits_middleware))
+            // Cap the body size.
+            .layer(DefaultBodyLimit::max(rest_config.limits.max_body_size))
+        };
+
+        // Record the latency, status codes and payload sizes of each route.
+        #[cfg(feature = "metrics")]
+        {
+            router = router.layer(middleware::from_fn(metrics_middleware));
+        }
+
+        // Apply the rate limit.
+        let rate_limit = &rest_config.rate_limit;
+        router = match &rest_config.api_keys {
+            // Requests with an API key are rate limited per key by the API key middleware, instead of per IP.
+            Some(api_keys) => {
+                debug!("REST API keys enabled - {} key(s) (required: {})", api_keys.len(), api_keys.is_required());
+                let keyed = router.clone();
+                let public = rate_limit.apply(router, rest_rps)?;
+                let steer = Steer::new([keyed, public], |request: &Request<Body>, _: &[_]| {
+                    match request.headers().contains_key(API_KEY_HEADER) {
+                        true => 0,
+                        false => 1,
+                    }
+                });
+                axum::Router::new()
+                    .fallback_service(steer)
+                    .layer(middleware::from_fn_with_state(api_keys.clone(), api_key_middleware))
+            }
+            None => rate_limit.apply(router, rest_rps)?,
         };
 
+        // Enable response compression, if configured.
+        if let Some(compression) = &rest_config.compression {
+            debug!(
+                "REST response compression enabled - {:?} (above {} bytes)",
+                compression.algorithms, compression.min_size
+            );
+            router = router.layer(compression.layer());
+        }
+
+        // Serve on the Unix socket, if configured.
+        if let Some(unix_socket) = &rest_config.unix_socket {
+            debug!("REST Unix socket enabled - listening on '{}'", unix_socket.path.display());
+            self.servers.lock().push(unix_socket.spawn(router.clone(), self.shutdown.clone())?);
+            // If the server only listens on the Unix socket, skip the TCP listener.
+            if unix_socket.exclusive {
+                return Ok(());
+            }
+        }
+
+        // Serve HTTPS, if configured.
+        if let Some(tls) = &rest_config.tls {
+            let tls_config = tls.load().await?;
+            debug!("REST TLS enabled - serving HTTPS with the certificate '{}'", tls.cert_path.display());
+            // Stop accepting new connections once the shutdown is triggered.
+            let handle = axum_server::Handle::new();
+            let shutdown = self.shutdown.clone();
+            tokio::spawn({
+                let handle = handle.clone();
+                async move {
+                    shutdown.wait().await;
+                    handle.graceful_shutdown(None);
+                }
+            });
+            self.servers.lock().push(tokio::spawn(async move {
+                axum_server::bind_rustls(rest_ip, tls_config)
+                    .handle(handle)
+                    .serve(router.into_make_service_with_connect_info::<SocketAddr>())
+                    .await
+                    .expect("couldn't start rest server");
+            }));
+            return Ok(());
+        }
+
         let rest_listener = TcpListener::bind(rest_ip).await.unwrap();
-        self.handles.lock().push(tokio::spawn(async move {
+        let shutdown = self.shutdown.clone();
+        self.servers.lock().push(tokio::spawn(async move {
             axum::serve(rest_listener, router.into_make_service_with_connect_info::<SocketAddr>())
+                .with_graceful_shutdown(async move { shutdown.wait().await })
                 .await
                 .expect("couldn't start rest server");
-        }))
+        }));
+        Ok(())
+    }
+}
+
+impl<N: Network, C: ConsensusStorage<N>, R: Routing<N>> Rest<N, C, R> {
+    /// Returns the router of the GraphQL endpoint, over the same ledger queries as the REST routes.
+    #[cfg(feature = "graphql")]
+    fn graphql_router(&self) -> axum::Router<Self> {
+        axum::Router::new().route_service("/mainnet/graphql", graphql::service(self.ledger.clone()))
+    }
+
+    /// Returns an empty router, as the GraphQL endpoint is not enabled.
+    #[cfg(not(feature = "graphql"))]
+    fn graphql_router(&self) -> axum::Router<Self> {
+        axum::Router::new()
+    }
+
+    /// Returns the router of the JSON-RPC compatibility endpoint.
+    #[cfg(feature = "json-rpc")]
+    fn json_rpc_router(&self) -> axum::Router<Self> {
+        json_rpc::router()
+    }
+
+    /// Returns an empty router, as the JSON-RPC endpoint is not enabled.
+    #[cfg(not(feature = "json-rpc"))]
+    fn json_rpc_router(&self) -> axum::Router<Self> {
+        axum::Router::new()
     }
 }
 
@@ -13,12 +13,16 @@
 // limitations under the License.
 
 use super::*;
-use snarkos_node_router::messages::UnconfirmedSolution;
+use snarkos_node_router::{messages::UnconfirmedSolution, PeerLimits, BAN_DURATION};
 use snarkvm::{
-    ledger::coinbase::ProverSolution,
-    prelude::{block::Transaction, Identifier, Plaintext},
+    ledger::{
+        coinbase::{ProverSolution, PuzzleCommitment},
+        narwhal::BatchHeader,
+    },
+    prelude::{block::Transaction, Address, Identifier, Plaintext, PrivateKey},
 };
 
+use axum::{extract::ws::WebSocketUpgrade, http::header, response::IntoResponse};
 use indexmap::IndexMap;
 use rayon::prelude::*;
 use serde::{Deserialize, Serialize};
@@ -34,9 +38,64 @@ pub(crate) struct BlockRange {
 }
 
 /// The `get_mapping_value` query object.
-#[derive(Deserialize, Serialize)]
+#[derive(Default, Deserialize, Serialize)]
 pub(crate) struct Metadata {
+    /// Whether to return the value with its block height.
+    #[serde(default)]
     metadata: bool,
+    /// The block height to query the value at, if not the latest one.
+    height: Option<u32>,
+}
+
+/// The `get_records_status` request object.
+#[derive(Deserialize, Serialize)]
+#[serde(bound = "")]
+pub(crate) struct RecordQuery<N: Network> {
+    /// The record commitment.
+    commitment: Option<Field<N>>,
+    /// The record serial number.
+    serial_number: Option<Field<N>>,
+}
+
+impl<N: Network> RecordQuery<N> {
+    /// Returns the status of the record, given whether a serial number and a commitment are in the ledger.
+    fn status(
+        &self,
+        contains_serial_number: impl Fn(&Field<N>) -> Result<bool>,
+        contains_commitment: impl Fn(&Field<N>) -> Result<bool>,
+    ) -> Result<RecordStatus> {
+        // If the serial number is in the ledger, the record is spent.
+        if let Some(serial_number) = &self.serial_number {
+            if contains_serial_number(serial_number)? {
+                return Ok(RecordStatus::Spent);
+            }
+        }
+        // If the commitment is in the ledger, and the serial number is not, the record is unspent.
+        match (&self.commitment, &self.serial_number) {
+            (Some(commitment), Some(_)) if contains_commitment(commitment)? => Ok(RecordStatus::Unspent),
+            _ => Ok(RecordStatus::Unknown),
+        }
+    }
+}
+
+/// The `get_equivocations` query object.
+#[derive(Deserialize, Serialize)]
+#[serde(bound = "")]
+pub(crate) struct EquivocationQuery<N: Network> {
+    /// Only includes the equivocations of the given validator.
+    author: Option<Address<N>>,
+}
+
+/// The `get_records_status` response object.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum RecordStatus {
+    /// The serial number of the record is in the ledger.
+    Spent,
+    /// The commitment of the record is in the ledger, and the given serial number is not.
+    Unspent,
+    /// The status of the record cannot be determined from the given inputs.
+    Unknown,
 }
 
 impl<N: Network, C: ConsensusStorage<N>, R: Routing<N>> Rest<N, C, R> {
@@ -102,9 +161,9 @@ impl<N: Network, C: ConsensusStorage<N>, R: Routing<N>> Rest<N, C, R> {
         let block = if let Ok(height) = height_or_hash.parse::<u32>() {
             rest.ledger.get_block(height)?
         } else {
-            let hash = height_or_hash
-                .parse::<N::BlockHash>()
-                .map_err(|_| RestError("invalid input, it is neither a block height nor a block hash".to_string()))?;
+            let hash = height_or_hash.parse::<N::BlockHash>().map_err(|_| {
+                RestError::invalid_request("invalid input, it is neither a block height nor a block hash")
+            })?;
 
             rest.ledger.get_block_by_hash(&hash)?
         };
@@ -124,15 +183,16 @@ impl<N: Network, C: ConsensusStorage<N>, R: Routing<N>> Rest<N, C, R> {
 
         // Ensure the end height is greater than the start height.
         if start_height > end_height {
-            return Err(RestError("Invalid block range".to_string()));
+            return Err(RestError::invalid_request("Invalid block range"));
         }
 
         // Ensure the block range is bounded.
         if end_height - start_height > MAX_BLOCK_RANGE {
-            return Err(RestError(format!(
+            return Err(RestError::invalid_request(format!(
                 "Cannot request more than {MAX_BLOCK_RANGE} blocks per call (requested {})",
                 end_height - start_height
-            )));
+            ))
+            .with_details(json!({ "max": MAX_BLOCK_RANGE })));
         }
 
         let blocks = cfg_into_iter!((start_height..end_height))
@@ -142,6 +202,153 @@ impl<N: Network, C: ConsensusStorage<N>, R: Routing<N>> Rest<N, C, R> {
         Ok(ErasedJson::pretty(blocks))
     }
 
+    // GET /mainnet/block/timestamp/{timestamp}
+    pub(crate) async fn get_block_by_timestamp(
+        State(rest): State<Self>,
+        Path(timestamp): Path<i64>,
+    ) -> Result<ErasedJson, RestError> {
+        match rest.timestamps.nearest(timestamp) {
+            Some(height) => Ok(ErasedJson::pretty(rest.ledger.get_block(height)?)),
+            None => Err(RestError::unavailable("The block timestamps are not indexed yet")),
+        }
+    }
+
+    // GET /mainnet/blocks/time?start={timestamp}&end={timestamp}
+    pub(crate) async fn get_blocks_by_time(
+        State(rest): State<Self>,
+        Query(time_range): Query<TimeRange>,
+    ) -> Result<ErasedJson, RestError> {
+        // Ensure the end timestamp is not before the start timestamp.
+        if time_range.start > time_range.end {
+            return Err(RestError::invalid_request("Invalid time range"));
+        }
+
+        // Ensure the number of blocks is bounded.
+        let heights = rest.timestamps.range(time_range.start, time_range.end);
+        if heights.len() > MAX_TIME_RANGE_BLOCKS {
+            return Err(RestError::invalid_request(format!(
+                "Cannot request more than {MAX_TIME_RANGE_BLOCKS} blocks per call (the time range has {})",
+                heights.len()
+            ))
+            .with_details(json!({ "max": MAX_TIME_RANGE_BLOCKS })));
+        }
+
+        let blocks =
+            cfg_into_iter!(heights).map(|height| rest.ledger.get_block(height)).collect::<Result<Vec<_>, _>>()?;
+
+        Ok(ErasedJson::pretty(blocks))
+    }
+
+    // GET /mainnet/cdn/latest.json
+    // GET /mainnet/cdn/{start}.{end}.blocks
+    pub(crate) async fn get_cdn_file(State(rest): State<Self>, Path(file): Path<String>) -> Response {
+        // Note: Only the complete bundles are advertised and served, as expected by the CDN client.
+        match snarkos_node_cdn::get_cdn_file(rest.ledger.vm().block_store().clone(), file).await {
+            Ok(bytes) => ([(header::CONTENT_TYPE, "application/octet-stream")], bytes).into_response(),
+            Err(error) => error.into_response(),
+        }
+    }
+
+    // GET /mainnet/fees/estimate?blocks={n}
+    pub(crate) async fn get_fee_estimate(
+        State(rest): State<Self>,
+        Query(query): Query<FeeEstimateQuery>,
+    ) -> Result<ErasedJson, RestError> {
+        let num_blocks = query.blocks()?;
+        let end_height = rest.ledger.latest_height();
+        let start_height = end_height.saturating_sub(num_blocks - 1);
+
+        let blocks = cfg_into_iter!((start_height..=end_height))
+            .map(|height| rest.ledger.get_block(height))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        // Note: Nodes without a memory pool report no pressure.
+        let unconfirmed = rest.consensus.as_ref().map_or(0, |consensus| consensus.num_unconfirmed_transmissions());
+        let pressure = MempoolPressure::new(unconfirmed, BatchHeader::<N>::MAX_TRANSMISSIONS_PER_BATCH);
+
+        Ok(ErasedJson::pretty(estimate_fees(&blocks, pressure)?))
+    }
+
+    // GET /mainnet/blocks/page?start={cursor}&limit={limit}&program={programID}&function={name}&address={address}
+    pub(crate) async fn get_blocks_page(
+        State(rest): State<Self>,
+        Query(query): Query<PageQuery<N>>,
+    ) -> Result<ErasedJson, RestError> {
+        let limit = query.limit()?;
+        let start_height = query.cursor()?.height;
+        let latest_height = rest.ledger.latest_height();
+        let end_height = latest_height.min(start_height.saturating_add(MAX_PAGE_SCANNED_BLOCKS - 1));
+
+        // Scan the blocks until the page is full, or the scan limit or the latest block is reached.
+        let mut items = Vec::new();
+        let mut height = start_height;
+        while height <= end_height && items.len() < limit as usize {
+            let block = rest.ledger.get_block(height)?;
+            // If the query has filters, only include the blocks with a matching transaction.
+            if !query.has_filters() || block.transactions().iter().any(|tx| query.matches(tx.transaction())) {
+                items.push(BlockSummary::from(&block));
+            }
+            height += 1;
+        }
+
+        let next = (height <= latest_height).then(|| Cursor { height, index: 0 }.to_string());
+        Ok(ErasedJson::pretty(Page { items, next }))
+    }
+
+    // GET /mainnet/transactions/page?start={cursor}&limit={limit}&program={programID}&function={name}&address={address}
+    pub(crate) async fn get_transactions_page(
+        State(rest): State<Self>,
+        Query(query): Query<PageQuery<N>>,
+    ) -> Result<ErasedJson, RestError> {
+        let limit = query.limit()?;
+        let mut cursor = query.cursor()?;
+        let latest_height = rest.ledger.latest_height();
+        let end_height = latest_height.min(cursor.height.saturating_add(MAX_PAGE_SCANNED_BLOCKS - 1));
+
+        // Scan the blocks until the page is full, or the scan limit or the latest block is reached.
+        let mut items = Vec::new();
+        'scan: while cursor.height <= end_height {
+            let block = rest.ledger.get_block(cursor.height)?;
+            for confirmed in block.transactions().iter().filter(|tx| tx.index() >= cursor.index) {
+                // If the page is full, resume from this transaction on the next page.
+                if items.len() == limit as usize {
+                    cursor.index = confirmed.index();
+                    break 'scan;
+                }
+                if query.matches(confirmed.transaction()) {
+                    items.push(TransactionSummary::new(cursor.height, confirmed));
+                }
+            }
+            cursor = Cursor { height: cursor.height + 1, index: 0 };
+        }
+
+        let next = (cursor.height <= latest_height).then(|| cursor.to_string());
+        Ok(ErasedJson::pretty(Page { items, next }))
+    }
+
+    // GET /mainnet/address/{address}/transactions?start={cursor}&limit={limit}
+    pub(crate) async fn get_address_transactions(
+        State(rest): State<Self>,
+        Path(address): Path<Address<N>>,
+        Query(query): Query<PageQuery<N>>,
+    ) -> Result<ErasedJson, RestError> {
+        let Some(address_index) = &rest.address_index else {
+            return Err(RestError::unavailable("The address index is not enabled on this node"));
+        };
+        if query.has_filters() {
+            return Err(RestError::invalid_request("The transactions of an address cannot be filtered"));
+        }
+
+        let page = address_index.page(&address, query.cursor()?, query.limit()?);
+        let items = page
+            .items
+            .into_iter()
+            .map(|(cursor, id)| Ok(TransactionSummary::new(cursor.height, &rest.ledger.get_confirmed_transaction(id)?)))
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(ErasedJson::pretty(Page { items, next: page.next }))
+    }
+
     // GET /mainnet/height/{blockHash}
     pub(crate) async fn get_height(
         State(rest): State<Self>,
@@ -158,6 +365,15 @@ impl<N: Network, C: ConsensusStorage<N>, R: Routing<N>> Rest<N, C, R> {
         Ok(ErasedJson::pretty(rest.ledger.get_transactions(height)?))
     }
 
+    // GET /mainnet/block/{height}/dependency-graph
+    pub(crate) async fn get_block_dependency_graph(
+        State(rest): State<Self>,
+        Path(height): Path<u32>,
+    ) -> Result<ErasedJson, RestError> {
+        let block = rest.ledger.get_block(height)?;
+        Ok(ErasedJson::pretty(DependencyGraph::new(&block)))
+    }
+
     // GET /mainnet/transaction/{transactionID}
     pub(crate) async fn get_transaction(
         State(rest): State<Self>,
@@ -166,6 +382,33 @@ impl<N: Network, C: ConsensusStorage<N>, R: Routing<N>> Rest<N, C, R> {
         Ok(ErasedJson::pretty(rest.ledger.get_transaction(tx_id)?))
     }
 
+    // GET /mainnet/transaction/{transactionID}/status
+    pub(crate) async fn get_transaction_status(
+        State(rest): State<Self>,
+        Path(tx_id): Path<N::TransactionID>,
+    ) -> Result<ErasedJson, RestError> {
+        // Return the status of the tracked transaction, if it is resolved.
+        let tracked = rest.transactions.get(&tx_id);
+        if let Some(status) = tracked.as_ref().filter(|status| !status.is_pending()) {
+            return Ok(ErasedJson::pretty(status));
+        }
+        // Check the ledger, for the transactions that were not submitted to this node.
+        if let Some(block_hash) = rest.ledger.find_block_hash(&tx_id)? {
+            let height = rest.ledger.get_height(&block_hash)?;
+            let confirmed = rest.ledger.get_confirmed_transaction(tx_id)?;
+            return Ok(ErasedJson::pretty(TransactionStatus::confirmed(height, &confirmed)));
+        }
+        // Check the memory pool.
+        let is_unconfirmed = match &rest.consensus {
+            Some(consensus) => consensus.unconfirmed_transactions().any(|(id, _)| id == tx_id),
+            None => false,
+        };
+        match is_unconfirmed || tracked.is_some() {
+            true => Ok(ErasedJson::pretty(TransactionStatus::Pending)),
+            false => Err(RestError::not_found(format!("Transaction '{tx_id}' is unknown to this node"))),
+        }
+    }
+
     // GET /mainnet/transaction/confirmed/{transactionID}
     pub(crate) async fn get_confirmed_transaction(
         State(rest): State<Self>,
@@ -180,7 +423,7 @@ impl<N: Network, C: ConsensusStorage<N>, R: Routing<N>> Rest<N, C, R> {
             Some(consensus) => {
                 Ok(ErasedJson::pretty(consensus.unconfirmed_transmissions().collect::<IndexMap<_, _>>()))
             }
-            None => Err(RestError("Route isn't available for this node type".to_string())),
+            None => Err(RestError::unavailable("Route isn't available for this node type")),
         }
     }
 
@@ -188,7 +431,7 @@ impl<N: Network, C: ConsensusStorage<N>, R: Routing<N>> Rest<N, C, R> {
     pub(crate) async fn get_memory_pool_solutions(State(rest): State<Self>) -> Result<ErasedJson, RestError> {
         match rest.consensus {
             Some(consensus) => Ok(ErasedJson::pretty(consensus.unconfirmed_solutions().collect::<IndexMap<_, _>>())),
-            None => Err(RestError("Route isn't available for this node type".to_string())),
+            None => Err(RestError::unavailable("Route isn't available for this node type")),
         }
     }
 
@@ -196,7 +439,98 @@ impl<N: Network, C: ConsensusStorage<N>, R: Routing<N>> Rest<N, C, R> {
     pub(crate) async fn get_memory_pool_transactions(State(rest): State<Self>) -> Result<ErasedJson, RestError> {
         match rest.consensus {
             Some(consensus) => Ok(ErasedJson::pretty(consensus.unconfirmed_transactions().collect::<IndexMap<_, _>>())),
-            None => Err(RestError("Route isn't available for this node type".to_string())),
+            None => Err(RestError::unavailable("Route isn't available for this node type")),
+        }
+    }
+
+    // GET /mainnet/memoryPool/solutions/summary
+    pub(crate) async fn get_memory_pool_solution_summaries(State(rest): State<Self>) -> Result<ErasedJson, RestError> {
+        match rest.consensus {
+            Some(consensus) => {
+                let summaries = consensus
+                    .unconfirmed_solutions()
+                    .map(|(commitment, solution)| {
+                        let age = consensus.unconfirmed_solution_age(&commitment);
+                        MempoolSolution::new(&solution.deserialize_blocking()?, age)
+                    })
+                    .collect::<Result<Vec<_>>>()?;
+                Ok(ErasedJson::pretty(summaries))
+            }
+            None => Err(RestError::unavailable("Route isn't available for this node type")),
+        }
+    }
+
+    // GET /mainnet/solutions/pending
+    pub(crate) async fn get_pending_solutions(State(rest): State<Self>) -> Result<ErasedJson, RestError> {
+        let proof_target = rest.ledger.latest_proof_target();
+        // Retrieve the solutions pending in the memory pool, if the node has one.
+        let pending = match &rest.consensus {
+            Some(consensus) => Some(
+                consensus
+                    .unconfirmed_solutions()
+                    .map(|(commitment, solution)| {
+                        let age = consensus.unconfirmed_solution_age(&commitment);
+                        PendingSolution::new(&solution.deserialize_blocking()?, age, proof_target)
+                    })
+                    .collect::<Result<Vec<_>>>()?,
+            ),
+            None => None,
+        };
+        Ok(ErasedJson::pretty(PuzzleStatus::new(&rest.ledger, pending)?))
+    }
+
+    // GET /mainnet/memoryPool/transactions/summary
+    pub(crate) async fn get_memory_pool_transaction_summaries(
+        State(rest): State<Self>,
+    ) -> Result<ErasedJson, RestError> {
+        match rest.consensus {
+            Some(consensus) => {
+                let summaries = consensus
+                    .unconfirmed_transactions()
+                    .map(|(tx_id, transaction)| {
+                        let age = consensus.unconfirmed_transaction_age(&tx_id);
+                        MempoolTransaction::new(&transaction.deserialize_blocking()?, age)
+                    })
+                    .collect::<Result<Vec<_>>>()?;
+                Ok(ErasedJson::pretty(summaries))
+            }
+            None => Err(RestError::unavailable("Route isn't available for this node type")),
+        }
+    }
+
+    // GET /mainnet/memoryPool/solution/{commitment}
+    pub(crate) async fn get_memory_pool_solution(
+        State(rest): State<Self>,
+        Path(commitment): Path<PuzzleCommitment<N>>,
+    ) -> Result<ErasedJson, RestError> {
+        let Some(consensus) = rest.consensus else {
+            return Err(RestError::unavailable("Route isn't available for this node type"));
+        };
+        match consensus.unconfirmed_solutions().find(|(id, _)| *id == commitment) {
+            Some((_, solution)) => {
+                let solution = solution.deserialize_blocking()?;
+                let summary = MempoolSolution::new(&solution, consensus.unconfirmed_solution_age(&commitment))?;
+                Ok(ErasedJson::pretty(json!({ "summary": summary, "solution": solution })))
+            }
+            None => Err(RestError::not_found(format!("Solution '{commitment}' is not in the memory pool"))),
+        }
+    }
+
+    // GET /mainnet/memoryPool/transaction/{transactionID}
+    pub(crate) async fn get_memory_pool_transaction(
+        State(rest): State<Self>,
+        Path(tx_id): Path<N::TransactionID>,
+    ) -> Result<ErasedJson, RestError> {
+        let Some(consensus) = rest.consensus else {
+            return Err(RestError::unavailable("Route isn't available for this node type"));
+        };
+        match consensus.unconfirmed_transactions().find(|(id, _)| *id == tx_id) {
+            Some((_, transaction)) => {
+                let transaction = transaction.deserialize_blocking()?;
+                let summary = MempoolTransaction::new(&transaction, consensus.unconfirmed_transaction_age(&tx_id))?;
+                Ok(ErasedJson::pretty(json!({ "summary": summary, "transaction": transaction })))
+            }
+            None => Err(RestError::not_found(format!("Transaction '{tx_id}' is not in the memory pool"))),
         }
     }
 
@@ -208,6 +542,14 @@ impl<N: Network, C: ConsensusStorage<N>, R: Routing<N>> Rest<N, C, R> {
         Ok(ErasedJson::pretty(rest.ledger.get_program(id)?))
     }
 
+    // GET /mainnet/program/{programID}/description
+    pub(crate) async fn get_program_description(
+        State(rest): State<Self>,
+        Path(id): Path<ProgramID<N>>,
+    ) -> Result<ErasedJson, RestError> {
+        Ok(ErasedJson::pretty(&*rest.programs.get(&rest.ledger, &id)?))
+    }
+
     // GET /mainnet/program/{programID}/mappings
     pub(crate) async fn get_mapping_names(
         State(rest): State<Self>,
@@ -223,14 +565,26 @@ impl<N: Network, C: ConsensusStorage<N>, R: Routing<N>> Rest<N, C, R> {
         Path((id, name, key)): Path<(ProgramID<N>, Identifier<N>, Plaintext<N>)>,
         metadata: Option<Query<Metadata>>,
     ) -> Result<ErasedJson, RestError> {
-        // Retrieve the mapping value.
-        let mapping_value = rest.ledger.vm().finalize_store().get_value_confirmed(id, name, &key)?;
+        let metadata = metadata.map(|Query(metadata)| metadata).unwrap_or_default();
+        let latest_height = rest.ledger.latest_height();
+
+        // Retrieve the mapping value, from the state history if a past height is requested.
+        let (mapping_value, height) = match metadata.height {
+            Some(height) if height > latest_height => {
+                return Err(RestError::not_found(format!("Block {height} is not in the ledger yet")));
+            }
+            Some(height) if height < latest_height => match &rest.state_history {
+                Some(state_history) => (state_history.get(id, name, key, height)?, height),
+                None => return Err(RestError::unavailable("The state history is not enabled on this node")),
+            },
+            _ => (rest.ledger.vm().finalize_store().get_value_confirmed(id, name, &key)?, latest_height),
+        };
 
         // Check if metadata is requested and return the value with metadata if so.
-        if metadata.map(|q| q.metadata).unwrap_or(false) {
+        if metadata.metadata {
             return Ok(ErasedJson::pretty(json!({
                 "data": mapping_value,
-                "height": rest.ledger.latest_height(),
+                "height": height,
             })));
         }
 
@@ -238,6 +592,64 @@ impl<N: Network, C: ConsensusStorage<N>, R: Routing<N>> Rest<N, C, R> {
         Ok(ErasedJson::pretty(mapping_value))
     }
 
+    // POST /mainnet/batch
+    pub(crate) async fn batch(
+        State(rest): State<Self>,
+        Json(requests): Json<Vec<BatchRequest<N>>>,
+    ) -> Result<ErasedJson, RestError> {
+        // Ensure the number of sub-requests is bounded.
+        check_batch_size(requests.len())?;
+
+        // Execute the sub-requests, returning the responses in the same order.
+        let responses = cfg_iter!(requests).map(|request| request.execute(&rest.ledger)).collect::<Vec<_>>();
+
+        Ok(ErasedJson::pretty(responses))
+    }
+
+    // POST /mainnet/records/status
+    pub(crate) async fn get_records_status(
+        State(rest): State<Self>,
+        Json(queries): Json<Vec<RecordQuery<N>>>,
+    ) -> Result<ErasedJson, RestError> {
+        const MAX_RECORD_QUERIES: usize = 1000;
+
+        // Ensure the number of queries is bounded.
+        if queries.len() > MAX_RECORD_QUERIES {
+            return Err(RestError::invalid_request(format!(
+                "Cannot request more than {MAX_RECORD_QUERIES} records per call (requested {})",
+                queries.len()
+            ))
+            .with_details(json!({ "max": MAX_RECORD_QUERIES })));
+        }
+
+        let statuses = queries
+            .iter()
+            .map(|query| {
+                query.status(
+                    |serial_number| rest.ledger.contains_serial_number(serial_number),
+                    |commitment| rest.ledger.contains_commitment(commitment),
+                )
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(ErasedJson::pretty(statuses))
+    }
+
+    // POST /mainnet/records/scan
+    pub(crate) async fn scan_records(
+        State(rest): State<Self>,
+        Json(request): Json<RecordScanRequest<N>>,
+    ) -> Result<ErasedJson, RestError> {
+        request.check().map_err(|error| RestError::invalid_request(error.to_string()))?;
+
+        // Scanning and decrypting the records is expensive, so it is run on a blocking thread.
+        let page = tokio::task::spawn_blocking(move || request.scan(&rest.ledger))
+            .await
+            .map_err(|error| RestError::internal(error.to_string()))??;
+
+        Ok(ErasedJson::pretty(page))
+    }
+
     // GET /mainnet/statePath/{commitment}
     pub(crate) async fn get_state_path_for_commitment(
         State(rest): State<Self>,
@@ -256,6 +668,47 @@ impl<N: Network, C: ConsensusStorage<N>, R: Routing<N>> Rest<N, C, R> {
         Ok(ErasedJson::pretty(rest.ledger.latest_committee()?))
     }
 
+    // GET /mainnet/committee/stake
+    pub(crate) async fn get_committee_stake(State(rest): State<Self>) -> Result<ErasedJson, RestError> {
+        let committee = CommitteeView::new(&rest.ledger.latest_committee()?).with_bonded_state(&rest.ledger)?;
+        Ok(ErasedJson::pretty(committee))
+    }
+
+    // GET /mainnet/committee/round/{round}
+    pub(crate) async fn get_committee_for_round(
+        State(rest): State<Self>,
+        Path(round): Path<u64>,
+    ) -> Result<ErasedJson, RestError> {
+        match rest.ledger.get_committee_for_round(round)? {
+            Some(committee) => Ok(ErasedJson::pretty(CommitteeView::new(&committee))),
+            None => Err(RestError::not_found(format!("No committee found for round {round}"))),
+        }
+    }
+
+    // GET /mainnet/validators/equivocations?author={address}
+    pub(crate) async fn get_equivocations(
+        State(rest): State<Self>,
+        Query(query): Query<EquivocationQuery<N>>,
+    ) -> Result<ErasedJson, RestError> {
+        match rest.consensus {
+            Some(consensus) => {
+                Ok(ErasedJson::pretty(consensus.bft().storage().equivocations().equivocations(query.author)))
+            }
+            None => Err(RestError::unavailable("Route isn't available for this node type")),
+        }
+    }
+
+    // GET /mainnet/committee/height/{height}
+    pub(crate) async fn get_committee_for_height(
+        State(rest): State<Self>,
+        Path(height): Path<u32>,
+    ) -> Result<ErasedJson, RestError> {
+        match rest.ledger.get_committee(height)? {
+            Some(committee) => Ok(ErasedJson::pretty(CommitteeView::new(&committee))),
+            None => Err(RestError::not_found(format!("No committee found for block {height}"))),
+        }
+    }
+
     // GET /mainnet/peers/count
     pub(crate) async fn get_peers_count(State(rest): State<Self>) -> ErasedJson {
         ErasedJson::pretty(rest.routing.router().number_of_connected_peers())
@@ -271,11 +724,238 @@ impl<N: Network, C: ConsensusStorage<N>, R: Routing<N>> Rest<N, C, R> {
         ErasedJson::pretty(rest.routing.router().connected_metrics())
     }
 
+    // GET /mainnet/peers/sync
+    pub(crate) async fn get_peers_sync(State(rest): State<Self>) -> Result<ErasedJson, RestError> {
+        match rest.routing.peer_sync_statuses() {
+            Some(statuses) => Ok(ErasedJson::pretty(statuses)),
+            None => Err(RestError::unavailable("Route isn't available for this node type")),
+        }
+    }
+
     // GET /mainnet/node/address
     pub(crate) async fn get_node_address(State(rest): State<Self>) -> ErasedJson {
         ErasedJson::pretty(rest.routing.router().address())
     }
 
+    // POST /mainnet/admin/peers/connect
+    pub(crate) async fn admin_connect_peer(
+        State(rest): State<Self>,
+        Json(request): Json<AdminPeerRequest>,
+    ) -> Result<ErasedJson, RestError> {
+        rest.admin()?;
+        let connected = match rest.routing.router().connect(request.ip) {
+            Some(handle) => handle.await.unwrap_or(false),
+            None => false,
+        };
+        Ok(ErasedJson::pretty(json!({ "peer": request.ip, "connected": connected })))
+    }
+
+    // POST /mainnet/admin/peers/disconnect
+    pub(crate) async fn admin_disconnect_peer(
+        State(rest): State<Self>,
+        Json(request): Json<AdminPeerRequest>,
+    ) -> Result<ErasedJson, RestError> {
+        rest.admin()?;
+        let disconnected = rest.routing.router().disconnect(request.ip).await.unwrap_or(false);
+        Ok(ErasedJson::pretty(json!({ "peer": request.ip, "disconnected": disconnected })))
+    }
+
+    // GET /mainnet/admin/peers/scores
+    pub(crate) async fn admin_get_peer_scores(State(rest): State<Self>) -> Result<ErasedJson, RestError> {
+        rest.admin()?;
+        Ok(ErasedJson::pretty(rest.routing.router().reputation().scores()))
+    }
+
+    // GET /mainnet/admin/peers/states
+    pub(crate) async fn admin_get_peer_states(State(rest): State<Self>) -> Result<ErasedJson, RestError> {
+        rest.admin()?;
+        Ok(ErasedJson::pretty(rest.routing.router().connection_states()))
+    }
+
+    // GET /mainnet/admin/peers/topology
+    // GET /mainnet/admin/peers/topology?format=dot
+    pub(crate) async fn admin_get_topology(
+        State(rest): State<Self>,
+        Query(query): Query<AdminTopologyQuery>,
+    ) -> Result<Response, RestError> {
+        rest.admin()?;
+        let topology = rest.routing.router().topology();
+        match query.format {
+            TopologyFormat::Json => Ok(ErasedJson::pretty(topology).into_response()),
+            TopologyFormat::Dot => {
+                Ok(([(header::CONTENT_TYPE, "text/vnd.graphviz")], topology.to_dot()).into_response())
+            }
+        }
+    }
+
+    // POST /mainnet/admin/peers/ban
+    pub(crate) async fn admin_ban_peer(
+        State(rest): State<Self>,
+        Json(request): Json<AdminBanRequest>,
+    ) -> Result<ErasedJson, RestError> {
+        rest.admin()?;
+        let duration = request.ttl_secs.map_or(BAN_DURATION, Duration::from_secs);
+        if duration.is_zero() {
+            return Err(RestError::invalid_request("The ban duration must be greater than 0 seconds"));
+        }
+        rest.routing.router().ban_peer(request.ip, duration);
+        Ok(ErasedJson::pretty(json!({ "peer": request.ip, "banned_for_secs": duration.as_secs() })))
+    }
+
+    // POST /mainnet/admin/peers/unban
+    pub(crate) async fn admin_unban_peer(
+        State(rest): State<Self>,
+        Json(request): Json<AdminPeerRequest>,
+    ) -> Result<ErasedJson, RestError> {
+        rest.admin()?;
+        let unbanned = rest.routing.router().reputation().unban(&request.ip);
+        Ok(ErasedJson::pretty(json!({ "peer": request.ip, "unbanned": unbanned })))
+    }
+
+    // GET /mainnet/admin/peers/limits
+    pub(crate) async fn admin_get_peer_limits(State(rest): State<Self>) -> Result<ErasedJson, RestError> {
+        rest.admin()?;
+        Ok(ErasedJson::pretty(rest.routing.router().peer_limits()))
+    }
+
+    // POST /mainnet/admin/peers/limits
+    pub(crate) async fn admin_set_peer_limits(
+        State(rest): State<Self>,
+        Json(peer_limits): Json<PeerLimits>,
+    ) -> Result<ErasedJson, RestError> {
+        rest.admin()?;
+        if let Err(error) = rest.routing.router().set_peer_limits(peer_limits) {
+            return Err(RestError::invalid_request(error.to_string()));
+        }
+        Ok(ErasedJson::pretty(peer_limits))
+    }
+
+    // POST /mainnet/admin/peers/access/reload
+    pub(crate) async fn admin_reload_access_lists(State(rest): State<Self>) -> Result<ErasedJson, RestError> {
+        rest.admin()?;
+        match rest.routing.router().reload_access_lists() {
+            Ok((allowed, denied)) => {
+                Ok(ErasedJson::pretty(json!({ "allowed_subnets": allowed, "denied_subnets": denied })))
+            }
+            Err(error) => Err(RestError::invalid_request(error.to_string())),
+        }
+    }
+
+    // POST /mainnet/admin/log
+    pub(crate) async fn admin_set_log_filter(
+        State(rest): State<Self>,
+        Json(request): Json<AdminLogRequest>,
+    ) -> Result<ErasedJson, RestError> {
+        let set_log_filter = rest
+            .admin()?
+            .set_log_filter
+            .ok_or_else(|| RestError::unavailable("The log filter can't be changed for this node"))?;
+        set_log_filter(&request.filter)?;
+        info!("Changed the log filter to '{}'", request.filter);
+        Ok(ErasedJson::pretty(json!({ "filter": request.filter })))
+    }
+
+    // POST /mainnet/admin/validator/signer
+    pub(crate) async fn admin_rotate_signer(
+        State(rest): State<Self>,
+        Json(request): Json<AdminSignerRequest>,
+    ) -> Result<ErasedJson, RestError> {
+        rest.admin()?;
+        let consensus = rest.consensus.as_ref().ok_or_else(|| RestError::unavailable("The node is not a validator"))?;
+        // Schedule the rotation to the new signer.
+        let result = match (request.private_key, request.remote_signer) {
+            (Some(private_key), None) => match private_key.parse::<PrivateKey<N>>() {
+                Ok(private_key) => consensus.rotate_to_private_key(private_key),
+                Err(_) => return Err(RestError::invalid_request("The private key is malformed")),
+            },
+            (None, Some(url)) => consensus.rotate_to_remote_signer(&url).await,
+            _ => return Err(RestError::invalid_request("Exactly one of 'private_key' or 'remote_signer' must be set")),
+        };
+        match result {
+            Ok((address, round)) => Ok(ErasedJson::pretty(json!({ "address": address, "round": round }))),
+            Err(error) => Err(RestError::invalid_request(error.to_string())),
+        }
+    }
+
+    // POST /mainnet/admin/sync/pause
+    pub(crate) async fn admin_pause_sync(State(rest): State<Self>) -> Result<ErasedJson, RestError> {
+        rest.admin()?;
+        rest.routing.set_block_sync_paused(true)?;
+        info!("Paused the block sync");
+        Ok(ErasedJson::pretty(json!({ "paused": true })))
+    }
+
+    // POST /mainnet/admin/sync/resume
+    pub(crate) async fn admin_resume_sync(State(rest): State<Self>) -> Result<ErasedJson, RestError> {
+        rest.admin()?;
+        rest.routing.set_block_sync_paused(false)?;
+        info!("Resumed the block sync");
+        Ok(ErasedJson::pretty(json!({ "paused": false })))
+    }
+
+    // GET /mainnet/node/info
+    pub(crate) async fn get_node_info(State(rest): State<Self>) -> ErasedJson {
+        ErasedJson::pretty(&*rest.node_info)
+    }
+
+    // GET /health/live
+    pub(crate) async fn get_health_live() -> ErasedJson {
+        ErasedJson::pretty(json!({ "status": "live" }))
+    }
+
+    // GET /health/ready
+    pub(crate) async fn get_health_ready(State(rest): State<Self>) -> (StatusCode, ErasedJson) {
+        let router = rest.routing.router();
+        // Retrieve the sync status and peer connectivity of the node.
+        let sync = SyncHealth::new(rest.ledger.latest_height(), rest.routing.greatest_peer_block_height());
+        let peers = PeerHealth { connected: router.number_of_connected_peers() };
+        // Check that the storage is readable.
+        let storage = StorageHealth::check(&rest.ledger);
+        // Retrieve the BFT participation, if the node is a validator.
+        let bft = rest.consensus.as_ref().map(|consensus| {
+            let bft = consensus.bft();
+            BftHealth {
+                is_synced: bft.is_synced(),
+                is_committee_member: rest
+                    .ledger
+                    .latest_committee()
+                    .map_or(false, |committee| committee.is_committee_member(bft.primary().gateway().address())),
+                current_round: bft.primary().current_round(),
+                leader: bft.leader(),
+                connected_validators: bft.primary().gateway().number_of_connected_peers(),
+            }
+        });
+
+        let report = HealthReport::new(sync, peers, storage, bft);
+        (report.status_code(), ErasedJson::pretty(report))
+    }
+
+    // GET /mainnet/events
+    pub(crate) async fn get_events(
+        State(rest): State<Self>,
+        headers: HeaderMap,
+        Query(query): Query<SseQuery>,
+    ) -> Result<Response, RestError> {
+        let topics = SseTopics::new(query.topics.as_deref()).map_err(|e| RestError::invalid_request(e.to_string()))?;
+        // Resume the stream after the last event received by a reconnecting client, if any.
+        let last_event_id = match headers.get(LAST_EVENT_ID_HEADER) {
+            Some(id) => Some(
+                id.to_str()
+                    .map_err(|_| RestError::invalid_request("Invalid Last-Event-ID header"))?
+                    .parse()
+                    .map_err(|e: anyhow::Error| RestError::invalid_request(e.to_string()))?,
+            ),
+            None => None,
+        };
+        Ok(sse_response(rest.ledger, query.from_height, topics, last_event_id))
+    }
+
+    // GET /mainnet/ws
+    pub(crate) async fn ws_subscribe(State(rest): State<Self>, ws: WebSocketUpgrade) -> Response {
+        let session = WsSession::new(rest.ledger, rest.consensus);
+        ws.on_upgrade(move |socket| session.run(socket))
+    }
+
     // GET /mainnet/find/blockHash/{transactionID}
     pub(crate) async fn find_block_hash(
         State(rest): State<Self>,
@@ -313,23 +993,20 @@ impl<N: Network, C: ConsensusStorage<N>, R: Routing<N>> Rest<N, C, R> {
         State(rest): State<Self>,
         Json(tx): Json<Transaction<N>>,
     ) -> Result<ErasedJson, RestError> {
-        // If the consensus module is enabled, add the unconfirmed transaction to the memory pool.
-        if let Some(consensus) = rest.consensus {
-            // Add the unconfirmed transaction to the memory pool.
-            consensus.add_unconfirmed_transaction(tx.clone()).await?;
-        }
-
-        // Prepare the unconfirmed transaction message.
-        let tx_id = tx.id();
-        let message = Message::UnconfirmedTransaction(UnconfirmedTransaction {
-            transaction_id: tx_id,
-            transaction: Data::Object(tx),
-        });
+        Ok(ErasedJson::pretty(rest.broadcast_transaction(tx).await?))
+    }
 
-        // Broadcast the transaction.
-        rest.routing.propagate(message, &[]);
+    // POST /mainnet/transaction/simulate
+    pub(crate) async fn transaction_simulate(
+        State(rest): State<Self>,
+        Json(tx): Json<Transaction<N>>,
+    ) -> Result<ErasedJson, RestError> {
+        // Verifying and speculating the transaction is expensive, so it is run on a blocking thread.
+        let simulation = tokio::task::spawn_blocking(move || simulate(&rest.ledger, &tx))
+            .await
+            .map_err(|error| RestError::internal(error.to_string()))??;
 
-        Ok(ErasedJson::pretty(tx_id))
+        Ok(ErasedJson::pretty(simulation))
     }
 
     // POST /mainnet/solution/broadcast
@@ -340,7 +1017,10 @@ impl<N: Network, C: ConsensusStorage<N>, R: Routing<N>> Rest<N, C, R> {
         // If the consensus module is enabled, add the unconfirmed solution to the memory pool.
         if let Some(consensus) = rest.consensus {
             // Add the unconfirmed solution to the memory pool.
-            consensus.add_unconfirmed_solution(prover_solution).await?;
+            consensus
+                .add_unconfirmed_solution(prover_solution)
+                .await
+                .map_err(|error| RestError::rejected(error.to_string()))?;
         }
 
         let commitment = prover_solution.commitment();
@@ -356,3 +1036,50 @@ impl<N: Network, C: ConsensusStorage<N>, R: Routing<N>> Rest<N, C, R> {
         Ok(ErasedJson::pretty(commitment))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkvm::prelude::MainnetV0;
+
+    use anyhow::bail;
+
+    type CurrentNetwork = MainnetV0;
+
+    #[test]
+    fn test_record_status() {
+        let spent = Field::<CurrentNetwork>::from_u64(1);
+        let unspent = Field::<CurrentNetwork>::from_u64(2);
+        let commitment = Field::<CurrentNetwork>::from_u64(3);
+        let missing = Field::<CurrentNetwork>::from_u64(4);
+
+        // Returns the status of the record, given the serial number and commitment in the sample ledger.
+        let status = |commitment: Option<Field<CurrentNetwork>>, serial_number: Option<Field<CurrentNetwork>>| {
+            RecordQuery { commitment, serial_number }
+                .status(|serial_number| Ok(*serial_number == spent), |candidate| Ok(*candidate == commitment))
+                .unwrap()
+        };
+
+        // Ensure a record is spent once its serial number is in the ledger, even without a commitment.
+        assert_eq!(status(Some(commitment), Some(spent)), RecordStatus::Spent);
+        assert_eq!(status(None, Some(spent)), RecordStatus::Spent);
+        // Ensure a record is unspent if its commitment is in the ledger, and its serial number is not.
+        assert_eq!(status(Some(commitment), Some(unspent)), RecordStatus::Unspent);
+        // Ensure the status is unknown without a serial number, or if the commitment is not in the ledger.
+        assert_eq!(status(Some(commitment), None), RecordStatus::Unknown);
+        assert_eq!(status(Some(missing), Some(unspent)), RecordStatus::Unknown);
+        assert_eq!(status(None, Some(unspent)), RecordStatus::Unknown);
+        assert_eq!(status(None, None), RecordStatus::Unknown);
+
+        // Ensure the errors of the ledger are returned.
+        let query = RecordQuery::<CurrentNetwork> { commitment: Some(commitment), serial_number: Some(unspent) };
+        assert!(query.status(|_| bail!("Failed to read the serial numbers"), |_| Ok(true)).is_err());
+        assert!(query.status(|_| Ok(false), |_| bail!("Failed to read the commitments")).is_err());
+    }
+
+    #[test]
+    fn test_record_status_serialization() {
+        let statuses = [RecordStatus::Spent, RecordStatus::Unspent, RecordStatus::Unknown];
+        assert_eq!(serde_json::to_string(&statuses).unwrap(), r#"["spent","unspent","unknown"]"#);
+    }
+}
@@ -14,7 +14,7 @@
 
 use super::*;
 
-use snarkvm::prelude::{FromBytes, ToBytes};
+use snarkvm::prelude::{error, FromBytes, ToBytes};
 
 use std::borrow::Cow;
 
@@ -25,6 +25,12 @@ pub struct ChallengeRequest<N: Network> {
     pub node_type: NodeType,
     pub address: Address<N>,
     pub nonce: u64,
+    /// The software version of the node.
+    pub node_version: NodeVersion,
+    /// The user agent of the node (e.g. `snarkOS/2.2.7`).
+    pub user_agent: String,
+    /// The optional features supported by the node.
+    pub capabilities: Capabilities,
 }
 
 impl<N: Network> MessageTrait for ChallengeRequest<N> {
@@ -42,6 +48,14 @@ impl<N: Network> ToBytes for ChallengeRequest<N> {
         self.node_type.write_le(&mut writer)?;
         self.address.write_le(&mut writer)?;
         self.nonce.write_le(&mut writer)?;
+        self.node_version.write_le(&mut writer)?;
+        // Ensure the user agent is within the length limit.
+        if self.user_agent.len() > MAX_USER_AGENT_LENGTH {
+            return Err(error(format!("The user agent exceeds {MAX_USER_AGENT_LENGTH} bytes")));
+        }
+        (self.user_agent.len() as u16).write_le(&mut writer)?;
+        writer.write_all(self.user_agent.as_bytes())?;
+        self.capabilities.write_le(&mut writer)?;
         Ok(())
     }
 }
@@ -53,20 +67,46 @@ impl<N: Network> FromBytes for ChallengeRequest<N> {
         let node_type = NodeType::read_le(&mut reader)?;
         let address = Address::<N>::read_le(&mut reader)?;
         let nonce = u64::read_le(&mut reader)?;
-
-        Ok(Self { version, listener_port, node_type, address, nonce })
+        let node_version = NodeVersion::read_le(&mut reader)?;
+        // Ensure the user agent is within the length limit.
+        let user_agent_length = u16::read_le(&mut reader)? as usize;
+        if user_agent_length > MAX_USER_AGENT_LENGTH {
+            return Err(error(format!("The user agent exceeds {MAX_USER_AGENT_LENGTH} bytes")));
+        }
+        let mut user_agent = vec![0u8; user_agent_length];
+        reader.read_exact(&mut user_agent)?;
+        let user_agent = String::from_utf8(user_agent).map_err(|_| error("The user agent is not valid UTF-8"))?;
+        let capabilities = Capabilities::read_le(&mut reader)?;
+
+        Ok(Self { version, listener_port, node_type, address, nonce, node_version, user_agent, capabilities })
     }
 }
 
 impl<N: Network> ChallengeRequest<N> {
-    pub fn new(listener_port: u16, node_type: NodeType, address: Address<N>, nonce: u64) -> Self {
-        Self { version: Message::<N>::VERSION, listener_port, node_type, address, nonce }
+    pub fn new(
+        listener_port: u16,
+        node_type: NodeType,
+        address: Address<N>,
+        nonce: u64,
+        user_agent: String,
+        capabilities: Capabilities,
+    ) -> Self {
+        Self {
+            version: Message::<N>::VERSION,
+            listener_port,
+            node_type,
+            address,
+            nonce,
+            node_version: NodeVersion::current(),
+            user_agent,
+            capabilities,
+        }
     }
 }
 
 #[cfg(test)]
 pub mod prop_tests {
-    use crate::{ChallengeRequest, NodeType};
+    use crate::{Capabilities, ChallengeRequest, NodeType, NodeVersion};
     use snarkvm::{
         console::prelude::{FromBytes, ToBytes},
         prelude::{Address, TestRng, Uniform},
@@ -93,14 +133,32 @@ pub mod prop_tests {
             .boxed()
     }
 
+    pub fn any_node_version() -> BoxedStrategy<NodeVersion> {
+        any::<(u16, u16, u16)>().prop_map(|(major, minor, patch)| NodeVersion::new(major, minor, patch)).boxed()
+    }
+
     pub fn any_challenge_request() -> BoxedStrategy<ChallengeRequest<CurrentNetwork>> {
-        (any_valid_address(), any::<u64>(), any::<u32>(), any::<u16>(), any_node_type())
-            .prop_map(|(address, nonce, version, listener_port, node_type)| ChallengeRequest {
-                address,
-                nonce,
-                version,
-                listener_port,
-                node_type,
+        (
+            any_valid_address(),
+            any::<u64>(),
+            any::<u32>(),
+            any::<u16>(),
+            any_node_type(),
+            any_node_version(),
+            "[ -~]{0,64}",
+            any::<u32>().prop_map(Capabilities::from_bits),
+        )
+            .prop_map(|(address, nonce, version, listener_port, node_type, node_version, user_agent, capabilities)| {
+                ChallengeRequest {
+                    address,
+                    nonce,
+                    version,
+                    listener_port,
+                    node_type,
+                    node_version,
+                    user_agent,
+                    capabilities,
+                }
             })
             .boxed()
     }
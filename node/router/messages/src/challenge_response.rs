@@ -26,6 +26,8 @@ pub struct ChallengeResponse<N: Network> {
     pub genesis_header: Header<N>,
     pub signature: Data<Signature<N>>,
     pub nonce: u64,
+    /// The address of the peer, as observed by the sender (i.e. the source address of the connection).
+    pub observed_addr: SocketAddr,
 }
 
 impl<N: Network> MessageTrait for ChallengeResponse<N> {
@@ -40,7 +42,8 @@ impl<N: Network> ToBytes for ChallengeResponse<N> {
     fn write_le<W: io::Write>(&self, mut writer: W) -> io::Result<()> {
         self.genesis_header.write_le(&mut writer)?;
         self.signature.write_le(&mut writer)?;
-        self.nonce.write_le(&mut writer)
+        self.nonce.write_le(&mut writer)?;
+        self.observed_addr.write_le(&mut writer)
     }
 }
 
@@ -49,14 +52,15 @@ impl<N: Network> FromBytes for ChallengeResponse<N> {
         Ok(Self {
             genesis_header: Header::read_le(&mut reader)?,
             signature: Data::read_le(&mut reader)?,
-            nonce: u64::read_le(reader)?,
+            nonce: u64::read_le(&mut reader)?,
+            observed_addr: SocketAddr::read_le(&mut reader)?,
         })
     }
 }
 
 #[cfg(test)]
 pub mod prop_tests {
-    use crate::ChallengeResponse;
+    use crate::{peer_response::prop_tests::any_valid_socket_addr, ChallengeResponse};
     use snarkvm::{
         console::prelude::{FromBytes, ToBytes},
         ledger::{ledger_test_helpers::sample_genesis_block, narwhal::Data},
@@ -86,11 +90,12 @@ pub mod prop_tests {
     }
 
     pub fn any_challenge_response() -> BoxedStrategy<ChallengeResponse<CurrentNetwork>> {
-        (any_signature(), any_genesis_header(), any::<u64>())
-            .prop_map(|(sig, genesis_header, nonce)| ChallengeResponse {
+        (any_signature(), any_genesis_header(), any::<u64>(), any_valid_socket_addr())
+            .prop_map(|(sig, genesis_header, nonce, observed_addr)| ChallengeResponse {
                 signature: Data::Object(sig),
                 genesis_header,
                 nonce,
+                observed_addr,
             })
             .boxed()
     }
@@ -104,6 +109,7 @@ pub mod prop_tests {
             ChallengeResponse::read_le(buf.into_inner().reader()).unwrap();
 
         assert_eq!(original.genesis_header, deserialized.genesis_header);
+        assert_eq!(original.observed_addr, deserialized.observed_addr);
         assert_eq!(
             original.signature.deserialize_blocking().unwrap(),
             deserialized.signature.deserialize_blocking().unwrap()
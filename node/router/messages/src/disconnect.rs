@@ -78,6 +78,7 @@ mod tests {
             DisconnectReason::TooManyPeers,
             DisconnectReason::YouNeedToSyncFirst,
             DisconnectReason::YourPortIsClosed(TestRng::default().gen()),
+            DisconnectReason::IncompatiblePeer,
         ];
 
         for reason in all_reasons.iter() {
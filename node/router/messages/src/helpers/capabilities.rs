@@ -0,0 +1,148 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use snarkvm::prelude::{FromBytes, ToBytes};
+
+use core::{fmt, ops::BitOr, str::FromStr};
+use serde::{Deserialize, Serialize};
+use std::io;
+
+/// The optional features supported by a node, as advertised during the handshake.
+///
+/// Unknown flags are preserved, so that nodes can advertise capabilities introduced in later versions.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Capabilities(u32);
+
+impl Capabilities {
+    /// No capabilities.
+    pub const NONE: Self = Self(0);
+    /// The node compresses messages with zstd, if the peer does too.
+    pub const COMPRESSION: Self = Self(1 << 0);
+    /// The node serves the archived blocks (i.e. it does not prune its ledger).
+    pub const ARCHIVE: Self = Self(1 << 1);
+    /// The node serves the blocks in the CDN format.
+    pub const CDN: Self = Self(1 << 2);
+    /// The node relays the connection setup between peers that are behind a NAT.
+    pub const RELAY: Self = Self(1 << 3);
+    /// The node encrypts the connection with a noise handshake, if the peer does too.
+    pub const NOISE: Self = Self(1 << 4);
+    /// The node connects to the peers introduced by a relay, through TCP hole punching.
+    pub const HOLE_PUNCHING: Self = Self(1 << 5);
+    /// The known capabilities and their names.
+    const NAMES: [(Self, &'static str); 6] = [
+        (Self::COMPRESSION, "compression"),
+        (Self::ARCHIVE, "archive"),
+        (Self::CDN, "cdn"),
+        (Self::RELAY, "relay"),
+        (Self::NOISE, "noise"),
+        (Self::HOLE_PUNCHING, "hole-punching"),
+    ];
+
+    /// Returns the capabilities with the given flags.
+    pub const fn from_bits(bits: u32) -> Self {
+        Self(bits)
+    }
+
+    /// Returns the flags of the capabilities.
+    pub const fn bits(&self) -> u32 {
+        self.0
+    }
+
+    /// Returns `true` if all of the given capabilities are included.
+    pub const fn contains(&self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    /// Adds the given capabilities.
+    pub fn insert(&mut self, other: Self) {
+        self.0 |= other.0;
+    }
+
+    /// Returns the names of the known capabilities that are included.
+    pub fn names(&self) -> Vec<&'static str> {
+        Self::NAMES.iter().filter(|(capability, _)| self.contains(*capability)).map(|(_, name)| *name).collect()
+    }
+}
+
+impl BitOr for Capabilities {
+    type Output = Self;
+
+    fn bitor(self, other: Self) -> Self {
+        Self(self.0 | other.0)
+    }
+}
+
+impl fmt::Display for Capabilities {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.names().join(","))
+    }
+}
+
+impl FromStr for Capabilities {
+    type Err = anyhow::Error;
+
+    /// Parses a comma-separated list of capability names (e.g. `compression,archive`).
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut capabilities = Self::NONE;
+        for name in s.split(',').map(str::trim).filter(|name| !name.is_empty()) {
+            match Self::NAMES.iter().find(|(_, candidate)| *candidate == name) {
+                Some((capability, _)) => capabilities.insert(*capability),
+                None => anyhow::bail!("Unknown capability '{name}'"),
+            }
+        }
+        Ok(capabilities)
+    }
+}
+
+impl ToBytes for Capabilities {
+    fn write_le<W: io::Write>(&self, writer: W) -> io::Result<()> {
+        self.0.write_le(writer)
+    }
+}
+
+impl FromBytes for Capabilities {
+    fn read_le<R: io::Read>(reader: R) -> io::Result<Self> {
+        Ok(Self(u32::read_le(reader)?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_capabilities() {
+        let capabilities = Capabilities::COMPRESSION | Capabilities::CDN;
+        assert!(capabilities.contains(Capabilities::COMPRESSION));
+        assert!(!capabilities.contains(Capabilities::ARCHIVE));
+        assert!(!capabilities.contains(Capabilities::RELAY));
+        assert!(capabilities.contains(Capabilities::NONE));
+        assert_eq!(capabilities.to_string(), "compression,cdn");
+
+        // Check the parsing of the capability names.
+        assert_eq!(Capabilities::from_str("cdn, compression").unwrap(), capabilities);
+        assert_eq!(
+            Capabilities::from_str("noise,hole-punching").unwrap(),
+            Capabilities::NOISE | Capabilities::HOLE_PUNCHING
+        );
+        assert_eq!(Capabilities::from_str("").unwrap(), Capabilities::NONE);
+        assert!(Capabilities::from_str("compression,teleportation").is_err());
+
+        // Check that unknown flags are preserved.
+        let unknown = Capabilities::from_bits(1 << 31 | 1);
+        let bytes = unknown.to_bytes_le().unwrap();
+        assert_eq!(Capabilities::read_le(&bytes[..]).unwrap(), unknown);
+        assert_eq!(unknown.names(), vec!["compression"]);
+    }
+}
@@ -15,19 +15,50 @@
 use crate::Message;
 use snarkvm::prelude::{FromBytes, Network, ToBytes};
 
-use ::bytes::{Buf, BufMut, BytesMut};
+use ::bytes::{Buf, BufMut, Bytes, BytesMut};
 use core::marker::PhantomData;
+use std::{
+    io::{self, Read},
+    sync::OnceLock,
+};
 use tokio_util::codec::{Decoder, Encoder, LengthDelimitedCodec};
 
 /// The maximum size of a message that can be transmitted during the handshake.
-const MAXIMUM_HANDSHAKE_MESSAGE_SIZE: usize = 1024 * 1024; // 1 MiB
+pub const MAXIMUM_HANDSHAKE_MESSAGE_SIZE: usize = 1024 * 1024; // 1 MiB
 
 /// The maximum size of a message that can be transmitted in the network.
-pub(crate) const MAXIMUM_MESSAGE_SIZE: usize = 128 * 1024 * 1024; // 128 MiB
+pub const MAXIMUM_MESSAGE_SIZE: usize = 128 * 1024 * 1024; // 128 MiB
+
+/// The maximum size of a message used by default, if it was set.
+static DEFAULT_MAXIMUM_MESSAGE_SIZE: OnceLock<usize> = OnceLock::new();
+
+/// Sets the maximum size of a message used by default (i.e. in [`MessageCodec::default`]), so that it applies
+/// to every codec created afterwards. Larger frames are rejected before they are buffered, and larger compressed
+/// messages before they are decompressed. Returns `false` if it was already set.
+pub fn set_default_maximum_message_size(size: usize) -> bool {
+    DEFAULT_MAXIMUM_MESSAGE_SIZE.set(size).is_ok()
+}
+
+/// Returns the maximum size of a message used by default, i.e. [`MAXIMUM_MESSAGE_SIZE`] unless it was lowered.
+pub fn default_maximum_message_size() -> usize {
+    DEFAULT_MAXIMUM_MESSAGE_SIZE.get().copied().unwrap_or(MAXIMUM_MESSAGE_SIZE)
+}
+
+/// The minimum size of a serialized message for it to be compressed.
+const COMPRESSION_THRESHOLD: usize = 1024; // 1 KiB
+/// The zstd compression level.
+const COMPRESSION_LEVEL: i32 = 3;
+
+/// The flag prefixed to an uncompressed message, when compression is enabled.
+const FLAG_UNCOMPRESSED: u8 = 0;
+/// The flag prefixed to a zstd-compressed message, when compression is enabled.
+const FLAG_ZSTD: u8 = 1;
 
 /// The codec used to decode and encode network `Message`s.
 pub struct MessageCodec<N: Network> {
     codec: LengthDelimitedCodec,
+    /// The boolean flag indicating whether every frame is prefixed with a compression flag.
+    compression: bool,
     _phantom: PhantomData<N>,
 }
 
@@ -37,12 +68,32 @@ impl<N: Network> MessageCodec<N> {
         codec.codec.set_max_frame_length(MAXIMUM_HANDSHAKE_MESSAGE_SIZE);
         codec
     }
+
+    /// Returns a codec that compresses the messages of at least [`COMPRESSION_THRESHOLD`] bytes with zstd.
+    /// Note: Both peers must use a compressing codec, as every frame is prefixed with a compression flag.
+    pub fn compressed() -> Self {
+        Self { compression: true, ..Default::default() }
+    }
+
+    /// Returns `true` if the codec compresses the messages.
+    pub const fn is_compressed(&self) -> bool {
+        self.compression
+    }
+
+    /// Returns the maximum length of a frame, excluding its length prefix.
+    pub fn max_frame_length(&self) -> usize {
+        self.codec.max_frame_length()
+    }
 }
 
 impl<N: Network> Default for MessageCodec<N> {
     fn default() -> Self {
         Self {
-            codec: LengthDelimitedCodec::builder().max_frame_length(MAXIMUM_MESSAGE_SIZE).little_endian().new_codec(),
+            codec: LengthDelimitedCodec::builder()
+                .max_frame_length(default_maximum_message_size())
+                .little_endian()
+                .new_codec(),
+            compression: false,
             _phantom: Default::default(),
         }
     }
@@ -59,6 +110,11 @@ impl<N: Network> Encoder<Message<N>> for MessageCodec<N> {
             .map_err(|_| std::io::Error::new(std::io::ErrorKind::InvalidData, "serialization error"))?;
 
         let serialized_message = dst.split_to(dst.len()).freeze();
+        // Compress the payload, if enabled.
+        let serialized_message = match self.compression {
+            true => compress(serialized_message)?,
+            false => serialized_message,
+        };
 
         self.codec.encode(serialized_message, dst)
     }
@@ -74,6 +130,11 @@ impl<N: Network> Decoder for MessageCodec<N> {
             Some(bytes) => bytes,
             None => return Ok(None),
         };
+        // Decompress the payload, if enabled.
+        let bytes = match self.compression {
+            true => decompress(bytes, self.codec.max_frame_length())?,
+            false => bytes.freeze(),
+        };
 
         // Convert the bytes to a message, or fail if it is not valid.
         let reader = bytes.reader();
@@ -86,3 +147,91 @@ impl<N: Network> Decoder for MessageCodec<N> {
         }
     }
 }
+
+/// Prefixes the given serialized message with a compression flag, compressing it with zstd
+/// if it is large enough and the compressed message is smaller.
+fn compress(bytes: Bytes) -> io::Result<Bytes> {
+    if bytes.len() >= COMPRESSION_THRESHOLD {
+        let mut compressed = vec![FLAG_ZSTD];
+        zstd::stream::copy_encode(&bytes[..], &mut compressed, COMPRESSION_LEVEL)?;
+        if compressed.len() <= bytes.len() {
+            return Ok(compressed.into());
+        }
+    }
+    let mut uncompressed = BytesMut::with_capacity(1 + bytes.len());
+    uncompressed.put_u8(FLAG_UNCOMPRESSED);
+    uncompressed.extend_from_slice(&bytes);
+    Ok(uncompressed.freeze())
+}
+
+/// Strips the compression flag from the given frame, decompressing the message if it was compressed.
+/// The decompressed message may not exceed the given maximum size.
+fn decompress(mut bytes: BytesMut, max_size: usize) -> io::Result<Bytes> {
+    if bytes.is_empty() {
+        return Err(io::Error::new(io::ErrorKind::InvalidData, "missing compression flag"));
+    }
+    match bytes.get_u8() {
+        FLAG_UNCOMPRESSED => Ok(bytes.freeze()),
+        FLAG_ZSTD => {
+            // Bound the decompressed size, to guard against decompression bombs.
+            let mut decompressed = Vec::new();
+            zstd::stream::read::Decoder::new(&bytes[..])?.take(max_size as u64 + 1).read_to_end(&mut decompressed)?;
+            if decompressed.len() > max_size {
+                return Err(io::Error::new(io::ErrorKind::InvalidData, "decompressed message is too large"));
+            }
+            Ok(decompressed.into())
+        }
+        flag => Err(io::Error::new(io::ErrorKind::InvalidData, format!("unknown compression flag {flag}"))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{PeerRequest, PeerResponse};
+
+    use std::net::{Ipv4Addr, SocketAddr};
+
+    type CurrentNetwork = snarkvm::prelude::MainnetV0;
+
+    fn roundtrip(codec: &mut MessageCodec<CurrentNetwork>, message: Message<CurrentNetwork>) -> usize {
+        let mut buffer = BytesMut::new();
+        codec.encode(message.clone(), &mut buffer).unwrap();
+        let frame_len = buffer.len();
+        assert_eq!(codec.decode(&mut buffer).unwrap(), Some(message));
+        assert!(buffer.is_empty());
+        frame_len
+    }
+
+    #[test]
+    fn test_compressed_roundtrip() {
+        let peers = vec![SocketAddr::new(Ipv4Addr::LOCALHOST.into(), 4130); 255];
+        let large = Message::<CurrentNetwork>::PeerResponse(PeerResponse { peers });
+        let small = Message::<CurrentNetwork>::PeerRequest(PeerRequest);
+
+        // Check that the large message is compressed, and the small message only gains the flag.
+        let large_len = roundtrip(&mut MessageCodec::default(), large.clone());
+        assert!(roundtrip(&mut MessageCodec::compressed(), large) < large_len);
+        let small_len = roundtrip(&mut MessageCodec::default(), small.clone());
+        assert_eq!(roundtrip(&mut MessageCodec::compressed(), small), small_len + 1);
+    }
+
+    #[test]
+    fn test_decompress_rejects_invalid_frames() {
+        // Check that an empty frame, an unknown flag, and a corrupt payload are rejected.
+        assert!(decompress(BytesMut::new(), MAXIMUM_MESSAGE_SIZE).is_err());
+        assert!(decompress(BytesMut::from(&[2u8, 0][..]), MAXIMUM_MESSAGE_SIZE).is_err());
+        assert!(decompress(BytesMut::from(&[FLAG_ZSTD, 1, 2, 3][..]), MAXIMUM_MESSAGE_SIZE).is_err());
+    }
+
+    #[test]
+    fn test_decompress_rejects_oversized_messages() {
+        // Compress a message of 64 KiB.
+        let compressed = compress(Bytes::from(vec![0u8; 64 * 1024])).unwrap();
+        assert_eq!(compressed[0], FLAG_ZSTD);
+
+        // Check that the message is rejected once decompressed, if it exceeds the maximum size.
+        assert_eq!(decompress(BytesMut::from(&compressed[..]), 64 * 1024).unwrap().len(), 64 * 1024);
+        assert!(decompress(BytesMut::from(&compressed[..]), 64 * 1024 - 1).is_err());
+    }
+}
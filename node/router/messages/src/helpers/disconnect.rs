@@ -49,6 +49,8 @@ pub enum DisconnectReason {
     YouNeedToSyncFirst,
     /// The peer's listening port is closed.
     YourPortIsClosed(u16),
+    /// The peer's node version, user agent, or capabilities are rejected by the handshake policy.
+    IncompatiblePeer,
 }
 
 impl ToBytes for DisconnectReason {
@@ -72,6 +74,7 @@ impl ToBytes for DisconnectReason {
                 14u8.write_le(&mut writer)?;
                 port.write_le(writer)
             }
+            Self::IncompatiblePeer => 15u8.write_le(writer),
         }
     }
 }
@@ -97,6 +100,7 @@ impl FromBytes for DisconnectReason {
                 let port = u16::read_le(reader)?;
                 Ok(Self::YourPortIsClosed(port))
             }
+            15 => Ok(Self::IncompatiblePeer),
             _ => Err(error("Invalid disconnect reason")),
         }
     }
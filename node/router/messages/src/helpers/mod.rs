@@ -12,11 +12,26 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+mod capabilities;
+pub use capabilities::Capabilities;
+
 mod codec;
-pub use codec::MessageCodec;
+pub use codec::{
+    default_maximum_message_size,
+    set_default_maximum_message_size,
+    MessageCodec,
+    MAXIMUM_HANDSHAKE_MESSAGE_SIZE,
+    MAXIMUM_MESSAGE_SIZE,
+};
 
 mod disconnect;
 pub use disconnect::DisconnectReason;
 
 mod node_type;
 pub use node_type::*;
+
+mod node_version;
+pub use node_version::*;
+
+mod noise;
+pub use noise::*;
@@ -0,0 +1,105 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use snarkvm::prelude::{FromBytes, ToBytes};
+
+use core::{fmt, str::FromStr};
+use serde::{Deserialize, Serialize};
+use std::io;
+
+/// The maximum length of the user agent of a node, in bytes.
+pub const MAX_USER_AGENT_LENGTH: usize = 256;
+
+/// Returns the user agent of this node (e.g. `snarkOS/2.2.7`).
+pub fn default_user_agent() -> String {
+    format!("snarkOS/{}", env!("CARGO_PKG_VERSION"))
+}
+
+/// The software version of a node (as opposed to the version of the message protocol).
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct NodeVersion {
+    /// The major version.
+    pub major: u16,
+    /// The minor version.
+    pub minor: u16,
+    /// The patch version.
+    pub patch: u16,
+}
+
+impl NodeVersion {
+    /// Initializes a new node version.
+    pub const fn new(major: u16, minor: u16, patch: u16) -> Self {
+        Self { major, minor, patch }
+    }
+
+    /// Returns the version of this node.
+    pub fn current() -> Self {
+        Self::from_str(env!("CARGO_PKG_VERSION")).expect("The crate version is not a valid node version")
+    }
+}
+
+impl fmt::Display for NodeVersion {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+impl FromStr for NodeVersion {
+    type Err = anyhow::Error;
+
+    /// Parses a version of the form `major.minor.patch`, ignoring any pre-release or build suffix.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let core = s.trim().split(['-', '+']).next().unwrap_or_default();
+        let components = core.split('.').map(u16::from_str).collect::<Result<Vec<_>, _>>()?;
+        match components[..] {
+            [major, minor, patch] => Ok(Self { major, minor, patch }),
+            _ => anyhow::bail!("Invalid node version '{s}' (expected 'major.minor.patch')"),
+        }
+    }
+}
+
+impl ToBytes for NodeVersion {
+    fn write_le<W: io::Write>(&self, mut writer: W) -> io::Result<()> {
+        self.major.write_le(&mut writer)?;
+        self.minor.write_le(&mut writer)?;
+        self.patch.write_le(&mut writer)
+    }
+}
+
+impl FromBytes for NodeVersion {
+    fn read_le<R: io::Read>(mut reader: R) -> io::Result<Self> {
+        let major = u16::read_le(&mut reader)?;
+        let minor = u16::read_le(&mut reader)?;
+        let patch = u16::read_le(&mut reader)?;
+        Ok(Self { major, minor, patch })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_node_version() {
+        assert_eq!(NodeVersion::from_str("2.2.7").unwrap(), NodeVersion::new(2, 2, 7));
+        assert_eq!(NodeVersion::from_str("3.0.0-rc.1").unwrap(), NodeVersion::new(3, 0, 0));
+        assert!(NodeVersion::from_str("2.2").is_err());
+        assert!(NodeVersion::from_str("two.2.7").is_err());
+
+        // Check the ordering of the versions.
+        assert!(NodeVersion::new(2, 10, 0) > NodeVersion::new(2, 9, 9));
+        assert!(NodeVersion::current() >= NodeVersion::new(2, 0, 0));
+        assert_eq!(NodeVersion::current().to_string(), env!("CARGO_PKG_VERSION"));
+    }
+}
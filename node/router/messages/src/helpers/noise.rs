@@ -0,0 +1,359 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::{Message, MessageCodec};
+use snarkvm::prelude::Network;
+
+use ::bytes::BytesMut;
+use rayon::{
+    iter::{IndexedParallelIterator, ParallelIterator},
+    prelude::ParallelSlice,
+};
+use snow::{HandshakeState, StatelessTransportState};
+use std::{fmt, io, sync::Arc};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::TcpStream,
+};
+use tokio_util::codec::{Decoder, Encoder, LengthDelimitedCodec};
+
+/// The type of noise handshake to use for network encryption.
+pub const NOISE_HANDSHAKE_TYPE: &str = "Noise_XX_25519_ChaChaPoly_BLAKE2s";
+
+// The maximum message size for noise messages. If the data to be encrypted exceeds it, it is chunked.
+const MAX_NOISE_MESSAGE_LEN: usize = 65535;
+/// The length of the authentication data appended to every noise transport message.
+const TAG_LEN: usize = 16;
+
+/// Returns an `InvalidData` error for the given noise error.
+fn noise_error(error: snow::Error) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, error)
+}
+
+/// Performs the noise handshake over the given stream, returning the transport state of the connection.
+///
+/// Each side uses a fresh static key, so the handshake only establishes the encryption keys;
+/// the peers authenticate each other afterwards, by signing the handshake hash with their address keys.
+/// Note: The handshake is only performed if both peers advertised `Capabilities::NOISE`.
+pub async fn noise_handshake(stream: &mut TcpStream, is_initiator: bool) -> io::Result<NoiseState> {
+    // Initialize the handshake state.
+    let builder = snow::Builder::new(NOISE_HANDSHAKE_TYPE.parse().map_err(noise_error)?);
+    let keypair = builder.generate_keypair().map_err(noise_error)?;
+    let builder = builder.local_private_key(&keypair.private);
+    let mut handshake = match is_initiator {
+        true => builder.build_initiator(),
+        false => builder.build_responder(),
+    }
+    .map_err(noise_error)?;
+
+    // Exchange the handshake messages, each prefixed with its length.
+    let mut buffer = vec![0u8; MAX_NOISE_MESSAGE_LEN];
+    while !handshake.is_handshake_finished() {
+        if handshake.is_my_turn() {
+            let len = handshake.write_message(&[], &mut buffer).map_err(noise_error)?;
+            stream.write_u16_le(len as u16).await?;
+            stream.write_all(&buffer[..len]).await?;
+        } else {
+            let len = stream.read_u16_le().await? as usize;
+            let mut message = vec![0u8; len];
+            stream.read_exact(&mut message).await?;
+            handshake.read_message(&message, &mut buffer).map_err(noise_error)?;
+        }
+    }
+    NoiseState::new(handshake)
+}
+
+/// The transport state of a connection, once the noise handshake is finished.
+#[derive(Clone)]
+pub struct NoiseState {
+    /// The transport state, shared by the reading and writing halves of the connection.
+    state: Arc<StatelessTransportState>,
+    /// The hash of the noise handshake, which is unique to the connection.
+    handshake_hash: Arc<[u8]>,
+    /// The nonce of the next outbound noise message.
+    tx_nonce: u64,
+    /// The nonce of the next inbound noise message.
+    rx_nonce: u64,
+}
+
+impl NoiseState {
+    /// Initializes the transport state from the given finished handshake.
+    fn new(handshake: HandshakeState) -> io::Result<Self> {
+        let handshake_hash = handshake.get_handshake_hash().into();
+        let state = handshake.into_stateless_transport_mode().map_err(noise_error)?;
+        Ok(Self { state: Arc::new(state), handshake_hash, tx_nonce: 0, rx_nonce: 0 })
+    }
+
+    /// Returns the hash of the noise handshake, which is unique to the connection.
+    pub fn handshake_hash(&self) -> &[u8] {
+        &self.handshake_hash
+    }
+}
+
+impl fmt::Debug for NoiseState {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("NoiseState").field("tx_nonce", &self.tx_nonce).field("rx_nonce", &self.rx_nonce).finish()
+    }
+}
+
+/// Returns the bytes signed in a challenge response, binding the given nonces to the connection,
+/// if the connection is encrypted.
+pub fn challenge_data(nonce: u64, response_nonce: u64, noise_state: Option<&NoiseState>) -> Vec<u8> {
+    let handshake_hash = noise_state.map_or(&[][..], NoiseState::handshake_hash);
+    [&nonce.to_le_bytes()[..], &response_nonce.to_le_bytes()[..], handshake_hash].concat()
+}
+
+/// The transport of a connection.
+enum Transport {
+    /// The messages are exchanged in plaintext, as the noise handshake was not negotiated.
+    Plaintext,
+    /// The messages are encrypted with the transport state of the noise handshake.
+    Encrypted(NoiseState),
+    /// The transport state is no longer available, so every message is rejected.
+    Failed,
+}
+
+/// The codec used to encrypt and decrypt network `Message`s, once the noise handshake is finished.
+/// If the peers did not negotiate the noise handshake, the messages are exchanged in plaintext.
+pub struct NoiseCodec<N: Network> {
+    codec: LengthDelimitedCodec,
+    message_codec: MessageCodec<N>,
+    transport: Transport,
+}
+
+impl<N: Network> NoiseCodec<N> {
+    /// Initializes a new noise codec, encrypting the messages encoded by the given message codec.
+    pub fn new(noise_state: NoiseState, message_codec: MessageCodec<N>) -> Self {
+        Self { transport: Transport::Encrypted(noise_state), ..Self::failed(message_codec) }
+    }
+
+    /// Initializes a noise codec that exchanges the messages encoded by the given message codec in plaintext.
+    pub fn plaintext(message_codec: MessageCodec<N>) -> Self {
+        Self { transport: Transport::Plaintext, ..Self::failed(message_codec) }
+    }
+
+    /// Initializes a noise codec with the given transport state, or in plaintext if there is none.
+    pub fn with_transport(noise_state: Option<NoiseState>, message_codec: MessageCodec<N>) -> Self {
+        match noise_state {
+            Some(noise_state) => Self::new(noise_state, message_codec),
+            None => Self::plaintext(message_codec),
+        }
+    }
+
+    /// Initializes a noise codec without a transport state, which rejects every message.
+    /// This is used for connections whose transport state is no longer available (e.g. a dropped peer).
+    pub fn failed(message_codec: MessageCodec<N>) -> Self {
+        // Bound the ciphertext by the maximum frame of the message codec (including its length prefix).
+        let plaintext_len = message_codec.max_frame_length() + 4;
+        let num_chunks = plaintext_len.div_ceil(MAX_NOISE_MESSAGE_LEN - TAG_LEN);
+        let codec = LengthDelimitedCodec::builder()
+            .max_frame_length(plaintext_len + num_chunks * TAG_LEN)
+            .little_endian()
+            .new_codec();
+        Self { codec, message_codec, transport: Transport::Failed }
+    }
+
+    /// Returns the transport state of the connection, if it is encrypted.
+    pub fn noise_state(&self) -> Option<&NoiseState> {
+        match &self.transport {
+            Transport::Encrypted(noise_state) => Some(noise_state),
+            Transport::Plaintext | Transport::Failed => None,
+        }
+    }
+}
+
+impl<N: Network> Encoder<Message<N>> for NoiseCodec<N> {
+    type Error = io::Error;
+
+    fn encode(&mut self, message: Message<N>, dst: &mut BytesMut) -> Result<(), Self::Error> {
+        let noise = match &mut self.transport {
+            Transport::Encrypted(noise) => noise,
+            // If the connection is not encrypted, encode the message using the message codec only.
+            Transport::Plaintext => return self.message_codec.encode(message, dst),
+            Transport::Failed => {
+                return Err(io::Error::new(io::ErrorKind::NotConnected, "missing noise transport state"));
+            }
+        };
+
+        // Encode the message using the message codec.
+        let mut plaintext = BytesMut::new();
+        self.message_codec.encode(message, &mut plaintext)?;
+
+        // Chunk the payload if necessary and encrypt with Noise.
+        //
+        // A Noise transport message is simply an AEAD ciphertext that is less than or
+        // equal to 65535 bytes in length, and that consists of an encrypted payload plus
+        // 16 bytes of authentication data.
+        //
+        // See: https://noiseprotocol.org/noise.html#the-handshakestate-object
+        let encrypted_chunks = plaintext
+            .par_chunks(MAX_NOISE_MESSAGE_LEN - TAG_LEN)
+            .enumerate()
+            .map(|(nonce_offset, plaintext_chunk)| {
+                let mut buffer = vec![0u8; MAX_NOISE_MESSAGE_LEN];
+                let len = noise
+                    .state
+                    .write_message(noise.tx_nonce + nonce_offset as u64, plaintext_chunk, &mut buffer)
+                    .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+                buffer.truncate(len);
+                Ok(buffer)
+            })
+            .collect::<io::Result<Vec<Vec<u8>>>>()?;
+
+        let mut ciphertext = BytesMut::with_capacity(plaintext.len() + encrypted_chunks.len() * TAG_LEN);
+        for chunk in encrypted_chunks {
+            ciphertext.extend_from_slice(&chunk);
+            noise.tx_nonce += 1;
+        }
+
+        // Encode the resulting ciphertext using the length-delimited codec.
+        self.codec.encode(ciphertext.freeze(), dst)
+    }
+}
+
+impl<N: Network> Decoder for NoiseCodec<N> {
+    type Error = io::Error;
+    type Item = Message<N>;
+
+    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
+        let noise = match &mut self.transport {
+            Transport::Encrypted(noise) => noise,
+            // If the connection is not encrypted, decode the message using the message codec only.
+            Transport::Plaintext => return self.message_codec.decode(src),
+            Transport::Failed => {
+                return Err(io::Error::new(io::ErrorKind::NotConnected, "missing noise transport state"));
+            }
+        };
+
+        // Decode the ciphertext with the length-delimited codec.
+        let Some(bytes) = self.codec.decode(src)? else {
+            return Ok(None);
+        };
+
+        // Decrypt the ciphertext in chunks.
+        let decrypted_chunks = bytes
+            .par_chunks(MAX_NOISE_MESSAGE_LEN)
+            .enumerate()
+            .map(|(nonce_offset, encrypted_chunk)| {
+                let mut buffer = vec![0u8; MAX_NOISE_MESSAGE_LEN];
+                let len = noise
+                    .state
+                    .read_message(noise.rx_nonce + nonce_offset as u64, encrypted_chunk, &mut buffer)
+                    .map_err(|_| io::ErrorKind::InvalidData)?;
+                buffer.truncate(len);
+                Ok(buffer)
+            })
+            .collect::<io::Result<Vec<Vec<u8>>>>()?;
+
+        // Collect the chunks into the plaintext to be passed to the message codec.
+        let mut plaintext = BytesMut::new();
+        for chunk in decrypted_chunks {
+            plaintext.extend_from_slice(&chunk);
+            noise.rx_nonce += 1;
+        }
+
+        // Decode the message, which must span the entire plaintext.
+        match self.message_codec.decode(&mut plaintext)? {
+            Some(message) if plaintext.is_empty() => Ok(Some(message)),
+            _ => Err(io::Error::new(io::ErrorKind::InvalidData, "malformed noise message")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{PeerRequest, PeerResponse};
+
+    use std::net::{Ipv4Addr, SocketAddr};
+    use tokio::net::TcpListener;
+
+    type CurrentNetwork = snarkvm::prelude::MainnetV0;
+
+    /// Returns the transport states of both sides of a connection, after the noise handshake.
+    async fn sample_noise_states() -> (NoiseState, NoiseState) {
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let responder = tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            noise_handshake(&mut stream, false).await.unwrap()
+        });
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+        let initiator = noise_handshake(&mut stream, true).await.unwrap();
+        (initiator, responder.await.unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_noise_roundtrip() {
+        let (initiator, responder) = sample_noise_states().await;
+        // Check that both sides agree on the handshake hash.
+        assert_eq!(initiator.handshake_hash(), responder.handshake_hash());
+        assert_eq!(challenge_data(1, 2, Some(&initiator)), challenge_data(1, 2, Some(&responder)));
+        // Check that the challenge data is bound to the connection, if it is encrypted.
+        assert_ne!(challenge_data(1, 2, Some(&initiator)), challenge_data(1, 2, None));
+
+        let mut sender = NoiseCodec::<CurrentNetwork>::new(initiator, MessageCodec::default());
+        let mut receiver = NoiseCodec::<CurrentNetwork>::new(responder, MessageCodec::default());
+
+        // Check that messages spanning one and several noise messages are decrypted, in order.
+        let peers = vec![SocketAddr::new(Ipv4Addr::LOCALHOST.into(), 4130); 255];
+        let messages = vec![
+            Message::<CurrentNetwork>::PeerRequest(PeerRequest),
+            Message::<CurrentNetwork>::PeerResponse(PeerResponse { peers }),
+            Message::<CurrentNetwork>::PeerRequest(PeerRequest),
+        ];
+        for message in messages {
+            let mut buffer = BytesMut::new();
+            sender.encode(message.clone(), &mut buffer).unwrap();
+            assert_eq!(receiver.decode(&mut buffer).unwrap(), Some(message));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_noise_rejects_tampering() {
+        let (initiator, responder) = sample_noise_states().await;
+        let mut sender = NoiseCodec::<CurrentNetwork>::new(initiator, MessageCodec::default());
+        let mut receiver = NoiseCodec::<CurrentNetwork>::new(responder, MessageCodec::default());
+
+        // Flip a bit of the ciphertext, and check that the message is rejected.
+        let mut buffer = BytesMut::new();
+        sender.encode(Message::PeerRequest(PeerRequest), &mut buffer).unwrap();
+        let last = buffer.len() - 1;
+        buffer[last] ^= 1;
+        assert!(receiver.decode(&mut buffer).is_err());
+    }
+
+    #[test]
+    fn test_plaintext_codec() {
+        let mut sender = NoiseCodec::<CurrentNetwork>::plaintext(MessageCodec::default());
+        let mut receiver = NoiseCodec::<CurrentNetwork>::with_transport(None, MessageCodec::default());
+        assert!(receiver.noise_state().is_none());
+
+        // Check that the messages are framed by the message codec only.
+        let mut buffer = BytesMut::new();
+        sender.encode(Message::PeerRequest(PeerRequest), &mut buffer).unwrap();
+        let mut expected = BytesMut::new();
+        MessageCodec::<CurrentNetwork>::default().encode(Message::PeerRequest(PeerRequest), &mut expected).unwrap();
+        assert_eq!(buffer, expected);
+        assert_eq!(receiver.decode(&mut buffer).unwrap(), Some(Message::PeerRequest(PeerRequest)));
+    }
+
+    #[test]
+    fn test_failed_codec_rejects_messages() {
+        let mut codec = NoiseCodec::<CurrentNetwork>::failed(MessageCodec::default());
+        assert!(codec.noise_state().is_none());
+        assert!(codec.encode(Message::PeerRequest(PeerRequest), &mut BytesMut::new()).is_err());
+        assert!(codec.decode(&mut BytesMut::from(&[4u8, 0, 0, 0, 1, 2, 3, 4][..])).is_err());
+    }
+}
@@ -53,6 +53,12 @@ pub use puzzle_request::PuzzleRequest;
 mod puzzle_response;
 pub use puzzle_response::PuzzleResponse;
 
+mod relay_introduction;
+pub use relay_introduction::RelayIntroduction;
+
+mod relay_request;
+pub use relay_request::RelayRequest;
+
 mod unconfirmed_solution;
 pub use unconfirmed_solution::UnconfirmedSolution;
 
@@ -99,6 +105,8 @@ pub enum Message<N: Network> {
     Pong(Pong),
     PuzzleRequest(PuzzleRequest),
     PuzzleResponse(PuzzleResponse<N>),
+    RelayIntroduction(RelayIntroduction),
+    RelayRequest(RelayRequest),
     UnconfirmedSolution(UnconfirmedSolution<N>),
     UnconfirmedTransaction(UnconfirmedTransaction<N>),
 }
@@ -111,7 +119,7 @@ impl<N: Network> From<DisconnectReason> for Message<N> {
 
 impl<N: Network> Message<N> {
     /// The version of the network protocol; it can be incremented in order to force users to update.
-    pub const VERSION: u32 = 14;
+    pub const VERSION: u32 = 15;
 
     /// Returns the message name.
     #[inline]
@@ -128,6 +136,8 @@ impl<N: Network> Message<N> {
             Self::Pong(message) => message.name(),
             Self::PuzzleRequest(message) => message.name(),
             Self::PuzzleResponse(message) => message.name(),
+            Self::RelayIntroduction(message) => message.name(),
+            Self::RelayRequest(message) => message.name(),
             Self::UnconfirmedSolution(message) => message.name(),
             Self::UnconfirmedTransaction(message) => message.name(),
         }
@@ -150,6 +160,8 @@ impl<N: Network> Message<N> {
             Self::PuzzleResponse(..) => 10,
             Self::UnconfirmedSolution(..) => 11,
             Self::UnconfirmedTransaction(..) => 12,
+            Self::RelayRequest(..) => 13,
+            Self::RelayIntroduction(..) => 14,
         }
     }
 }
@@ -170,6 +182,8 @@ impl<N: Network> ToBytes for Message<N> {
             Self::Pong(message) => message.write_le(writer),
             Self::PuzzleRequest(message) => message.write_le(writer),
             Self::PuzzleResponse(message) => message.write_le(writer),
+            Self::RelayIntroduction(message) => message.write_le(writer),
+            Self::RelayRequest(message) => message.write_le(writer),
             Self::UnconfirmedSolution(message) => message.write_le(writer),
             Self::UnconfirmedTransaction(message) => message.write_le(writer),
         }
@@ -198,7 +212,9 @@ impl<N: Network> FromBytes for Message<N> {
             10 => Self::PuzzleResponse(PuzzleResponse::read_le(&mut reader)?),
             11 => Self::UnconfirmedSolution(UnconfirmedSolution::read_le(&mut reader)?),
             12 => Self::UnconfirmedTransaction(UnconfirmedTransaction::read_le(&mut reader)?),
-            13.. => return Err(error("Unknown message ID {id}")),
+            13 => Self::RelayRequest(RelayRequest::read_le(&mut reader)?),
+            14 => Self::RelayIntroduction(RelayIntroduction::read_le(&mut reader)?),
+            15.. => return Err(error("Unknown message ID {id}")),
         };
 
         // Ensure that there are no "dangling" bytes.
@@ -0,0 +1,84 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::*;
+
+use snarkvm::prelude::{FromBytes, ToBytes};
+
+use std::borrow::Cow;
+
+/// An introduction from a relay to a peer that a connection through the NAT can be set up with,
+/// sent to both the peer that requested the introduction and its target.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RelayIntroduction {
+    /// The listener address of the introduced peer.
+    pub listener_addr: SocketAddr,
+    /// The address of the introduced peer, as observed by the relay (i.e. its mapping in the NAT).
+    pub observed_addr: SocketAddr,
+    /// The listener address of the target of the relay request, as observed by the relay.
+    pub target: SocketAddr,
+}
+
+impl MessageTrait for RelayIntroduction {
+    /// Returns the message name.
+    #[inline]
+    fn name(&self) -> Cow<'static, str> {
+        "RelayIntroduction".into()
+    }
+}
+
+impl ToBytes for RelayIntroduction {
+    fn write_le<W: io::Write>(&self, mut writer: W) -> io::Result<()> {
+        self.listener_addr.write_le(&mut writer)?;
+        self.observed_addr.write_le(&mut writer)?;
+        self.target.write_le(&mut writer)
+    }
+}
+
+impl FromBytes for RelayIntroduction {
+    fn read_le<R: io::Read>(mut reader: R) -> io::Result<Self> {
+        let listener_addr = SocketAddr::read_le(&mut reader)?;
+        let observed_addr = SocketAddr::read_le(&mut reader)?;
+        let target = SocketAddr::read_le(&mut reader)?;
+        Ok(Self { listener_addr, observed_addr, target })
+    }
+}
+
+#[cfg(test)]
+pub mod prop_tests {
+    use crate::{peer_response::prop_tests::any_valid_socket_addr, RelayIntroduction};
+    use snarkvm::utilities::{FromBytes, ToBytes};
+
+    use bytes::{Buf, BufMut, BytesMut};
+    use proptest::prelude::{BoxedStrategy, Strategy};
+    use test_strategy::proptest;
+
+    pub fn any_relay_introduction() -> BoxedStrategy<RelayIntroduction> {
+        (any_valid_socket_addr(), any_valid_socket_addr(), any_valid_socket_addr())
+            .prop_map(|(listener_addr, observed_addr, target)| RelayIntroduction {
+                listener_addr,
+                observed_addr,
+                target,
+            })
+            .boxed()
+    }
+
+    #[proptest]
+    fn relay_introduction_roundtrip(#[strategy(any_relay_introduction())] relay_introduction: RelayIntroduction) {
+        let mut bytes = BytesMut::default().writer();
+        relay_introduction.write_le(&mut bytes).unwrap();
+        let decoded = RelayIntroduction::read_le(&mut bytes.into_inner().reader()).unwrap();
+        assert_eq!(decoded, relay_introduction);
+    }
+}
@@ -0,0 +1,69 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::*;
+
+use snarkvm::prelude::{FromBytes, ToBytes};
+
+use std::borrow::Cow;
+
+/// A request to a relay to introduce this node to the given target peer, so that the two peers
+/// can connect to each other through their NATs (i.e. TCP hole punching).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RelayRequest {
+    /// The listener address of the target peer.
+    pub target: SocketAddr,
+}
+
+impl MessageTrait for RelayRequest {
+    /// Returns the message name.
+    #[inline]
+    fn name(&self) -> Cow<'static, str> {
+        "RelayRequest".into()
+    }
+}
+
+impl ToBytes for RelayRequest {
+    fn write_le<W: io::Write>(&self, writer: W) -> io::Result<()> {
+        self.target.write_le(writer)
+    }
+}
+
+impl FromBytes for RelayRequest {
+    fn read_le<R: io::Read>(reader: R) -> io::Result<Self> {
+        Ok(Self { target: SocketAddr::read_le(reader)? })
+    }
+}
+
+#[cfg(test)]
+pub mod prop_tests {
+    use crate::{peer_response::prop_tests::any_valid_socket_addr, RelayRequest};
+    use snarkvm::utilities::{FromBytes, ToBytes};
+
+    use bytes::{Buf, BufMut, BytesMut};
+    use proptest::prelude::{BoxedStrategy, Strategy};
+    use test_strategy::proptest;
+
+    pub fn any_relay_request() -> BoxedStrategy<RelayRequest> {
+        any_valid_socket_addr().prop_map(|target| RelayRequest { target }).boxed()
+    }
+
+    #[proptest]
+    fn relay_request_roundtrip(#[strategy(any_relay_request())] relay_request: RelayRequest) {
+        let mut bytes = BytesMut::default().writer();
+        relay_request.write_le(&mut bytes).unwrap();
+        let decoded = RelayRequest::read_le(&mut bytes.into_inner().reader()).unwrap();
+        assert_eq!(decoded, relay_request);
+    }
+}
@@ -13,7 +13,20 @@
 // limitations under the License.
 
 use crate::{
-    messages::{ChallengeRequest, ChallengeResponse, DisconnectReason, Message, MessageCodec, MessageTrait},
+    messages::{
+        challenge_data,
+        default_user_agent,
+        noise_handshake,
+        Capabilities,
+        ChallengeRequest,
+        ChallengeResponse,
+        DisconnectReason,
+        Message,
+        MessageCodec,
+        MessageTrait,
+        NoiseCodec,
+        NoiseState,
+    },
     Peer,
     Router,
 };
@@ -71,7 +84,7 @@ macro_rules! expect_message {
 
 /// Send the given message to the peer.
 async fn send<N: Network>(
-    framed: &mut Framed<&mut TcpStream, MessageCodec<N>>,
+    framed: &mut Framed<&mut TcpStream, NoiseCodec<N>>,
     peer_addr: SocketAddr,
     message: Message<N>,
 ) -> io::Result<()> {
@@ -79,6 +92,30 @@ async fn send<N: Network>(
     framed.send(message).await
 }
 
+/// Encrypts the remainder of the connection with a noise handshake, if both sides advertised `Capabilities::NOISE`.
+/// Otherwise, the connection remains in plaintext.
+async fn negotiate_noise<'a, N: Network>(
+    framed: Framed<&'a mut TcpStream, NoiseCodec<N>>,
+    peer_addr: SocketAddr,
+    is_initiator: bool,
+    our_capabilities: Capabilities,
+    peer_capabilities: Capabilities,
+) -> io::Result<Framed<&'a mut TcpStream, NoiseCodec<N>>> {
+    // If either side does not support the noise handshake, proceed in plaintext.
+    if !our_capabilities.contains(Capabilities::NOISE) || !peer_capabilities.contains(Capabilities::NOISE) {
+        return Ok(framed);
+    }
+    // Ensure the peer did not send any data ahead of the noise handshake.
+    let parts = framed.into_parts();
+    if !parts.read_buf.is_empty() {
+        return Err(error(format!("'{peer_addr}' sent unexpected data before the noise handshake")));
+    }
+    let stream = parts.io;
+    // Perform the noise handshake, which encrypts the remainder of the connection.
+    let noise_state = noise_handshake(stream, is_initiator).await?;
+    Ok(Framed::new(stream, NoiseCodec::new(noise_state, MessageCodec::handshake())))
+}
+
 impl<N: Network> Router<N> {
     /// Executes the handshake protocol.
     pub async fn handshake<'a>(
@@ -87,7 +124,7 @@ impl<N: Network> Router<N> {
         stream: &'a mut TcpStream,
         peer_side: ConnectionSide,
         genesis_header: Header<N>,
-    ) -> io::Result<(SocketAddr, Framed<&mut TcpStream, MessageCodec<N>>)> {
+    ) -> io::Result<(SocketAddr, Framed<&mut TcpStream, NoiseCodec<N>>)> {
         // If this is an inbound connection, we log it, but don't know the listening address yet.
         // Otherwise, we can immediately register the listening address.
         let mut peer_ip = if peer_side == ConnectionSide::Initiator {
@@ -125,11 +162,11 @@ impl<N: Network> Router<N> {
         peer_ip: &mut Option<SocketAddr>,
         stream: &'a mut TcpStream,
         genesis_header: Header<N>,
-    ) -> io::Result<(SocketAddr, Framed<&mut TcpStream, MessageCodec<N>>)> {
+    ) -> io::Result<(SocketAddr, Framed<&mut TcpStream, NoiseCodec<N>>)> {
         // This value is immediately guaranteed to be present, so it can be unwrapped.
         let peer_ip = peer_ip.unwrap();
-        // Construct the stream.
-        let mut framed = Framed::new(stream, MessageCodec::<N>::handshake());
+        // Construct the stream, which remains in plaintext until the noise handshake is negotiated.
+        let mut framed = Framed::new(stream, NoiseCodec::plaintext(MessageCodec::handshake()));
 
         // Initialize an RNG.
         let rng = &mut OsRng;
@@ -138,45 +175,79 @@ impl<N: Network> Router<N> {
 
         // Sample a random nonce.
         let our_nonce = rng.gen();
+        // Determine the capabilities to advertise.
+        let our_capabilities = self.capabilities();
         // Send a challenge request to the peer.
-        let our_request = ChallengeRequest::new(self.local_ip().port(), self.node_type, self.address(), our_nonce);
+        let our_request = ChallengeRequest::new(
+            self.advertised_port(),
+            self.node_type,
+            self.address(),
+            our_nonce,
+            default_user_agent(),
+            our_capabilities,
+        );
         send(&mut framed, peer_addr, Message::ChallengeRequest(our_request)).await?;
 
-        /* Step 2: Receive the peer's challenge response followed by the challenge request. */
+        /* Step 2: Receive the peer's challenge request, and negotiate the noise handshake. */
 
-        // Listen for the challenge response message.
-        let peer_response = expect_message!(Message::ChallengeResponse, framed, peer_addr);
         // Listen for the challenge request message.
         let peer_request = expect_message!(Message::ChallengeRequest, framed, peer_addr);
-
-        // Verify the challenge response. If a disconnect reason was returned, send the disconnect message and abort.
-        if let Some(reason) = self
-            .verify_challenge_response(peer_addr, peer_request.address, peer_response, genesis_header, our_nonce)
-            .await
-        {
-            send(&mut framed, peer_addr, reason.into()).await?;
-            return Err(error(format!("Dropped '{peer_addr}' for reason: {reason:?}")));
-        }
         // Verify the challenge request. If a disconnect reason was returned, send the disconnect message and abort.
         if let Some(reason) = self.verify_challenge_request(peer_addr, &peer_request) {
             send(&mut framed, peer_addr, reason.into()).await?;
             return Err(error(format!("Dropped '{peer_addr}' for reason: {reason:?}")));
         }
+        // Encrypt the remainder of the connection, if both sides support it.
+        let mut framed = negotiate_noise(framed, peer_addr, true, our_capabilities, peer_request.capabilities).await?;
+        let noise_state = framed.codec().noise_state().cloned();
+
         /* Step 3: Send the challenge response. */
 
         let response_nonce: u64 = rng.gen();
-        let data = [peer_request.nonce.to_le_bytes(), response_nonce.to_le_bytes()].concat();
+        let data = challenge_data(peer_request.nonce, response_nonce, noise_state.as_ref());
         // Sign the counterparty nonce.
         let Ok(our_signature) = self.account.sign_bytes(&data, rng) else {
             return Err(error(format!("Failed to sign the challenge request nonce from '{peer_addr}'")));
         };
         // Send the challenge response.
-        let our_response =
-            ChallengeResponse { genesis_header, signature: Data::Object(our_signature), nonce: response_nonce };
+        let our_response = ChallengeResponse {
+            genesis_header,
+            signature: Data::Object(our_signature),
+            nonce: response_nonce,
+            observed_addr: peer_addr,
+        };
         send(&mut framed, peer_addr, Message::ChallengeResponse(our_response)).await?;
 
-        // Add the peer to the router.
-        self.insert_connected_peer(Peer::new(peer_ip, &peer_request), peer_addr);
+        /* Step 4: Receive the peer's challenge response. */
+
+        // Listen for the challenge response message.
+        let peer_response = expect_message!(Message::ChallengeResponse, framed, peer_addr);
+        // Retrieve the address of this node, as observed by the peer.
+        let observed_addr = peer_response.observed_addr;
+        // Verify the challenge response. If a disconnect reason was returned, send the disconnect message and abort.
+        if let Some(reason) = self
+            .verify_challenge_response(
+                peer_addr,
+                peer_request.address,
+                peer_response,
+                genesis_header,
+                our_nonce,
+                noise_state.as_ref(),
+            )
+            .await
+        {
+            send(&mut framed, peer_addr, reason.into()).await?;
+            return Err(error(format!("Dropped '{peer_addr}' for reason: {reason:?}")));
+        }
+
+        // Add the peer to the router, along with the transport state of the connection.
+        let compression = our_capabilities.contains(Capabilities::COMPRESSION)
+            && peer_request.capabilities.contains(Capabilities::COMPRESSION);
+        let noise_state = framed.codec().noise_state().cloned();
+        self.insert_connected_peer(Peer::new(peer_ip, &peer_request, true, compression, noise_state), peer_addr);
+        // Record the address of this node observed by the peer. Only the outbound connections are considered,
+        // as the peers that initiated a connection merely echo the address they dialed.
+        self.record_observed_addr(peer_ip, observed_addr);
 
         Ok((peer_ip, framed))
     }
@@ -188,9 +259,9 @@ impl<N: Network> Router<N> {
         peer_ip: &mut Option<SocketAddr>,
         stream: &'a mut TcpStream,
         genesis_header: Header<N>,
-    ) -> io::Result<(SocketAddr, Framed<&mut TcpStream, MessageCodec<N>>)> {
-        // Construct the stream.
-        let mut framed = Framed::new(stream, MessageCodec::<N>::handshake());
+    ) -> io::Result<(SocketAddr, Framed<&mut TcpStream, NoiseCodec<N>>)> {
+        // Construct the stream, which remains in plaintext until the noise handshake is negotiated.
+        let mut framed = Framed::new(stream, NoiseCodec::plaintext(MessageCodec::handshake()));
 
         /* Step 1: Receive the challenge request. */
 
@@ -210,27 +281,29 @@ impl<N: Network> Router<N> {
             send(&mut framed, peer_addr, reason.into()).await?;
             return Err(error(format!("Dropped '{peer_addr}' for reason: {reason:?}")));
         }
-        /* Step 2: Send the challenge response followed by own challenge request. */
+
+        /* Step 2: Send own challenge request, and negotiate the noise handshake. */
 
         // Initialize an RNG.
         let rng = &mut OsRng;
 
-        // Sign the counterparty nonce.
-        let response_nonce: u64 = rng.gen();
-        let data = [peer_request.nonce.to_le_bytes(), response_nonce.to_le_bytes()].concat();
-        let Ok(our_signature) = self.account.sign_bytes(&data, rng) else {
-            return Err(error(format!("Failed to sign the challenge request nonce from '{peer_addr}'")));
-        };
-        // Send the challenge response.
-        let our_response =
-            ChallengeResponse { genesis_header, signature: Data::Object(our_signature), nonce: response_nonce };
-        send(&mut framed, peer_addr, Message::ChallengeResponse(our_response)).await?;
-
         // Sample a random nonce.
         let our_nonce = rng.gen();
+        // Determine the capabilities to advertise.
+        let our_capabilities = self.capabilities();
         // Send the challenge request.
-        let our_request = ChallengeRequest::new(self.local_ip().port(), self.node_type, self.address(), our_nonce);
+        let our_request = ChallengeRequest::new(
+            self.advertised_port(),
+            self.node_type,
+            self.address(),
+            our_nonce,
+            default_user_agent(),
+            our_capabilities,
+        );
         send(&mut framed, peer_addr, Message::ChallengeRequest(our_request)).await?;
+        // Encrypt the remainder of the connection, if both sides support it.
+        let mut framed = negotiate_noise(framed, peer_addr, false, our_capabilities, peer_request.capabilities).await?;
+        let noise_state = framed.codec().noise_state().cloned();
 
         /* Step 3: Receive the challenge response. */
 
@@ -238,14 +311,42 @@ impl<N: Network> Router<N> {
         let peer_response = expect_message!(Message::ChallengeResponse, framed, peer_addr);
         // Verify the challenge response. If a disconnect reason was returned, send the disconnect message and abort.
         if let Some(reason) = self
-            .verify_challenge_response(peer_addr, peer_request.address, peer_response, genesis_header, our_nonce)
+            .verify_challenge_response(
+                peer_addr,
+                peer_request.address,
+                peer_response,
+                genesis_header,
+                our_nonce,
+                noise_state.as_ref(),
+            )
             .await
         {
             send(&mut framed, peer_addr, reason.into()).await?;
             return Err(error(format!("Dropped '{peer_addr}' for reason: {reason:?}")));
         }
-        // Add the peer to the router.
-        self.insert_connected_peer(Peer::new(peer_ip, &peer_request), peer_addr);
+
+        /* Step 4: Send the challenge response. */
+
+        // Sign the counterparty nonce.
+        let response_nonce: u64 = rng.gen();
+        let data = challenge_data(peer_request.nonce, response_nonce, noise_state.as_ref());
+        let Ok(our_signature) = self.account.sign_bytes(&data, rng) else {
+            return Err(error(format!("Failed to sign the challenge request nonce from '{peer_addr}'")));
+        };
+        // Send the challenge response.
+        let our_response = ChallengeResponse {
+            genesis_header,
+            signature: Data::Object(our_signature),
+            nonce: response_nonce,
+            observed_addr: peer_addr,
+        };
+        send(&mut framed, peer_addr, Message::ChallengeResponse(our_response)).await?;
+
+        // Add the peer to the router, along with the transport state of the connection.
+        let compression = our_capabilities.contains(Capabilities::COMPRESSION)
+            && peer_request.capabilities.contains(Capabilities::COMPRESSION);
+        let noise_state = framed.codec().noise_state().cloned();
+        self.insert_connected_peer(Peer::new(peer_ip, &peer_request, false, compression, noise_state), peer_addr);
 
         Ok((peer_ip, framed))
     }
@@ -268,6 +369,15 @@ impl<N: Network> Router<N> {
         if self.is_restricted(&peer_ip) {
             bail!("Dropping connection request from '{peer_ip}' (restricted)")
         }
+        // Ensure the peer is allowed by the access lists.
+        if !self.is_allowed(&peer_ip) {
+            bail!("Dropping connection request from '{peer_ip}' (not allowed)")
+        }
+        // Ensure the node does not surpass the maximum number of inbound peers.
+        let is_trusted = self.trusted_peers.contains(&peer_ip);
+        if !is_trusted && self.number_of_inbound_peers() >= self.peer_limits().max_inbound {
+            bail!("Dropping connection request from '{peer_ip}' (maximum inbound peers reached)")
+        }
         // Ensure the peer is not spamming connection attempts.
         if !peer_ip.ip().is_loopback() {
             // Add this connection attempt and retrieve the number of attempts.
@@ -289,13 +399,21 @@ impl<N: Network> Router<N> {
         message: &ChallengeRequest<N>,
     ) -> Option<DisconnectReason> {
         // Retrieve the components of the challenge request.
-        let &ChallengeRequest { version, listener_port: _, node_type: _, address: _, nonce: _ } = message;
+        let &ChallengeRequest { version, listener_port, .. } = message;
 
         // Ensure the message protocol version is not outdated.
         if version < Message::<N>::VERSION {
             warn!("Dropping '{peer_addr}' on version {version} (outdated)");
             return Some(DisconnectReason::OutdatedClientVersion);
         }
+        // Ensure the peer satisfies the handshake policy, unless it is trusted.
+        let peer_ip = SocketAddr::new(peer_addr.ip(), listener_port);
+        if !self.trusted_peers.contains(&peer_ip) {
+            if let Err((reason, violation)) = self.handshake_policy.read().check(message) {
+                warn!("Dropping '{peer_addr}' (rejected by the handshake policy: {violation})");
+                return Some(reason);
+            }
+        }
         None
     }
 
@@ -307,9 +425,10 @@ impl<N: Network> Router<N> {
         response: ChallengeResponse<N>,
         expected_genesis_header: Header<N>,
         expected_nonce: u64,
+        noise_state: Option<&NoiseState>,
     ) -> Option<DisconnectReason> {
         // Retrieve the components of the challenge response.
-        let ChallengeResponse { genesis_header, signature, nonce } = response;
+        let ChallengeResponse { genesis_header, signature, nonce, .. } = response;
 
         // Verify the challenge response, by checking that the block header matches.
         if genesis_header != expected_genesis_header {
@@ -322,7 +441,9 @@ impl<N: Network> Router<N> {
             return Some(DisconnectReason::InvalidChallengeResponse);
         };
         // Verify the signature.
-        if !signature.verify_bytes(&peer_address, &[expected_nonce.to_le_bytes(), nonce.to_le_bytes()].concat()) {
+        // Note: If the connection is encrypted, the signed data includes the noise handshake hash,
+        // which binds the signature to this connection.
+        if !signature.verify_bytes(&peer_address, &challenge_data(expected_nonce, nonce, noise_state)) {
             warn!("Handshake with '{peer_addr}' failed (invalid signature)");
             return Some(DisconnectReason::InvalidChallengeResponse);
         }
@@ -13,33 +13,23 @@
 // limitations under the License.
 
 use crate::{
-    messages::{DisconnectReason, Message, PeerRequest},
+    messages::{DisconnectReason, Message, PeerRequest, RelayRequest},
     Outbound,
     Router,
+    MINIMUM_NUMBER_OF_PEERS,
 };
 use snarkvm::prelude::Network;
 
 use colored::Colorize;
 use rand::{prelude::IteratorRandom, rngs::OsRng};
 
-/// A helper function to compute the maximum of two numbers.
-/// See Rust issue 92391: https://github.com/rust-lang/rust/issues/92391.
-pub const fn max(a: usize, b: usize) -> usize {
-    match a > b {
-        true => a,
-        false => b,
-    }
-}
-
 pub trait Heartbeat<N: Network>: Outbound<N> {
     /// The duration in seconds to sleep in between heartbeat executions.
     const HEARTBEAT_IN_SECS: u64 = 25; // 25 seconds
-    /// The minimum number of peers required to maintain connections with.
-    const MINIMUM_NUMBER_OF_PEERS: usize = 3;
-    /// The median number of peers to maintain connections with.
-    const MEDIAN_NUMBER_OF_PEERS: usize = max(Self::MAXIMUM_NUMBER_OF_PEERS / 2, Self::MINIMUM_NUMBER_OF_PEERS);
-    /// The maximum number of peers permitted to maintain connections with.
-    const MAXIMUM_NUMBER_OF_PEERS: usize = 21;
+    /// The minimum number of failed dials to a candidate peer, before an introduction is requested from a relay.
+    const MINIMUM_DIAL_FAILURES_BEFORE_RELAY: u32 = 2;
+    /// The maximum number of relay requests sent per heartbeat.
+    const MAXIMUM_RELAY_REQUESTS_PER_HEARTBEAT: usize = 2;
 
     /// Handles the heartbeat request.
     fn heartbeat(&self) {
@@ -56,18 +46,26 @@ pub trait Heartbeat<N: Network>: Outbound<N> {
         self.handle_bootstrap_peers();
         // Keep the trusted peers connected.
         self.handle_trusted_peers();
+        // Request introductions to the unreachable candidate peers.
+        self.handle_relay_requests();
         // Keep the puzzle request up to date.
         self.handle_puzzle_request();
+        // Forget the peers that have fully recovered their score.
+        self.router().reputation().prune();
+        // Forget the stale dial history.
+        self.router().dial_scheduler().prune();
+        // Save the connected peers to the peer store.
+        self.router().save_peer_store();
     }
 
     /// TODO (howardwu): Consider checking minimum number of validators, to exclude clients and provers.
     /// This function performs safety checks on the setting for the minimum number of peers.
     fn safety_check_minimum_number_of_peers(&self) {
         // Perform basic sanity checks on the configuration for the number of peers.
-        assert!(Self::MINIMUM_NUMBER_OF_PEERS >= 1, "The minimum number of peers must be at least 1.");
-        assert!(Self::MINIMUM_NUMBER_OF_PEERS <= Self::MAXIMUM_NUMBER_OF_PEERS);
-        assert!(Self::MINIMUM_NUMBER_OF_PEERS <= Self::MEDIAN_NUMBER_OF_PEERS);
-        assert!(Self::MEDIAN_NUMBER_OF_PEERS <= Self::MAXIMUM_NUMBER_OF_PEERS);
+        let limits = self.router().peer_limits();
+        assert!(limits.target_outbound >= 1, "The target number of outbound peers must be at least 1.");
+        assert!(limits.target_outbound <= limits.max_outbound);
+        assert!(limits.max_connections() <= self.router().max_connected_peers());
     }
 
     /// This function logs the connected peers.
@@ -100,7 +98,7 @@ pub trait Heartbeat<N: Network>: Outbound<N> {
     /// This function only triggers if the router is above the minimum number of connected peers.
     fn remove_oldest_connected_peer(&self) {
         // Skip if the router is at or below the minimum number of connected peers.
-        if self.router().number_of_connected_peers() <= Self::MINIMUM_NUMBER_OF_PEERS {
+        if self.router().number_of_connected_peers() <= MINIMUM_NUMBER_OF_PEERS {
             return;
         }
 
@@ -128,17 +126,26 @@ pub trait Heartbeat<N: Network>: Outbound<N> {
     }
 
     /// TODO (howardwu): If the node is a validator, keep the validator.
-    /// This function keeps the number of connected peers within the allowed range.
+    /// This function keeps the number of inbound and outbound peers within the allowed range.
     fn handle_connected_peers(&self) {
-        // Obtain the number of connected peers.
-        let num_connected = self.router().number_of_connected_peers();
-        // Compute the number of surplus peers.
-        let num_surplus = num_connected.saturating_sub(Self::MAXIMUM_NUMBER_OF_PEERS);
-        // Compute the number of deficit peers.
-        let num_deficient = Self::MEDIAN_NUMBER_OF_PEERS.saturating_sub(num_connected);
+        // Retrieve the peer limits.
+        let limits = self.router().peer_limits();
+        // Obtain the number of inbound and outbound peers.
+        let num_inbound = self.router().number_of_inbound_peers();
+        let num_outbound = self.router().number_of_outbound_peers();
+        // Compute the number of deficit outbound peers.
+        let num_deficient = limits.target_outbound.saturating_sub(num_outbound);
 
-        if num_surplus > 0 {
-            debug!("Exceeded maximum number of connected peers, disconnecting from {num_surplus} peers");
+        // Compute the number of surplus inbound and outbound peers.
+        let surplus = [
+            ("inbound", false, num_inbound.saturating_sub(limits.max_inbound)),
+            ("outbound", true, num_outbound.saturating_sub(limits.max_outbound)),
+        ];
+        for (direction, is_outbound, num_surplus) in surplus {
+            if num_surplus == 0 {
+                continue;
+            }
+            debug!("Exceeded maximum number of {direction} peers, disconnecting from {num_surplus} peers");
 
             // Retrieve the trusted peers.
             let trusted = self.router().trusted_peers();
@@ -153,8 +160,10 @@ pub trait Heartbeat<N: Network>: Outbound<N> {
             // Determine the peers to disconnect from.
             let peer_ips_to_disconnect = self
                 .router()
-                .connected_peers()
+                .get_connected_peers()
                 .into_iter()
+                .filter(|peer| peer.is_outbound() == is_outbound)
+                .map(|peer| peer.ip())
                 .filter(|peer_ip| !trusted.contains(peer_ip) && !bootstrap.contains(peer_ip))
                 .choose_multiple(rng, num_surplus);
 
@@ -169,7 +178,7 @@ pub trait Heartbeat<N: Network>: Outbound<N> {
                     }
                 }
 
-                info!("Disconnecting from '{peer_ip}' (exceeded maximum connections)");
+                info!("Disconnecting from '{peer_ip}' (exceeded maximum {direction} connections)");
                 self.send(peer_ip, Message::Disconnect(DisconnectReason::TooManyPeers.into()));
                 // Disconnect from this peer.
                 self.router().disconnect(peer_ip);
@@ -180,8 +189,9 @@ pub trait Heartbeat<N: Network>: Outbound<N> {
             // Initialize an RNG.
             let rng = &mut OsRng;
 
-            // Attempt to connect to more peers.
-            for peer_ip in self.router().candidate_peers().into_iter().choose_multiple(rng, num_deficient) {
+            // Attempt to connect to more peers, preferring the freshly gossiped ones that are not backed off.
+            let candidate_peers = self.router().candidate_peers();
+            for peer_ip in self.router().dial_scheduler().schedule(candidate_peers, num_deficient, rng) {
                 self.router().connect(peer_ip);
             }
             // Request more peers from the connected peers.
@@ -206,8 +216,8 @@ pub trait Heartbeat<N: Network>: Outbound<N> {
         if connected_bootstrap.is_empty() {
             // Initialize an RNG.
             let rng = &mut OsRng;
-            // Attempt to connect to a bootstrap peer.
-            if let Some(peer_ip) = candidate_bootstrap.into_iter().choose(rng) {
+            // Attempt to connect to a bootstrap peer that is not backed off.
+            if let Some(peer_ip) = self.router().dial_scheduler().schedule(candidate_bootstrap, 1, rng).pop() {
                 self.router().connect(peer_ip);
             }
         }
@@ -230,14 +240,52 @@ pub trait Heartbeat<N: Network>: Outbound<N> {
     fn handle_trusted_peers(&self) {
         // Ensure that the trusted nodes are connected.
         for peer_ip in self.router().trusted_peers() {
-            // If the peer is not connected, attempt to connect to it.
-            if !self.router().is_connected(peer_ip) {
+            // If the peer is not connected and not backed off, attempt to connect to it.
+            if !self.router().is_connected(peer_ip) && !self.router().dial_scheduler().is_backed_off(peer_ip) {
                 // Attempt to connect to the trusted peer.
                 self.router().connect(*peer_ip);
             }
         }
     }
 
+    /// This function requests introductions to the candidate peers that could not be dialed directly from the
+    /// connected relays, so that this node and the candidate peers can connect through their NATs.
+    fn handle_relay_requests(&self) {
+        // Only request introductions if hole punching is enabled, and more outbound peers are needed.
+        if !self.router().is_hole_punching_enabled()
+            || self.router().number_of_outbound_peers() >= self.router().peer_limits().target_outbound
+        {
+            return;
+        }
+        // Retrieve the connected relays.
+        let relays = self.router().connected_relays();
+        if relays.is_empty() {
+            return;
+        }
+
+        // Initialize an RNG.
+        let rng = &mut OsRng;
+
+        // Select the unreachable candidate peers that were not recently requested.
+        let targets = self
+            .router()
+            .candidate_peers()
+            .into_iter()
+            .filter(|peer_ip| {
+                self.router().dial_scheduler().failures(peer_ip) >= Self::MINIMUM_DIAL_FAILURES_BEFORE_RELAY
+            })
+            .filter(|peer_ip| !self.router().cache.contains_outbound_relay_request(peer_ip))
+            .choose_multiple(rng, Self::MAXIMUM_RELAY_REQUESTS_PER_HEARTBEAT);
+        // Request the introductions from random relays.
+        for target in targets {
+            if let Some(relay) = relays.iter().choose(rng) {
+                debug!("Requesting an introduction to '{target}' from '{relay}'");
+                self.router().cache.insert_outbound_relay_request(target);
+                self.send(*relay, Message::RelayRequest(RelayRequest { target }));
+            }
+        }
+    }
+
     /// This function updates the coinbase puzzle if network has updated.
     fn handle_puzzle_request(&self) {
         // No-op
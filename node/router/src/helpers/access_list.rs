@@ -0,0 +1,201 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+
+use anyhow::{bail, Context, Result};
+use std::{
+    fmt,
+    net::IpAddr,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+/// An IP subnet in CIDR notation (e.g. `10.0.0.0/8`), or a single IP address.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct IpSubnet {
+    /// The network address.
+    network: IpAddr,
+    /// The length of the network prefix, in bits.
+    prefix_len: u8,
+}
+
+impl IpSubnet {
+    /// Returns `true` if the given IP address belongs to the subnet.
+    pub fn contains(&self, ip: IpAddr) -> bool {
+        // Compare IPv4-mapped IPv6 addresses as IPv4 addresses.
+        let ip = match ip {
+            IpAddr::V6(ipv6) => ipv6.to_ipv4_mapped().map(IpAddr::V4).unwrap_or(ip),
+            ip => ip,
+        };
+        match (self.network, ip) {
+            (IpAddr::V4(network), IpAddr::V4(ip)) => {
+                let mask = u32::MAX.checked_shl(32 - self.prefix_len as u32).unwrap_or(0);
+                u32::from(network) & mask == u32::from(ip) & mask
+            }
+            (IpAddr::V6(network), IpAddr::V6(ip)) => {
+                let mask = u128::MAX.checked_shl(128 - self.prefix_len as u32).unwrap_or(0);
+                u128::from(network) & mask == u128::from(ip) & mask
+            }
+            _ => false,
+        }
+    }
+}
+
+impl FromStr for IpSubnet {
+    type Err = anyhow::Error;
+
+    fn from_str(subnet: &str) -> Result<Self> {
+        let (network, prefix_len) = match subnet.split_once('/') {
+            Some((network, prefix_len)) => (network.parse::<IpAddr>()?, Some(prefix_len.parse::<u8>()?)),
+            None => (subnet.parse::<IpAddr>()?, None),
+        };
+        let max_prefix_len = if network.is_ipv4() { 32 } else { 128 };
+        let prefix_len = prefix_len.unwrap_or(max_prefix_len);
+        if prefix_len > max_prefix_len {
+            bail!("Invalid prefix length '{prefix_len}' for '{network}'")
+        }
+        Ok(Self { network, prefix_len })
+    }
+}
+
+impl fmt::Display for IpSubnet {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}/{}", self.network, self.prefix_len)
+    }
+}
+
+/// The files to load the peer allowlist and denylist from.
+#[derive(Clone, Debug, Default)]
+pub struct AccessListFiles {
+    /// The path to the allowlist file, if any.
+    pub allowlist: Option<PathBuf>,
+    /// The path to the denylist file, if any.
+    pub denylist: Option<PathBuf>,
+}
+
+/// The allowlist and denylist of peer IP addresses and subnets.
+///
+/// A peer is allowed if it does not belong to the denylist, and the allowlist is empty or contains it.
+#[derive(Clone, Debug, Default)]
+pub struct AccessList {
+    /// The allowed subnets.
+    allow: Vec<IpSubnet>,
+    /// The denied subnets.
+    deny: Vec<IpSubnet>,
+}
+
+impl AccessList {
+    /// Loads the access list from the given files.
+    pub fn load(files: &AccessListFiles) -> Result<Self> {
+        let allow = files.allowlist.as_deref().map(parse_subnets_file).transpose()?.unwrap_or_default();
+        let deny = files.denylist.as_deref().map(parse_subnets_file).transpose()?.unwrap_or_default();
+        Ok(Self { allow, deny })
+    }
+
+    /// Returns `true` if the given IP address is allowed.
+    pub fn is_allowed(&self, ip: IpAddr) -> bool {
+        if self.deny.iter().any(|subnet| subnet.contains(ip)) {
+            return false;
+        }
+        self.allow.is_empty() || self.allow.iter().any(|subnet| subnet.contains(ip))
+    }
+
+    /// Returns the number of allowed and denied subnets.
+    pub fn num_subnets(&self) -> (usize, usize) {
+        (self.allow.len(), self.deny.len())
+    }
+}
+
+/// Parses the given file of subnets, with one IP address or subnet per line.
+/// Empty lines and comments (starting with `#`) are ignored.
+fn parse_subnets_file(path: &Path) -> Result<Vec<IpSubnet>> {
+    let contents = std::fs::read_to_string(path).with_context(|| format!("Failed to read '{}'", path.display()))?;
+    parse_subnets(&contents).with_context(|| format!("Failed to parse '{}'", path.display()))
+}
+
+/// Parses the given list of subnets, with one IP address or subnet per line.
+/// Empty lines and comments (starting with `#`) are ignored.
+fn parse_subnets(contents: &str) -> Result<Vec<IpSubnet>> {
+    contents
+        .lines()
+        .enumerate()
+        .map(|(i, line)| (i, line.split('#').next().unwrap_or_default().trim()))
+        .filter(|(_, line)| !line.is_empty())
+        .map(|(i, line)| line.parse().with_context(|| format!("Invalid subnet '{line}' on line {}", i + 1)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ip_subnet() {
+        let subnet = IpSubnet::from_str("10.1.0.0/16").unwrap();
+        assert!(subnet.contains("10.1.2.3".parse().unwrap()));
+        assert!(subnet.contains("::ffff:10.1.2.3".parse().unwrap()));
+        assert!(!subnet.contains("10.2.0.1".parse().unwrap()));
+        assert!(!subnet.contains("::1".parse().unwrap()));
+
+        let subnet = IpSubnet::from_str("1.2.3.4").unwrap();
+        assert_eq!(subnet.to_string(), "1.2.3.4/32");
+        assert!(subnet.contains("1.2.3.4".parse().unwrap()));
+        assert!(!subnet.contains("1.2.3.5".parse().unwrap()));
+
+        let subnet = IpSubnet::from_str("2001:db8::/32").unwrap();
+        assert!(subnet.contains("2001:db8::1".parse().unwrap()));
+        assert!(!subnet.contains("2001:db9::1".parse().unwrap()));
+
+        // Ensure every address belongs to the empty prefix.
+        assert!(IpSubnet::from_str("0.0.0.0/0").unwrap().contains("8.8.8.8".parse().unwrap()));
+
+        assert!(IpSubnet::from_str("1.2.3.4/33").is_err());
+        assert!(IpSubnet::from_str("1.2.3/8").is_err());
+    }
+
+    #[test]
+    fn test_access_list() {
+        let contents = "# The allowed peers.\n10.0.0.0/8\n\n192.168.1.1 # A single peer.\n";
+        let allow = parse_subnets(contents).unwrap();
+        assert_eq!(allow.len(), 2);
+        let deny = parse_subnets("10.0.0.13").unwrap();
+
+        let access_list = AccessList { allow, deny };
+        assert!(access_list.is_allowed("10.0.0.1".parse().unwrap()));
+        assert!(access_list.is_allowed("192.168.1.1".parse().unwrap()));
+        assert!(!access_list.is_allowed("10.0.0.13".parse().unwrap()));
+        assert!(!access_list.is_allowed("8.8.8.8".parse().unwrap()));
+
+        // Ensure an empty allowlist allows every peer that is not denied.
+        let access_list = AccessList { allow: vec![], deny: parse_subnets("8.8.8.0/24").unwrap() };
+        assert!(access_list.is_allowed("1.1.1.1".parse().unwrap()));
+        assert!(!access_list.is_allowed("8.8.8.8".parse().unwrap()));
+
+        assert!(parse_subnets("10.0.0.1\nnot-an-ip\n").is_err());
+    }
+
+    #[test]
+    fn test_access_list_load() {
+        let path = std::env::temp_dir().join(format!("snarkos-router-denylist-{}", std::process::id()));
+        std::fs::write(&path, "1.2.3.4\n").unwrap();
+
+        let files = AccessListFiles { allowlist: None, denylist: Some(path.clone()) };
+        let access_list = AccessList::load(&files).unwrap();
+        assert_eq!(access_list.num_subnets(), (0, 1));
+        assert!(!access_list.is_allowed("1.2.3.4".parse().unwrap()));
+
+        std::fs::remove_file(&path).unwrap();
+        assert!(AccessList::load(&files).is_err());
+    }
+}
@@ -24,8 +24,23 @@ use std::{
 };
 use time::{Duration, OffsetDateTime};
 
-/// The maximum number of items to store in a cache map.
-const MAX_CACHE_SIZE: usize = 1 << 17;
+/// The default maximum number of items to store in a cache map.
+pub const DEFAULT_CACHE_SIZE: usize = 1 << 17;
+
+/// The configuration of the caches of the recently seen solutions and transactions.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct CacheConfig {
+    /// The maximum number of items to store in each cache map.
+    pub capacity: usize,
+    /// The duration after which an item is no longer considered seen, if any.
+    pub ttl: Option<std::time::Duration>,
+}
+
+impl Default for CacheConfig {
+    fn default() -> Self {
+        Self { capacity: DEFAULT_CACHE_SIZE, ttl: None }
+    }
+}
 
 /// A helper containing the peer IP and solution commitment.
 type SolutionKey<N> = (SocketAddr, PuzzleCommitment<N>);
@@ -40,6 +55,10 @@ pub struct Cache<N: Network> {
     seen_inbound_messages: RwLock<HashMap<SocketAddr, VecDeque<OffsetDateTime>>>,
     /// The map of peer IPs to their recent timestamps.
     seen_inbound_puzzle_requests: RwLock<HashMap<SocketAddr, VecDeque<OffsetDateTime>>>,
+    /// The map of peer IPs to the timestamps of their recent relay messages.
+    seen_inbound_relay_messages: RwLock<HashMap<SocketAddr, VecDeque<OffsetDateTime>>>,
+    /// The map of introduced peer addresses to the timestamp of the relay introduction received for them.
+    seen_inbound_relay_introductions: RwLock<HashMap<SocketAddr, OffsetDateTime>>,
     /// The map of solution commitments to their last seen timestamp.
     seen_inbound_solutions: RwLock<LinkedHashMap<SolutionKey<N>, OffsetDateTime>>,
     /// The map of transaction IDs to their last seen timestamp.
@@ -54,6 +73,10 @@ pub struct Cache<N: Network> {
     seen_outbound_transactions: RwLock<LinkedHashMap<TransactionKey<N>, OffsetDateTime>>,
     /// The map of peer IPs to the number of sent peer requests.
     seen_outbound_peer_requests: RwLock<HashMap<SocketAddr, u32>>,
+    /// The map of target peer IPs to the timestamp of the relay request sent for them.
+    seen_outbound_relay_requests: RwLock<HashMap<SocketAddr, OffsetDateTime>>,
+    /// The configuration of the cache maps of solutions and transactions.
+    config: RwLock<CacheConfig>,
 }
 
 impl<N: Network> Default for Cache<N> {
@@ -66,19 +89,39 @@ impl<N: Network> Default for Cache<N> {
 impl<N: Network> Cache<N> {
     /// Initializes a new instance of the cache.
     pub fn new() -> Self {
+        Self::with_config(CacheConfig::default())
+    }
+
+    /// Initializes a new instance of the cache with the given configuration.
+    pub fn with_config(config: CacheConfig) -> Self {
         Self {
             seen_inbound_connections: Default::default(),
             seen_inbound_messages: Default::default(),
             seen_inbound_puzzle_requests: Default::default(),
-            seen_inbound_solutions: RwLock::new(LinkedHashMap::with_capacity(MAX_CACHE_SIZE)),
-            seen_inbound_transactions: RwLock::new(LinkedHashMap::with_capacity(MAX_CACHE_SIZE)),
+            seen_inbound_relay_messages: Default::default(),
+            seen_inbound_relay_introductions: Default::default(),
+            seen_inbound_solutions: Default::default(),
+            seen_inbound_transactions: Default::default(),
             seen_outbound_block_requests: Default::default(),
             seen_outbound_puzzle_requests: Default::default(),
-            seen_outbound_solutions: RwLock::new(LinkedHashMap::with_capacity(MAX_CACHE_SIZE)),
-            seen_outbound_transactions: RwLock::new(LinkedHashMap::with_capacity(MAX_CACHE_SIZE)),
+            seen_outbound_solutions: Default::default(),
+            seen_outbound_transactions: Default::default(),
             seen_outbound_peer_requests: Default::default(),
+            seen_outbound_relay_requests: Default::default(),
+            config: RwLock::new(config),
         }
     }
+
+    /// Returns the configuration of the cache maps of solutions and transactions.
+    pub fn config(&self) -> CacheConfig {
+        *self.config.read()
+    }
+
+    /// Updates the configuration of the cache maps of solutions and transactions.
+    /// Note: The cache maps are shrunk to the new capacity on their next insertion.
+    pub fn set_config(&self, config: CacheConfig) {
+        *self.config.write() = config;
+    }
 }
 
 impl<N: Network> Cache<N> {
@@ -97,13 +140,18 @@ impl<N: Network> Cache<N> {
         Self::retain_and_insert(&self.seen_inbound_puzzle_requests, peer_ip, 60)
     }
 
+    /// Inserts a new timestamp for the given peer IP, returning the number of recent relay messages.
+    pub fn insert_inbound_relay_message(&self, peer_ip: SocketAddr) -> usize {
+        Self::retain_and_insert(&self.seen_inbound_relay_messages, peer_ip, 60)
+    }
+
     /// Inserts a solution commitment into the cache, returning the previously seen timestamp if it existed.
     pub fn insert_inbound_solution(
         &self,
         peer_ip: SocketAddr,
         solution: PuzzleCommitment<N>,
     ) -> Option<OffsetDateTime> {
-        Self::refresh_and_insert(&self.seen_inbound_solutions, (peer_ip, solution))
+        self.refresh_and_insert("inbound_solutions", &self.seen_inbound_solutions, (peer_ip, solution))
     }
 
     /// Inserts a transaction ID into the cache, returning the previously seen timestamp if it existed.
@@ -112,7 +160,7 @@ impl<N: Network> Cache<N> {
         peer_ip: SocketAddr,
         transaction: N::TransactionID,
     ) -> Option<OffsetDateTime> {
-        Self::refresh_and_insert(&self.seen_inbound_transactions, (peer_ip, transaction))
+        self.refresh_and_insert("inbound_transactions", &self.seen_inbound_transactions, (peer_ip, transaction))
     }
 }
 
@@ -157,7 +205,7 @@ impl<N: Network> Cache<N> {
         peer_ip: SocketAddr,
         solution: PuzzleCommitment<N>,
     ) -> Option<OffsetDateTime> {
-        Self::refresh_and_insert(&self.seen_outbound_solutions, (peer_ip, solution))
+        self.refresh_and_insert("outbound_solutions", &self.seen_outbound_solutions, (peer_ip, solution))
     }
 
     /// Inserts a transaction ID into the cache, returning the previously seen timestamp if it existed.
@@ -166,7 +214,7 @@ impl<N: Network> Cache<N> {
         peer_ip: SocketAddr,
         transaction: N::TransactionID,
     ) -> Option<OffsetDateTime> {
-        Self::refresh_and_insert(&self.seen_outbound_transactions, (peer_ip, transaction))
+        self.refresh_and_insert("outbound_transactions", &self.seen_outbound_transactions, (peer_ip, transaction))
     }
 
     /// Returns `true` if the cache contains a peer request from the given peer.
@@ -183,9 +231,53 @@ impl<N: Network> Cache<N> {
     pub fn decrement_outbound_peer_requests(&self, peer_ip: SocketAddr) -> u32 {
         Self::decrement_counter(&self.seen_outbound_peer_requests, peer_ip)
     }
+
+    /// Returns `true` if the cache contains a recent relay request for the given target peer.
+    pub fn contains_outbound_relay_request(&self, target: &SocketAddr) -> bool {
+        let now = OffsetDateTime::now_utc();
+        self.seen_outbound_relay_requests.read().get(target).map_or(false, |t| Self::is_recent_relay_request(now, *t))
+    }
+
+    /// Inserts a relay request for the given target peer, and evicts the expired relay requests.
+    pub fn insert_outbound_relay_request(&self, target: SocketAddr) {
+        let now = OffsetDateTime::now_utc();
+        let mut map_write = self.seen_outbound_relay_requests.write();
+        map_write.retain(|_, timestamp| Self::is_recent_relay_request(now, *timestamp));
+        map_write.insert(target, now);
+    }
+
+    /// Inserts a relay introduction for the given introduced peer address, and evicts the expired introductions.
+    /// Returns `false` if a recent introduction was already present.
+    pub fn insert_inbound_relay_introduction(&self, observed_addr: SocketAddr) -> bool {
+        let now = OffsetDateTime::now_utc();
+        let mut map_write = self.seen_inbound_relay_introductions.write();
+        map_write.retain(|_, timestamp| Self::is_recent_relay_request(now, *timestamp));
+        if map_write.contains_key(&observed_addr) {
+            return false;
+        }
+        map_write.insert(observed_addr, now);
+        true
+    }
+
+    /// Removes the relay request for the given target peer, returning `true` if a recent request was present.
+    pub fn remove_outbound_relay_request(&self, target: &SocketAddr) -> bool {
+        let now = OffsetDateTime::now_utc();
+        self.seen_outbound_relay_requests
+            .write()
+            .remove(target)
+            .map_or(false, |t| Self::is_recent_relay_request(now, t))
+    }
 }
 
 impl<N: Network> Cache<N> {
+    /// The duration in seconds after which an unanswered relay request expires.
+    const RELAY_REQUEST_TIMEOUT_IN_SECS: i64 = 60;
+
+    /// Returns `true` if the relay request sent at the given timestamp has not expired.
+    fn is_recent_relay_request(now: OffsetDateTime, timestamp: OffsetDateTime) -> bool {
+        now - timestamp <= Duration::seconds(Self::RELAY_REQUEST_TIMEOUT_IN_SECS)
+    }
+
     /// Insert a new timestamp for the given key, returning the number of recent entries.
     fn retain_and_insert<K: Eq + Hash + Clone>(
         map: &RwLock<HashMap<K, VecDeque<OffsetDateTime>>>,
@@ -234,24 +326,56 @@ impl<N: Network> Cache<N> {
         value
     }
 
-    /// Updates the map by enforcing the maximum cache size.
-    fn refresh<K: Eq + Hash, V>(map: &RwLock<LinkedHashMap<K, V>>) {
-        let mut map_write = map.write();
-        while map_write.len() >= MAX_CACHE_SIZE {
-            map_write.pop_front();
+    /// Updates the map by evicting the expired items, and enforcing the maximum cache size.
+    /// Returns the number of evicted items.
+    fn refresh<K: Eq + Hash>(
+        map: &mut LinkedHashMap<K, OffsetDateTime>,
+        config: CacheConfig,
+        now: OffsetDateTime,
+    ) -> usize {
+        let mut num_evicted = 0;
+        // Note: The items are ordered from the least to the most recently seen.
+        while let Some((_, timestamp)) = map.front() {
+            let is_expired = config.ttl.map_or(false, |ttl| now - *timestamp > ttl);
+            if !is_expired && map.len() <= config.capacity {
+                break;
+            }
+            map.pop_front();
+            num_evicted += 1;
         }
+        num_evicted
     }
 
-    /// Updates the map by enforcing the maximum cache size, and inserts the given key.
-    /// Returns the previously seen timestamp if it existed.
+    /// Inserts the given key into the named map, and updates the map by evicting the expired items,
+    /// and enforcing the maximum cache size. Returns the previously seen timestamp if it has not expired.
     fn refresh_and_insert<K: Eq + Hash>(
+        &self,
+        _name: &'static str,
         map: &RwLock<LinkedHashMap<K, OffsetDateTime>>,
         key: K,
     ) -> Option<OffsetDateTime> {
-        // Insert the key, and return the previous timestamp if it existed.
-        let previous_timestamp = map.write().insert(key, OffsetDateTime::now_utc());
+        let config = self.config();
+        // Fetch the current timestamp.
+        let now = OffsetDateTime::now_utc();
+
+        let mut map_write = map.write();
+        // Insert the key, and retrieve the previous timestamp if it has not expired.
+        let previous_timestamp =
+            map_write.insert(key, now).filter(|timestamp| config.ttl.map_or(true, |ttl| now - *timestamp <= ttl));
         // Refresh the cache.
-        Self::refresh(map);
+        let _num_evicted = Self::refresh(&mut map_write, config, now);
+        drop(map_write);
+
+        #[cfg(feature = "metrics")]
+        {
+            let labels = [("cache", _name.to_string())];
+            let outcome = match previous_timestamp.is_some() {
+                true => metrics::router::CACHE_HITS,
+                false => metrics::router::CACHE_MISSES,
+            };
+            metrics::increment_counter_with_labels(outcome, &labels);
+            metrics::counter_with_labels(metrics::router::CACHE_EVICTIONS, &labels, _num_evicted as u64);
+        }
         // Return the previous timestamp.
         previous_timestamp
     }
@@ -354,6 +478,45 @@ mod tests {
         assert_eq!(cache.seen_outbound_transactions.read().len(), 1);
     }
 
+    #[test]
+    fn test_cache_capacity() {
+        let cache = Cache::<CurrentNetwork>::with_config(CacheConfig { capacity: 2, ttl: None });
+        let solution = PuzzleCommitment::<CurrentNetwork>::default();
+        let peer_ips: Vec<_> = (0..3).map(|port| SocketAddr::new(Ipv4Addr::LOCALHOST.into(), port)).collect();
+
+        // Insert more solutions than the capacity.
+        for peer_ip in &peer_ips {
+            assert!(cache.insert_inbound_solution(*peer_ip, solution).is_none());
+        }
+
+        // Check that the least recently seen solution was evicted.
+        assert_eq!(cache.seen_inbound_solutions.read().len(), 2);
+        assert!(cache.insert_inbound_solution(peer_ips[2], solution).is_some());
+        assert!(cache.insert_inbound_solution(peer_ips[0], solution).is_none());
+    }
+
+    #[test]
+    fn test_cache_ttl() {
+        let config = CacheConfig { capacity: DEFAULT_CACHE_SIZE, ttl: Some(std::time::Duration::from_secs(60)) };
+        let mut map = LinkedHashMap::new();
+        let now = OffsetDateTime::now_utc();
+        map.insert(1, now - Duration::seconds(120));
+        map.insert(2, now - Duration::seconds(30));
+
+        // Check that only the expired item is evicted.
+        assert_eq!(Cache::<CurrentNetwork>::refresh(&mut map, config, now), 1);
+        assert!(!map.contains_key(&1));
+        assert!(map.contains_key(&2));
+
+        // Check that an expired item is not considered seen.
+        let cache = Cache::<CurrentNetwork>::with_config(config);
+        let peer_ip = SocketAddr::new(Ipv4Addr::LOCALHOST.into(), 1234);
+        let transaction = Default::default();
+        cache.seen_inbound_transactions.write().insert((peer_ip, transaction), now - Duration::seconds(120));
+        assert!(cache.insert_inbound_transaction(peer_ip, transaction).is_none());
+        assert!(cache.insert_inbound_transaction(peer_ip, transaction).is_some());
+    }
+
     #[test]
     fn test_outbound_peer_request() {
         let cache = Cache::<CurrentNetwork>::default();
@@ -384,4 +547,34 @@ mod tests {
         // Check the cache is empty.
         assert!(!cache.contains_outbound_peer_request(peer_ip));
     }
+
+    #[test]
+    fn test_outbound_relay_request() {
+        let cache = Cache::<CurrentNetwork>::default();
+        let target = SocketAddr::new(Ipv4Addr::LOCALHOST.into(), 1234);
+
+        // Check the cache is empty.
+        assert!(!cache.contains_outbound_relay_request(&target));
+        assert!(!cache.remove_outbound_relay_request(&target));
+
+        // Insert a relay request.
+        cache.insert_outbound_relay_request(target);
+        assert!(cache.contains_outbound_relay_request(&target));
+
+        // Check that the relay request is only answered once.
+        assert!(cache.remove_outbound_relay_request(&target));
+        assert!(!cache.remove_outbound_relay_request(&target));
+        assert!(!cache.contains_outbound_relay_request(&target));
+    }
+
+    #[test]
+    fn test_inbound_relay_introduction() {
+        let cache = Cache::<CurrentNetwork>::default();
+        let peer = SocketAddr::new(Ipv4Addr::LOCALHOST.into(), 1234);
+
+        // Check that a relay introduction is only accepted once per introduced peer, until it expires.
+        assert!(cache.insert_inbound_relay_introduction(peer));
+        assert!(!cache.insert_inbound_relay_introduction(peer));
+        assert!(cache.insert_inbound_relay_introduction(SocketAddr::new(Ipv4Addr::LOCALHOST.into(), 1235)));
+    }
 }
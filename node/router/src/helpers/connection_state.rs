@@ -0,0 +1,61 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::messages::NodeType;
+
+use serde::Serialize;
+use std::{fmt, net::SocketAddr};
+
+/// The state of the connection with a peer, as reported by the router.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConnectionState {
+    /// The peer is connected.
+    Connected,
+    /// The handshake with the peer is in progress.
+    Connecting,
+    /// The peer is banned for its offenses, or by the operator.
+    Banned,
+    /// The peer is restricted for a while, after a failed or a dropped connection.
+    Restricted,
+    /// The peer is known, but not connected.
+    Candidate,
+}
+
+impl fmt::Display for ConnectionState {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", match self {
+            Self::Connected => "connected",
+            Self::Connecting => "connecting",
+            Self::Banned => "banned",
+            Self::Restricted => "restricted",
+            Self::Candidate => "candidate",
+        })
+    }
+}
+
+/// The connection with a peer, as reported by [`Router::connection_states`](crate::Router::connection_states).
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct PeerConnectionState {
+    /// The listener IP of the peer.
+    pub ip: SocketAddr,
+    /// The state of the connection.
+    pub state: ConnectionState,
+    /// The node type of the peer, if it is connected.
+    pub node_type: Option<NodeType>,
+    /// Whether the connection was initiated by this node, if the peer is connected.
+    pub is_outbound: Option<bool>,
+    /// The number of consecutive failed dials to the peer.
+    pub dial_failures: u32,
+}
@@ -0,0 +1,234 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use parking_lot::RwLock;
+use rand::{seq::SliceRandom, Rng};
+use std::{
+    cmp::Reverse,
+    collections::HashMap,
+    net::SocketAddr,
+    time::{Duration, Instant},
+};
+
+/// The delay before redialing an address after its first failed dial.
+pub const BASE_DIAL_BACKOFF: Duration = Duration::from_secs(30);
+/// The maximum delay before redialing an address after consecutive failed dials.
+pub const MAX_DIAL_BACKOFF: Duration = Duration::from_secs(1800);
+/// The duration for which an address is considered fresh after it was last gossiped.
+const FRESH_ADDRESS_WINDOW: Duration = Duration::from_secs(600);
+
+/// The dial history of an address.
+#[derive(Clone, Debug, Default)]
+struct DialState {
+    /// The number of consecutive failed dials.
+    failures: u32,
+    /// The time before which the address must not be redialed, if it is backed off.
+    retry_at: Option<Instant>,
+    /// The time the address was last received from a peer or a seed.
+    seen_at: Option<Instant>,
+}
+
+impl DialState {
+    /// Returns `true` if the address is backed off at the given time.
+    fn is_backed_off(&self, now: Instant) -> bool {
+        self.retry_at.map_or(false, |retry_at| retry_at > now)
+    }
+
+    /// Returns `true` if the address was gossiped recently, as of the given time.
+    fn is_fresh(&self, now: Instant) -> bool {
+        self.seen_at.map_or(false, |seen_at| now.saturating_duration_since(seen_at) < FRESH_ADDRESS_WINDOW)
+    }
+}
+
+/// Returns the delay before redialing an address after the given number of consecutive failed dials.
+pub fn dial_backoff(failures: u32) -> Duration {
+    let exponent = failures.saturating_sub(1).min(16);
+    BASE_DIAL_BACKOFF.saturating_mul(1 << exponent).min(MAX_DIAL_BACKOFF)
+}
+
+/// The scheduler of the outbound dials of the router.
+///
+/// Every failed dial puts the address in an exponential backoff, starting at [`BASE_DIAL_BACKOFF`]
+/// and doubling up to [`MAX_DIAL_BACKOFF`], which is cleared once a connection succeeds.
+/// Among the addresses that are not backed off, the ones gossiped most recently are dialed first.
+#[derive(Debug, Default)]
+pub struct DialScheduler {
+    /// The dial history of the addresses that failed or were gossiped recently.
+    states: RwLock<HashMap<SocketAddr, DialState>>,
+}
+
+impl DialScheduler {
+    /// Records that the given addresses were received from a peer or a seed.
+    pub fn record_seen(&self, peer_ips: &[SocketAddr]) {
+        let now = Instant::now();
+        let mut states = self.states.write();
+        for peer_ip in peer_ips {
+            states.entry(*peer_ip).or_default().seen_at = Some(now);
+        }
+    }
+
+    /// Records a failed dial to the given address, returning the delay before it may be redialed.
+    pub fn record_failure(&self, peer_ip: SocketAddr) -> Duration {
+        self.record_failure_at(peer_ip, Instant::now())
+    }
+
+    /// Records a failed dial to the given address at the given time, returning the delay before it may be redialed.
+    fn record_failure_at(&self, peer_ip: SocketAddr, now: Instant) -> Duration {
+        let mut states = self.states.write();
+        let state = states.entry(peer_ip).or_default();
+        state.failures = state.failures.saturating_add(1);
+        let backoff = dial_backoff(state.failures);
+        state.retry_at = Some(now + backoff);
+        backoff
+    }
+
+    /// Records a successful connection with the given address, clearing its backoff.
+    pub fn record_success(&self, peer_ip: &SocketAddr) {
+        self.states.write().remove(peer_ip);
+    }
+
+    /// Returns `true` if the given address is backed off after failed dials.
+    pub fn is_backed_off(&self, peer_ip: &SocketAddr) -> bool {
+        self.states.read().get(peer_ip).map_or(false, |state| state.is_backed_off(Instant::now()))
+    }
+
+    /// Returns the number of consecutive failed dials to the given address.
+    pub fn failures(&self, peer_ip: &SocketAddr) -> u32 {
+        self.states.read().get(peer_ip).map_or(0, |state| state.failures)
+    }
+
+    /// Returns the time the given address was last received from a peer or a seed, if it was recently.
+    pub fn last_seen(&self, peer_ip: &SocketAddr) -> Option<Instant> {
+        self.states.read().get(peer_ip).and_then(|state| state.seen_at)
+    }
+
+    /// Returns up to `count` of the given addresses to dial, skipping the ones that are backed off,
+    /// and preferring the ones that were gossiped most recently.
+    pub fn schedule<R: Rng>(
+        &self,
+        peer_ips: impl IntoIterator<Item = SocketAddr>,
+        count: usize,
+        rng: &mut R,
+    ) -> Vec<SocketAddr> {
+        self.schedule_at(peer_ips, count, rng, Instant::now())
+    }
+
+    /// Returns up to `count` of the given addresses to dial at the given time.
+    fn schedule_at<R: Rng>(
+        &self,
+        peer_ips: impl IntoIterator<Item = SocketAddr>,
+        count: usize,
+        rng: &mut R,
+        now: Instant,
+    ) -> Vec<SocketAddr> {
+        let states = self.states.read();
+        // Collect the addresses that are not backed off, along with the time they were last gossiped.
+        let mut candidates: Vec<_> = peer_ips
+            .into_iter()
+            .filter_map(|peer_ip| match states.get(&peer_ip) {
+                Some(state) if state.is_backed_off(now) => None,
+                Some(state) => Some((peer_ip, state.seen_at)),
+                None => Some((peer_ip, None)),
+            })
+            .collect();
+        // Shuffle the addresses, so that the ties are broken randomly.
+        candidates.shuffle(rng);
+        // Order the addresses from the most to the least recently gossiped.
+        candidates.sort_by_key(|(_, seen_at)| Reverse(*seen_at));
+        candidates.into_iter().take(count).map(|(peer_ip, _)| peer_ip).collect()
+    }
+
+    /// Removes the addresses that are neither backed off nor fresh, and have not failed for a while.
+    pub fn prune(&self) {
+        self.prune_at(Instant::now())
+    }
+
+    /// Removes the stale addresses as of the given time.
+    fn prune_at(&self, now: Instant) {
+        self.states.write().retain(|_, state| {
+            // Note: The failures are remembered for a while after the backoff, so that they keep escalating.
+            let recently_failed = state.retry_at.map_or(false, |retry_at| now < retry_at + MAX_DIAL_BACKOFF);
+            recently_failed || state.is_fresh(now)
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::OsRng;
+
+    #[test]
+    fn test_dial_backoff() {
+        assert_eq!(dial_backoff(1), BASE_DIAL_BACKOFF);
+        assert_eq!(dial_backoff(2), BASE_DIAL_BACKOFF * 2);
+        assert_eq!(dial_backoff(3), BASE_DIAL_BACKOFF * 4);
+        assert_eq!(dial_backoff(10), MAX_DIAL_BACKOFF);
+        assert_eq!(dial_backoff(u32::MAX), MAX_DIAL_BACKOFF);
+    }
+
+    #[test]
+    fn test_backoff_and_success() {
+        let scheduler = DialScheduler::default();
+        let peer_ip = "1.2.3.4:4130".parse().unwrap();
+        let now = Instant::now();
+
+        // Check that consecutive failures escalate the backoff.
+        assert_eq!(scheduler.record_failure_at(peer_ip, now), BASE_DIAL_BACKOFF);
+        assert_eq!(scheduler.record_failure_at(peer_ip, now), BASE_DIAL_BACKOFF * 2);
+        assert_eq!(scheduler.failures(&peer_ip), 2);
+        assert!(scheduler.is_backed_off(&peer_ip));
+
+        // Check that the address is not scheduled while it is backed off, but is once the backoff expires.
+        assert!(scheduler.schedule_at([peer_ip], 1, &mut OsRng, now).is_empty());
+        assert_eq!(scheduler.schedule_at([peer_ip], 1, &mut OsRng, now + BASE_DIAL_BACKOFF * 2), vec![peer_ip]);
+
+        // Check that a successful connection clears the backoff.
+        scheduler.record_success(&peer_ip);
+        assert_eq!(scheduler.failures(&peer_ip), 0);
+        assert!(!scheduler.is_backed_off(&peer_ip));
+    }
+
+    #[test]
+    fn test_schedule_prefers_fresh_addresses() {
+        let scheduler = DialScheduler::default();
+        let stale: Vec<SocketAddr> = (0..10).map(|i| format!("1.2.3.{i}:4130").parse().unwrap()).collect();
+        let fresh: Vec<SocketAddr> = (0..3).map(|i| format!("5.6.7.{i}:4130").parse().unwrap()).collect();
+        scheduler.record_seen(&fresh);
+
+        // Check that the freshly gossiped addresses are dialed first.
+        let candidates = stale.iter().chain(&fresh).copied();
+        let scheduled = scheduler.schedule(candidates.clone(), 3, &mut OsRng);
+        assert!(scheduled.iter().all(|peer_ip| fresh.contains(peer_ip)));
+        // Check that the remaining addresses fill in the rest.
+        assert_eq!(scheduler.schedule(candidates, 20, &mut OsRng).len(), 13);
+    }
+
+    #[test]
+    fn test_prune() {
+        let scheduler = DialScheduler::default();
+        let failed = "1.2.3.4:4130".parse().unwrap();
+        let seen = "5.6.7.8:4130".parse().unwrap();
+        let now = Instant::now();
+        scheduler.record_failure_at(failed, now);
+        scheduler.record_seen(&[seen]);
+
+        // Check that the recent entries are kept.
+        scheduler.prune_at(now);
+        assert_eq!(scheduler.states.read().len(), 2);
+        // Check that the entries are removed once they are stale.
+        scheduler.prune_at(now + BASE_DIAL_BACKOFF + MAX_DIAL_BACKOFF + FRESH_ADDRESS_WINDOW);
+        assert!(scheduler.states.read().is_empty());
+    }
+}
@@ -0,0 +1,68 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::{net::SocketAddr, time::Duration};
+
+/// The port of the peers returned by a DNS seed, unless the seed specifies a port.
+pub const DNS_SEED_DEFAULT_PORT: u16 = 4130;
+/// The interval in between queries to the DNS seeds.
+pub const DNS_SEED_INTERVAL: Duration = Duration::from_secs(30 * 60);
+
+/// Returns the `host:port` address to resolve for the given DNS seed, using the default port if none is given.
+fn seed_address(seed: &str) -> String {
+    match seed.rsplit_once(':').map(|(_, port)| port.parse::<u16>()) {
+        Some(Ok(_)) => seed.to_string(),
+        _ => format!("{seed}:{DNS_SEED_DEFAULT_PORT}"),
+    }
+}
+
+/// Resolves the given DNS seed hostnames to the peer addresses they list.
+/// Note: The seeds that fail to resolve are skipped.
+pub async fn resolve_dns_seeds(seeds: &[String]) -> Vec<SocketAddr> {
+    let mut peers = Vec::new();
+    for seed in seeds {
+        match tokio::net::lookup_host(seed_address(seed)).await {
+            Ok(addresses) => {
+                let num_peers = peers.len();
+                for address in addresses {
+                    if !peers.contains(&address) {
+                        peers.push(address);
+                    }
+                }
+                debug!("Resolved {} peer(s) from the DNS seed '{seed}'", peers.len() - num_peers);
+            }
+            Err(error) => warn!("Failed to resolve the DNS seed '{seed}' - {error}"),
+        }
+    }
+    peers
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_seed_address() {
+        assert_eq!(seed_address("seed.example.com"), "seed.example.com:4130");
+        assert_eq!(seed_address("seed.example.com:4133"), "seed.example.com:4133");
+        assert_eq!(seed_address("seed.example.com:"), "seed.example.com::4130");
+    }
+
+    #[tokio::test]
+    async fn test_resolve_dns_seeds() {
+        let seeds = vec!["127.0.0.1".to_string(), "127.0.0.1:4130".to_string(), "127.0.0.2:4131".to_string()];
+        let peers = resolve_dns_seeds(&seeds).await;
+        assert_eq!(peers, vec![SocketAddr::from(([127, 0, 0, 1], 4130)), SocketAddr::from(([127, 0, 0, 2], 4131))]);
+    }
+}
@@ -0,0 +1,109 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use indexmap::IndexMap;
+use parking_lot::Mutex;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+/// The minimum number of observer subnets that must agree on the external IP, before it is detected.
+pub const MINIMUM_AGREEING_OBSERVERS: usize = 3;
+/// The maximum number of observations kept, after which the oldest observation is evicted.
+const MAXIMUM_OBSERVATIONS: usize = 64;
+
+/// Derives the external IP of the node from the source addresses observed by its peers.
+///
+/// Each observer subnet (a /24 for IPv4, and a /48 for IPv6) has a single vote, which is updated by its latest
+/// observation, so that a peer cannot sway the result by connecting from many addresses. An IP is only detected
+/// once it is observed by at least [`MINIMUM_AGREEING_OBSERVERS`] subnets, and by a strict majority of them.
+#[derive(Debug, Default)]
+pub struct ExternalAddrDetector {
+    /// The map of observer subnets to their latest observed IP, from the oldest to the latest observation.
+    observations: Mutex<IndexMap<IpAddr, IpAddr>>,
+}
+
+impl ExternalAddrDetector {
+    /// Records the IP of this node observed by the given peer IP, and returns the detected external IP (if any).
+    pub fn record(&self, observer: IpAddr, observed: IpAddr) -> Option<IpAddr> {
+        let mut observations = self.observations.lock();
+        // Replace the previous observation of the subnet, moving it to the end.
+        let subnet = subnet_of(observer);
+        observations.shift_remove(&subnet);
+        observations.insert(subnet, observed);
+        // Evict the oldest observations.
+        while observations.len() > MAXIMUM_OBSERVATIONS {
+            observations.shift_remove_index(0);
+        }
+        Self::detect(&observations)
+    }
+
+    /// Returns the detected external IP, if any.
+    pub fn detected(&self) -> Option<IpAddr> {
+        Self::detect(&self.observations.lock())
+    }
+
+    /// Returns the IP observed by a strict majority of (at least `MINIMUM_AGREEING_OBSERVERS`) subnets, if any.
+    fn detect(observations: &IndexMap<IpAddr, IpAddr>) -> Option<IpAddr> {
+        // Count the votes for each observed IP.
+        let mut votes = IndexMap::<IpAddr, usize>::new();
+        for observed in observations.values() {
+            *votes.entry(*observed).or_default() += 1;
+        }
+        // Select the IP with the most votes.
+        let (ip, num_votes) = votes.into_iter().max_by_key(|(_, num_votes)| *num_votes)?;
+        (num_votes >= MINIMUM_AGREEING_OBSERVERS && num_votes * 2 > observations.len()).then_some(ip)
+    }
+}
+
+/// Returns the subnet of the given IP (i.e. its /24 prefix for IPv4, and its /48 prefix for IPv6).
+fn subnet_of(ip: IpAddr) -> IpAddr {
+    match ip {
+        IpAddr::V4(ip) => {
+            let [a, b, c, _] = ip.octets();
+            IpAddr::V4(Ipv4Addr::new(a, b, c, 0))
+        }
+        IpAddr::V6(ip) => {
+            let [a, b, c, ..] = ip.segments();
+            IpAddr::V6(Ipv6Addr::new(a, b, c, 0, 0, 0, 0, 0))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_external_addr_detection() {
+        let detector = ExternalAddrDetector::default();
+        let external: IpAddr = "203.0.113.7".parse().unwrap();
+
+        // Check that the observations from a single subnet count as a single vote.
+        assert_eq!(detector.record("198.51.100.1".parse().unwrap(), external), None);
+        assert_eq!(detector.record("198.51.100.2".parse().unwrap(), external), None);
+        assert_eq!(detector.record("198.51.100.3".parse().unwrap(), external), None);
+
+        // Check that the IP is detected once enough subnets agree.
+        assert_eq!(detector.record("192.0.2.1".parse().unwrap(), external), None);
+        assert_eq!(detector.record("100.64.0.1".parse().unwrap(), external), Some(external));
+        assert_eq!(detector.detected(), Some(external));
+
+        // Check that a minority of spoofed observations does not change the detected IP.
+        let spoofed: IpAddr = "203.0.113.66".parse().unwrap();
+        assert_eq!(detector.record("10.1.1.1".parse().unwrap(), spoofed), Some(external));
+        assert_eq!(detector.record("10.2.2.2".parse().unwrap(), spoofed), Some(external));
+
+        // Check that the IP is no longer detected without a strict majority.
+        assert_eq!(detector.record("10.3.3.3".parse().unwrap(), spoofed), None);
+    }
+}
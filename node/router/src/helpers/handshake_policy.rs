@@ -0,0 +1,113 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::messages::{Capabilities, ChallengeRequest, DisconnectReason, NodeVersion};
+use snarkvm::prelude::Network;
+
+/// The policy deciding which peers are accepted during the handshake, based on what they advertise.
+#[derive(Clone, Debug, Default)]
+pub struct HandshakePolicy {
+    /// The minimum node version of the peers, if any.
+    pub min_node_version: Option<NodeVersion>,
+    /// The capabilities every peer must support.
+    pub required_capabilities: Capabilities,
+    /// The user agent prefixes of the peers to reject (e.g. `snarkOS/2.1`), compared case-insensitively.
+    pub denied_user_agents: Vec<String>,
+}
+
+impl HandshakePolicy {
+    /// Returns `true` if the policy accepts every peer.
+    pub fn is_permissive(&self) -> bool {
+        self.min_node_version.is_none()
+            && self.required_capabilities == Capabilities::NONE
+            && self.denied_user_agents.is_empty()
+    }
+
+    /// Checks the given challenge request against the policy.
+    /// Returns a disconnect reason, along with a description of the violation, if the peer is rejected.
+    pub fn check<N: Network>(&self, request: &ChallengeRequest<N>) -> Result<(), (DisconnectReason, String)> {
+        // Ensure the node version of the peer is recent enough.
+        if let Some(min_node_version) = self.min_node_version {
+            if request.node_version < min_node_version {
+                return Err((
+                    DisconnectReason::OutdatedClientVersion,
+                    format!("node version {} is below {min_node_version}", request.node_version),
+                ));
+            }
+        }
+        // Ensure the peer supports the required capabilities.
+        if !request.capabilities.contains(self.required_capabilities) {
+            return Err((
+                DisconnectReason::IncompatiblePeer,
+                format!("capabilities '{}' lack '{}'", request.capabilities, self.required_capabilities),
+            ));
+        }
+        // Ensure the user agent of the peer is not denied.
+        let user_agent = request.user_agent.to_lowercase();
+        if let Some(denied) =
+            self.denied_user_agents.iter().find(|denied| user_agent.starts_with(&denied.to_lowercase()))
+        {
+            return Err((
+                DisconnectReason::IncompatiblePeer,
+                format!("user agent '{}' matches '{denied}'", request.user_agent),
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::messages::NodeType;
+    use snarkvm::prelude::{Address, MainnetV0, TestRng, Uniform};
+
+    type CurrentNetwork = MainnetV0;
+
+    fn sample_request(user_agent: &str, capabilities: Capabilities) -> ChallengeRequest<CurrentNetwork> {
+        let rng = &mut TestRng::default();
+        let mut request =
+            ChallengeRequest::new(4130, NodeType::Client, Address::rand(rng), 0, user_agent.to_string(), capabilities);
+        request.node_version = NodeVersion::new(2, 2, 7);
+        request
+    }
+
+    #[test]
+    fn test_handshake_policy() {
+        let request = sample_request("snarkOS/2.2.7", Capabilities::COMPRESSION);
+
+        // Check that the default policy accepts every peer.
+        let policy = HandshakePolicy::default();
+        assert!(policy.is_permissive());
+        assert!(policy.check(&request).is_ok());
+
+        // Check the minimum node version.
+        let policy = HandshakePolicy { min_node_version: Some(NodeVersion::new(2, 3, 0)), ..Default::default() };
+        assert_eq!(policy.check(&request).unwrap_err().0, DisconnectReason::OutdatedClientVersion);
+        let policy = HandshakePolicy { min_node_version: Some(NodeVersion::new(2, 2, 7)), ..Default::default() };
+        assert!(policy.check(&request).is_ok());
+
+        // Check the required capabilities.
+        let policy = HandshakePolicy { required_capabilities: Capabilities::ARCHIVE, ..Default::default() };
+        assert_eq!(policy.check(&request).unwrap_err().0, DisconnectReason::IncompatiblePeer);
+        let request = sample_request("snarkOS/2.2.7", Capabilities::COMPRESSION | Capabilities::ARCHIVE);
+        assert!(policy.check(&request).is_ok());
+
+        // Check the denied user agents.
+        let policy = HandshakePolicy { denied_user_agents: vec!["SNARKOS/2.2".to_string()], ..Default::default() };
+        assert_eq!(policy.check(&request).unwrap_err().0, DisconnectReason::IncompatiblePeer);
+        let policy = HandshakePolicy { denied_user_agents: vec!["snarkOS/2.1".to_string()], ..Default::default() };
+        assert!(policy.check(&request).is_ok());
+    }
+}
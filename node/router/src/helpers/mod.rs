@@ -12,11 +12,52 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+mod access_list;
+pub use access_list::*;
+
 mod cache;
-pub use cache::Cache;
+pub use cache::{Cache, CacheConfig, DEFAULT_CACHE_SIZE};
+
+mod connection_state;
+pub use connection_state::*;
+
+mod dial_scheduler;
+pub use dial_scheduler::*;
+
+mod dns_seeds;
+pub use dns_seeds::*;
+
+mod external_addr;
+pub use external_addr::*;
+
+mod handshake_policy;
+pub use handshake_policy::*;
 
 mod peer;
 pub use peer::*;
 
+mod peer_limits;
+pub use peer_limits::*;
+
+mod peer_store;
+pub use peer_store::*;
+
+mod port_mapping;
+pub use port_mapping::*;
+
+#[cfg(feature = "record")]
+mod recorder;
+#[cfg(feature = "record")]
+pub use recorder::*;
+
+mod reputation;
+pub use reputation::*;
+
 mod resolver;
 pub use resolver::*;
+
+mod sync_status;
+pub use sync_status::*;
+
+mod topology;
+pub use topology::*;
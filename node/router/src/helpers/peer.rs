@@ -12,10 +12,13 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::messages::{ChallengeRequest, NodeType};
+use crate::messages::{Capabilities, ChallengeRequest, NodeType, NodeVersion, NoiseState};
 use snarkvm::prelude::{Address, Network};
 
-use std::{net::SocketAddr, time::Instant};
+use std::{
+    net::SocketAddr,
+    time::{Duration, Instant},
+};
 
 /// The state for each connected peer.
 #[derive(Clone, Debug)]
@@ -28,22 +31,53 @@ pub struct Peer<N: Network> {
     node_type: NodeType,
     /// The message version of the peer.
     version: u32,
+    /// The boolean flag indicating whether the connection was initiated by this node.
+    is_outbound: bool,
+    /// The software version of the peer.
+    node_version: NodeVersion,
+    /// The user agent of the peer.
+    user_agent: String,
+    /// The optional features supported by the peer.
+    capabilities: Capabilities,
+    /// The boolean flag indicating whether the messages exchanged with the peer are compressed.
+    compression: bool,
+    /// The transport state of the encrypted connection to the peer, as of the end of the handshake.
+    /// Note: This is `None` if the peers did not negotiate the noise handshake.
+    noise_state: Option<NoiseState>,
     /// The timestamp of the first message received from the peer.
     first_seen: Instant,
     /// The timestamp of the last message received from this peer.
     last_seen: Instant,
+    /// The timestamp of the last ping sent to the peer, if it is awaiting a pong.
+    ping_sent_at: Option<Instant>,
+    /// The round-trip time of the last ping to the peer, if it was measured.
+    latency: Option<Duration>,
 }
 
 impl<N: Network> Peer<N> {
     /// Initializes a new instance of `Peer`.
-    pub fn new(listening_ip: SocketAddr, challenge_request: &ChallengeRequest<N>) -> Self {
+    pub fn new(
+        listening_ip: SocketAddr,
+        challenge_request: &ChallengeRequest<N>,
+        is_outbound: bool,
+        compression: bool,
+        noise_state: Option<NoiseState>,
+    ) -> Self {
         Self {
             peer_ip: listening_ip,
             address: challenge_request.address,
             node_type: challenge_request.node_type,
             version: challenge_request.version,
+            node_version: challenge_request.node_version,
+            user_agent: challenge_request.user_agent.clone(),
+            capabilities: challenge_request.capabilities,
+            is_outbound,
+            compression,
+            noise_state,
             first_seen: Instant::now(),
             last_seen: Instant::now(),
+            ping_sent_at: None,
+            latency: None,
         }
     }
 
@@ -82,6 +116,37 @@ impl<N: Network> Peer<N> {
         self.version
     }
 
+    /// Returns the software version of the peer.
+    pub const fn node_version(&self) -> NodeVersion {
+        self.node_version
+    }
+
+    /// Returns the user agent of the peer.
+    pub fn user_agent(&self) -> &str {
+        &self.user_agent
+    }
+
+    /// Returns the optional features supported by the peer.
+    pub const fn capabilities(&self) -> Capabilities {
+        self.capabilities
+    }
+
+    /// Returns `true` if the connection was initiated by this node.
+    pub const fn is_outbound(&self) -> bool {
+        self.is_outbound
+    }
+
+    /// Returns `true` if the messages exchanged with the peer are compressed.
+    pub const fn is_compressed(&self) -> bool {
+        self.compression
+    }
+
+    /// Returns the transport state of the encrypted connection to the peer, as of the end of the handshake,
+    /// or `None` if the connection is not encrypted.
+    pub fn noise_state(&self) -> Option<&NoiseState> {
+        self.noise_state.as_ref()
+    }
+
     /// Returns the first seen timestamp of the peer.
     pub fn first_seen(&self) -> Instant {
         self.first_seen
@@ -91,6 +156,11 @@ impl<N: Network> Peer<N> {
     pub fn last_seen(&self) -> Instant {
         self.last_seen
     }
+
+    /// Returns the round-trip time of the last ping to the peer, if it was measured.
+    pub fn latency(&self) -> Option<Duration> {
+        self.latency
+    }
 }
 
 impl<N: Network> Peer<N> {
@@ -108,4 +178,16 @@ impl<N: Network> Peer<N> {
     pub fn set_last_seen(&mut self, last_seen: Instant) {
         self.last_seen = last_seen;
     }
+
+    /// Records that a ping was sent to the peer.
+    pub fn set_ping_sent(&mut self, sent_at: Instant) {
+        self.ping_sent_at = Some(sent_at);
+    }
+
+    /// Records that a pong was received from the peer, updating its latency.
+    pub fn set_pong_received(&mut self, received_at: Instant) {
+        if let Some(sent_at) = self.ping_sent_at.take() {
+            self.latency = Some(received_at.saturating_duration_since(sent_at));
+        }
+    }
 }
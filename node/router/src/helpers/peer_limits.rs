@@ -0,0 +1,109 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+
+use crate::messages::NodeType;
+
+use anyhow::{bail, Result};
+use serde::{Deserialize, Serialize};
+
+/// The minimum number of peers required to maintain connections with.
+pub const MINIMUM_NUMBER_OF_PEERS: usize = 3;
+/// The default maximum number of peers for clients and provers.
+const DEFAULT_MAXIMUM_NUMBER_OF_PEERS: usize = 21;
+/// The default maximum number of peers for validators.
+const DEFAULT_MAXIMUM_NUMBER_OF_VALIDATOR_PEERS: usize = 200;
+
+/// The limits on the number of inbound and outbound peer connections.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct PeerLimits {
+    /// The maximum number of connections initiated by peers.
+    pub max_inbound: usize,
+    /// The maximum number of connections initiated by this node.
+    pub max_outbound: usize,
+    /// The number of connections this node attempts to initiate and maintain.
+    pub target_outbound: usize,
+}
+
+impl PeerLimits {
+    /// Returns the default peer limits for the given node type.
+    pub fn new(node_type: NodeType) -> Self {
+        match node_type {
+            NodeType::Validator => Self::from_max_peers(DEFAULT_MAXIMUM_NUMBER_OF_VALIDATOR_PEERS),
+            NodeType::Client | NodeType::Prover => Self::from_max_peers(DEFAULT_MAXIMUM_NUMBER_OF_PEERS),
+        }
+    }
+
+    /// Returns the peer limits for the given maximum number of peers, which are split into
+    /// (roughly) half outbound connections and half inbound connections.
+    pub fn from_max_peers(max_peers: usize) -> Self {
+        let target_outbound = (max_peers / 2).max(MINIMUM_NUMBER_OF_PEERS).min(max_peers);
+        Self { max_inbound: max_peers - target_outbound, max_outbound: target_outbound, target_outbound }
+    }
+
+    /// Returns the maximum number of connections.
+    pub fn max_connections(&self) -> usize {
+        self.max_inbound.saturating_add(self.max_outbound)
+    }
+
+    /// Ensures the peer limits are consistent, and fit within the given maximum number of connections.
+    pub fn validate(&self, max_connections: usize) -> Result<()> {
+        if self.target_outbound == 0 {
+            bail!("The target number of outbound peers must be at least 1")
+        }
+        if self.target_outbound > self.max_outbound {
+            bail!(
+                "The target number of outbound peers ({}) exceeds the maximum ({})",
+                self.target_outbound,
+                self.max_outbound
+            )
+        }
+        if self.max_connections() > max_connections {
+            bail!("The maximum number of peers ({}) exceeds the limit of {max_connections}", self.max_connections())
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_peer_limits_defaults() {
+        let limits = PeerLimits::new(NodeType::Client);
+        assert_eq!(limits, PeerLimits { max_inbound: 11, max_outbound: 10, target_outbound: 10 });
+        assert_eq!(limits.max_connections(), DEFAULT_MAXIMUM_NUMBER_OF_PEERS);
+
+        let limits = PeerLimits::new(NodeType::Validator);
+        assert_eq!(limits, PeerLimits { max_inbound: 100, max_outbound: 100, target_outbound: 100 });
+        assert_eq!(limits.max_connections(), DEFAULT_MAXIMUM_NUMBER_OF_VALIDATOR_PEERS);
+
+        // Ensure small limits still target the minimum number of peers.
+        let limits = PeerLimits::from_max_peers(4);
+        assert_eq!(limits, PeerLimits { max_inbound: 1, max_outbound: 3, target_outbound: 3 });
+        assert!(limits.validate(4).is_ok());
+    }
+
+    #[test]
+    fn test_peer_limits_validate() {
+        let limits = PeerLimits { max_inbound: 10, max_outbound: 8, target_outbound: 8 };
+        assert!(limits.validate(18).is_ok());
+        // Ensure the limits must fit within the maximum number of connections.
+        assert!(limits.validate(17).is_err());
+        // Ensure the target must be within the maximum number of outbound peers.
+        assert!(PeerLimits { target_outbound: 9, ..limits }.validate(18).is_err());
+        assert!(PeerLimits { target_outbound: 0, ..limits }.validate(18).is_err());
+    }
+}
@@ -0,0 +1,186 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::{messages::NodeType, Peer};
+use snarkvm::prelude::Network;
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    fs,
+    net::SocketAddr,
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+/// The name of the peer store file.
+pub const PEER_STORE_FILE: &str = "peers.json";
+/// The maximum number of peers in the peer store.
+const MAX_STORED_PEERS: usize = 256;
+/// The duration after which a peer that has not been seen is removed from the peer store.
+const STORED_PEER_EXPIRY: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
+/// A peer in the peer store.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StoredPeer {
+    /// The IP address of the peer, with the port set to the listener port.
+    pub ip: SocketAddr,
+    /// The node type of the peer.
+    pub node_type: NodeType,
+    /// The UNIX timestamp (in seconds) of the last message received from the peer.
+    pub last_seen: u64,
+    /// The latency of the peer, in milliseconds, if it was measured.
+    pub latency_ms: Option<u64>,
+}
+
+impl StoredPeer {
+    /// Initializes the stored peer for the given connected peer.
+    pub fn new<N: Network>(peer: &Peer<N>) -> Self {
+        Self {
+            ip: peer.ip(),
+            node_type: peer.node_type(),
+            last_seen: unix_timestamp().saturating_sub(peer.last_seen().elapsed().as_secs()),
+            latency_ms: peer.latency().map(|latency| latency.as_millis() as u64),
+        }
+    }
+}
+
+/// An on-disk store of the known-good peers, so that a restarted node reconnects to them quickly,
+/// instead of relying solely on the bootstrap peers.
+///
+/// The store keeps the peers seen in the last week, up to [`MAX_STORED_PEERS`] of the most recently seen.
+#[derive(Debug)]
+pub struct PeerStore {
+    /// The path of the peer store file.
+    path: PathBuf,
+    /// The stored peers.
+    peers: HashMap<SocketAddr, StoredPeer>,
+}
+
+impl PeerStore {
+    /// Opens the peer store at the given path, loading the stored peers (if any).
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let peers: Vec<StoredPeer> = match path.exists() {
+            true => serde_json::from_slice(&fs::read(&path)?)?,
+            false => Vec::new(),
+        };
+        let mut store = Self { path, peers: peers.into_iter().map(|peer| (peer.ip, peer)).collect() };
+        store.prune();
+        Ok(store)
+    }
+
+    /// Returns the stored peers, from the most to the least recently seen.
+    pub fn peers(&self) -> Vec<StoredPeer> {
+        let mut peers: Vec<_> = self.peers.values().cloned().collect();
+        peers.sort_by(|a, b| b.last_seen.cmp(&a.last_seen));
+        peers
+    }
+
+    /// Inserts or updates the given peer.
+    pub fn insert(&mut self, peer: StoredPeer) {
+        self.peers.insert(peer.ip, peer);
+    }
+
+    /// Removes the given peer, if it exists.
+    pub fn remove(&mut self, peer_ip: &SocketAddr) {
+        self.peers.remove(peer_ip);
+    }
+
+    /// Writes the peer store to disk.
+    pub fn save(&mut self) -> Result<()> {
+        self.prune();
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        // Write to a temporary file first, so that an interrupted write never corrupts the peer store.
+        let temp_path = self.path.with_extension("tmp");
+        fs::write(&temp_path, serde_json::to_vec_pretty(&self.peers())?)?;
+        fs::rename(temp_path, &self.path)?;
+        Ok(())
+    }
+
+    /// Removes the expired peers, and the least recently seen peers beyond the maximum.
+    fn prune(&mut self) {
+        let expiry = unix_timestamp().saturating_sub(STORED_PEER_EXPIRY.as_secs());
+        self.peers.retain(|_, peer| peer.last_seen >= expiry);
+        if self.peers.len() > MAX_STORED_PEERS {
+            let peers = self.peers();
+            self.peers = peers.into_iter().take(MAX_STORED_PEERS).map(|peer| (peer.ip, peer)).collect();
+        }
+    }
+}
+
+/// Returns the current UNIX timestamp, in seconds.
+fn unix_timestamp() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |duration| duration.as_secs())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns a sample peer with the given port, last seen at the given UNIX timestamp.
+    fn sample_peer(port: u16, last_seen: u64) -> StoredPeer {
+        StoredPeer {
+            ip: SocketAddr::from(([1, 2, 3, 4], port)),
+            node_type: NodeType::Client,
+            last_seen,
+            latency_ms: Some(port as u64),
+        }
+    }
+
+    #[test]
+    fn test_peer_store() {
+        let dir = std::env::temp_dir().join(format!("snarkos-peer-store-{}", std::process::id()));
+        let path = dir.join(PEER_STORE_FILE);
+        let now = unix_timestamp();
+
+        // Check that a missing peer store is empty.
+        let mut store = PeerStore::open(&path).unwrap();
+        assert!(store.peers().is_empty());
+
+        // Check that the peers are persisted, except for the expired ones.
+        store.insert(sample_peer(4130, now - 60));
+        store.insert(sample_peer(4131, now));
+        store.insert(sample_peer(4132, now - STORED_PEER_EXPIRY.as_secs() - 60));
+        store.remove(&SocketAddr::from(([1, 2, 3, 4], 4131)));
+        store.insert(sample_peer(4133, now));
+        store.save().unwrap();
+
+        let store = PeerStore::open(&path).unwrap();
+        assert_eq!(store.peers(), vec![sample_peer(4133, now), sample_peer(4130, now - 60)]);
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_peer_store_limit() {
+        let path = std::env::temp_dir().join(format!("snarkos-peer-store-limit-{}.json", std::process::id()));
+        let now = unix_timestamp();
+        let mut store = PeerStore::open(&path).unwrap();
+        for port in 0..MAX_STORED_PEERS as u16 + 10 {
+            store.insert(sample_peer(port, now - port as u64));
+        }
+        store.save().unwrap();
+
+        // Check that only the most recently seen peers are kept.
+        let peers = PeerStore::open(&path).unwrap().peers();
+        assert_eq!(peers.len(), MAX_STORED_PEERS);
+        assert_eq!(peers.last().unwrap().ip.port(), MAX_STORED_PEERS as u16 - 1);
+
+        fs::remove_file(path).unwrap();
+    }
+}
@@ -0,0 +1,150 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use anyhow::{anyhow, bail, ensure, Result};
+use igd_next::{aio::tokio::search_gateway, PortMappingProtocol, SearchOptions};
+use std::{
+    net::{IpAddr, Ipv4Addr, SocketAddr, SocketAddrV4},
+    time::Duration,
+};
+use tokio::net::UdpSocket;
+
+/// The duration of a port mapping lease, after which the mapping expires unless it is renewed.
+pub const PORT_MAPPING_LEASE: Duration = Duration::from_secs(60 * 60);
+/// The description of the port mapping, as shown by the router.
+const PORT_MAPPING_DESCRIPTION: &str = "snarkOS";
+/// The time to wait for the router to respond.
+const GATEWAY_TIMEOUT: Duration = Duration::from_secs(3);
+/// The port of the NAT-PMP service of the router.
+const NAT_PMP_PORT: u16 = 5351;
+
+/// Asks the local router to forward the given TCP port of this node, first over UPnP and then over NAT-PMP,
+/// returning the resulting external address of the node.
+pub async fn map_port(port: u16) -> Result<SocketAddr> {
+    let local_ip = local_ipv4().await?;
+    // Note: For NAT-PMP, the router is assumed to be the first host of the local /24 subnet (e.g. `192.168.1.1`).
+    let [a, b, c, _] = local_ip.octets();
+    let gateway = SocketAddr::from((Ipv4Addr::new(a, b, c, 1), NAT_PMP_PORT));
+    match map_port_upnp(local_ip, port).await {
+        Ok(external_addr) => Ok(external_addr),
+        Err(upnp_error) => match map_port_nat_pmp(gateway, port).await {
+            Ok(external_addr) => Ok(external_addr),
+            Err(nat_pmp_error) => bail!("UPnP failed ({upnp_error}), and NAT-PMP failed ({nat_pmp_error})"),
+        },
+    }
+}
+
+/// Returns the IPv4 address of the local interface that routes to the internet.
+/// Note: Connecting a UDP socket does not send any packets.
+async fn local_ipv4() -> Result<Ipv4Addr> {
+    let socket = UdpSocket::bind((Ipv4Addr::UNSPECIFIED, 0)).await?;
+    socket.connect((Ipv4Addr::new(1, 1, 1, 1), 80)).await?;
+    match socket.local_addr()?.ip() {
+        IpAddr::V4(ip) if !ip.is_unspecified() => Ok(ip),
+        ip => bail!("Unexpected local address '{ip}'"),
+    }
+}
+
+/// Maps the given port over UPnP, returning the external address.
+async fn map_port_upnp(local_ip: Ipv4Addr, port: u16) -> Result<SocketAddr> {
+    let options = SearchOptions { timeout: Some(GATEWAY_TIMEOUT), ..Default::default() };
+    let gateway = search_gateway(options).await?;
+    let external_ip = gateway.get_external_ip().await?;
+    let local_addr = SocketAddr::V4(SocketAddrV4::new(local_ip, port));
+    let lease = PORT_MAPPING_LEASE.as_secs() as u32;
+    gateway.add_port(PortMappingProtocol::TCP, port, local_addr, lease, PORT_MAPPING_DESCRIPTION).await?;
+    Ok(SocketAddr::new(external_ip, port))
+}
+
+/// Maps the given port over NAT-PMP (RFC 6886) with the given router, returning the external address.
+async fn map_port_nat_pmp(gateway: SocketAddr, port: u16) -> Result<SocketAddr> {
+    let socket = UdpSocket::bind((Ipv4Addr::UNSPECIFIED, 0)).await?;
+    socket.connect(gateway).await?;
+
+    // Request the external address.
+    let response = nat_pmp_request(&socket, &[0, 0], 12).await?;
+    ensure!(response[1] == 128, "Unexpected NAT-PMP response to the external address request");
+    let external_ip = Ipv4Addr::new(response[8], response[9], response[10], response[11]);
+
+    // Request the mapping of the TCP port.
+    let mut request = vec![0, 2, 0, 0];
+    request.extend_from_slice(&port.to_be_bytes());
+    request.extend_from_slice(&port.to_be_bytes());
+    request.extend_from_slice(&(PORT_MAPPING_LEASE.as_secs() as u32).to_be_bytes());
+    let response = nat_pmp_request(&socket, &request, 16).await?;
+    ensure!(response[1] == 130, "Unexpected NAT-PMP response to the mapping request");
+    let external_port = u16::from_be_bytes([response[10], response[11]]);
+    Ok(SocketAddr::new(IpAddr::V4(external_ip), external_port))
+}
+
+/// Sends the given NAT-PMP request, returning the response of the given length if it succeeded.
+async fn nat_pmp_request(socket: &UdpSocket, request: &[u8], length: usize) -> Result<Vec<u8>> {
+    socket.send(request).await?;
+    let mut response = vec![0u8; length];
+    let num_bytes = tokio::time::timeout(GATEWAY_TIMEOUT, socket.recv(&mut response))
+        .await
+        .map_err(|_| anyhow!("The router did not respond"))??;
+    ensure!(num_bytes == length, "Malformed NAT-PMP response ({num_bytes} bytes)");
+    // Ensure the request succeeded.
+    match u16::from_be_bytes([response[2], response[3]]) {
+        0 => Ok(response),
+        result => bail!("The NAT-PMP request failed with result code {result}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_map_port_nat_pmp() {
+        // Initialize a fake NAT-PMP router.
+        let router = UdpSocket::bind((Ipv4Addr::LOCALHOST, 0)).await.unwrap();
+        let gateway = router.local_addr().unwrap();
+        let handle = tokio::spawn(async move {
+            let mut request = [0u8; 12];
+            // Respond to the external address request.
+            let (num_bytes, peer) = router.recv_from(&mut request).await.unwrap();
+            assert_eq!(&request[..num_bytes], &[0, 0]);
+            let response = [0, 128, 0, 0, 0, 0, 0, 1, 203, 0, 113, 7];
+            router.send_to(&response, peer).await.unwrap();
+            // Respond to the mapping request, with a different external port.
+            let (num_bytes, peer) = router.recv_from(&mut request).await.unwrap();
+            assert_eq!(num_bytes, 12);
+            assert_eq!(&request[..6], &[0, 2, 0, 0, 0x10, 0x22]);
+            let response = [0, 130, 0, 0, 0, 0, 0, 2, 0x10, 0x22, 0x10, 0x23, 0, 0, 0x0e, 0x10];
+            router.send_to(&response, peer).await.unwrap();
+        });
+
+        let external_addr = map_port_nat_pmp(gateway, 4130).await.unwrap();
+        assert_eq!(external_addr, SocketAddr::from(([203, 0, 113, 7], 4131)));
+        handle.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_map_port_nat_pmp_failure() {
+        // Initialize a fake NAT-PMP router, which refuses the requests.
+        let router = UdpSocket::bind((Ipv4Addr::LOCALHOST, 0)).await.unwrap();
+        let gateway = router.local_addr().unwrap();
+        tokio::spawn(async move {
+            let mut request = [0u8; 12];
+            let (_, peer) = router.recv_from(&mut request).await.unwrap();
+            // Respond with the "not authorized" result code.
+            router.send_to(&[0, 128, 0, 2, 0, 0, 0, 1, 0, 0, 0, 0], peer).await.unwrap();
+        });
+
+        let error = map_port_nat_pmp(gateway, 4130).await.unwrap_err();
+        assert_eq!(error.to_string(), "The NAT-PMP request failed with result code 2");
+    }
+}
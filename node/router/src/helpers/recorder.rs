@@ -0,0 +1,180 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::messages::Message;
+use snarkvm::prelude::{FromBytes, Network, ToBytes};
+
+use anyhow::{bail, Result};
+use std::{
+    fs::File,
+    io::{self, BufReader, BufWriter, Read, Write},
+    net::SocketAddr,
+    path::Path,
+    time::Instant,
+};
+
+/// The magic bytes at the start of every recording.
+const RECORDING_MAGIC: [u8; 8] = *b"SNARKREC";
+/// The version of the recording format.
+const RECORDING_VERSION: u16 = 1;
+
+/// An inbound message, as captured by the [`Recorder`].
+#[derive(Clone, Debug)]
+pub struct RecordedMessage<N: Network> {
+    /// The number of milliseconds elapsed since the recording started.
+    pub elapsed_ms: u64,
+    /// The (ambiguous) peer address the message was received from.
+    pub peer_addr: SocketAddr,
+    /// The listener IP of the peer.
+    pub peer_ip: SocketAddr,
+    /// The message.
+    pub message: Message<N>,
+}
+
+/// A recorder that captures the inbound messages of the router to disk.
+///
+/// Every message is flushed as soon as it is recorded, so that the recording is complete
+/// up to (and including) the message that triggered a crash.
+pub struct Recorder {
+    /// The writer to the recording file.
+    writer: BufWriter<File>,
+    /// The time the recording started.
+    started_at: Instant,
+}
+
+impl Recorder {
+    /// Creates a new recording at the given path, overwriting any existing file.
+    pub fn create<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        // Write the header.
+        writer.write_all(&RECORDING_MAGIC)?;
+        RECORDING_VERSION.write_le(&mut writer)?;
+        writer.flush()?;
+        Ok(Self { writer, started_at: Instant::now() })
+    }
+
+    /// Records the given inbound message.
+    pub fn record<N: Network>(
+        &mut self,
+        peer_addr: SocketAddr,
+        peer_ip: SocketAddr,
+        message: &Message<N>,
+    ) -> Result<()> {
+        // Serialize the message.
+        let bytes = message.to_bytes_le()?;
+        // Write the frame.
+        (self.started_at.elapsed().as_millis() as u64).write_le(&mut self.writer)?;
+        peer_addr.write_le(&mut self.writer)?;
+        peer_ip.write_le(&mut self.writer)?;
+        (bytes.len() as u32).write_le(&mut self.writer)?;
+        self.writer.write_all(&bytes)?;
+        // Flush the frame to disk.
+        self.writer.flush()?;
+        Ok(())
+    }
+}
+
+/// A replayer that reads back the inbound messages captured by the [`Recorder`], in order.
+pub struct Replayer {
+    /// The reader of the recording file.
+    reader: BufReader<File>,
+}
+
+impl Replayer {
+    /// Opens the recording at the given path.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let mut reader = BufReader::new(File::open(path)?);
+        // Read the header.
+        let mut magic = [0u8; 8];
+        reader.read_exact(&mut magic)?;
+        if magic != RECORDING_MAGIC {
+            bail!("The file is not a router recording")
+        }
+        let version = u16::read_le(&mut reader)?;
+        if version != RECORDING_VERSION {
+            bail!("Unsupported recording version {version} (expected {RECORDING_VERSION})")
+        }
+        Ok(Self { reader })
+    }
+
+    /// Returns the next recorded message, or `None` if the end of the recording is reached.
+    pub fn next_message<N: Network>(&mut self) -> Result<Option<RecordedMessage<N>>> {
+        // Read the timestamp, stopping cleanly at the end of the recording.
+        let elapsed_ms = match u64::read_le(&mut self.reader) {
+            Ok(elapsed_ms) => elapsed_ms,
+            Err(error) if error.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+            Err(error) => return Err(error.into()),
+        };
+        // Read the remainder of the frame.
+        let peer_addr = SocketAddr::read_le(&mut self.reader)?;
+        let peer_ip = SocketAddr::read_le(&mut self.reader)?;
+        let num_bytes = u32::read_le(&mut self.reader)?;
+        let mut bytes = vec![0u8; num_bytes as usize];
+        self.reader.read_exact(&mut bytes)?;
+        // Deserialize the message.
+        let message = Message::read_le(&bytes[..])?;
+        Ok(Some(RecordedMessage { elapsed_ms, peer_addr, peer_ip, message }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::messages::{PeerRequest, Pong};
+    use snarkvm::prelude::MainnetV0;
+
+    use std::net::Ipv4Addr;
+
+    type CurrentNetwork = MainnetV0;
+
+    #[test]
+    fn test_record_and_replay() {
+        let path = std::env::temp_dir().join(format!("snarkos-router-recording-{}", std::process::id()));
+        let peer_addr = SocketAddr::new(Ipv4Addr::LOCALHOST.into(), 51234);
+        let peer_ip = SocketAddr::new(Ipv4Addr::LOCALHOST.into(), 4130);
+
+        let messages = vec![
+            Message::<CurrentNetwork>::PeerRequest(PeerRequest),
+            Message::<CurrentNetwork>::Pong(Pong { is_fork: Some(false) }),
+            Message::<CurrentNetwork>::Pong(Pong { is_fork: None }),
+        ];
+
+        // Record the messages.
+        let mut recorder = Recorder::create(&path).unwrap();
+        for message in &messages {
+            recorder.record(peer_addr, peer_ip, message).unwrap();
+        }
+        drop(recorder);
+
+        // Replay the messages, and check they are returned in order.
+        let mut replayer = Replayer::open(&path).unwrap();
+        for expected in &messages {
+            let recorded = replayer.next_message::<CurrentNetwork>().unwrap().unwrap();
+            assert_eq!(recorded.peer_addr, peer_addr);
+            assert_eq!(recorded.peer_ip, peer_ip);
+            assert_eq!(&recorded.message, expected);
+        }
+        assert!(replayer.next_message::<CurrentNetwork>().unwrap().is_none());
+
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_replay_rejects_unknown_file() {
+        let path = std::env::temp_dir().join(format!("snarkos-router-not-a-recording-{}", std::process::id()));
+        std::fs::write(&path, b"not a recording").unwrap();
+        assert!(Replayer::open(&path).is_err());
+        std::fs::remove_file(path).unwrap();
+    }
+}
@@ -0,0 +1,318 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use parking_lot::RwLock;
+use serde::Serialize;
+use std::{
+    collections::HashMap,
+    fmt,
+    net::SocketAddr,
+    time::{Duration, Instant},
+};
+
+/// The score of a peer without any offenses.
+pub const MAX_PEER_SCORE: i32 = 100;
+/// The score below which a peer is banned.
+pub const BAN_THRESHOLD: i32 = 0;
+/// The duration of a ban.
+pub const BAN_DURATION: Duration = Duration::from_secs(3600);
+/// The interval in which a peer recovers one point of its score.
+const SCORE_RECOVERY_INTERVAL: Duration = Duration::from_secs(6);
+
+/// An offense of a peer, which lowers its score.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Offense {
+    /// Sending a malformed message (e.g. one that fails to deserialize).
+    InvalidMessage,
+    /// Sending blocks that cannot be applied to the ledger (e.g. stale or forked blocks).
+    StaleBlock,
+    /// Sending more messages than allowed, or the same message repeatedly.
+    ExcessiveTraffic,
+    /// Sending a message that is not expected by the protocol.
+    ProtocolViolation,
+}
+
+impl Offense {
+    /// Returns the number of points deducted from the score of the peer for the offense.
+    pub const fn penalty(&self) -> i32 {
+        match self {
+            Self::InvalidMessage => 20,
+            Self::StaleBlock => 10,
+            Self::ExcessiveTraffic => 5,
+            Self::ProtocolViolation => 25,
+        }
+    }
+
+    /// Returns an error for the given reason, attributing it to the offense.
+    pub fn error(self, reason: impl Into<String>) -> PeerOffense {
+        PeerOffense { offense: self, reason: reason.into() }
+    }
+
+    /// Returns the offense that caused the given error, if the error was caused by the peer.
+    pub fn of(error: &anyhow::Error) -> Option<Self> {
+        error.downcast_ref::<PeerOffense>().map(|error| error.offense)
+    }
+}
+
+/// An error caused by an offense of a peer.
+#[derive(Clone, Debug)]
+pub struct PeerOffense {
+    /// The offense.
+    pub offense: Offense,
+    /// The reason for the error.
+    pub reason: String,
+}
+
+impl fmt::Display for PeerOffense {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.reason)
+    }
+}
+
+impl std::error::Error for PeerOffense {}
+
+/// The reputation of a peer, as reported by [`Reputation::scores`].
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct PeerScore {
+    /// The IP address of the peer.
+    pub ip: SocketAddr,
+    /// The current score of the peer.
+    pub score: i32,
+    /// The number of offenses of each kind by the peer.
+    pub offenses: HashMap<Offense, u32>,
+    /// The number of seconds until the ban of the peer is lifted, if the peer is banned.
+    pub banned_for_secs: Option<u64>,
+}
+
+/// The score of a peer.
+#[derive(Clone, Debug)]
+struct Score {
+    /// The score, as of `updated_at`.
+    value: i32,
+    /// The time the score was last updated.
+    updated_at: Instant,
+    /// The number of offenses of each kind.
+    offenses: HashMap<Offense, u32>,
+    /// The time the ban of the peer is lifted, if the peer is banned.
+    banned_until: Option<Instant>,
+}
+
+impl Score {
+    /// Initializes a new score at the given time.
+    fn new(now: Instant) -> Self {
+        Self { value: MAX_PEER_SCORE, updated_at: now, offenses: Default::default(), banned_until: None }
+    }
+
+    /// Recovers the points accrued since the last update, and lifts the ban if it expired.
+    fn recover(&mut self, now: Instant) {
+        let intervals = now.saturating_duration_since(self.updated_at).as_secs() / SCORE_RECOVERY_INTERVAL.as_secs();
+        self.value = (self.value as i64 + intervals as i64).min(MAX_PEER_SCORE as i64) as i32;
+        // Note: The remaining intervals are few, unless the score is fully recovered.
+        self.updated_at = match self.value == MAX_PEER_SCORE {
+            true => now,
+            false => self.updated_at + SCORE_RECOVERY_INTERVAL * intervals as u32,
+        };
+        // If the ban expired, reset the score.
+        if self.banned_until.map_or(false, |banned_until| banned_until <= now) {
+            *self = Self::new(now);
+        }
+    }
+
+    /// Returns `true` if the peer is banned at the given time.
+    fn is_banned(&self, now: Instant) -> bool {
+        self.banned_until.map_or(false, |banned_until| banned_until > now)
+    }
+}
+
+/// The reputation of the peers, which are temporarily banned when their score falls below [`BAN_THRESHOLD`].
+///
+/// Every peer starts at [`MAX_PEER_SCORE`], loses points for each offense, and recovers one point
+/// every few seconds. Once the ban of a peer is lifted, its score is reset.
+#[derive(Debug, Default)]
+pub struct Reputation {
+    /// The scores of the peers with recent offenses.
+    scores: RwLock<HashMap<SocketAddr, Score>>,
+}
+
+impl Reputation {
+    /// Records the given offense of the peer, returning `true` if the peer is banned as a result.
+    pub fn penalize(&self, peer_ip: SocketAddr, offense: Offense) -> bool {
+        self.penalize_at(peer_ip, offense, Instant::now())
+    }
+
+    /// Records the given offense of the peer at the given time, returning `true` if the peer is banned as a result.
+    fn penalize_at(&self, peer_ip: SocketAddr, offense: Offense, now: Instant) -> bool {
+        let mut scores = self.scores.write();
+        let score = scores.entry(peer_ip).or_insert_with(|| Score::new(now));
+        score.recover(now);
+        // If the peer is already banned, there is nothing left to do.
+        if score.is_banned(now) {
+            return false;
+        }
+        // Deduct the penalty, and ban the peer if its score falls below the threshold.
+        score.value -= offense.penalty();
+        *score.offenses.entry(offense).or_default() += 1;
+        if score.value < BAN_THRESHOLD {
+            score.banned_until = Some(now + BAN_DURATION);
+            return true;
+        }
+        false
+    }
+
+    /// Bans the given peer for the given duration, regardless of its score.
+    pub fn ban(&self, peer_ip: SocketAddr, duration: Duration) {
+        let now = Instant::now();
+        let mut scores = self.scores.write();
+        let score = scores.entry(peer_ip).or_insert_with(|| Score::new(now));
+        score.recover(now);
+        score.banned_until = Some(now + duration);
+    }
+
+    /// Returns `true` if the given peer is banned.
+    pub fn is_banned(&self, peer_ip: &SocketAddr) -> bool {
+        self.scores.read().get(peer_ip).map_or(false, |score| score.is_banned(Instant::now()))
+    }
+
+    /// Lifts the ban of the given peer and resets its score, returning `true` if the peer was banned.
+    pub fn unban(&self, peer_ip: &SocketAddr) -> bool {
+        self.scores.write().remove(peer_ip).map_or(false, |score| score.is_banned(Instant::now()))
+    }
+
+    /// Returns the current score of the given peer.
+    pub fn score(&self, peer_ip: &SocketAddr) -> i32 {
+        let now = Instant::now();
+        self.scores.write().get_mut(peer_ip).map_or(MAX_PEER_SCORE, |score| {
+            score.recover(now);
+            score.value
+        })
+    }
+
+    /// Returns the scores of the peers with recent offenses, from the lowest to the highest score.
+    pub fn scores(&self) -> Vec<PeerScore> {
+        let now = Instant::now();
+        let mut scores: Vec<_> = self
+            .scores
+            .write()
+            .iter_mut()
+            .map(|(ip, score)| {
+                score.recover(now);
+                let banned_for = score.banned_until.map(|banned_until| banned_until.saturating_duration_since(now));
+                PeerScore {
+                    ip: *ip,
+                    score: score.value,
+                    offenses: score.offenses.clone(),
+                    banned_for_secs: banned_for.map(|banned_for| banned_for.as_secs()),
+                }
+            })
+            .collect();
+        scores.sort_by_key(|score| score.score);
+        scores
+    }
+
+    /// Removes the peers that are not banned and have fully recovered their score.
+    pub fn prune(&self) {
+        let now = Instant::now();
+        self.scores.write().retain(|_, score| {
+            score.recover(now);
+            score.is_banned(now) || score.value < MAX_PEER_SCORE
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ban_below_threshold() {
+        let reputation = Reputation::default();
+        let peer_ip = "1.2.3.4:4130".parse().unwrap();
+        let now = Instant::now();
+
+        // Check that the peer is not banned while its score is above the threshold.
+        for _ in 0..4 {
+            assert!(!reputation.penalize_at(peer_ip, Offense::ProtocolViolation, now));
+        }
+        assert_eq!(reputation.score(&peer_ip), 0);
+        assert!(!reputation.is_banned(&peer_ip));
+
+        // Check that the peer is banned once its score falls below the threshold.
+        assert!(reputation.penalize_at(peer_ip, Offense::ExcessiveTraffic, now));
+        assert!(reputation.is_banned(&peer_ip));
+        // Check that further offenses do not extend the ban.
+        assert!(!reputation.penalize_at(peer_ip, Offense::InvalidMessage, now));
+
+        let scores = reputation.scores();
+        assert_eq!(scores.len(), 1);
+        assert_eq!(scores[0].score, -5);
+        assert_eq!(scores[0].offenses[&Offense::ProtocolViolation], 4);
+        assert_eq!(scores[0].offenses[&Offense::ExcessiveTraffic], 1);
+        assert!(scores[0].banned_for_secs.is_some());
+
+        // Check that the ban can be lifted.
+        assert!(reputation.unban(&peer_ip));
+        assert!(!reputation.is_banned(&peer_ip));
+        assert_eq!(reputation.score(&peer_ip), MAX_PEER_SCORE);
+    }
+
+    #[test]
+    fn test_manual_ban() {
+        let reputation = Reputation::default();
+        let peer_ip = "1.2.3.4:4130".parse().unwrap();
+
+        // Check that the peer is banned regardless of its score.
+        reputation.ban(peer_ip, Duration::from_secs(60));
+        assert!(reputation.is_banned(&peer_ip));
+        assert_eq!(reputation.score(&peer_ip), MAX_PEER_SCORE);
+        assert_eq!(reputation.scores()[0].banned_for_secs, Some(59));
+
+        // Check that the ban is not pruned, and can be lifted.
+        reputation.prune();
+        assert!(reputation.is_banned(&peer_ip));
+        assert!(reputation.unban(&peer_ip));
+        assert!(!reputation.is_banned(&peer_ip));
+    }
+
+    #[test]
+    fn test_score_recovery() {
+        let now = Instant::now();
+        let mut score = Score::new(now);
+        score.value = 50;
+
+        // Check that the score recovers one point per interval.
+        score.recover(now + SCORE_RECOVERY_INTERVAL * 10);
+        assert_eq!(score.value, 60);
+        // Check that the score does not exceed the maximum.
+        score.recover(now + SCORE_RECOVERY_INTERVAL * 1000);
+        assert_eq!(score.value, MAX_PEER_SCORE);
+
+        // Check that the score is reset once the ban expires.
+        score.value = -10;
+        score.banned_until = Some(now + BAN_DURATION);
+        score.updated_at = now;
+        assert!(score.is_banned(now));
+        score.recover(now + BAN_DURATION);
+        assert!(!score.is_banned(now + BAN_DURATION));
+        assert_eq!(score.value, MAX_PEER_SCORE);
+    }
+
+    #[test]
+    fn test_offense_of_error() {
+        let error = anyhow::Error::from(Offense::StaleBlock.error("Peer '1.2.3.4:4130' sent a stale block"));
+        assert_eq!(Offense::of(&error), Some(Offense::StaleBlock));
+        assert_eq!(error.to_string(), "Peer '1.2.3.4:4130' sent a stale block");
+        assert_eq!(Offense::of(&anyhow::anyhow!("Unrelated error")), None);
+    }
+}
@@ -0,0 +1,37 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use snarkos_node_sync_locators::BlockLocators;
+use snarkvm::prelude::Network;
+
+use serde::Serialize;
+use std::net::SocketAddr;
+
+/// The sync state of a connected peer, as seen by the block sync of the node.
+#[derive(Clone, Debug, Serialize)]
+#[serde(bound = "")]
+pub struct PeerSyncStatus<N: Network> {
+    /// The IP address of the peer.
+    pub ip: SocketAddr,
+    /// The latest block height advertised by the peer.
+    pub height: u32,
+    /// The block locators advertised by the peer.
+    pub locators: BlockLocators<N>,
+    /// The heights of the blocks currently requested from the peer.
+    pub requests: Vec<u32>,
+    /// The number of recent block requests to the peer that timed out.
+    pub timeouts: usize,
+    /// `true` if the peer is currently selected to sync blocks from.
+    pub is_sync_peer: bool,
+}
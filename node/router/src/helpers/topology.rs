@@ -0,0 +1,146 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::{
+    messages::{NodeType, NodeVersion},
+    ConnectionState,
+};
+
+use serde::Serialize;
+use std::{fmt::Write, net::SocketAddr};
+
+/// A snapshot of the view of the network of the node, as reported by [`Router::topology`](crate::Router::topology).
+#[derive(Clone, Debug, Serialize)]
+pub struct Topology {
+    /// The listener IP of the node.
+    pub ip: SocketAddr,
+    /// The node type of the node.
+    pub node_type: NodeType,
+    /// The software version of the node.
+    pub node_version: NodeVersion,
+    /// The known peers, ordered by state and IP.
+    pub peers: Vec<TopologyPeer>,
+}
+
+/// A peer in the [`Topology`] snapshot.
+#[derive(Clone, Debug, Serialize)]
+pub struct TopologyPeer {
+    /// The listener IP of the peer.
+    pub ip: SocketAddr,
+    /// The state of the connection.
+    pub state: ConnectionState,
+    /// The Aleo address of the peer, if it is connected.
+    pub address: Option<String>,
+    /// The node type of the peer, if it is connected.
+    pub node_type: Option<NodeType>,
+    /// The software version of the peer, if it is connected.
+    pub node_version: Option<NodeVersion>,
+    /// The user agent of the peer, if it is connected.
+    pub user_agent: Option<String>,
+    /// The capabilities of the peer, if it is connected.
+    pub capabilities: Option<Vec<&'static str>>,
+    /// Whether the connection was initiated by this node, if the peer is connected.
+    pub is_outbound: Option<bool>,
+    /// The number of seconds since the last message from the peer if it is connected,
+    /// or since the peer was last gossiped otherwise (if it was).
+    pub last_seen_secs: Option<u64>,
+    /// The round-trip time of the last ping to the peer in milliseconds, if it was measured.
+    pub latency_ms: Option<u64>,
+}
+
+impl Topology {
+    /// Returns the snapshot in the Graphviz DOT format.
+    ///
+    /// The connections are drawn from the initiating side, and the peers that are not connected
+    /// are drawn with dashed edges, labeled with their state.
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph topology {\n");
+        let _ = writeln!(
+            dot,
+            "    \"{}\" [label=\"{}\\n{} {}\", shape=doublecircle];",
+            self.ip, self.ip, self.node_type, self.node_version
+        );
+        for peer in &self.peers {
+            // Write the peer.
+            let mut label = peer.ip.to_string();
+            if let Some(node_type) = peer.node_type {
+                let _ = write!(label, "\\n{node_type}");
+            }
+            if let Some(node_version) = peer.node_version {
+                let _ = write!(label, " {node_version}");
+            }
+            let _ = writeln!(dot, "    \"{}\" [label=\"{label}\"];", peer.ip);
+            // Write the edge to the peer.
+            match (peer.state, peer.is_outbound) {
+                (ConnectionState::Connected, is_outbound) => {
+                    let (from, to) = match is_outbound {
+                        Some(false) => (peer.ip, self.ip),
+                        _ => (self.ip, peer.ip),
+                    };
+                    let label = peer.latency_ms.map(|latency| format!("{latency}ms")).unwrap_or_default();
+                    let _ = writeln!(dot, "    \"{from}\" -> \"{to}\" [label=\"{label}\"];");
+                }
+                (state, _) => {
+                    let _ = writeln!(dot, "    \"{}\" -> \"{}\" [label=\"{state}\", style=dashed];", self.ip, peer.ip);
+                }
+            }
+        }
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_peer(ip: &str, state: ConnectionState, is_outbound: Option<bool>) -> TopologyPeer {
+        TopologyPeer {
+            ip: ip.parse().unwrap(),
+            state,
+            address: None,
+            node_type: is_outbound.map(|_| NodeType::Client),
+            node_version: is_outbound.map(|_| NodeVersion::new(2, 2, 7)),
+            user_agent: None,
+            capabilities: None,
+            is_outbound,
+            last_seen_secs: None,
+            latency_ms: is_outbound.map(|_| 12),
+        }
+    }
+
+    #[test]
+    fn test_topology_to_dot() {
+        let topology = Topology {
+            ip: "127.0.0.1:4130".parse().unwrap(),
+            node_type: NodeType::Validator,
+            node_version: NodeVersion::new(2, 2, 7),
+            peers: vec![
+                sample_peer("1.1.1.1:4130", ConnectionState::Connected, Some(true)),
+                sample_peer("2.2.2.2:4130", ConnectionState::Connected, Some(false)),
+                sample_peer("3.3.3.3:4130", ConnectionState::Candidate, None),
+            ],
+        };
+        let dot = topology.to_dot();
+        assert!(dot.starts_with("digraph topology {\n"));
+        assert!(dot.ends_with("}\n"));
+        assert!(dot.contains("\"127.0.0.1:4130\" [label=\"127.0.0.1:4130\\nValidator 2.2.7\", shape=doublecircle];"));
+        assert!(dot.contains("\"1.1.1.1:4130\" [label=\"1.1.1.1:4130\\nClient 2.2.7\"];"));
+        // Ensure the connections are drawn from the initiating side.
+        assert!(dot.contains("\"127.0.0.1:4130\" -> \"1.1.1.1:4130\" [label=\"12ms\"];"));
+        assert!(dot.contains("\"2.2.2.2:4130\" -> \"127.0.0.1:4130\" [label=\"12ms\"];"));
+        // Ensure the peers that are not connected are labeled with their state.
+        assert!(dot.contains("\"127.0.0.1:4130\" -> \"3.3.3.3:4130\" [label=\"candidate\", style=dashed];"));
+    }
+}
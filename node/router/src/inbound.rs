@@ -16,16 +16,20 @@ use crate::{
     messages::{
         BlockRequest,
         BlockResponse,
+        Capabilities,
         DataBlocks,
         Message,
         PeerResponse,
         Ping,
         Pong,
+        RelayIntroduction,
         UnconfirmedSolution,
         UnconfirmedTransaction,
     },
+    Offense,
     Outbound,
     Peer,
+    Router,
 };
 use snarkos_node_tcp::protocols::Reading;
 use snarkvm::prelude::{
@@ -34,15 +38,20 @@ use snarkvm::prelude::{
     Network,
 };
 
-use anyhow::{anyhow, bail, Result};
+use anyhow::{bail, Result};
 use snarkos_node_tcp::is_bogon_ip;
-use std::{net::SocketAddr, time::Instant};
+use std::{
+    net::SocketAddr,
+    time::{Duration, Instant},
+};
 use tokio::task::spawn_blocking;
 
 #[async_trait]
 pub trait Inbound<N: Network>: Reading + Outbound<N> {
     /// The maximum number of puzzle requests per interval.
     const MAXIMUM_PUZZLE_REQUESTS_PER_INTERVAL: usize = 5;
+    /// The maximum number of relay requests and introductions per interval.
+    const MAXIMUM_RELAY_MESSAGES_PER_INTERVAL: usize = 10;
     /// The duration in seconds to sleep in between ping requests with a connected peer.
     const PING_SLEEP_IN_SECS: u64 = 20; // 20 seconds
     /// The time frame to enforce the `MESSAGE_LIMIT`.
@@ -58,11 +67,16 @@ pub trait Inbound<N: Network>: Reading + Outbound<N> {
             None => bail!("Unable to resolve the (ambiguous) peer address '{peer_addr}'"),
         };
 
+        // Record the message, if recording is enabled.
+        #[cfg(feature = "record")]
+        self.router().record_inbound(peer_addr, peer_ip, &message);
+
         // Drop the peer, if they have sent more than `MESSAGE_LIMIT` messages
         // in the last `MESSAGE_LIMIT_TIME_FRAME_IN_SECS` seconds.
         let num_messages = self.router().cache.insert_inbound_message(peer_ip, Self::MESSAGE_LIMIT_TIME_FRAME_IN_SECS);
         if num_messages > Self::MESSAGE_LIMIT {
-            bail!("Dropping '{peer_ip}' for spamming messages (num_messages = {num_messages})")
+            bail!(Offense::ExcessiveTraffic
+                .error(format!("Dropping '{peer_ip}' for spamming messages (num_messages = {num_messages})")))
         }
 
         trace!("Received '{}' from '{peer_ip}'", message.name());
@@ -75,17 +89,23 @@ pub trait Inbound<N: Network>: Reading + Outbound<N> {
 
                 // Ensure the block request is well-formed.
                 if start_height >= end_height {
-                    bail!("Block request from '{peer_ip}' has an invalid range ({start_height}..{end_height})")
+                    bail!(Offense::InvalidMessage.error(format!(
+                        "Block request from '{peer_ip}' has an invalid range ({start_height}..{end_height})"
+                    )))
                 }
                 // Ensure that the block request is within the allowed bounds.
                 if end_height - start_height > DataBlocks::<N>::MAXIMUM_NUMBER_OF_BLOCKS as u32 {
-                    bail!("Block request from '{peer_ip}' has an excessive range ({start_height}..{end_height})")
+                    bail!(Offense::InvalidMessage.error(format!(
+                        "Block request from '{peer_ip}' has an excessive range ({start_height}..{end_height})"
+                    )))
                 }
 
                 let node = self.clone();
                 match spawn_blocking(move || node.block_request(peer_ip, message)).await? {
                     true => Ok(()),
-                    false => bail!("Peer '{peer_ip}' sent an invalid block request"),
+                    false => {
+                        bail!(Offense::InvalidMessage.error(format!("Peer '{peer_ip}' sent an invalid block request")))
+                    }
                 }
             }
             Message::BlockResponse(message) => {
@@ -93,55 +113,71 @@ pub trait Inbound<N: Network>: Reading + Outbound<N> {
 
                 // Remove the block request, checking if this node previously sent a block request to this peer.
                 if !self.router().cache.remove_outbound_block_request(peer_ip, &request) {
-                    bail!("Peer '{peer_ip}' is not following the protocol (unexpected block response)")
+                    bail!(Offense::ProtocolViolation
+                        .error(format!("Peer '{peer_ip}' is not following the protocol (unexpected block response)")))
                 }
                 // Perform the deferred non-blocking deserialization of the blocks.
-                let blocks = blocks.deserialize().await.map_err(|error| anyhow!("[BlockResponse] {error}"))?;
+                let blocks = blocks
+                    .deserialize()
+                    .await
+                    .map_err(|error| Offense::InvalidMessage.error(format!("[BlockResponse] {error}")))?;
                 // Ensure the block response is well-formed.
-                blocks.ensure_response_is_well_formed(peer_ip, request.start_height, request.end_height)?;
+                blocks
+                    .ensure_response_is_well_formed(peer_ip, request.start_height, request.end_height)
+                    .map_err(|error| Offense::InvalidMessage.error(error.to_string()))?;
 
                 // Process the block response.
                 let node = self.clone();
                 match spawn_blocking(move || node.block_response(peer_ip, blocks.0)).await? {
                     true => Ok(()),
-                    false => bail!("Peer '{peer_ip}' sent an invalid block response"),
+                    false => {
+                        bail!(Offense::StaleBlock.error(format!("Peer '{peer_ip}' sent an invalid block response")))
+                    }
                 }
             }
             Message::ChallengeRequest(..) | Message::ChallengeResponse(..) => {
                 // Disconnect as the peer is not following the protocol.
-                bail!("Peer '{peer_ip}' is not following the protocol")
+                bail!(Offense::ProtocolViolation.error(format!("Peer '{peer_ip}' is not following the protocol")))
             }
             Message::Disconnect(message) => {
                 bail!("{:?}", message.reason)
             }
             Message::PeerRequest(..) => match self.peer_request(peer_ip) {
                 true => Ok(()),
-                false => bail!("Peer '{peer_ip}' sent an invalid peer request"),
+                false => bail!(Offense::InvalidMessage.error(format!("Peer '{peer_ip}' sent an invalid peer request"))),
             },
             Message::PeerResponse(message) => {
                 if !self.router().cache.contains_outbound_peer_request(peer_ip) {
-                    bail!("Peer '{peer_ip}' is not following the protocol (unexpected peer response)")
+                    bail!(Offense::ProtocolViolation
+                        .error(format!("Peer '{peer_ip}' is not following the protocol (unexpected peer response)")))
                 }
 
                 match self.peer_response(peer_ip, &message.peers) {
                     true => Ok(()),
-                    false => bail!("Peer '{peer_ip}' sent an invalid peer response"),
+                    false => {
+                        bail!(Offense::InvalidMessage.error(format!("Peer '{peer_ip}' sent an invalid peer response")))
+                    }
                 }
             }
             Message::Ping(message) => {
                 // Ensure the message protocol version is not outdated.
                 if message.version < Message::<N>::VERSION {
-                    bail!("Dropping '{peer_ip}' on message version {} (outdated)", message.version);
+                    bail!(Offense::ProtocolViolation
+                        .error(format!("Dropping '{peer_ip}' on message version {} (outdated)", message.version)));
                 }
 
                 // If the peer is a client or validator, ensure there are block locators.
                 let is_client_or_validator = message.node_type.is_client() || message.node_type.is_validator();
                 if is_client_or_validator && message.block_locators.is_none() {
-                    bail!("Peer '{peer_ip}' is a {}, but no block locators were provided", message.node_type);
+                    bail!(Offense::InvalidMessage.error(format!(
+                        "Peer '{peer_ip}' is a {}, but no block locators were provided",
+                        message.node_type
+                    )));
                 }
                 // If the peer is a prover, ensure there are no block locators.
                 else if message.node_type.is_prover() && message.block_locators.is_some() {
-                    bail!("Peer '{peer_ip}' is a prover or client, but block locators were provided");
+                    bail!(Offense::InvalidMessage
+                        .error(format!("Peer '{peer_ip}' is a prover or client, but block locators were provided")));
                 }
 
                 // Update the connected peer.
@@ -155,36 +191,47 @@ pub trait Inbound<N: Network>: Reading + Outbound<N> {
                         peer.set_last_seen(Instant::now());
                     })
                 {
-                    bail!("[Ping] {error}");
+                    bail!(Offense::ProtocolViolation.error(format!("[Ping] {error}")));
                 }
 
                 // Process the ping message.
                 match self.ping(peer_ip, message) {
                     true => Ok(()),
-                    false => bail!("Peer '{peer_ip}' sent an invalid ping"),
+                    false => bail!(Offense::InvalidMessage.error(format!("Peer '{peer_ip}' sent an invalid ping"))),
+                }
+            }
+            Message::Pong(message) => {
+                // Update the latency of the peer.
+                if let Some(peer) = self.router().connected_peers.write().get_mut(&peer_ip) {
+                    peer.set_pong_received(Instant::now());
+                }
+                // Process the pong message.
+                match self.pong(peer_ip, message) {
+                    true => Ok(()),
+                    false => bail!(Offense::InvalidMessage.error(format!("Peer '{peer_ip}' sent an invalid pong"))),
                 }
             }
-            Message::Pong(message) => match self.pong(peer_ip, message) {
-                true => Ok(()),
-                false => bail!("Peer '{peer_ip}' sent an invalid pong"),
-            },
             Message::PuzzleRequest(..) => {
                 // Insert the puzzle request for the peer, and fetch the recent frequency.
                 let frequency = self.router().cache.insert_inbound_puzzle_request(peer_ip);
                 // Check if the number of puzzle requests is within the limit.
                 if frequency > Self::MAXIMUM_PUZZLE_REQUESTS_PER_INTERVAL {
-                    bail!("Peer '{peer_ip}' is not following the protocol (excessive puzzle requests)")
+                    bail!(Offense::ExcessiveTraffic
+                        .error(format!("Peer '{peer_ip}' is not following the protocol (excessive puzzle requests)")))
                 }
                 // Process the puzzle request.
                 match self.puzzle_request(peer_ip) {
                     true => Ok(()),
-                    false => bail!("Peer '{peer_ip}' sent an invalid puzzle request"),
+                    false => {
+                        bail!(Offense::InvalidMessage.error(format!("Peer '{peer_ip}' sent an invalid puzzle request")))
+                    }
                 }
             }
             Message::PuzzleResponse(message) => {
                 // Check that this node previously sent a puzzle request to this peer.
                 if !self.router().cache.contains_outbound_puzzle_request(&peer_ip) {
-                    bail!("Peer '{peer_ip}' is not following the protocol (unexpected puzzle response)")
+                    bail!(Offense::ProtocolViolation
+                        .error(format!("Peer '{peer_ip}' is not following the protocol (unexpected puzzle response)")))
                 }
                 // Decrement the number of puzzle requests.
                 self.router().cache.decrement_outbound_puzzle_requests(peer_ip);
@@ -192,12 +239,56 @@ pub trait Inbound<N: Network>: Reading + Outbound<N> {
                 // Perform the deferred non-blocking deserialization of the block header.
                 let header = match message.block_header.deserialize().await {
                     Ok(header) => header,
-                    Err(error) => bail!("[PuzzleResponse] {error}"),
+                    Err(error) => bail!(Offense::InvalidMessage.error(format!("[PuzzleResponse] {error}"))),
                 };
                 // Process the puzzle response.
                 match self.puzzle_response(peer_ip, message.epoch_challenge, header) {
                     true => Ok(()),
-                    false => bail!("Peer '{peer_ip}' sent an invalid puzzle response"),
+                    false => {
+                        bail!(Offense::InvalidMessage
+                            .error(format!("Peer '{peer_ip}' sent an invalid puzzle response")))
+                    }
+                }
+            }
+            Message::RelayIntroduction(message) => {
+                // Insert the relay message for the peer, and fetch the recent frequency.
+                let frequency = self.router().cache.insert_inbound_relay_message(peer_ip);
+                // Check if the number of relay messages is within the limit.
+                if frequency > Self::MAXIMUM_RELAY_MESSAGES_PER_INTERVAL {
+                    bail!(Offense::ExcessiveTraffic
+                        .error(format!("Peer '{peer_ip}' is not following the protocol (excessive relay messages)")))
+                }
+                // Process the relay introduction.
+                match self.relay_introduction(peer_ip, message) {
+                    true => Ok(()),
+                    false => bail!(Offense::InvalidMessage
+                        .error(format!("Peer '{peer_ip}' sent an invalid relay introduction"))),
+                }
+            }
+            Message::RelayRequest(message) => {
+                // Ensure this node advertised that it relays the connection setup,
+                // and the peer advertised that it connects through TCP hole punching.
+                let is_hole_punching = self
+                    .router()
+                    .get_connected_peer(&peer_ip)
+                    .is_some_and(|peer| peer.capabilities().contains(Capabilities::HOLE_PUNCHING));
+                if !self.router().is_relay_enabled() || !is_hole_punching {
+                    bail!(Offense::ProtocolViolation
+                        .error(format!("Peer '{peer_ip}' is not following the protocol (unexpected relay request)")))
+                }
+                // Insert the relay message for the peer, and fetch the recent frequency.
+                let frequency = self.router().cache.insert_inbound_relay_message(peer_ip);
+                // Check if the number of relay messages is within the limit.
+                if frequency > Self::MAXIMUM_RELAY_MESSAGES_PER_INTERVAL {
+                    bail!(Offense::ExcessiveTraffic
+                        .error(format!("Peer '{peer_ip}' is not following the protocol (excessive relay messages)")))
+                }
+                // Process the relay request.
+                match self.relay_request(peer_ip, peer_addr, message.target) {
+                    true => Ok(()),
+                    false => {
+                        bail!(Offense::InvalidMessage.error(format!("Peer '{peer_ip}' sent an invalid relay request")))
+                    }
                 }
             }
             Message::UnconfirmedSolution(message) => {
@@ -207,21 +298,25 @@ pub trait Inbound<N: Network>: Reading + Outbound<N> {
                 let seen_before = self.router().cache.insert_inbound_solution(peer_ip, message.solution_id).is_some();
                 // Determine whether to propagate the solution.
                 if seen_before {
-                    bail!("Skipping 'UnconfirmedSolution' from '{peer_ip}'")
+                    bail!(Offense::ExcessiveTraffic.error(format!("Skipping 'UnconfirmedSolution' from '{peer_ip}'")))
                 }
                 // Perform the deferred non-blocking deserialization of the solution.
                 let solution = match message.solution.deserialize().await {
                     Ok(solution) => solution,
-                    Err(error) => bail!("[UnconfirmedSolution] {error}"),
+                    Err(error) => bail!(Offense::InvalidMessage.error(format!("[UnconfirmedSolution] {error}"))),
                 };
                 // Check that the solution parameters match.
                 if message.solution_id != solution.commitment() {
-                    bail!("Peer '{peer_ip}' is not following the 'UnconfirmedSolution' protocol")
+                    bail!(Offense::ProtocolViolation
+                        .error(format!("Peer '{peer_ip}' is not following the 'UnconfirmedSolution' protocol")))
                 }
                 // Handle the unconfirmed solution.
                 match self.unconfirmed_solution(peer_ip, serialized, solution).await {
                     true => Ok(()),
-                    false => bail!("Peer '{peer_ip}' sent an invalid unconfirmed solution"),
+                    false => {
+                        bail!(Offense::InvalidMessage
+                            .error(format!("Peer '{peer_ip}' sent an invalid unconfirmed solution")))
+                    }
                 }
             }
             Message::UnconfirmedTransaction(message) => {
@@ -232,26 +327,54 @@ pub trait Inbound<N: Network>: Reading + Outbound<N> {
                     self.router().cache.insert_inbound_transaction(peer_ip, message.transaction_id).is_some();
                 // Determine whether to propagate the transaction.
                 if seen_before {
-                    bail!("Skipping 'UnconfirmedTransaction' from '{peer_ip}'")
+                    bail!(Offense::ExcessiveTraffic
+                        .error(format!("Skipping 'UnconfirmedTransaction' from '{peer_ip}'")))
                 }
                 // Perform the deferred non-blocking deserialization of the transaction.
                 let transaction = match message.transaction.deserialize().await {
                     Ok(transaction) => transaction,
-                    Err(error) => bail!("[UnconfirmedTransaction] {error}"),
+                    Err(error) => bail!(Offense::InvalidMessage.error(format!("[UnconfirmedTransaction] {error}"))),
                 };
                 // Check that the transaction parameters match.
                 if message.transaction_id != transaction.id() {
-                    bail!("Peer '{peer_ip}' is not following the 'UnconfirmedTransaction' protocol")
+                    bail!(Offense::ProtocolViolation
+                        .error(format!("Peer '{peer_ip}' is not following the 'UnconfirmedTransaction' protocol")))
                 }
                 // Handle the unconfirmed transaction.
                 match self.unconfirmed_transaction(peer_ip, serialized, transaction).await {
                     true => Ok(()),
-                    false => bail!("Peer '{peer_ip}' sent an invalid unconfirmed transaction"),
+                    false => bail!(Offense::InvalidMessage
+                        .error(format!("Peer '{peer_ip}' sent an invalid unconfirmed transaction"))),
                 }
             }
         }
     }
 
+    /// Replays the inbound messages from the recording at the given path.
+    ///
+    /// The messages are processed one at a time, in the order they were recorded, regardless of
+    /// their original timing. The peers are resolved as they were during the recording, however
+    /// messages that are rejected by the router are only logged, so that the replay continues.
+    #[cfg(feature = "record")]
+    async fn replay<P: AsRef<std::path::Path> + Send>(&self, path: P) -> Result<usize> {
+        let mut replayer = crate::Replayer::open(path)?;
+        let mut num_messages = 0;
+        while let Some(recorded) = replayer.next_message::<N>()? {
+            let crate::RecordedMessage { elapsed_ms, peer_addr, peer_ip, message } = recorded;
+            // Resolve the peer as it was during the recording.
+            if self.router().resolve_to_listener(&peer_addr).is_none() {
+                self.router().resolver.insert_peer(peer_ip, peer_addr);
+            }
+            debug!("Replaying '{}' from '{peer_ip}' (recorded at {elapsed_ms}ms)", message.name());
+            // Process the message.
+            if let Err(error) = self.inbound(peer_addr, message).await {
+                warn!("Replayed message {num_messages} from '{peer_ip}' was rejected - {error}");
+            }
+            num_messages += 1;
+        }
+        Ok(num_messages)
+    }
+
     /// Handles a `BlockRequest` message.
     fn block_request(&self, peer_ip: SocketAddr, _message: BlockRequest) -> bool;
 
@@ -288,6 +411,95 @@ pub trait Inbound<N: Network>: Reading + Outbound<N> {
         true
     }
 
+    /// Handles a `RelayRequest` message, by introducing the peer and the target peer to each other.
+    fn relay_request(&self, peer_ip: SocketAddr, peer_addr: SocketAddr, target: SocketAddr) -> bool {
+        // Ensure the peer is not requesting an introduction to itself.
+        if target == peer_ip {
+            return false;
+        }
+        // Retrieve the address of the target, as observed by this node.
+        let target_addr = match self.router().resolve_to_ambiguous(&target) {
+            Some(target_addr) if self.router().is_connected(&target) => target_addr,
+            // If the target is not connected, there is nothing to relay.
+            _ => {
+                debug!("Unable to introduce '{peer_ip}' to '{target}' (not connected)");
+                return true;
+            }
+        };
+        // Ensure the target advertised that it connects through TCP hole punching,
+        // as the other peers do not expect a relay introduction.
+        let is_hole_punching = self
+            .router()
+            .get_connected_peer(&target)
+            .is_some_and(|peer| peer.capabilities().contains(Capabilities::HOLE_PUNCHING));
+        if !is_hole_punching {
+            debug!("Unable to introduce '{peer_ip}' to '{target}' (no hole punching)");
+            return true;
+        }
+        debug!("Introducing '{peer_ip}' and '{target}' to each other");
+        // Introduce the peer to the target first, so that the target opens a mapping in its NAT in time.
+        let introduction = RelayIntroduction { listener_addr: peer_ip, observed_addr: peer_addr, target };
+        self.send(target, Message::RelayIntroduction(introduction));
+        let introduction = RelayIntroduction { listener_addr: target, observed_addr: target_addr, target };
+        self.send(peer_ip, Message::RelayIntroduction(introduction));
+        true
+    }
+
+    /// Handles a `RelayIntroduction` message, by connecting to the introduced peer if this node requested the
+    /// introduction, or by opening a mapping in the NAT towards the introduced peer if this node is the target
+    /// of the introduction. Any other introduction is ignored, so that a relay can not make this node reach
+    /// arbitrary addresses.
+    fn relay_introduction(&self, relay_ip: SocketAddr, introduction: RelayIntroduction) -> bool {
+        let RelayIntroduction { listener_addr, observed_addr, target } = introduction;
+        // Ensure the relay is a connected peer that advertised that it relays the connection setup.
+        let is_relay = self
+            .router()
+            .get_connected_peer(&relay_ip)
+            .is_some_and(|peer| peer.capabilities().contains(Capabilities::RELAY));
+        if !is_relay {
+            return false;
+        }
+        // Ignore the introduction if hole punching is disabled, or if the peer is already connected.
+        if !self.router().is_hole_punching_enabled() || self.router().is_connected(&listener_addr) {
+            return true;
+        }
+        // Ensure the introduced address is the mapping of the introduced peer, as observed by the relay.
+        if listener_addr.ip() != observed_addr.ip() {
+            return false;
+        }
+        // Ensure the introduced peer is valid.
+        let is_valid = match self.router().is_dev() {
+            // In development mode, relax the validity requirements to make operating devnets more flexible.
+            true => !is_bogon_ip(observed_addr.ip()),
+            // In production mode, ensure the peer IP is valid.
+            false => self.router().is_valid_peer_ip(&observed_addr),
+        };
+        if !is_valid {
+            return false;
+        }
+        // If this node requested the introduction, connect to the peer once it has opened a mapping in its NAT.
+        if target == listener_addr && self.router().cache.remove_outbound_relay_request(&listener_addr) {
+            debug!("Connecting to '{listener_addr}' through '{observed_addr}' (introduced by '{relay_ip}')");
+            let router = self.router().clone();
+            tokio::spawn(async move {
+                tokio::time::sleep(Duration::from_millis(Router::<N>::RELAYED_CONNECT_DELAY_IN_MS)).await;
+                router.connect(observed_addr);
+            });
+            return true;
+        }
+        // If this node is the target of the introduction, open a mapping in the NAT, so that the connection attempt
+        // of the peer reaches the listener. The mapping is opened at most once per peer, until the request expires.
+        let is_target = target != listener_addr && target.port() == self.router().advertised_port();
+        if is_target && self.router().cache.insert_inbound_relay_introduction(observed_addr) {
+            debug!("Punching a hole towards '{listener_addr}' at '{observed_addr}' (introduced by '{relay_ip}')");
+            self.router().punch(observed_addr);
+            return true;
+        }
+        // Otherwise, the introduction was not requested, and is ignored.
+        debug!("Ignoring the introduction of '{listener_addr}' by '{relay_ip}' (not requested)");
+        true
+    }
+
     /// Handles a `Ping` message.
     fn ping(&self, peer_ip: SocketAddr, message: Ping<N>) -> bool;
 
@@ -38,9 +38,16 @@ pub use outbound::*;
 mod routing;
 pub use routing::*;
 
-use crate::messages::NodeType;
+use crate::messages::{Capabilities, Message, MessageCodec, NodeType, NodeVersion, NoiseCodec};
 use snarkos_account::Account;
-use snarkos_node_tcp::{is_bogon_ip, is_unspecified_or_broadcast_ip, Config, Tcp};
+use snarkos_node_tcp::{
+    canonical_addr,
+    is_bogon_ip,
+    is_unspecified_or_broadcast_ip,
+    Config,
+    MessagePriority,
+    Tcp,
+};
 use snarkvm::prelude::{Address, Network, PrivateKey, ViewKey};
 
 use anyhow::{bail, Result};
@@ -52,7 +59,7 @@ use std::{
     ops::Deref,
     str::FromStr,
     sync::Arc,
-    time::Instant,
+    time::{Duration, Instant},
 };
 use tokio::task::JoinHandle;
 
@@ -91,10 +98,35 @@ pub struct InnerRouter<N: Network> {
     candidate_peers: RwLock<HashSet<SocketAddr>>,
     /// The set of restricted peer IPs.
     restricted_peers: RwLock<HashMap<SocketAddr, Instant>>,
+    /// The reputation of the peers.
+    reputation: Reputation,
+    /// The scheduler of the outbound dials.
+    dial_scheduler: DialScheduler,
+    /// The store of the known-good peers, if enabled.
+    peer_store: Mutex<Option<PeerStore>>,
+    /// The external address of the node, if it was detected by the port mapping.
+    external_addr: RwLock<Option<SocketAddr>>,
+    /// The external address advertised to the peers instead of the listener address, if set.
+    advertised_addr: RwLock<Option<SocketAddr>>,
+    /// The detector of the external address from the addresses observed by the peers, if enabled.
+    external_addr_detector: RwLock<Option<ExternalAddrDetector>>,
+    /// The limits on the number of inbound and outbound peers.
+    peer_limits: RwLock<PeerLimits>,
+    /// The allowlist and denylist of peers.
+    access_list: RwLock<AccessList>,
+    /// The files the allowlist and denylist of peers are loaded from, if enabled.
+    access_list_files: RwLock<Option<AccessListFiles>>,
+    /// The optional features this node advertises during the handshake (including the noise encryption, by default).
+    capabilities: RwLock<Capabilities>,
+    /// The policy deciding which peers are accepted during the handshake.
+    handshake_policy: RwLock<HandshakePolicy>,
     /// The spawned handles.
     handles: Mutex<Vec<JoinHandle<()>>>,
     /// The boolean flag for the development mode.
     is_dev: bool,
+    /// The recorder of inbound messages, if recording is enabled.
+    #[cfg(feature = "record")]
+    recorder: Mutex<Option<Recorder>>,
 }
 
 impl<N: Network> Router<N> {
@@ -105,6 +137,11 @@ impl<N: Network> Router<N> {
     /// The duration in seconds after which a connected peer is considered inactive or
     /// disconnected if no message has been received in the meantime.
     const RADIO_SILENCE_IN_SECS: u64 = 150; // 2.5 minutes
+    /// The duration in seconds of a dial that opens a mapping in the NAT this node is behind.
+    const PUNCH_DURATION_IN_SECS: u64 = 1;
+    /// The delay in milliseconds before connecting to a peer introduced by a relay, so that the
+    /// peer has opened a mapping in its NAT.
+    pub const RELAYED_CONNECT_DELAY_IN_MS: u64 = 1_500;
 }
 
 impl<N: Network> Router<N> {
@@ -114,11 +151,13 @@ impl<N: Network> Router<N> {
         node_type: NodeType,
         account: Account<N>,
         trusted_peers: &[SocketAddr],
-        max_peers: u16,
+        peer_limits: PeerLimits,
         is_dev: bool,
     ) -> Result<Self> {
+        // Ensure the peer limits are valid.
+        peer_limits.validate(u16::MAX as usize)?;
         // Initialize the TCP stack.
-        let tcp = Tcp::new(Config::new(node_ip, max_peers));
+        let tcp = Tcp::new(Config::new(node_ip, peer_limits.max_connections() as u16));
         // Initialize the router.
         Ok(Self(Arc::new(InnerRouter {
             tcp,
@@ -126,13 +165,26 @@ impl<N: Network> Router<N> {
             account,
             cache: Default::default(),
             resolver: Default::default(),
-            trusted_peers: trusted_peers.iter().copied().collect(),
+            trusted_peers: trusted_peers.iter().copied().map(canonical_addr).collect(),
             connected_peers: Default::default(),
             connecting_peers: Default::default(),
             candidate_peers: Default::default(),
             restricted_peers: Default::default(),
+            reputation: Default::default(),
+            dial_scheduler: Default::default(),
+            peer_store: Default::default(),
+            external_addr: Default::default(),
+            advertised_addr: Default::default(),
+            external_addr_detector: Default::default(),
+            peer_limits: RwLock::new(peer_limits),
+            access_list: Default::default(),
+            access_list_files: Default::default(),
+            capabilities: RwLock::new(Capabilities::NOISE),
+            handshake_policy: Default::default(),
             handles: Default::default(),
             is_dev,
+            #[cfg(feature = "record")]
+            recorder: Default::default(),
         })))
     }
 }
@@ -140,6 +192,7 @@ impl<N: Network> Router<N> {
 impl<N: Network> Router<N> {
     /// Attempts to connect to the given peer IP.
     pub fn connect(&self, peer_ip: SocketAddr) -> Option<JoinHandle<bool>> {
+        let peer_ip = canonical_addr(peer_ip);
         // Return early if the attempt is against the protocol rules.
         if let Err(forbidden_message) = self.check_connection_attempt(peer_ip) {
             warn!("{forbidden_message}");
@@ -155,10 +208,11 @@ impl<N: Network> Router<N> {
                     router.remove_candidate_peer(peer_ip);
                     true
                 }
-                // If the connection was not allowed, log the error.
+                // If the connection was not allowed, log the error and back off from the peer.
                 Err(error) => {
                     router.connecting_peers.lock().remove(&peer_ip);
-                    warn!("Unable to connect to '{peer_ip}' - {error}");
+                    let backoff = router.dial_scheduler.record_failure(peer_ip);
+                    warn!("Unable to connect to '{peer_ip}' - {error} (retrying in {}s)", backoff.as_secs());
                     false
                 }
             }
@@ -175,6 +229,11 @@ impl<N: Network> Router<N> {
         if self.number_of_connected_peers() >= self.max_connected_peers() {
             bail!("Dropping connection attempt to '{peer_ip}' (maximum peers reached)")
         }
+        // Ensure the node does not surpass the maximum number of outbound peers.
+        let is_trusted = self.trusted_peers.contains(&peer_ip);
+        if !is_trusted && self.number_of_outbound_peers() >= self.peer_limits().max_outbound {
+            bail!("Dropping connection attempt to '{peer_ip}' (maximum outbound peers reached)")
+        }
         // Ensure the node is not already connected to this peer.
         if self.is_connected(&peer_ip) {
             bail!("Dropping connection attempt to '{peer_ip}' (already connected)")
@@ -183,6 +242,10 @@ impl<N: Network> Router<N> {
         if self.is_restricted(&peer_ip) {
             bail!("Dropping connection attempt to '{peer_ip}' (restricted)")
         }
+        // Ensure the peer is allowed by the access lists.
+        if !self.is_allowed(&peer_ip) {
+            bail!("Dropping connection attempt to '{peer_ip}' (not allowed)")
+        }
         // Ensure the node is not already connecting to this peer.
         if !self.connecting_peers.lock().insert(peer_ip) {
             bail!("Dropping connection attempt to '{peer_ip}' (already shaking hands as the initiator)")
@@ -210,6 +273,21 @@ impl<N: Network> Router<N> {
         })
     }
 
+    /// Opens a mapping towards the given peer address in the NAT this node is behind, so that the
+    /// connection attempt of the peer reaches the listener (i.e. TCP hole punching).
+    pub fn punch(&self, peer_addr: SocketAddr) -> JoinHandle<bool> {
+        let router = self.clone();
+        tokio::spawn(async move {
+            match router.tcp.punch(peer_addr, Duration::from_secs(Self::PUNCH_DURATION_IN_SECS)).await {
+                Ok(()) => true,
+                Err(error) => {
+                    debug!("Unable to punch a hole towards '{peer_addr}' - {error}");
+                    false
+                }
+            }
+        })
+    }
+
     /// Returns the IP address of this node.
     pub fn local_ip(&self) -> SocketAddr {
         self.tcp.listening_addr().expect("The TCP listener is not enabled")
@@ -218,7 +296,10 @@ impl<N: Network> Router<N> {
     /// Returns `true` if the given IP is this node.
     pub fn is_local_ip(&self, ip: &SocketAddr) -> bool {
         *ip == self.local_ip()
-            || (ip.ip().is_unspecified() || ip.ip().is_loopback()) && ip.port() == self.local_ip().port()
+            || Some(*ip) == self.advertised_addr()
+            // Behind a proxy, loopback IPs may be virtual addresses mapped by the proxy (e.g. Tor .onion addresses).
+            || (ip.ip().is_unspecified() || (ip.ip().is_loopback() && self.tcp.config().proxy.is_none()))
+                && ip.port() == self.local_ip().port()
     }
 
     /// Returns `true` if the given IP is not this node, is not a bogon address, and is not unspecified.
@@ -226,6 +307,21 @@ impl<N: Network> Router<N> {
         !self.is_local_ip(ip) && !is_bogon_ip(ip.ip()) && !is_unspecified_or_broadcast_ip(ip.ip())
     }
 
+    /// Returns the external address of the node, if it was detected by the port mapping.
+    pub fn external_addr(&self) -> Option<SocketAddr> {
+        *self.external_addr.read()
+    }
+
+    /// Returns the external address advertised to the peers instead of the listener address, if set.
+    pub fn advertised_addr(&self) -> Option<SocketAddr> {
+        *self.advertised_addr.read()
+    }
+
+    /// Returns the listener port advertised to the peers.
+    pub fn advertised_port(&self) -> u16 {
+        self.advertised_addr().unwrap_or_else(|| self.local_ip()).port()
+    }
+
     /// Returns the node type.
     pub fn node_type(&self) -> NodeType {
         self.node_type
@@ -251,6 +347,63 @@ impl<N: Network> Router<N> {
         self.is_dev
     }
 
+    /// Returns the optional features this node advertises during the handshake.
+    pub fn capabilities(&self) -> Capabilities {
+        *self.capabilities.read()
+    }
+
+    /// Returns `true` if this node offers to compress messages during the handshake.
+    pub fn is_compression_enabled(&self) -> bool {
+        self.capabilities().contains(Capabilities::COMPRESSION)
+    }
+
+    /// Returns `true` if this node relays the connection setup between the peers that are behind a NAT.
+    pub fn is_relay_enabled(&self) -> bool {
+        self.capabilities().contains(Capabilities::RELAY)
+    }
+
+    /// Returns `true` if this node connects to the unreachable peers through TCP hole punching.
+    pub fn is_hole_punching_enabled(&self) -> bool {
+        self.tcp.config().hole_punching
+    }
+
+    /// Returns the policy deciding which peers are accepted during the handshake.
+    pub fn handshake_policy(&self) -> HandshakePolicy {
+        self.handshake_policy.read().clone()
+    }
+
+    /// Returns the codec for the given (ambiguous) peer address, which encrypts the messages with the
+    /// transport state of the connection if encryption was negotiated with the peer, and compresses them
+    /// if compression was negotiated with the peer.
+    pub fn message_codec(&self, peer_addr: SocketAddr) -> NoiseCodec<N> {
+        let peer = self.resolve_to_listener(&peer_addr).and_then(|peer_ip| self.get_connected_peer(&peer_ip));
+        match peer {
+            Some(peer) => {
+                let message_codec = match peer.is_compressed() {
+                    true => MessageCodec::compressed(),
+                    false => MessageCodec::default(),
+                };
+                NoiseCodec::with_transport(peer.noise_state().cloned(), message_codec)
+            }
+            // The peer disconnected in the meantime, so the connection is rejected.
+            None => NoiseCodec::failed(MessageCodec::default()),
+        }
+    }
+
+    /// Returns the priority of the given outbound message, so that the latest block headers and the control
+    /// messages preempt the transaction gossip and the sync responses whenever a connection is backed up.
+    pub fn message_priority(&self, message: &Message<N>) -> MessagePriority {
+        match message {
+            Message::Disconnect(..)
+            | Message::Ping(..)
+            | Message::Pong(..)
+            | Message::PuzzleResponse(..)
+            | Message::RelayIntroduction(..) => MessagePriority::High,
+            Message::BlockResponse(..) | Message::UnconfirmedTransaction(..) => MessagePriority::Low,
+            _ => MessagePriority::Normal,
+        }
+    }
+
     /// Returns the listener IP address from the (ambiguous) peer address.
     pub fn resolve_to_listener(&self, peer_addr: &SocketAddr) -> Option<SocketAddr> {
         self.resolver.get_listener(peer_addr)
@@ -286,13 +439,20 @@ impl<N: Network> Router<N> {
         self.connecting_peers.lock().contains(ip)
     }
 
-    /// Returns `true` if the given IP is restricted.
+    /// Returns `true` if the given IP is restricted or banned.
     pub fn is_restricted(&self, ip: &SocketAddr) -> bool {
         self.restricted_peers
             .read()
             .get(ip)
             .map(|time| time.elapsed().as_secs() < Self::RADIO_SILENCE_IN_SECS)
             .unwrap_or(false)
+            || self.reputation.is_banned(ip)
+    }
+
+    /// Returns `true` if the given peer IP is allowed by the allowlist and denylist.
+    /// Trusted peers are always allowed.
+    pub fn is_allowed(&self, ip: &SocketAddr) -> bool {
+        self.trusted_peers.contains(ip) || self.access_list.read().is_allowed(ip.ip())
     }
 
     /// Returns the maximum number of connected peers.
@@ -300,11 +460,35 @@ impl<N: Network> Router<N> {
         self.tcp.config().max_connections as usize
     }
 
+    /// Returns the limits on the number of inbound and outbound peers.
+    pub fn peer_limits(&self) -> PeerLimits {
+        *self.peer_limits.read()
+    }
+
+    /// Updates the limits on the number of inbound and outbound peers.
+    /// The new limits must fit within the maximum number of connected peers.
+    pub fn set_peer_limits(&self, peer_limits: PeerLimits) -> Result<()> {
+        peer_limits.validate(self.max_connected_peers())?;
+        *self.peer_limits.write() = peer_limits;
+        info!("Updated the peer limits to {peer_limits:?}");
+        Ok(())
+    }
+
     /// Returns the number of connected peers.
     pub fn number_of_connected_peers(&self) -> usize {
         self.connected_peers.read().len()
     }
 
+    /// Returns the number of connected peers that initiated the connection.
+    pub fn number_of_inbound_peers(&self) -> usize {
+        self.connected_peers.read().values().filter(|peer| !peer.is_outbound()).count()
+    }
+
+    /// Returns the number of connected peers that this node initiated the connection with.
+    pub fn number_of_outbound_peers(&self) -> usize {
+        self.connected_peers.read().values().filter(|peer| peer.is_outbound()).count()
+    }
+
     /// Returns the number of connected validators.
     pub fn number_of_connected_validators(&self) -> usize {
         self.connected_peers.read().values().filter(|peer| peer.is_validator()).count()
@@ -360,6 +544,16 @@ impl<N: Network> Router<N> {
         self.connected_peers.read().iter().filter(|(_, peer)| peer.is_client()).map(|(ip, _)| *ip).collect()
     }
 
+    /// Returns the list of connected peers that relay the connection setup between the peers behind a NAT.
+    pub fn connected_relays(&self) -> Vec<SocketAddr> {
+        self.connected_peers
+            .read()
+            .iter()
+            .filter(|(_, peer)| peer.capabilities().contains(Capabilities::RELAY))
+            .map(|(ip, _)| *ip)
+            .collect()
+    }
+
     /// Returns the list of candidate peers.
     pub fn candidate_peers(&self) -> HashSet<SocketAddr> {
         self.candidate_peers.read().clone()
@@ -370,6 +564,79 @@ impl<N: Network> Router<N> {
         self.restricted_peers.read().keys().copied().collect()
     }
 
+    /// Returns the connection states of the known peers, ordered by state and IP.
+    pub fn connection_states(&self) -> Vec<PeerConnectionState> {
+        let mut states: HashMap<SocketAddr, PeerConnectionState> = HashMap::new();
+        let mut insert = |ip: SocketAddr, state: ConnectionState, peer: Option<&Peer<N>>| {
+            // Note: A peer is reported in its most advanced state, which is inserted first.
+            states.entry(ip).or_insert_with(|| PeerConnectionState {
+                ip,
+                state,
+                node_type: peer.map(|peer| peer.node_type()),
+                is_outbound: peer.map(|peer| peer.is_outbound()),
+                dial_failures: self.dial_scheduler.failures(&ip),
+            });
+        };
+        for (ip, peer) in self.connected_peers.read().iter() {
+            insert(*ip, ConnectionState::Connected, Some(peer));
+        }
+        for ip in self.connecting_peers.lock().iter() {
+            insert(*ip, ConnectionState::Connecting, None);
+        }
+        for score in self.reputation.scores().into_iter().filter(|score| score.banned_for_secs.is_some()) {
+            insert(score.ip, ConnectionState::Banned, None);
+        }
+        for ip in self.restricted_peers().into_iter().filter(|ip| self.is_restricted(ip)) {
+            insert(ip, ConnectionState::Restricted, None);
+        }
+        for ip in self.candidate_peers.read().iter() {
+            insert(*ip, ConnectionState::Candidate, None);
+        }
+        let mut states: Vec<_> = states.into_values().collect();
+        states.sort_by_key(|state| (state.state as u8, state.ip));
+        states
+    }
+
+    /// Returns a snapshot of the view of the network of the node, including the known peers.
+    pub fn topology(&self) -> Topology {
+        let now = Instant::now();
+        let states = self.connection_states();
+        let connected_peers = self.connected_peers.read();
+        let peers = states
+            .into_iter()
+            .map(|state| {
+                let peer = connected_peers.get(&state.ip);
+                let last_seen = match peer {
+                    Some(peer) => Some(peer.last_seen()),
+                    None => self.dial_scheduler.last_seen(&state.ip),
+                };
+                TopologyPeer {
+                    ip: state.ip,
+                    state: state.state,
+                    address: peer.map(|peer| peer.address().to_string()),
+                    node_type: state.node_type,
+                    node_version: peer.map(|peer| peer.node_version()),
+                    user_agent: peer.map(|peer| peer.user_agent().to_string()),
+                    capabilities: peer.map(|peer| peer.capabilities().names()),
+                    is_outbound: state.is_outbound,
+                    last_seen_secs: last_seen.map(|last_seen| now.saturating_duration_since(last_seen).as_secs()),
+                    latency_ms: peer.and_then(|peer| peer.latency()).map(|latency| latency.as_millis() as u64),
+                }
+            })
+            .collect();
+        Topology { ip: self.local_ip(), node_type: self.node_type, node_version: NodeVersion::current(), peers }
+    }
+
+    /// Returns the reputation of the peers.
+    pub fn reputation(&self) -> &Reputation {
+        &self.reputation
+    }
+
+    /// Returns the scheduler of the outbound dials.
+    pub fn dial_scheduler(&self) -> &DialScheduler {
+        &self.dial_scheduler
+    }
+
     /// Returns the list of trusted peers.
     pub fn trusted_peers(&self) -> &HashSet<SocketAddr> {
         &self.trusted_peers
@@ -412,6 +679,8 @@ impl<N: Network> Router<N> {
         self.candidate_peers.write().remove(&peer_ip);
         // Remove this peer from the restricted peers, if it exists.
         self.restricted_peers.write().remove(&peer_ip);
+        // Clear the dial backoff of this peer, if it exists.
+        self.dial_scheduler.record_success(&peer_ip);
         #[cfg(feature = "metrics")]
         self.update_metrics();
     }
@@ -426,12 +695,17 @@ impl<N: Network> Router<N> {
         // Ensure the combined number of peers does not surpass the threshold.
         let eligible_peers = peers
             .iter()
+            .copied()
+            .map(canonical_addr)
             .filter(|peer_ip| {
                 // Ensure the peer is not itself, is not already connected, and is not restricted.
                 !self.is_local_ip(peer_ip) && !self.is_connected(peer_ip) && !self.is_restricted(peer_ip)
             })
-            .take(max_candidate_peers);
+            .take(max_candidate_peers)
+            .collect::<Vec<_>>();
 
+        // Record the eligible candidate peer IPs as freshly gossiped, so that they are dialed first.
+        self.dial_scheduler.record_seen(&eligible_peers);
         // Proceed to insert the eligible candidate peer IPs.
         self.candidate_peers.write().extend(eligible_peers);
         #[cfg(feature = "metrics")]
@@ -448,6 +722,37 @@ impl<N: Network> Router<N> {
         self.update_metrics();
     }
 
+    /// Records the given offense of the peer, and disconnects from the peer if it is banned as a result.
+    /// Note: The offenses of trusted peers are ignored.
+    pub fn penalize_peer(&self, peer_ip: SocketAddr, offense: Offense) {
+        if self.trusted_peers.contains(&peer_ip) {
+            return;
+        }
+        if self.reputation.penalize(peer_ip, offense) {
+            warn!("Banning '{peer_ip}' for {}s (score below {BAN_THRESHOLD})", BAN_DURATION.as_secs());
+            self.evict_banned_peer(peer_ip);
+        }
+    }
+
+    /// Bans the given peer for the given duration, and disconnects from the peer.
+    pub fn ban_peer(&self, peer_ip: SocketAddr, duration: Duration) {
+        let peer_ip = canonical_addr(peer_ip);
+        info!("Banning '{peer_ip}' for {}s", duration.as_secs());
+        self.reputation.ban(peer_ip, duration);
+        self.evict_banned_peer(peer_ip);
+    }
+
+    /// Removes the given banned peer from the candidate peers and the peer store, and disconnects from it.
+    fn evict_banned_peer(&self, peer_ip: SocketAddr) {
+        // Remove this peer from the candidate peers and the peer store, if it exists.
+        self.candidate_peers.write().remove(&peer_ip);
+        if let Some(peer_store) = self.peer_store.lock().as_mut() {
+            peer_store.remove(&peer_ip);
+        }
+        // Disconnect from this peer.
+        self.disconnect(peer_ip);
+    }
+
     /// Updates the connected peer with the given function.
     pub fn update_connected_peer<Fn: FnMut(&mut Peer<N>)>(
         &self,
@@ -493,6 +798,202 @@ impl<N: Network> Router<N> {
         self.update_metrics();
     }
 
+    /// Queries the given DNS seeds for peers at startup and every [`DNS_SEED_INTERVAL`] thereafter,
+    /// adding the resolved peers to the candidate peers.
+    pub fn enable_dns_seeds(&self, seeds: Vec<String>) {
+        if seeds.is_empty() {
+            return;
+        }
+        let router = self.clone();
+        self.spawn(async move {
+            loop {
+                let peers = resolve_dns_seeds(&seeds).await;
+                // Filter out invalid addresses, unless in development mode.
+                let peers: Vec<_> =
+                    peers.into_iter().filter(|ip| router.is_dev() || router.is_valid_peer_ip(ip)).collect();
+                router.insert_candidate_peers(&peers);
+                tokio::time::sleep(DNS_SEED_INTERVAL).await;
+            }
+        });
+    }
+
+    /// Asks the local router to forward the listener port of this node over UPnP or NAT-PMP,
+    /// and renews the mapping before its lease expires.
+    pub fn enable_port_mapping(&self) {
+        let router = self.clone();
+        self.spawn(async move {
+            let port = router.local_ip().port();
+            loop {
+                match map_port(port).await {
+                    Ok(external_addr) => {
+                        if router.external_addr.write().replace(external_addr) != Some(external_addr) {
+                            info!("Mapped the listener port {port} - the node is reachable at '{external_addr}'");
+                        }
+                    }
+                    Err(error) => warn!("Failed to map the listener port {port} - {error}"),
+                }
+                tokio::time::sleep(PORT_MAPPING_LEASE / 2).await;
+            }
+        });
+    }
+
+    /// Advertises the given external address (e.g. of a public interface, or of a port forwarded by a NAT)
+    /// to the peers that connect from now on, instead of the listener address.
+    /// Note: Only the port is advertised in the handshake, as the peers derive the IP from the connection.
+    pub fn set_advertised_addr(&self, addr: SocketAddr) {
+        *self.advertised_addr.write() = Some(addr);
+    }
+
+    /// Derives the external IP of the node from the source addresses observed by the peers it connects to,
+    /// and advertises it along with the listener port once enough peers agree on it.
+    /// Note: This overrides the advertised address, so it should not be combined with `set_advertised_addr`.
+    pub fn enable_external_addr_detection(&self) {
+        *self.external_addr_detector.write() = Some(Default::default());
+    }
+
+    /// Records the address of this node observed by the given peer, and advertises the detected external address
+    /// if it changed. The observations that are not public addresses are ignored.
+    fn record_observed_addr(&self, peer_ip: SocketAddr, observed_addr: SocketAddr) {
+        let observed_ip = observed_addr.ip();
+        if is_bogon_ip(observed_ip) || is_unspecified_or_broadcast_ip(observed_ip) {
+            return;
+        }
+        let detected_ip = match &*self.external_addr_detector.read() {
+            Some(detector) => detector.record(peer_ip.ip(), observed_ip),
+            None => return,
+        };
+        if let Some(detected_ip) = detected_ip {
+            let external_addr = SocketAddr::new(detected_ip, self.advertised_port());
+            if self.advertised_addr.write().replace(external_addr) != Some(external_addr) {
+                info!("Detected the external address '{external_addr}' from the addresses observed by the peers");
+            }
+        }
+    }
+
+    /// Sets the capacity and TTL of the caches of the recently seen solutions and transactions.
+    pub fn set_cache_config(&self, config: CacheConfig) {
+        self.cache.set_config(config);
+    }
+
+    /// Offers to compress messages with zstd to the peers that connect from now on.
+    /// Compression is used with the peers that offer it as well.
+    pub fn enable_compression(&self) {
+        self.enable_capabilities(Capabilities::COMPRESSION);
+    }
+
+    /// Advertises the given optional features to the peers that connect from now on.
+    pub fn enable_capabilities(&self, capabilities: Capabilities) {
+        self.capabilities.write().insert(capabilities);
+    }
+
+    /// Sets the policy deciding which peers are accepted during the handshake, from now on.
+    pub fn set_handshake_policy(&self, policy: HandshakePolicy) {
+        *self.handshake_policy.write() = policy;
+    }
+
+    /// Loads the allowlist and denylist of peers from the given files, and reloads them whenever
+    /// the node receives a SIGHUP.
+    pub fn enable_access_lists(&self, files: AccessListFiles) -> Result<()> {
+        if files.allowlist.is_none() && files.denylist.is_none() {
+            return Ok(());
+        }
+        *self.access_list_files.write() = Some(files);
+        self.reload_access_lists()?;
+
+        // Reload the access lists on SIGHUP.
+        #[cfg(unix)]
+        {
+            use tokio::signal::unix::{signal, SignalKind};
+
+            let mut hangup = signal(SignalKind::hangup())?;
+            let router = self.clone();
+            self.spawn(async move {
+                while hangup.recv().await.is_some() {
+                    if let Err(error) = router.reload_access_lists() {
+                        warn!("Failed to reload the peer access lists - {error}");
+                    }
+                }
+            });
+        }
+        Ok(())
+    }
+
+    /// Re-reads the allowlist and denylist files, and disconnects from the peers that are no longer allowed.
+    /// Returns the number of allowed and denied subnets.
+    pub fn reload_access_lists(&self) -> Result<(usize, usize)> {
+        let Some(files) = self.access_list_files.read().clone() else {
+            bail!("The peer access lists are not enabled")
+        };
+        // Load the access lists.
+        let access_list = AccessList::load(&files)?;
+        let (num_allowed, num_denied) = access_list.num_subnets();
+        *self.access_list.write() = access_list;
+        info!("Loaded the peer access lists ({num_allowed} allowed and {num_denied} denied subnets)");
+
+        // Disconnect from the peers that are no longer allowed.
+        for peer_ip in self.connected_peers() {
+            if !self.is_allowed(&peer_ip) {
+                info!("Disconnecting from '{peer_ip}' (not allowed)");
+                self.disconnect(peer_ip);
+            }
+        }
+        Ok((num_allowed, num_denied))
+    }
+
+    /// Enables the peer store at the given path, adding the stored peers to the candidate peers.
+    pub fn enable_peer_store<P: AsRef<std::path::Path>>(&self, path: P) {
+        let peer_store = match PeerStore::open(&path) {
+            Ok(peer_store) => peer_store,
+            Err(error) => {
+                warn!("Failed to load the peer store at '{}' - {error}", path.as_ref().display());
+                return;
+            }
+        };
+        let peers: Vec<_> = peer_store.peers().into_iter().map(|peer| peer.ip).collect();
+        debug!("Loaded {} peer(s) from the peer store", peers.len());
+        self.insert_candidate_peers(&peers);
+        *self.peer_store.lock() = Some(peer_store);
+    }
+
+    /// Writes the connected peers to the peer store, if it is enabled.
+    pub fn save_peer_store(&self) {
+        let mut peer_store = self.peer_store.lock();
+        if let Some(peer_store) = peer_store.as_mut() {
+            for peer in self.connected_peers.read().values() {
+                peer_store.insert(StoredPeer::new(peer));
+            }
+            if let Err(error) = peer_store.save() {
+                warn!("Failed to save the peer store - {error}");
+            }
+        }
+    }
+
+    /// Starts recording the inbound messages to the given path, replacing any ongoing recording.
+    #[cfg(feature = "record")]
+    pub fn start_recording<P: AsRef<std::path::Path>>(&self, path: P) -> Result<()> {
+        *self.recorder.lock() = Some(Recorder::create(path)?);
+        Ok(())
+    }
+
+    /// Stops recording the inbound messages.
+    #[cfg(feature = "record")]
+    pub fn stop_recording(&self) {
+        *self.recorder.lock() = None;
+    }
+
+    /// Records the given inbound message, if recording is enabled.
+    #[cfg(feature = "record")]
+    fn record_inbound(&self, peer_addr: SocketAddr, peer_ip: SocketAddr, message: &messages::Message<N>) {
+        let mut recorder = self.recorder.lock();
+        if let Some(recorder_ref) = recorder.as_mut() {
+            // Stop recording on failure, as the recording would no longer be faithful.
+            if let Err(error) = recorder_ref.record(peer_addr, peer_ip, message) {
+                error!("Stopped recording the inbound messages - {error}");
+                *recorder = None;
+            }
+        }
+    }
+
     /// Spawns a task with the given future; it should only be used for long-running tasks.
     pub fn spawn<T: Future<Output = ()> + Send + 'static>(&self, future: T) {
         self.handles.lock().push(tokio::spawn(future));
@@ -501,6 +1002,8 @@ impl<N: Network> Router<N> {
     /// Shuts down the router.
     pub async fn shut_down(&self) {
         info!("Shutting down the router...");
+        // Save the connected peers.
+        self.save_peer_store();
         // Abort the tasks.
         self.handles.lock().iter().for_each(|handle| handle.abort());
         // Close the listener.
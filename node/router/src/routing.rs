@@ -12,13 +12,14 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::{Heartbeat, Inbound, Outbound};
+use crate::{Heartbeat, Inbound, Outbound, PeerSyncStatus};
 use snarkos_node_tcp::{
     protocols::{Disconnect, Handshake, OnConnect},
     P2P,
 };
 use snarkvm::prelude::Network;
 
+use anyhow::{bail, Result};
 use core::time::Duration;
 
 #[async_trait]
@@ -39,6 +40,21 @@ pub trait Routing<N: Network>:
         self.initialize_heartbeat();
     }
 
+    /// Returns the greatest block height advertised by the connected peers, if the node syncs blocks from them.
+    fn greatest_peer_block_height(&self) -> Option<u32> {
+        None
+    }
+
+    /// Returns the sync state of each connected peer, if the node syncs blocks from them.
+    fn peer_sync_statuses(&self) -> Option<Vec<PeerSyncStatus<N>>> {
+        None
+    }
+
+    /// Pauses or resumes the block sync of the node.
+    fn set_block_sync_paused(&self, _paused: bool) -> Result<()> {
+        bail!("The block sync cannot be paused for this node type")
+    }
+
     // Start listening for inbound connections.
     async fn enable_listener(&self) {
         self.tcp().enable_listener().await.expect("Failed to enable the TCP listener");
@@ -23,7 +23,7 @@ use std::{
 };
 
 use snarkos_account::Account;
-use snarkos_node_router::{messages::NodeType, Router};
+use snarkos_node_router::{messages::NodeType, PeerLimits, Router};
 use snarkvm::prelude::{block::Block, FromBytes, MainnetV0 as CurrentNetwork, Network};
 
 /// A helper macro to print the TCP listening address, along with the connected and connecting peers.
@@ -68,6 +68,12 @@ pub fn initialize_logger(level: u8) {
     let _ = tracing_subscriber::fmt().with_env_filter(filter).with_target(level == 3).try_init();
 }
 
+/// Returns peer limits allowing up to `max_peers` inbound and `max_peers` outbound connections.
+pub fn sample_peer_limits(max_peers: u16) -> PeerLimits {
+    let max_peers = max_peers as usize;
+    PeerLimits { max_inbound: max_peers, max_outbound: max_peers, target_outbound: max_peers }
+}
+
 /// Initializes a client router. Setting the `listening_port = 0` will result in a random port being assigned.
 #[allow(dead_code)]
 pub async fn client(listening_port: u16, max_peers: u16) -> TestRouter<CurrentNetwork> {
@@ -76,7 +82,7 @@ pub async fn client(listening_port: u16, max_peers: u16) -> TestRouter<CurrentNe
         NodeType::Client,
         sample_account(),
         &[],
-        max_peers,
+        sample_peer_limits(max_peers),
         true,
     )
     .await
@@ -92,7 +98,7 @@ pub async fn prover(listening_port: u16, max_peers: u16) -> TestRouter<CurrentNe
         NodeType::Prover,
         sample_account(),
         &[],
-        max_peers,
+        sample_peer_limits(max_peers),
         true,
     )
     .await
@@ -108,7 +114,7 @@ pub async fn validator(listening_port: u16, max_peers: u16) -> TestRouter<Curren
         NodeType::Validator,
         sample_account(),
         &[],
-        max_peers,
+        sample_peer_limits(max_peers),
         true,
     )
     .await
@@ -18,7 +18,7 @@ use snarkos_node_router::{
         BlockRequest,
         DisconnectReason,
         Message,
-        MessageCodec,
+        NoiseCodec,
         Ping,
         Pong,
         UnconfirmedSolution,
@@ -105,25 +105,25 @@ impl<N: Network> Disconnect for TestRouter<N> {
 
 #[async_trait]
 impl<N: Network> Writing for TestRouter<N> {
-    type Codec = MessageCodec<N>;
+    type Codec = NoiseCodec<N>;
     type Message = Message<N>;
 
     /// Creates an [`Encoder`] used to write the outbound messages to the target stream.
     /// The `side` parameter indicates the connection side **from the node's perspective**.
-    fn codec(&self, _addr: SocketAddr, _side: ConnectionSide) -> Self::Codec {
-        Default::default()
+    fn codec(&self, peer_addr: SocketAddr, _side: ConnectionSide) -> Self::Codec {
+        self.router().message_codec(peer_addr)
     }
 }
 
 #[async_trait]
 impl<N: Network> Reading for TestRouter<N> {
-    type Codec = MessageCodec<N>;
+    type Codec = NoiseCodec<N>;
     type Message = Message<N>;
 
     /// Creates a [`Decoder`] used to interpret messages from the network.
     /// The `side` param indicates the connection side **from the node's perspective**.
-    fn codec(&self, _peer_addr: SocketAddr, _side: ConnectionSide) -> Self::Codec {
-        Default::default()
+    fn codec(&self, peer_addr: SocketAddr, _side: ConnectionSide) -> Self::Codec {
+        self.router().message_codec(peer_addr)
     }
 
     /// Processes a message received from the network.
@@ -18,6 +18,7 @@ use common::*;
 use snarkos_node_tcp::{protocols::Handshake, P2P};
 
 use core::time::Duration;
+use std::net::SocketAddr;
 
 #[tokio::test]
 async fn test_connect_without_handshake() {
@@ -150,6 +151,35 @@ async fn test_connect_with_handshake() {
     }
 }
 
+#[tokio::test]
+async fn test_connect_with_advertised_addr() {
+    // Create 2 routers.
+    let node0 = validator(0, 2).await;
+    let node1 = client(0, 2).await;
+
+    // Enable handshake protocol.
+    node0.enable_handshake().await;
+    node1.enable_handshake().await;
+
+    // Start listening.
+    node0.tcp().enable_listener().await.unwrap();
+    node1.tcp().enable_listener().await.unwrap();
+
+    // Advertise an external address for node0.
+    let advertised_addr = SocketAddr::from(([127, 0, 0, 1], node0.local_ip().port().wrapping_add(1)));
+    node0.set_advertised_addr(advertised_addr);
+    assert!(node0.is_local_ip(&advertised_addr));
+
+    // Connect node0 to node1.
+    node0.connect(node1.local_ip());
+    // Sleep briefly.
+    tokio::time::sleep(Duration::from_millis(200)).await;
+
+    // Check that node1 knows node0 by the advertised port.
+    assert_eq!(node1.number_of_connected_peers(), 1);
+    assert!(node1.is_connected(&advertised_addr));
+}
+
 #[ignore]
 #[tokio::test]
 async fn test_connect_simultaneously_with_handshake() {
@@ -15,6 +15,7 @@
 mod common;
 use common::*;
 
+use snarkos_node_router::ConnectionState;
 use snarkos_node_tcp::{protocols::Handshake, P2P};
 
 use core::time::Duration;
@@ -108,3 +109,47 @@ async fn test_disconnect_with_handshake() {
     assert_eq!(node1.tcp().num_connected(), 1); // Router 1 has no way of knowing that Router 0 disconnected.
     assert_eq!(node1.tcp().num_connecting(), 0);
 }
+
+#[tokio::test]
+async fn test_ban_with_handshake() {
+    // Create 2 routers.
+    let node0 = validator(0, 1).await;
+    let node1 = client(0, 1).await;
+
+    // Enable handshake protocol.
+    node0.enable_handshake().await;
+    node1.enable_handshake().await;
+
+    // Start listening.
+    node0.tcp().enable_listener().await.unwrap();
+    node1.tcp().enable_listener().await.unwrap();
+
+    // Connect node0 to node1.
+    node0.connect(node1.local_ip());
+    // Sleep briefly.
+    tokio::time::sleep(Duration::from_millis(200)).await;
+
+    // Check the connection state of node1.
+    let states = node0.connection_states();
+    assert_eq!(states.len(), 1);
+    assert_eq!(states[0].ip, node1.local_ip());
+    assert_eq!(states[0].state, ConnectionState::Connected);
+    assert_eq!(states[0].is_outbound, Some(true));
+
+    // Ban node1 from node0.
+    node0.ban_peer(node1.local_ip(), Duration::from_secs(60));
+    // Sleep briefly.
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    // Check that node1 is disconnected and banned.
+    assert_eq!(node0.number_of_connected_peers(), 0);
+    let states = node0.connection_states();
+    assert_eq!(states.len(), 1);
+    assert_eq!(states[0].state, ConnectionState::Banned);
+    // Check that node0 refuses to connect to node1.
+    assert!(node0.connect(node1.local_ip()).is_none());
+
+    // Lift the ban, and check that node0 may connect to node1 again.
+    assert!(node0.reputation().unban(&node1.local_ip()));
+    assert!(node0.connect(node1.local_ip()).is_some());
+}
@@ -0,0 +1,74 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![cfg(feature = "record")]
+
+mod common;
+use common::*;
+
+use snarkos_node_router::{
+    messages::{Message, PeerRequest},
+    Inbound,
+    Outbound,
+    Replayer,
+};
+use snarkos_node_tcp::{
+    protocols::{Handshake, Reading, Writing},
+    P2P,
+};
+use snarkvm::prelude::MainnetV0 as CurrentNetwork;
+
+use core::time::Duration;
+
+#[tokio::test]
+async fn test_record_and_replay_inbound() {
+    let path = std::env::temp_dir().join(format!("snarkos-router-record-inbound-{}", std::process::id()));
+
+    // Create 2 routers.
+    let node0 = validator(0, 2).await;
+    let node1 = client(0, 2).await;
+    for node in [&node0, &node1] {
+        node.enable_handshake().await;
+        node.enable_reading().await;
+        node.enable_writing().await;
+        node.tcp().enable_listener().await.unwrap();
+    }
+
+    // Record the inbound messages of node1.
+    node1.start_recording(&path).unwrap();
+
+    // Connect node0 to node1, and send a message.
+    node0.connect(node1.local_ip());
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    assert_eq!(node1.number_of_connected_peers(), 1);
+    node0.send(node1.local_ip(), Message::PeerRequest(PeerRequest));
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    node1.stop_recording();
+
+    // Check that the message was recorded, along with the peer it was received from.
+    let mut replayer = Replayer::open(&path).unwrap();
+    let mut num_recorded = 0;
+    let mut is_recorded = false;
+    while let Some(recorded) = replayer.next_message::<CurrentNetwork>().unwrap() {
+        assert_eq!(recorded.peer_ip, node0.local_ip());
+        is_recorded |= recorded.message == Message::PeerRequest(PeerRequest);
+        num_recorded += 1;
+    }
+    assert!(is_recorded);
+
+    // Replay the recording into node1, and check that every message is processed.
+    assert_eq!(node1.replay(&path).await.unwrap(), num_recorded);
+
+    std::fs::remove_file(path).unwrap();
+}
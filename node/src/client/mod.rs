@@ -17,14 +17,16 @@ mod router;
 use crate::traits::NodeInterface;
 use snarkos_account::Account;
 use snarkos_node_bft::ledger_service::CoreLedgerService;
-use snarkos_node_rest::Rest;
+use snarkos_node_cdn::CdnConfig;
+use snarkos_node_rest::{Rest, RestConfig};
 use snarkos_node_router::{
     messages::{Message, NodeType, UnconfirmedSolution},
-    Heartbeat,
     Inbound,
     Outbound,
+    PeerLimits,
     Router,
     Routing,
+    PEER_STORE_FILE,
 };
 use snarkos_node_sync::{BlockSync, BlockSyncMode};
 use snarkos_node_tcp::{
@@ -78,10 +80,12 @@ impl<N: Network, C: ConsensusStorage<N>> Client<N, C> {
         node_ip: SocketAddr,
         rest_ip: Option<SocketAddr>,
         rest_rps: u32,
+        rest_config: RestConfig,
         account: Account<N>,
         trusted_peers: &[SocketAddr],
+        peer_limits: PeerLimits,
         genesis: Block<N>,
-        cdn: Option<String>,
+        cdn: Option<CdnConfig>,
         storage_mode: StorageMode,
     ) -> Result<Self> {
         // Prepare the shutdown flag.
@@ -94,11 +98,11 @@ impl<N: Network, C: ConsensusStorage<N>> Client<N, C> {
         let ledger = Ledger::<N, C>::load(genesis.clone(), storage_mode.clone())?;
 
         // Initialize the CDN.
-        if let Some(base_url) = cdn {
-            // Sync the ledger with the CDN.
-            if let Err((_, error)) =
-                snarkos_node_cdn::sync_ledger_with_cdn(&base_url, ledger.clone(), shutdown.clone()).await
-            {
+        if let Some(cdn) = cdn {
+            // Sync the ledger with the CDN, falling back to P2P sync if the CDN is unreliable.
+            let result =
+                snarkos_node_cdn::sync_ledger_with_fallback(&cdn, ledger.clone(), shutdown.clone(), None).await;
+            if let Err((_, error)) = result {
                 crate::log_clean_error(&storage_mode);
                 return Err(error);
             }
@@ -115,10 +119,15 @@ impl<N: Network, C: ConsensusStorage<N>> Client<N, C> {
             NodeType::Client,
             account,
             trusted_peers,
-            Self::MAXIMUM_NUMBER_OF_PEERS as u16,
+            peer_limits,
             matches!(storage_mode, StorageMode::Development(_)),
         )
         .await?;
+        // Load the known-good peers from the previous run.
+        router.enable_peer_store(aleo_std::aleo_ledger_dir(N::ID, storage_mode.clone()).join(PEER_STORE_FILE));
+        // Persist the REST indexes next to the ledger.
+        let rest_config =
+            RestConfig { storage_dir: Some(aleo_std::aleo_ledger_dir(N::ID, storage_mode.clone())), ..rest_config };
         // Load the coinbase puzzle.
         let coinbase_puzzle = CoinbasePuzzle::<N>::load()?;
         // Initialize the node.
@@ -135,7 +144,8 @@ impl<N: Network, C: ConsensusStorage<N>> Client<N, C> {
 
         // Initialize the REST server.
         if let Some(rest_ip) = rest_ip {
-            node.rest = Some(Rest::start(rest_ip, rest_rps, None, ledger.clone(), Arc::new(node.clone())).await?);
+            node.rest =
+                Some(Rest::start(rest_ip, rest_rps, rest_config, None, ledger.clone(), Arc::new(node.clone())).await?);
         }
         // Initialize the routing.
         node.initialize_routing().await;
@@ -197,6 +207,11 @@ impl<N: Network, C: ConsensusStorage<N>> NodeInterface<N> for Client<N, C> {
         trace!("Shutting down the node...");
         self.shutdown.store(true, std::sync::atomic::Ordering::Relaxed);
 
+        // Shut down the REST server, draining the in-flight requests.
+        if let Some(rest) = &self.rest {
+            rest.shut_down().await;
+        }
+
         // Abort the tasks.
         trace!("Shutting down the validator...");
         self.handles.lock().iter().for_each(|handle| handle.abort());
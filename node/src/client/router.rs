@@ -19,15 +19,17 @@ use snarkos_node_router::{
         BlockResponse,
         DataBlocks,
         DisconnectReason,
-        MessageCodec,
+        NoiseCodec,
         Ping,
         Pong,
         PuzzleResponse,
         UnconfirmedTransaction,
     },
+    Offense,
+    PeerSyncStatus,
     Routing,
 };
-use snarkos_node_tcp::{Connection, ConnectionSide, Tcp};
+use snarkos_node_tcp::{Connection, ConnectionSide, MessagePriority, Tcp};
 use snarkvm::{
     ledger::narwhal::Data,
     prelude::{block::Transaction, Network},
@@ -92,25 +94,30 @@ impl<N: Network, C: ConsensusStorage<N>> Disconnect for Client<N, C> {
 
 #[async_trait]
 impl<N: Network, C: ConsensusStorage<N>> Writing for Client<N, C> {
-    type Codec = MessageCodec<N>;
+    type Codec = NoiseCodec<N>;
     type Message = Message<N>;
 
     /// Creates an [`Encoder`] used to write the outbound messages to the target stream.
     /// The `side` parameter indicates the connection side **from the node's perspective**.
-    fn codec(&self, _addr: SocketAddr, _side: ConnectionSide) -> Self::Codec {
-        Default::default()
+    fn codec(&self, peer_addr: SocketAddr, _side: ConnectionSide) -> Self::Codec {
+        self.router().message_codec(peer_addr)
+    }
+
+    /// Returns the priority of the given outbound message.
+    fn message_priority(&self, message: &Self::Message) -> MessagePriority {
+        self.router().message_priority(message)
     }
 }
 
 #[async_trait]
 impl<N: Network, C: ConsensusStorage<N>> Reading for Client<N, C> {
-    type Codec = MessageCodec<N>;
+    type Codec = NoiseCodec<N>;
     type Message = Message<N>;
 
     /// Creates a [`Decoder`] used to interpret messages from the network.
     /// The `side` param indicates the connection side **from the node's perspective**.
-    fn codec(&self, _peer_addr: SocketAddr, _side: ConnectionSide) -> Self::Codec {
-        Default::default()
+    fn codec(&self, peer_addr: SocketAddr, _side: ConnectionSide) -> Self::Codec {
+        self.router().message_codec(peer_addr)
     }
 
     /// Processes a message received from the network.
@@ -119,6 +126,10 @@ impl<N: Network, C: ConsensusStorage<N>> Reading for Client<N, C> {
         if let Err(error) = self.inbound(peer_addr, message).await {
             if let Some(peer_ip) = self.router().resolve_to_listener(&peer_addr) {
                 warn!("Disconnecting from '{peer_ip}' - {error}");
+                // Penalize the peer, if the error was caused by an offense.
+                if let Some(offense) = Offense::of(&error) {
+                    self.router().penalize_peer(peer_ip, offense);
+                }
                 Outbound::send(self, peer_ip, Message::Disconnect(DisconnectReason::ProtocolViolation.into()));
                 // Disconnect from this peer.
                 self.router().disconnect(peer_ip);
@@ -154,7 +165,39 @@ impl<N: Network, C: ConsensusStorage<N>> CommunicationService for Client<N, C> {
 }
 
 #[async_trait]
-impl<N: Network, C: ConsensusStorage<N>> Routing<N> for Client<N, C> {}
+impl<N: Network, C: ConsensusStorage<N>> Routing<N> for Client<N, C> {
+    /// Returns the greatest block height advertised by the connected peers.
+    fn greatest_peer_block_height(&self) -> Option<u32> {
+        self.sync.greatest_peer_block_height()
+    }
+
+    /// Returns the sync state of each connected peer.
+    fn peer_sync_statuses(&self) -> Option<Vec<PeerSyncStatus<N>>> {
+        let mut requests = self.sync.get_peer_block_requests();
+        let timeouts = self.sync.get_peer_request_timeouts();
+        let sync_peers = self.sync.find_sync_peers().map(|(sync_peers, _)| sync_peers).unwrap_or_default();
+        let statuses = self
+            .sync
+            .get_peer_locators()
+            .into_iter()
+            .map(|(ip, locators)| PeerSyncStatus {
+                ip,
+                height: locators.latest_locator_height(),
+                locators,
+                requests: requests.swap_remove(&ip).unwrap_or_default(),
+                timeouts: timeouts.get(&ip).copied().unwrap_or_default(),
+                is_sync_peer: sync_peers.contains_key(&ip),
+            })
+            .collect();
+        Some(statuses)
+    }
+
+    /// Pauses or resumes the block sync of the client.
+    fn set_block_sync_paused(&self, paused: bool) -> Result<()> {
+        self.sync.set_paused(paused);
+        Ok(())
+    }
+}
 
 impl<N: Network, C: ConsensusStorage<N>> Heartbeat<N> for Client<N, C> {}
 
@@ -217,6 +260,10 @@ impl<N: Network, C: ConsensusStorage<N>> Inbound<N> for Client<N, C> {
 
     /// Sleeps for a period and then sends a `Ping` message to the peer.
     fn pong(&self, peer_ip: SocketAddr, _message: Pong) -> bool {
+        // Update the latency of the peer in the sync module, to prefer low-latency peers for block requests.
+        if let Some(latency) = self.router().get_connected_peer(&peer_ip).and_then(|peer| peer.latency()) {
+            self.sync.update_peer_latency(peer_ip, latency);
+        }
         // Spawn an asynchronous task for the `Ping` request.
         let self_ = self.clone();
         tokio::spawn(async move {
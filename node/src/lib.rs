@@ -47,6 +47,21 @@ pub use traits::*;
 
 use aleo_std::StorageMode;
 
+/// Returns the build features the node was compiled with.
+pub fn build_features() -> Vec<&'static str> {
+    [
+        ("graphql", cfg!(feature = "graphql")),
+        ("json-rpc", cfg!(feature = "json-rpc")),
+        ("metrics", cfg!(feature = "metrics")),
+        ("parallel", cfg!(feature = "parallel")),
+        ("record", cfg!(feature = "record")),
+        ("timer", cfg!(feature = "timer")),
+    ]
+    .into_iter()
+    .filter_map(|(feature, enabled)| enabled.then_some(feature))
+    .collect()
+}
+
 /// A helper to log instructions to recover.
 pub fn log_clean_error(storage_mode: &StorageMode) {
     match storage_mode {
@@ -14,7 +14,16 @@
 
 use crate::{traits::NodeInterface, Client, Prover, Validator};
 use snarkos_account::Account;
-use snarkos_node_router::messages::NodeType;
+use snarkos_node_bft::helpers::BftConfig;
+use snarkos_node_cdn::CdnConfig;
+use snarkos_node_rest::RestConfig;
+use snarkos_node_router::{
+    messages::{Capabilities, NodeType},
+    AccessListFiles,
+    CacheConfig,
+    HandshakePolicy,
+    PeerLimits,
+};
 use snarkvm::prelude::{
     block::Block,
     store::helpers::{memory::ConsensusMemory, rocksdb::ConsensusDB},
@@ -25,7 +34,9 @@ use snarkvm::prelude::{
 };
 
 use aleo_std::StorageMode;
-use anyhow::Result;
+use anyhow::{bail, Result};
+#[cfg(feature = "record")]
+use std::path::Path;
 use std::{net::SocketAddr, sync::Arc};
 
 pub enum Node<N: Network> {
@@ -44,12 +55,15 @@ impl<N: Network> Node<N> {
         bft_ip: Option<SocketAddr>,
         rest_ip: Option<SocketAddr>,
         rest_rps: u32,
+        rest_config: RestConfig,
         account: Account<N>,
         trusted_peers: &[SocketAddr],
+        peer_limits: PeerLimits,
         trusted_validators: &[SocketAddr],
         genesis: Block<N>,
-        cdn: Option<String>,
+        cdn: Option<CdnConfig>,
         storage_mode: StorageMode,
+        bft_config: BftConfig<N>,
     ) -> Result<Self> {
         Ok(Self::Validator(Arc::new(
             Validator::new(
@@ -57,12 +71,15 @@ impl<N: Network> Node<N> {
                 bft_ip,
                 rest_ip,
                 rest_rps,
+                rest_config,
                 account,
                 trusted_peers,
+                peer_limits,
                 trusted_validators,
                 genesis,
                 cdn,
                 storage_mode,
+                bft_config,
             )
             .await?,
         )))
@@ -73,10 +90,13 @@ impl<N: Network> Node<N> {
         node_ip: SocketAddr,
         account: Account<N>,
         trusted_peers: &[SocketAddr],
+        peer_limits: PeerLimits,
         genesis: Block<N>,
         storage_mode: StorageMode,
     ) -> Result<Self> {
-        Ok(Self::Prover(Arc::new(Prover::new(node_ip, account, trusted_peers, genesis, storage_mode).await?)))
+        Ok(Self::Prover(Arc::new(
+            Prover::new(node_ip, account, trusted_peers, peer_limits, genesis, storage_mode).await?,
+        )))
     }
 
     /// Initializes a new client node.
@@ -84,14 +104,28 @@ impl<N: Network> Node<N> {
         node_ip: SocketAddr,
         rest_ip: Option<SocketAddr>,
         rest_rps: u32,
+        rest_config: RestConfig,
         account: Account<N>,
         trusted_peers: &[SocketAddr],
+        peer_limits: PeerLimits,
         genesis: Block<N>,
-        cdn: Option<String>,
+        cdn: Option<CdnConfig>,
         storage_mode: StorageMode,
     ) -> Result<Self> {
         Ok(Self::Client(Arc::new(
-            Client::new(node_ip, rest_ip, rest_rps, account, trusted_peers, genesis, cdn, storage_mode).await?,
+            Client::new(
+                node_ip,
+                rest_ip,
+                rest_rps,
+                rest_config,
+                account,
+                trusted_peers,
+                peer_limits,
+                genesis,
+                cdn,
+                storage_mode,
+            )
+            .await?,
         )))
     }
 
@@ -139,4 +173,116 @@ impl<N: Network> Node<N> {
             Self::Client(node) => node.is_dev(),
         }
     }
+
+    /// Advertises the given external address to the peers, instead of the listener address of the node.
+    pub fn set_advertised_addr(&self, addr: SocketAddr) {
+        match self {
+            Self::Validator(node) => node.set_advertised_addr(addr),
+            Self::Prover(node) => node.set_advertised_addr(addr),
+            Self::Client(node) => node.set_advertised_addr(addr),
+        }
+    }
+
+    /// Detects the external address of the node from the addresses observed by the peers, and advertises it.
+    pub fn enable_external_addr_detection(&self) {
+        match self {
+            Self::Validator(node) => node.enable_external_addr_detection(),
+            Self::Prover(node) => node.enable_external_addr_detection(),
+            Self::Client(node) => node.enable_external_addr_detection(),
+        }
+    }
+
+    /// Advertises the given external address to the validators, instead of the listener address of the BFT.
+    pub fn set_bft_advertised_addr(&self, addr: SocketAddr) -> Result<()> {
+        match self {
+            Self::Validator(node) => {
+                node.set_bft_advertised_addr(addr);
+                Ok(())
+            }
+            Self::Prover(_) | Self::Client(_) => bail!("Only validators run the BFT"),
+        }
+    }
+
+    /// Sets the capacity and TTL of the caches of the recently seen solutions and transactions.
+    pub fn set_cache_config(&self, config: CacheConfig) {
+        match self {
+            Self::Validator(node) => node.set_cache_config(config),
+            Self::Prover(node) => node.set_cache_config(config),
+            Self::Client(node) => node.set_cache_config(config),
+        }
+    }
+
+    /// Offers to compress the P2P messages exchanged with the peers that connect from now on.
+    pub fn enable_compression(&self) {
+        match self {
+            Self::Validator(node) => node.enable_compression(),
+            Self::Prover(node) => node.enable_compression(),
+            Self::Client(node) => node.enable_compression(),
+        }
+    }
+
+    /// Advertises the given optional features to the peers that connect from now on.
+    pub fn enable_capabilities(&self, capabilities: Capabilities) {
+        match self {
+            Self::Validator(node) => node.enable_capabilities(capabilities),
+            Self::Prover(node) => node.enable_capabilities(capabilities),
+            Self::Client(node) => node.enable_capabilities(capabilities),
+        }
+    }
+
+    /// Sets the policy deciding which peers are accepted during the handshake.
+    pub fn set_handshake_policy(&self, policy: HandshakePolicy) {
+        match self {
+            Self::Validator(node) => node.set_handshake_policy(policy),
+            Self::Prover(node) => node.set_handshake_policy(policy),
+            Self::Client(node) => node.set_handshake_policy(policy),
+        }
+    }
+
+    /// Queries the given DNS seeds for peers, at startup and periodically thereafter.
+    pub fn enable_dns_seeds(&self, seeds: Vec<String>) {
+        match self {
+            Self::Validator(node) => node.enable_dns_seeds(seeds),
+            Self::Prover(node) => node.enable_dns_seeds(seeds),
+            Self::Client(node) => node.enable_dns_seeds(seeds),
+        }
+    }
+
+    /// Asks the local router to forward the listener port of the node, over UPnP or NAT-PMP.
+    pub fn enable_port_mapping(&self) {
+        match self {
+            Self::Validator(node) => node.enable_port_mapping(),
+            Self::Prover(node) => node.enable_port_mapping(),
+            Self::Client(node) => node.enable_port_mapping(),
+        }
+    }
+
+    /// Loads the allowlist and denylist of peers from the given files, reloading them on SIGHUP.
+    pub fn enable_access_lists(&self, files: AccessListFiles) -> Result<()> {
+        match self {
+            Self::Validator(node) => node.enable_access_lists(files),
+            Self::Prover(node) => node.enable_access_lists(files),
+            Self::Client(node) => node.enable_access_lists(files),
+        }
+    }
+
+    /// Starts recording the inbound P2P messages to the given path.
+    #[cfg(feature = "record")]
+    pub fn start_recording(&self, path: &Path) -> Result<()> {
+        match self {
+            Self::Validator(node) => node.start_recording(path),
+            Self::Prover(node) => node.start_recording(path),
+            Self::Client(node) => node.start_recording(path),
+        }
+    }
+
+    /// Replays the inbound P2P messages recorded at the given path, returning the number of replayed messages.
+    #[cfg(feature = "record")]
+    pub async fn replay_inbound(&self, path: &Path) -> Result<usize> {
+        match self {
+            Self::Validator(node) => node.replay_inbound(path).await,
+            Self::Prover(node) => node.replay_inbound(path).await,
+            Self::Client(node) => node.replay_inbound(path).await,
+        }
+    }
 }
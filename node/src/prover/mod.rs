@@ -19,11 +19,12 @@ use snarkos_account::Account;
 use snarkos_node_bft::ledger_service::ProverLedgerService;
 use snarkos_node_router::{
     messages::{Message, NodeType, UnconfirmedSolution},
-    Heartbeat,
     Inbound,
     Outbound,
+    PeerLimits,
     Router,
     Routing,
+    PEER_STORE_FILE,
 };
 use snarkos_node_sync::{BlockSync, BlockSyncMode};
 use snarkos_node_tcp::{
@@ -88,6 +89,7 @@ impl<N: Network, C: ConsensusStorage<N>> Prover<N, C> {
         node_ip: SocketAddr,
         account: Account<N>,
         trusted_peers: &[SocketAddr],
+        peer_limits: PeerLimits,
         genesis: Block<N>,
         storage_mode: StorageMode,
     ) -> Result<Self> {
@@ -108,10 +110,12 @@ impl<N: Network, C: ConsensusStorage<N>> Prover<N, C> {
             NodeType::Prover,
             account,
             trusted_peers,
-            Self::MAXIMUM_NUMBER_OF_PEERS as u16,
+            peer_limits,
             matches!(storage_mode, StorageMode::Development(_)),
         )
         .await?;
+        // Load the known-good peers from the previous run.
+        router.enable_peer_store(aleo_std::aleo_ledger_dir(N::ID, storage_mode.clone()).join(PEER_STORE_FILE));
         // Load the coinbase puzzle.
         let coinbase_puzzle = CoinbasePuzzle::<N>::load()?;
         // Compute the maximum number of puzzle instances.
@@ -14,17 +14,20 @@
 
 use super::*;
 
-use snarkos_node_router::messages::{
-    BlockRequest,
-    DisconnectReason,
-    Message,
-    MessageCodec,
-    Ping,
-    Pong,
-    PuzzleRequest,
-    UnconfirmedTransaction,
+use snarkos_node_router::{
+    messages::{
+        BlockRequest,
+        DisconnectReason,
+        Message,
+        NoiseCodec,
+        Ping,
+        Pong,
+        PuzzleRequest,
+        UnconfirmedTransaction,
+    },
+    Offense,
 };
-use snarkos_node_tcp::{Connection, ConnectionSide, Tcp};
+use snarkos_node_tcp::{Connection, ConnectionSide, MessagePriority, Tcp};
 use snarkvm::prelude::{block::Transaction, Network};
 
 use std::{io, net::SocketAddr};
@@ -77,25 +80,30 @@ impl<N: Network, C: ConsensusStorage<N>> Disconnect for Prover<N, C> {
 
 #[async_trait]
 impl<N: Network, C: ConsensusStorage<N>> Writing for Prover<N, C> {
-    type Codec = MessageCodec<N>;
+    type Codec = NoiseCodec<N>;
     type Message = Message<N>;
 
     /// Creates an [`Encoder`] used to write the outbound messages to the target stream.
     /// The `side` parameter indicates the connection side **from the node's perspective**.
-    fn codec(&self, _addr: SocketAddr, _side: ConnectionSide) -> Self::Codec {
-        Default::default()
+    fn codec(&self, peer_addr: SocketAddr, _side: ConnectionSide) -> Self::Codec {
+        self.router().message_codec(peer_addr)
+    }
+
+    /// Returns the priority of the given outbound message.
+    fn message_priority(&self, message: &Self::Message) -> MessagePriority {
+        self.router().message_priority(message)
     }
 }
 
 #[async_trait]
 impl<N: Network, C: ConsensusStorage<N>> Reading for Prover<N, C> {
-    type Codec = MessageCodec<N>;
+    type Codec = NoiseCodec<N>;
     type Message = Message<N>;
 
     /// Creates a [`Decoder`] used to interpret messages from the network.
     /// The `side` param indicates the connection side **from the node's perspective**.
-    fn codec(&self, _peer_addr: SocketAddr, _side: ConnectionSide) -> Self::Codec {
-        Default::default()
+    fn codec(&self, peer_addr: SocketAddr, _side: ConnectionSide) -> Self::Codec {
+        self.router().message_codec(peer_addr)
     }
 
     /// Processes a message received from the network.
@@ -104,6 +112,10 @@ impl<N: Network, C: ConsensusStorage<N>> Reading for Prover<N, C> {
         if let Err(error) = self.inbound(peer_addr, message).await {
             if let Some(peer_ip) = self.router().resolve_to_listener(&peer_addr) {
                 warn!("Disconnecting from '{peer_addr}' - {error}");
+                // Penalize the peer, if the error was caused by an offense.
+                if let Some(offense) = Offense::of(&error) {
+                    self.router().penalize_peer(peer_ip, offense);
+                }
                 Outbound::send(self, peer_ip, Message::Disconnect(DisconnectReason::ProtocolViolation.into()));
                 // Disconnect from this peer.
                 self.router().disconnect(peer_ip);
@@ -114,7 +126,13 @@ impl<N: Network, C: ConsensusStorage<N>> Reading for Prover<N, C> {
 }
 
 #[async_trait]
-impl<N: Network, C: ConsensusStorage<N>> Routing<N> for Prover<N, C> {}
+impl<N: Network, C: ConsensusStorage<N>> Routing<N> for Prover<N, C> {
+    /// Pauses or resumes the block sync of the prover, which only tracks the block locators of its peers.
+    fn set_block_sync_paused(&self, paused: bool) -> Result<()> {
+        self.sync.set_paused(paused);
+        Ok(())
+    }
+}
 
 impl<N: Network, C: ConsensusStorage<N>> Heartbeat<N> for Prover<N, C> {
     /// This function updates the coinbase puzzle if network has updated.
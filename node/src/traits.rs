@@ -12,11 +12,21 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use snarkos_node_router::{messages::NodeType, Routing};
+use snarkos_node_router::{
+    messages::{Capabilities, NodeType},
+    AccessListFiles,
+    CacheConfig,
+    HandshakePolicy,
+    Routing,
+};
 use snarkvm::prelude::{Address, Network, PrivateKey, ViewKey};
 
+use anyhow::Result;
 use once_cell::sync::OnceCell;
+#[cfg(feature = "record")]
+use std::path::Path;
 use std::{
+    net::SocketAddr,
     sync::{
         atomic::{AtomicBool, Ordering},
         Arc,
@@ -51,9 +61,66 @@ pub trait NodeInterface<N: Network>: Routing<N> {
         self.router().is_dev()
     }
 
+    /// Advertises the given external address to the peers, instead of the listener address of the node.
+    fn set_advertised_addr(&self, addr: SocketAddr) {
+        self.router().set_advertised_addr(addr)
+    }
+
+    /// Detects the external address of the node from the addresses observed by the peers, and advertises it.
+    fn enable_external_addr_detection(&self) {
+        self.router().enable_external_addr_detection()
+    }
+
+    /// Sets the capacity and TTL of the caches of the recently seen solutions and transactions.
+    fn set_cache_config(&self, config: CacheConfig) {
+        self.router().set_cache_config(config)
+    }
+
+    /// Offers to compress the P2P messages exchanged with the peers that connect from now on.
+    fn enable_compression(&self) {
+        self.router().enable_compression()
+    }
+
+    /// Advertises the given optional features to the peers that connect from now on.
+    fn enable_capabilities(&self, capabilities: Capabilities) {
+        self.router().enable_capabilities(capabilities)
+    }
+
+    /// Sets the policy deciding which peers are accepted during the handshake.
+    fn set_handshake_policy(&self, policy: HandshakePolicy) {
+        self.router().set_handshake_policy(policy)
+    }
+
+    /// Queries the given DNS seeds for peers, at startup and periodically thereafter.
+    fn enable_dns_seeds(&self, seeds: Vec<String>) {
+        self.router().enable_dns_seeds(seeds)
+    }
+
+    /// Asks the local router to forward the listener port of the node, over UPnP or NAT-PMP.
+    fn enable_port_mapping(&self) {
+        self.router().enable_port_mapping()
+    }
+
+    /// Loads the allowlist and denylist of peers from the given files, reloading them on SIGHUP.
+    fn enable_access_lists(&self, files: AccessListFiles) -> Result<()> {
+        self.router().enable_access_lists(files)
+    }
+
+    /// Starts recording the inbound P2P messages to the given path.
+    #[cfg(feature = "record")]
+    fn start_recording(&self, path: &Path) -> Result<()> {
+        self.router().start_recording(path)
+    }
+
+    /// Replays the inbound P2P messages recorded at the given path, returning the number of replayed messages.
+    #[cfg(feature = "record")]
+    async fn replay_inbound(&self, path: &Path) -> Result<usize> {
+        self.replay(path).await
+    }
+
     /// Handles OS signals for the node to intercept and perform a clean shutdown.
     /// The optional `shutdown_flag` flag can be used to cleanly terminate the syncing process.
-    /// Note: Only Ctrl-C is supported; it should work on both Unix-family systems and Windows.
+    /// Note: Ctrl-C is supported on both Unix-family systems and Windows, and SIGTERM on Unix-family systems.
     fn handle_signals(shutdown_flag: Arc<AtomicBool>) -> Arc<OnceCell<Self>> {
         // In order for the signal handler to be started as early as possible, a reference to the node needs
         // to be passed to it at a later time.
@@ -61,7 +128,7 @@ pub trait NodeInterface<N: Network>: Routing<N> {
 
         let node_clone = node.clone();
         tokio::task::spawn(async move {
-            match tokio::signal::ctrl_c().await {
+            match wait_for_shutdown_signal().await {
                 Ok(()) => {
                     match node_clone.get() {
                         // If the node is already initialized, then shut it down.
@@ -76,7 +143,7 @@ pub trait NodeInterface<N: Network>: Routing<N> {
                     // Terminate the process.
                     std::process::exit(0);
                 }
-                Err(error) => error!("tokio::signal::ctrl_c encountered an error: {}", error),
+                Err(error) => error!("The shutdown signal handler encountered an error: {}", error),
             }
         });
 
@@ -86,3 +153,19 @@ pub trait NodeInterface<N: Network>: Routing<N> {
     /// Shuts down the node.
     async fn shut_down(&self);
 }
+
+/// Waits for Ctrl-C or, on Unix-family systems, SIGTERM (e.g. from `systemctl stop`).
+async fn wait_for_shutdown_signal() -> std::io::Result<()> {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{signal, SignalKind};
+
+        let mut terminate = signal(SignalKind::terminate())?;
+        tokio::select! {
+            result = tokio::signal::ctrl_c() => result,
+            _ = terminate.recv() => Ok(()),
+        }
+    }
+    #[cfg(not(unix))]
+    tokio::signal::ctrl_c().await
+}
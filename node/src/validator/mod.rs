@@ -16,16 +16,22 @@ mod router;
 
 use crate::traits::NodeInterface;
 use snarkos_account::Account;
-use snarkos_node_bft::{helpers::init_primary_channels, ledger_service::CoreLedgerService};
-use snarkos_node_consensus::Consensus;
-use snarkos_node_rest::Rest;
+use snarkos_node_bft::{
+    helpers::{init_primary_channels, BftConfig, EQUIVOCATIONS_FILE},
+    ledger_service::CoreLedgerService,
+    MAX_SHUTDOWN_DELAY_IN_MS,
+};
+use snarkos_node_cdn::CdnConfig;
+use snarkos_node_consensus::{Consensus, MEMPOOL_FILE};
+use snarkos_node_rest::{Rest, RestConfig};
 use snarkos_node_router::{
     messages::{NodeType, PuzzleResponse, UnconfirmedSolution, UnconfirmedTransaction},
-    Heartbeat,
     Inbound,
     Outbound,
+    PeerLimits,
     Router,
     Routing,
+    PEER_STORE_FILE,
 };
 use snarkos_node_sync::{BlockSync, BlockSyncMode};
 use snarkos_node_tcp::{
@@ -77,12 +83,15 @@ impl<N: Network, C: ConsensusStorage<N>> Validator<N, C> {
         bft_ip: Option<SocketAddr>,
         rest_ip: Option<SocketAddr>,
         rest_rps: u32,
+        rest_config: RestConfig,
         account: Account<N>,
         trusted_peers: &[SocketAddr],
+        peer_limits: PeerLimits,
         trusted_validators: &[SocketAddr],
         genesis: Block<N>,
-        cdn: Option<String>,
+        cdn: Option<CdnConfig>,
         storage_mode: StorageMode,
+        bft_config: BftConfig<N>,
     ) -> Result<Self> {
         // Prepare the shutdown flag.
         let shutdown: Arc<AtomicBool> = Default::default();
@@ -94,11 +103,11 @@ impl<N: Network, C: ConsensusStorage<N>> Validator<N, C> {
         let ledger = Ledger::load(genesis, storage_mode.clone())?;
 
         // Initialize the CDN.
-        if let Some(base_url) = cdn {
-            // Sync the ledger with the CDN.
-            if let Err((_, error)) =
-                snarkos_node_cdn::sync_ledger_with_cdn(&base_url, ledger.clone(), shutdown.clone()).await
-            {
+        if let Some(cdn) = cdn {
+            // Sync the ledger with the CDN, falling back to P2P sync if the CDN is unreliable.
+            let result =
+                snarkos_node_cdn::sync_ledger_with_fallback(&cdn, ledger.clone(), shutdown.clone(), None).await;
+            if let Err((_, error)) = result {
                 crate::log_clean_error(&storage_mode);
                 return Err(error);
             }
@@ -110,12 +119,30 @@ impl<N: Network, C: ConsensusStorage<N>> Validator<N, C> {
         let sync = BlockSync::new(BlockSyncMode::Gateway, ledger_service.clone());
 
         // Initialize the consensus.
-        let mut consensus =
-            Consensus::new(account.clone(), ledger_service, bft_ip, trusted_validators, storage_mode.clone())?;
+        let mut consensus = Consensus::new(
+            account.clone(),
+            ledger_service,
+            bft_ip,
+            trusted_validators,
+            storage_mode.clone(),
+            bft_config,
+        )?;
+        // Persist the equivocations observed by the BFT, loading the ones from the previous runs.
+        let equivocations_path = aleo_std::aleo_ledger_dir(N::ID, storage_mode.clone()).join(EQUIVOCATIONS_FILE);
+        if let Err(error) = consensus.bft().storage().equivocations().open(&equivocations_path) {
+            warn!("Failed to load the equivocation log at '{}' - {error}", equivocations_path.display());
+        }
         // Initialize the primary channels.
         let (primary_sender, primary_receiver) = init_primary_channels::<N>();
         // Start the consensus.
         consensus.run(primary_sender, primary_receiver).await?;
+        // Reload the memory pool persisted by the previous run, and persist it again on shutdown.
+        let mempool_path = aleo_std::aleo_ledger_dir(N::ID, storage_mode.clone()).join(MEMPOOL_FILE);
+        match consensus.restore_mempool(mempool_path).await {
+            Ok(0) => (),
+            Ok(num) => info!("Reloaded {num} unconfirmed transmissions from the previous run"),
+            Err(error) => warn!("Failed to reload the memory pool from the previous run - {error}"),
+        }
 
         // Initialize the node router.
         let router = Router::new(
@@ -123,10 +150,15 @@ impl<N: Network, C: ConsensusStorage<N>> Validator<N, C> {
             NodeType::Validator,
             account,
             trusted_peers,
-            Self::MAXIMUM_NUMBER_OF_PEERS as u16,
+            peer_limits,
             matches!(storage_mode, StorageMode::Development(_)),
         )
         .await?;
+        // Load the known-good peers from the previous run.
+        router.enable_peer_store(aleo_std::aleo_ledger_dir(N::ID, storage_mode.clone()).join(PEER_STORE_FILE));
+        // Persist the REST indexes next to the ledger.
+        let rest_config =
+            RestConfig { storage_dir: Some(aleo_std::aleo_ledger_dir(N::ID, storage_mode.clone())), ..rest_config };
 
         // Initialize the node.
         let mut node = Self {
@@ -143,8 +175,10 @@ impl<N: Network, C: ConsensusStorage<N>> Validator<N, C> {
 
         // Initialize the REST server.
         if let Some(rest_ip) = rest_ip {
-            node.rest =
-                Some(Rest::start(rest_ip, rest_rps, Some(consensus), ledger.clone(), Arc::new(node.clone())).await?);
+            node.rest = Some(
+                Rest::start(rest_ip, rest_rps, rest_config, Some(consensus), ledger.clone(), Arc::new(node.clone()))
+                    .await?,
+            );
         }
         // Initialize the routing.
         node.initialize_routing().await;
@@ -165,6 +199,11 @@ impl<N: Network, C: ConsensusStorage<N>> Validator<N, C> {
     pub fn rest(&self) -> &Option<Rest<N, C, Self>> {
         &self.rest
     }
+
+    /// Advertises the given external address to the validators, instead of the listener address of the BFT.
+    pub fn set_bft_advertised_addr(&self, addr: SocketAddr) {
+        self.consensus.bft().primary().gateway().set_advertised_addr(addr)
+    }
 }
 
 impl<N: Network, C: ConsensusStorage<N>> Validator<N, C> {
@@ -429,10 +468,18 @@ impl<N: Network, C: ConsensusStorage<N>> NodeInterface<N> for Validator<N, C> {
     async fn shut_down(&self) {
         info!("Shutting down...");
 
+        // Finish the current round, so the validator is not considered unresponsive by its peers.
+        self.consensus.bft().primary().finish_round(Duration::from_millis(MAX_SHUTDOWN_DELAY_IN_MS)).await;
+
         // Shut down the node.
         trace!("Shutting down the node...");
         self.shutdown.store(true, std::sync::atomic::Ordering::Relaxed);
 
+        // Shut down the REST server, draining the in-flight requests.
+        if let Some(rest) = &self.rest {
+            rest.shut_down().await;
+        }
+
         // Abort the tasks.
         trace!("Shutting down the validator...");
         self.handles.lock().iter().for_each(|handle| handle.abort());
@@ -489,6 +536,7 @@ mod tests {
             None,
             Some(rest),
             10,
+            RestConfig::default(),
             account,
             &[],
             &[],
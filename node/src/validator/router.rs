@@ -13,18 +13,21 @@
 // limitations under the License.
 
 use super::*;
-use snarkos_node_router::messages::{
-    BlockRequest,
-    BlockResponse,
-    DataBlocks,
-    DisconnectReason,
-    Message,
-    MessageCodec,
-    Ping,
-    Pong,
-    UnconfirmedTransaction,
+use snarkos_node_router::{
+    messages::{
+        BlockRequest,
+        BlockResponse,
+        DataBlocks,
+        DisconnectReason,
+        Message,
+        NoiseCodec,
+        Ping,
+        Pong,
+        UnconfirmedTransaction,
+    },
+    Offense,
 };
-use snarkos_node_tcp::{Connection, ConnectionSide, Tcp};
+use snarkos_node_tcp::{Connection, ConnectionSide, MessagePriority, Tcp};
 use snarkvm::{
     ledger::narwhal::Data,
     prelude::{block::Transaction, coinbase::EpochChallenge, error, Network},
@@ -88,25 +91,30 @@ impl<N: Network, C: ConsensusStorage<N>> Disconnect for Validator<N, C> {
 
 #[async_trait]
 impl<N: Network, C: ConsensusStorage<N>> Writing for Validator<N, C> {
-    type Codec = MessageCodec<N>;
+    type Codec = NoiseCodec<N>;
     type Message = Message<N>;
 
     /// Creates an [`Encoder`] used to write the outbound messages to the target stream.
     /// The `side` parameter indicates the connection side **from the node's perspective**.
-    fn codec(&self, _addr: SocketAddr, _side: ConnectionSide) -> Self::Codec {
-        Default::default()
+    fn codec(&self, peer_addr: SocketAddr, _side: ConnectionSide) -> Self::Codec {
+        self.router().message_codec(peer_addr)
+    }
+
+    /// Returns the priority of the given outbound message.
+    fn message_priority(&self, message: &Self::Message) -> MessagePriority {
+        self.router().message_priority(message)
     }
 }
 
 #[async_trait]
 impl<N: Network, C: ConsensusStorage<N>> Reading for Validator<N, C> {
-    type Codec = MessageCodec<N>;
+    type Codec = NoiseCodec<N>;
     type Message = Message<N>;
 
     /// Creates a [`Decoder`] used to interpret messages from the network.
     /// The `side` param indicates the connection side **from the node's perspective**.
-    fn codec(&self, _peer_addr: SocketAddr, _side: ConnectionSide) -> Self::Codec {
-        Default::default()
+    fn codec(&self, peer_addr: SocketAddr, _side: ConnectionSide) -> Self::Codec {
+        self.router().message_codec(peer_addr)
     }
 
     /// Processes a message received from the network.
@@ -115,6 +123,10 @@ impl<N: Network, C: ConsensusStorage<N>> Reading for Validator<N, C> {
         if let Err(error) = self.inbound(peer_addr, message).await {
             if let Some(peer_ip) = self.router().resolve_to_listener(&peer_addr) {
                 warn!("Disconnecting from '{peer_ip}' - {error}");
+                // Penalize the peer, if the error was caused by an offense.
+                if let Some(offense) = Offense::of(&error) {
+                    self.router().penalize_peer(peer_ip, offense);
+                }
                 Outbound::send(self, peer_ip, Message::Disconnect(DisconnectReason::ProtocolViolation.into()));
                 // Disconnect from this peer.
                 self.router().disconnect(peer_ip);
@@ -125,13 +137,16 @@ impl<N: Network, C: ConsensusStorage<N>> Reading for Validator<N, C> {
 }
 
 #[async_trait]
-impl<N: Network, C: ConsensusStorage<N>> Routing<N> for Validator<N, C> {}
-
-impl<N: Network, C: ConsensusStorage<N>> Heartbeat<N> for Validator<N, C> {
-    /// The maximum number of peers permitted to maintain connections with.
-    const MAXIMUM_NUMBER_OF_PEERS: usize = 200;
+impl<N: Network, C: ConsensusStorage<N>> Routing<N> for Validator<N, C> {
+    /// Pauses or resumes the block sync of the validator, which runs in the BFT.
+    fn set_block_sync_paused(&self, paused: bool) -> Result<()> {
+        self.consensus.bft().primary().set_block_sync_paused(paused);
+        Ok(())
+    }
 }
 
+impl<N: Network, C: ConsensusStorage<N>> Heartbeat<N> for Validator<N, C> {}
+
 impl<N: Network, C: ConsensusStorage<N>> Outbound<N> for Validator<N, C> {
     /// Returns a reference to the router.
     fn router(&self) -> &Router<N> {
@@ -191,6 +206,10 @@ impl<N: Network, C: ConsensusStorage<N>> Inbound<N> for Validator<N, C> {
 
     /// Sleeps for a period and then sends a `Ping` message to the peer.
     fn pong(&self, peer_ip: SocketAddr, _message: Pong) -> bool {
+        // Update the latency of the peer in the sync module, to prefer low-latency peers for block requests.
+        if let Some(latency) = self.router().get_connected_peer(&peer_ip).and_then(|peer| peer.latency()) {
+            self.sync.update_peer_latency(peer_ip, latency);
+        }
         // Spawn an asynchronous task for the `Ping` request.
         let self_ = self.clone();
         tokio::spawn(async move {
@@ -13,7 +13,7 @@
 // limitations under the License.
 
 use crate::{
-    helpers::{PeerPair, SyncRequest},
+    helpers::{sync_peer_weights, PeerPair, PeerPerformance, SyncRequest},
     locators::BlockLocators,
 };
 use snarkos_node_bft_ledger_service::LedgerService;
@@ -25,15 +25,15 @@ use anyhow::{bail, ensure, Result};
 use indexmap::{IndexMap, IndexSet};
 use itertools::Itertools;
 use parking_lot::{Mutex, RwLock};
-use rand::{prelude::IteratorRandom, CryptoRng, Rng};
+use rand::{prelude::SliceRandom, CryptoRng, Rng};
 use std::{
     collections::BTreeMap,
     net::{IpAddr, Ipv4Addr, SocketAddr},
     sync::{
-        atomic::{AtomicBool, Ordering},
+        atomic::{AtomicBool, AtomicU32, Ordering},
         Arc,
     },
-    time::Instant,
+    time::{Duration, Instant},
 };
 
 #[cfg(not(test))]
@@ -107,8 +107,15 @@ pub struct BlockSync<N: Network> {
     /// The map of (timed out) peer IPs to their request timestamps.
     /// This map is used to determine which peers to remove if they have timed out too many times.
     request_timeouts: Arc<RwLock<IndexMap<SocketAddr, Vec<Instant>>>>,
+    /// The map of peer IPs to their measured latency and block response time.
+    /// This map is used to favor the low-latency, high-throughput peers for block requests.
+    performances: Arc<RwLock<IndexMap<SocketAddr, PeerPerformance>>>,
     /// The boolean indicator of whether the node is synced up to the latest block (within the given tolerance).
     is_block_synced: Arc<AtomicBool>,
+    /// The number of blocks the node is behind the greatest peer height, or `u32::MAX` if no peer locators are known.
+    num_blocks_behind: Arc<AtomicU32>,
+    /// The boolean indicator of whether the block sync is paused (e.g. by an operator).
+    is_paused: Arc<AtomicBool>,
     /// The lock to guarantee advance_with_sync_blocks() is called only once at a time.
     advance_with_sync_blocks_lock: Arc<Mutex<()>>,
 }
@@ -125,7 +132,10 @@ impl<N: Network> BlockSync<N> {
             responses: Default::default(),
             request_timestamps: Default::default(),
             request_timeouts: Default::default(),
+            performances: Default::default(),
             is_block_synced: Default::default(),
+            num_blocks_behind: Arc::new(AtomicU32::new(u32::MAX)),
+            is_paused: Default::default(),
             advance_with_sync_blocks_lock: Default::default(),
         }
     }
@@ -141,6 +151,59 @@ impl<N: Network> BlockSync<N> {
     pub fn is_block_synced(&self) -> bool {
         self.is_block_synced.load(Ordering::SeqCst)
     }
+
+    /// Returns the number of blocks the node is behind the greatest peer height,
+    /// or `None` if the block locators of the peers are not yet known.
+    #[inline]
+    pub fn num_blocks_behind(&self) -> Option<u32> {
+        match self.num_blocks_behind.load(Ordering::SeqCst) {
+            u32::MAX => None,
+            num_blocks_behind => Some(num_blocks_behind),
+        }
+    }
+
+    /// Returns `true` if the block sync is paused.
+    #[inline]
+    pub fn is_paused(&self) -> bool {
+        self.is_paused.load(Ordering::SeqCst)
+    }
+
+    /// Pauses or resumes the block sync.
+    pub fn set_paused(&self, paused: bool) {
+        self.is_paused.store(paused, Ordering::SeqCst);
+    }
+
+    /// Returns the greatest block height advertised by the peers, if any.
+    #[inline]
+    pub fn greatest_peer_block_height(&self) -> Option<u32> {
+        self.locators.read().values().map(|locators| locators.latest_locator_height()).max()
+    }
+
+    /// Returns the block locators of each peer.
+    pub fn get_peer_locators(&self) -> IndexMap<SocketAddr, BlockLocators<N>> {
+        self.locators.read().clone()
+    }
+
+    /// Returns the heights of the pending block requests to each peer.
+    pub fn get_peer_block_requests(&self) -> IndexMap<SocketAddr, Vec<u32>> {
+        let mut peer_requests = IndexMap::<_, Vec<_>>::new();
+        for (height, (_, _, sync_ips)) in self.requests.read().iter() {
+            for sync_ip in sync_ips {
+                peer_requests.entry(*sync_ip).or_default().push(*height);
+            }
+        }
+        peer_requests
+    }
+
+    /// Returns the number of recent block request timeouts of each peer.
+    pub fn get_peer_request_timeouts(&self) -> IndexMap<SocketAddr, usize> {
+        self.request_timeouts.read().iter().map(|(peer_ip, timestamps)| (*peer_ip, timestamps.len())).collect()
+    }
+
+    /// Returns the measured latency and block response time of each peer.
+    pub fn get_peer_performances(&self) -> IndexMap<SocketAddr, PeerPerformance> {
+        self.performances.read().clone()
+    }
 }
 
 #[allow(dead_code)]
@@ -216,6 +279,12 @@ impl<N: Network> BlockSync<N> {
     /// Performs one iteration of the block sync.
     #[inline]
     pub async fn try_block_sync<C: CommunicationService>(&self, communication: &C) {
+        // If the block sync is paused, do not request or advance any blocks.
+        if self.is_paused() {
+            trace!("Skipping the block sync, as it is paused");
+            return;
+        }
+
         // Prepare the block requests, if any.
         // In the process, we update the state of `is_block_synced` for the sync module.
         let block_requests = self.prepare_block_requests();
@@ -404,6 +473,13 @@ impl<N: Network> BlockSync<N> {
         self.remove_block_requests_to_peer(peer_ip);
         // Remove the timeouts for the peer.
         self.request_timeouts.write().swap_remove(peer_ip);
+        // Remove the performance measurements of the peer.
+        self.performances.write().swap_remove(peer_ip);
+    }
+
+    /// Records the given round-trip time of the peer (e.g. measured by a ping).
+    pub fn update_peer_latency(&self, peer_ip: SocketAddr, latency: Duration) {
+        self.performances.write().entry(peer_ip).or_default().update_latency(latency);
     }
 }
 
@@ -441,6 +517,11 @@ impl<N: Network> BlockSync<N> {
         let is_synced = num_blocks_behind <= max_blocks_behind;
         // Update the sync status.
         self.is_block_synced.store(is_synced, Ordering::SeqCst);
+        // Update the number of blocks behind, if the block locators of any peer are known.
+        match self.locators.read().is_empty() {
+            true => self.num_blocks_behind.store(u32::MAX, Ordering::SeqCst),
+            false => self.num_blocks_behind.store(num_blocks_behind, Ordering::SeqCst),
+        }
     }
 
     /// Inserts a block request for the given height.
@@ -474,6 +555,10 @@ impl<N: Network> BlockSync<N> {
         if let Some((_, _, sync_ips)) = self.requests.write().get_mut(&height) {
             sync_ips.swap_remove(&peer_ip);
         }
+        // Record the time the peer took to respond.
+        if let Some(timestamp) = self.get_block_request_timestamp(height) {
+            self.performances.write().entry(peer_ip).or_default().update_response_time(timestamp.elapsed());
+        }
 
         // Acquire the write lock on the responses map.
         let mut responses = self.responses.write();
@@ -663,9 +748,15 @@ impl<N: Network> BlockSync<N> {
         if !timeout_ips.is_empty() {
             // Acquire the write lock on the request timeouts map.
             let mut request_timeouts = self.request_timeouts.write();
-            // Add each timeout IP to the request timeouts map.
+            // Acquire the write lock on the performances map.
+            let mut performances = self.performances.write();
+            // Add each timeout IP to the request timeouts map, and demote it as a slow peer.
             for timeout_ip in timeout_ips {
                 request_timeouts.entry(timeout_ip).or_default().push(now);
+                performances
+                    .entry(timeout_ip)
+                    .or_default()
+                    .update_response_time(Duration::from_secs(BLOCK_REQUEST_TIMEOUT_IN_SECS));
             }
         }
 
@@ -777,6 +868,14 @@ impl<N: Network> BlockSync<N> {
 
         let mut requests = Vec::with_capacity((start_height..end_height).len());
 
+        // Weigh the sync peers, favoring the low-latency, high-throughput peers.
+        let weighted_peers: Vec<(SocketAddr, f64)> = {
+            let performances = self.performances.read();
+            let performances: Vec<_> =
+                sync_peers.keys().map(|peer_ip| performances.get(peer_ip).copied().unwrap_or_default()).collect();
+            sync_peers.keys().copied().zip_eq(sync_peer_weights(&performances)).collect()
+        };
+
         for height in start_height..end_height {
             // Ensure the current height is not canonized or already requested.
             if self.check_block_request(height).is_err() {
@@ -796,11 +895,17 @@ impl<N: Network> BlockSync<N> {
                 }
             }
 
-            // Pick the sync peers.
-            let sync_ips = sync_peers.keys().copied().choose_multiple(rng, num_sync_ips);
+            // Pick the sync peers, with a probability proportional to their weight.
+            let sync_ips: IndexSet<_> = match weighted_peers.choose_multiple_weighted(rng, num_sync_ips, |(_, w)| *w) {
+                Ok(sync_ips) => sync_ips.map(|(peer_ip, _)| *peer_ip).collect(),
+                Err(error) => {
+                    warn!("Failed to pick the sync peers for block {height} - {error}");
+                    break;
+                }
+            };
 
             // Append the request.
-            requests.push((height, (hash, previous_hash, sync_ips.into_iter().collect())));
+            requests.push((height, (hash, previous_hash, sync_ips)));
         }
 
         requests
@@ -1016,6 +1121,28 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_num_blocks_behind() {
+        let sync = sample_sync_at_height(5);
+
+        // Check that the number of blocks behind is unknown without peer locators.
+        sync.prepare_block_requests();
+        assert_eq!(sync.num_blocks_behind(), None);
+
+        // Check that the number of blocks behind is computed from the greatest peer height.
+        sync.update_peer_locators(sample_peer_ip(1), sample_block_locators(20)).unwrap();
+        sync.prepare_block_requests();
+        assert_eq!(sync.num_blocks_behind(), Some(15));
+        assert!(!sync.is_block_synced());
+
+        // Check that a peer behind the node does not make the node behind.
+        sync.remove_peer(&sample_peer_ip(1));
+        sync.update_peer_locators(sample_peer_ip(2), sample_block_locators(3)).unwrap();
+        sync.prepare_block_requests();
+        assert_eq!(sync.num_blocks_behind(), Some(0));
+        assert!(sync.is_block_synced());
+    }
+
     #[test]
     fn test_prepare_block_requests_with_leading_fork_at_11() {
         let sync = sample_sync_at_height(0);
@@ -1253,6 +1380,47 @@ mod tests {
         assert_eq!(sync.get_peer_height(&peer_ip), None);
     }
 
+    #[test]
+    fn test_greatest_peer_block_height() {
+        let sync = sample_sync_at_height(0);
+        assert_eq!(sync.greatest_peer_block_height(), None);
+
+        sync.update_peer_locators(sample_peer_ip(1), sample_block_locators(100)).unwrap();
+        sync.update_peer_locators(sample_peer_ip(2), sample_block_locators(200)).unwrap();
+        assert_eq!(sync.greatest_peer_block_height(), Some(200));
+
+        sync.remove_peer(&sample_peer_ip(2));
+        assert_eq!(sync.greatest_peer_block_height(), Some(100));
+    }
+
+    #[test]
+    fn test_pause_block_sync() {
+        let sync = sample_sync_at_height(0);
+        assert!(!sync.is_paused());
+        sync.set_paused(true);
+        assert!(sync.is_paused());
+        sync.set_paused(false);
+        assert!(!sync.is_paused());
+    }
+
+    #[test]
+    fn test_get_peer_block_requests() {
+        let sync = sample_sync_at_height(0);
+        let (peer_1, peer_2) = (sample_peer_ip(1), sample_peer_ip(2));
+        sync.update_peer_locators(peer_1, sample_block_locators(10)).unwrap();
+        assert_eq!(sync.get_peer_locators().get(&peer_1), Some(&sample_block_locators(10)));
+
+        // Insert the block requests.
+        sync.insert_block_request(1, (None, None, indexset![peer_1, peer_2])).unwrap();
+        sync.insert_block_request(2, (None, None, indexset![peer_1])).unwrap();
+
+        // Check the pending block requests to each peer.
+        let peer_requests = sync.get_peer_block_requests();
+        assert_eq!(peer_requests.get(&peer_1), Some(&vec![1, 2]));
+        assert_eq!(peer_requests.get(&peer_2), Some(&vec![1]));
+        assert!(sync.get_peer_request_timeouts().is_empty());
+    }
+
     #[test]
     fn test_locators_insert_remove_insert() {
         let sync = sample_sync_at_height(0);
@@ -12,6 +12,9 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+mod peer_performance;
+pub use peer_performance::*;
+
 use snarkvm::prelude::Network;
 
 use core::hash::Hash;
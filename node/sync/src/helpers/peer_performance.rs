@@ -0,0 +1,134 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::time::Duration;
+
+/// The smoothing factor of the moving averages of the peer measurements, in `(0, 1]`.
+const SMOOTHING_FACTOR: f64 = 0.2;
+/// The factor by which a peer must be slower than the median sync peer to be demoted.
+const SLOW_PEER_FACTOR: f64 = 4.0;
+/// The factor by which the weight of a demoted peer is reduced.
+const DEMOTED_PEER_WEIGHT_FACTOR: f64 = 0.01;
+
+/// The measured performance of a sync peer.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct PeerPerformance {
+    /// The smoothed round-trip time of the peer (measured by the router pings), in milliseconds.
+    latency_ms: Option<f64>,
+    /// The smoothed time the peer takes to respond to a block request, in milliseconds.
+    response_time_ms: Option<f64>,
+}
+
+impl PeerPerformance {
+    /// Returns the smoothed round-trip time of the peer, if it was measured.
+    pub fn latency(&self) -> Option<Duration> {
+        self.latency_ms.map(|latency_ms| Duration::from_secs_f64(latency_ms / 1000.0))
+    }
+
+    /// Returns the smoothed time the peer takes to respond to a block request, if it was measured.
+    pub fn response_time(&self) -> Option<Duration> {
+        self.response_time_ms.map(|response_time_ms| Duration::from_secs_f64(response_time_ms / 1000.0))
+    }
+
+    /// Records a round-trip time of the peer.
+    pub fn update_latency(&mut self, latency: Duration) {
+        self.latency_ms = Some(smooth(self.latency_ms, latency.as_secs_f64() * 1000.0));
+    }
+
+    /// Records the time the peer took to respond to a block request.
+    pub fn update_response_time(&mut self, response_time: Duration) {
+        self.response_time_ms = Some(smooth(self.response_time_ms, response_time.as_secs_f64() * 1000.0));
+    }
+
+    /// Returns the cost of requesting blocks from the peer, in milliseconds, if it was measured.
+    /// The response time reflects both the latency and the throughput of the peer, so it is preferred;
+    /// until it is measured, the latency is used instead.
+    fn cost_ms(&self) -> Option<f64> {
+        self.response_time_ms.or(self.latency_ms)
+    }
+}
+
+/// Returns the moving average of the given previous value and new sample.
+fn smooth(previous: Option<f64>, sample: f64) -> f64 {
+    match previous {
+        Some(previous) => previous + SMOOTHING_FACTOR * (sample - previous),
+        None => sample,
+    }
+}
+
+/// Returns the weights with which to pick each of the given peers for a block request,
+/// favoring the low-latency, high-throughput peers.
+///
+/// The peers that were not measured yet are given the median cost, and the peers that are
+/// slower than [`SLOW_PEER_FACTOR`] times the median are demoted.
+pub(crate) fn sync_peer_weights(performances: &[PeerPerformance]) -> Vec<f64> {
+    // Compute the median cost of the measured peers.
+    let mut costs: Vec<f64> = performances.iter().filter_map(PeerPerformance::cost_ms).collect();
+    costs.sort_by(f64::total_cmp);
+    let Some(median) = costs.get(costs.len() / 2).copied() else {
+        // If no peer was measured yet, weigh them equally.
+        return vec![1.0; performances.len()];
+    };
+    // Ensure the costs are positive, so that the weights are finite.
+    let median = median.max(1.0);
+
+    performances
+        .iter()
+        .map(|performance| {
+            let cost = performance.cost_ms().unwrap_or(median).max(1.0);
+            match cost > SLOW_PEER_FACTOR * median {
+                true => DEMOTED_PEER_WEIGHT_FACTOR / cost,
+                false => 1.0 / cost,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_performance(response_time_ms: u64) -> PeerPerformance {
+        let mut performance = PeerPerformance::default();
+        performance.update_response_time(Duration::from_millis(response_time_ms));
+        performance
+    }
+
+    #[test]
+    fn test_smoothing() {
+        let mut performance = PeerPerformance::default();
+        assert_eq!(performance.latency(), None);
+        performance.update_latency(Duration::from_millis(100));
+        assert_eq!(performance.latency(), Some(Duration::from_millis(100)));
+        // Check that a single outlier only moves the average partially.
+        performance.update_latency(Duration::from_millis(600));
+        assert_eq!(performance.latency(), Some(Duration::from_millis(200)));
+    }
+
+    #[test]
+    fn test_sync_peer_weights() {
+        // Check that unmeasured peers are weighed equally.
+        assert_eq!(sync_peer_weights(&[PeerPerformance::default(); 3]), vec![1.0; 3]);
+
+        let fast = sample_performance(50);
+        let median = sample_performance(100);
+        let slow = sample_performance(1000);
+        let weights = sync_peer_weights(&[fast, median, slow, PeerPerformance::default()]);
+        // Check that the faster peer is favored, and the unmeasured peer is given the median cost.
+        assert!(weights[0] > weights[1]);
+        assert_eq!(weights[1], weights[3]);
+        // Check that the slow peer is demoted.
+        assert!(weights[2] < weights[1] / 100.0);
+    }
+}
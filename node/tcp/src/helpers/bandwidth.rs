@@ -0,0 +1,82 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+
+use std::{
+    num::NonZeroU32,
+    time::{Duration, Instant},
+};
+
+/// A token bucket limiting the bandwidth of a single connection in one direction.
+///
+/// The bucket holds up to one second worth of bytes, which allows short bursts. Messages larger than the
+/// bucket are let through, but put the bucket into debt, delaying the subsequent messages accordingly.
+#[derive(Debug)]
+pub struct TokenBucket {
+    /// The number of bytes added to the bucket per second.
+    rate: f64,
+    /// The number of bytes currently available in the bucket (negative if in debt).
+    tokens: f64,
+    /// The time the bucket was last refilled.
+    last_refill: Instant,
+}
+
+impl TokenBucket {
+    /// Initializes a full token bucket with the given rate, in bytes per second.
+    pub fn new(bytes_per_sec: NonZeroU32) -> Self {
+        let rate = bytes_per_sec.get() as f64;
+        Self { rate, tokens: rate, last_refill: Instant::now() }
+    }
+
+    /// Consumes the given number of bytes, and returns the delay required to stay within the rate.
+    pub fn consume(&mut self, bytes: usize) -> Duration {
+        self.consume_at(bytes, Instant::now())
+    }
+
+    /// Consumes the given number of bytes at the given time, and returns the delay required to stay within the rate.
+    fn consume_at(&mut self, bytes: usize, now: Instant) -> Duration {
+        // Refill the bucket, up to its capacity.
+        let elapsed = now.saturating_duration_since(self.last_refill).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.rate).min(self.rate);
+        self.last_refill = now;
+        // Consume the bytes.
+        self.tokens -= bytes as f64;
+        match self.tokens < 0.0 {
+            true => Duration::from_secs_f64(-self.tokens / self.rate),
+            false => Duration::ZERO,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_token_bucket() {
+        let mut bucket = TokenBucket::new(NonZeroU32::new(1000).unwrap());
+        let start = bucket.last_refill;
+
+        // Ensure a burst of up to the rate is not delayed.
+        assert_eq!(bucket.consume_at(600, start), Duration::ZERO);
+        assert_eq!(bucket.consume_at(400, start), Duration::ZERO);
+        // Ensure the bytes beyond the rate are delayed.
+        assert_eq!(bucket.consume_at(500, start), Duration::from_millis(500));
+        // Ensure the bucket refills over time.
+        assert_eq!(bucket.consume_at(500, start + Duration::from_secs(1)), Duration::ZERO);
+        // Ensure the bucket does not refill beyond its capacity.
+        assert_eq!(bucket.consume_at(1000, start + Duration::from_secs(10)), Duration::ZERO);
+        assert_eq!(bucket.consume_at(2000, start + Duration::from_secs(10)), Duration::from_secs(2));
+    }
+}
@@ -15,11 +15,51 @@
 use std::{
     io::{self, ErrorKind::*},
     net::{IpAddr, Ipv4Addr, SocketAddr},
+    num::NonZeroU32,
 };
 
+use once_cell::sync::OnceCell;
+
 #[cfg(doc)]
 use crate::protocols::{self, Handshake, Reading, Writing};
 
+/// The SOCKS5 proxy used by default for outbound connections, if one was set.
+static DEFAULT_PROXY: OnceCell<SocketAddr> = OnceCell::new();
+
+/// Sets the SOCKS5 proxy used by default (i.e. in [`Config::default`]) for outbound connections,
+/// so that it applies to every `Tcp` created afterwards. Returns `false` if it was already set.
+pub fn set_default_proxy(proxy: SocketAddr) -> bool {
+    DEFAULT_PROXY.set(proxy).is_ok()
+}
+
+/// The per-connection bandwidth limits (inbound, outbound) used by default, if they were set.
+static DEFAULT_BANDWIDTH_LIMITS: OnceCell<(Option<NonZeroU32>, Option<NonZeroU32>)> = OnceCell::new();
+
+/// Sets the per-connection bandwidth limits, in bytes per second, used by default (i.e. in [`Config::default`])
+/// for inbound and outbound messages, so that they apply to every `Tcp` created afterwards.
+/// Returns `false` if they were already set.
+pub fn set_default_bandwidth_limits(inbound: Option<NonZeroU32>, outbound: Option<NonZeroU32>) -> bool {
+    DEFAULT_BANDWIDTH_LIMITS.set((inbound, outbound)).is_ok()
+}
+
+/// The handshake and read timeouts (in milliseconds) used by default, if they were set.
+static DEFAULT_TIMEOUTS: OnceCell<(u64, u64)> = OnceCell::new();
+
+/// Sets the handshake and read timeouts, in milliseconds, used by default (i.e. in [`Config::default`]),
+/// so that they apply to every `Tcp` created afterwards. Returns `false` if they were already set.
+pub fn set_default_timeouts(handshake_timeout_ms: u64, read_timeout_ms: u64) -> bool {
+    DEFAULT_TIMEOUTS.set((handshake_timeout_ms, read_timeout_ms)).is_ok()
+}
+
+/// Whether TCP hole punching is enabled by default, if it was set.
+static DEFAULT_HOLE_PUNCHING: OnceCell<bool> = OnceCell::new();
+
+/// Sets whether TCP hole punching is enabled by default (i.e. in [`Config::default`]),
+/// so that it applies to every `Tcp` created afterwards. Returns `false` if it was already set.
+pub fn set_default_hole_punching(enabled: bool) -> bool {
+    DEFAULT_HOLE_PUNCHING.set(enabled).is_ok()
+}
+
 /// The Tcp's configuration. See the source of [`Config::default`] for the defaults.
 #[derive(Debug, Clone)]
 pub struct Config {
@@ -54,6 +94,38 @@ pub struct Config {
     pub max_connections: u16,
     /// The maximum time (in milliseconds) allowed to establish a raw (before the [`Handshake`] protocol) TCP connection.
     pub connection_timeout_ms: u16,
+    /// The maximum time (in milliseconds) allowed for a connection to complete the [`Handshake`], before it is dropped.
+    pub handshake_timeout_ms: u64,
+    /// The maximum time (in milliseconds) allowed between two messages from a connection, before it is dropped.
+    ///
+    /// note: If set to `0`, reads never time out. Tcp needs to implement the [`Reading`] protocol in order for it to
+    /// have any effect.
+    pub read_timeout_ms: u64,
+    /// The maximum number of inbound connection attempts accepted from a single IP address per minute.
+    ///
+    /// note: If set to `0`, inbound connection attempts are not rate-limited. Loopback addresses are exempt.
+    pub max_inbound_attempts_per_minute: u32,
+    /// The maximum number of inbound connections that can be pending (i.e. not done with the [`Handshake`]) at once.
+    pub max_pending_inbound: u16,
+    /// The address of the SOCKS5 proxy (e.g. Tor) that all outbound connections are routed through.
+    ///
+    /// note: If set to `None`, outbound connections are established directly.
+    pub proxy: Option<SocketAddr>,
+    /// The maximum number of bytes per second that can be received from a single connection.
+    ///
+    /// note: If set to `None`, inbound messages are not rate-limited. Tcp needs to implement the [`Reading`]
+    /// protocol in order for it to have any effect.
+    pub inbound_bandwidth_limit: Option<NonZeroU32>,
+    /// The maximum number of bytes per second that can be sent to a single connection.
+    ///
+    /// note: If set to `None`, outbound messages are not rate-limited. Tcp needs to implement the [`Writing`]
+    /// protocol in order for it to have any effect.
+    pub outbound_bandwidth_limit: Option<NonZeroU32>,
+    /// Allow the outbound connections to be dialed from the listening port, so that a peer behind a NAT can
+    /// connect through the mapping opened by a dial in the opposite direction (i.e. TCP hole punching).
+    ///
+    /// note: This is only supported on Unix, and has no effect if [`Config::proxy`] is set.
+    pub hole_punching: bool,
 }
 
 impl Config {
@@ -82,6 +154,10 @@ impl Default for Config {
             Some(IpAddr::V4(Ipv4Addr::UNSPECIFIED))
         }
 
+        let (inbound_bandwidth_limit, outbound_bandwidth_limit) =
+            DEFAULT_BANDWIDTH_LIMITS.get().copied().unwrap_or_default();
+        let (handshake_timeout_ms, read_timeout_ms) = DEFAULT_TIMEOUTS.get().copied().unwrap_or((3_000, 0));
+
         Self {
             name: None,
             listener_ip: default_ip(),
@@ -90,6 +166,14 @@ impl Default for Config {
             fatal_io_errors: vec![ConnectionReset, ConnectionAborted, BrokenPipe, InvalidData, UnexpectedEof],
             max_connections: 100,
             connection_timeout_ms: 1_000,
+            handshake_timeout_ms,
+            read_timeout_ms,
+            max_inbound_attempts_per_minute: 30,
+            max_pending_inbound: 64,
+            proxy: DEFAULT_PROXY.get().copied(),
+            inbound_bandwidth_limit,
+            outbound_bandwidth_limit,
+            hole_punching: DEFAULT_HOLE_PUNCHING.get().copied().unwrap_or_default(),
         }
     }
 }
@@ -0,0 +1,90 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::{
+    collections::{HashMap, VecDeque},
+    net::IpAddr,
+    time::{Duration, Instant},
+};
+
+use parking_lot::Mutex;
+
+/// The window over which the inbound connection attempts of an IP address are counted.
+pub const CONNECTION_ATTEMPT_WINDOW: Duration = Duration::from_secs(60);
+/// The number of tracked IP addresses above which the ones without recent attempts are forgotten.
+const MAX_TRACKED_IPS: usize = 4096;
+
+/// Tracks the recent inbound connection attempts of each IP address, to rate-limit them.
+#[derive(Debug, Default)]
+pub struct ConnectionAttempts {
+    /// The timestamps of the recent connection attempts, per IP address.
+    attempts: Mutex<HashMap<IpAddr, VecDeque<Instant>>>,
+}
+
+impl ConnectionAttempts {
+    /// Registers a connection attempt from the given IP address, returning `false` if the IP address
+    /// already made `limit` attempts within the last [`CONNECTION_ATTEMPT_WINDOW`].
+    /// Note: Rejected attempts are not registered.
+    pub fn register(&self, ip: IpAddr, limit: u32) -> bool {
+        self.register_at(ip, limit, Instant::now())
+    }
+
+    /// Registers a connection attempt from the given IP address at the given time.
+    fn register_at(&self, ip: IpAddr, limit: u32, now: Instant) -> bool {
+        let is_recent = |timestamp: &Instant| now.saturating_duration_since(*timestamp) < CONNECTION_ATTEMPT_WINDOW;
+
+        let mut attempts = self.attempts.lock();
+        // Forget the IP addresses without recent attempts, to bound the memory use.
+        if attempts.len() >= MAX_TRACKED_IPS {
+            attempts.retain(|_, timestamps| timestamps.back().map_or(false, is_recent));
+        }
+        // Retain only the recent attempts of the IP address.
+        let timestamps = attempts.entry(ip).or_default();
+        while timestamps.front().map_or(false, |timestamp| !is_recent(timestamp)) {
+            timestamps.pop_front();
+        }
+        // Ensure the IP address has not reached the limit.
+        if timestamps.len() >= limit as usize {
+            return false;
+        }
+        timestamps.push_back(now);
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::net::Ipv4Addr;
+
+    #[test]
+    fn test_connection_attempts() {
+        let attempts = ConnectionAttempts::default();
+        let ip = IpAddr::V4(Ipv4Addr::new(1, 2, 3, 4));
+        let other_ip = IpAddr::V4(Ipv4Addr::new(5, 6, 7, 8));
+        let now = Instant::now();
+
+        // Check that the attempts are accepted up to the limit.
+        for _ in 0..3 {
+            assert!(attempts.register_at(ip, 3, now));
+        }
+        assert!(!attempts.register_at(ip, 3, now));
+        // Check that the limit applies per IP address.
+        assert!(attempts.register_at(other_ip, 3, now));
+
+        // Check that the attempts are accepted again once the window has passed.
+        assert!(attempts.register_at(ip, 3, now + CONNECTION_ATTEMPT_WINDOW));
+    }
+}
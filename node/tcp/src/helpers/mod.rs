@@ -12,8 +12,20 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+mod bandwidth;
+pub use bandwidth::TokenBucket;
+
+mod connection_attempts;
+pub use connection_attempts::{ConnectionAttempts, CONNECTION_ATTEMPT_WINDOW};
+
 mod config;
-pub use config::Config;
+pub use config::{
+    set_default_bandwidth_limits,
+    set_default_hole_punching,
+    set_default_proxy,
+    set_default_timeouts,
+    Config,
+};
 
 pub mod connections;
 pub use connections::{Connection, ConnectionSide};
@@ -21,6 +33,13 @@ pub use connections::{Connection, ConnectionSide};
 mod known_peers;
 pub use known_peers::KnownPeers;
 
+mod priority;
+pub use priority::MessagePriority;
+pub(crate) use priority::{priority_channel, PrioritySender};
+
+mod socks5;
+pub use socks5::{socks5_connect, socks5_resolve, Socks5Target};
+
 mod stats;
 pub use stats::Stats;
 
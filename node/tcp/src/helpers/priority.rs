@@ -0,0 +1,133 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::{future::poll_fn, task::Poll};
+use tokio::sync::mpsc;
+
+/// The priority of an outbound message, which decides the order messages are written to a connection in.
+///
+/// Whenever a connection is backed up (e.g. by its bandwidth limit), the queued messages of a higher priority
+/// are written before any of a lower priority, regardless of the order they were queued in.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum MessagePriority {
+    /// Latency-critical messages (e.g. block propagation and BFT certificates).
+    High,
+    /// Regular messages.
+    #[default]
+    Normal,
+    /// Bulk messages (e.g. transaction gossip and sync responses).
+    Low,
+}
+
+impl MessagePriority {
+    /// The number of message priorities.
+    pub(crate) const COUNT: usize = 3;
+
+    /// Returns the index of the queue of the priority, starting from the highest priority.
+    const fn index(&self) -> usize {
+        match self {
+            Self::High => 0,
+            Self::Normal => 1,
+            Self::Low => 2,
+        }
+    }
+}
+
+/// Creates a set of bounded queues, one per message priority, each with the given depth.
+pub(crate) fn priority_channel<T>(depth: usize) -> (PrioritySender<T>, PriorityReceiver<T>) {
+    let (high_sender, high_receiver) = mpsc::channel(depth);
+    let (normal_sender, normal_receiver) = mpsc::channel(depth);
+    let (low_sender, low_receiver) = mpsc::channel(depth);
+    let sender = PrioritySender([high_sender, normal_sender, low_sender]);
+    let receiver = PriorityReceiver([high_receiver, normal_receiver, low_receiver]);
+    (sender, receiver)
+}
+
+/// The sending half of the queues of a connection.
+pub(crate) struct PrioritySender<T>([mpsc::Sender<T>; MessagePriority::COUNT]);
+
+impl<T> Clone for PrioritySender<T> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+impl<T> PrioritySender<T> {
+    /// Queues the given item with the given priority, failing if its queue is full.
+    pub(crate) fn try_send(&self, priority: MessagePriority, item: T) -> Result<(), mpsc::error::TrySendError<T>> {
+        self.0[priority.index()].try_send(item)
+    }
+}
+
+/// The receiving half of the queues of a connection.
+pub(crate) struct PriorityReceiver<T>([mpsc::Receiver<T>; MessagePriority::COUNT]);
+
+impl<T> PriorityReceiver<T> {
+    /// Receives the next item of the highest priority available, waiting for one if all the queues are empty.
+    /// Returns `None` once all the senders are dropped and the queues are drained.
+    pub(crate) async fn recv(&mut self) -> Option<T> {
+        poll_fn(|cx| {
+            let mut is_closed = true;
+            for receiver in self.0.iter_mut() {
+                match receiver.poll_recv(cx) {
+                    Poll::Ready(Some(item)) => return Poll::Ready(Some(item)),
+                    Poll::Ready(None) => {}
+                    Poll::Pending => is_closed = false,
+                }
+            }
+            match is_closed {
+                true => Poll::Ready(None),
+                false => Poll::Pending,
+            }
+        })
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_priority_channel() {
+        let (sender, mut receiver) = priority_channel(4);
+
+        // Queue the messages in the reverse order of their priority.
+        sender.try_send(MessagePriority::Low, "sync response").unwrap();
+        sender.try_send(MessagePriority::Normal, "peer request").unwrap();
+        sender.try_send(MessagePriority::Low, "transaction").unwrap();
+        sender.try_send(MessagePriority::High, "block").unwrap();
+
+        // Ensure the messages are received by priority, and in order within a priority.
+        assert_eq!(receiver.recv().await, Some("block"));
+        assert_eq!(receiver.recv().await, Some("peer request"));
+        assert_eq!(receiver.recv().await, Some("sync response"));
+        assert_eq!(receiver.recv().await, Some("transaction"));
+
+        // Ensure each queue is bounded separately.
+        for _ in 0..4 {
+            sender.try_send(MessagePriority::Low, "transaction").unwrap();
+        }
+        assert!(sender.try_send(MessagePriority::Low, "transaction").is_err());
+        sender.try_send(MessagePriority::High, "block").unwrap();
+        assert_eq!(receiver.recv().await, Some("block"));
+
+        // Ensure the queues are drained before the receiver is closed.
+        drop(sender);
+        for _ in 0..4 {
+            assert_eq!(receiver.recv().await, Some("transaction"));
+        }
+        assert_eq!(receiver.recv().await, None);
+    }
+}
@@ -0,0 +1,231 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+
+use std::{
+    io::{self, ErrorKind},
+    net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr},
+};
+
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::TcpStream,
+};
+
+/// The SOCKS protocol version.
+const SOCKS_VERSION: u8 = 0x05;
+/// The "no authentication required" method.
+const METHOD_NO_AUTH: u8 = 0x00;
+/// The CONNECT command.
+const CMD_CONNECT: u8 = 0x01;
+/// The RESOLVE command (a Tor extension to SOCKS5).
+const CMD_RESOLVE: u8 = 0xF0;
+/// The IPv4 address type.
+const ATYP_IPV4: u8 = 0x01;
+/// The domain name address type.
+const ATYP_DOMAIN: u8 = 0x03;
+/// The IPv6 address type.
+const ATYP_IPV6: u8 = 0x04;
+
+/// The destination of a SOCKS5 request.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Socks5Target {
+    /// An IP address and port.
+    Addr(SocketAddr),
+    /// A domain name and port, resolved by the proxy (e.g. a `.onion` address).
+    Domain(String, u16),
+}
+
+/// Opens a TCP connection to the given target through the SOCKS5 proxy at the given address.
+pub async fn socks5_connect(proxy: SocketAddr, target: Socks5Target) -> io::Result<TcpStream> {
+    let mut stream = TcpStream::connect(proxy).await?;
+    // Perform the handshake, and issue the CONNECT request.
+    socks5_handshake(&mut stream).await?;
+    socks5_request(&mut stream, CMD_CONNECT, &target).await?;
+    Ok(stream)
+}
+
+/// Resolves the given host name through the SOCKS5 proxy at the given address, using the Tor RESOLVE extension.
+///
+/// note: With `AutomapHostsOnResolve` enabled, Tor maps `.onion` addresses to virtual IP addresses
+/// (by default in `127.192.0.0/10`), which can subsequently be dialed through the proxy.
+pub async fn socks5_resolve(proxy: SocketAddr, host: &str) -> io::Result<IpAddr> {
+    let mut stream = TcpStream::connect(proxy).await?;
+    // Perform the handshake, and issue the RESOLVE request.
+    socks5_handshake(&mut stream).await?;
+    let addr = socks5_request(&mut stream, CMD_RESOLVE, &Socks5Target::Domain(host.to_string(), 0)).await?;
+    Ok(addr.ip())
+}
+
+/// Performs the SOCKS5 method negotiation, without authentication.
+async fn socks5_handshake(stream: &mut TcpStream) -> io::Result<()> {
+    stream.write_all(&[SOCKS_VERSION, 1, METHOD_NO_AUTH]).await?;
+    let mut reply = [0u8; 2];
+    stream.read_exact(&mut reply).await?;
+    if reply[0] != SOCKS_VERSION {
+        return Err(io::Error::new(ErrorKind::InvalidData, "the proxy is not a SOCKS5 proxy"));
+    }
+    if reply[1] != METHOD_NO_AUTH {
+        return Err(io::Error::new(ErrorKind::PermissionDenied, "the SOCKS5 proxy requires authentication"));
+    }
+    Ok(())
+}
+
+/// Sends a SOCKS5 request with the given command, and returns the bound address in the reply.
+async fn socks5_request(stream: &mut TcpStream, command: u8, target: &Socks5Target) -> io::Result<SocketAddr> {
+    // Prepare the request.
+    let mut request = vec![SOCKS_VERSION, command, 0x00];
+    let port = match target {
+        Socks5Target::Addr(SocketAddr::V4(addr)) => {
+            request.push(ATYP_IPV4);
+            request.extend_from_slice(&addr.ip().octets());
+            addr.port()
+        }
+        Socks5Target::Addr(SocketAddr::V6(addr)) => {
+            request.push(ATYP_IPV6);
+            request.extend_from_slice(&addr.ip().octets());
+            addr.port()
+        }
+        Socks5Target::Domain(host, port) => {
+            let length = u8::try_from(host.len())
+                .map_err(|_| io::Error::new(ErrorKind::InvalidInput, "the SOCKS5 domain name is too long"))?;
+            request.extend_from_slice(&[ATYP_DOMAIN, length]);
+            request.extend_from_slice(host.as_bytes());
+            *port
+        }
+    };
+    request.extend_from_slice(&port.to_be_bytes());
+    stream.write_all(&request).await?;
+
+    // Read the reply header.
+    let mut header = [0u8; 4];
+    stream.read_exact(&mut header).await?;
+    if header[0] != SOCKS_VERSION {
+        return Err(io::Error::new(ErrorKind::InvalidData, "invalid SOCKS5 reply"));
+    }
+    if header[1] != 0x00 {
+        return Err(reply_error(header[1]));
+    }
+    // Read the bound address.
+    let ip = match header[3] {
+        ATYP_IPV4 => {
+            let mut octets = [0u8; 4];
+            stream.read_exact(&mut octets).await?;
+            IpAddr::V4(Ipv4Addr::from(octets))
+        }
+        ATYP_IPV6 => {
+            let mut octets = [0u8; 16];
+            stream.read_exact(&mut octets).await?;
+            IpAddr::V6(Ipv6Addr::from(octets))
+        }
+        ATYP_DOMAIN => {
+            // The bound domain name is not needed, so it is skipped.
+            let length = stream.read_u8().await?;
+            let mut domain = vec![0u8; length as usize];
+            stream.read_exact(&mut domain).await?;
+            IpAddr::V4(Ipv4Addr::UNSPECIFIED)
+        }
+        _ => return Err(io::Error::new(ErrorKind::InvalidData, "invalid SOCKS5 address type")),
+    };
+    let port = stream.read_u16().await?;
+    Ok(SocketAddr::new(ip, port))
+}
+
+/// Returns the IO error corresponding to the given SOCKS5 reply code.
+fn reply_error(code: u8) -> io::Error {
+    let (kind, reason) = match code {
+        0x02 => (ErrorKind::PermissionDenied, "connection not allowed by ruleset"),
+        0x03 => (ErrorKind::ConnectionRefused, "network unreachable"),
+        0x04 => (ErrorKind::ConnectionRefused, "host unreachable"),
+        0x05 => (ErrorKind::ConnectionRefused, "connection refused"),
+        0x06 => (ErrorKind::TimedOut, "TTL expired"),
+        0x07 => (ErrorKind::Unsupported, "command not supported"),
+        0x08 => (ErrorKind::Unsupported, "address type not supported"),
+        _ => (ErrorKind::Other, "general SOCKS server failure"),
+    };
+    io::Error::new(kind, format!("SOCKS5 proxy error: {reason}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use tokio::net::TcpListener;
+
+    /// Spawns a fake SOCKS5 proxy that accepts a single request, returning its address and the received request.
+    async fn fake_proxy(reply: Vec<u8>) -> (SocketAddr, tokio::task::JoinHandle<Vec<u8>>) {
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let handle = tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            // Negotiate the method.
+            let mut greeting = [0u8; 3];
+            stream.read_exact(&mut greeting).await.unwrap();
+            assert_eq!(greeting, [SOCKS_VERSION, 1, METHOD_NO_AUTH]);
+            stream.write_all(&[SOCKS_VERSION, METHOD_NO_AUTH]).await.unwrap();
+            // Read the request.
+            let mut request = vec![0u8; 5];
+            stream.read_exact(&mut request).await.unwrap();
+            let remaining = match request[3] {
+                ATYP_IPV4 => 4 - 1 + 2,
+                ATYP_IPV6 => 16 - 1 + 2,
+                _ => request[4] as usize + 2,
+            };
+            let mut rest = vec![0u8; remaining];
+            stream.read_exact(&mut rest).await.unwrap();
+            request.extend(rest);
+            // Send the reply.
+            stream.write_all(&reply).await.unwrap();
+            request
+        });
+        (addr, handle)
+    }
+
+    #[tokio::test]
+    async fn test_socks5_connect() {
+        let reply = vec![SOCKS_VERSION, 0x00, 0x00, ATYP_IPV4, 0, 0, 0, 0, 0, 0];
+        let (proxy, handle) = fake_proxy(reply).await;
+
+        let target = Socks5Target::Addr(SocketAddr::new(Ipv4Addr::new(1, 2, 3, 4).into(), 4130));
+        socks5_connect(proxy, target).await.unwrap();
+
+        // Ensure the CONNECT request targets the given address.
+        let request = handle.await.unwrap();
+        assert_eq!(request, vec![SOCKS_VERSION, CMD_CONNECT, 0x00, ATYP_IPV4, 1, 2, 3, 4, 0x10, 0x22]);
+    }
+
+    #[tokio::test]
+    async fn test_socks5_connect_refused() {
+        let reply = vec![SOCKS_VERSION, 0x05, 0x00, ATYP_IPV4, 0, 0, 0, 0, 0, 0];
+        let (proxy, _handle) = fake_proxy(reply).await;
+
+        let target = Socks5Target::Domain("example.onion".to_string(), 4130);
+        let error = socks5_connect(proxy, target).await.unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::ConnectionRefused);
+    }
+
+    #[tokio::test]
+    async fn test_socks5_resolve() {
+        let reply = vec![SOCKS_VERSION, 0x00, 0x00, ATYP_IPV4, 127, 192, 0, 1, 0, 0];
+        let (proxy, handle) = fake_proxy(reply).await;
+
+        let ip = socks5_resolve(proxy, "example.onion").await.unwrap();
+        assert_eq!(ip, IpAddr::V4(Ipv4Addr::new(127, 192, 0, 1)));
+
+        // Ensure the RESOLVE request carries the domain name.
+        let request = handle.await.unwrap();
+        assert_eq!(&request[..5], &[SOCKS_VERSION, CMD_RESOLVE, 0x00, ATYP_DOMAIN, 13]);
+        assert_eq!(&request[5..18], b"example.onion");
+    }
+}
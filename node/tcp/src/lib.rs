@@ -25,7 +25,7 @@ pub mod protocols;
 mod tcp;
 pub use tcp::Tcp;
 
-use std::net::IpAddr;
+use std::net::{IpAddr, SocketAddr};
 
 /// A trait for objects containing a [`Tcp`]; it is required to implement protocols.
 pub trait P2P {
@@ -38,16 +38,62 @@ pub trait P2P {
 /// A bogon address is an IP address that should not appear on the public Internet.
 /// This includes private addresses, loopback addresses, and link-local addresses.
 pub fn is_bogon_ip(ip: IpAddr) -> bool {
-    match ip {
+    match canonical_ip(ip) {
         IpAddr::V4(ipv4) => ipv4.is_loopback() || ipv4.is_private() || ipv4.is_link_local(),
-        IpAddr::V6(ipv6) => ipv6.is_loopback(),
+        IpAddr::V6(ipv6) => {
+            // Check for the unique local (fc00::/7) and link-local (fe80::/10) ranges.
+            let segment = ipv6.segments()[0];
+            ipv6.is_loopback() || (segment & 0xfe00) == 0xfc00 || (segment & 0xffc0) == 0xfe80
+        }
     }
 }
 
 /// Checks if the given IP address is unspecified or broadcast.
 pub fn is_unspecified_or_broadcast_ip(ip: IpAddr) -> bool {
-    match ip {
+    match canonical_ip(ip) {
         IpAddr::V4(ipv4) => ipv4.is_unspecified() || ipv4.is_broadcast(),
         ipv6 => ipv6.is_unspecified(),
     }
 }
+
+/// Returns the canonical form of the given IP address, i.e. an IPv4-mapped IPv6 address
+/// (as seen by a dual-stack listener for an IPv4 peer) is converted to its IPv4 address.
+pub fn canonical_ip(ip: IpAddr) -> IpAddr {
+    match ip {
+        IpAddr::V6(ipv6) => ipv6.to_ipv4_mapped().map_or(ip, IpAddr::V4),
+        ipv4 => ipv4,
+    }
+}
+
+/// Returns the canonical form of the given socket address (see [`canonical_ip`]).
+pub fn canonical_addr(addr: SocketAddr) -> SocketAddr {
+    SocketAddr::new(canonical_ip(addr.ip()), addr.port())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::net::{Ipv4Addr, Ipv6Addr};
+
+    #[test]
+    fn test_canonical_addr() {
+        let ipv4 = SocketAddr::new(Ipv4Addr::new(1, 2, 3, 4).into(), 4130);
+        let mapped = SocketAddr::new(Ipv4Addr::new(1, 2, 3, 4).to_ipv6_mapped().into(), 4130);
+        let ipv6 = SocketAddr::new("2001:db8::1".parse::<Ipv6Addr>().unwrap().into(), 4130);
+        assert_eq!(canonical_addr(mapped), ipv4);
+        assert_eq!(canonical_addr(ipv4), ipv4);
+        assert_eq!(canonical_addr(ipv6), ipv6);
+    }
+
+    #[test]
+    fn test_is_bogon_ip() {
+        for ip in ["127.0.0.1", "10.0.0.1", "192.168.1.1", "::1", "::ffff:10.0.0.1", "fd00::1", "fe80::1"] {
+            assert!(is_bogon_ip(ip.parse().unwrap()), "{ip} should be a bogon");
+        }
+        for ip in ["1.2.3.4", "::ffff:1.2.3.4", "2001:db8::1"] {
+            assert!(!is_bogon_ip(ip.parse().unwrap()), "{ip} should not be a bogon");
+        }
+        assert!(is_unspecified_or_broadcast_ip("::ffff:255.255.255.255".parse().unwrap()));
+    }
+}
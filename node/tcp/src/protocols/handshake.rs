@@ -36,11 +36,6 @@ pub trait Handshake: P2P
 where
     Self: Clone + Send + Sync + 'static,
 {
-    /// The maximum time allowed for a connection to perform a handshake before it is rejected.
-    ///
-    /// The default value is 3000ms.
-    const TIMEOUT_MS: u64 = 3_000;
-
     /// Prepares the node to perform specified network handshakes.
     async fn enable_handshake(&self) {
         let (from_node_sender, mut from_node_receiver) = mpsc::unbounded_channel::<ReturnableConnection>();
@@ -60,7 +55,9 @@ where
                 let node = self_clone.clone();
                 tokio::spawn(async move {
                     debug!(parent: node.tcp().span(), "shaking hands with {} as the {:?}", addr, !conn.side());
-                    let result = timeout(Duration::from_millis(Self::TIMEOUT_MS), node.perform_handshake(conn)).await;
+                    // Drop the connection if the handshake is not completed in time.
+                    let timeout_ms = node.tcp().config().handshake_timeout_ms;
+                    let result = timeout(Duration::from_millis(timeout_ms), node.perform_handshake(conn)).await;
 
                     let ret = match result {
                         Ok(Ok(conn)) => {
@@ -18,16 +18,18 @@ use crate::{
     protocols::{ProtocolHandler, ReturnableConnection},
     ConnectionSide,
     Tcp,
+    TokenBucket,
     P2P,
 };
 
 use async_trait::async_trait;
 use bytes::BytesMut;
 use futures_util::StreamExt;
-use std::{io, net::SocketAddr};
+use std::{io, net::SocketAddr, time::Duration};
 use tokio::{
     io::AsyncRead,
     sync::{mpsc, oneshot},
+    time::{sleep, timeout},
 };
 use tokio_util::codec::{Decoder, FramedRead};
 use tracing::*;
@@ -38,7 +40,9 @@ use tracing::*;
 /// Each inbound message is isolated by the user-supplied [`Reading::Codec`], creating a [`Reading::Message`],
 /// which is immediately queued (with a [`Reading::MESSAGE_QUEUE_DEPTH`] limit) to be processed by
 /// [`Reading::process_message`]. The configured fatal IO errors result in an immediate disconnect
-/// (in order to e.g. avoid accidentally reading "borked" messages).
+/// (in order to e.g. avoid accidentally reading "borked" messages). If [`Config::inbound_bandwidth_limit`]
+/// is set, reading from a connection is paused whenever it exceeds the limit. If [`Config::read_timeout_ms`]
+/// is set, a connection that stays silent for longer than the timeout is dropped.
 #[async_trait]
 pub trait Reading: P2P
 where
@@ -165,7 +169,25 @@ impl<R: Reading> ReadingInternal for R {
             // this task gets aborted, so there is no need for a dedicated timeout
             let _ = rx_conn_ready.await;
 
-            while let Some(bytes) = framed.next().await {
+            // the inbound bandwidth limit of the connection
+            let mut bucket = node.config().inbound_bandwidth_limit.map(TokenBucket::new);
+
+            // the maximum time allowed between two messages, if any
+            let read_timeout = node.config().read_timeout_ms;
+
+            loop {
+                let bytes = match read_timeout {
+                    0 => framed.next().await,
+                    ms => match timeout(Duration::from_millis(ms), framed.next()).await {
+                        Ok(bytes) => bytes,
+                        Err(_) => {
+                            warn!(parent: node.span(), "no message from {addr} in {ms}ms; disconnecting");
+                            node.known_peers().register_failure(addr);
+                            break;
+                        }
+                    },
+                };
+                let Some(bytes) = bytes else { break };
                 match bytes {
                     Ok(msg) => {
                         // send the message for further processing
@@ -175,6 +197,17 @@ impl<R: Reading> ReadingInternal for R {
                         }
                         #[cfg(feature = "metrics")]
                         metrics::increment_gauge(metrics::tcp::TCP_TASKS, 1f64);
+
+                        // pause reading if the connection exceeds its bandwidth limit
+                        if let Some(bucket) = bucket.as_mut() {
+                            let delay = bucket.consume(framed.decoder().last_message_len);
+                            if !delay.is_zero() {
+                                trace!(parent: node.span(), "throttling reads from {addr} for {delay:?}");
+                                #[cfg(feature = "metrics")]
+                                metrics::counter(metrics::tcp::INBOUND_THROTTLE_TIME, delay.as_millis() as u64);
+                                sleep(delay).await;
+                            }
+                        }
                     }
                     Err(e) => {
                         error!(parent: node.span(), "can't read from {addr}: {e}");
@@ -202,7 +235,7 @@ impl<R: Reading> ReadingInternal for R {
         framed: FramedRead<T, Self::Codec>,
         addr: SocketAddr,
     ) -> FramedRead<T, CountingCodec<Self::Codec>> {
-        framed.map_decoder(|codec| CountingCodec { codec, node: self.tcp().clone(), addr, acc: 0 })
+        framed.map_decoder(|codec| CountingCodec { codec, node: self.tcp().clone(), addr, acc: 0, last_message_len: 0 })
     }
 }
 
@@ -212,6 +245,7 @@ struct CountingCodec<D: Decoder> {
     node: Tcp,
     addr: SocketAddr,
     acc: usize,
+    last_message_len: usize,
 }
 
 impl<D: Decoder> Decoder for CountingCodec<D> {
@@ -229,6 +263,7 @@ impl<D: Decoder> Decoder for CountingCodec<D> {
 
             if ret.is_some() {
                 self.acc = 0;
+                self.last_message_len = read_len;
                 self.node.known_peers().register_received_message(self.addr, read_len);
                 self.node.stats().register_received_message(read_len);
             } else {
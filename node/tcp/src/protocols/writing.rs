@@ -20,6 +20,7 @@ use parking_lot::RwLock;
 use tokio::{
     io::AsyncWrite,
     sync::{mpsc, oneshot},
+    time::sleep,
 };
 use tokio_util::codec::{Encoder, FramedWrite};
 use tracing::*;
@@ -27,23 +28,30 @@ use tracing::*;
 #[cfg(doc)]
 use crate::{protocols::Handshake, Config, Tcp};
 use crate::{
+    priority_channel,
     protocols::{Protocol, ProtocolHandler, ReturnableConnection},
     Connection,
     ConnectionSide,
+    MessagePriority,
+    PrioritySender,
+    TokenBucket,
     P2P,
 };
 
-type WritingSenders = Arc<RwLock<HashMap<SocketAddr, mpsc::Sender<WrappedMessage>>>>;
+type WritingSenders = Arc<RwLock<HashMap<SocketAddr, PrioritySender<WrappedMessage>>>>;
 
 /// Can be used to specify and enable writing, i.e. sending outbound messages. If the [`Handshake`]
 /// protocol is enabled too, it goes into force only after the handshake has been concluded.
+/// If [`Config::outbound_bandwidth_limit`] is set, writing to a connection is paused whenever
+/// it exceeds the limit. Every connection has a separate queue per [`MessagePriority`], and the
+/// queued messages of a higher priority are always written first.
 #[async_trait]
 pub trait Writing: P2P
 where
     Self: Clone + Send + Sync + 'static,
 {
-    /// The depth of per-connection queues used to send outbound messages; the greater it is, the more outbound
-    /// messages the node can enqueue. Setting it to a large value is not recommended, as doing it might
+    /// The depth of per-connection queues used to send outbound messages (per priority); the greater it is, the more
+    /// outbound messages the node can enqueue. Setting it to a large value is not recommended, as doing it might
     /// obscure potential issues with your implementation (like slow serialization) or network.
     ///
     /// The default value is 1024.
@@ -92,6 +100,11 @@ where
     /// The `side` param indicates the connection side **from the node's perspective**.
     fn codec(&self, addr: SocketAddr, side: ConnectionSide) -> Self::Codec;
 
+    /// Returns the priority of the given outbound message; by default, all messages have the normal priority.
+    fn message_priority(&self, _message: &Self::Message) -> MessagePriority {
+        MessagePriority::Normal
+    }
+
     /// Sends the provided message to the specified [`SocketAddr`]. Returns as soon as the message is queued to
     /// be sent, without waiting for the actual delivery; instead, the caller is provided with a [`oneshot::Receiver`]
     /// which can be used to determine when and whether the message has been delivered.
@@ -100,16 +113,17 @@ where
     ///
     /// The following errors can be returned:
     /// - [`io::ErrorKind::NotConnected`] if the node is not connected to the provided address
-    /// - [`io::ErrorKind::Other`] if the outbound message queue for this address is full
+    /// - [`io::ErrorKind::Other`] if the outbound message queue of the message's priority for this address is full
     /// - [`io::ErrorKind::Unsupported`] if [`Writing::enable_writing`] hadn't been called yet
     fn unicast(&self, addr: SocketAddr, message: Self::Message) -> io::Result<oneshot::Receiver<io::Result<()>>> {
         // access the protocol handler
         if let Some(handler) = self.tcp().protocols.writing.get() {
             // find the message sender for the given address
             if let Some(sender) = handler.senders.read().get(&addr).cloned() {
+                let priority = self.message_priority(&message);
                 let (msg, delivery) = WrappedMessage::new(Box::new(message));
                 sender
-                    .try_send(msg)
+                    .try_send(priority, msg)
                     .map_err(|e| {
                         error!(parent: self.tcp().span(), "can't send a message to {}: {}", addr, e);
                         self.tcp().stats().register_failure();
@@ -139,9 +153,10 @@ where
         // access the protocol handler
         if let Some(handler) = self.tcp().protocols.writing.get() {
             let senders = handler.senders.read().clone();
+            let priority = self.message_priority(&message);
             for (addr, message_sender) in senders {
                 let (msg, _delivery) = WrappedMessage::new(Box::new(message.clone()));
-                let _ = message_sender.try_send(msg).map_err(|e| {
+                let _ = message_sender.try_send(priority, msg).map_err(|e| {
                     error!(parent: self.tcp().span(), "can't send a message to {}: {}", addr, e);
                     self.tcp().stats().register_failure();
                 });
@@ -192,7 +207,7 @@ impl<W: Writing> WritingInternal for W {
         let writer = conn.writer.take().expect("missing connection writer!");
         let mut framed = FramedWrite::new(writer, codec);
 
-        let (outbound_message_sender, mut outbound_message_receiver) = mpsc::channel(Self::MESSAGE_QUEUE_DEPTH);
+        let (outbound_message_sender, mut outbound_message_receiver) = priority_channel(Self::MESSAGE_QUEUE_DEPTH);
 
         // register the connection's message sender with the Writing protocol handler
         conn_senders.write().insert(addr, outbound_message_sender);
@@ -213,6 +228,9 @@ impl<W: Writing> WritingInternal for W {
             // move the cleanup into the task that gets aborted on disconnect
             let _auto_cleanup = auto_cleanup;
 
+            // the outbound bandwidth limit of the connection
+            let mut bucket = node.config().outbound_bandwidth_limit.map(TokenBucket::new);
+
             while let Some(wrapped_msg) = outbound_message_receiver.recv().await {
                 let msg = wrapped_msg.msg.downcast().unwrap();
 
@@ -222,6 +240,17 @@ impl<W: Writing> WritingInternal for W {
                         node.known_peers().register_sent_message(addr, len);
                         node.stats().register_sent_message(len);
                         trace!(parent: node.span(), "sent {}B to {}", len, addr);
+
+                        // pause writing if the connection exceeds its bandwidth limit
+                        if let Some(bucket) = bucket.as_mut() {
+                            let delay = bucket.consume(len);
+                            if !delay.is_zero() {
+                                trace!(parent: node.span(), "throttling writes to {addr} for {delay:?}");
+                                #[cfg(feature = "metrics")]
+                                metrics::counter(metrics::tcp::OUTBOUND_THROTTLE_TIME, delay.as_millis() as u64);
+                                sleep(delay).await;
+                            }
+                        }
                     }
                     Err(e) => {
                         node.known_peers().register_failure(addr);
@@ -16,7 +16,7 @@ use std::{
     collections::HashSet,
     fmt,
     io,
-    net::{IpAddr, SocketAddr},
+    net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr},
     ops::Deref,
     sync::{
         atomic::{AtomicUsize, Ordering::*},
@@ -27,9 +27,10 @@ use std::{
 
 use once_cell::sync::OnceCell;
 use parking_lot::Mutex;
+use socket2::{Domain, Protocol as SocketProtocol, Socket, Type};
 use tokio::{
     io::split,
-    net::{TcpListener, TcpStream},
+    net::{TcpListener, TcpSocket, TcpStream},
     sync::oneshot,
     task::JoinHandle,
     time::timeout,
@@ -37,10 +38,14 @@ use tokio::{
 use tracing::*;
 
 use crate::{
+    canonical_addr,
     connections::{Connection, ConnectionSide, Connections},
     protocols::{Protocol, Protocols},
+    socks5_connect,
     Config,
+    ConnectionAttempts,
     KnownPeers,
+    Socks5Target,
     Stats,
 };
 
@@ -71,6 +76,10 @@ pub struct InnerTcp {
     pub(crate) protocols: Protocols,
     /// A set of connections that have not been finalized yet.
     connecting: Mutex<HashSet<SocketAddr>>,
+    /// The number of inbound connections that have not been finalized yet.
+    pending_inbound: AtomicUsize,
+    /// The recent inbound connection attempts, per IP address.
+    inbound_attempts: ConnectionAttempts,
     /// Contains objects related to the node's active connections.
     connections: Connections,
     /// Collects statistics related to the node's peers.
@@ -99,6 +108,8 @@ impl Tcp {
             listening_addr: Default::default(),
             protocols: Default::default(),
             connecting: Default::default(),
+            pending_inbound: Default::default(),
+            inbound_attempts: Default::default(),
             connections: Default::default(),
             known_peers: Default::default(),
             stats: Default::default(),
@@ -226,9 +237,7 @@ impl Tcp {
         }
 
         let stream =
-            match timeout(Duration::from_millis(self.config().connection_timeout_ms.into()), TcpStream::connect(addr))
-                .await
-            {
+            match timeout(Duration::from_millis(self.config().connection_timeout_ms.into()), self.dial(addr)).await {
                 Ok(Ok(stream)) => Ok(stream),
                 Ok(err) => {
                     self.connecting.lock().remove(&addr);
@@ -316,7 +325,8 @@ impl Tcp {
             loop {
                 // Await for a new connection.
                 match listener.accept().await {
-                    Ok((stream, addr)) => tcp.handle_connection(stream, addr),
+                    // Note: A dual-stack listener sees IPv4 peers as IPv4-mapped IPv6 addresses.
+                    Ok((stream, addr)) => tcp.handle_connection(stream, canonical_addr(addr)),
                     Err(e) => error!(parent: tcp.span(), "Failed to accept a connection: {e}"),
                 }
             }
@@ -335,7 +345,7 @@ impl Tcp {
             // Construct the desired listening IP address.
             let desired_listening_addr = SocketAddr::new(listener_ip, port);
             // If a desired listening port is set, try to bind to it.
-            match TcpListener::bind(desired_listening_addr).await {
+            match bind_listener(desired_listening_addr, self.config().hole_punching) {
                 Ok(listener) => listener,
                 Err(e) => {
                     if self.config().allow_random_port {
@@ -344,7 +354,7 @@ impl Tcp {
                             "Trying any listening port, as the desired port is unavailable: {e}"
                         );
                         let random_available_addr = SocketAddr::new(listener_ip, 0);
-                        TcpListener::bind(random_available_addr).await?
+                        bind_listener(random_available_addr, self.config().hole_punching)?
                     } else {
                         error!(parent: self.span(), "The desired listening port is unavailable: {e}");
                         return Err(e);
@@ -353,7 +363,7 @@ impl Tcp {
             }
         } else if self.config().allow_random_port {
             let random_available_addr = SocketAddr::new(listener_ip, 0);
-            TcpListener::bind(random_available_addr).await?
+            bind_listener(random_available_addr, self.config().hole_punching)?
         } else {
             panic!("As 'listener_ip' is set, either 'desired_listening_port' or 'allow_random_port' must be set");
         };
@@ -365,16 +375,19 @@ impl Tcp {
     fn handle_connection(&self, stream: TcpStream, addr: SocketAddr) {
         debug!(parent: self.span(), "Received a connection from {addr}");
 
-        if !self.can_add_connection() || self.is_self_connect(addr) {
+        if !self.can_add_connection() || self.is_self_connect(addr) || !self.can_add_inbound_attempt(addr.ip()) {
             debug!(parent: self.span(), "Rejecting the connection from {addr}");
             return;
         }
 
         self.connecting.lock().insert(addr);
+        self.pending_inbound.fetch_add(1, Relaxed);
 
         let tcp = self.clone();
         tokio::spawn(async move {
-            if let Err(e) = tcp.adapt_stream(stream, addr, ConnectionSide::Responder).await {
+            let result = tcp.adapt_stream(stream, addr, ConnectionSide::Responder).await;
+            tcp.pending_inbound.fetch_sub(1, Relaxed);
+            if let Err(e) = result {
                 tcp.connecting.lock().remove(&addr);
                 tcp.known_peers().register_failure(addr);
                 error!(parent: tcp.span(), "Failed to connect with {addr}: {e}");
@@ -382,12 +395,78 @@ impl Tcp {
         });
     }
 
+    /// Checks whether the `Tcp` can accept another inbound connection attempt from the given IP address,
+    /// registering the attempt if so.
+    fn can_add_inbound_attempt(&self, ip: IpAddr) -> bool {
+        // Bound the number of inbound connections that are pending at once.
+        let max_pending = self.config.max_pending_inbound as usize;
+        if self.pending_inbound.load(Relaxed) >= max_pending {
+            warn!(parent: self.span(), "Maximum number of pending inbound connections ({max_pending}) reached");
+            return false;
+        }
+        // Rate-limit the inbound connection attempts of the IP address.
+        let limit = self.config.max_inbound_attempts_per_minute;
+        if limit > 0 && !ip.is_loopback() && !self.inbound_attempts.register(ip, limit) {
+            warn!(parent: self.span(), "Too many connection attempts from {ip} (over {limit} per minute)");
+            return false;
+        }
+        true
+    }
+
+    /// Opens a TCP connection to the given address, through the configured proxy (if any).
+    async fn dial(&self, addr: SocketAddr) -> io::Result<TcpStream> {
+        match self.config.proxy {
+            Some(proxy) => socks5_connect(proxy, Socks5Target::Addr(addr)).await,
+            None if self.config.hole_punching && cfg!(unix) => self.dial_from_listener(addr).await,
+            None => TcpStream::connect(addr).await,
+        }
+    }
+
+    /// Dials the given address from the listening port, so that the connection goes through the same NAT mapping
+    /// as the listener, and the peer can reach the listener through the mapping opened by the dial.
+    async fn dial_from_listener(&self, addr: SocketAddr) -> io::Result<TcpStream> {
+        let socket = match addr {
+            SocketAddr::V4(..) => TcpSocket::new_v4()?,
+            SocketAddr::V6(..) => TcpSocket::new_v6()?,
+        };
+        // Share the listening port with the listener, which is bound with the same options.
+        socket.set_reuseaddr(true)?;
+        #[cfg(unix)]
+        socket.set_reuseport(true)?;
+        let local_ip: IpAddr = match addr {
+            SocketAddr::V4(..) => Ipv4Addr::UNSPECIFIED.into(),
+            SocketAddr::V6(..) => Ipv6Addr::UNSPECIFIED.into(),
+        };
+        socket.bind(SocketAddr::new(local_ip, self.listening_addr()?.port()))?;
+        socket.connect(addr).await
+    }
+
+    /// Opens a mapping towards the given address in the NAT this node is behind, by briefly dialing it from the
+    /// listening port, so that the subsequent connection attempt of the peer reaches the listener (i.e. TCP hole
+    /// punching). The dial is expected to fail, unless the peer is directly reachable.
+    pub async fn punch(&self, addr: SocketAddr, duration: Duration) -> io::Result<()> {
+        if !self.config.hole_punching || self.config.proxy.is_some() || !cfg!(unix) {
+            return Err(io::ErrorKind::Unsupported.into());
+        }
+        debug!(parent: self.span(), "Punching a hole towards {addr}");
+        // The stream is dropped right away, as the connection is initiated by the peer.
+        match timeout(duration, self.dial_from_listener(addr)).await {
+            Ok(Ok(_stream)) => Ok(()),
+            Ok(Err(error)) if error.kind() == io::ErrorKind::ConnectionRefused => Ok(()),
+            Ok(Err(error)) => Err(error),
+            Err(_) => Ok(()),
+        }
+    }
+
     /// Checks if the given IP address is the same as the listening address of this `Tcp`.
     fn is_self_connect(&self, addr: SocketAddr) -> bool {
         // SAFETY: if we're opening connections, this should never fail.
         let listening_addr = self.listening_addr().unwrap();
 
         match listening_addr.ip().is_loopback() {
+            // If behind a proxy, loopback peers may be virtual addresses mapped by the proxy (e.g. Tor .onion
+            // addresses), so only an exact match indicates a self-connect.
+            true if self.config.proxy.is_some() => listening_addr == addr,
             // If localhost, check the ports, this only works on outbound connections, since we
             // don't know the ephemeral port a peer might be using if they initiate the connection.
             true => listening_addr.port() == addr.port(),
@@ -493,6 +572,29 @@ impl Tcp {
     }
 }
 
+/// Binds a TCP listener to the given address. If the address is the unspecified IPv6 address,
+/// the listener is dual-stack, i.e. it accepts both IPv4 and IPv6 connections. If `reuse_port` is set, the
+/// listening port can be shared with the outbound connections, as required by TCP hole punching.
+fn bind_listener(addr: SocketAddr, reuse_port: bool) -> io::Result<TcpListener> {
+    let socket = Socket::new(Domain::for_address(addr), Type::STREAM, Some(SocketProtocol::TCP))?;
+    if addr.is_ipv6() && addr.ip().is_unspecified() {
+        socket.set_only_v6(false)?;
+    }
+    // Match the behavior of `TcpListener::bind`, which allows rebinding a port in the TIME_WAIT state.
+    #[cfg(unix)]
+    socket.set_reuse_address(true)?;
+    #[cfg(unix)]
+    if reuse_port {
+        socket.set_reuse_port(true)?;
+    }
+    #[cfg(not(unix))]
+    let _ = reuse_port;
+    socket.set_nonblocking(true)?;
+    socket.bind(&addr.into())?;
+    socket.listen(1024)?;
+    TcpListener::from_std(socket.into())
+}
+
 impl fmt::Debug for Tcp {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "The TCP stack config: {:?}", self.config)
@@ -503,7 +605,7 @@ impl fmt::Debug for Tcp {
 mod tests {
     use super::*;
 
-    use std::net::{IpAddr, Ipv4Addr};
+    use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
 
     #[tokio::test]
     async fn test_new() {
@@ -550,6 +652,32 @@ mod tests {
         assert!(!tcp.is_connecting(peer_ip));
     }
 
+    #[tokio::test]
+    async fn test_dual_stack_listener() {
+        // Initialize a dual-stack node, skipping the test if IPv6 is unavailable.
+        let tcp = Tcp::new(Config {
+            listener_ip: Some(IpAddr::V6(Ipv6Addr::UNSPECIFIED)),
+            desired_listening_port: Some(0),
+            ..Default::default()
+        });
+        let Ok(node_ip) = tcp.enable_listener().await else { return };
+
+        // Initialize an IPv4 peer.
+        let peer = Tcp::new(Config {
+            listener_ip: Some(IpAddr::V4(Ipv4Addr::LOCALHOST)),
+            desired_listening_port: Some(0),
+            ..Default::default()
+        });
+        peer.enable_listener().await.unwrap();
+
+        // Connect to the node over IPv4, and check that the node sees the canonical IPv4 address.
+        peer.connect(SocketAddr::new(Ipv4Addr::LOCALHOST.into(), node_ip.port())).await.unwrap();
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        let connected_addrs = tcp.connected_addrs();
+        assert_eq!(connected_addrs.len(), 1);
+        assert_eq!(connected_addrs[0].ip(), IpAddr::V4(Ipv4Addr::LOCALHOST));
+    }
+
     #[tokio::test]
     async fn test_disconnect() {
         let tcp = Tcp::new(Config::default());
@@ -630,6 +758,26 @@ mod tests {
         assert!(tcp.can_add_connection());
     }
 
+    #[tokio::test]
+    async fn test_can_add_inbound_attempt() {
+        let tcp = Tcp::new(Config { max_inbound_attempts_per_minute: 2, max_pending_inbound: 1, ..Default::default() });
+        let peer_ip = IpAddr::V4(Ipv4Addr::new(1, 2, 3, 4));
+
+        // Ensure the connection attempts are rate-limited per IP address.
+        assert!(tcp.can_add_inbound_attempt(peer_ip));
+        assert!(tcp.can_add_inbound_attempt(peer_ip));
+        assert!(!tcp.can_add_inbound_attempt(peer_ip));
+        assert!(tcp.can_add_inbound_attempt(IpAddr::V4(Ipv4Addr::new(5, 6, 7, 8))));
+        // Ensure loopback addresses are exempt.
+        for _ in 0..3 {
+            assert!(tcp.can_add_inbound_attempt(IpAddr::V4(Ipv4Addr::LOCALHOST)));
+        }
+
+        // Ensure the number of pending inbound connections is bounded.
+        tcp.pending_inbound.fetch_add(1, Relaxed);
+        assert!(!tcp.can_add_inbound_attempt(IpAddr::V4(Ipv4Addr::LOCALHOST)));
+    }
+
     #[tokio::test]
     async fn test_handle_connection() {
         let tcp = Tcp::new(Config {
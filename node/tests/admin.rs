@@ -0,0 +1,150 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![recursion_limit = "256"]
+
+#[allow(dead_code)]
+mod common;
+use common::{node::*, sample_account};
+
+use snarkos_node::rest::{AdminConfig, Claims, RestConfig};
+use snarkos_node_router::Routing;
+
+use std::{
+    io::{Read, Write},
+    net::{SocketAddr, TcpListener, TcpStream},
+};
+
+/// Returns an available local address for the REST server.
+fn sample_rest_ip() -> SocketAddr {
+    TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap()
+}
+
+/// Returns the REST configuration with the admin API enabled.
+fn admin_rest_config() -> RestConfig {
+    RestConfig { admin: Some(AdminConfig { set_log_filter: Some(|_| Ok(())) }), ..Default::default() }
+}
+
+/// Returns a JWT token of the node.
+fn sample_jwt() -> String {
+    Claims::new(sample_account().address()).to_jwt_string().unwrap()
+}
+
+/// Sends a POST request with the given JSON body to the REST server, and returns the status code and the body.
+async fn post(rest_ip: SocketAddr, path: &str, jwt: Option<String>, body: &str) -> (u16, String) {
+    let auth = jwt.map(|jwt| format!("Authorization: Bearer {jwt}\r\n")).unwrap_or_default();
+    let request = format!(
+        "POST {path} HTTP/1.1\r\nHost: {rest_ip}\r\n{auth}Content-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    );
+    tokio::task::spawn_blocking(move || {
+        let mut stream = TcpStream::connect(rest_ip).unwrap();
+        stream.write_all(request.as_bytes()).unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        let status = response.split(' ').nth(1).unwrap().parse().unwrap();
+        let (_, body) = response.split_once("\r\n\r\n").unwrap();
+        (status, body.to_string())
+    })
+    .await
+    .unwrap()
+}
+
+#[tokio::test]
+async fn test_admin_routes_require_auth() {
+    let rest_ip = sample_rest_ip();
+    let _node = client_with_rest(rest_ip, admin_rest_config()).await;
+
+    // Check that the admin routes reject the requests without a valid JWT.
+    assert_eq!(post(rest_ip, "/mainnet/admin/sync/pause", None, "").await.0, 401);
+    assert_eq!(post(rest_ip, "/mainnet/admin/sync/pause", Some("invalid".to_string()), "").await.0, 401);
+}
+
+#[tokio::test]
+async fn test_admin_routes_disabled() {
+    let rest_ip = sample_rest_ip();
+    let _node = client_with_rest(rest_ip, RestConfig::default()).await;
+
+    // Check that the admin routes are unavailable unless the admin API is enabled.
+    let (status, body) = post(rest_ip, "/mainnet/admin/sync/pause", Some(sample_jwt()), "").await;
+    assert_eq!(status, 501);
+    assert!(body.contains("The admin API is disabled"));
+}
+
+/// Checks that the block sync of the node with the REST server at the given address can be paused and resumed.
+async fn check_pause_and_resume_sync(rest_ip: SocketAddr) {
+    let (status, body) = post(rest_ip, "/mainnet/admin/sync/pause", Some(sample_jwt()), "").await;
+    assert_eq!(status, 200);
+    assert!(body.contains(r#""paused": true"#));
+
+    let (status, body) = post(rest_ip, "/mainnet/admin/sync/resume", Some(sample_jwt()), "").await;
+    assert_eq!(status, 200);
+    assert!(body.contains(r#""paused": false"#));
+}
+
+#[tokio::test]
+async fn test_admin_pause_sync_client() {
+    let rest_ip = sample_rest_ip();
+    let _node = client_with_rest(rest_ip, admin_rest_config()).await;
+    check_pause_and_resume_sync(rest_ip).await;
+}
+
+#[tokio::test]
+async fn test_admin_pause_sync_validator() {
+    let rest_ip = sample_rest_ip();
+    let _node = validator_with_rest(rest_ip, admin_rest_config()).await;
+    check_pause_and_resume_sync(rest_ip).await;
+}
+
+#[tokio::test]
+async fn test_pause_sync_prover() {
+    // The prover has no REST server, so check the routing directly.
+    let node = prover().await;
+    node.set_block_sync_paused(true).unwrap();
+    node.set_block_sync_paused(false).unwrap();
+}
+
+#[tokio::test]
+async fn test_admin_set_log_filter() {
+    let rest_ip = sample_rest_ip();
+    let _node = client_with_rest(rest_ip, admin_rest_config()).await;
+
+    let body = r#"{"filter": "info,snarkos_node_router=trace"}"#;
+    let (status, body) = post(rest_ip, "/mainnet/admin/log", Some(sample_jwt()), body).await;
+    assert_eq!(status, 200);
+    assert!(body.contains(r#""filter": "info,snarkos_node_router=trace""#));
+
+    // Check that the log filter can't be changed if the node does not support it.
+    let rest_ip = sample_rest_ip();
+    let config = RestConfig { admin: Some(AdminConfig { set_log_filter: None }), ..Default::default() };
+    let _node = client_with_rest(rest_ip, config).await;
+    let (status, _) = post(rest_ip, "/mainnet/admin/log", Some(sample_jwt()), r#"{"filter": "info"}"#).await;
+    assert_eq!(status, 501);
+}
+
+#[tokio::test]
+async fn test_admin_connect_and_disconnect_peer() {
+    let rest_ip = sample_rest_ip();
+    let _node = client_with_rest(rest_ip, admin_rest_config()).await;
+
+    // Check that the node does not disconnect from a peer it is not connected to.
+    let body = r#"{"ip": "127.0.0.1:4130"}"#;
+    let (status, response) = post(rest_ip, "/mainnet/admin/peers/disconnect", Some(sample_jwt()), body).await;
+    assert_eq!(status, 200);
+    assert!(response.contains(r#""disconnected": false"#));
+
+    // Check that a malformed request is rejected.
+    let (status, _) = post(rest_ip, "/mainnet/admin/peers/connect", Some(sample_jwt()), r#"{"ip": "x"}"#).await;
+    assert_eq!(status, 422);
+}
@@ -14,19 +14,27 @@
 
 use crate::common::test_peer::sample_genesis_block;
 use snarkos_account::Account;
-use snarkos_node::{Client, Prover, Validator};
+use snarkos_node::{
+    rest::RestConfig,
+    router::{messages::NodeType, PeerLimits},
+    Client,
+    Prover,
+    Validator,
+};
 use snarkvm::prelude::{store::helpers::memory::ConsensusMemory, MainnetV0 as CurrentNetwork};
 
 use aleo_std::StorageMode;
-use std::str::FromStr;
+use std::{net::SocketAddr, str::FromStr};
 
 pub async fn client() -> Client<CurrentNetwork, ConsensusMemory<CurrentNetwork>> {
     Client::new(
         "127.0.0.1:0".parse().unwrap(),
         None,
         10,
+        RestConfig::default(),
         Account::<CurrentNetwork>::from_str("APrivateKey1zkp2oVPTci9kKcUprnbzMwq95Di1MQERpYBhEeqvkrDirK1").unwrap(),
         &[],
+        PeerLimits::new(NodeType::Client),
         sample_genesis_block(),
         None, // No CDN.
         StorageMode::Production,
@@ -40,6 +48,7 @@ pub async fn prover() -> Prover<CurrentNetwork, ConsensusMemory<CurrentNetwork>>
         "127.0.0.1:0".parse().unwrap(),
         Account::<CurrentNetwork>::from_str("APrivateKey1zkp2oVPTci9kKcUprnbzMwq95Di1MQERpYBhEeqvkrDirK1").unwrap(),
         &[],
+        PeerLimits::new(NodeType::Prover),
         sample_genesis_block(),
         StorageMode::Production,
     )
@@ -53,12 +62,60 @@ pub async fn validator() -> Validator<CurrentNetwork, ConsensusMemory<CurrentNet
         None,
         None,
         10,
+        RestConfig::default(),
         Account::<CurrentNetwork>::from_str("APrivateKey1zkp2oVPTci9kKcUprnbzMwq95Di1MQERpYBhEeqvkrDirK1").unwrap(),
         &[],
+        PeerLimits::new(NodeType::Validator),
         &[],
         sample_genesis_block(), // Should load the current network's genesis block.
         None,                   // No CDN.
         StorageMode::Production,
+        Default::default(),
+    )
+    .await
+    .expect("couldn't create validator instance")
+}
+
+/// Initializes a client with the REST server at the given address.
+pub async fn client_with_rest(
+    rest_ip: SocketAddr,
+    rest_config: RestConfig,
+) -> Client<CurrentNetwork, ConsensusMemory<CurrentNetwork>> {
+    Client::new(
+        "127.0.0.1:0".parse().unwrap(),
+        Some(rest_ip),
+        10,
+        rest_config,
+        Account::<CurrentNetwork>::from_str("APrivateKey1zkp2oVPTci9kKcUprnbzMwq95Di1MQERpYBhEeqvkrDirK1").unwrap(),
+        &[],
+        PeerLimits::new(NodeType::Client),
+        sample_genesis_block(),
+        None, // No CDN.
+        StorageMode::Production,
+    )
+    .await
+    .expect("couldn't create client instance")
+}
+
+/// Initializes a validator with the REST server at the given address.
+pub async fn validator_with_rest(
+    rest_ip: SocketAddr,
+    rest_config: RestConfig,
+) -> Validator<CurrentNetwork, ConsensusMemory<CurrentNetwork>> {
+    Validator::new(
+        "127.0.0.1:0".parse().unwrap(),
+        None,
+        Some(rest_ip),
+        10,
+        rest_config,
+        Account::<CurrentNetwork>::from_str("APrivateKey1zkp2oVPTci9kKcUprnbzMwq95Di1MQERpYBhEeqvkrDirK1").unwrap(),
+        &[],
+        PeerLimits::new(NodeType::Validator),
+        &[],
+        sample_genesis_block(),
+        None, // No CDN.
+        StorageMode::Production,
+        Default::default(),
     )
     .await
     .expect("couldn't create validator instance")
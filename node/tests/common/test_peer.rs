@@ -15,7 +15,20 @@
 use snarkos_account::Account;
 use snarkos_node_router::{
     expect_message,
-    messages::{ChallengeRequest, ChallengeResponse, Message, MessageCodec, MessageTrait, NodeType},
+    messages::{
+        challenge_data,
+        default_user_agent,
+        noise_handshake,
+        Capabilities,
+        ChallengeRequest,
+        ChallengeResponse,
+        Message,
+        MessageCodec,
+        MessageTrait,
+        NodeType,
+        NoiseCodec,
+        NoiseState,
+    },
 };
 use snarkvm::{
     ledger::narwhal::Data,
@@ -23,12 +36,15 @@ use snarkvm::{
 };
 
 use std::{
+    collections::HashMap,
     io,
     net::{IpAddr, Ipv4Addr, SocketAddr},
     str::FromStr,
+    sync::Arc,
 };
 
 use futures_util::{sink::SinkExt, TryStreamExt};
+use parking_lot::Mutex;
 use pea2pea::{
     protocols::{Disconnect, Handshake, Reading, Writing},
     Config,
@@ -58,6 +74,7 @@ pub struct TestPeer {
     node: Node,
     node_type: NodeType,
     account: Account<CurrentNetwork>,
+    noise_states: Arc<Mutex<HashMap<SocketAddr, Option<NoiseState>>>>,
 }
 
 impl Pea2Pea for TestPeer {
@@ -88,6 +105,7 @@ impl TestPeer {
             }),
             node_type,
             account,
+            noise_states: Default::default(),
         };
 
         peer.enable_handshake().await;
@@ -123,74 +141,98 @@ impl Handshake for TestPeer {
         let peer_addr = conn.addr();
         let node_side = !conn.side();
         let stream = self.borrow_stream(&mut conn);
-        let mut framed = Framed::new(stream, MessageCodec::<CurrentNetwork>::default());
+        let mut framed = Framed::new(stream, NoiseCodec::<CurrentNetwork>::plaintext(MessageCodec::default()));
 
         // Retrieve the genesis block header.
         let genesis_header = *sample_genesis_block().header();
 
-        // TODO(nkls): add assertions on the contents of messages.
-        match node_side {
+        // Exchange the challenge requests, advertising the noise handshake.
+        let our_request = ChallengeRequest::new(
+            local_ip.port(),
+            self.node_type(),
+            self.address(),
+            rng.gen(),
+            default_user_agent(),
+            Capabilities::NOISE,
+        );
+        let peer_request = match node_side {
             ConnectionSide::Initiator => {
-                // Send a challenge request to the peer.
-                let our_request = ChallengeRequest::new(local_ip.port(), self.node_type(), self.address(), rng.gen());
                 framed.send(Message::ChallengeRequest(our_request)).await?;
-
-                // Receive the peer's challenge bundle.
-                let _peer_response = expect_message!(Message::ChallengeResponse, framed, peer_addr);
-                let peer_request = expect_message!(Message::ChallengeRequest, framed, peer_addr);
-
-                // Sign the nonce.
-                let response_nonce: u64 = rng.gen();
-                let data = [peer_request.nonce.to_le_bytes(), response_nonce.to_le_bytes()].concat();
-                let signature = self.account().sign_bytes(&data, rng).unwrap();
-
-                // Send the challenge response.
-                let our_response =
-                    ChallengeResponse { genesis_header, signature: Data::Object(signature), nonce: response_nonce };
-                framed.send(Message::ChallengeResponse(our_response)).await?;
+                expect_message!(Message::ChallengeRequest, framed, peer_addr)
             }
             ConnectionSide::Responder => {
-                // Listen for the challenge request.
                 let peer_request = expect_message!(Message::ChallengeRequest, framed, peer_addr);
+                framed.send(Message::ChallengeRequest(our_request)).await?;
+                peer_request
+            }
+        };
 
-                // Sign the nonce.
-                let response_nonce: u64 = rng.gen();
-                let data = [peer_request.nonce.to_le_bytes(), response_nonce.to_le_bytes()].concat();
-                let signature = self.account().sign_bytes(&data, rng).unwrap();
+        // Encrypt the remainder of the connection, if the peer supports it.
+        let stream = framed.into_inner();
+        let noise_state = match peer_request.capabilities.contains(Capabilities::NOISE) {
+            true => Some(noise_handshake(stream, node_side == ConnectionSide::Initiator).await?),
+            false => None,
+        };
+        let codec = NoiseCodec::<CurrentNetwork>::with_transport(noise_state.clone(), MessageCodec::default());
+        let mut framed = Framed::new(stream, codec);
+
+        // Sign the nonce.
+        let response_nonce: u64 = rng.gen();
+        let data = challenge_data(peer_request.nonce, response_nonce, noise_state.as_ref());
+        let signature = self.account().sign_bytes(&data, rng).unwrap();
+        let our_response = ChallengeResponse {
+            genesis_header,
+            signature: Data::Object(signature),
+            nonce: response_nonce,
+            observed_addr: peer_addr,
+        };
 
-                // Send our challenge bundle.
-                let our_response =
-                    ChallengeResponse { genesis_header, signature: Data::Object(signature), nonce: response_nonce };
+        // TODO(nkls): add assertions on the contents of messages.
+        match node_side {
+            ConnectionSide::Initiator => {
+                // Send the challenge response, and receive the peer's.
                 framed.send(Message::ChallengeResponse(our_response)).await?;
-                let our_request = ChallengeRequest::new(local_ip.port(), self.node_type(), self.address(), rng.gen());
-                framed.send(Message::ChallengeRequest(our_request)).await?;
-
-                // Listen for the challenge response.
                 let _peer_response = expect_message!(Message::ChallengeResponse, framed, peer_addr);
             }
+            ConnectionSide::Responder => {
+                // Receive the peer's challenge response, and send ours.
+                let _peer_response = expect_message!(Message::ChallengeResponse, framed, peer_addr);
+                framed.send(Message::ChallengeResponse(our_response)).await?;
+            }
         }
 
+        // Store the transport state of the connection, for the reading and writing codecs.
+        let noise_state = framed.codec().noise_state().cloned();
+        self.noise_states.lock().insert(peer_addr, noise_state);
+
         Ok(conn)
     }
 }
 
 #[async_trait::async_trait]
 impl Writing for TestPeer {
-    type Codec = MessageCodec<CurrentNetwork>;
+    type Codec = NoiseCodec<CurrentNetwork>;
     type Message = Message<CurrentNetwork>;
 
-    fn codec(&self, _addr: SocketAddr, _side: ConnectionSide) -> Self::Codec {
-        Default::default()
+    fn codec(&self, addr: SocketAddr, _side: ConnectionSide) -> Self::Codec {
+        // The writing codec is created last, so the transport state is no longer needed.
+        match self.noise_states.lock().remove(&addr) {
+            Some(noise_state) => NoiseCodec::with_transport(noise_state, MessageCodec::default()),
+            None => NoiseCodec::failed(MessageCodec::default()),
+        }
     }
 }
 
 #[async_trait::async_trait]
 impl Reading for TestPeer {
-    type Codec = MessageCodec<CurrentNetwork>;
+    type Codec = NoiseCodec<CurrentNetwork>;
     type Message = Message<CurrentNetwork>;
 
-    fn codec(&self, _peer_addr: SocketAddr, _side: ConnectionSide) -> Self::Codec {
-        Default::default()
+    fn codec(&self, peer_addr: SocketAddr, _side: ConnectionSide) -> Self::Codec {
+        match self.noise_states.lock().get(&peer_addr) {
+            Some(noise_state) => NoiseCodec::with_transport(noise_state.clone(), MessageCodec::default()),
+            None => NoiseCodec::failed(MessageCodec::default()),
+        }
     }
 
     async fn process_message(&self, _peer_ip: SocketAddr, _message: Self::Message) -> io::Result<()> {